@@ -1,3 +1,6 @@
+use derive_more::Display;
+use enum_iterator::IntoEnumIterator;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -33,7 +36,7 @@ pub struct Glue {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub takeover_mode: Option<TakeoverMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub control_transformation: Option<String>,
+    pub control_transformation: Option<Transformation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub step_size_interval: Option<Interval<f64>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -48,6 +51,11 @@ pub struct Glue {
     pub interaction: Option<Interaction>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fire_mode: Option<FireMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gesture: Option<Gesture>,
+    /// Number of taps for the [`Gesture::MultiTap`] gesture.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tap_count: Option<u32>,
     //endregion
 
     //region Relevant for feedback only (guaranteed)
@@ -141,6 +149,53 @@ pub struct OnSinglePressFireMode {
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
 pub struct OnDoublePressFireMode;
 
+/// Press sequence which a mapping reacts to.
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Display,
+    IntoEnumIterator,
+    TryFromPrimitive,
+    IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum Gesture {
+    /// No gesture recognition, every button press and release is passed through.
+    #[display(fmt = "Off")]
+    Off,
+    /// A single short press which is not followed by another one.
+    #[display(fmt = "Tap")]
+    Tap,
+    #[display(fmt = "Double tap")]
+    DoubleTap,
+    #[display(fmt = "Triple tap")]
+    TripleTap,
+    /// A configurable number of short presses.
+    #[display(fmt = "Multi-tap")]
+    MultiTap,
+    /// A press which is held for a while. Sends "off" on release.
+    #[display(fmt = "Long press")]
+    LongPress,
+    /// A long press which keeps firing while held.
+    #[display(fmt = "Press and hold (repeat)")]
+    PressAndHoldRepeat,
+    /// Each press sends the tempo derived from the intervals between the last presses.
+    #[display(fmt = "Tap tempo")]
+    TapTempo,
+}
+
+impl Default for Gesture {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum VirtualColor {
@@ -169,6 +224,69 @@ impl Default for OutOfRangeBehavior {
     }
 }
 
+/// A control or feedback transformation.
+///
+/// A plain string is an EEL script. Scripts written in other languages must state their language.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Transformation {
+    Eel(String),
+    Script(TransformationScript),
+}
+
+impl Transformation {
+    pub fn new(language: TransformationLanguage, script: String) -> Self {
+        match language {
+            TransformationLanguage::Eel => Self::Eel(script),
+            TransformationLanguage::Lua => Self::Script(TransformationScript { language, script }),
+        }
+    }
+
+    pub fn into_parts(self) -> (TransformationLanguage, String) {
+        match self {
+            Transformation::Eel(script) => (TransformationLanguage::Eel, script),
+            Transformation::Script(s) => (s.language, s.script),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TransformationScript {
+    pub language: TransformationLanguage,
+    pub script: String,
+}
+
+/// Language of a control or feedback transformation
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    IntoEnumIterator,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+    JsonSchema,
+)]
+#[repr(usize)]
+pub enum TransformationLanguage {
+    #[serde(rename = "eel")]
+    #[display(fmt = "EEL")]
+    Eel,
+    #[serde(rename = "lua")]
+    #[display(fmt = "Lua")]
+    Lua,
+}
+
+impl Default for TransformationLanguage {
+    fn default() -> Self {
+        TransformationLanguage::Eel
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum TakeoverMode {
     Off,
@@ -232,7 +350,7 @@ pub struct NumericFeedback {
     #[serde(flatten)]
     pub commons: FeedbackCommons,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub transformation: Option<String>,
+    pub transformation: Option<Transformation>,
 }
 
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
@@ -36,6 +36,12 @@ pub struct Mapping {
     pub glue: Option<Glue>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub target: Option<Target>,
+    /// Additional target for the Y axis of an XY source.
+    ///
+    /// Only has an effect if the source is a virtual XY control element. Then the X axis
+    /// controls [`Self::target`] and the Y axis controls this target.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y_target: Option<Target>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub success_audio_feedback: Option<SuccessAudioFeedback>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -129,6 +135,8 @@ pub enum VirtualControlElementId {
 pub enum VirtualControlElementCharacter {
     Multi,
     Button,
+    /// Two-dimensional control element such as an XY pad or a joystick.
+    Xy,
 }
 
 impl Default for VirtualControlElementCharacter {
@@ -137,6 +145,18 @@ impl Default for VirtualControlElementCharacter {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum XyAxis {
+    X,
+    Y,
+}
+
+impl Default for XyAxis {
+    fn default() -> Self {
+        Self::X
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
 pub struct OscArgument {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -7,6 +7,7 @@ mod root;
 mod session;
 mod source;
 mod target;
+mod validation;
 
 pub use compartment::*;
 pub use glue::*;
@@ -17,14 +18,17 @@ pub use root::*;
 pub use session::*;
 pub use source::*;
 pub use target::*;
+pub use validation::*;
 
 use playtime_api::persistence::Matrix;
+use schemars::JsonSchema;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Envelope<T> {
     #[serde(default)]
+    #[schemars(with = "Option<String>")]
     pub version: Option<Version>,
     pub value: T,
 }
@@ -35,9 +39,10 @@ impl<T> Envelope<T> {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "kind")]
 pub enum ApiObject {
+    Session(Envelope<Box<Session>>),
     ClipMatrix(Envelope<Box<Option<Matrix>>>),
     MainCompartment(Envelope<Box<Compartment>>),
     ControllerCompartment(Envelope<Box<Compartment>>),
@@ -105,4 +110,40 @@ mod tests {
         serde_json::to_string_pretty(&mapping).unwrap();
         // std::fs::write("src/schema/test/example_from_lua.json", json).unwrap();
     }
+
+    #[test]
+    fn transformations_from_lua() {
+        use mlua::{Lua, LuaSerdeExt};
+        let lua = Lua::new();
+        let value = lua
+            .load(
+                r#"
+                return {
+                    control_transformation = "y = 1 - x",
+                    feedback = {
+                        kind = "Numeric",
+                        transformation = {
+                            language = "lua",
+                            script = "x = 1 - y",
+                        },
+                    },
+                }
+                "#,
+            )
+            .eval()
+            .unwrap();
+        let glue: Glue = lua.from_value(value).unwrap();
+        assert_eq!(
+            glue.control_transformation,
+            Some(Transformation::Eel("y = 1 - x".to_string()))
+        );
+        let feedback_transformation = match glue.feedback {
+            Some(Feedback::Numeric(fb)) => fb.transformation,
+            _ => panic!("numeric feedback expected"),
+        };
+        assert_eq!(
+            feedback_transformation.map(Transformation::into_parts),
+            Some((TransformationLanguage::Lua, "x = 1 - y".to_string()))
+        );
+    }
 }
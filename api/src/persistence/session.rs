@@ -1,12 +1,188 @@
 use crate::persistence::*;
 use schemars::JsonSchema;
 
-/// Only used for JSON schema generation at the moment.
-#[derive(JsonSchema)]
+/// A complete ReaLearn instance.
+///
+/// The state of the Pot preset browser is not part of this because it's UI state that only makes
+/// sense on the machine on which it was saved.
+#[derive(Default, Serialize, Deserialize, JsonSchema)]
 pub struct Session {
-    _main_compartment: Option<Compartment>,
-    _clip_matrix: Option<Matrix>,
-    _mapping_snapshots: Vec<MappingSnapshot>,
+    /// Session ID which can be used to refer to this instance from elsewhere.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_input: Option<ControlInput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feedback_output: Option<FeedbackOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub let_matched_events_through: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub let_unmatched_events_through: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stay_active_when_project_in_background: Option<StayActiveWhenProjectInBackground>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_correct_settings: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lives_on_upper_floor: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_feedback_only_if_armed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reset_feedback_when_releasing_source: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speech: Option<SpeechSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_preset_auto_load_mode: Option<MainPresetAutoLoadMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_track: Option<TrackDescriptor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_fx: Option<FxDescriptor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Tags of the instance which are currently active (used by "ReaLearn: Enable/disable
+    /// instances" targets).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_instance_tags: Option<Vec<String>>,
+    /// Links from FX to main presets which are specific to this instance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_preset_links: Option<Vec<PresetLink>>,
+    /// If `true`, the global preset links are ignored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_instance_preset_links_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub controller: Option<SessionCompartment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main: Option<SessionCompartment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clip_matrix: Option<ClipMatrixRef>,
+    /// Main compartment which has been memorized in order to be restored later (when
+    /// auto-loading presets and the instance FX doesn't have a linked preset anymore).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memorized_main_compartment: Option<Compartment>,
+}
+
+/// Speech settings of the instance, used by speech sources unless they override them.
+#[derive(Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SpeechSettings {
+    /// Name of the voice. If not set, the default voice of the speech backend is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+    /// Speech rate in percent of the normal rate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_policy: Option<SpeechQueuePolicy>,
+}
+
+/// A compartment as part of a session, including the instance-specific state that a preset
+/// doesn't contain.
+///
+/// The active mapping of each exclusive group is not part of this because it refers to technical
+/// IDs which are not stable between ReaLearn sessions.
+#[derive(Default, Serialize, Deserialize, JsonSchema)]
+pub struct SessionCompartment {
+    /// ID of the preset which is currently loaded into this compartment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_preset_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compartment: Option<Compartment>,
+    /// Current values of the compartment parameters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter_values: Option<Vec<ParameterValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping_snapshots: Option<Vec<MappingSnapshot>>,
+    /// Mapping tags which are currently active (used by "ReaLearn: Enable/disable mappings"
+    /// targets).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_mapping_tags: Option<Vec<String>>,
+    /// Mapping snapshots which are currently active, one for each snapshot tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_mapping_snapshots: Option<Vec<ActiveMappingSnapshot>>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ActiveMappingSnapshot {
+    pub tag: String,
+    pub id: String,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PresetLink {
+    pub fx: PresetLinkFx,
+    pub preset_id: String,
+}
+
+/// Identifies the FX to which a preset is linked. Empty properties match any FX.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PresetLinkFx {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset_name: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ParameterValue {
+    pub index: u32,
+    pub value: f64,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind")]
+pub enum ControlInput {
+    FxInput,
+    Keyboard,
+    MidiDevice { id: u8 },
+    OscDevice { id: String },
+}
+
+impl Default for ControlInput {
+    fn default() -> Self {
+        Self::FxInput
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind")]
+pub enum FeedbackOutput {
+    FxOutput,
+    MidiDevice { id: u8 },
+    OscDevice { id: String },
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum StayActiveWhenProjectInBackground {
+    Never,
+    OnlyIfBackgroundProjectIsRunning,
+    Always,
+}
+
+impl Default for StayActiveWhenProjectInBackground {
+    fn default() -> Self {
+        Self::OnlyIfBackgroundProjectIsRunning
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum MainPresetAutoLoadMode {
+    Off,
+    InstanceFx,
+}
+
+impl Default for MainPresetAutoLoadMode {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind")]
+pub enum ClipMatrixRef {
+    /// A clip matrix owned by this instance.
+    Own(Box<Matrix>),
+    /// A reference to the clip matrix of another instance.
+    Foreign { session_id: String },
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
@@ -1,5 +1,8 @@
+use derive_more::Display;
+use enum_iterator::IntoEnumIterator;
 pub use keyboard::*;
 pub use midi::*;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 pub use osc::*;
 pub use reaper::*;
 use schemars::JsonSchema;
@@ -17,6 +20,8 @@ pub enum Source {
     MidiDeviceChanges(MidiDeviceChangesSource),
     RealearnInstanceStart(RealearnInstanceStartSource),
     Timer(TimerSource),
+    MusicalTimer(MusicalTimerSource),
+    AudioLevel(AudioLevelSource),
     RealearnParameter(RealearnParameterSource),
     Speech(SpeechSource),
     // MIDI
@@ -33,6 +38,8 @@ pub enum Source {
     MidiClockTransport(MidiClockTransportSource),
     MidiRaw(MidiRawSource),
     MidiScript(MidiScriptSource),
+    MidiMpe(MidiMpeSource),
+    MidiChord(MidiChordSource),
     MackieLcd(MackieLcdSource),
     XTouchMackieLcd(XTouchMackieLcdSource),
     MackieSevenSegmentDisplay(MackieSevenSegmentDisplaySource),
@@ -45,6 +52,7 @@ pub enum Source {
     Key(KeySource),
     // Virtual
     Virtual(VirtualSource),
+    VirtualChord(VirtualChordSource),
 }
 
 impl Default for Source {
@@ -67,8 +75,42 @@ impl Default for FeedbackBehavior {
     }
 }
 
+/// Decides when the elements of a chord source count as "chord played".
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Display,
+    IntoEnumIterator,
+    TryFromPrimitive,
+    IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum ChordMode {
+    /// All elements held and no other element of the same kind held.
+    #[display(fmt = "Exact")]
+    Exact,
+    /// All elements held, other elements may be held as well.
+    #[display(fmt = "Contains")]
+    Contains,
+    /// All elements pressed one after the other in the given order.
+    #[display(fmt = "Sequence")]
+    Sequence,
+}
+
+impl Default for ChordMode {
+    fn default() -> Self {
+        Self::Exact
+    }
+}
+
 mod midi {
-    use crate::persistence::FeedbackBehavior;
+    use crate::persistence::{ChordMode, FeedbackBehavior};
     use derive_more::Display;
     use enum_iterator::IntoEnumIterator;
     use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -193,8 +235,12 @@ mod midi {
     pub struct MidiScriptSource {
         #[serde(skip_serializing_if = "Option::is_none")]
         pub kind: Option<MidiScriptKind>,
+        /// Feedback script (encodes feedback values as MIDI messages).
         #[serde(skip_serializing_if = "Option::is_none")]
         pub script: Option<String>,
+        /// Control script (decodes incoming MIDI messages).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub control_script: Option<String>,
     }
 
     /// Kind of a MIDI script
@@ -228,6 +274,143 @@ mod midi {
         }
     }
 
+    /// Source which tracks the notes played on an MPE (MIDI Polyphonic Expression) controller.
+    #[derive(Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+    pub struct MidiMpeSource {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub zone: Option<MpeZone>,
+        /// Number of member channels of the zone (1 to 15).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub member_channel_count: Option<u8>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub dimension: Option<MpeDimension>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub note: Option<MpeNoteSelector>,
+        /// Zero-rooted index of the held note, in the order in which the notes were pressed.
+        ///
+        /// Only relevant for [`MpeNoteSelector::HeldNote`].
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub held_note_index: Option<u8>,
+    }
+
+    /// MPE zone whose member channels are tracked.
+    #[derive(
+        Copy,
+        Clone,
+        Eq,
+        PartialEq,
+        Debug,
+        Serialize,
+        Deserialize,
+        JsonSchema,
+        Display,
+        IntoEnumIterator,
+        TryFromPrimitive,
+        IntoPrimitive,
+    )]
+    #[repr(usize)]
+    pub enum MpeZone {
+        /// Master channel 1, member channels counting upwards from channel 2.
+        #[display(fmt = "Lower zone")]
+        Lower,
+        /// Master channel 16, member channels counting downwards from channel 15.
+        #[display(fmt = "Upper zone")]
+        Upper,
+    }
+
+    impl Default for MpeZone {
+        fn default() -> Self {
+            Self::Lower
+        }
+    }
+
+    /// Per-note dimension which is exposed as control value.
+    #[derive(
+        Copy,
+        Clone,
+        Eq,
+        PartialEq,
+        Debug,
+        Serialize,
+        Deserialize,
+        JsonSchema,
+        Display,
+        IntoEnumIterator,
+        TryFromPrimitive,
+        IntoPrimitive,
+    )]
+    #[repr(usize)]
+    pub enum MpeDimension {
+        /// Note-on velocity (note-off yields zero).
+        #[display(fmt = "Velocity (strike)")]
+        Velocity,
+        #[display(fmt = "Note number")]
+        NoteNumber,
+        /// Per-note pitch bend.
+        #[display(fmt = "Pitch bend (glide)")]
+        PitchBend,
+        /// Per-note channel pressure.
+        #[display(fmt = "Pressure")]
+        Pressure,
+        /// Per-note CC 74.
+        #[display(fmt = "Slide (CC 74)")]
+        Slide,
+    }
+
+    impl Default for MpeDimension {
+        fn default() -> Self {
+            Self::Pressure
+        }
+    }
+
+    /// Decides which of the currently played notes is used.
+    #[derive(
+        Copy,
+        Clone,
+        Eq,
+        PartialEq,
+        Debug,
+        Serialize,
+        Deserialize,
+        JsonSchema,
+        Display,
+        IntoEnumIterator,
+        TryFromPrimitive,
+        IntoPrimitive,
+    )]
+    #[repr(usize)]
+    pub enum MpeNoteSelector {
+        /// The most recently pressed note.
+        #[display(fmt = "Last touched note")]
+        LastTouched,
+        /// The n-th held note (in the order in which the notes were pressed).
+        #[display(fmt = "Held note")]
+        HeldNote,
+    }
+
+    impl Default for MpeNoteSelector {
+        fn default() -> Self {
+            Self::LastTouched
+        }
+    }
+
+    /// Source which fires when a combination of notes is played.
+    #[derive(Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+    pub struct MidiChordSource {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub channel: Option<u8>,
+        /// Key numbers of the notes which make up the chord.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub notes: Option<Vec<u8>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub mode: Option<ChordMode>,
+        /// Maximum time in milliseconds between the first and the last note of the chord.
+        ///
+        /// Zero means there's no time limit.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub time_window: Option<u64>,
+    }
+
     #[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
     pub enum SourceCharacter {
         Range,
@@ -332,10 +515,21 @@ mod osc {
     pub struct OscSource {
         #[serde(skip_serializing_if = "Option::is_none")]
         pub feedback_behavior: Option<FeedbackBehavior>,
+        /// OSC address pattern.
+        ///
+        /// Can contain placeholders such as `/track/{n}/volume`. Each placeholder matches a number,
+        /// which is then available in target expressions as variable with the placeholder name.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub address: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub argument: Option<OscArgument>,
+        /// Indexes of further arguments to be bound in addition to the primary `argument`.
+        ///
+        /// They share kind, value range and relativity with the primary argument. Each one
+        /// produces its own control value, addressing the virtual control elements following the
+        /// one of the virtual target.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub additional_argument_indexes: Option<Vec<u32>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub relative: Option<bool>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -354,13 +548,241 @@ mod reaper {
         pub parameter_index: u32,
     }
 
+    /// Settings which are not set are taken from the speech settings of the instance.
     #[derive(Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
-    pub struct SpeechSource {}
+    pub struct SpeechSource {
+        /// Name of the voice.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub voice: Option<String>,
+        /// Speech rate in percent of the normal rate.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub rate: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub queue_policy: Option<SpeechQueuePolicy>,
+    }
+
+    /// Decides what happens if a new text should be spoken while the previous one is still being
+    /// spoken.
+    #[derive(
+        Copy,
+        Clone,
+        Eq,
+        PartialEq,
+        Debug,
+        Serialize,
+        Deserialize,
+        JsonSchema,
+        Display,
+        IntoEnumIterator,
+        TryFromPrimitive,
+        IntoPrimitive,
+    )]
+    #[repr(usize)]
+    pub enum SpeechQueuePolicy {
+        /// Stops the previous text and speaks the new one immediately.
+        #[display(fmt = "Interrupt")]
+        Interrupt,
+        /// Speaks the new text as soon as the previous texts have been spoken.
+        #[display(fmt = "Enqueue")]
+        Enqueue,
+    }
+
+    impl Default for SpeechQueuePolicy {
+        fn default() -> Self {
+            Self::Interrupt
+        }
+    }
 
     #[derive(Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
     pub struct TimerSource {
         pub duration: u64,
     }
+
+    /// Timer which fires on musical positions of the project timeline.
+    #[derive(Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+    pub struct MusicalTimerSource {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub trigger: Option<MusicalTimerTrigger>,
+        /// Number of bars or beats between two firings.
+        ///
+        /// Only relevant for [`MusicalTimerTrigger::EveryNBars`] and
+        /// [`MusicalTimerTrigger::EveryNBeats`].
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub interval: Option<u32>,
+        /// Bar numbers (as displayed in REAPER, starting with 1) on which to fire.
+        ///
+        /// Only relevant for [`MusicalTimerTrigger::SpecificBars`].
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bars: Option<Vec<u32>>,
+        /// If `true`, the timer doesn't fire while the project is stopped (and the edit cursor is
+        /// moved around).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub only_while_playing: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub output: Option<MusicalTimerOutput>,
+    }
+
+    /// Musical positions on which a musical timer fires.
+    #[derive(
+        Copy,
+        Clone,
+        Eq,
+        PartialEq,
+        Debug,
+        Serialize,
+        Deserialize,
+        JsonSchema,
+        Display,
+        IntoEnumIterator,
+        TryFromPrimitive,
+        IntoPrimitive,
+    )]
+    #[repr(usize)]
+    pub enum MusicalTimerTrigger {
+        #[display(fmt = "Every n bars")]
+        EveryNBars,
+        #[display(fmt = "Every n beats")]
+        EveryNBeats,
+        #[display(fmt = "Specific bars")]
+        SpecificBars,
+        #[display(fmt = "Region start")]
+        RegionStart,
+        #[display(fmt = "Region end")]
+        RegionEnd,
+        #[display(fmt = "Marker")]
+        Marker,
+    }
+
+    impl Default for MusicalTimerTrigger {
+        fn default() -> Self {
+            Self::EveryNBars
+        }
+    }
+
+    /// What a musical timer emits.
+    #[derive(
+        Copy,
+        Clone,
+        Eq,
+        PartialEq,
+        Debug,
+        Serialize,
+        Deserialize,
+        JsonSchema,
+        Display,
+        IntoEnumIterator,
+        TryFromPrimitive,
+        IntoPrimitive,
+    )]
+    #[repr(usize)]
+    pub enum MusicalTimerOutput {
+        /// Emits 100% whenever the timer fires.
+        #[display(fmt = "Trigger")]
+        Trigger,
+        /// Continuously emits the progress through the current interval (0% to 100%).
+        #[display(fmt = "Ramp")]
+        Ramp,
+    }
+
+    impl Default for MusicalTimerOutput {
+        fn default() -> Self {
+            Self::Trigger
+        }
+    }
+
+    /// Source which turns the audio level of a track or hardware input into a control value.
+    #[derive(Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+    pub struct AudioLevelSource {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub input: Option<AudioLevelInput>,
+        /// Track position or hardware input channel, starting with 1.
+        ///
+        /// Not relevant for [`AudioLevelInput::ThisTrack`] and [`AudioLevelInput::MasterTrack`].
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub index: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub measurement: Option<AudioLevelMeasurement>,
+        /// Attack time of the envelope follower in milliseconds.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub attack: Option<u32>,
+        /// Release time of the envelope follower in milliseconds.
+        ///
+        /// Also used as reference for onset detection.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub release: Option<u32>,
+        /// Onsets below this level (in dB) are ignored.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub onset_threshold: Option<i32>,
+    }
+
+    /// Which signal an audio level source analyzes.
+    #[derive(
+        Copy,
+        Clone,
+        Eq,
+        PartialEq,
+        Debug,
+        Serialize,
+        Deserialize,
+        JsonSchema,
+        Display,
+        IntoEnumIterator,
+        TryFromPrimitive,
+        IntoPrimitive,
+    )]
+    #[repr(usize)]
+    pub enum AudioLevelInput {
+        /// The track which contains ReaLearn.
+        #[display(fmt = "This track")]
+        ThisTrack,
+        #[display(fmt = "Track by position")]
+        TrackByPosition,
+        #[display(fmt = "Master track")]
+        MasterTrack,
+        #[display(fmt = "Hardware input")]
+        HardwareInput,
+    }
+
+    impl Default for AudioLevelInput {
+        fn default() -> Self {
+            Self::ThisTrack
+        }
+    }
+
+    /// What an audio level source measures.
+    #[derive(
+        Copy,
+        Clone,
+        Eq,
+        PartialEq,
+        Debug,
+        Serialize,
+        Deserialize,
+        JsonSchema,
+        Display,
+        IntoEnumIterator,
+        TryFromPrimitive,
+        IntoPrimitive,
+    )]
+    #[repr(usize)]
+    pub enum AudioLevelMeasurement {
+        #[display(fmt = "Peak")]
+        Peak,
+        #[display(fmt = "RMS")]
+        Rms,
+        /// Envelope follower with configurable attack and release.
+        #[display(fmt = "Envelope")]
+        Envelope,
+        /// Emits 100% whenever a transient is detected.
+        #[display(fmt = "Onset")]
+        Onset,
+    }
+
+    impl Default for AudioLevelMeasurement {
+        fn default() -> Self {
+            Self::Peak
+        }
+    }
 }
 
 mod keyboard {
@@ -380,7 +802,7 @@ mod keyboard {
 }
 
 mod virt {
-    use crate::persistence::{VirtualControlElementCharacter, VirtualControlElementId};
+    use crate::persistence::{ChordMode, VirtualControlElementCharacter, VirtualControlElementId};
     use schemars::JsonSchema;
     use serde::{Deserialize, Serialize};
 
@@ -390,4 +812,18 @@ mod virt {
         #[serde(skip_serializing_if = "Option::is_none")]
         pub character: Option<VirtualControlElementCharacter>,
     }
+
+    /// Source which fires when a combination of virtual buttons is pressed.
+    #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+    pub struct VirtualChordSource {
+        /// IDs of the virtual buttons which make up the chord.
+        pub ids: Vec<VirtualControlElementId>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub mode: Option<ChordMode>,
+        /// Maximum time in milliseconds between the first and the last button press.
+        ///
+        /// Zero means there's no time limit.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub time_window: Option<u64>,
+    }
 }
@@ -1,5 +1,5 @@
 use crate::persistence::{
-    OscArgument, TargetValue, VirtualControlElementCharacter, VirtualControlElementId,
+    OscArgument, TargetValue, VirtualControlElementCharacter, VirtualControlElementId, XyAxis,
 };
 use derive_more::Display;
 use enum_iterator::IntoEnumIterator;
@@ -23,6 +23,8 @@ pub enum Target {
     PlayRate(PlayRateTarget),
     Tempo(TempoTarget),
     GoToBookmark(GoToBookmarkTarget),
+    MarkerManagement(MarkerManagementTarget),
+    TimeRange(TimeRangeTarget),
     TrackArmState(TrackArmStateTarget),
     TrackParentSendState(TrackParentSendStateTarget),
     AllTrackFxOnOffState(AllTrackFxOnOffStateTarget),
@@ -40,6 +42,7 @@ pub enum Target {
     TrackTool(TrackToolTarget),
     TrackVisibility(TrackVisibilityTarget),
     TrackSoloState(TrackSoloStateTarget),
+    BrowseItems(BrowseItemsTarget),
     #[serde(alias = "CycleThroughFx")]
     BrowseFxChain(BrowseFxChainTarget),
     FxOnOffState(FxOnOffStateTarget),
@@ -59,6 +62,20 @@ pub enum Target {
     RoutePan(RoutePanTarget),
     RouteVolume(RouteVolumeTarget),
     RouteTouchState(RouteTouchStateTarget),
+    EnvelopeArmState(EnvelopeArmStateTarget),
+    EnvelopeVisibility(EnvelopeVisibilityTarget),
+    EnvelopeWriteValue(EnvelopeWriteValueTarget),
+    BrowseAutomationItems(BrowseAutomationItemsTarget),
+    ItemVolume(ItemVolumeTarget),
+    ItemMuteState(ItemMuteStateTarget),
+    ItemLockState(ItemLockStateTarget),
+    ItemSelectionState(ItemSelectionStateTarget),
+    ItemPosition(ItemPositionTarget),
+    BrowseItemTakes(BrowseItemTakesTarget),
+    TakeVolume(TakeVolumeTarget),
+    TakePitch(TakePitchTarget),
+    TakePlayRate(TakePlayRateTarget),
+    TakeFxParameterValue(TakeFxParameterValueTarget),
     ClipTransportAction(ClipTransportActionTarget),
     ClipColumnAction(ClipColumnTarget),
     ClipRowAction(ClipRowTarget),
@@ -215,6 +232,169 @@ pub struct GoToBookmarkTarget {
     pub seek_behavior: Option<SeekBehavior>,
 }
 
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TimeRangeTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<TimeRangeKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<TimeRangeAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<TimeRangeQuantization>,
+    /// Zero-based index counting regions only (relevant for action "Set to region").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region_index: Option<u32>,
+    /// Relevant for action "Set to bars".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bar_count: Option<u32>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    derive_more::Display,
+    enum_iterator::IntoEnumIterator,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum TimeRangeKind {
+    #[display(fmt = "Time selection")]
+    TimeSelection,
+    #[display(fmt = "Loop points")]
+    LoopPoints,
+}
+
+impl Default for TimeRangeKind {
+    fn default() -> Self {
+        Self::TimeSelection
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    derive_more::Display,
+    enum_iterator::IntoEnumIterator,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum TimeRangeAction {
+    #[display(fmt = "Move start")]
+    MoveStart,
+    #[display(fmt = "Move end")]
+    MoveEnd,
+    #[display(fmt = "Set to region")]
+    SetToRegion,
+    #[display(fmt = "Set to bars")]
+    SetToBars,
+}
+
+impl Default for TimeRangeAction {
+    fn default() -> Self {
+        Self::MoveStart
+    }
+}
+
+impl TimeRangeAction {
+    pub fn moves_edge(&self) -> bool {
+        matches!(self, Self::MoveStart | Self::MoveEnd)
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    derive_more::Display,
+    enum_iterator::IntoEnumIterator,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum TimeRangeQuantization {
+    #[display(fmt = "Off")]
+    Off,
+    #[display(fmt = "Grid")]
+    Grid,
+    #[display(fmt = "Bars")]
+    Bars,
+}
+
+impl Default for TimeRangeQuantization {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MarkerManagementTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<MarkerManagementAction>,
+    /// Name of new markers/regions or new name of the renamed marker.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    derive_more::Display,
+    enum_iterator::IntoEnumIterator,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum MarkerManagementAction {
+    #[display(fmt = "Add marker at cursor")]
+    AddMarker,
+    #[display(fmt = "Rename nearest marker")]
+    RenameNearestMarker,
+    #[display(fmt = "Delete nearest marker")]
+    DeleteNearestMarker,
+    #[display(fmt = "Create region from time selection")]
+    CreateRegionFromTimeSelection,
+}
+
+impl Default for MarkerManagementAction {
+    fn default() -> Self {
+        Self::AddMarker
+    }
+}
+
+impl MarkerManagementAction {
+    pub fn uses_name(&self) -> bool {
+        !matches!(self, Self::DeleteNearestMarker)
+    }
+}
+
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
 pub struct TrackArmStateTarget {
     #[serde(flatten)]
@@ -571,6 +751,18 @@ pub struct TrackSoloStateTarget {
     pub use_selection_ganging: Option<bool>,
 }
 
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct BrowseItemsTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_arrange_view: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_edit_cursor: Option<bool>,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct BrowseFxChainTarget {
     #[serde(flatten)]
@@ -743,6 +935,124 @@ pub struct RouteTouchStateTarget {
     pub touched_parameter: TouchedRouteParameter,
 }
 
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EnvelopeArmStateTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub envelope: EnvelopeDescriptor,
+}
+
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EnvelopeVisibilityTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub envelope: EnvelopeDescriptor,
+}
+
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EnvelopeWriteValueTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub envelope: EnvelopeDescriptor,
+}
+
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct BrowseAutomationItemsTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub envelope: EnvelopeDescriptor,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ItemVolumeTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ItemMuteStateTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ItemLockStateTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ItemSelectionStateTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ItemPositionTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BrowseItemTakesTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TakeVolumeTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TakePitchTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TakePlayRateTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TakeFxParameterValueTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub item: ItemDescriptor,
+    pub fx_index: u32,
+    pub parameter_index: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ClipTransportActionTarget {
     #[serde(flatten)]
@@ -1046,6 +1356,11 @@ pub struct VirtualTarget {
     pub id: VirtualControlElementId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub character: Option<VirtualControlElementCharacter>,
+    /// The axis to be controlled if this is an XY control element.
+    ///
+    /// Values of additional OSC arguments go to the other axis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub axis: Option<XyAxis>,
 }
 
 #[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -1675,6 +1990,132 @@ pub enum RouteDescriptor {
     },
 }
 
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "address")]
+pub enum EnvelopeDescriptor {
+    /// One of the built-in track envelopes.
+    Track {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        track: Option<TrackDescriptor>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        kind: Option<TrackEnvelopeKind>,
+    },
+    /// Envelope of an FX parameter.
+    FxParameter { parameter: FxParameterDescriptor },
+    /// One of the envelopes of a send or receive.
+    Route {
+        route: RouteDescriptor,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        kind: Option<RouteEnvelopeKind>,
+    },
+}
+
+impl Default for EnvelopeDescriptor {
+    fn default() -> Self {
+        Self::Track {
+            track: None,
+            kind: None,
+        }
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    derive_more::Display,
+    enum_iterator::IntoEnumIterator,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum TrackEnvelopeKind {
+    #[display(fmt = "Volume")]
+    Volume,
+    #[display(fmt = "Pan")]
+    Pan,
+    #[display(fmt = "Width")]
+    Width,
+}
+
+impl Default for TrackEnvelopeKind {
+    fn default() -> Self {
+        Self::Volume
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    derive_more::Display,
+    enum_iterator::IntoEnumIterator,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum RouteEnvelopeKind {
+    #[display(fmt = "Volume")]
+    Volume,
+    #[display(fmt = "Pan")]
+    Pan,
+}
+
+impl Default for RouteEnvelopeKind {
+    fn default() -> Self {
+        Self::Volume
+    }
+}
+
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ItemDescriptorCommons {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "address")]
+pub enum ItemDescriptor {
+    /// Selected item(s), no matter on which track.
+    Selected {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        allow_multiple: Option<bool>,
+    },
+    /// Item on the track at the edit cursor position.
+    AtEditCursor {
+        #[serde(flatten)]
+        commons: ItemDescriptorCommons,
+    },
+    Dynamic {
+        #[serde(flatten)]
+        commons: ItemDescriptorCommons,
+        expression: String,
+    },
+    ByIndex {
+        #[serde(flatten)]
+        commons: ItemDescriptorCommons,
+        index: u32,
+    },
+}
+
+impl Default for ItemDescriptor {
+    fn default() -> Self {
+        Self::Selected {
+            allow_multiple: None,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum TrackRouteKind {
     Send,
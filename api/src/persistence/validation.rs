@@ -0,0 +1,842 @@
+use crate::persistence::ApiObject;
+use schemars::gen::SchemaSettings;
+use schemars::schema::{
+    ArrayValidation, InstanceType, NumberValidation, ObjectValidation, Schema, SchemaObject,
+    SingleOrVec,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt::{Display, Formatter};
+
+/// Value used for asking serde how it treats a certain field or enum variant name.
+///
+/// Aliases (`#[serde(alias)]`) are not part of the schema, so serde has the final say.
+const PROBE_VALUE: &str = "__realearn_validation_probe__";
+
+/// Validates the given API object (e.g. obtained from a Lua table).
+///
+/// If the value can be deserialized into an [`ApiObject`], this returns warnings for unknown
+/// fields (which would be silently ignored otherwise). If not, it returns errors for all values
+/// which violate the JSON schema of [`ApiObject`].
+///
+/// Paths are relative to the envelope value, e.g. `mappings[12].glue.step_size_interval`.
+pub fn validate_api_object(value: &Value) -> Vec<ValidationIssue> {
+    let mut issues = match round_trip::<ApiObject>(value) {
+        Some(normalized_value) => {
+            UnknownFieldFinder::new(value, &normalized_value, round_trip::<ApiObject>).find()
+        }
+        None => find_schema_violations(value),
+    };
+    for issue in &mut issues {
+        if issue.path.first() == Some(&PathSegment::Field("value".to_owned())) {
+            issue.path.remove(0);
+        }
+    }
+    issues
+}
+
+/// Returns warnings for all fields which are ignored when deserializing the given value as `T`.
+///
+/// Returns nothing if the value can't be deserialized at all.
+pub fn find_unknown_fields<T: DeserializeOwned + Serialize>(value: &Value) -> Vec<ValidationIssue> {
+    match round_trip::<T>(value) {
+        None => vec![],
+        Some(normalized_value) => {
+            UnknownFieldFinder::new(value, &normalized_value, round_trip::<T>).find()
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ValidationIssue {
+    pub level: ValidationIssueLevel,
+    pub path: Vec<PathSegment>,
+    pub message: String,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ValidationIssueLevel {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+impl ValidationIssue {
+    pub fn is_error(&self) -> bool {
+        self.level == ValidationIssueLevel::Error
+    }
+
+    /// Returns the path in a notation such as `mappings[12].glue.step_size_interval`.
+    ///
+    /// Use a `first_index` of 1 when reporting paths to Lua users.
+    pub fn path_expression(&self, first_index: usize) -> String {
+        if self.path.is_empty() {
+            return "<root>".to_owned();
+        }
+        let mut expression = String::new();
+        for segment in &self.path {
+            match segment {
+                PathSegment::Field(name) => {
+                    if !expression.is_empty() {
+                        expression.push('.');
+                    }
+                    expression.push_str(name);
+                }
+                PathSegment::Index(i) => {
+                    expression.push_str(&format!("[{}]", i + first_index));
+                }
+            }
+        }
+        expression
+    }
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path_expression(0), self.message)
+    }
+}
+
+/// Deserializes the given value and serializes it again, which yields a normalized value (no
+/// unknown fields, no aliases, no explicit default values).
+fn round_trip<T: DeserializeOwned + Serialize>(value: &Value) -> Option<Value> {
+    let object: T = serde_json::from_value(value.clone()).ok()?;
+    serde_json::to_value(object).ok()
+}
+
+/// Finds fields which serde ignores.
+///
+/// Fields which are missing in the normalized value are candidates. They are confirmed by
+/// replacing them with a probe value: If deserialization still succeeds and yields the same
+/// result, serde doesn't care about the field. Otherwise it's a known field (e.g. an alias or a
+/// field with a default value).
+struct UnknownFieldFinder<'a> {
+    root: &'a Value,
+    normalized_root: &'a Value,
+    round_trip: fn(&Value) -> Option<Value>,
+    path: Vec<PathSegment>,
+    issues: Vec<ValidationIssue>,
+}
+
+impl<'a> UnknownFieldFinder<'a> {
+    fn new(
+        root: &'a Value,
+        normalized_root: &'a Value,
+        round_trip: fn(&Value) -> Option<Value>,
+    ) -> Self {
+        Self {
+            root,
+            normalized_root,
+            round_trip,
+            path: vec![],
+            issues: vec![],
+        }
+    }
+
+    fn find(mut self) -> Vec<ValidationIssue> {
+        self.visit(self.root, self.normalized_root);
+        self.issues
+    }
+
+    fn visit(&mut self, value: &Value, normalized_value: &Value) {
+        match (value, normalized_value) {
+            (Value::Object(map), Value::Object(normalized_map)) => {
+                for (key, v) in map {
+                    self.path.push(PathSegment::Field(key.clone()));
+                    match normalized_map.get(key) {
+                        Some(normalized_v) => self.visit(v, normalized_v),
+                        None => {
+                            if self.is_ignored() {
+                                self.issues.push(ValidationIssue {
+                                    level: ValidationIssueLevel::Warning,
+                                    path: self.path.clone(),
+                                    message: "Unknown field. It will be ignored.".to_owned(),
+                                });
+                            }
+                        }
+                    }
+                    self.path.pop();
+                }
+            }
+            (Value::Array(items), Value::Array(normalized_items))
+                if items.len() == normalized_items.len() =>
+            {
+                for (i, (v, normalized_v)) in items.iter().zip(normalized_items).enumerate() {
+                    self.path.push(PathSegment::Index(i));
+                    self.visit(v, normalized_v);
+                    self.path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn is_ignored(&self) -> bool {
+        let mut probe = self.root.clone();
+        match value_at_mut(&mut probe, &self.path) {
+            None => return false,
+            Some(v) => *v = Value::String(PROBE_VALUE.to_owned()),
+        }
+        (self.round_trip)(&probe).as_ref() == Some(self.normalized_root)
+    }
+}
+
+fn value_at_mut<'a>(value: &'a mut Value, path: &[PathSegment]) -> Option<&'a mut Value> {
+    path.iter().try_fold(value, |v, segment| match segment {
+        PathSegment::Field(key) => v.as_object_mut()?.get_mut(key),
+        PathSegment::Index(i) => v.as_array_mut()?.get_mut(*i),
+    })
+}
+
+/// Returns a copy of the given value which only contains the given path, with the given leaf at
+/// its end.
+///
+/// String siblings along the path are kept because they might be tags of internally tagged enums.
+/// Deserializing such a pruned value makes serde report problems with the leaf, not with
+/// anything else.
+fn prune(value: &Value, path: &[PathSegment], leaf: Value) -> Value {
+    let (segment, rest) = match path.split_first() {
+        None => return leaf,
+        Some(s) => s,
+    };
+    match (segment, value) {
+        (PathSegment::Field(key), Value::Object(map)) => {
+            let mut pruned_map: Map<String, Value> = map
+                .iter()
+                .filter(|(k, v)| *k != key && v.is_string())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            let child = map.get(key).unwrap_or(&Value::Null);
+            pruned_map.insert(key.clone(), prune(child, rest, leaf));
+            Value::Object(pruned_map)
+        }
+        (PathSegment::Index(i), Value::Array(items)) => {
+            let child = items.get(*i).unwrap_or(&Value::Null);
+            Value::Array(vec![prune(child, rest, leaf)])
+        }
+        _ => leaf,
+    }
+}
+
+fn find_schema_violations(value: &Value) -> Vec<ValidationIssue> {
+    let root_schema = SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<ApiObject>();
+    let mut validator = Validator {
+        root: value,
+        definitions: &root_schema.definitions,
+        path: vec![],
+        issues: vec![],
+    };
+    validator.validate(&Schema::Object(root_schema.schema.clone()), value);
+    validator.issues
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    definitions: &'a schemars::Map<String, Schema>,
+    path: Vec<PathSegment>,
+    issues: Vec<ValidationIssue>,
+}
+
+impl<'a> Validator<'a> {
+    fn validate(&mut self, schema: &Schema, value: &Value) {
+        let schema = match schema {
+            Schema::Bool(true) => return,
+            Schema::Bool(false) => {
+                self.error("Value not allowed here");
+                return;
+            }
+            Schema::Object(s) => s,
+        };
+        if let Some(s) = self.resolve_reference(schema) {
+            self.validate(s, value);
+            return;
+        }
+        if let Some(instance_type) = &schema.instance_type {
+            if !matches_instance_type(instance_type, value) {
+                self.error(format!(
+                    "Expected {} but found {}",
+                    describe_instance_type(instance_type),
+                    describe_value(value)
+                ));
+                return;
+            }
+        }
+        if let Some(enum_values) = &schema.enum_values {
+            if !enum_values.contains(value) && !self.is_alias(&self.path, value) {
+                self.error(format!(
+                    "Expected one of {} but found {}",
+                    join_values(enum_values),
+                    value
+                ));
+                return;
+            }
+        }
+        if let Some(const_value) = &schema.const_value {
+            if const_value != value && !self.is_alias(&self.path, value) {
+                self.error(format!("Expected {} but found {}", const_value, value));
+                return;
+            }
+        }
+        if let Some(subschemas) = &schema.subschemas {
+            if let Some(all_of) = &subschemas.all_of {
+                for s in all_of {
+                    self.validate(s, value);
+                }
+            }
+            if let Some(variants) = subschemas.any_of.as_ref().or(subschemas.one_of.as_ref()) {
+                self.validate_variants(variants, value);
+            }
+        }
+        match value {
+            Value::Object(map) => {
+                if let Some(object) = &schema.object {
+                    self.validate_object(object, map);
+                }
+            }
+            Value::Array(items) => {
+                if let Some(array) = &schema.array {
+                    self.validate_array(array, items);
+                }
+            }
+            Value::Number(_) => {
+                self.validate_number(schema.number.as_deref(), schema.format.as_deref(), value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Validates the value against the variant matching it best.
+    ///
+    /// For tagged enums, this is the variant with the matching tag. Otherwise, it's the
+    /// variant which produces the least errors.
+    fn validate_variants(&mut self, variants: &[Schema], value: &Value) {
+        // Variants whose tag is present and matches are preferred over variants without tag
+        let candidates_with_match_count: Vec<_> = variants
+            .iter()
+            .filter_map(|v| Some((v, self.count_matching_discriminators(v, value)?)))
+            .collect();
+        let max_match_count = candidates_with_match_count
+            .iter()
+            .map(|(_, count)| *count)
+            .max();
+        let candidates: Vec<_> = candidates_with_match_count
+            .into_iter()
+            .filter(|(_, count)| Some(*count) == max_match_count)
+            .map(|(v, _)| v)
+            .collect();
+        if candidates.is_empty() {
+            if let Some(issue) = self.create_discriminator_error(variants, value) {
+                self.issues.push(issue);
+            }
+            return;
+        }
+        let best_trial = candidates
+            .into_iter()
+            .map(|v| {
+                let mut trial = Validator {
+                    root: self.root,
+                    definitions: self.definitions,
+                    path: self.path.clone(),
+                    issues: vec![],
+                };
+                trial.validate(v, value);
+                trial
+            })
+            .min_by_key(|trial| trial.issues.iter().filter(|i| i.is_error()).count());
+        if let Some(trial) = best_trial {
+            self.issues.extend(trial.issues);
+        }
+    }
+
+    /// Returns how many discriminators of the given variant schema are present in the value or
+    /// `None` if one of them doesn't match.
+    fn count_matching_discriminators(&self, schema: &Schema, value: &Value) -> Option<usize> {
+        let map = match value {
+            Value::Object(map) => map,
+            _ => return Some(0),
+        };
+        let mut count = 0;
+        for (key, allowed) in self.discriminators(schema) {
+            match map.get(&key) {
+                None => {}
+                Some(actual) if actual == &allowed => count += 1,
+                Some(_) => return None,
+            }
+        }
+        Some(count)
+    }
+
+    /// Returns all properties of the given object schema which only allow one single value.
+    fn discriminators(&self, schema: &Schema) -> Vec<(String, Value)> {
+        let schema = match schema {
+            Schema::Object(s) => s,
+            Schema::Bool(_) => return vec![],
+        };
+        if let Some(s) = self.resolve_reference(schema) {
+            return self.discriminators(s);
+        }
+        let mut discriminators = vec![];
+        if let Some(object) = &schema.object {
+            for (key, property_schema) in &object.properties {
+                if let Schema::Object(p) = property_schema {
+                    if let Some(v) = single_allowed_value(p) {
+                        discriminators.push((key.clone(), v.clone()));
+                    }
+                }
+            }
+        }
+        if let Some(all_of) = schema.subschemas.as_ref().and_then(|s| s.all_of.as_ref()) {
+            for s in all_of {
+                discriminators.extend(self.discriminators(s));
+            }
+        }
+        discriminators
+    }
+
+    /// Returns `None` if the discriminator value is an alias of one of the variants.
+    fn create_discriminator_error(
+        &self,
+        variants: &[Schema],
+        value: &Value,
+    ) -> Option<ValidationIssue> {
+        let discriminators: Vec<_> = variants
+            .iter()
+            .flat_map(|v| self.discriminators(v))
+            .collect();
+        let key = match discriminators.first() {
+            None => return Some(self.create_issue(ValidationIssueLevel::Error, "Invalid value")),
+            Some((key, _)) => key,
+        };
+        let mut path = self.path.clone();
+        path.push(PathSegment::Field(key.clone()));
+        let actual = value.get(key).cloned().unwrap_or(Value::Null);
+        if self.is_alias(&path, &actual) {
+            return None;
+        }
+        let allowed_values: Vec<_> = discriminators
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .collect();
+        let issue = ValidationIssue {
+            level: ValidationIssueLevel::Error,
+            path,
+            message: format!(
+                "Unknown {} {}. Expected one of {}",
+                key,
+                actual,
+                join_values(&allowed_values)
+            ),
+        };
+        Some(issue)
+    }
+
+    /// Returns `true` if serde accepts the given string at the given path although the schema
+    /// doesn't allow it (e.g. an old enum variant name declared via `#[serde(alias)]`).
+    ///
+    /// We let serde deserialize a pruned version of the root value, once with the given string
+    /// and once with a probe value. If the errors differ, serde knows the string.
+    fn is_alias(&self, path: &[PathSegment], value: &Value) -> bool {
+        let actual = match value.as_str() {
+            None => return false,
+            Some(s) => s,
+        };
+        let deserialize_with = |leaf: &str| {
+            let pruned_value = prune(self.root, path, Value::String(leaf.to_owned()));
+            match serde_json::from_value::<ApiObject>(pruned_value) {
+                Ok(_) => None,
+                Err(e) => Some(
+                    e.to_string()
+                        .replace(&format!("`{}`", leaf), &format!("`{}`", PROBE_VALUE)),
+                ),
+            }
+        };
+        deserialize_with(actual) != deserialize_with(PROBE_VALUE)
+    }
+
+    fn validate_object(&mut self, object: &ObjectValidation, map: &Map<String, Value>) {
+        for key in &object.required {
+            if !map.contains_key(key) {
+                self.error(format!("Missing required field {}", key));
+            }
+        }
+        for (key, value) in map {
+            let property_schema = match object.properties.get(key) {
+                Some(s) => s,
+                None => match &object.additional_properties {
+                    Some(s) => s,
+                    // Unknown fields are not an error. They are reported as warnings if
+                    // deserialization succeeds.
+                    None => continue,
+                },
+            };
+            self.path.push(PathSegment::Field(key.clone()));
+            self.validate(property_schema, value);
+            self.path.pop();
+        }
+    }
+
+    fn validate_array(&mut self, array: &ArrayValidation, items: &[Value]) {
+        if let Some(min_items) = array.min_items {
+            if items.len() < min_items as usize {
+                self.error(format!("Expected at least {} items", min_items));
+            }
+        }
+        if let Some(max_items) = array.max_items {
+            if items.len() > max_items as usize {
+                self.error(format!("Expected at most {} items", max_items));
+            }
+        }
+        for (i, item) in items.iter().enumerate() {
+            let item_schema = match &array.items {
+                None => continue,
+                Some(SingleOrVec::Single(s)) => s.as_ref(),
+                Some(SingleOrVec::Vec(tuple)) => match tuple.get(i) {
+                    None => continue,
+                    Some(s) => s,
+                },
+            };
+            self.path.push(PathSegment::Index(i));
+            self.validate(item_schema, item);
+            self.path.pop();
+        }
+    }
+
+    fn validate_number(
+        &mut self,
+        number: Option<&NumberValidation>,
+        format: Option<&str>,
+        value: &Value,
+    ) {
+        let v = match value.as_f64() {
+            None => return,
+            Some(v) => v,
+        };
+        let format_maximum = match format {
+            Some("uint8") => Some(u8::MAX as f64),
+            Some("uint16") => Some(u16::MAX as f64),
+            Some("uint32") => Some(u32::MAX as f64),
+            _ => None,
+        };
+        let minimum = number.and_then(|n| n.minimum);
+        let maximum = number.and_then(|n| n.maximum).or(format_maximum);
+        if let Some(min) = minimum {
+            if v < min {
+                self.error(format!(
+                    "Expected a value of at least {} but found {}",
+                    min, v
+                ));
+            }
+        }
+        if let Some(max) = maximum {
+            if v > max {
+                self.error(format!(
+                    "Expected a value of at most {} but found {}",
+                    max, v
+                ));
+            }
+        }
+    }
+
+    fn resolve_reference(&self, schema: &SchemaObject) -> Option<&'a Schema> {
+        let reference = schema.reference.as_ref()?;
+        let name = reference.strip_prefix("#/definitions/")?;
+        self.definitions.get(name)
+    }
+
+    fn error(&mut self, message: impl Into<String>) {
+        let issue = self.create_issue(ValidationIssueLevel::Error, message);
+        self.issues.push(issue);
+    }
+
+    fn create_issue(
+        &self,
+        level: ValidationIssueLevel,
+        message: impl Into<String>,
+    ) -> ValidationIssue {
+        ValidationIssue {
+            level,
+            path: self.path.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+fn single_allowed_value(schema: &SchemaObject) -> Option<&Value> {
+    if let Some(v) = &schema.const_value {
+        return Some(v);
+    }
+    match schema.enum_values.as_deref() {
+        Some([v]) => Some(v),
+        _ => None,
+    }
+}
+
+fn matches_instance_type(instance_type: &SingleOrVec<InstanceType>, value: &Value) -> bool {
+    match instance_type {
+        SingleOrVec::Single(t) => matches_single_instance_type(**t, value),
+        SingleOrVec::Vec(types) => types
+            .iter()
+            .any(|t| matches_single_instance_type(*t, value)),
+    }
+}
+
+fn matches_single_instance_type(instance_type: InstanceType, value: &Value) -> bool {
+    use InstanceType::*;
+    match instance_type {
+        Null => value.is_null(),
+        Boolean => value.is_boolean(),
+        // Empty Lua tables can't be distinguished from empty arrays
+        Object => value.is_object() || value.as_array().map(|a| a.is_empty()).unwrap_or(false),
+        Array => value.is_array() || value.as_object().map(|o| o.is_empty()).unwrap_or(false),
+        Number => value.is_number(),
+        String => value.is_string(),
+        Integer => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().map(|v| v.fract() == 0.0) == Some(true)
+        }
+    }
+}
+
+fn describe_instance_type(instance_type: &SingleOrVec<InstanceType>) -> String {
+    match instance_type {
+        SingleOrVec::Single(t) => describe_single_instance_type(**t).to_owned(),
+        SingleOrVec::Vec(types) => {
+            let descriptions: Vec<_> = types
+                .iter()
+                .filter(|t| **t != InstanceType::Null)
+                .map(|t| describe_single_instance_type(*t))
+                .collect();
+            descriptions.join(" or ")
+        }
+    }
+}
+
+fn describe_single_instance_type(instance_type: InstanceType) -> &'static str {
+    use InstanceType::*;
+    match instance_type {
+        Null => "nothing",
+        Boolean => "a boolean",
+        Object => "a table",
+        Array => "a list",
+        Number => "a number",
+        String => "a string",
+        Integer => "an integer",
+    }
+}
+
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Null => "nothing".to_owned(),
+        Value::Object(_) => "a table".to_owned(),
+        Value::Array(_) => "a list".to_owned(),
+        v => v.to_string(),
+    }
+}
+
+fn join_values(values: &[Value]) -> String {
+    let strings: Vec<_> = values.iter().map(|v| v.to_string()).collect();
+    strings.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn valid_compartment() {
+        // Given
+        let value = json!({
+            "kind": "MainCompartment",
+            "value": {
+                "mappings": [
+                    {
+                        "id": "volume",
+                        "source": {
+                            "kind": "MidiControlChangeValue",
+                            "channel": 0,
+                            "controller_number": 7
+                        },
+                        "glue": {
+                            "source_interval": [0.0, 0.5]
+                        },
+                        "target": {
+                            "kind": "TrackVolume"
+                        }
+                    }
+                ]
+            }
+        });
+        // When
+        let issues = validate_api_object(&value);
+        // Then
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    fn invalid_values() {
+        // Given
+        let value = json!({
+            "kind": "MainCompartment",
+            "value": {
+                "mappings": [
+                    {},
+                    {
+                        "source": {
+                            "kind": "MidiControlChangeValue",
+                            "channel": 300
+                        },
+                        "glue": {
+                            "step_size_interval": "small"
+                        },
+                        "target": {
+                            "kind": "TrackVolumes"
+                        }
+                    }
+                ]
+            }
+        });
+        // When
+        let issues = validate_api_object(&value);
+        // Then
+        let errors: Vec<_> = issues
+            .iter()
+            .filter(|i| i.is_error())
+            .map(|i| i.path_expression(1))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "mappings[2].glue.step_size_interval",
+                "mappings[2].source.channel",
+                "mappings[2].target.kind"
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_fields() {
+        // Given
+        let value = json!({
+            "kind": "Mapping",
+            "value": {
+                "name": "Test",
+                "nmae": "Test",
+                "target": {
+                    "kind": "TrackVolume",
+                    "trakc": {
+                        "address": "Selected"
+                    }
+                }
+            }
+        });
+        // When
+        let mut issues = validate_api_object(&value);
+        // Then
+        issues.sort_by_key(|i| i.path_expression(0));
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue {
+                    level: ValidationIssueLevel::Warning,
+                    path: vec![PathSegment::Field("nmae".to_owned())],
+                    message: "Unknown field. It will be ignored.".to_owned()
+                },
+                ValidationIssue {
+                    level: ValidationIssueLevel::Warning,
+                    path: vec![
+                        PathSegment::Field("target".to_owned()),
+                        PathSegment::Field("trakc".to_owned())
+                    ],
+                    message: "Unknown field. It will be ignored.".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn field_aliases_are_no_unknown_fields() {
+        // Given
+        let value = json!({
+            "kind": "Mapping",
+            "value": {
+                "target": {
+                    "kind": "AutomationModeOverride",
+                    "override": {
+                        "kind": "Bypass"
+                    }
+                }
+            }
+        });
+        // When
+        let issues = validate_api_object(&value);
+        // Then
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    fn enum_variant_aliases_are_no_errors() {
+        // Given
+        let value = json!({
+            "kind": "MainCompartment",
+            "value": {
+                "mappings": [
+                    {
+                        "source": {
+                            "kind": "MidiControlChangeValue",
+                            "channel": 300
+                        },
+                        "target": {
+                            "kind": "CycleThroughTracks"
+                        }
+                    },
+                    {
+                        "target": {
+                            "kind": "ReaperAction",
+                            "invocation": "Absolute"
+                        }
+                    }
+                ]
+            }
+        });
+        // When
+        let issues = validate_api_object(&value);
+        // Then
+        let errors: Vec<_> = issues
+            .iter()
+            .filter(|i| i.is_error())
+            .map(|i| i.path_expression(1))
+            .collect();
+        assert_eq!(errors, vec!["mappings[1].source.channel"]);
+    }
+
+    #[test]
+    fn unknown_fields_of_arbitrary_type() {
+        // Given
+        let value = json!({
+            "name": "Test",
+            "nmae": "Test",
+        });
+        // When
+        let issues = find_unknown_fields::<crate::persistence::Mapping>(&value);
+        // Then
+        assert_eq!(
+            issues,
+            vec![ValidationIssue {
+                level: ValidationIssueLevel::Warning,
+                path: vec![PathSegment::Field("nmae".to_owned())],
+                message: "Unknown field. It will be ignored.".to_owned()
+            }]
+        );
+    }
+}
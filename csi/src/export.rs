@@ -0,0 +1,586 @@
+use crate::schema::{Acceleration, Accelerations, Capability, Widget};
+use crate::{Annotator, CsiResult};
+use helgoboss_midi::{
+    Channel, ControllerNumber, KeyNumber, RawShortMessage, ShortMessageFactory, U14, U7,
+};
+use realearn_api::persistence::{
+    ButtonFilter, Compartment, Glue, MackieLcdSource, MackieSevenSegmentDisplayScope,
+    MackieSevenSegmentDisplaySource, Mapping, MidiControlChangeValueSource, MidiNoteVelocitySource,
+    MidiPitchBendChangeValueSource, MidiPolyphonicKeyPressureAmountSource, MidiRawSource, Source,
+    SourceCharacter, Target, VirtualControlElementCharacter, VirtualControlElementId,
+    VirtualTarget,
+};
+use std::convert::TryFrom;
+
+/// Highest acceleration which can be expressed without overlapping increments and decrements.
+const MAX_ACCELERATION: u8 = 63;
+
+pub(crate) fn convert_compartment_to_widgets(
+    compartment: Compartment,
+    annotator: &mut Annotator,
+) -> Vec<Widget> {
+    let mut widgets: Vec<Widget> = vec![];
+    let mappings = compartment.mappings.unwrap_or_default();
+    for (i, mapping) in mappings.into_iter().enumerate() {
+        let context = match &mapping.name {
+            None => format!("Mapping {}", i + 1),
+            Some(name) => format!("Mapping \"{}\"", name),
+        };
+        annotator.with_context(context, |annotator| {
+            match convert_mapping(mapping, annotator) {
+                Ok(None) => {}
+                Ok(Some(res)) => match widgets.iter_mut().find(|w| w.name == res.widget_name) {
+                    None => widgets.push(Widget {
+                        name: res.widget_name,
+                        capabilities: res.capabilities,
+                    }),
+                    Some(w) => w.capabilities.extend(res.capabilities),
+                },
+                Err(e) => annotator.warn(e.to_string()),
+            }
+        });
+    }
+    widgets
+}
+
+struct MappingConvResult {
+    widget_name: String,
+    capabilities: Vec<Capability>,
+}
+
+fn convert_mapping(
+    mapping: Mapping,
+    annotator: &mut Annotator,
+) -> CsiResult<Option<MappingConvResult>> {
+    if mapping.enabled == Some(false) {
+        annotator.info("Mapping is disabled and was therefore not exported.");
+        return Ok(None);
+    }
+    let target = match mapping.target {
+        Some(Target::Virtual(t)) => t,
+        _ => {
+            return Err("Only mappings with virtual targets can be expressed as CSI widgets".into())
+        }
+    };
+    let element = convert_virtual_target(&target, annotator);
+    let input = SourceConvInput {
+        control: mapping.control_enabled != Some(false),
+        feedback: mapping.feedback_enabled != Some(false),
+        role: element.role,
+        glue: mapping.glue.unwrap_or_default(),
+    };
+    if !input.control && !input.feedback {
+        return Err("Neither control nor feedback is enabled".into());
+    }
+    let source = mapping.source.unwrap_or_default();
+    let capabilities = convert_source(source, &input, annotator)?;
+    let res = MappingConvResult {
+        widget_name: element.widget_name,
+        capabilities,
+    };
+    Ok(Some(res))
+}
+
+/// The part of a widget that's addressed by a virtual control element.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum WidgetRole {
+    Main,
+    Touch,
+    Push,
+}
+
+struct ControlElementConvResult {
+    widget_name: String,
+    role: WidgetRole,
+}
+
+fn convert_virtual_target(
+    target: &VirtualTarget,
+    annotator: &mut Annotator,
+) -> ControlElementConvResult {
+    let id = match &target.id {
+        VirtualControlElementId::Named(name) => name.clone(),
+        VirtualControlElementId::Indexed(i) => {
+            let prefix = match target.character.unwrap_or_default() {
+                VirtualControlElementCharacter::Multi => "Multi",
+                VirtualControlElementCharacter::Button => "Button",
+                VirtualControlElementCharacter::Xy => "Xy",
+            };
+            format!("{}{}", prefix, i + 1)
+        }
+    };
+    // The CSI-to-ReaLearn conversion produces IDs such as "Fader1/touch", so we do the reverse.
+    let (base, role) = match id.split_once('/') {
+        Some((base, "touch")) => (base, WidgetRole::Touch),
+        Some((base, "push")) => (base, WidgetRole::Push),
+        _ => (id.as_str(), WidgetRole::Main),
+    };
+    let widget_name: String = base
+        .chars()
+        .map(|ch| {
+            if ch.is_alphanumeric() || matches!(ch, '-' | '_') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if widget_name != base {
+        annotator.info(format!(
+            "Control element ID \"{}\" contains characters which are not allowed in CSI widget names, therefore it was changed to \"{}\".",
+            base, widget_name
+        ));
+    }
+    ControlElementConvResult { widget_name, role }
+}
+
+struct SourceConvInput {
+    control: bool,
+    feedback: bool,
+    role: WidgetRole,
+    glue: Glue,
+}
+
+/// Describes which MIDI messages a source sends/receives.
+enum SourceShape {
+    /// Messages with a 7-bit value.
+    SevenBit {
+        min: RawShortMessage,
+        max: RawShortMessage,
+        character: SourceCharacter,
+    },
+    /// Messages with a 14-bit value.
+    FourteenBit {
+        min: RawShortMessage,
+        max: RawShortMessage,
+    },
+    /// Exactly one fixed message.
+    Fixed(RawShortMessage),
+    /// Sources that are directly supported by CSI feedback capabilities.
+    Feedback(Capability),
+}
+
+fn convert_source(
+    source: Source,
+    input: &SourceConvInput,
+    annotator: &mut Annotator,
+) -> CsiResult<Vec<Capability>> {
+    let shape = determine_source_shape(source)?;
+    if input.role != WidgetRole::Main {
+        return convert_source_shape_for_touch_or_push(shape, input, annotator);
+    }
+    let mut capabilities = vec![];
+    match shape {
+        SourceShape::SevenBit {
+            min,
+            max,
+            character,
+        } => match character {
+            SourceCharacter::Button | SourceCharacter::StatefulButton => {
+                let (on, off) = on_and_off(min, max, &input.glue);
+                if input.control {
+                    let release = if input.glue.button_filter == Some(ButtonFilter::PressOnly) {
+                        None
+                    } else {
+                        Some(off)
+                    };
+                    capabilities.push(Capability::Press { press: on, release });
+                }
+                if input.feedback {
+                    capabilities.push(Capability::FbTwoState { on, off });
+                }
+            }
+            SourceCharacter::Range => {
+                if input.control {
+                    capabilities.push(Capability::Fader7Bit { max });
+                }
+                if input.feedback {
+                    capabilities.push(Capability::FbFader7Bit { max });
+                }
+            }
+            SourceCharacter::Relative1
+            | SourceCharacter::Relative2
+            | SourceCharacter::Relative3 => {
+                if input.control {
+                    let accelerations = create_accelerations(character, &input.glue, annotator);
+                    capabilities.push(Capability::Encoder {
+                        main: max,
+                        accelerations,
+                    });
+                }
+                if input.feedback {
+                    capabilities.push(Capability::FbEncoder { max });
+                }
+            }
+        },
+        SourceShape::FourteenBit { max, .. } => {
+            if input.control {
+                capabilities.push(Capability::Fader14Bit { max });
+            }
+            if input.feedback {
+                capabilities.push(Capability::FbFader14Bit { max });
+            }
+        }
+        SourceShape::Fixed(msg) => {
+            if input.control {
+                capabilities.push(Capability::Press {
+                    press: msg,
+                    release: None,
+                });
+            }
+            if input.feedback {
+                annotator.info(
+                    "Feedback of a fixed raw MIDI message can't be expressed in CSI and was ignored.",
+                );
+            }
+        }
+        SourceShape::Feedback(capability) => {
+            if input.feedback {
+                capabilities.push(capability);
+            }
+        }
+    }
+    Ok(capabilities)
+}
+
+/// Touch and push states are control-only in CSI.
+fn convert_source_shape_for_touch_or_push(
+    shape: SourceShape,
+    input: &SourceConvInput,
+    annotator: &mut Annotator,
+) -> CsiResult<Vec<Capability>> {
+    let (min, max) = match shape {
+        SourceShape::SevenBit { min, max, .. } | SourceShape::FourteenBit { min, max } => {
+            (min, max)
+        }
+        SourceShape::Fixed(msg) => (msg, msg),
+        SourceShape::Feedback(_) => {
+            return Err(
+                "Touch and push control elements need a source which can be used for control"
+                    .into(),
+            )
+        }
+    };
+    if input.feedback && input.control {
+        annotator.info(
+            "Feedback of touch and push control elements can't be expressed in CSI and was ignored.",
+        );
+    }
+    if !input.control {
+        return Ok(vec![]);
+    }
+    let (on, off) = on_and_off(min, max, &input.glue);
+    let capability = if input.role == WidgetRole::Touch {
+        Capability::Touch {
+            touch: on,
+            release: off,
+        }
+    } else {
+        Capability::Toggle { on }
+    };
+    Ok(vec![capability])
+}
+
+fn on_and_off(
+    min: RawShortMessage,
+    max: RawShortMessage,
+    glue: &Glue,
+) -> (RawShortMessage, RawShortMessage) {
+    if glue.reverse == Some(true) {
+        (min, max)
+    } else {
+        (max, min)
+    }
+}
+
+fn determine_source_shape(source: Source) -> CsiResult<SourceShape> {
+    let shape = match source {
+        Source::MidiNoteVelocity(MidiNoteVelocitySource {
+            channel,
+            key_number,
+            ..
+        }) => {
+            let channel = convert_channel(channel)?;
+            let key_number: KeyNumber = convert_7_bit(key_number, "key number")?;
+            SourceShape::SevenBit {
+                min: RawShortMessage::note_on(channel, key_number, U7::MIN),
+                max: RawShortMessage::note_on(channel, key_number, U7::MAX),
+                // Notes are usually sent by buttons or pads
+                character: SourceCharacter::Button,
+            }
+        }
+        Source::MidiPolyphonicKeyPressureAmount(MidiPolyphonicKeyPressureAmountSource {
+            channel,
+            key_number,
+            ..
+        }) => {
+            let channel = convert_channel(channel)?;
+            let key_number: KeyNumber = convert_7_bit(key_number, "key number")?;
+            SourceShape::SevenBit {
+                min: RawShortMessage::polyphonic_key_pressure(channel, key_number, U7::MIN),
+                max: RawShortMessage::polyphonic_key_pressure(channel, key_number, U7::MAX),
+                character: SourceCharacter::Range,
+            }
+        }
+        Source::MidiControlChangeValue(MidiControlChangeValueSource {
+            channel,
+            controller_number,
+            character,
+            fourteen_bit,
+            ..
+        }) => {
+            let channel = convert_channel(channel)?;
+            let controller_number: ControllerNumber =
+                convert_7_bit(controller_number, "controller number")?;
+            let max = RawShortMessage::control_change(channel, controller_number, U7::MAX);
+            let min = RawShortMessage::control_change(channel, controller_number, U7::MIN);
+            if fourteen_bit == Some(true) {
+                SourceShape::FourteenBit { min, max }
+            } else {
+                SourceShape::SevenBit {
+                    min,
+                    max,
+                    character: character.unwrap_or_default(),
+                }
+            }
+        }
+        Source::MidiPitchBendChangeValue(MidiPitchBendChangeValueSource { channel, .. }) => {
+            let channel = convert_channel(channel)?;
+            SourceShape::FourteenBit {
+                min: RawShortMessage::pitch_bend_change(channel, U14::MIN),
+                max: RawShortMessage::pitch_bend_change(channel, U14::MAX),
+            }
+        }
+        Source::MidiRaw(MidiRawSource { pattern, .. }) => {
+            let pattern = pattern.ok_or("Raw MIDI source without pattern")?;
+            if let Some(index) = parse_mackie_vu_meter_pattern(&pattern) {
+                SourceShape::Feedback(Capability::FbMcuVuMeter { index })
+            } else {
+                let msg = parse_fixed_short_msg_pattern(&pattern).ok_or_else(|| {
+                    format!(
+                        "Raw MIDI pattern \"{}\" can't be expressed in CSI because it's not a fixed 3-byte message",
+                        pattern
+                    )
+                })?;
+                SourceShape::Fixed(msg)
+            }
+        }
+        Source::MackieLcd(MackieLcdSource {
+            extender_index,
+            channel,
+            line,
+        }) => {
+            if extender_index.unwrap_or(0) > 0 {
+                return Err("Mackie LCD sources for extenders can't be expressed in CSI".into());
+            }
+            let index = channel
+                .ok_or("Mackie LCD sources spanning all channels can't be expressed in CSI")?;
+            let capability = match line {
+                Some(0) => Capability::FbMcuDisplayUpper { index },
+                Some(1) => Capability::FbMcuDisplayLower { index },
+                _ => {
+                    return Err(
+                        "Mackie LCD sources spanning both lines can't be expressed in CSI".into(),
+                    )
+                }
+            };
+            SourceShape::Feedback(capability)
+        }
+        Source::MackieSevenSegmentDisplay(MackieSevenSegmentDisplaySource { scope }) => {
+            if scope != Some(MackieSevenSegmentDisplayScope::Tc) {
+                return Err(
+                    "Only the time code scope of Mackie 7-segment displays can be expressed in CSI"
+                        .into(),
+                );
+            }
+            SourceShape::Feedback(Capability::FbMcuTimeDisplay)
+        }
+        _ => return Err("This kind of source can't be expressed in CSI".into()),
+    };
+    Ok(shape)
+}
+
+fn create_accelerations(
+    character: SourceCharacter,
+    glue: &Glue,
+    annotator: &mut Annotator,
+) -> Option<Accelerations> {
+    let max_factor = glue.step_factor_interval.map(|i| i.1).unwrap_or(1);
+    let max = if max_factor > MAX_ACCELERATION as i32 {
+        annotator.warn(format!(
+            "Acceleration was limited to {} because CSI can't express more",
+            MAX_ACCELERATION
+        ));
+        MAX_ACCELERATION
+    } else {
+        max_factor.max(1) as u8
+    };
+    let accelerations = match character {
+        SourceCharacter::Relative1 => Accelerations {
+            decrements: Acceleration::Sequence((1..=max).map(|i| 128 - i).collect()),
+            increments: Acceleration::Range(1..=max),
+        },
+        SourceCharacter::Relative2 => Accelerations {
+            decrements: Acceleration::Sequence((1..=max).map(|i| 64 - i).collect()),
+            increments: Acceleration::Range(65..=64 + max),
+        },
+        // Encoders without acceleration profile are interpreted as Relative3 by the
+        // CSI-to-ReaLearn conversion.
+        SourceCharacter::Relative3 if max == 1 => return None,
+        SourceCharacter::Relative3 => Accelerations {
+            decrements: Acceleration::Range(65..=64 + max),
+            increments: Acceleration::Range(1..=max),
+        },
+        _ => return None,
+    };
+    Some(accelerations)
+}
+
+fn convert_channel(channel: Option<u8>) -> CsiResult<Channel> {
+    let channel = channel.ok_or("Sources listening to any channel can't be expressed in CSI")?;
+    Channel::try_from(channel).map_err(|_| "Invalid MIDI channel".into())
+}
+
+fn convert_7_bit<T: TryFrom<u8>>(value: Option<u8>, label: &str) -> CsiResult<T> {
+    let value =
+        value.ok_or_else(|| format!("Sources with any {} can't be expressed in CSI", label))?;
+    T::try_from(value).map_err(|_| format!("Invalid {}", label).into())
+}
+
+/// Recognizes the pattern produced for `FB_MCUVUMeter`, e.g. `D0 [0011 dcba]`.
+fn parse_mackie_vu_meter_pattern(pattern: &str) -> Option<u8> {
+    let bits = pattern
+        .trim()
+        .strip_prefix("D0 [")?
+        .strip_suffix(" dcba]")?;
+    if bits.len() != 4 {
+        return None;
+    }
+    u8::from_str_radix(bits, 2).ok()
+}
+
+fn parse_fixed_short_msg_pattern(pattern: &str) -> Option<RawShortMessage> {
+    let bytes: Vec<u8> = pattern
+        .split_whitespace()
+        .map(|b| u8::from_str_radix(b, 16).ok())
+        .collect::<Option<_>>()?;
+    if let [status_byte, data_byte_1, data_byte_2] = bytes[..] {
+        RawShortMessage::from_bytes((
+            status_byte,
+            U7::try_from(data_byte_1).ok()?,
+            U7::try_from(data_byte_2).ok()?,
+        ))
+        .ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{deserialize_csi_object_from_csi, CsiObject};
+    use realearn_api::persistence::{ApiObject, Interval};
+
+    #[test]
+    fn round_trip_test_widgets() {
+        // Given
+        let mst_content = include_str!("test_data/test.mst");
+        let original_widgets = match deserialize_csi_object_from_csi(mst_content).unwrap() {
+            CsiObject::Widgets(w) => w,
+            CsiObject::Zones(_) => panic!("expected widgets"),
+        };
+        let api_object = CsiObject::Widgets(parser_widgets(mst_content))
+            .try_into_api_object()
+            .unwrap()
+            .value;
+        let compartment = match api_object {
+            ApiObject::ControllerCompartment(envelope) => *envelope.value,
+            _ => panic!("expected controller compartment"),
+        };
+        // When
+        let mut annotator = Annotator::new();
+        let widgets = convert_compartment_to_widgets(compartment, &mut annotator);
+        // Then
+        assert_eq!(widgets.len(), original_widgets.len());
+        for (original, exported) in original_widgets.iter().zip(widgets.iter()) {
+            // The master fader touch release message of this file can't be reconstructed
+            if original.name.len() <= 16 && original.name != "MasterFader" {
+                // Asymmetric acceleration profiles get lost in the conversion to ReaLearn
+                assert_eq!(
+                    without_accelerations(exported),
+                    without_accelerations(original)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_7_bit_fader() {
+        // Given
+        let mst_content =
+            "Widget Knob1\n\tFader7Bit b0 10 7f\n\tFB_Fader7Bit b0 10 7f\nWidgetEnd\n";
+        let original_widgets = parser_widgets(mst_content);
+        let api_object = CsiObject::Widgets(parser_widgets(mst_content))
+            .try_into_api_object()
+            .unwrap()
+            .value;
+        let compartment = match api_object {
+            ApiObject::ControllerCompartment(envelope) => *envelope.value,
+            _ => panic!("expected controller compartment"),
+        };
+        // When
+        let mut annotator = Annotator::new();
+        let widgets = convert_compartment_to_widgets(compartment, &mut annotator);
+        // Then
+        assert_eq!(widgets, original_widgets);
+    }
+
+    #[test]
+    fn relative_1_accelerations() {
+        // Given
+        let glue = Glue {
+            step_factor_interval: Some(Interval(1, 3)),
+            ..Default::default()
+        };
+        // When
+        let accelerations =
+            create_accelerations(SourceCharacter::Relative1, &glue, &mut Annotator::new()).unwrap();
+        // Then
+        assert_eq!(
+            accelerations,
+            Accelerations {
+                decrements: Acceleration::Sequence(vec![0x7f, 0x7e, 0x7d]),
+                increments: Acceleration::Range(1..=3),
+            }
+        );
+    }
+
+    #[test]
+    fn relative_3_without_accelerations() {
+        let accelerations = create_accelerations(
+            SourceCharacter::Relative3,
+            &Glue::default(),
+            &mut Annotator::new(),
+        );
+        assert_eq!(accelerations, None);
+    }
+
+    fn without_accelerations(widget: &Widget) -> Vec<Capability> {
+        widget
+            .capabilities
+            .iter()
+            .map(|c| match c {
+                Capability::Encoder { main, .. } => Capability::Encoder {
+                    main: *main,
+                    accelerations: None,
+                },
+                c => c.clone(),
+            })
+            .collect()
+    }
+
+    fn parser_widgets(mst_content: &str) -> Vec<Widget> {
+        crate::parser::mst_file_content(mst_content).unwrap()
+    }
+}
@@ -12,20 +12,58 @@ use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
+mod export;
 mod parser;
 mod schema;
+mod serializer;
+mod zone;
 
 pub use schema::*;
 
 pub enum CsiObject {
     Widgets(Vec<Widget>),
+    Zones(Vec<Zone>),
 }
 
 type CsiResult<T> = Result<T, Box<dyn Error>>;
 
+/// Deserializes either a surface file (`.mst`) or a zone file (`.zon`).
 pub fn deserialize_csi_object_from_csi(text: &str) -> Result<CsiObject, Box<dyn Error>> {
-    let widgets = parser::mst_file_content(text)?;
-    Ok(CsiObject::Widgets(widgets))
+    let object = if looks_like_zone_file(text) {
+        CsiObject::Zones(parser::zon_file_content(text)?)
+    } else {
+        CsiObject::Widgets(parser::mst_file_content(text)?)
+    };
+    Ok(object)
+}
+
+/// Serializes a surface file (`.mst`).
+pub fn serialize_csi_object_to_csi(object: &CsiObject) -> Result<String, Box<dyn Error>> {
+    match object {
+        CsiObject::Widgets(widgets) => Ok(serializer::mst_file_content(widgets)),
+        CsiObject::Zones(_) => Err("Serializing CSI zone files is not supported".into()),
+    }
+}
+
+/// Checks whether the given text looks like a surface file (`.mst`) or a zone file (`.zon`).
+///
+/// This doesn't validate the content, it just looks at the first relevant line.
+pub fn looks_like_csi_file(text: &str) -> bool {
+    first_relevant_line(text)
+        .map(|l| l.starts_with("Zone") || l.starts_with("Widget"))
+        .unwrap_or(false)
+}
+
+fn looks_like_zone_file(text: &str) -> bool {
+    first_relevant_line(text)
+        .map(|l| l.starts_with("Zone"))
+        .unwrap_or(false)
+}
+
+fn first_relevant_line(text: &str) -> Option<&str> {
+    text.lines()
+        .map(|l| l.trim_start())
+        .find(|l| !l.is_empty() && !l.starts_with('/'))
 }
 
 #[derive(Default)]
@@ -104,6 +142,13 @@ impl<T> AnnotatedResult<T> {
             annotations: vec![],
         }
     }
+
+    pub fn map<R>(self, f: impl FnOnce(T) -> R) -> AnnotatedResult<R> {
+        AnnotatedResult {
+            value: f(self.value),
+            annotations: self.annotations,
+        }
+    }
 }
 
 impl CsiObject {
@@ -143,9 +188,34 @@ impl CsiObject {
                     value: Box::new(compartment),
                 })
             }
+            Zones(zones) => {
+                let compartment = zone::convert_zones(zones, &mut annotator);
+                ApiObject::MainCompartment(Envelope {
+                    version: None,
+                    value: Box::new(compartment),
+                })
+            }
         };
         Ok(annotator.build_result(api_object))
     }
+
+    pub fn try_from_api_object(
+        api_object: ApiObject,
+    ) -> Result<AnnotatedResult<CsiObject>, Box<dyn Error>> {
+        let mut annotator = Annotator::new();
+        let csi_object = match api_object {
+            ApiObject::ControllerCompartment(Envelope { value, .. }) => {
+                let widgets = export::convert_compartment_to_widgets(*value, &mut annotator);
+                CsiObject::Widgets(widgets)
+            }
+            _ => {
+                return Err(
+                    "Only controller compartments can be exported as CSI surface file".into(),
+                )
+            }
+        };
+        Ok(annotator.build_result(csi_object))
+    }
 }
 
 struct WidgetConvResult {
@@ -368,6 +438,36 @@ fn convert_capability_to_mappings(
             };
             vec![mapping]
         }
+        Capability::Fader7Bit { max } => {
+            let max_res = convert_max_short_msg_to_source(MsgConvInput {
+                msg: max,
+                character: SourceCharacter::Range,
+                press_only: false,
+                fourteen_bit: false,
+            })?;
+            let mapping = Mapping {
+                feedback_enabled: Some(false),
+                source: Some(max_res.source),
+                target: virtual_target(widget_id.to_owned(), target_character),
+                ..base_mapping
+            };
+            vec![mapping]
+        }
+        Capability::FbFader7Bit { max } => {
+            let max_res = convert_max_short_msg_to_source(MsgConvInput {
+                msg: max,
+                character: SourceCharacter::Range,
+                press_only: false,
+                fourteen_bit: false,
+            })?;
+            let mapping = Mapping {
+                control_enabled: Some(false),
+                source: Some(max_res.source),
+                target: virtual_target(widget_id.to_owned(), target_character),
+                ..base_mapping
+            };
+            vec![mapping]
+        }
         Capability::Fader14Bit { max } => {
             let max_res = convert_max_short_msg_to_source(MsgConvInput {
                 msg: max,
@@ -452,6 +552,7 @@ fn virtual_target(id: String, character: VirtualControlElementCharacter) -> Opti
     let t = VirtualTarget {
         id: VirtualControlElementId::Named(id),
         character: Some(character),
+        axis: None,
     };
     Some(Target::Virtual(t))
 }
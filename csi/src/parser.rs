@@ -1,11 +1,13 @@
-use crate::schema::{Acceleration, Accelerations, Capability, Widget};
+use crate::schema::{
+    Acceleration, Accelerations, Action, ActionLine, Capability, Modifier, Navigator, Widget, Zone,
+};
 use helgoboss_midi::{RawShortMessage, ShortMessageFactory};
 use nom::branch::alt;
-use nom::bytes::complete::{tag, take_while1, take_while_m_n};
+use nom::bytes::complete::{tag, take_while, take_while1, take_while_m_n};
 use nom::character::complete::{multispace0, not_line_ending, space0, space1};
 use nom::combinator::{all_consuming, map, map_res, opt, verify};
 use nom::error::ParseError;
-use nom::multi::{separated_list0, separated_list1};
+use nom::multi::{many0, separated_list0, separated_list1};
 use nom::sequence::{preceded, separated_pair};
 use nom::{character::complete::char, sequence::delimited, sequence::tuple, Err, IResult, Parser};
 use std::convert::TryInto;
@@ -13,19 +15,39 @@ use std::convert::TryInto;
 type Res<'a, T> = IResult<&'a str, T>;
 
 pub fn mst_file_content(input: &str) -> Result<Vec<Widget>, String> {
+    let input_without_comments = remove_comment_lines(input);
+    let (_, widgets) =
+        all_consuming(widgets)(&input_without_comments).map_err(shorten_error_message)?;
+    Ok(widgets)
+}
+
+pub fn zon_file_content(input: &str) -> Result<Vec<Zone>, String> {
+    let input_without_comments = remove_comment_lines(input);
+    let (_, zones) =
+        all_consuming(zones)(&input_without_comments).map_err(shorten_error_message)?;
+    Ok(zones)
+}
+
+fn remove_comment_lines(input: &str) -> String {
     let non_comment_lines: Vec<_> = input
         .lines()
         .filter(|l| !l.trim_start().starts_with('/'))
         .collect();
-    let input_without_comments = non_comment_lines.join("\n");
-    let (_, widgets) = all_consuming(widgets)(&input_without_comments).map_err(|e| {
-        let short_err = match e {
-            Err::Error(e) => Err::Error(nom::error::Error::new(&e.input[0..30], e.code)),
-            e => e,
-        };
-        short_err.to_string()
-    })?;
-    Ok(widgets)
+    non_comment_lines.join("\n")
+}
+
+fn shorten_error_message(e: Err<nom::error::Error<&str>>) -> String {
+    let short_err = match e {
+        Err::Error(e) => {
+            let short_input = match e.input.char_indices().nth(30) {
+                None => e.input,
+                Some((i, _)) => &e.input[0..i],
+            };
+            Err::Error(nom::error::Error::new(short_input, e.code))
+        }
+        e => e,
+    };
+    short_err.to_string()
 }
 
 fn widgets(input: &str) -> Res<Vec<Widget>> {
@@ -70,6 +92,8 @@ fn capability(input: &str) -> Res<Capability> {
         capability_encoder,
         capability_fb_encoder,
         capability_toggle,
+        capability_fader_7_bit,
+        capability_fb_fader_7_bit,
         capability_fader_14_bit,
         capability_fb_fader_14_bit,
         capability_touch,
@@ -105,6 +129,18 @@ fn capability_toggle(input: &str) -> Res<Capability> {
     })(input)
 }
 
+fn capability_fader_7_bit(input: &str) -> Res<Capability> {
+    map(util::capability_msg("Fader7Bit"), |max| {
+        Capability::Fader7Bit { max }
+    })(input)
+}
+
+fn capability_fb_fader_7_bit(input: &str) -> Res<Capability> {
+    map(util::capability_msg("FB_Fader7Bit"), |max| {
+        Capability::FbFader7Bit { max }
+    })(input)
+}
+
 fn capability_fader_14_bit(input: &str) -> Res<Capability> {
     map(util::capability_msg("Fader14Bit"), |max| {
         Capability::Fader14Bit { max }
@@ -170,6 +206,137 @@ fn capability_unknown(input: &str) -> Res<Capability> {
     )(input)
 }
 
+fn zones(input: &str) -> Res<Vec<Zone>> {
+    delimited(
+        multispace0,
+        separated_list0(space_with_at_least_one_line_ending, zone),
+        multispace0,
+    )(input)
+}
+
+fn zone(input: &str) -> Res<Zone> {
+    map(
+        tuple((
+            zone_begin,
+            many0(preceded(space_with_at_least_one_line_ending, zone_line)),
+            space_with_at_least_one_line_ending,
+            tag("ZoneEnd"),
+        )),
+        |((name, alias), lines, _, _)| {
+            let mut zone = Zone {
+                name: name.to_owned(),
+                alias: alias.map(|a| a.to_owned()),
+                navigator: None,
+                included_zones: vec![],
+                action_lines: vec![],
+            };
+            for line in lines {
+                match line {
+                    ZoneLine::Navigator(n) => zone.navigator = Some(n),
+                    ZoneLine::IncludedZones(names) => zone.included_zones.extend(names),
+                    ZoneLine::Action(a) => zone.action_lines.push(a),
+                }
+            }
+            zone
+        },
+    )(input)
+}
+
+enum ZoneLine {
+    Navigator(Navigator),
+    IncludedZones(Vec<String>),
+    Action(ActionLine),
+}
+
+fn zone_begin(input: &str) -> Res<(&str, Option<&str>)> {
+    preceded(
+        tuple((tag("Zone"), space1)),
+        tuple((quoted_string, opt(preceded(space1, quoted_string)))),
+    )(input)
+}
+
+fn zone_line(input: &str) -> Res<ZoneLine> {
+    alt((
+        map(included_zones, ZoneLine::IncludedZones),
+        map(action_line, ZoneLine::Action),
+        map(navigator, ZoneLine::Navigator),
+    ))(input)
+}
+
+fn included_zones(input: &str) -> Res<Vec<String>> {
+    map(
+        tuple((
+            tag("IncludedZones"),
+            many0(preceded(space_with_at_least_one_line_ending, quoted_string)),
+            space_with_at_least_one_line_ending,
+            tag("IncludedZonesEnd"),
+        )),
+        |(_, names, _, _)| names.into_iter().map(|n| n.to_owned()).collect(),
+    )(input)
+}
+
+fn navigator(input: &str) -> Res<Navigator> {
+    map(
+        verify(token, |t: &str| t.ends_with("Navigator")),
+        |name: &str| match name {
+            "TrackNavigator" => Navigator::Track,
+            "SelectedTrackNavigator" => Navigator::SelectedTrack,
+            "MasterTrackNavigator" => Navigator::MasterTrack,
+            "FocusedFXNavigator" => Navigator::FocusedFx,
+            _ => Navigator::Unknown(name.to_owned()),
+        },
+    )(input)
+}
+
+fn action_line(input: &str) -> Res<ActionLine> {
+    map(
+        tuple((
+            widget_with_modifiers,
+            space1,
+            token,
+            many0(preceded(space1, action_param)),
+            space0,
+        )),
+        |((modifiers, widget_name), _, name, params, _)| ActionLine {
+            modifiers,
+            widget_name: widget_name.to_owned(),
+            action: Action {
+                name: name.to_owned(),
+                params: params.into_iter().map(|p| p.to_owned()).collect(),
+            },
+        },
+    )(input)
+}
+
+fn widget_with_modifiers(input: &str) -> Res<(Vec<Modifier>, &str)> {
+    map(
+        verify(token, |t: &str| t != "ZoneEnd" && !t.ends_with('+')),
+        |t: &str| {
+            let mut parts: Vec<_> = t.split('+').collect();
+            let widget_name = parts.pop().expect("split always returns at least one item");
+            let modifiers = parts.into_iter().map(Modifier::from_name).collect();
+            (modifiers, widget_name)
+        },
+    )(input)
+}
+
+fn action_param(input: &str) -> Res<&str> {
+    alt((quoted_string, token))(input)
+}
+
+fn quoted_string(input: &str) -> Res<&str> {
+    delimited(
+        char('"'),
+        take_while(|ch: char| !matches!(ch, '"' | '\r' | '\n')),
+        char('"'),
+    )(input)
+}
+
+/// A sequence of non-whitespace characters.
+fn token(input: &str) -> Res<&str> {
+    take_while1(|ch: char| !ch.is_whitespace())(input)
+}
+
 fn short_midi_msg(input: &str) -> Res<RawShortMessage> {
     map_res(
         tuple((hex_byte, space1, hex_byte, space1, hex_byte)),
@@ -449,6 +616,94 @@ WidgetEnd"
         assert_eq!(hex_byte("90"), Ok(("", 0x90)));
     }
 
+    #[test]
+    fn parse_zones() {
+        let zon_content = include_str!("test_data/test.zon");
+        let zones = zon_file_content(zon_content).unwrap();
+        assert_eq!(zones.len(), 5);
+        let home = &zones[0];
+        assert_eq!(home.name, "Home");
+        assert_eq!(home.included_zones, vec!["Buttons", "Channel"]);
+        assert_eq!(home.action_lines.len(), 9);
+        let channel = &zones[2];
+        assert_eq!(channel.navigator, Some(Navigator::Track));
+        assert_eq!(channel.action_lines.len(), 11);
+        let send = &zones[3];
+        assert_eq!(send.alias.as_deref(), Some("Sends"));
+        assert_eq!(send.navigator, Some(Navigator::SelectedTrack));
+    }
+
+    #[test]
+    fn parse_zone() {
+        assert_eq!(
+            zone(
+                "\
+Zone \"Channel\"
+    TrackNavigator
+    Fader| TrackVolume
+ZoneEnd"
+            ),
+            Ok((
+                "",
+                Zone {
+                    name: "Channel".to_owned(),
+                    alias: None,
+                    navigator: Some(Navigator::Track),
+                    included_zones: vec![],
+                    action_lines: vec![ActionLine {
+                        modifiers: vec![],
+                        widget_name: "Fader|".to_owned(),
+                        action: Action {
+                            name: "TrackVolume".to_owned(),
+                            params: vec![]
+                        }
+                    }]
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_empty_zone() {
+        assert_eq!(
+            zone("Zone \"Empty\"\nZoneEnd"),
+            Ok((
+                "",
+                Zone {
+                    name: "Empty".to_owned(),
+                    alias: None,
+                    navigator: None,
+                    included_zones: vec![],
+                    action_lines: vec![]
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_action_line_with_modifiers_and_params() {
+        assert_eq!(
+            action_line("Shift+Touch+Rotary1 FXParam 3 \"Cut off\""),
+            Ok((
+                "",
+                ActionLine {
+                    modifiers: vec![Modifier::Shift, Modifier::Touch],
+                    widget_name: "Rotary1".to_owned(),
+                    action: Action {
+                        name: "FXParam".to_owned(),
+                        params: vec!["3".to_owned(), "Cut off".to_owned()]
+                    }
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_action_line_with_unknown_modifier() {
+        let (_, line) = action_line("Weird+Fader1 TrackVolume").unwrap();
+        assert_eq!(line.modifiers, vec![Modifier::Unknown("Weird".to_owned())]);
+    }
+
     fn short(status_byte: u8, data_byte_1: u8, data_byte_2: u8) -> RawShortMessage {
         RawShortMessage::from_bytes((status_byte, u7(data_byte_1), u7(data_byte_2))).unwrap()
     }
@@ -1,5 +1,6 @@
 use derive_more::Display;
 use helgoboss_midi::RawShortMessage;
+use std::fmt::Formatter;
 use std::ops::RangeInclusive;
 
 #[derive(Eq, PartialEq, Debug)]
@@ -8,7 +9,7 @@ pub struct Widget {
     pub capabilities: Vec<Capability>,
 }
 
-#[derive(Eq, PartialEq, Debug, Display)]
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
 pub enum Capability {
     #[display(fmt = "Press")]
     Press {
@@ -29,6 +30,12 @@ pub enum Capability {
     FbEncoder { max: RawShortMessage },
     #[display(fmt = "Toggle")]
     Toggle { on: RawShortMessage },
+    /// Fader or knob sending plain 7-bit values.
+    #[display(fmt = "Fader7Bit")]
+    Fader7Bit { max: RawShortMessage },
+    /// Feedback counterpart of [`Capability::Fader7Bit`].
+    #[display(fmt = "FB_Fader7Bit")]
+    FbFader7Bit { max: RawShortMessage },
     #[display(fmt = "Fader14Bit")]
     Fader14Bit { max: RawShortMessage },
     #[display(fmt = "FB_Fader14Bit")]
@@ -61,14 +68,119 @@ impl Capability {
     }
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Accelerations {
     pub decrements: Acceleration,
     pub increments: Acceleration,
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub enum Acceleration {
     Sequence(Vec<u8>),
     Range(RangeInclusive<u8>),
 }
+
+#[derive(Eq, PartialEq, Debug)]
+pub struct Zone {
+    pub name: String,
+    pub alias: Option<String>,
+    pub navigator: Option<Navigator>,
+    pub included_zones: Vec<String>,
+    pub action_lines: Vec<ActionLine>,
+}
+
+#[derive(Eq, PartialEq, Debug, Display)]
+pub enum Navigator {
+    #[display(fmt = "TrackNavigator")]
+    Track,
+    #[display(fmt = "SelectedTrackNavigator")]
+    SelectedTrack,
+    #[display(fmt = "MasterTrackNavigator")]
+    MasterTrack,
+    #[display(fmt = "FocusedFXNavigator")]
+    FocusedFx,
+    #[display(fmt = "{}", "_0")]
+    Unknown(String),
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub struct ActionLine {
+    pub modifiers: Vec<Modifier>,
+    pub widget_name: String,
+    pub action: Action,
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub struct Action {
+    pub name: String,
+    pub params: Vec<String>,
+}
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Display)]
+pub enum Modifier {
+    Shift,
+    Option,
+    Control,
+    Alt,
+    Flip,
+    Global,
+    Marker,
+    Nudge,
+    Zoom,
+    Scrub,
+    Touch,
+    Press,
+    Release,
+    Hold,
+    #[display(fmt = "InvertFB")]
+    InvertFb,
+    #[display(fmt = "{}", "_0")]
+    Unknown(String),
+}
+
+impl Modifier {
+    /// Returns `true` if this modifier represents the state of a modifier button (as opposed to
+    /// a modifier that just changes the way the widget itself is interpreted).
+    pub fn is_global(&self) -> bool {
+        use Modifier::*;
+        matches!(
+            self,
+            Shift | Option | Control | Alt | Flip | Global | Marker | Nudge | Zoom | Scrub
+        )
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        use Modifier::*;
+        match name {
+            "Shift" => Shift,
+            "Option" => Option,
+            "Control" => Control,
+            "Alt" => Alt,
+            "Flip" => Flip,
+            "Global" => Global,
+            "Marker" => Marker,
+            "Nudge" => Nudge,
+            "Zoom" => Zoom,
+            "Scrub" => Scrub,
+            "Touch" => Touch,
+            "Press" => Press,
+            "Release" => Release,
+            "Hold" => Hold,
+            "InvertFB" => InvertFb,
+            _ => Unknown(name.to_owned()),
+        }
+    }
+}
+
+impl std::fmt::Display for ActionLine {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for m in &self.modifiers {
+            write!(f, "{}+", m)?;
+        }
+        write!(f, "{} {}", self.widget_name, self.action.name)?;
+        for p in &self.action.params {
+            write!(f, " {}", p)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use crate::schema::{Acceleration, Accelerations, Capability, Widget};
+use helgoboss_midi::{RawShortMessage, ShortMessage};
+use std::fmt::Write;
+
+/// Produces the content of a CSI surface file (`.mst`) that can be read again by
+/// [`crate::parser::mst_file_content`].
+pub fn mst_file_content(widgets: &[Widget]) -> String {
+    let mut out = String::new();
+    for w in widgets {
+        write_widget(&mut out, w);
+        out.push('\n');
+    }
+    out
+}
+
+fn write_widget(out: &mut String, widget: &Widget) {
+    writeln!(out, "Widget {}", widget.name).unwrap();
+    for c in &widget.capabilities {
+        writeln!(out, "\t{}", capability(c)).unwrap();
+    }
+    writeln!(out, "WidgetEnd").unwrap();
+}
+
+fn capability(capability: &Capability) -> String {
+    use Capability::*;
+    match capability {
+        Press { press, release } => match release {
+            None => format!("{} {}", capability, short_midi_msg(*press)),
+            Some(release) => format!(
+                "{} {} {}",
+                capability,
+                short_midi_msg(*press),
+                short_midi_msg(*release)
+            ),
+        },
+        FbTwoState { on, off } => format!(
+            "{} {} {}",
+            capability,
+            short_midi_msg(*on),
+            short_midi_msg(*off)
+        ),
+        Encoder {
+            main,
+            accelerations,
+        } => match accelerations {
+            None => format!("{} {}", capability, short_midi_msg(*main)),
+            Some(acc) => format!(
+                "{} {} {}",
+                capability,
+                short_midi_msg(*main),
+                self::accelerations(acc)
+            ),
+        },
+        FbEncoder { max: msg }
+        | Toggle { on: msg }
+        | Fader7Bit { max: msg }
+        | FbFader7Bit { max: msg }
+        | Fader14Bit { max: msg }
+        | FbFader14Bit { max: msg } => format!("{} {}", capability, short_midi_msg(*msg)),
+        Touch { touch, release } => format!(
+            "{} {} {}",
+            capability,
+            short_midi_msg(*touch),
+            short_midi_msg(*release)
+        ),
+        FbMcuDisplayLower { index } | FbMcuDisplayUpper { index } | FbMcuVuMeter { index } => {
+            format!("{} {}", capability, index)
+        }
+        FbMcuTimeDisplay | Unknown(_) => capability.to_string(),
+    }
+}
+
+fn short_midi_msg(msg: RawShortMessage) -> String {
+    let (status_byte, data_byte_1, data_byte_2) = msg.to_bytes();
+    format!(
+        "{:02x} {:02x} {:02x}",
+        status_byte,
+        data_byte_1.get(),
+        data_byte_2.get()
+    )
+}
+
+fn accelerations(accelerations: &Accelerations) -> String {
+    format!(
+        "[ < {} > {} ]",
+        acceleration(&accelerations.decrements),
+        acceleration(&accelerations.increments)
+    )
+}
+
+fn acceleration(acceleration: &Acceleration) -> String {
+    match acceleration {
+        Acceleration::Sequence(values) => {
+            let values: Vec<_> = values.iter().map(|v| format!("{:02x}", v)).collect();
+            values.join(" ")
+        }
+        Acceleration::Range(range) => format!("{:02x}-{:02x}", range.start(), range.end()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn round_trip_test_widgets() {
+        // Given
+        let widgets = parser::mst_file_content(include_str!("test_data/test.mst")).unwrap();
+        // When
+        let mst_content = mst_file_content(&widgets);
+        // Then
+        let parsed_widgets = parser::mst_file_content(&mst_content).unwrap();
+        assert_eq!(parsed_widgets, widgets);
+    }
+}
@@ -0,0 +1,838 @@
+use crate::schema::{Action, ActionLine, Modifier, Navigator, Zone};
+use crate::{convert_widget_name_to_id, extended_control_element_id, Annotator, CsiResult};
+use realearn_api::persistence::{
+    AbsoluteMode, ActionInvocationKind, ActivationCondition, AfterTimeoutFireMode,
+    BankActivationCondition, ButtonFilter, Compartment, ExpressionActivationCondition, Feedback,
+    FireMode, FxDescriptor, FxParameterDescriptor, FxParameterValueTarget, Glue, Group, Interval,
+    Mapping, ModifierActivationCondition, ModifierState, ParamRef, Parameter,
+    RealearnInstanceStartSource, ReaperActionTarget, ReaperCommand, Source, Target, TextFeedback,
+    TouchedTrackParameter, TrackArmStateTarget, TrackAutomationTouchStateTarget, TrackDescriptor,
+    TrackExclusivity, TrackMuteStateTarget, TrackPanTarget, TrackPeakTarget,
+    TrackSelectionStateTarget, TrackSoloStateTarget, TrackToolTarget, TrackVolumeTarget,
+    TrackWidthTarget, TransportAction, TransportActionTarget, VirtualControlElementCharacter,
+    VirtualControlElementId, VirtualSource,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::num::NonZeroU32;
+
+/// CSI derives the number of channels from the surface definition in `CSI.ini`. We don't have
+/// this information when looking at the zone file only, so we assume a typical 8-channel surface.
+const CHANNEL_COUNT: u32 = 8;
+
+const ROOT_ZONE_NAME: &str = "Home";
+
+/// Number of values of the parameter that represents the track bank offset.
+const TRACK_BANK_VALUE_COUNT: u32 = 100;
+
+/// CSI's default hold duration in milliseconds.
+const HOLD_TIMEOUT: u32 = 1000;
+
+pub(crate) fn convert_zones(zones: Vec<Zone>, annotator: &mut Annotator) -> Compartment {
+    let layout = CompartmentLayout::new(&zones);
+    if !layout.unreachable_zones.is_empty() {
+        annotator.warn(format!(
+            "The following zones are included by zones which are included themselves. ReaLearn supports just one level of inclusion, so they will be activated together with their top-level zone: {}",
+            layout.unreachable_zones.join(", ")
+        ));
+    }
+    let has_channel_widgets = zones
+        .iter()
+        .flat_map(|z| &z.action_lines)
+        .any(|l| l.widget_name.contains('|'));
+    if has_channel_widgets {
+        annotator.info(format!(
+            "Expanded channel widgets (containing \"|\") for {} channels because CSI zone files don't contain the channel count of the surface.",
+            CHANNEL_COUNT
+        ));
+    }
+    let mut groups = vec![];
+    let mut mappings = vec![];
+    for zone in &zones {
+        annotator.with_context(format!("Zone \"{}\"", zone.name), |annotator| {
+            groups.push(layout.create_group(zone));
+            let zone_mappings = convert_zone(zone, &layout, annotator);
+            mappings.extend(zone_mappings);
+        });
+    }
+    Compartment {
+        parameters: Some(layout.create_parameters()),
+        groups: Some(groups),
+        mappings: Some(mappings),
+        ..Default::default()
+    }
+}
+
+/// Describes how CSI concepts are laid out onto compartment parameters and groups.
+struct CompartmentLayout {
+    /// All global modifiers used in the zone file, in combination with the index of the
+    /// compartment parameter which represents them.
+    modifier_params: Vec<(Modifier, u32)>,
+    /// Index of the compartment parameter used for switching between zones (only set if there's
+    /// more than one top-level zone).
+    zone_param: Option<u32>,
+    /// Index of the compartment parameter used for track banking.
+    track_bank_param: Option<u32>,
+    /// Names of the top-level zones. Their position corresponds to the bank index.
+    top_level_zones: Vec<String>,
+    /// Maps each zone name to the bank index of the top-level zone that activates it.
+    bank_index_by_zone: HashMap<String, u32>,
+    unreachable_zones: Vec<String>,
+}
+
+impl CompartmentLayout {
+    fn new(zones: &[Zone]) -> Self {
+        let all_lines = || zones.iter().flat_map(|z| &z.action_lines);
+        // Modifiers
+        let mut used_modifiers: BTreeSet<Modifier> = all_lines()
+            .flat_map(|l| &l.modifiers)
+            .filter(|m| m.is_global())
+            .cloned()
+            .collect();
+        used_modifiers.extend(
+            all_lines()
+                .map(|l| Modifier::from_name(&l.action.name))
+                .filter(|m| m.is_global()),
+        );
+        let modifier_params: Vec<_> = used_modifiers.into_iter().zip(0u32..).collect();
+        let mut next_param_index = modifier_params.len() as u32;
+        // Zones
+        let included_zone_names: BTreeSet<&str> = zones
+            .iter()
+            .flat_map(|z| &z.included_zones)
+            .map(|n| n.as_str())
+            .collect();
+        let mut top_level_zones: Vec<String> = zones
+            .iter()
+            .filter(|z| !included_zone_names.contains(z.name.as_str()))
+            .map(|z| z.name.clone())
+            .collect();
+        // The root zone should always be the first one because it's active initially.
+        top_level_zones.sort_by_key(|name| name != ROOT_ZONE_NAME);
+        let mut bank_index_by_zone: HashMap<String, u32> = HashMap::new();
+        for (name, bank_index) in top_level_zones.iter().zip(0u32..) {
+            bank_index_by_zone.insert(name.clone(), bank_index);
+            let top_level_zone = zones.iter().find(|z| &z.name == name);
+            if let Some(z) = top_level_zone {
+                for included_zone in &z.included_zones {
+                    bank_index_by_zone
+                        .entry(included_zone.clone())
+                        .or_insert(bank_index);
+                }
+            }
+        }
+        let mut unreachable_zones = vec![];
+        for z in zones {
+            if bank_index_by_zone.contains_key(&z.name) {
+                continue;
+            }
+            // Included by a zone which is included itself. Activate it together with the
+            // top-level zone of its includer (if there is one).
+            let bank_index = zones
+                .iter()
+                .filter(|includer| includer.included_zones.contains(&z.name))
+                .find_map(|includer| bank_index_by_zone.get(&includer.name).copied())
+                .unwrap_or(0);
+            bank_index_by_zone.insert(z.name.clone(), bank_index);
+            unreachable_zones.push(z.name.clone());
+        }
+        let zone_param = if top_level_zones.len() > 1 {
+            let i = next_param_index;
+            next_param_index += 1;
+            Some(i)
+        } else {
+            None
+        };
+        let track_bank_param = if all_lines().any(|l| l.action.name == "TrackBank") {
+            Some(next_param_index)
+        } else {
+            None
+        };
+        Self {
+            modifier_params,
+            zone_param,
+            track_bank_param,
+            top_level_zones,
+            bank_index_by_zone,
+            unreachable_zones,
+        }
+    }
+
+    fn create_parameters(&self) -> Vec<Parameter> {
+        let modifier_params = self.modifier_params.iter().map(|(m, i)| Parameter {
+            index: *i,
+            id: Some(m.to_string().to_lowercase()),
+            name: Some(m.to_string()),
+            ..Default::default()
+        });
+        let zone_param = self.zone_param.map(|i| Parameter {
+            index: i,
+            id: Some("zone".to_owned()),
+            name: Some("Zone".to_owned()),
+            value_count: NonZeroU32::new(self.top_level_zones.len() as u32),
+            value_labels: Some(self.top_level_zones.clone()),
+        });
+        let track_bank_param = self.track_bank_param.map(|i| Parameter {
+            index: i,
+            id: Some("track_bank".to_owned()),
+            name: Some("Track bank".to_owned()),
+            value_count: NonZeroU32::new(TRACK_BANK_VALUE_COUNT),
+            value_labels: None,
+        });
+        modifier_params
+            .chain(zone_param)
+            .chain(track_bank_param)
+            .collect()
+    }
+
+    fn create_group(&self, zone: &Zone) -> Group {
+        let activation_condition = self.zone_param.map(|param_index| {
+            let bank_index = self
+                .bank_index_by_zone
+                .get(&zone.name)
+                .copied()
+                .unwrap_or_default();
+            ActivationCondition::Bank(BankActivationCondition {
+                parameter: ParamRef::Index(param_index),
+                bank_index,
+            })
+        });
+        Group {
+            id: Some(zone.name.clone()),
+            name: Some(zone.alias.clone().unwrap_or_else(|| zone.name.clone())),
+            activation_condition,
+            ..Default::default()
+        }
+    }
+
+    fn modifier_param(&self, modifier: &Modifier) -> Option<u32> {
+        self.modifier_params
+            .iter()
+            .find(|(m, _)| m == modifier)
+            .map(|(_, i)| *i)
+    }
+}
+
+fn convert_zone(
+    zone: &Zone,
+    layout: &CompartmentLayout,
+    annotator: &mut Annotator,
+) -> Vec<Mapping> {
+    // In CSI, an action without modifiers is only active if no modifier is pressed. So we need to
+    // know which modifiers are used for each widget within this zone.
+    let mut modifiers_by_widget: HashMap<&str, BTreeSet<&Modifier>> = HashMap::new();
+    for line in &zone.action_lines {
+        modifiers_by_widget
+            .entry(line.widget_name.as_str())
+            .or_default()
+            .extend(line.modifiers.iter().filter(|m| m.is_global()));
+    }
+    let mut mappings = vec![];
+    for line in &zone.action_lines {
+        annotator.with_context(format!("Line \"{}\"", line), |annotator| {
+            let ctx = LineConvContext {
+                zone,
+                layout,
+                used_modifiers: &modifiers_by_widget[line.widget_name.as_str()],
+            };
+            match convert_action_line(line, &ctx, annotator) {
+                Ok(line_mappings) => mappings.extend(line_mappings),
+                Err(e) => annotator.warn(e.to_string()),
+            }
+        });
+    }
+    mappings
+}
+
+struct LineConvContext<'a> {
+    zone: &'a Zone,
+    layout: &'a CompartmentLayout,
+    used_modifiers: &'a BTreeSet<&'a Modifier>,
+}
+
+fn convert_action_line(
+    line: &ActionLine,
+    ctx: &LineConvContext,
+    annotator: &mut Annotator,
+) -> CsiResult<Vec<Mapping>> {
+    if let Some(m) = line
+        .modifiers
+        .iter()
+        .find(|m| matches!(m, Modifier::Unknown(_)))
+    {
+        return Err(format!("Unknown modifier \"{}\"", m).into());
+    }
+    let channel_indexes = if line.widget_name.contains('|') {
+        (0..CHANNEL_COUNT).map(Some).collect()
+    } else {
+        vec![None]
+    };
+    channel_indexes
+        .into_iter()
+        .map(|channel_index| {
+            let mapping = convert_action_line_for_channel(line, channel_index, ctx, annotator)?;
+            Ok(Mapping {
+                activation_condition: create_activation_condition(line, ctx)?,
+                ..mapping
+            })
+        })
+        .collect()
+}
+
+fn create_activation_condition(
+    line: &ActionLine,
+    ctx: &LineConvContext,
+) -> CsiResult<Option<ActivationCondition>> {
+    let param = |m: &Modifier| {
+        ctx.layout
+            .modifier_param(m)
+            .ok_or_else(|| format!("No parameter for modifier {}", m))
+    };
+    let mut states = vec![];
+    for m in ctx.used_modifiers.iter() {
+        let on = line.modifiers.contains(m);
+        states.push((param(m)?, on));
+    }
+    if states.is_empty() {
+        return Ok(None);
+    }
+    // ReaLearn's modifier condition supports at most 2 modifiers. Use an expression otherwise.
+    let condition = if states.len() <= 2 {
+        ActivationCondition::Modifier(ModifierActivationCondition {
+            modifiers: Some(
+                states
+                    .into_iter()
+                    .map(|(i, on)| ModifierState {
+                        parameter: ParamRef::Index(i),
+                        on,
+                    })
+                    .collect(),
+            ),
+        })
+    } else {
+        let expressions: Vec<_> = states
+            .into_iter()
+            .map(|(i, on)| {
+                if on {
+                    format!("p[{}] > 0", i)
+                } else {
+                    format!("p[{}] == 0", i)
+                }
+            })
+            .collect();
+        ActivationCondition::Expression(ExpressionActivationCondition {
+            condition: expressions.join(" && "),
+        })
+    };
+    Ok(Some(condition))
+}
+
+fn convert_action_line_for_channel(
+    line: &ActionLine,
+    channel_index: Option<u32>,
+    ctx: &LineConvContext,
+    annotator: &mut Annotator,
+) -> CsiResult<Mapping> {
+    let widget_name = match channel_index {
+        None => line.widget_name.clone(),
+        Some(i) => line.widget_name.replace('|', &(i + 1).to_string()),
+    };
+    let res = convert_action(&line.action, channel_index, ctx)?;
+    let source = if widget_name == "OnInitialization" {
+        Source::RealearnInstanceStart(RealearnInstanceStartSource)
+    } else if widget_name.starts_with("On") {
+        return Err(format!("Virtual widget \"{}\" not supported", widget_name).into());
+    } else {
+        let widget_id = convert_widget_name_to_id(&widget_name, annotator)?;
+        let touch = line.modifiers.contains(&Modifier::Touch);
+        let (id, character) = if touch {
+            let id = extended_control_element_id(&widget_id, "touch")?;
+            (id, VirtualControlElementCharacter::Button)
+        } else {
+            (widget_id, res.character)
+        };
+        Source::Virtual(VirtualSource {
+            id: VirtualControlElementId::Named(id),
+            character: Some(character),
+        })
+    };
+    let mut glue = res.glue;
+    for m in &line.modifiers {
+        match m {
+            Modifier::Press => glue.button_filter = Some(ButtonFilter::PressOnly),
+            Modifier::Release => glue.button_filter = Some(ButtonFilter::ReleaseOnly),
+            Modifier::Hold => {
+                glue.fire_mode = Some(FireMode::AfterTimeout(AfterTimeoutFireMode {
+                    timeout: Some(HOLD_TIMEOUT),
+                }))
+            }
+            Modifier::InvertFb => {
+                annotator.info("Modifier InvertFB is not supported and was ignored.");
+            }
+            _ => {}
+        }
+    }
+    let modifier_prefix: String = line.modifiers.iter().map(|m| format!("{}+", m)).collect();
+    let mapping = Mapping {
+        id: Some(format!(
+            "{}/{}{}/{}",
+            ctx.zone.name, modifier_prefix, widget_name, line.action.name
+        )),
+        name: Some(format!(
+            "{}{} - {}",
+            modifier_prefix, widget_name, line.action.name
+        )),
+        group: Some(ctx.zone.name.clone()),
+        control_enabled: if res.control_enabled {
+            None
+        } else {
+            Some(false)
+        },
+        feedback_enabled: if res.feedback_enabled {
+            None
+        } else {
+            Some(false)
+        },
+        source: Some(source),
+        glue: if glue == Glue::default() {
+            None
+        } else {
+            Some(glue)
+        },
+        target: Some(res.target),
+        ..Default::default()
+    };
+    Ok(mapping)
+}
+
+struct ActionConvResult {
+    target: Target,
+    character: VirtualControlElementCharacter,
+    glue: Glue,
+    control_enabled: bool,
+    feedback_enabled: bool,
+}
+
+impl ActionConvResult {
+    fn multi(target: Target) -> Self {
+        Self {
+            target,
+            character: VirtualControlElementCharacter::Multi,
+            glue: Default::default(),
+            control_enabled: true,
+            feedback_enabled: true,
+        }
+    }
+
+    /// For buttons that toggle the target value with each press, which is the usual behavior of
+    /// CSI actions such as `TrackMute`.
+    fn toggle_button(target: Target) -> Self {
+        Self {
+            character: VirtualControlElementCharacter::Button,
+            glue: Glue {
+                absolute_mode: Some(AbsoluteMode::ToggleButton),
+                ..Default::default()
+            },
+            ..Self::multi(target)
+        }
+    }
+
+    fn trigger_button(target: Target) -> Self {
+        Self {
+            character: VirtualControlElementCharacter::Button,
+            glue: Glue {
+                button_filter: Some(ButtonFilter::PressOnly),
+                ..Default::default()
+            },
+            feedback_enabled: false,
+            ..Self::multi(target)
+        }
+    }
+
+    fn display(target: Target, text_expression: impl Into<String>) -> Self {
+        Self {
+            glue: Glue {
+                feedback: Some(Feedback::Text(TextFeedback {
+                    text_expression: Some(text_expression.into()),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            },
+            control_enabled: false,
+            ..Self::multi(target)
+        }
+    }
+}
+
+fn convert_action(
+    action: &Action,
+    channel_index: Option<u32>,
+    ctx: &LineConvContext,
+) -> CsiResult<ActionConvResult> {
+    let track = || track_descriptor(ctx, channel_index);
+    let res = match action.name.as_str() {
+        "TrackVolume" => ActionConvResult::multi(Target::TrackVolume(TrackVolumeTarget {
+            track: Some(track()?),
+            ..Default::default()
+        })),
+        "TrackPan" => ActionConvResult::multi(Target::TrackPan(TrackPanTarget {
+            track: Some(track()?),
+            ..Default::default()
+        })),
+        "TrackPanWidth" => ActionConvResult::multi(Target::TrackWidth(TrackWidthTarget {
+            track: Some(track()?),
+            ..Default::default()
+        })),
+        "TrackMute" => {
+            ActionConvResult::toggle_button(Target::TrackMuteState(TrackMuteStateTarget {
+                track: Some(track()?),
+                ..Default::default()
+            }))
+        }
+        "TrackSolo" => {
+            ActionConvResult::toggle_button(Target::TrackSoloState(TrackSoloStateTarget {
+                track: Some(track()?),
+                ..Default::default()
+            }))
+        }
+        "TrackRecordArm" => {
+            ActionConvResult::toggle_button(Target::TrackArmState(TrackArmStateTarget {
+                track: Some(track()?),
+                ..Default::default()
+            }))
+        }
+        "TrackSelect" => ActionConvResult::toggle_button(Target::TrackSelectionState(
+            TrackSelectionStateTarget {
+                track: Some(track()?),
+                ..Default::default()
+            },
+        )),
+        "TrackUniqueSelect" => ActionConvResult::toggle_button(Target::TrackSelectionState(
+            TrackSelectionStateTarget {
+                track: Some(track()?),
+                exclusivity: Some(TrackExclusivity::WithinProject),
+                ..Default::default()
+            },
+        )),
+        "TrackTouch" => {
+            let target = Target::TrackAutomationTouchState(TrackAutomationTouchStateTarget {
+                commons: Default::default(),
+                track: Some(track()?),
+                exclusivity: None,
+                touched_parameter: TouchedTrackParameter::Volume,
+            });
+            ActionConvResult {
+                character: VirtualControlElementCharacter::Button,
+                ..ActionConvResult::multi(target)
+            }
+        }
+        "TrackNameDisplay" => ActionConvResult::display(
+            Target::TrackTool(TrackToolTarget {
+                track: Some(track()?),
+                ..Default::default()
+            }),
+            "{{ target.track.name }}",
+        ),
+        "TrackVolumeDisplay" => ActionConvResult::display(
+            Target::TrackVolume(TrackVolumeTarget {
+                track: Some(track()?),
+                ..Default::default()
+            }),
+            "{{ target.text_value }}",
+        ),
+        "TrackPanDisplay" => ActionConvResult::display(
+            Target::TrackPan(TrackPanTarget {
+                track: Some(track()?),
+                ..Default::default()
+            }),
+            "{{ target.text_value }}",
+        ),
+        "TrackPanWidthDisplay" => ActionConvResult::display(
+            Target::TrackWidth(TrackWidthTarget {
+                track: Some(track()?),
+                ..Default::default()
+            }),
+            "{{ target.text_value }}",
+        ),
+        "TrackOutputMeter" | "TrackOutputMeterAverageLR" | "TrackOutputMeterMaxPeakLR" => {
+            ActionConvResult {
+                control_enabled: false,
+                ..ActionConvResult::multi(Target::TrackPeak(TrackPeakTarget {
+                    track: Some(track()?),
+                    ..Default::default()
+                }))
+            }
+        }
+        "FXParam" => ActionConvResult::multi(fx_parameter_target(action, ctx)?),
+        "FXParamNameDisplay" => {
+            // The second parameter is an optional alias for the parameter name.
+            let text_expression = match action.params.get(1) {
+                None => "{{ target.fx_parameter.name }}".to_owned(),
+                Some(alias) => alias.clone(),
+            };
+            ActionConvResult::display(fx_parameter_target(action, ctx)?, text_expression)
+        }
+        "FXParamValueDisplay" => {
+            ActionConvResult::display(fx_parameter_target(action, ctx)?, "{{ target.text_value }}")
+        }
+        "Play" => ActionConvResult::toggle_button(transport_target(TransportAction::PlayStop)),
+        "Stop" => ActionConvResult::trigger_button(transport_target(TransportAction::Stop)),
+        "Pause" => ActionConvResult::trigger_button(transport_target(TransportAction::Pause)),
+        "Record" => ActionConvResult::toggle_button(transport_target(TransportAction::Record)),
+        "CycleTimeline" => {
+            ActionConvResult::toggle_button(transport_target(TransportAction::Repeat))
+        }
+        "Reaper" => {
+            let command = required_param(action, 0)?;
+            let command = match command.parse::<u32>() {
+                Ok(id) => ReaperCommand::Id(id),
+                Err(_) => ReaperCommand::Name(command.to_owned()),
+            };
+            ActionConvResult::trigger_button(Target::ReaperAction(ReaperActionTarget {
+                command: Some(command),
+                invocation: Some(ActionInvocationKind::Trigger),
+                ..Default::default()
+            }))
+        }
+        "GoZone" => {
+            let zone_name = required_param(action, 0)?;
+            let zone_param = ctx
+                .layout
+                .zone_param
+                .ok_or("There's just one top-level zone, so switching zones has no effect")?;
+            let bank_index = ctx
+                .layout
+                .top_level_zones
+                .iter()
+                .position(|z| z == zone_name)
+                .ok_or_else(|| {
+                    format!(
+                        "Zone \"{}\" doesn't exist or is not a top-level zone",
+                        zone_name
+                    )
+                })?;
+            let max_index = ctx.layout.top_level_zones.len() - 1;
+            let value = bank_index as f64 / max_index as f64;
+            let mut res = ActionConvResult::trigger_button(own_parameter_target(zone_param));
+            res.glue.target_interval = Some(Interval(value, value));
+            res
+        }
+        "TrackBank" => {
+            let amount: i32 = required_param(action, 0)?
+                .parse()
+                .map_err(|_| "TrackBank amount is not a number")?;
+            let track_bank_param = ctx
+                .layout
+                .track_bank_param
+                .expect("track bank parameter should exist if TrackBank action is used");
+            let step_size = amount.unsigned_abs() as f64 / (TRACK_BANK_VALUE_COUNT - 1) as f64;
+            ActionConvResult {
+                character: VirtualControlElementCharacter::Button,
+                glue: Glue {
+                    absolute_mode: Some(AbsoluteMode::IncrementalButton),
+                    step_size_interval: Some(Interval(step_size, step_size)),
+                    reverse: if amount < 0 { Some(true) } else { None },
+                    ..Default::default()
+                },
+                feedback_enabled: false,
+                ..ActionConvResult::multi(own_parameter_target(track_bank_param))
+            }
+        }
+        name => {
+            let modifier = Modifier::from_name(name);
+            let param_index = if modifier.is_global() {
+                ctx.layout.modifier_param(&modifier)
+            } else {
+                None
+            };
+            let param_index =
+                param_index.ok_or_else(|| format!("Action \"{}\" not supported", name))?;
+            ActionConvResult {
+                character: VirtualControlElementCharacter::Button,
+                ..ActionConvResult::multi(own_parameter_target(param_index))
+            }
+        }
+    };
+    Ok(res)
+}
+
+fn track_descriptor(
+    ctx: &LineConvContext,
+    channel_index: Option<u32>,
+) -> CsiResult<TrackDescriptor> {
+    let desc = match &ctx.zone.navigator {
+        None | Some(Navigator::SelectedTrack) => TrackDescriptor::Selected {
+            allow_multiple: None,
+        },
+        Some(Navigator::MasterTrack) => TrackDescriptor::Master {
+            commons: Default::default(),
+        },
+        Some(Navigator::Track) => {
+            let channel_index = channel_index.ok_or(
+                "Zones with TrackNavigator need channel widgets (containing \"|\") in order to address tracks",
+            )?;
+            match ctx.layout.track_bank_param {
+                None => TrackDescriptor::ByIndex {
+                    commons: Default::default(),
+                    index: channel_index,
+                    scope: None,
+                },
+                Some(param_index) => TrackDescriptor::Dynamic {
+                    commons: Default::default(),
+                    expression: format!("p[{}] + {}", param_index, channel_index),
+                    scope: None,
+                },
+            }
+        }
+        Some(n) => {
+            return Err(format!("Track actions within zones using {} not supported", n).into())
+        }
+    };
+    Ok(desc)
+}
+
+fn fx_parameter_target(action: &Action, ctx: &LineConvContext) -> CsiResult<Target> {
+    let index: u32 = required_param(action, 0)?
+        .parse()
+        .map_err(|_| "FX parameter index is not a number")?;
+    let fx = match &ctx.zone.navigator {
+        None | Some(Navigator::FocusedFx) => FxDescriptor::Focused,
+        Some(n) => return Err(format!("FX actions within zones using {} not supported", n).into()),
+    };
+    let target = Target::FxParameterValue(FxParameterValueTarget {
+        commons: Default::default(),
+        parameter: FxParameterDescriptor::ByIndex {
+            fx: Some(fx),
+            index,
+        },
+        poll_for_feedback: None,
+        retrigger: None,
+    });
+    Ok(target)
+}
+
+/// Targets a parameter of this ReaLearn instance, which is how mappings can change compartment
+/// parameters.
+fn own_parameter_target(param_index: u32) -> Target {
+    Target::FxParameterValue(FxParameterValueTarget {
+        commons: Default::default(),
+        parameter: FxParameterDescriptor::ByIndex {
+            fx: Some(FxDescriptor::This {
+                commons: Default::default(),
+            }),
+            index: param_index,
+        },
+        poll_for_feedback: None,
+        retrigger: None,
+    })
+}
+
+fn transport_target(action: TransportAction) -> Target {
+    Target::TransportAction(TransportActionTarget {
+        commons: Default::default(),
+        action,
+    })
+}
+
+fn required_param(action: &Action, index: usize) -> CsiResult<&str> {
+    let param = action.params.get(index).ok_or_else(|| {
+        format!(
+            "Action \"{}\" is missing parameter {}",
+            action.name,
+            index + 1
+        )
+    })?;
+    Ok(param)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::zon_file_content;
+
+    #[test]
+    fn convert_test_zones() {
+        // Given
+        let zones = zon_file_content(include_str!("test_data/test.zon")).unwrap();
+        let mut annotator = Annotator::new();
+        // When
+        let compartment = convert_zones(zones, &mut annotator);
+        // Then
+        let params = compartment.parameters.unwrap();
+        let param_names: Vec<_> = params.iter().map(|p| p.name.as_deref().unwrap()).collect();
+        assert_eq!(param_names, vec!["Shift", "Option", "Zone", "Track bank"]);
+        assert!(params[2].value_labels.as_ref().unwrap() == &vec!["Home", "Send", "FocusedFX"]);
+        let groups = compartment.groups.unwrap();
+        assert!(groups.len() == 5);
+        let channel_group = groups
+            .iter()
+            .find(|g| g.id.as_deref() == Some("Channel"))
+            .unwrap();
+        assert!(
+            channel_group.activation_condition
+                == Some(ActivationCondition::Bank(BankActivationCondition {
+                    parameter: ParamRef::Index(2),
+                    bank_index: 0
+                }))
+        );
+        let mappings = compartment.mappings.unwrap();
+        let find = |id: &str| mappings.iter().find(|m| m.id.as_deref() == Some(id));
+        // Channel widgets are expanded
+        let fader_3 = find("Channel/Fader3/TrackVolume").unwrap();
+        assert!(
+            fader_3.target
+                == Some(Target::TrackVolume(TrackVolumeTarget {
+                    track: Some(TrackDescriptor::Dynamic {
+                        commons: Default::default(),
+                        expression: "p[3] + 2".to_owned(),
+                        scope: None
+                    }),
+                    ..Default::default()
+                }))
+        );
+        // Modifiers are translated into activation conditions
+        let rotary_1 = find("Channel/Rotary1/TrackPan").unwrap();
+        assert!(
+            rotary_1.activation_condition
+                == Some(ActivationCondition::Modifier(ModifierActivationCondition {
+                    modifiers: Some(vec![ModifierState {
+                        parameter: ParamRef::Index(0),
+                        on: false
+                    }])
+                }))
+        );
+        let shift_rotary_1 = find("Channel/Shift+Rotary1/TrackPanWidth").unwrap();
+        assert!(
+            shift_rotary_1.activation_condition
+                == Some(ActivationCondition::Modifier(ModifierActivationCondition {
+                    modifiers: Some(vec![ModifierState {
+                        parameter: ParamRef::Index(0),
+                        on: true
+                    }])
+                }))
+        );
+        // Zone switching sets the zone parameter
+        let go_home = find("Send/Home/GoZone").unwrap();
+        assert!(go_home.target == Some(own_parameter_target(2)));
+        assert!(go_home.glue.as_ref().unwrap().target_interval == Some(Interval(0.0, 0.0)));
+        // Touch modifier addresses the touch control element
+        let touch_fader_1 = find("Channel/Touch+Fader1/TrackTouch").unwrap();
+        assert!(
+            touch_fader_1.source
+                == Some(Source::Virtual(VirtualSource {
+                    id: VirtualControlElementId::Named("Fader1/touch".to_owned()),
+                    character: Some(VirtualControlElementCharacter::Button)
+                }))
+        );
+        // Unsupported actions are reported
+        let annotations: Vec<_> = annotator
+            .build_result(())
+            .annotations
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        assert!(annotations
+            .iter()
+            .any(|a| a.contains("DoSomethingWeird") && a.contains("not supported")));
+        assert!(annotations.iter().any(|a| a.contains("TrackSendVolume")));
+    }
+}
@@ -274,6 +274,16 @@ pub fn create(context: ScopedContext, ids: &mut IdGenerator) -> Dialog {
             ids.named_id("ID_TARGET_LINE_5_EDIT_CONTROL"),
             context.rect(282, 175, 127, 14),
         ) + ES_AUTOHSCROLL,
+        dropdown(
+            ids.named_id("ID_TARGET_LINE_5_COMBO_BOX_1"),
+            context.rect(220, 176, 58, 30),
+        ) + WS_VSCROLL
+            + WS_TABSTOP,
+        dropdown(
+            ids.named_id("ID_TARGET_LINE_5_COMBO_BOX_2"),
+            context.rect(283, 176, 155, 15),
+        ) + WS_VSCROLL
+            + WS_TABSTOP,
         context.checkbox(
             "Monitoring FX",
             ids.named_id("ID_TARGET_CHECK_BOX_1"),
@@ -491,6 +501,24 @@ pub fn create(context: ScopedContext, ids: &mut IdGenerator) -> Dialog {
             ids.named_id("ID_SETTINGS_MODE_COMBO_BOX"),
             context.rect(50, 355, 168, 15),
         ) + WS_TABSTOP,
+        ltext(
+            "Gesture",
+            ids.named_id("ID_MODE_GESTURE_LABEL_TEXT"),
+            context.rect(15, 376, 30, 9),
+        ) + NOT_WS_GROUP,
+        dropdown(
+            ids.named_id("ID_MODE_GESTURE_COMBO_BOX"),
+            context.rect(50, 374, 100, 15),
+        ) + WS_TABSTOP,
+        edittext(
+            ids.named_id("ID_MODE_GESTURE_TAP_COUNT_EDIT_CONTROL"),
+            context.rect(155, 374, 25, 14),
+        ) + ES_AUTOHSCROLL,
+        ltext(
+            "taps",
+            ids.named_id("ID_MODE_GESTURE_TAP_COUNT_LABEL_TEXT"),
+            context.rect(184, 377, 30, 9),
+        ) + NOT_WS_GROUP,
         ltext(
             "Takeover",
             ids.named_id("ID_MODE_TAKEOVER_LABEL"),
@@ -7,10 +7,11 @@ use crate::application::{
 };
 use crate::domain::{
     ActivationCondition, Compartment, CompoundMappingSource, CompoundMappingTarget,
-    EelTransformation, ExtendedProcessorContext, ExtendedSourceCharacter, FeedbackSendBehavior,
-    GroupId, MainMapping, MappingId, MappingKey, Mode, PersistentMappingProcessingState,
-    ProcessorMappingOptions, QualifiedMappingId, RealearnTarget, ReaperTarget, Script, Tag,
-    TargetCharacter, UnresolvedCompoundMappingTarget, VirtualFx, VirtualTrack,
+    ExtendedProcessorContext, ExtendedSourceCharacter, FeedbackSendBehavior,
+    FlexibleTransformation, GroupId, MainMapping, MappingId, MappingKey, Mode,
+    PersistentMappingProcessingState, ProcessorMappingOptions, QualifiedMappingId, RealearnTarget,
+    ReaperTarget, Script, Tag, TargetCharacter, UnresolvedCompoundMappingTarget, VirtualFx,
+    VirtualTrack,
 };
 use helgoboss_learn::{
     AbsoluteMode, ControlType, DetailedSourceCharacter, DiscreteIncrement, Interval,
@@ -36,6 +37,9 @@ pub enum MappingCommand {
     ChangeSource(SourceCommand),
     ChangeMode(ModeCommand),
     ChangeTarget(TargetCommand),
+    SetYTarget(Option<TargetModel>),
+    /// Exchanges target and Y target (if there's a Y target).
+    SwapTargetAndYTarget,
 }
 
 #[derive(Eq, PartialEq)]
@@ -54,6 +58,7 @@ pub enum MappingProp {
     InSource(Affected<SourceProp>),
     InMode(Affected<ModeProp>),
     InTarget(Affected<TargetProp>),
+    YTarget,
 }
 
 impl GetProcessingRelevance for MappingProp {
@@ -67,7 +72,8 @@ impl GetProcessingRelevance for MappingProp {
             | P::FeedbackSendBehavior
             | P::VisibleInProjection
             | P::AdvancedSettings
-            | P::BeepOnSuccess => Some(ProcessingRelevance::ProcessingRelevant),
+            | P::BeepOnSuccess
+            | P::YTarget => Some(ProcessingRelevance::ProcessingRelevant),
             P::InActivationCondition(p) => p.processing_relevance(),
             P::InMode(p) => p.processing_relevance(),
             P::InSource(p) => p.processing_relevance(),
@@ -100,6 +106,8 @@ pub struct MappingModel {
     pub source_model: SourceModel,
     pub mode_model: ModeModel,
     pub target_model: TargetModel,
+    /// Target controlled by the Y axis of an XY source.
+    y_target_model: Option<TargetModel>,
     advanced_settings: Option<serde_yaml::mapping::Mapping>,
     extension_model: MappingExtensionModel,
 }
@@ -194,6 +202,15 @@ impl<'a> Change<'a> for MappingModel {
                     .change(cmd)
                     .map(|affected| One(P::InTarget(affected)));
             }
+            C::SetYTarget(v) => {
+                self.y_target_model = v;
+                One(P::YTarget)
+            }
+            C::SwapTargetAndYTarget => {
+                let y_target_model = self.y_target_model.as_mut()?;
+                std::mem::swap(&mut self.target_model, y_target_model);
+                Multiple
+            }
         };
         Some(affected)
     }
@@ -223,6 +240,7 @@ impl MappingModel {
             source_model: SourceModel::new(),
             mode_model: Default::default(),
             target_model: TargetModel::default_for_compartment(compartment),
+            y_target_model: None,
             advanced_settings: None,
             extension_model: Default::default(),
         }
@@ -236,6 +254,10 @@ impl MappingModel {
         &self.key
     }
 
+    pub fn y_target_model(&self) -> Option<&TargetModel> {
+        self.y_target_model.as_ref()
+    }
+
     pub fn group_id(&self) -> GroupId {
         self.group_id
     }
@@ -485,8 +507,9 @@ impl MappingModel {
             // TODO-high-discrete Enable (also taking source into consideration!)
             target_supports_discrete_values: false,
             control_transformation_uses_time: {
-                let transformation = EelTransformation::compile_for_control(
-                    self.mode_model.eel_control_transformation(),
+                let transformation = FlexibleTransformation::compile_for_control(
+                    self.mode_model.control_transformation_language(),
+                    self.mode_model.control_transformation(),
                 );
                 transformation.map(|t| t.uses_time()).unwrap_or(false)
             },
@@ -545,6 +568,13 @@ impl MappingModel {
         self.target_model.create_target(self.compartment).ok()
     }
 
+    fn create_y_target(&self) -> Option<UnresolvedCompoundMappingTarget> {
+        self.y_target_model
+            .as_ref()?
+            .create_target(self.compartment)
+            .ok()
+    }
+
     pub fn create_persistent_mapping_processing_state(&self) -> PersistentMappingProcessingState {
         PersistentMappingProcessingState {
             is_enabled: self.is_enabled(),
@@ -582,7 +612,9 @@ impl MappingModel {
             source,
             mode,
             self.mode_model.group_interaction(),
+            self.mode_model.create_gesture_recognizer(),
             unresolved_target,
+            self.create_y_target(),
             group_data.activation_condition,
             activation_condition,
             options,
@@ -1,4 +1,4 @@
-use crate::domain::{EelTransformation, Mode};
+use crate::domain::{FlexibleTransformation, GestureRecognizer, Mode};
 
 use helgoboss_learn::{
     check_mode_applicability, create_unit_value_interval, full_discrete_interval,
@@ -9,7 +9,7 @@ use helgoboss_learn::{
 };
 
 use crate::application::{Affected, Change, GetProcessingRelevance, ProcessingRelevance};
-use realearn_api::persistence::FeedbackValueTable;
+use realearn_api::persistence::{FeedbackValueTable, Gesture, TransformationLanguage};
 use std::time::Duration;
 
 pub enum ModeCommand {
@@ -32,8 +32,10 @@ pub enum ModeCommand {
     SetTakeoverMode(TakeoverMode),
     SetButtonUsage(ButtonUsage),
     SetEncoderUsage(EncoderUsage),
-    SetEelControlTransformation(String),
-    SetEelFeedbackTransformation(String),
+    SetControlTransformationLanguage(TransformationLanguage),
+    SetFeedbackTransformationLanguage(TransformationLanguage),
+    SetControlTransformation(String),
+    SetFeedbackTransformation(String),
     SetStepSizeInterval(Interval<UnitValue>),
     SetStepFactorInterval(Interval<DiscreteIncrement>),
     SetMinStepSize(UnitValue),
@@ -43,6 +45,8 @@ pub enum ModeCommand {
     SetRotate(bool),
     SetMakeAbsolute(bool),
     SetGroupInteraction(GroupInteraction),
+    SetGesture(Gesture),
+    SetGestureTapCount(u32),
     SetTargetValueSequence(ValueSequence),
     SetFeedbackType(FeedbackType),
     SetTextualFeedbackExpression(String),
@@ -68,13 +72,17 @@ pub enum ModeProp {
     TakeoverMode,
     ButtonUsage,
     EncoderUsage,
-    EelControlTransformation,
-    EelFeedbackTransformation,
+    ControlTransformationLanguage,
+    FeedbackTransformationLanguage,
+    ControlTransformation,
+    FeedbackTransformation,
     StepSizeInterval,
     StepFactorInterval,
     Rotate,
     MakeAbsolute,
     GroupInteraction,
+    Gesture,
+    GestureTapCount,
     TargetValueSequence,
     FeedbackType,
     TextualFeedbackExpression,
@@ -111,8 +119,11 @@ pub struct ModeModel {
     takeover_mode: TakeoverMode,
     button_usage: ButtonUsage,
     encoder_usage: EncoderUsage,
-    eel_control_transformation: String,
-    eel_feedback_transformation: String,
+    control_transformation_language: TransformationLanguage,
+    /// Not relevant for textual feedback, text expressions are always EEL.
+    feedback_transformation_language: TransformationLanguage,
+    control_transformation: String,
+    feedback_transformation: String,
     // For relative control values.
     /// A step size is the positive, absolute size of an increment. 0.0 represents no increment,
     /// 1.0 represents an increment over the whole value range (not very useful).
@@ -132,6 +143,9 @@ pub struct ModeModel {
     rotate: bool,
     make_absolute: bool,
     group_interaction: GroupInteraction,
+    gesture: Gesture,
+    /// Only relevant for [`Gesture::MultiTap`].
+    gesture_tap_count: u32,
     target_value_sequence: ValueSequence,
     feedback_type: FeedbackType,
     textual_feedback_expression: String,
@@ -159,13 +173,17 @@ impl Default for ModeModel {
             takeover_mode: Default::default(),
             button_usage: Default::default(),
             encoder_usage: Default::default(),
-            eel_control_transformation: String::new(),
-            eel_feedback_transformation: String::new(),
+            control_transformation_language: Default::default(),
+            feedback_transformation_language: Default::default(),
+            control_transformation: String::new(),
+            feedback_transformation: String::new(),
             step_size_interval: Self::default_step_size_interval(),
             step_factor_interval: Self::default_step_factor_interval(),
             rotate: false,
             make_absolute: false,
             group_interaction: Default::default(),
+            gesture: Default::default(),
+            gesture_tap_count: Self::DEFAULT_GESTURE_TAP_COUNT,
             target_value_sequence: Default::default(),
             feedback_type: Default::default(),
             textual_feedback_expression: Default::default(),
@@ -267,13 +285,21 @@ impl<'a> Change<'a> for ModeModel {
                 self.encoder_usage = v;
                 One(P::EncoderUsage)
             }
-            C::SetEelControlTransformation(v) => {
-                self.eel_control_transformation = v;
-                One(P::EelControlTransformation)
+            C::SetControlTransformationLanguage(v) => {
+                self.control_transformation_language = v;
+                One(P::ControlTransformationLanguage)
             }
-            C::SetEelFeedbackTransformation(v) => {
-                self.eel_feedback_transformation = v;
-                One(P::EelFeedbackTransformation)
+            C::SetFeedbackTransformationLanguage(v) => {
+                self.feedback_transformation_language = v;
+                One(P::FeedbackTransformationLanguage)
+            }
+            C::SetControlTransformation(v) => {
+                self.control_transformation = v;
+                One(P::ControlTransformation)
+            }
+            C::SetFeedbackTransformation(v) => {
+                self.feedback_transformation = v;
+                One(P::FeedbackTransformation)
             }
             C::SetStepSizeInterval(v) => {
                 self.step_size_interval = v;
@@ -311,6 +337,14 @@ impl<'a> Change<'a> for ModeModel {
                 self.group_interaction = v;
                 One(P::GroupInteraction)
             }
+            C::SetGesture(v) => {
+                self.gesture = v;
+                One(P::Gesture)
+            }
+            C::SetGestureTapCount(v) => {
+                self.gesture_tap_count = v.max(1);
+                One(P::GestureTapCount)
+            }
             C::SetTargetValueSequence(v) => {
                 self.target_value_sequence = v;
                 One(P::TargetValueSequence)
@@ -345,6 +379,8 @@ impl<'a> Change<'a> for ModeModel {
 }
 
 impl ModeModel {
+    pub const DEFAULT_GESTURE_TAP_COUNT: u32 = 4;
+
     pub fn default_step_size_interval() -> Interval<UnitValue> {
         // 0.01 has been chosen as default minimum step size because it corresponds to 1%.
         //
@@ -415,12 +451,20 @@ impl ModeModel {
         self.encoder_usage
     }
 
-    pub fn eel_control_transformation(&self) -> &str {
-        &self.eel_control_transformation
+    pub fn control_transformation_language(&self) -> TransformationLanguage {
+        self.control_transformation_language
+    }
+
+    pub fn feedback_transformation_language(&self) -> TransformationLanguage {
+        self.feedback_transformation_language
     }
 
-    pub fn eel_feedback_transformation(&self) -> &str {
-        &self.eel_feedback_transformation
+    pub fn control_transformation(&self) -> &str {
+        &self.control_transformation
+    }
+
+    pub fn feedback_transformation(&self) -> &str {
+        &self.feedback_transformation
     }
 
     pub fn step_size_interval(&self) -> Interval<UnitValue> {
@@ -443,6 +487,18 @@ impl ModeModel {
         self.group_interaction
     }
 
+    pub fn gesture(&self) -> Gesture {
+        self.gesture
+    }
+
+    pub fn gesture_tap_count(&self) -> u32 {
+        self.gesture_tap_count
+    }
+
+    pub fn create_gesture_recognizer(&self) -> GestureRecognizer {
+        GestureRecognizer::new(self.gesture, self.gesture_tap_count)
+    }
+
     pub fn target_value_sequence(&self) -> &ValueSequence {
         &self.target_value_sequence
     }
@@ -608,12 +664,20 @@ impl ModeModel {
                 OutOfRangeBehavior::default()
             },
             control_transformation: if is_relevant(ModeParameter::ControlTransformation) {
-                EelTransformation::compile_for_control(&self.eel_control_transformation).ok()
+                FlexibleTransformation::compile_for_control(
+                    self.control_transformation_language,
+                    &self.control_transformation,
+                )
+                .ok()
             } else {
                 None
             },
             feedback_transformation: if is_relevant(ModeParameter::FeedbackTransformation) {
-                EelTransformation::compile_for_feedback(&self.eel_feedback_transformation).ok()
+                FlexibleTransformation::compile_for_feedback(
+                    self.feedback_transformation_language,
+                    &self.feedback_transformation,
+                )
+                .ok()
             } else {
                 None
             },
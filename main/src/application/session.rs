@@ -12,15 +12,16 @@ use crate::base::{
 use crate::domain::{
     convert_plugin_param_index_range_to_iter, BackboneState, BasicSettings, Compartment,
     CompartmentParamIndex, CompartmentParams, CompoundMappingSource, ControlContext, ControlInput,
-    DomainEvent, DomainEventHandler, ExtendedProcessorContext, FeedbackAudioHookTask,
+    DomainEvent, DomainEventHandler, Exclusivity, ExtendedProcessorContext, FeedbackAudioHookTask,
     FeedbackOutput, FeedbackRealTimeTask, FinalSourceFeedbackValue, GroupId, GroupKey,
     IncomingCompoundSourceValue, InputDescriptor, InstanceContainer, InstanceId, InstanceState,
     MainMapping, MappingId, MappingKey, MappingMatchedEvent, MessageCaptureEvent, MidiControlInput,
     NormalMainTask, NormalRealTimeTask, OscFeedbackTask, ParamSetting, PluginParams,
     ProcessorContext, ProjectionFeedbackValue, QualifiedMappingId, RealearnClipMatrix,
-    RealearnTarget, ReaperTarget, SharedInstanceState, StayActiveWhenProjectInBackground, Tag,
-    TargetControlEvent, TargetValueChangedEvent, VirtualControlElementId, VirtualFx, VirtualSource,
-    VirtualSourceValue,
+    RealearnTarget, ReaperTarget, SharedInstanceState, SpeechSettings,
+    StayActiveWhenProjectInBackground, Tag, TagScope, TargetControlEvent, TargetValueChangedEvent,
+    UpdatedSingleMappingOnStateEvent, VirtualControlElementId, VirtualFx,
+    VirtualMappingSnapshotIdForLoad, VirtualSource, VirtualSourceValue,
 };
 use derivative::Derivative;
 use enum_map::EnumMap;
@@ -45,7 +46,9 @@ use std::rc::{Rc, Weak};
 
 pub trait SessionUi {
     fn show_mapping(&self, compartment: Compartment, mapping_id: MappingId);
-    fn target_value_changed(&self, event: TargetValueChangedEvent);
+    fn target_value_changed(&self, session: &Session, event: TargetValueChangedEvent);
+    fn mapping_on_states_changed(&self, session: &Session);
+    fn mapping_on_state_changed(&self, session: &Session, event: UpdatedSingleMappingOnStateEvent);
     fn parameters_changed(&self, session: &Session);
     fn midi_devices_changed(&self);
     fn celebrate_success(&self);
@@ -101,6 +104,8 @@ pub struct Session {
     pub target_control_logging_enabled: Prop<bool>,
     pub send_feedback_only_if_armed: Prop<bool>,
     pub reset_feedback_when_releasing_source: Prop<bool>,
+    /// Used by speech sources unless they override them.
+    pub speech_settings: Prop<SpeechSettings>,
     pub control_input: Prop<ControlInput>,
     pub feedback_output: Prop<Option<FeedbackOutput>>,
     pub main_preset_auto_load_mode: Prop<MainPresetAutoLoadMode>,
@@ -259,6 +264,7 @@ impl Session {
             reset_feedback_when_releasing_source: prop(
                 session_defaults::RESET_FEEDBACK_WHEN_RELEASING_SOURCE,
             ),
+            speech_settings: prop(Default::default()),
             control_input: prop(Default::default()),
             feedback_output: prop(None),
             main_preset_auto_load_mode: prop(session_defaults::MAIN_PRESET_AUTO_LOAD_MODE),
@@ -461,6 +467,64 @@ impl Session {
             .send_complaining(NormalMainTask::HitTarget { id, value });
     }
 
+    /// Controls main mappings with virtual sources as if a controller mapping had emitted the
+    /// given value.
+    pub fn process_virtual_source_value(&self, value: VirtualSourceValue) {
+        self.normal_main_task_sender
+            .send_complaining(NormalMainTask::ProcessVirtualSourceValue(value));
+    }
+
+    /// Enables or disables all mappings in the given compartment which match the given tag scope.
+    pub fn enable_mappings_by_tag(
+        &self,
+        compartment: Compartment,
+        scope: TagScope,
+        exclusivity: Exclusivity,
+        is_enable: bool,
+    ) {
+        self.normal_main_task_sender
+            .send_complaining(NormalMainTask::EnableMappingsByTag {
+                compartment,
+                scope,
+                exclusivity,
+                is_enable,
+            });
+    }
+
+    /// Loads the given mapping snapshot into all mappings in the given compartment which match the
+    /// given tag scope.
+    pub fn load_mapping_snapshot(
+        &self,
+        compartment: Compartment,
+        scope: TagScope,
+        active_mappings_only: bool,
+        snapshot_id: VirtualMappingSnapshotIdForLoad,
+    ) {
+        self.normal_main_task_sender
+            .send_complaining(NormalMainTask::LoadMappingSnapshot {
+                compartment,
+                scope,
+                active_mappings_only,
+                snapshot_id,
+            });
+    }
+
+    /// Changes the value of the given compartment parameter as if it had been automated.
+    pub fn set_compartment_parameter_value(
+        &self,
+        compartment: Compartment,
+        index: CompartmentParamIndex,
+        value: UnitValue,
+    ) {
+        let fx = self.processor_context.containing_fx().clone();
+        let plugin_param_index = compartment.to_plugin_param_index(index);
+        let _ = Global::task_support().do_later_in_main_thread_from_main_thread_asap(move || {
+            let _ = fx
+                .parameter_by_index(plugin_param_index.get())
+                .set_reaper_normalized_value(value.get());
+        });
+    }
+
     /// Connects the dots.
     // TODO-low Too large. Split this into several methods.
     pub fn activate(&mut self, weak_session: WeakSession) {
@@ -584,6 +648,7 @@ impl Session {
             .merge(self.auto_correct_settings.changed())
             .merge(self.send_feedback_only_if_armed.changed())
             .merge(self.reset_feedback_when_releasing_source.changed())
+            .merge(self.speech_settings.changed())
             .merge(self.main_preset_auto_load_mode.changed())
             .merge(self.real_input_logging_enabled.changed())
             .merge(self.real_output_logging_enabled.changed())
@@ -777,7 +842,7 @@ impl Session {
         self.add_group(compartment, group)
     }
 
-    fn add_group(&mut self, compartment: Compartment, group: GroupModel) -> GroupId {
+    pub fn add_group(&mut self, compartment: Compartment, group: GroupModel) -> GroupId {
         let id = group.id();
         let shared_group = Rc::new(RefCell::new(group));
         self.groups[compartment].push(shared_group);
@@ -866,6 +931,30 @@ impl Session {
         Ok(())
     }
 
+    /// Replaces the group which has the same ID as the given one.
+    pub fn replace_group(
+        &mut self,
+        group: GroupModel,
+        weak_session: WeakSession,
+    ) -> Result<(), &'static str> {
+        use Affected::*;
+        let compartment = group.compartment();
+        let id = group.id();
+        let shared_group = self
+            .find_group_by_id(compartment, id)
+            .ok_or("group not found")?;
+        *shared_group.borrow_mut() = group;
+        self.handle_affected(
+            One(SessionProp::InCompartment(
+                compartment,
+                One(CompartmentProp::InGroup(id, Multiple)),
+            )),
+            None,
+            weak_session,
+        );
+        Ok(())
+    }
+
     pub fn remove_group(&mut self, compartment: Compartment, id: GroupId, delete_mappings: bool) {
         self.groups[compartment].retain(|g| g.borrow().id() != id);
         if delete_mappings {
@@ -1513,6 +1602,12 @@ impl Session {
         })
     }
 
+    pub fn find_mapping_key_by_id(&self, id: QualifiedMappingId) -> Option<MappingKey> {
+        let (_, m) = self.find_mapping_and_index_by_id(id.compartment, id.id)?;
+        let key = m.borrow().key().clone();
+        Some(key)
+    }
+
     pub fn mappings(&self, compartment: Compartment) -> impl Iterator<Item = &SharedMapping> {
         self.mappings[compartment].iter()
     }
@@ -1753,6 +1848,21 @@ impl Session {
         Ok(())
     }
 
+    /// Replaces the mapping which has the same ID as the given one.
+    pub fn replace_mapping(
+        &mut self,
+        mapping: MappingModel,
+        weak_session: WeakSession,
+    ) -> Result<(), &'static str> {
+        let id = mapping.qualified_id();
+        let (_, shared_mapping) = self
+            .find_mapping_and_index_by_qualified_id(id)
+            .ok_or("mapping not found")?;
+        *shared_mapping.borrow_mut() = mapping;
+        self.notify_mapping_has_changed(id, weak_session);
+        Ok(())
+    }
+
     pub fn remove_mapping(&mut self, id: QualifiedMappingId) {
         self.stop_mapping_actions();
         self.mappings[id.compartment].retain(|m| m.borrow().id() != id.id);
@@ -2301,6 +2411,10 @@ impl Session {
             .send_complaining(NormalMainTask::UpdateSettings(settings));
         self.normal_real_time_task_sender
             .send_complaining(NormalRealTimeTask::UpdateSettings(settings));
+        self.normal_main_task_sender
+            .send_complaining(NormalMainTask::UpdateSpeechSettings(
+                self.speech_settings.get_ref().clone(),
+            ));
     }
 
     fn sync_persistent_mapping_processing_state(&self, mapping: &MappingModel) {
@@ -2432,11 +2546,9 @@ impl DomainEventHandler for WeakSession {
                 session.borrow_mut().captured_incoming_message(event);
             }
             UpdatedOnMappings(on_mappings) => {
-                session
-                    .borrow()
-                    .instance_state
-                    .borrow_mut()
-                    .set_on_mappings(on_mappings);
+                let s = session.borrow();
+                s.instance_state.borrow_mut().set_on_mappings(on_mappings);
+                s.ui.mapping_on_states_changed(&s);
             }
             GlobalControlAndFeedbackStateChanged(state) => {
                 session
@@ -2446,18 +2558,19 @@ impl DomainEventHandler for WeakSession {
                     .set_global_control_and_feedback_state(state);
             }
             UpdatedSingleMappingOnState(event) => {
-                session
-                    .borrow()
-                    .instance_state
+                let s = session.borrow();
+                s.instance_state
                     .borrow_mut()
                     .set_mapping_on(event.id, event.is_on);
+                s.ui.mapping_on_state_changed(&s, event);
             }
             TargetValueChanged(e) => {
                 // If the session is borrowed already, just let it be. It happens only in a very
                 // particular case of reentrancy (because of a quirk in REAPER related to master
                 // tempo notification, https://github.com/helgoboss/realearn/issues/199). If the
                 // target value slider is not updated then ... so what.
-                session.try_borrow()?.ui.target_value_changed(e);
+                let s = session.try_borrow()?;
+                s.ui.target_value_changed(&s, e);
             }
             UpdatedSingleParameterValue { index, value } => {
                 let mut session = session.borrow_mut();
@@ -2,22 +2,29 @@ use crate::application::{
     Affected, Change, GetProcessingRelevance, MappingProp, ProcessingRelevance,
 };
 use crate::domain::{
-    BackboneState, Compartment, CompartmentParamIndex, CompoundMappingSource, EelMidiSourceScript,
-    ExtendedSourceCharacter, FlexibleMidiSourceScript, KeySource, Keystroke, LuaMidiSourceScript,
-    MidiSource, RealearnParameterSource, ReaperSource, SpeechSource, TimerSource,
-    VirtualControlElement, VirtualControlElementId, VirtualSource, VirtualTarget,
+    AudioLevelSettings, AudioLevelSource, BackboneState, ChordElements, ChordSource, Compartment,
+    CompartmentParamIndex, CompoundMappingSource, EelMidiSourceControlScript, EelMidiSourceScript,
+    ExtendedSourceCharacter, FlexibleMidiSourceControlScript, FlexibleMidiSourceScript, KeySource,
+    Keystroke, LuaMidiSourceControlScript, LuaMidiSourceScript, MidiSource, MidiSourceScripts,
+    MpeNote, MpeSource, MusicalTimerSettings, MusicalTimerSource, OscSource,
+    RealearnParameterSource, ReaperSource, SpeechSettingsOverride, SpeechSource, TimerSource,
+    VirtualControlElement, VirtualControlElementId, VirtualSource, VirtualTarget, MAX_SPEECH_RATE,
+    MIN_SPEECH_RATE,
 };
 use derive_more::Display;
 use enum_iterator::IntoEnumIterator;
 use helgoboss_learn::{
     ControlValue, DetailedSourceCharacter, DisplaySpec, DisplayType, Interval, MackieLcdScope,
-    MackieSevenSegmentDisplayScope, MidiClockTransportMessage, OscArgDescriptor, OscSource,
-    OscTypeTag, SiniConE24Scope, SlKeyboardDisplayScope, SourceCharacter, UnitValue,
+    MackieSevenSegmentDisplayScope, MidiClockTransportMessage, OscArgDescriptor, OscTypeTag,
+    SiniConE24Scope, SlKeyboardDisplayScope, SourceCharacter, UnitValue,
     DEFAULT_OSC_ARG_VALUE_RANGE,
 };
 use helgoboss_midi::{Channel, U14, U7};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use realearn_api::persistence::MidiScriptKind;
+use realearn_api::persistence::{
+    AudioLevelInput, AudioLevelMeasurement, ChordMode, MidiScriptKind, MpeDimension,
+    MpeNoteSelector, MpeZone, MusicalTimerOutput, MusicalTimerTrigger, SpeechQueuePolicy,
+};
 use serde::{Deserialize, Serialize};
 use serde_repr::*;
 use std::borrow::Cow;
@@ -40,21 +47,46 @@ pub enum SourceCommand {
     SetRawMidiPattern(String),
     SetMidiScriptKind(MidiScriptKind),
     SetMidiScript(String),
+    SetMidiControlScript(String),
     SetDisplayType(DisplayType),
     SetDisplayId(Option<u8>),
     SetLine(Option<u8>),
+    SetMpeZone(MpeZone),
+    SetMpeMemberChannelCount(u8),
+    SetMpeDimension(MpeDimension),
+    SetMpeNoteSelector(MpeNoteSelector),
+    SetMpeHeldNoteIndex(u8),
+    SetChordMode(ChordMode),
+    SetChordTimeWindowMillis(u64),
+    SetChordNotes(Vec<U7>),
     SetOscAddressPattern(String),
     SetOscArgIndex(Option<u32>),
     SetOscArgTypeTag(OscTypeTag),
     SetOscArgIsRelative(bool),
     SetOscArgValueRange(Interval<f64>),
+    SetOscAdditionalArgIndexes(Vec<u32>),
     SetOscFeedbackArgs(Vec<String>),
     SetReaperSourceType(ReaperSourceType),
     SetTimerMillis(u64),
+    SetMusicalTimerTrigger(MusicalTimerTrigger),
+    SetMusicalTimerInterval(u32),
+    SetMusicalTimerBars(Vec<u32>),
+    SetMusicalTimerOnlyWhilePlaying(bool),
+    SetMusicalTimerOutput(MusicalTimerOutput),
+    SetAudioLevelInput(AudioLevelInput),
+    SetAudioLevelIndex(u32),
+    SetAudioLevelMeasurement(AudioLevelMeasurement),
+    SetAudioLevelAttackMillis(u64),
+    SetAudioLevelReleaseMillis(u64),
+    SetAudioLevelOnsetThresholdDb(i32),
     SetParameterIndex(CompartmentParamIndex),
+    SetSpeechVoice(Option<String>),
+    SetSpeechRate(Option<u32>),
+    SetSpeechQueuePolicy(Option<SpeechQueuePolicy>),
     SetKeystroke(Option<Keystroke>),
     SetControlElementType(VirtualControlElementType),
     SetControlElementId(VirtualControlElementId),
+    SetChordControlElementIds(Vec<VirtualControlElementId>),
 }
 
 #[derive(Eq, PartialEq)]
@@ -71,20 +103,45 @@ pub enum SourceProp {
     RawMidiPattern,
     MidiScriptKind,
     MidiScript,
+    MidiControlScript,
     DisplayType,
     DisplayId,
     Line,
+    MpeZone,
+    MpeMemberChannelCount,
+    MpeDimension,
+    MpeNoteSelector,
+    MpeHeldNoteIndex,
+    ChordMode,
+    ChordTimeWindowMillis,
+    ChordNotes,
     OscAddressPattern,
     OscArgIndex,
     OscArgTypeTag,
     OscArgIsRelative,
     OscArgValueRange,
+    OscAdditionalArgIndexes,
     OscFeedbackArgs,
     ReaperSourceType,
     ControlElementType,
     ControlElementId,
+    ChordControlElementIds,
     TimerMillis,
+    MusicalTimerTrigger,
+    MusicalTimerInterval,
+    MusicalTimerBars,
+    MusicalTimerOnlyWhilePlaying,
+    MusicalTimerOutput,
+    AudioLevelInput,
+    AudioLevelIndex,
+    AudioLevelMeasurement,
+    AudioLevelAttackMillis,
+    AudioLevelReleaseMillis,
+    AudioLevelOnsetThresholdDb,
     ParameterIndex,
+    SpeechVoice,
+    SpeechRate,
+    SpeechQueuePolicy,
     Keystroke,
 }
 
@@ -152,6 +209,10 @@ impl<'a> Change<'a> for SourceModel {
                 self.midi_script = v;
                 One(P::MidiScript)
             }
+            C::SetMidiControlScript(v) => {
+                self.midi_control_script = v;
+                One(P::MidiControlScript)
+            }
             C::SetDisplayType(v) => {
                 self.display_type = v;
                 One(P::DisplayType)
@@ -164,6 +225,38 @@ impl<'a> Change<'a> for SourceModel {
                 self.line = v;
                 One(P::Line)
             }
+            C::SetMpeZone(v) => {
+                self.mpe_zone = v;
+                One(P::MpeZone)
+            }
+            C::SetMpeMemberChannelCount(v) => {
+                self.mpe_member_channel_count = v.clamp(1, 15);
+                One(P::MpeMemberChannelCount)
+            }
+            C::SetMpeDimension(v) => {
+                self.mpe_dimension = v;
+                One(P::MpeDimension)
+            }
+            C::SetMpeNoteSelector(v) => {
+                self.mpe_note_selector = v;
+                One(P::MpeNoteSelector)
+            }
+            C::SetMpeHeldNoteIndex(v) => {
+                self.mpe_held_note_index = v;
+                One(P::MpeHeldNoteIndex)
+            }
+            C::SetChordMode(v) => {
+                self.chord_mode = v;
+                One(P::ChordMode)
+            }
+            C::SetChordTimeWindowMillis(v) => {
+                self.chord_time_window_millis = v;
+                One(P::ChordTimeWindowMillis)
+            }
+            C::SetChordNotes(v) => {
+                self.chord_notes = v;
+                One(P::ChordNotes)
+            }
             C::SetOscAddressPattern(v) => {
                 self.osc_address_pattern = v;
                 One(P::OscAddressPattern)
@@ -184,6 +277,10 @@ impl<'a> Change<'a> for SourceModel {
                 self.osc_arg_value_range = v;
                 One(P::OscArgValueRange)
             }
+            C::SetOscAdditionalArgIndexes(v) => {
+                self.osc_additional_arg_indexes = v;
+                One(P::OscAdditionalArgIndexes)
+            }
             C::SetOscFeedbackArgs(v) => {
                 self.osc_feedback_args = v;
                 One(P::OscFeedbackArgs)
@@ -200,14 +297,74 @@ impl<'a> Change<'a> for SourceModel {
                 self.control_element_id = v;
                 One(P::ControlElementId)
             }
+            C::SetChordControlElementIds(v) => {
+                self.chord_control_element_ids = v;
+                One(P::ChordControlElementIds)
+            }
             C::SetTimerMillis(v) => {
                 self.timer_millis = v;
                 One(P::TimerMillis)
             }
+            C::SetMusicalTimerTrigger(v) => {
+                self.musical_timer_settings.trigger = v;
+                One(P::MusicalTimerTrigger)
+            }
+            C::SetMusicalTimerInterval(v) => {
+                self.musical_timer_settings.interval = v.max(1);
+                One(P::MusicalTimerInterval)
+            }
+            C::SetMusicalTimerBars(v) => {
+                self.musical_timer_settings.bars = v;
+                One(P::MusicalTimerBars)
+            }
+            C::SetMusicalTimerOnlyWhilePlaying(v) => {
+                self.musical_timer_settings.only_while_playing = v;
+                One(P::MusicalTimerOnlyWhilePlaying)
+            }
+            C::SetMusicalTimerOutput(v) => {
+                self.musical_timer_settings.output = v;
+                One(P::MusicalTimerOutput)
+            }
+            C::SetAudioLevelInput(v) => {
+                self.audio_level_settings.input = v;
+                One(P::AudioLevelInput)
+            }
+            C::SetAudioLevelIndex(v) => {
+                self.audio_level_settings.index = v.max(1);
+                One(P::AudioLevelIndex)
+            }
+            C::SetAudioLevelMeasurement(v) => {
+                self.audio_level_settings.measurement = v;
+                One(P::AudioLevelMeasurement)
+            }
+            C::SetAudioLevelAttackMillis(v) => {
+                self.audio_level_settings.attack = Duration::from_millis(v);
+                One(P::AudioLevelAttackMillis)
+            }
+            C::SetAudioLevelReleaseMillis(v) => {
+                self.audio_level_settings.release = Duration::from_millis(v);
+                One(P::AudioLevelReleaseMillis)
+            }
+            C::SetAudioLevelOnsetThresholdDb(v) => {
+                self.audio_level_settings.onset_threshold_db = v.min(0);
+                One(P::AudioLevelOnsetThresholdDb)
+            }
             C::SetParameterIndex(v) => {
                 self.parameter_index = v;
                 One(P::ParameterIndex)
             }
+            C::SetSpeechVoice(v) => {
+                self.speech_settings.voice = v;
+                One(P::SpeechVoice)
+            }
+            C::SetSpeechRate(v) => {
+                self.speech_settings.rate = v.map(|r| r.clamp(MIN_SPEECH_RATE, MAX_SPEECH_RATE));
+                One(P::SpeechRate)
+            }
+            C::SetSpeechQueuePolicy(v) => {
+                self.speech_settings.queue_policy = v;
+                One(P::SpeechQueuePolicy)
+            }
             C::SetKeystroke(v) => {
                 self.keystroke = v;
                 One(P::Keystroke)
@@ -233,25 +390,43 @@ pub struct SourceModel {
     raw_midi_pattern: String,
     midi_script_kind: MidiScriptKind,
     midi_script: String,
+    midi_control_script: String,
     display_type: DisplayType,
     display_id: Option<u8>,
     line: Option<u8>,
+    mpe_zone: MpeZone,
+    mpe_member_channel_count: u8,
+    mpe_dimension: MpeDimension,
+    mpe_note_selector: MpeNoteSelector,
+    mpe_held_note_index: u8,
+    chord_notes: Vec<U7>,
+    // MIDI and virtual
+    chord_mode: ChordMode,
+    /// Zero means no time limit.
+    chord_time_window_millis: u64,
     // OSC
     osc_address_pattern: String,
     osc_arg_index: Option<u32>,
     osc_arg_type_tag: OscTypeTag,
     osc_arg_is_relative: bool,
     osc_arg_value_range: Interval<f64>,
+    /// Indexes of arguments which are bound in addition to the primary one.
+    osc_additional_arg_indexes: Vec<u32>,
     osc_feedback_args: Vec<String>,
     // REAPER
     reaper_source_type: ReaperSourceType,
     timer_millis: u64,
+    musical_timer_settings: MusicalTimerSettings,
+    audio_level_settings: AudioLevelSettings,
     parameter_index: CompartmentParamIndex,
+    speech_settings: SpeechSettingsOverride,
     // Key
     keystroke: Option<Keystroke>,
     // Virtual
     control_element_type: VirtualControlElementType,
     control_element_id: VirtualControlElementId,
+    /// Additional buttons which must be pressed together with the primary one.
+    chord_control_element_ids: Vec<VirtualControlElementId>,
 }
 
 impl SourceModel {
@@ -271,19 +446,33 @@ impl SourceModel {
             raw_midi_pattern: "".to_owned(),
             midi_script_kind: Default::default(),
             midi_script: "".to_owned(),
+            midi_control_script: "".to_owned(),
             display_type: Default::default(),
             display_id: Default::default(),
             line: None,
+            mpe_zone: Default::default(),
+            mpe_member_channel_count: DEFAULT_MPE_MEMBER_CHANNEL_COUNT,
+            mpe_dimension: Default::default(),
+            mpe_note_selector: Default::default(),
+            mpe_held_note_index: 0,
+            chord_notes: vec![],
+            chord_mode: Default::default(),
+            chord_time_window_millis: 0,
             osc_address_pattern: "".to_owned(),
             osc_arg_index: Some(0),
             osc_arg_type_tag: Default::default(),
             osc_arg_is_relative: false,
             osc_arg_value_range: DEFAULT_OSC_ARG_VALUE_RANGE,
+            osc_additional_arg_indexes: vec![],
             osc_feedback_args: vec![],
             reaper_source_type: Default::default(),
             timer_millis: Default::default(),
+            musical_timer_settings: Default::default(),
+            audio_level_settings: Default::default(),
             parameter_index: Default::default(),
+            speech_settings: Default::default(),
             keystroke: None,
+            chord_control_element_ids: vec![],
         }
     }
 
@@ -335,6 +524,10 @@ impl SourceModel {
         &self.midi_script
     }
 
+    pub fn midi_control_script(&self) -> &str {
+        &self.midi_control_script
+    }
+
     pub fn display_type(&self) -> DisplayType {
         self.display_type
     }
@@ -347,6 +540,50 @@ impl SourceModel {
         self.line
     }
 
+    pub fn mpe_zone(&self) -> MpeZone {
+        self.mpe_zone
+    }
+
+    pub fn mpe_member_channel_count(&self) -> u8 {
+        self.mpe_member_channel_count
+    }
+
+    pub fn mpe_dimension(&self) -> MpeDimension {
+        self.mpe_dimension
+    }
+
+    pub fn mpe_note_selector(&self) -> MpeNoteSelector {
+        self.mpe_note_selector
+    }
+
+    pub fn mpe_held_note_index(&self) -> u8 {
+        self.mpe_held_note_index
+    }
+
+    pub fn chord_mode(&self) -> ChordMode {
+        self.chord_mode
+    }
+
+    pub fn chord_time_window_millis(&self) -> u64 {
+        self.chord_time_window_millis
+    }
+
+    pub fn chord_notes(&self) -> &[U7] {
+        &self.chord_notes
+    }
+
+    pub fn chord_control_element_ids(&self) -> &[VirtualControlElementId] {
+        &self.chord_control_element_ids
+    }
+
+    /// Returns whether this is a virtual button source which needs other buttons to be pressed
+    /// at the same time.
+    pub fn is_virtual_chord(&self) -> bool {
+        self.category == SourceCategory::Virtual
+            && self.control_element_type == VirtualControlElementType::Button
+            && !self.chord_control_element_ids.is_empty()
+    }
+
     pub fn osc_address_pattern(&self) -> &str {
         &self.osc_address_pattern
     }
@@ -367,6 +604,10 @@ impl SourceModel {
         self.osc_arg_value_range
     }
 
+    pub fn osc_additional_arg_indexes(&self) -> &[u32] {
+        &self.osc_additional_arg_indexes
+    }
+
     pub fn osc_feedback_args(&self) -> &[String] {
         &self.osc_feedback_args
     }
@@ -387,6 +628,18 @@ impl SourceModel {
         self.timer_millis
     }
 
+    pub fn musical_timer_settings(&self) -> &MusicalTimerSettings {
+        &self.musical_timer_settings
+    }
+
+    pub fn audio_level_settings(&self) -> &AudioLevelSettings {
+        &self.audio_level_settings
+    }
+
+    pub fn speech_settings(&self) -> &SpeechSettingsOverride {
+        &self.speech_settings
+    }
+
     pub fn control_element_type(&self) -> VirtualControlElementType {
         self.control_element_type
     }
@@ -483,6 +736,7 @@ impl SourceModel {
                 self.category = SourceCategory::Virtual;
                 self.control_element_type = VirtualControlElementType::from_source(s);
                 self.control_element_id = s.control_element().id();
+                self.chord_control_element_ids = vec![];
             }
             Osc(s) => {
                 self.category = SourceCategory::Osc;
@@ -494,6 +748,8 @@ impl SourceModel {
                     .arg_descriptor()
                     .map(|d| d.is_relative())
                     .unwrap_or_default();
+                self.osc_additional_arg_indexes =
+                    s.additional_arg_descriptors().map(|d| d.index()).collect();
             }
             Reaper(s) => {
                 self.category = SourceCategory::Reaper;
@@ -503,7 +759,16 @@ impl SourceModel {
                     RealearnParameter(p) => {
                         self.parameter_index = p.parameter_index;
                     }
-                    MidiDeviceChanges | RealearnInstanceStart | Timer(_) | Speech(_) => {}
+                    MusicalTimer(s) => {
+                        self.musical_timer_settings = s.settings().clone();
+                    }
+                    AudioLevel(s) => {
+                        self.audio_level_settings = s.settings().clone();
+                    }
+                    Speech(s) => {
+                        self.speech_settings = s.settings().clone();
+                    }
+                    MidiDeviceChanges | RealearnInstanceStart | Timer(_) => {}
                 }
             }
             Never => {
@@ -513,6 +778,45 @@ impl SourceModel {
                 self.category = SourceCategory::Keyboard;
                 self.keystroke = Some(s.stroke());
             }
+            Mpe(s) => {
+                self.category = SourceCategory::Midi;
+                self.midi_source_type = MidiSourceType::Mpe;
+                self.mpe_zone = s.zone;
+                self.mpe_member_channel_count = s.member_channel_count;
+                self.mpe_dimension = s.dimension;
+                match s.note {
+                    MpeNote::LastTouched => {
+                        self.mpe_note_selector = MpeNoteSelector::LastTouched;
+                    }
+                    MpeNote::Held(i) => {
+                        self.mpe_note_selector = MpeNoteSelector::HeldNote;
+                        self.mpe_held_note_index = i;
+                    }
+                }
+            }
+            Chord(s) => {
+                match s.elements() {
+                    ChordElements::Notes {
+                        channel,
+                        key_numbers,
+                    } => {
+                        self.category = SourceCategory::Midi;
+                        self.midi_source_type = MidiSourceType::Chord;
+                        self.channel = *channel;
+                        self.chord_notes = key_numbers.clone();
+                    }
+                    ChordElements::VirtualButtons(ids) => {
+                        self.category = SourceCategory::Virtual;
+                        self.control_element_type = VirtualControlElementType::Button;
+                        if let Some((first, rest)) = ids.split_first() {
+                            self.control_element_id = *first;
+                            self.chord_control_element_ids = rest.to_vec();
+                        }
+                    }
+                }
+                self.chord_mode = s.mode();
+                self.chord_time_window_millis = s.time_window().as_millis() as _;
+            }
         };
         Some(Affected::Multiple)
     }
@@ -534,7 +838,7 @@ impl SourceModel {
             CompoundMappingSource::Midi(s) => s.possible_detailed_characters(),
             CompoundMappingSource::Osc(s) => s.possible_detailed_characters(),
             CompoundMappingSource::Virtual(s) => match s.control_element() {
-                VirtualControlElement::Multi(_) => vec![
+                VirtualControlElement::Multi(_) | VirtualControlElement::Xy(_) => vec![
                     DetailedSourceCharacter::MomentaryVelocitySensitiveButton,
                     DetailedSourceCharacter::MomentaryOnOffButton,
                     DetailedSourceCharacter::Trigger,
@@ -556,6 +860,8 @@ impl SourceModel {
                 DetailedSourceCharacter::Relative,
             ],
             CompoundMappingSource::Key(_) => vec![DetailedSourceCharacter::MomentaryOnOffButton],
+            CompoundMappingSource::Mpe(s) => s.possible_detailed_characters(),
+            CompoundMappingSource::Chord(s) => s.possible_detailed_characters(),
         }
     }
 
@@ -626,7 +932,7 @@ impl SourceModel {
                     },
                     Script => MidiSource::Script {
                         script: {
-                            match self.midi_script_kind {
+                            let feedback_script = match self.midi_script_kind {
                                 MidiScriptKind::Eel => {
                                     EelMidiSourceScript::compile(&self.midi_script)
                                         .ok()
@@ -638,21 +944,52 @@ impl SourceModel {
                                         .ok()
                                         .map(FlexibleMidiSourceScript::Lua)
                                 }
-                            }
+                            };
+                            let control_script = match self.midi_script_kind {
+                                MidiScriptKind::Eel => {
+                                    EelMidiSourceControlScript::compile(&self.midi_control_script)
+                                        .ok()
+                                        .map(FlexibleMidiSourceControlScript::Eel)
+                                }
+                                MidiScriptKind::Lua => {
+                                    LuaMidiSourceControlScript::compile(&self.midi_control_script)
+                                        .ok()
+                                        .map(FlexibleMidiSourceControlScript::Lua)
+                                }
+                            };
+                            MidiSourceScripts::new(feedback_script, control_script)
                         },
                     },
                     Display => MidiSource::Display {
                         spec: self.display_spec(),
                     },
+                    Mpe => return Some(CompoundMappingSource::Mpe(self.create_mpe_source())),
+                    Chord => {
+                        let elements = ChordElements::Notes {
+                            channel,
+                            key_numbers: self.chord_notes.clone(),
+                        };
+                        return Some(CompoundMappingSource::Chord(
+                            self.create_chord_source(elements),
+                        ));
+                    }
                 };
                 CompoundMappingSource::Midi(midi_source)
             }
             Virtual => {
-                let virtual_source = VirtualSource::new(self.create_control_element());
-                CompoundMappingSource::Virtual(virtual_source)
+                if self.is_virtual_chord() {
+                    let ids = std::iter::once(self.control_element_id)
+                        .chain(self.chord_control_element_ids.iter().copied())
+                        .collect();
+                    let elements = ChordElements::VirtualButtons(ids);
+                    CompoundMappingSource::Chord(self.create_chord_source(elements))
+                } else {
+                    let virtual_source = VirtualSource::new(self.create_control_element());
+                    CompoundMappingSource::Virtual(virtual_source)
+                }
             }
             Osc => {
-                let osc_source = OscSource::new(
+                let primary_source = helgoboss_learn::OscSource::new(
                     self.osc_address_pattern.clone(),
                     self.osc_arg_descriptor(),
                     self.osc_feedback_args
@@ -660,6 +997,8 @@ impl SourceModel {
                         .map(|prop_string| prop_string.parse().unwrap_or_default())
                         .collect(),
                 );
+                let osc_source =
+                    OscSource::new(primary_source, self.osc_additional_arg_descriptors());
                 CompoundMappingSource::Osc(osc_source)
             }
             Reaper => {
@@ -668,10 +1007,16 @@ impl SourceModel {
                     MidiDeviceChanges => ReaperSource::MidiDeviceChanges,
                     RealearnInstanceStart => ReaperSource::RealearnInstanceStart,
                     Timer => ReaperSource::Timer(self.create_timer_source()),
+                    MusicalTimer => ReaperSource::MusicalTimer(MusicalTimerSource::new(
+                        self.musical_timer_settings.clone(),
+                    )),
+                    AudioLevel => ReaperSource::AudioLevel(AudioLevelSource::new(
+                        self.audio_level_settings.clone(),
+                    )),
                     RealearnParameter => {
                         ReaperSource::RealearnParameter(self.create_realearn_parameter_source())
                     }
-                    Speech => ReaperSource::Speech(SpeechSource::new()),
+                    Speech => ReaperSource::Speech(SpeechSource::new(self.speech_settings.clone())),
                 };
                 CompoundMappingSource::Reaper(reaper_source)
             }
@@ -685,6 +1030,26 @@ impl SourceModel {
         Some(KeySource::new(self.keystroke?))
     }
 
+    fn create_mpe_source(&self) -> MpeSource {
+        MpeSource {
+            zone: self.mpe_zone,
+            member_channel_count: self.mpe_member_channel_count,
+            dimension: self.mpe_dimension,
+            note: match self.mpe_note_selector {
+                MpeNoteSelector::LastTouched => MpeNote::LastTouched,
+                MpeNoteSelector::HeldNote => MpeNote::Held(self.mpe_held_note_index),
+            },
+        }
+    }
+
+    fn create_chord_source(&self, elements: ChordElements) -> ChordSource {
+        ChordSource::new(
+            elements,
+            self.chord_mode,
+            Duration::from_millis(self.chord_time_window_millis),
+        )
+    }
+
     fn create_timer_source(&self) -> TimerSource {
         TimerSource::new(Duration::from_millis(self.timer_millis))
     }
@@ -758,6 +1123,18 @@ impl SourceModel {
         Some(arg_desc)
     }
 
+    /// Additional arguments share type, relativity and value range with the primary argument.
+    fn osc_additional_arg_descriptors(&self) -> impl Iterator<Item = OscArgDescriptor> + '_ {
+        self.osc_additional_arg_indexes.iter().map(|i| {
+            OscArgDescriptor::new(
+                *i,
+                self.osc_arg_type_tag,
+                self.osc_arg_is_relative,
+                self.osc_arg_value_range,
+            )
+        })
+    }
+
     pub fn supports_type(&self) -> bool {
         use SourceCategory::*;
         matches!(self.category, Midi | Virtual | Reaper)
@@ -889,12 +1266,44 @@ impl Display for SourceModel {
                     vec![t.to_string().into(), self.channel_label(), line_3, line_4]
                 }
                 t @ MidiSourceType::Display => vec![t.to_string().into()],
+                MidiSourceType::Mpe => {
+                    let note_label: Cow<str> = match self.mpe_note_selector {
+                        MpeNoteSelector::LastTouched => "Last touched note".into(),
+                        MpeNoteSelector::HeldNote => {
+                            format!("Held note {}", self.mpe_held_note_index + 1).into()
+                        }
+                    };
+                    vec![
+                        "MPE".into(),
+                        self.mpe_dimension.to_string().into(),
+                        note_label,
+                    ]
+                }
+                MidiSourceType::Chord => {
+                    vec![
+                        format!("Chord ({})", self.chord_mode).into(),
+                        self.channel_label(),
+                        format!("Notes {}", format_chord_notes(&self.chord_notes)).into(),
+                    ]
+                }
                 t => vec![t.to_string().into(), self.channel_label()],
             },
-            Virtual => vec![
-                "Virtual".into(),
-                self.create_control_element().to_string().into(),
-            ],
+            Virtual => {
+                let mut lines = vec![
+                    "Virtual".into(),
+                    self.create_control_element().to_string().into(),
+                ];
+                if self.is_virtual_chord() {
+                    lines.push(
+                        format!(
+                            "+ {}",
+                            format_chord_control_element_ids(&self.chord_control_element_ids)
+                        )
+                        .into(),
+                    );
+                }
+                lines
+            }
             Osc => vec!["OSC".into(), (&self.osc_address_pattern).into()],
             Reaper => {
                 let type_label = self.reaper_source_type.to_string().into();
@@ -902,6 +1311,36 @@ impl Display for SourceModel {
                     ReaperSourceType::Timer => {
                         vec![type_label, format!("{} ms", self.timer_millis).into()]
                     }
+                    ReaperSourceType::MusicalTimer => {
+                        let settings = &self.musical_timer_settings;
+                        let schedule = match settings.trigger {
+                            MusicalTimerTrigger::EveryNBars => {
+                                format!("Every {} bar(s)", settings.interval)
+                            }
+                            MusicalTimerTrigger::EveryNBeats => {
+                                format!("Every {} beat(s)", settings.interval)
+                            }
+                            MusicalTimerTrigger::SpecificBars => {
+                                format!("Bars {}", format_musical_timer_bars(&settings.bars))
+                            }
+                            t => t.to_string(),
+                        };
+                        vec![type_label, schedule.into()]
+                    }
+                    ReaperSourceType::AudioLevel => {
+                        let settings = &self.audio_level_settings;
+                        let input = match settings.input {
+                            AudioLevelInput::TrackByPosition => {
+                                format!("Track #{}", settings.index)
+                            }
+                            AudioLevelInput::HardwareInput => format!("Input {}", settings.index),
+                            i => i.to_string(),
+                        };
+                        vec![
+                            type_label,
+                            format!("{} {}", input, settings.measurement).into(),
+                        ]
+                    }
                     ReaperSourceType::RealearnParameter => {
                         vec![
                             type_label,
@@ -929,6 +1368,9 @@ impl Display for SourceModel {
 
 pub const KEY_UNDEFINED_LABEL: &str = "<Key undefined>";
 
+/// Most MPE controllers use all 15 member channels of the lower zone by default.
+pub const DEFAULT_MPE_MEMBER_CHANNEL_COUNT: u8 = 15;
+
 #[derive(
     Clone,
     Copy,
@@ -1039,6 +1481,10 @@ pub enum MidiSourceType {
     Display = 12,
     #[display(fmt = "Specific program change")]
     SpecificProgramChange = 13,
+    #[display(fmt = "MPE (per-note dimension)")]
+    Mpe = 14,
+    #[display(fmt = "Chord (note combination)")]
+    Chord = 15,
 }
 
 impl Default for MidiSourceType {
@@ -1093,6 +1539,7 @@ impl MidiSourceType {
                 | PitchBendChangeValue
                 | ProgramChangeNumber
                 | SpecificProgramChange
+                | Chord
         )
     }
 
@@ -1133,7 +1580,7 @@ impl MidiSourceType {
 
     pub fn supports_feedback(self) -> bool {
         use MidiSourceType::*;
-        !matches!(self, ClockTempo | ClockTransport)
+        !matches!(self, ClockTempo | ClockTransport | Mpe | Chord)
     }
 }
 
@@ -1159,6 +1606,9 @@ pub enum VirtualControlElementType {
     #[serde(rename = "button")]
     #[display(fmt = "Button")]
     Button,
+    #[serde(rename = "xy")]
+    #[display(fmt = "XY")]
+    Xy,
 }
 
 impl Default for VirtualControlElementType {
@@ -1173,6 +1623,7 @@ impl VirtualControlElementType {
         match source.control_element() {
             Multi(_) => VirtualControlElementType::Multi,
             Button(_) => VirtualControlElementType::Button,
+            Xy(_) => VirtualControlElementType::Xy,
         }
     }
 
@@ -1181,6 +1632,7 @@ impl VirtualControlElementType {
         match target.control_element() {
             Multi(_) => VirtualControlElementType::Multi,
             Button(_) => VirtualControlElementType::Button,
+            Xy(_) => VirtualControlElementType::Xy,
         }
     }
 
@@ -1189,6 +1641,7 @@ impl VirtualControlElementType {
         match self {
             Multi => VirtualControlElement::Multi(id),
             Button => VirtualControlElement::Button(id),
+            Xy => VirtualControlElement::Xy(id),
         }
     }
 }
@@ -1218,11 +1671,17 @@ pub enum ReaperSourceType {
     #[serde(rename = "timer")]
     #[display(fmt = "Timer")]
     Timer,
+    #[serde(rename = "musical-timer")]
+    #[display(fmt = "Musical timer")]
+    MusicalTimer,
+    #[serde(rename = "audio-level")]
+    #[display(fmt = "Audio level")]
+    AudioLevel,
     #[serde(rename = "realearn-parameter")]
     #[display(fmt = "ReaLearn parameter")]
     RealearnParameter,
     #[serde(rename = "speech")]
-    #[display(fmt = "Speech (feedback only, no Linux)")]
+    #[display(fmt = "Speech (feedback only)")]
     Speech,
 }
 
@@ -1239,6 +1698,8 @@ impl ReaperSourceType {
             MidiDeviceChanges => Self::MidiDeviceChanges,
             RealearnInstanceStart => Self::RealearnInstanceStart,
             Timer(_) => Self::Timer,
+            MusicalTimer(_) => Self::MusicalTimer,
+            AudioLevel(_) => Self::AudioLevel,
             RealearnParameter(_) => Self::RealearnParameter,
             Speech(_) => Self::Speech,
         }
@@ -1247,7 +1708,12 @@ impl ReaperSourceType {
     pub fn supports_control(self) -> bool {
         use ReaperSourceType::*;
         match self {
-            MidiDeviceChanges | RealearnInstanceStart | Timer | RealearnParameter => true,
+            MidiDeviceChanges
+            | RealearnInstanceStart
+            | Timer
+            | MusicalTimer
+            | AudioLevel
+            | RealearnParameter => true,
             Speech => false,
         }
     }
@@ -1255,12 +1721,44 @@ impl ReaperSourceType {
     pub fn supports_feedback(self) -> bool {
         use ReaperSourceType::*;
         match self {
-            MidiDeviceChanges | RealearnInstanceStart | Timer | RealearnParameter => false,
+            MidiDeviceChanges
+            | RealearnInstanceStart
+            | Timer
+            | MusicalTimer
+            | AudioLevel
+            | RealearnParameter => false,
             Speech => true,
         }
     }
 }
 
+/// Parses bar numbers separated by commas or whitespace, ignoring anything invalid.
+pub fn parse_musical_timer_bars(text: &str) -> Vec<u32> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|s| s.parse().ok())
+        .filter(|bar| *bar > 0)
+        .collect()
+}
+
+pub fn format_musical_timer_bars(bars: &[u32]) -> String {
+    itertools::join(bars.iter(), ", ")
+}
+
+/// Parses attack and release time in milliseconds, separated by a slash, comma or whitespace.
+pub fn parse_audio_level_attack_release(text: &str) -> Option<(u64, u64)> {
+    let mut numbers = text
+        .split(|c: char| c == '/' || c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().ok());
+    let attack = numbers.next()??;
+    let release = numbers.next()??;
+    Some((attack, release))
+}
+
+pub fn format_audio_level_attack_release(attack: Duration, release: Duration) -> String {
+    format!("{} / {}", attack.as_millis(), release.as_millis())
+}
+
 pub fn parse_osc_feedback_args(text: &str) -> Vec<String> {
     text.split_whitespace().map(|s| s.to_owned()).collect()
 }
@@ -1269,6 +1767,42 @@ pub fn format_osc_feedback_args(args: &[String]) -> String {
     itertools::join(args.iter(), " ")
 }
 
+/// Parses one-based argument positions (as displayed in the UI) into argument indexes.
+pub fn parse_osc_additional_arg_indexes(text: &str) -> Vec<u32> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|s| s.parse::<u32>().ok())
+        .filter(|pos| *pos > 0)
+        .map(|pos| pos - 1)
+        .collect()
+}
+
+/// Formats argument indexes as one-based argument positions (as displayed in the UI).
+pub fn format_osc_additional_arg_indexes(indexes: &[u32]) -> String {
+    itertools::join(indexes.iter().map(|i| i + 1), " ")
+}
+
+pub fn parse_chord_notes(text: &str) -> Vec<U7> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|s| s.parse::<u8>().ok())
+        .filter_map(|n| U7::try_from(n).ok())
+        .collect()
+}
+
+pub fn format_chord_notes(notes: &[U7]) -> String {
+    itertools::join(notes.iter().map(|n| n.get()), " ")
+}
+
+pub fn parse_chord_control_element_ids(text: &str) -> Vec<VirtualControlElementId> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+pub fn format_chord_control_element_ids(ids: &[VirtualControlElementId]) -> String {
+    itertools::join(ids.iter(), " ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
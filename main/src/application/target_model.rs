@@ -19,39 +19,45 @@ use crate::application::{
 use crate::domain::{
     find_bookmark, get_fx_name, get_fx_params, get_non_present_virtual_route_label,
     get_non_present_virtual_track_label, get_track_routes, ActionInvocationType, AnyOnParameter,
-    Compartment, CompoundMappingTarget, Exclusivity, ExpressionEvaluator, ExtendedProcessorContext,
-    FeedbackResolution, FxDescriptor, FxDisplayType, FxParameterDescriptor, GroupId,
-    MappingSnapshotId, MouseActionType, OscDeviceId, PotFilterItemsTargetSettings,
-    ProcessorContext, RealearnTarget, ReaperTarget, ReaperTargetType, SeekOptions,
-    SendMidiDestination, SoloBehavior, Tag, TagScope, TouchedRouteParameterType,
-    TouchedTrackParameterType, TrackDescriptor, TrackExclusivity, TrackGangBehavior,
-    TrackRouteDescriptor, TrackRouteSelector, TrackRouteType, TransportAction,
+    Compartment, CompoundMappingTarget, EnvelopeDescriptor, Exclusivity, ExpressionEvaluator,
+    ExtendedProcessorContext, FeedbackResolution, FxDescriptor, FxDisplayType,
+    FxParameterDescriptor, GroupId, ItemDescriptor, MappingSnapshotId, MouseActionType,
+    OscDeviceId, PotFilterItemsTargetSettings, ProcessorContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, SeekOptions, SendMidiDestination, SoloBehavior, Tag, TagScope,
+    TouchedRouteParameterType, TouchedTrackParameterType, TrackDescriptor, TrackExclusivity,
+    TrackGangBehavior, TrackRouteDescriptor, TrackRouteSelector, TrackRouteType, TransportAction,
     UnresolvedActionTarget, UnresolvedAllTrackFxEnableTarget, UnresolvedAnyOnTarget,
-    UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget,
+    UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseAutomationItemsTarget,
+    UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget, UnresolvedBrowseItemsTarget,
     UnresolvedBrowsePotFilterItemsTarget, UnresolvedBrowsePotPresetsTarget,
     UnresolvedBrowseTracksTarget, UnresolvedClipColumnTarget, UnresolvedClipManagementTarget,
     UnresolvedClipMatrixTarget, UnresolvedClipRowTarget, UnresolvedClipSeekTarget,
     UnresolvedClipTransportTarget, UnresolvedClipVolumeTarget, UnresolvedCompoundMappingTarget,
     UnresolvedDummyTarget, UnresolvedEnableInstancesTarget, UnresolvedEnableMappingsTarget,
+    UnresolvedEnvelopeArmTarget, UnresolvedEnvelopeShowTarget, UnresolvedEnvelopeWriteValueTarget,
     UnresolvedFxEnableTarget, UnresolvedFxOnlineTarget, UnresolvedFxOpenTarget,
     UnresolvedFxParameterTarget, UnresolvedFxParameterTouchStateTarget, UnresolvedFxPresetTarget,
-    UnresolvedFxToolTarget, UnresolvedGoToBookmarkTarget, UnresolvedLastTouchedTarget,
+    UnresolvedFxToolTarget, UnresolvedGoToBookmarkTarget, UnresolvedItemBrowseTakesTarget,
+    UnresolvedItemLockTarget, UnresolvedItemMuteTarget, UnresolvedItemPositionTarget,
+    UnresolvedItemSelectionTarget, UnresolvedItemVolumeTarget, UnresolvedLastTouchedTarget,
     UnresolvedLoadFxSnapshotTarget, UnresolvedLoadMappingSnapshotTarget,
-    UnresolvedLoadPotPresetTarget, UnresolvedMidiSendTarget, UnresolvedMouseTarget,
-    UnresolvedOscSendTarget, UnresolvedPlayrateTarget, UnresolvedPreviewPotPresetTarget,
-    UnresolvedReaperTarget, UnresolvedRouteAutomationModeTarget, UnresolvedRouteMonoTarget,
-    UnresolvedRouteMuteTarget, UnresolvedRoutePanTarget, UnresolvedRoutePhaseTarget,
-    UnresolvedRouteTouchStateTarget, UnresolvedRouteVolumeTarget, UnresolvedSeekTarget,
-    UnresolvedTakeMappingSnapshotTarget, UnresolvedTempoTarget, UnresolvedTrackArmTarget,
+    UnresolvedLoadPotPresetTarget, UnresolvedMarkerManagementTarget, UnresolvedMidiSendTarget,
+    UnresolvedMouseTarget, UnresolvedOscSendTarget, UnresolvedPlayrateTarget,
+    UnresolvedPreviewPotPresetTarget, UnresolvedReaperTarget, UnresolvedRouteAutomationModeTarget,
+    UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget, UnresolvedRoutePanTarget,
+    UnresolvedRoutePhaseTarget, UnresolvedRouteTouchStateTarget, UnresolvedRouteVolumeTarget,
+    UnresolvedSeekTarget, UnresolvedTakeFxParameterTarget, UnresolvedTakeMappingSnapshotTarget,
+    UnresolvedTakePitchTarget, UnresolvedTakePlayrateTarget, UnresolvedTakeVolumeTarget,
+    UnresolvedTempoTarget, UnresolvedTimeRangeTarget, UnresolvedTrackArmTarget,
     UnresolvedTrackAutomationModeTarget, UnresolvedTrackMonitoringModeTarget,
     UnresolvedTrackMuteTarget, UnresolvedTrackPanTarget, UnresolvedTrackParentSendTarget,
     UnresolvedTrackPeakTarget, UnresolvedTrackPhaseTarget, UnresolvedTrackSelectionTarget,
     UnresolvedTrackShowTarget, UnresolvedTrackSoloTarget, UnresolvedTrackToolTarget,
     UnresolvedTrackTouchStateTarget, UnresolvedTrackVolumeTarget, UnresolvedTrackWidthTarget,
     UnresolvedTransportTarget, VirtualChainFx, VirtualClipColumn, VirtualClipRow, VirtualClipSlot,
-    VirtualControlElement, VirtualControlElementId, VirtualFx, VirtualFxParameter,
+    VirtualControlElement, VirtualControlElementId, VirtualFx, VirtualFxParameter, VirtualItem,
     VirtualMappingSnapshotIdForLoad, VirtualMappingSnapshotIdForTake, VirtualTarget, VirtualTrack,
-    VirtualTrackRoute,
+    VirtualTrackRoute, XyAxis,
 };
 use serde_repr::*;
 use std::borrow::Cow;
@@ -64,9 +70,10 @@ use realearn_api::persistence::{
     Axis, BrowseTracksMode, ClipColumnAction, ClipColumnDescriptor, ClipColumnTrackContext,
     ClipManagementAction, ClipMatrixAction, ClipRowAction, ClipRowDescriptor, ClipSlotDescriptor,
     ClipTransportAction, FxChainDescriptor, FxDescriptorCommons, FxToolAction,
-    MappingSnapshotDescForLoad, MappingSnapshotDescForTake, MonitoringMode, MouseAction,
-    MouseButton, PotFilterItemKind, SeekBehavior, TrackDescriptorCommons, TrackFxChain, TrackScope,
-    TrackToolAction,
+    MappingSnapshotDescForLoad, MappingSnapshotDescForTake, MarkerManagementAction, MonitoringMode,
+    MouseAction, MouseButton, PotFilterItemKind, RouteEnvelopeKind, SeekBehavior, TimeRangeAction,
+    TimeRangeKind, TimeRangeQuantization, TrackDescriptorCommons, TrackEnvelopeKind, TrackFxChain,
+    TrackScope, TrackToolAction,
 };
 use reaper_medium::{
     AutomationMode, BookmarkId, GlobalAutomationModeOverride, InputMonitoringMode, TrackArea,
@@ -83,6 +90,7 @@ pub enum TargetCommand {
     SetUnit(TargetUnit),
     SetControlElementType(VirtualControlElementType),
     SetControlElementId(VirtualControlElementId),
+    SetControlElementAxis(XyAxis),
     SetTargetType(ReaperTargetType),
     SetAction(Option<Action>),
     SetActionInvocationType(ActionInvocationType),
@@ -107,6 +115,14 @@ pub enum TargetCommand {
     SetRouteIndex(u32),
     SetRouteName(String),
     SetRouteExpression(String),
+    SetItemType(VirtualItemType),
+    SetItemIndex(u32),
+    SetItemExpression(String),
+    SetTakeFxIndex(u32),
+    SetTakeFxParamIndex(u32),
+    SetEnvelopeType(EnvelopeType),
+    SetTrackEnvelopeKind(TrackEnvelopeKind),
+    SetRouteEnvelopeKind(RouteEnvelopeKind),
     SetSeekBehavior(SeekBehavior),
     SetSoloBehavior(SoloBehavior),
     SetTrackExclusivity(TrackExclusivity),
@@ -136,6 +152,14 @@ pub enum TargetCommand {
     SetFxDisplayType(FxDisplayType),
     SetScrollArrangeView(bool),
     SetScrollMixer(bool),
+    SetMoveEditCursor(bool),
+    SetTimeRangeKind(TimeRangeKind),
+    SetTimeRangeAction(TimeRangeAction),
+    SetTimeRangeQuantization(TimeRangeQuantization),
+    SetTimeRangeRegionIndex(u32),
+    SetTimeRangeBarCount(u32),
+    SetMarkerManagementAction(MarkerManagementAction),
+    SetMarkerName(String),
     SetRawMidiPattern(String),
     SetSendMidiDestination(SendMidiDestination),
     SetOscAddressPattern(String),
@@ -176,6 +200,7 @@ pub enum TargetProp {
     Unit,
     ControlElementType,
     ControlElementId,
+    ControlElementAxis,
     TargetType,
     Action,
     ActionInvocationType,
@@ -204,6 +229,14 @@ pub enum TargetProp {
     RouteIndex,
     RouteName,
     RouteExpression,
+    ItemType,
+    ItemIndex,
+    ItemExpression,
+    TakeFxIndex,
+    TakeFxParamIndex,
+    EnvelopeType,
+    TrackEnvelopeKind,
+    RouteEnvelopeKind,
     SoloBehavior,
     SeekBehavior,
     TrackExclusivity,
@@ -233,6 +266,14 @@ pub enum TargetProp {
     FxDisplayType,
     ScrollArrangeView,
     ScrollMixer,
+    MoveEditCursor,
+    TimeRangeKind,
+    TimeRangeAction,
+    TimeRangeQuantization,
+    TimeRangeRegionIndex,
+    TimeRangeBarCount,
+    MarkerManagementAction,
+    MarkerName,
     RawMidiPattern,
     SendMidiDestination,
     OscAddressPattern,
@@ -299,6 +340,10 @@ impl<'a> Change<'a> for TargetModel {
                 self.control_element_id = v;
                 One(P::ControlElementId)
             }
+            C::SetControlElementAxis(v) => {
+                self.control_element_axis = v;
+                One(P::ControlElementAxis)
+            }
             C::SetTargetType(v) => {
                 self.r#type = v;
                 One(P::TargetType)
@@ -395,6 +440,38 @@ impl<'a> Change<'a> for TargetModel {
                 self.route_expression = v;
                 One(P::RouteExpression)
             }
+            C::SetItemType(v) => {
+                self.item_type = v;
+                One(P::ItemType)
+            }
+            C::SetItemIndex(v) => {
+                self.item_index = v;
+                One(P::ItemIndex)
+            }
+            C::SetItemExpression(v) => {
+                self.item_expression = v;
+                One(P::ItemExpression)
+            }
+            C::SetTakeFxIndex(v) => {
+                self.take_fx_index = v;
+                One(P::TakeFxIndex)
+            }
+            C::SetTakeFxParamIndex(v) => {
+                self.take_fx_param_index = v;
+                One(P::TakeFxParamIndex)
+            }
+            C::SetEnvelopeType(v) => {
+                self.envelope_type = v;
+                One(P::EnvelopeType)
+            }
+            C::SetTrackEnvelopeKind(v) => {
+                self.track_envelope_kind = v;
+                One(P::TrackEnvelopeKind)
+            }
+            C::SetRouteEnvelopeKind(v) => {
+                self.route_envelope_kind = v;
+                One(P::RouteEnvelopeKind)
+            }
             C::SetSoloBehavior(v) => {
                 self.solo_behavior = v;
                 One(P::SoloBehavior)
@@ -511,6 +588,38 @@ impl<'a> Change<'a> for TargetModel {
                 self.scroll_mixer = v;
                 One(P::ScrollMixer)
             }
+            C::SetMoveEditCursor(v) => {
+                self.move_edit_cursor = v;
+                One(P::MoveEditCursor)
+            }
+            C::SetTimeRangeKind(v) => {
+                self.time_range_kind = v;
+                One(P::TimeRangeKind)
+            }
+            C::SetTimeRangeAction(v) => {
+                self.time_range_action = v;
+                One(P::TimeRangeAction)
+            }
+            C::SetTimeRangeQuantization(v) => {
+                self.time_range_quantization = v;
+                One(P::TimeRangeQuantization)
+            }
+            C::SetTimeRangeRegionIndex(v) => {
+                self.time_range_region_index = v;
+                One(P::TimeRangeRegionIndex)
+            }
+            C::SetTimeRangeBarCount(v) => {
+                self.time_range_bar_count = v.max(1);
+                One(P::TimeRangeBarCount)
+            }
+            C::SetMarkerManagementAction(v) => {
+                self.marker_management_action = v;
+                One(P::MarkerManagementAction)
+            }
+            C::SetMarkerName(v) => {
+                self.marker_name = v;
+                One(P::MarkerName)
+            }
             C::SetRawMidiPattern(v) => {
                 self.raw_midi_pattern = v;
                 One(P::RawMidiPattern)
@@ -653,6 +762,7 @@ pub struct TargetModel {
     // # For virtual targets
     control_element_type: VirtualControlElementType,
     control_element_id: VirtualControlElementId,
+    control_element_axis: XyAxis,
     // # For REAPER targets
     // TODO-low Rename this to reaper_target_type
     r#type: ReaperTargetType,
@@ -695,6 +805,16 @@ pub struct TargetModel {
     route_name: String,
     route_expression: String,
     touched_route_parameter_type: TouchedRouteParameterType,
+    // # For item and take targets
+    item_type: VirtualItemType,
+    item_index: u32,
+    item_expression: String,
+    take_fx_index: u32,
+    take_fx_param_index: u32,
+    // # For envelope targets
+    envelope_type: EnvelopeType,
+    track_envelope_kind: TrackEnvelopeKind,
+    route_envelope_kind: RouteEnvelopeKind,
     // # For track solo targets
     solo_behavior: SoloBehavior,
     // # For seek and goto bookmark targets
@@ -735,6 +855,17 @@ pub struct TargetModel {
     // # For track selection related targets
     scroll_arrange_view: bool,
     scroll_mixer: bool,
+    // # For Browse items target
+    move_edit_cursor: bool,
+    // # For time range target
+    time_range_kind: TimeRangeKind,
+    time_range_action: TimeRangeAction,
+    time_range_quantization: TimeRangeQuantization,
+    time_range_region_index: u32,
+    time_range_bar_count: u32,
+    // # For marker management target
+    marker_management_action: MarkerManagementAction,
+    marker_name: String,
     // # For Send MIDI target
     raw_midi_pattern: String,
     send_midi_destination: SendMidiDestination,
@@ -782,6 +913,7 @@ impl Default for TargetModel {
             unit: Default::default(),
             control_element_type: VirtualControlElementType::default(),
             control_element_id: Default::default(),
+            control_element_axis: Default::default(),
             r#type: ReaperTargetType::Dummy,
             action: None,
             action_invocation_type: ActionInvocationType::default(),
@@ -811,6 +943,14 @@ impl Default for TargetModel {
             route_name: Default::default(),
             route_expression: Default::default(),
             touched_route_parameter_type: Default::default(),
+            item_type: Default::default(),
+            item_index: 0,
+            item_expression: Default::default(),
+            take_fx_index: 0,
+            take_fx_param_index: 0,
+            envelope_type: Default::default(),
+            track_envelope_kind: Default::default(),
+            route_envelope_kind: Default::default(),
             solo_behavior: Default::default(),
             seek_behavior: Default::default(),
             track_exclusivity: Default::default(),
@@ -835,6 +975,14 @@ impl Default for TargetModel {
             fx_display_type: Default::default(),
             scroll_arrange_view: false,
             scroll_mixer: false,
+            move_edit_cursor: false,
+            time_range_kind: Default::default(),
+            time_range_action: Default::default(),
+            time_range_quantization: Default::default(),
+            time_range_region_index: 0,
+            time_range_bar_count: 1,
+            marker_management_action: Default::default(),
+            marker_name: Default::default(),
             raw_midi_pattern: Default::default(),
             send_midi_destination: Default::default(),
             osc_address_pattern: "".to_owned(),
@@ -893,6 +1041,10 @@ impl TargetModel {
         self.control_element_id
     }
 
+    pub fn control_element_axis(&self) -> XyAxis {
+        self.control_element_axis
+    }
+
     pub fn target_type(&self) -> ReaperTargetType {
         self.r#type
     }
@@ -997,6 +1149,38 @@ impl TargetModel {
         &self.route_expression
     }
 
+    pub fn item_type(&self) -> VirtualItemType {
+        self.item_type
+    }
+
+    pub fn item_index(&self) -> u32 {
+        self.item_index
+    }
+
+    pub fn item_expression(&self) -> &str {
+        &self.item_expression
+    }
+
+    pub fn take_fx_index(&self) -> u32 {
+        self.take_fx_index
+    }
+
+    pub fn take_fx_param_index(&self) -> u32 {
+        self.take_fx_param_index
+    }
+
+    pub fn envelope_type(&self) -> EnvelopeType {
+        self.envelope_type
+    }
+
+    pub fn track_envelope_kind(&self) -> TrackEnvelopeKind {
+        self.track_envelope_kind
+    }
+
+    pub fn route_envelope_kind(&self) -> RouteEnvelopeKind {
+        self.route_envelope_kind
+    }
+
     pub fn solo_behavior(&self) -> SoloBehavior {
         self.solo_behavior
     }
@@ -1129,6 +1313,38 @@ impl TargetModel {
         self.scroll_mixer
     }
 
+    pub fn move_edit_cursor(&self) -> bool {
+        self.move_edit_cursor
+    }
+
+    pub fn time_range_kind(&self) -> TimeRangeKind {
+        self.time_range_kind
+    }
+
+    pub fn time_range_action(&self) -> TimeRangeAction {
+        self.time_range_action
+    }
+
+    pub fn time_range_quantization(&self) -> TimeRangeQuantization {
+        self.time_range_quantization
+    }
+
+    pub fn time_range_region_index(&self) -> u32 {
+        self.time_range_region_index
+    }
+
+    pub fn time_range_bar_count(&self) -> u32 {
+        self.time_range_bar_count
+    }
+
+    pub fn marker_management_action(&self) -> MarkerManagementAction {
+        self.marker_management_action
+    }
+
+    pub fn marker_name(&self) -> &str {
+        &self.marker_name
+    }
+
     pub fn raw_midi_pattern(&self) -> &str {
         &self.raw_midi_pattern
     }
@@ -2111,6 +2327,33 @@ impl TargetModel {
         Ok(route)
     }
 
+    pub fn item_descriptor(&self) -> Result<ItemDescriptor, &'static str> {
+        let desc = ItemDescriptor {
+            track_descriptor: self.track_descriptor()?,
+            item: self.virtual_item().ok_or("item not set")?,
+        };
+        Ok(desc)
+    }
+
+    pub fn virtual_item(&self) -> Option<VirtualItem> {
+        use VirtualItemType::*;
+        let item = match self.item_type {
+            Selected => VirtualItem::Selected {
+                allow_multiple: false,
+            },
+            AllSelected => VirtualItem::Selected {
+                allow_multiple: true,
+            },
+            AtEditCursor => VirtualItem::AtEditCursor,
+            Dynamic => {
+                let evaluator = ExpressionEvaluator::compile(&self.item_expression).ok()?;
+                VirtualItem::Dynamic(Box::new(evaluator))
+            }
+            ByIndex => VirtualItem::ByIndex(self.item_index),
+        };
+        Some(item)
+    }
+
     pub fn virtual_fx_parameter(&self) -> Option<VirtualFxParameter> {
         use VirtualFxParameterType::*;
         let param = match self.param_type {
@@ -2133,6 +2376,23 @@ impl TargetModel {
         Ok(desc)
     }
 
+    fn envelope_descriptor(&self) -> Result<EnvelopeDescriptor, &'static str> {
+        let desc = match self.envelope_type {
+            EnvelopeType::Track => EnvelopeDescriptor::Track {
+                track_descriptor: self.track_descriptor()?,
+                kind: self.track_envelope_kind,
+            },
+            EnvelopeType::FxParameter => {
+                EnvelopeDescriptor::FxParameter(self.fx_parameter_descriptor()?)
+            }
+            EnvelopeType::Route => EnvelopeDescriptor::Route {
+                route_descriptor: self.route_descriptor()?,
+                kind: self.route_envelope_kind,
+            },
+        };
+        Ok(desc)
+    }
+
     pub fn create_target(
         &self,
         compartment: Compartment,
@@ -2257,6 +2517,57 @@ impl TargetModel {
                     RoutePan => UnresolvedReaperTarget::RoutePan(UnresolvedRoutePanTarget {
                         descriptor: self.route_descriptor()?,
                     }),
+                    ItemVolume => UnresolvedReaperTarget::ItemVolume(UnresolvedItemVolumeTarget {
+                        descriptor: self.item_descriptor()?,
+                        poll_for_feedback: self.poll_for_feedback,
+                    }),
+                    ItemMute => UnresolvedReaperTarget::ItemMute(UnresolvedItemMuteTarget {
+                        descriptor: self.item_descriptor()?,
+                        poll_for_feedback: self.poll_for_feedback,
+                    }),
+                    ItemLock => UnresolvedReaperTarget::ItemLock(UnresolvedItemLockTarget {
+                        descriptor: self.item_descriptor()?,
+                        poll_for_feedback: self.poll_for_feedback,
+                    }),
+                    ItemSelection => {
+                        UnresolvedReaperTarget::ItemSelection(UnresolvedItemSelectionTarget {
+                            descriptor: self.item_descriptor()?,
+                            poll_for_feedback: self.poll_for_feedback,
+                        })
+                    }
+                    ItemPosition => {
+                        UnresolvedReaperTarget::ItemPosition(UnresolvedItemPositionTarget {
+                            descriptor: self.item_descriptor()?,
+                        })
+                    }
+                    ItemBrowseTakes => {
+                        UnresolvedReaperTarget::ItemBrowseTakes(UnresolvedItemBrowseTakesTarget {
+                            descriptor: self.item_descriptor()?,
+                            poll_for_feedback: self.poll_for_feedback,
+                        })
+                    }
+                    TakeVolume => UnresolvedReaperTarget::TakeVolume(UnresolvedTakeVolumeTarget {
+                        descriptor: self.item_descriptor()?,
+                        poll_for_feedback: self.poll_for_feedback,
+                    }),
+                    TakePitch => UnresolvedReaperTarget::TakePitch(UnresolvedTakePitchTarget {
+                        descriptor: self.item_descriptor()?,
+                        poll_for_feedback: self.poll_for_feedback,
+                    }),
+                    TakePlayrate => {
+                        UnresolvedReaperTarget::TakePlayrate(UnresolvedTakePlayrateTarget {
+                            descriptor: self.item_descriptor()?,
+                            poll_for_feedback: self.poll_for_feedback,
+                        })
+                    }
+                    TakeFxParameter => {
+                        UnresolvedReaperTarget::TakeFxParameter(UnresolvedTakeFxParameterTarget {
+                            descriptor: self.item_descriptor()?,
+                            fx_index: self.take_fx_index,
+                            param_index: self.take_fx_param_index,
+                            poll_for_feedback: self.poll_for_feedback,
+                        })
+                    }
                     RouteMute => UnresolvedReaperTarget::RouteMute(UnresolvedRouteMuteTarget {
                         descriptor: self.route_descriptor()?,
                         poll_for_feedback: self.poll_for_feedback,
@@ -2282,6 +2593,26 @@ impl TargetModel {
                             parameter_type: self.touched_route_parameter_type,
                         })
                     }
+                    EnvelopeArm => {
+                        UnresolvedReaperTarget::EnvelopeArm(UnresolvedEnvelopeArmTarget {
+                            descriptor: self.envelope_descriptor()?,
+                        })
+                    }
+                    EnvelopeShow => {
+                        UnresolvedReaperTarget::EnvelopeShow(UnresolvedEnvelopeShowTarget {
+                            descriptor: self.envelope_descriptor()?,
+                        })
+                    }
+                    EnvelopeWriteValue => UnresolvedReaperTarget::EnvelopeWriteValue(
+                        UnresolvedEnvelopeWriteValueTarget {
+                            descriptor: self.envelope_descriptor()?,
+                        },
+                    ),
+                    BrowseAutomationItems => UnresolvedReaperTarget::BrowseAutomationItems(
+                        UnresolvedBrowseAutomationItemsTarget {
+                            descriptor: self.envelope_descriptor()?,
+                        },
+                    ),
                     Tempo => UnresolvedReaperTarget::Tempo(UnresolvedTempoTarget),
                     PlayRate => UnresolvedReaperTarget::Playrate(UnresolvedPlayrateTarget),
                     AutomationModeOverride => UnresolvedReaperTarget::AutomationModeOverride(
@@ -2324,6 +2655,13 @@ impl TargetModel {
                             mode: self.browse_tracks_mode,
                         })
                     }
+                    BrowseItems => {
+                        UnresolvedReaperTarget::BrowseItems(UnresolvedBrowseItemsTarget {
+                            track_descriptor: self.track_descriptor()?,
+                            scroll_arrange_view: self.scroll_arrange_view,
+                            move_edit_cursor: self.move_edit_cursor,
+                        })
+                    }
                     BrowseFxs => UnresolvedReaperTarget::BrowseFxs(UnresolvedBrowseFxsTarget {
                         track_descriptor: self.track_descriptor()?,
                         is_input_fx: self.fx_is_input_fx,
@@ -2368,10 +2706,23 @@ impl TargetModel {
                             seek_behavior: self.seek_behavior,
                         })
                     }
+                    MarkerManagement => {
+                        UnresolvedReaperTarget::MarkerManagement(UnresolvedMarkerManagementTarget {
+                            action: self.marker_management_action,
+                            name: self.marker_name.clone(),
+                        })
+                    }
                     Seek => UnresolvedReaperTarget::Seek(UnresolvedSeekTarget {
                         options: self.seek_options(),
                         behavior: self.seek_behavior,
                     }),
+                    TimeRange => UnresolvedReaperTarget::TimeRange(UnresolvedTimeRangeTarget {
+                        kind: self.time_range_kind,
+                        action: self.time_range_action,
+                        quantization: self.time_range_quantization,
+                        region_index: self.time_range_region_index,
+                        bar_count: self.time_range_bar_count,
+                    }),
                     SendMidi => UnresolvedReaperTarget::SendMidi(UnresolvedMidiSendTarget {
                         pattern: self.raw_midi_pattern.parse().unwrap_or_default(),
                         destination: self.send_midi_destination,
@@ -2479,7 +2830,8 @@ impl TargetModel {
                 Ok(UnresolvedCompoundMappingTarget::Reaper(target))
             }
             Virtual => {
-                let virtual_target = VirtualTarget::new(self.create_control_element());
+                let virtual_target = VirtualTarget::new(self.create_control_element())
+                    .with_axis(self.control_element_axis);
                 Ok(UnresolvedCompoundMappingTarget::Virtual(virtual_target))
             }
         }
@@ -2670,7 +3022,10 @@ impl TargetModel {
         if !self.uses_track_apart_from_type() {
             return false;
         }
-        if self.r#type.supports_fx() && !self.fx_type.requires_fx_chain() {
+        if self.supports_fx() && !self.fx_type.requires_fx_chain() {
+            return false;
+        }
+        if self.r#type.supports_item() && !self.item_type.requires_track() {
             return false;
         }
         true
@@ -2690,18 +3045,44 @@ impl TargetModel {
         }
     }
 
+    pub fn supports_item(&self) -> bool {
+        if !self.is_reaper() {
+            return false;
+        }
+        self.r#type.supports_item()
+    }
+
     pub fn supports_fx(&self) -> bool {
         if !self.is_reaper() {
             return false;
         }
-        self.r#type.supports_fx()
+        self.r#type.supports_fx() || self.supports_envelope_of_type(EnvelopeType::FxParameter)
+    }
+
+    pub fn supports_fx_parameter(&self) -> bool {
+        if !self.is_reaper() {
+            return false;
+        }
+        self.r#type.supports_fx_parameter()
+            || self.supports_envelope_of_type(EnvelopeType::FxParameter)
     }
 
     pub fn supports_route(&self) -> bool {
         if !self.is_reaper() {
             return false;
         }
-        self.r#type.supports_send()
+        self.r#type.supports_send() || self.supports_envelope_of_type(EnvelopeType::Route)
+    }
+
+    pub fn supports_envelope(&self) -> bool {
+        if !self.is_reaper() {
+            return false;
+        }
+        self.r#type.supports_envelope()
+    }
+
+    fn supports_envelope_of_type(&self, envelope_type: EnvelopeType) -> bool {
+        self.r#type.supports_envelope() && self.envelope_type == envelope_type
     }
 
     pub fn supports_automation_mode(&self) -> bool {
@@ -2811,6 +3192,16 @@ impl<'a> Display for TargetModelFormatVeryShort<'a> {
                     AnyOn => {
                         write!(f, "{}", self.0.any_on_parameter)
                     }
+                    MarkerManagement => {
+                        write!(f, "{}", self.0.marker_management_action)
+                    }
+                    TimeRange => {
+                        write!(
+                            f,
+                            "{}: {}",
+                            self.0.time_range_kind, self.0.time_range_action
+                        )
+                    }
                     GoToBookmark => {
                         let type_label = match self.0.bookmark_type {
                             BookmarkType::Marker => "Marker",
@@ -2837,6 +3228,17 @@ impl<'a> Display for TargetModelFormatVeryShort<'a> {
                             self.0.touched_track_parameter_type
                         )
                     }
+                    EnvelopeArm | EnvelopeShow | EnvelopeWriteValue | BrowseAutomationItems => {
+                        match self.0.envelope_type {
+                            EnvelopeType::Track => {
+                                write!(f, "{}: {}", tt.short_name(), self.0.track_envelope_kind)
+                            }
+                            EnvelopeType::FxParameter => f.write_str(tt.short_name()),
+                            EnvelopeType::Route => {
+                                write!(f, "{}: {}", tt.short_name(), self.0.route_envelope_kind)
+                            }
+                        }
+                    }
                     _ => f.write_str(tt.short_name()),
                 }
             }
@@ -2926,6 +3328,13 @@ impl<'a> TargetModelFormatMultiLine<'a> {
         }
     }
 
+    fn item_label(&self) -> String {
+        match self.target.virtual_item() {
+            None => TARGET_UNDEFINED_LABEL.into(),
+            Some(item) => item.to_string(),
+        }
+    }
+
     fn fx_label(&self) -> Cow<str> {
         let fx_descriptor = match self.target.fx_descriptor() {
             Ok(d) => d,
@@ -3029,7 +3438,7 @@ impl<'a> Display for TargetModelFormatMultiLine<'a> {
                     ),
                     TrackTool | TrackVolume | TrackPeak | TrackPan | TrackWidth | TrackArm
                     | TrackSelection | TrackMute | TrackPhase | TrackSolo | TrackShow
-                    | BrowseFxs | AllTrackFxEnable | TrackParentSend => {
+                    | BrowseItems | BrowseFxs | AllTrackFxEnable | TrackParentSend => {
                         write!(f, "{}\nTrack {}", tt, self.track_label())
                     }
                     TrackAutomationMode => {
@@ -3050,6 +3459,23 @@ impl<'a> Display for TargetModelFormatMultiLine<'a> {
                         self.target.route_type,
                         self.route_label()
                     ),
+                    ItemVolume | ItemMute | ItemLock | ItemSelection | ItemPosition
+                    | ItemBrowseTakes | TakeVolume | TakePitch | TakePlayrate => write!(
+                        f,
+                        "{}\nTrack {}\nItem {}",
+                        tt,
+                        self.track_label(),
+                        self.item_label()
+                    ),
+                    TakeFxParameter => write!(
+                        f,
+                        "{}\nTrack {}\nItem {}\nFX #{} / Param #{}",
+                        tt,
+                        self.track_label(),
+                        self.item_label(),
+                        self.target.take_fx_index + 1,
+                        self.target.take_fx_param_index + 1
+                    ),
                     FxOpen | FxEnable | FxPreset | FxTool => write!(
                         f,
                         "{}\nTrack {}\nFX {}",
@@ -3104,6 +3530,42 @@ impl<'a> Display for TargetModelFormatMultiLine<'a> {
                     GoToBookmark => {
                         write!(f, "{}\n{}", tt, self.bookmark_label())
                     }
+                    MarkerManagement => {
+                        write!(f, "{}\n{}", tt, self.target.marker_management_action)
+                    }
+                    TimeRange => write!(
+                        f,
+                        "{}\n{}\n{}",
+                        tt, self.target.time_range_kind, self.target.time_range_action
+                    ),
+                    EnvelopeArm | EnvelopeShow | EnvelopeWriteValue | BrowseAutomationItems => {
+                        match self.target.envelope_type {
+                            EnvelopeType::Track => write!(
+                                f,
+                                "{}\nTrack {}\n{} envelope",
+                                tt,
+                                self.track_label(),
+                                self.target.track_envelope_kind
+                            ),
+                            EnvelopeType::FxParameter => write!(
+                                f,
+                                "{}\nTrack {}\nFX {}\nParam {}",
+                                tt,
+                                self.track_label(),
+                                self.fx_label(),
+                                self.fx_param_label()
+                            ),
+                            EnvelopeType::Route => write!(
+                                f,
+                                "{}\nTrack {}\n{} {}\n{} envelope",
+                                tt,
+                                self.track_label(),
+                                self.target.route_type,
+                                self.route_label(),
+                                self.target.route_envelope_kind
+                            ),
+                        }
+                    }
                     Mouse => {
                         write!(f, "{}\n{}", tt, self.target.mouse_action_type)?;
                         if self.target.supports_axis() {
@@ -3814,6 +4276,84 @@ pub enum TrackRouteSelectorType {
     ByIndex,
 }
 
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    IntoEnumIterator,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+    Serialize,
+    Deserialize,
+)]
+#[repr(usize)]
+pub enum VirtualItemType {
+    #[display(fmt = "<Selected>")]
+    #[serde(rename = "selected")]
+    Selected,
+    #[display(fmt = "<All selected>")]
+    #[serde(rename = "all-selected")]
+    AllSelected,
+    #[display(fmt = "<At edit cursor>")]
+    #[serde(rename = "edit-cursor")]
+    AtEditCursor,
+    #[display(fmt = "<Dynamic>")]
+    #[serde(rename = "dynamic")]
+    Dynamic,
+    #[display(fmt = "At position")]
+    #[serde(rename = "index")]
+    ByIndex,
+}
+
+impl Default for VirtualItemType {
+    fn default() -> Self {
+        Self::Selected
+    }
+}
+
+impl VirtualItemType {
+    pub fn requires_track(&self) -> bool {
+        use VirtualItemType::*;
+        !matches!(self, Selected | AllSelected)
+    }
+}
+
+/// Determines which object an envelope belongs to.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    IntoEnumIterator,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+    Serialize,
+    Deserialize,
+)]
+#[repr(usize)]
+pub enum EnvelopeType {
+    #[display(fmt = "Track")]
+    #[serde(rename = "track")]
+    Track,
+    #[display(fmt = "FX parameter")]
+    #[serde(rename = "fx-parameter")]
+    FxParameter,
+    #[display(fmt = "Send/receive")]
+    #[serde(rename = "route")]
+    Route,
+}
+
+impl Default for EnvelopeType {
+    fn default() -> Self {
+        Self::Track
+    }
+}
+
 impl Default for TrackRouteSelectorType {
     fn default() -> Self {
         Self::ByIndex
@@ -91,6 +91,27 @@ impl Vm {
         unsafe { &*slice }
     }
 
+    /// Writes the given values into the memory starting at the given index and returns the number
+    /// of written values.
+    ///
+    /// Allocates the memory block on first use, so it's a good idea to call this once in a
+    /// non-real-time thread.
+    pub fn write_mem(&self, index: u32, values: impl IntoIterator<Item = f64>) -> u32 {
+        let mut valid_count = MaybeUninit::zeroed();
+        let ptr = unsafe { root::NSEEL_VM_getramptr(self.vm_ctx, index, valid_count.as_mut_ptr()) };
+        let valid_count = unsafe { valid_count.assume_init() };
+        if ptr.is_null() || valid_count <= 0 {
+            return 0;
+        }
+        let slice = unsafe { std::slice::from_raw_parts_mut(ptr, valid_count as _) };
+        let mut count = 0;
+        for (dest, v) in slice.iter_mut().zip(values) {
+            *dest = v;
+            count += 1;
+        }
+        count
+    }
+
     pub fn compile(&self, code: &str) -> Result<Program, String> {
         if code.trim().is_empty() {
             return Err("Empty".to_owned());
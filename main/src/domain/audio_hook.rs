@@ -1,8 +1,8 @@
 use crate::base::non_blocking_lock;
 use crate::domain::{
-    classify_midi_message, AudioBlockProps, ControlEvent, ControlEventTimestamp, Garbage,
-    GarbageBin, IncomingMidiMessage, InstanceId, MidiControlInput, MidiEvent,
-    MidiMessageClassification, MidiScanResult, MidiScanner, RealTimeProcessor,
+    classify_midi_message, hardware_input_levels, AudioBlockProps, ControlEvent,
+    ControlEventTimestamp, Garbage, GarbageBin, IncomingMidiMessage, InstanceId, MidiControlInput,
+    MidiEvent, MidiMessageClassification, MidiScanResult, MidiScanner, RealTimeProcessor,
 };
 use assert_no_alloc::*;
 use helgoboss_learn::{AbstractTimestamp, MidiSourceValue, RawMidiEvents};
@@ -357,6 +357,7 @@ impl OnAudioBuffer for RealearnAudioHook {
             if !args.is_post {
                 let block_props = AudioBlockProps::from_on_audio_buffer_args(&args);
                 global_steady_timeline_state().on_audio_buffer(block_props.to_playtime());
+                hardware_input_levels().process_audio_block(&args);
                 let current_time = Instant::now();
                 let time_of_last_run = self.time_of_last_run.replace(current_time);
                 let might_be_rebirth = if let Some(time) = time_of_last_run {
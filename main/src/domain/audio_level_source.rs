@@ -0,0 +1,419 @@
+use crate::domain::ui_util::volume_unit_value;
+use crate::domain::{get_track_peak, ProcessorContext};
+use helgoboss_learn::{ControlValue, UnitValue};
+use once_cell::sync::Lazy;
+use realearn_api::persistence::{AudioLevelInput, AudioLevelMeasurement};
+use reaper_high::{Track, Volume};
+use reaper_medium::{OnAudioBufferArgs, ReaperVolumeValue};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Hardware input channels beyond this number can't be analyzed.
+pub const MAX_HARDWARE_INPUT_CHANNEL_COUNT: usize = 64;
+/// Time in which the hardware input peak falls by roughly 63%.
+const HARDWARE_INPUT_PEAK_RELEASE: Duration = Duration::from_millis(100);
+/// Time constant of the RMS measurement.
+const RMS_WINDOW: Duration = Duration::from_millis(300);
+/// Hardware inputs are not analyzed anymore if no source asked for them during this number of
+/// audio blocks.
+const HARDWARE_INPUT_DEMAND_BLOCK_COUNT: u64 = 1000;
+/// An onset is detected if the peak exceeds the slow envelope by this factor (roughly 6 dB).
+const ONSET_RISE_FACTOR: f64 = 2.0;
+/// Onsets closer to each other than this are ignored.
+const ONSET_MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Source which turns the audio level of a track or hardware input into a control value.
+///
+/// Track levels are taken from the track meters. Hardware inputs are analyzed in the audio hook
+/// (see [`HardwareInputLevels`]). Both are read when polling, so everything else (envelope
+/// follower, onset detection) happens in the main thread.
+#[derive(Clone, Debug)]
+pub struct AudioLevelSource {
+    settings: AudioLevelSettings,
+    analyzer: LevelAnalyzer,
+    last_value: Option<UnitValue>,
+}
+
+// The state is transient and not relevant for deciding whether two sources are the same.
+impl PartialEq for AudioLevelSource {
+    fn eq(&self, other: &Self) -> bool {
+        self.settings == other.settings
+    }
+}
+
+impl Eq for AudioLevelSource {}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct AudioLevelSettings {
+    pub input: AudioLevelInput,
+    /// Track position or hardware input channel, starting with 1.
+    pub index: u32,
+    pub measurement: AudioLevelMeasurement,
+    pub attack: Duration,
+    pub release: Duration,
+    /// Onsets below this level are ignored.
+    pub onset_threshold_db: i32,
+}
+
+pub const DEFAULT_AUDIO_LEVEL_ATTACK_MILLIS: u64 = 10;
+pub const DEFAULT_AUDIO_LEVEL_RELEASE_MILLIS: u64 = 300;
+pub const DEFAULT_AUDIO_LEVEL_ONSET_THRESHOLD_DB: i32 = -30;
+
+impl Default for AudioLevelSettings {
+    fn default() -> Self {
+        Self {
+            input: Default::default(),
+            index: 1,
+            measurement: Default::default(),
+            attack: Duration::from_millis(DEFAULT_AUDIO_LEVEL_ATTACK_MILLIS),
+            release: Duration::from_millis(DEFAULT_AUDIO_LEVEL_RELEASE_MILLIS),
+            onset_threshold_db: DEFAULT_AUDIO_LEVEL_ONSET_THRESHOLD_DB,
+        }
+    }
+}
+
+impl AudioLevelSource {
+    pub fn new(settings: AudioLevelSettings) -> Self {
+        Self {
+            settings,
+            analyzer: Default::default(),
+            last_value: None,
+        }
+    }
+
+    pub fn settings(&self) -> &AudioLevelSettings {
+        &self.settings
+    }
+
+    pub fn on_deactivate(&mut self) {
+        self.analyzer = Default::default();
+        self.last_value = None;
+    }
+
+    pub fn poll(&mut self, context: &ProcessorContext) -> Option<ControlValue> {
+        let reading = self.read_level(context)?;
+        let output = self
+            .analyzer
+            .process(&self.settings, reading, Instant::now())?;
+        let value = match output {
+            AnalyzerOutput::Level(amplitude) => {
+                let volume = Volume::from_reaper_value(ReaperVolumeValue::new(amplitude));
+                volume_unit_value(volume)
+            }
+            AnalyzerOutput::Onset => {
+                // Each onset must fire, even if the previous one had the same value.
+                self.last_value = None;
+                return Some(ControlValue::AbsoluteContinuous(UnitValue::MAX));
+            }
+        };
+        if self.last_value == Some(value) {
+            return None;
+        }
+        self.last_value = Some(value);
+        Some(ControlValue::AbsoluteContinuous(value))
+    }
+
+    fn read_level(&self, context: &ProcessorContext) -> Option<LevelReading> {
+        let track = match self.settings.input {
+            AudioLevelInput::ThisTrack => context.track().cloned(),
+            AudioLevelInput::TrackByPosition => context
+                .project_or_current_project()
+                .track_by_index(self.settings.index.checked_sub(1)?),
+            AudioLevelInput::MasterTrack => {
+                context.project_or_current_project().master_track().ok()
+            }
+            AudioLevelInput::HardwareInput => {
+                let channel_index = self.settings.index.checked_sub(1)? as usize;
+                return hardware_input_levels().read(channel_index);
+            }
+        };
+        read_track_level(&track?)
+    }
+}
+
+fn read_track_level(track: &Track) -> Option<LevelReading> {
+    let peak = get_track_peak(track)?;
+    let reading = LevelReading {
+        peak: peak.reaper_value().get(),
+        // Track meters don't tell us the RMS, so it's derived from the peaks.
+        mean_square: None,
+    };
+    Some(reading)
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct LevelReading {
+    /// Linear amplitude.
+    peak: f64,
+    /// `None` if not available.
+    mean_square: Option<f64>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum AnalyzerOutput {
+    /// Linear amplitude.
+    Level(f64),
+    Onset,
+}
+
+#[derive(Clone, Debug, Default)]
+struct LevelAnalyzer {
+    last_time: Option<Instant>,
+    envelope: f64,
+    mean_square: f64,
+    last_onset_time: Option<Instant>,
+}
+
+impl LevelAnalyzer {
+    fn process(
+        &mut self,
+        settings: &AudioLevelSettings,
+        reading: LevelReading,
+        now: Instant,
+    ) -> Option<AnalyzerOutput> {
+        let elapsed = match self.last_time.replace(now) {
+            None => {
+                // First reading
+                self.envelope = reading.peak;
+                self.mean_square = reading.peak * reading.peak;
+                None
+            }
+            Some(t) => Some(now.saturating_duration_since(t)),
+        };
+        let previous_envelope = self.envelope;
+        if let Some(elapsed) = elapsed {
+            let smoothing_time = match settings.measurement {
+                AudioLevelMeasurement::Envelope if reading.peak > self.envelope => settings.attack,
+                _ => settings.release,
+            };
+            self.envelope +=
+                (reading.peak - self.envelope) * smoothing_factor(elapsed, smoothing_time);
+            self.mean_square += (reading.peak * reading.peak - self.mean_square)
+                * smoothing_factor(elapsed, RMS_WINDOW);
+        }
+        let output = match settings.measurement {
+            AudioLevelMeasurement::Peak => AnalyzerOutput::Level(reading.peak),
+            AudioLevelMeasurement::Rms => {
+                let mean_square = reading.mean_square.unwrap_or(self.mean_square);
+                AnalyzerOutput::Level(mean_square.max(0.0).sqrt())
+            }
+            AudioLevelMeasurement::Envelope => AnalyzerOutput::Level(self.envelope),
+            AudioLevelMeasurement::Onset => {
+                // The slowly following envelope serves as reference.
+                let threshold = db_to_amplitude(settings.onset_threshold_db);
+                let is_onset = elapsed.is_some()
+                    && reading.peak >= threshold
+                    && reading.peak > previous_envelope * ONSET_RISE_FACTOR
+                    && self
+                        .last_onset_time
+                        .map(|t| now.saturating_duration_since(t) >= ONSET_MIN_INTERVAL)
+                        .unwrap_or(true);
+                if !is_onset {
+                    return None;
+                }
+                self.last_onset_time = Some(now);
+                AnalyzerOutput::Onset
+            }
+        };
+        Some(output)
+    }
+}
+
+/// Returns how much of the difference to the new value should be applied for exponential
+/// smoothing with the given time constant.
+fn smoothing_factor(elapsed: Duration, time_constant: Duration) -> f64 {
+    if time_constant.is_zero() {
+        return 1.0;
+    }
+    1.0 - (-elapsed.as_secs_f64() / time_constant.as_secs_f64()).exp()
+}
+
+fn db_to_amplitude(db: i32) -> f64 {
+    10f64.powf(db as f64 / 20.0)
+}
+
+/// Returns the global hardware input levels, measured in the audio hook.
+pub fn hardware_input_levels() -> &'static HardwareInputLevels {
+    static LEVELS: Lazy<HardwareInputLevels> = Lazy::new(HardwareInputLevels::default);
+    &LEVELS
+}
+
+/// Levels of the hardware input channels.
+///
+/// Written by the audio hook, read by the main thread. The audio hook only does the analysis if
+/// some source has been asking for levels recently.
+#[derive(Debug)]
+pub struct HardwareInputLevels {
+    block_count: AtomicU64,
+    /// Value of `block_count` when a level has been read most recently (zero if never).
+    demand_block_count: AtomicU64,
+    channels: [HardwareInputChannelLevel; MAX_HARDWARE_INPUT_CHANNEL_COUNT],
+}
+
+#[derive(Debug, Default)]
+struct HardwareInputChannelLevel {
+    /// Bits of the linear peak amplitude (with a short release).
+    peak: AtomicU64,
+    /// Bits of the smoothed mean square.
+    mean_square: AtomicU64,
+}
+
+impl Default for HardwareInputLevels {
+    fn default() -> Self {
+        Self {
+            block_count: AtomicU64::new(0),
+            demand_block_count: AtomicU64::new(0),
+            channels: std::array::from_fn(|_| Default::default()),
+        }
+    }
+}
+
+impl HardwareInputLevels {
+    /// Reads the current level of the given hardware input channel.
+    fn read(&self, channel_index: usize) -> Option<LevelReading> {
+        let channel = self.channels.get(channel_index)?;
+        let block_count = self.block_count.load(Ordering::Relaxed).max(1);
+        self.demand_block_count
+            .store(block_count, Ordering::Relaxed);
+        let reading = LevelReading {
+            peak: load_f64(&channel.peak),
+            mean_square: Some(load_f64(&channel.mean_square)),
+        };
+        Some(reading)
+    }
+
+    /// Analyzes the hardware inputs of the current audio block.
+    ///
+    /// Must be called from the audio hook (before processing). Doesn't allocate.
+    pub fn process_audio_block(&self, args: &OnAudioBufferArgs) {
+        let block_count = self.block_count.fetch_add(1, Ordering::Relaxed) + 1;
+        let demand_block_count = self.demand_block_count.load(Ordering::Relaxed);
+        if demand_block_count == 0
+            || block_count.saturating_sub(demand_block_count) > HARDWARE_INPUT_DEMAND_BLOCK_COUNT
+        {
+            return;
+        }
+        let reg = unsafe { args.reg.get().as_ref() };
+        let get_buffer = match reg.GetBuffer {
+            None => return,
+            Some(f) => f,
+        };
+        let block_length = args.len as usize;
+        if block_length == 0 {
+            return;
+        }
+        let block_duration = Duration::from_secs_f64(block_length as f64 / args.srate.get());
+        let peak_decay = 1.0 - smoothing_factor(block_duration, HARDWARE_INPUT_PEAK_RELEASE);
+        let rms_factor = smoothing_factor(block_duration, RMS_WINDOW);
+        let channel_count = (reg.input_nch.max(0) as usize).min(MAX_HARDWARE_INPUT_CHANNEL_COUNT);
+        for (i, channel) in self.channels.iter().enumerate().take(channel_count) {
+            let buf = unsafe { (get_buffer)(false, i as _) };
+            if buf.is_null() {
+                continue;
+            }
+            let samples = unsafe { std::slice::from_raw_parts(buf, block_length) };
+            let mut block_peak: f64 = 0.0;
+            let mut sum_of_squares = 0.0;
+            for sample in samples {
+                block_peak = block_peak.max(sample.abs());
+                sum_of_squares += sample * sample;
+            }
+            let block_mean_square = sum_of_squares / block_length as f64;
+            let peak = block_peak.max(load_f64(&channel.peak) * peak_decay);
+            let old_mean_square = load_f64(&channel.mean_square);
+            let mean_square = old_mean_square + (block_mean_square - old_mean_square) * rms_factor;
+            store_f64(&channel.peak, peak);
+            store_f64(&channel.mean_square, mean_square);
+        }
+    }
+}
+
+fn load_f64(atomic: &AtomicU64) -> f64 {
+    f64::from_bits(atomic.load(Ordering::Relaxed))
+}
+
+fn store_f64(atomic: &AtomicU64, value: f64) {
+    atomic.store(value.to_bits(), Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_follows_attack_and_release() {
+        // Given
+        let settings = settings(AudioLevelMeasurement::Envelope);
+        let mut analyzer = LevelAnalyzer::default();
+        let start = Instant::now();
+        // When
+        let first = analyzer.process(&settings, peak(0.0), start);
+        let second = analyzer.process(&settings, peak(1.0), start + Duration::from_millis(10));
+        let third = analyzer.process(&settings, peak(0.0), start + Duration::from_millis(20));
+        // Then
+        assert_eq!(first, Some(AnalyzerOutput::Level(0.0)));
+        let second = level(second);
+        let third = level(third);
+        // After one attack time constant, the envelope should be at roughly 63%.
+        assert!((second - 0.632).abs() < 0.01);
+        // The release is much slower than the attack.
+        assert!(third < second && third > 0.6);
+    }
+
+    #[test]
+    fn onset() {
+        // Given
+        let settings = settings(AudioLevelMeasurement::Onset);
+        let mut analyzer = LevelAnalyzer::default();
+        let start = Instant::now();
+        // When
+        let first = analyzer.process(&settings, peak(0.001), start);
+        let second = analyzer.process(&settings, peak(0.8), start + Duration::from_millis(30));
+        let third = analyzer.process(&settings, peak(0.9), start + Duration::from_millis(60));
+        let fourth = analyzer.process(&settings, peak(0.1), start + Duration::from_millis(400));
+        let fifth = analyzer.process(&settings, peak(0.9), start + Duration::from_millis(700));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, Some(AnalyzerOutput::Onset));
+        // Too close to the previous onset and not a big enough rise
+        assert_eq!(third, None);
+        assert_eq!(fourth, None);
+        assert_eq!(fifth, Some(AnalyzerOutput::Onset));
+    }
+
+    #[test]
+    fn rms_prefers_measured_mean_square() {
+        // Given
+        let settings = settings(AudioLevelMeasurement::Rms);
+        let mut analyzer = LevelAnalyzer::default();
+        let reading = LevelReading {
+            peak: 1.0,
+            mean_square: Some(0.25),
+        };
+        // When
+        let output = analyzer.process(&settings, reading, Instant::now());
+        // Then
+        assert_eq!(output, Some(AnalyzerOutput::Level(0.5)));
+    }
+
+    fn settings(measurement: AudioLevelMeasurement) -> AudioLevelSettings {
+        AudioLevelSettings {
+            measurement,
+            ..Default::default()
+        }
+    }
+
+    fn peak(peak: f64) -> LevelReading {
+        LevelReading {
+            peak,
+            mean_square: None,
+        }
+    }
+
+    fn level(output: Option<AnalyzerOutput>) -> f64 {
+        match output {
+            Some(AnalyzerOutput::Level(l)) => l,
+            _ => panic!("expected level"),
+        }
+    }
+}
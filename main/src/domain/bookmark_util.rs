@@ -0,0 +1,109 @@
+//! Access to marker/region functions which are not (yet) exposed by the high-level API.
+use reaper_high::{BasicBookmarkInfo, BookmarkType, Project, Reaper};
+use reaper_medium::PositionInSeconds;
+use std::ffi::CString;
+
+/// Adds a marker at the given position and returns its index.
+pub fn add_marker(
+    project: Project,
+    pos: PositionInSeconds,
+    name: &str,
+) -> Result<u32, &'static str> {
+    add_bookmark(project, false, pos, pos, name)
+}
+
+/// Adds a region spanning the given range and returns its index.
+pub fn add_region(
+    project: Project,
+    start: PositionInSeconds,
+    end: PositionInSeconds,
+    name: &str,
+) -> Result<u32, &'static str> {
+    add_bookmark(project, true, start, end, name)
+}
+
+fn add_bookmark(
+    project: Project,
+    is_region: bool,
+    start: PositionInSeconds,
+    end: PositionInSeconds,
+    name: &str,
+) -> Result<u32, &'static str> {
+    let name = CString::new(name).map_err(|_| "name contains nul character")?;
+    let index = unsafe {
+        Reaper::get().medium_reaper().low().AddProjectMarker2(
+            project.raw().as_ptr(),
+            is_region,
+            start.get(),
+            end.get(),
+            name.as_ptr(),
+            -1,
+            0,
+        )
+    };
+    if index < 0 {
+        return Err("couldn't add marker/region");
+    }
+    update_timeline();
+    Ok(index as u32)
+}
+
+/// Renames the marker or region described by the given info.
+pub fn rename_bookmark(
+    project: Project,
+    info: &BasicBookmarkInfo,
+    name: &str,
+) -> Result<(), &'static str> {
+    let name = CString::new(name).map_err(|_| "name contains nul character")?;
+    let successful = unsafe {
+        Reaper::get().medium_reaper().low().SetProjectMarker2(
+            project.raw().as_ptr(),
+            info.id.get() as i32,
+            info.bookmark_type() == BookmarkType::Region,
+            info.position.get(),
+            info.region_end_position.unwrap_or(info.position).get(),
+            name.as_ptr(),
+        )
+    };
+    if !successful {
+        return Err("couldn't rename marker/region");
+    }
+    update_timeline();
+    Ok(())
+}
+
+/// Deletes the marker or region at the given index (counting both markers and regions).
+pub fn delete_bookmark(project: Project, index: u32) -> Result<(), &'static str> {
+    let successful = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .DeleteProjectMarkerByIndex(project.raw().as_ptr(), index as i32)
+    };
+    if !successful {
+        return Err("couldn't delete marker/region");
+    }
+    update_timeline();
+    Ok(())
+}
+
+/// Returns index and info of the marker which is closest to the given position.
+pub fn find_nearest_marker(
+    project: Project,
+    pos: PositionInSeconds,
+) -> Option<(u32, BasicBookmarkInfo)> {
+    project
+        .bookmarks()
+        .enumerate()
+        .map(|(i, b)| (i as u32, b.basic_info()))
+        .filter(|(_, info)| info.bookmark_type() == BookmarkType::Marker)
+        .min_by(|(_, a), (_, b)| {
+            let distance_a = (a.position.get() - pos.get()).abs();
+            let distance_b = (b.position.get() - pos.get()).abs();
+            distance_a.total_cmp(&distance_b)
+        })
+}
+
+fn update_timeline() {
+    Reaper::get().medium_reaper().low().UpdateTimeline();
+}
@@ -0,0 +1,485 @@
+use crate::domain::{
+    ControlEvent, ControlEventTimestamp, VirtualControlElementId, VirtualSourceValue,
+};
+use helgoboss_learn::{ControlValue, DetailedSourceCharacter, MidiSourceValue, UnitValue};
+use helgoboss_midi::{Channel, RawShortMessage, ShortMessage, StructuredShortMessage, U7};
+use realearn_api::persistence::ChordMode;
+use std::time::Duration;
+
+/// Source which fires when a combination of notes or virtual buttons is pressed.
+///
+/// Sends 100% as soon as the chord is complete and 0% as soon as one of its elements is released
+/// again. In contrast to most other sources, this one is stateful. The state is allocated when
+/// creating the source, so processing incoming events doesn't allocate (important because this
+/// can run in the real-time processor).
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ChordSource {
+    elements: ChordElements,
+    mode: ChordMode,
+    /// Zero means no time limit.
+    time_window: Duration,
+    state: ChordState,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ChordElements {
+    Notes {
+        /// `None` means any channel.
+        channel: Option<Channel>,
+        key_numbers: Vec<U7>,
+    },
+    VirtualButtons(Vec<VirtualControlElementId>),
+}
+
+impl ChordElements {
+    fn len(&self) -> usize {
+        match self {
+            ChordElements::Notes { key_numbers, .. } => key_numbers.len(),
+            ChordElements::VirtualButtons(ids) => ids.len(),
+        }
+    }
+
+    fn element_eq(&self, a: usize, b: usize) -> bool {
+        match self {
+            ChordElements::Notes { key_numbers, .. } => key_numbers[a] == key_numbers[b],
+            ChordElements::VirtualButtons(ids) => ids[a] == ids[b],
+        }
+    }
+
+    fn remove_duplicates(&mut self) {
+        match self {
+            ChordElements::Notes { key_numbers, .. } => remove_duplicates(key_numbers),
+            ChordElements::VirtualButtons(ids) => remove_duplicates(ids),
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+struct ChordState {
+    /// Press time of each chord element, `None` if not held.
+    press_times: Vec<Option<ControlEventTimestamp>>,
+    /// Number of currently held elements which are not part of the chord.
+    foreign_held_count: u32,
+    /// Number of chord elements which have been pressed in the correct order so far.
+    sequence_progress: usize,
+    last_sequence_press: Option<ControlEventTimestamp>,
+    is_on: bool,
+}
+
+impl ChordSource {
+    /// In a sequence, elements can repeat. In the other modes, duplicate elements are removed
+    /// because one key can't be held twice at the same time.
+    pub fn new(mut elements: ChordElements, mode: ChordMode, time_window: Duration) -> Self {
+        if mode != ChordMode::Sequence {
+            elements.remove_duplicates();
+        }
+        let state = ChordState {
+            press_times: vec![None; elements.len()],
+            ..Default::default()
+        };
+        Self {
+            elements,
+            mode,
+            time_window,
+            state,
+        }
+    }
+
+    pub fn elements(&self) -> &ChordElements {
+        &self.elements
+    }
+
+    pub fn mode(&self) -> ChordMode {
+        self.mode
+    }
+
+    pub fn time_window(&self) -> Duration {
+        self.time_window
+    }
+
+    /// Forgets which elements are currently held.
+    pub fn reset(&mut self) {
+        for t in self.state.press_times.iter_mut() {
+            *t = None;
+        }
+        self.state.foreign_held_count = 0;
+        self.state.sequence_progress = 0;
+        self.state.last_sequence_press = None;
+        self.state.is_on = false;
+    }
+
+    pub fn possible_detailed_characters(&self) -> Vec<DetailedSourceCharacter> {
+        vec![
+            DetailedSourceCharacter::MomentaryOnOffButton,
+            DetailedSourceCharacter::Trigger,
+        ]
+    }
+
+    pub fn control_midi(
+        &mut self,
+        evt: ControlEvent<&MidiSourceValue<RawShortMessage>>,
+    ) -> Option<ControlValue> {
+        let (channel, key_numbers) = match &self.elements {
+            ChordElements::Notes {
+                channel,
+                key_numbers,
+            } => (*channel, key_numbers),
+            ChordElements::VirtualButtons(_) => return None,
+        };
+        let msg = match evt.payload() {
+            MidiSourceValue::Plain(msg) => msg,
+            _ => return None,
+        };
+        use StructuredShortMessage::*;
+        let (msg_channel, key_number, is_press) = match msg.to_structured() {
+            NoteOn {
+                channel,
+                key_number,
+                velocity,
+            } => (channel, key_number, velocity > U7::MIN),
+            NoteOff {
+                channel,
+                key_number,
+                ..
+            } => (channel, key_number, false),
+            _ => return None,
+        };
+        if channel.map(|ch| ch != msg_channel).unwrap_or(false) {
+            return None;
+        }
+        let key_number: U7 = key_number.into();
+        let is_match = |i: usize| key_numbers[i] == key_number;
+        self.process(is_match, is_press, evt.timestamp())
+    }
+
+    pub fn control_virtual(
+        &mut self,
+        evt: ControlEvent<&VirtualSourceValue>,
+    ) -> Option<ControlValue> {
+        let ids = match &self.elements {
+            ChordElements::VirtualButtons(ids) => ids,
+            ChordElements::Notes { .. } => return None,
+        };
+        let value = evt.payload();
+        let is_press = match value.control_value() {
+            ControlValue::AbsoluteContinuous(v) => !v.is_zero(),
+            ControlValue::AbsoluteDiscrete(f) => f.actual() > 0,
+            // Relative values don't have a notion of press and release.
+            ControlValue::RelativeDiscrete(_) | ControlValue::RelativeContinuous(_) => return None,
+        };
+        let id = value.control_element().id();
+        let is_match = |i: usize| ids[i] == id;
+        self.process(is_match, is_press, evt.timestamp())
+    }
+
+    /// `is_match` tells whether the chord element at the given index corresponds to the incoming
+    /// event. In a sequence, several elements can correspond to it.
+    fn process(
+        &mut self,
+        is_match: impl Fn(usize) -> bool,
+        is_press: bool,
+        timestamp: ControlEventTimestamp,
+    ) -> Option<ControlValue> {
+        let state = &mut self.state;
+        let element_count = state.press_times.len();
+        if !(0..element_count).any(&is_match) {
+            // Element which is not part of the chord
+            if is_press {
+                state.foreign_held_count += 1;
+                state.sequence_progress = 0;
+                if state.is_on && self.mode == ChordMode::Exact {
+                    state.is_on = false;
+                    return Some(off());
+                }
+            } else {
+                state.foreign_held_count = state.foreign_held_count.saturating_sub(1);
+            }
+            return None;
+        }
+        if !is_press {
+            for (i, t) in state.press_times.iter_mut().enumerate() {
+                if is_match(i) {
+                    *t = None;
+                }
+            }
+            let ends_chord = match self.mode {
+                ChordMode::Exact | ChordMode::Contains => true,
+                // A sequence is over when its last element is released.
+                ChordMode::Sequence => is_match(element_count - 1),
+            };
+            if state.is_on && ends_chord {
+                state.is_on = false;
+                return Some(off());
+            }
+            return None;
+        }
+        for (i, t) in state.press_times.iter_mut().enumerate() {
+            if is_match(i) {
+                *t = Some(timestamp);
+            }
+        }
+        if state.is_on {
+            return None;
+        }
+        let is_complete = match self.mode {
+            ChordMode::Exact | ChordMode::Contains => {
+                if self.mode == ChordMode::Exact && state.foreign_held_count > 0 {
+                    return None;
+                }
+                state.press_times.iter().all(|t| match t {
+                    None => false,
+                    Some(t) => is_within_window(self.time_window, *t, timestamp),
+                })
+            }
+            ChordMode::Sequence => {
+                let is_in_time = state
+                    .last_sequence_press
+                    .map(|t| is_within_window(self.time_window, t, timestamp))
+                    .unwrap_or(true);
+                let progress = if is_in_time {
+                    state.sequence_progress
+                } else {
+                    0
+                };
+                // Compare with the element which is expected next. If it doesn't match, the
+                // press might still continue a shorter part of the sequence because elements can
+                // repeat (e.g. the third press of "C C C G" continues "C C"). In the simplest
+                // case, it starts over with the first element.
+                let elements = &self.elements;
+                state.sequence_progress = (0..=progress)
+                    .rev()
+                    .find(|k| {
+                        is_match(*k) && (0..*k).all(|i| elements.element_eq(progress - k + i, i))
+                    })
+                    .map(|k| k + 1)
+                    .unwrap_or(0);
+                state.last_sequence_press = if state.sequence_progress > 0 {
+                    Some(timestamp)
+                } else {
+                    None
+                };
+                if state.sequence_progress == state.press_times.len() {
+                    state.sequence_progress = 0;
+                    state.last_sequence_press = None;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        if !is_complete {
+            return None;
+        }
+        state.is_on = true;
+        Some(ControlValue::AbsoluteContinuous(UnitValue::MAX))
+    }
+}
+
+/// Removes duplicates while preserving the order. Doesn't need to be fast, it's called when
+/// creating the source only.
+fn remove_duplicates<T: PartialEq>(items: &mut Vec<T>) {
+    let mut unique = Vec::with_capacity(items.len());
+    for item in items.drain(..) {
+        if !unique.contains(&item) {
+            unique.push(item);
+        }
+    }
+    *items = unique;
+}
+
+fn off() -> ControlValue {
+    ControlValue::AbsoluteContinuous(UnitValue::MIN)
+}
+
+fn is_within_window(
+    window: Duration,
+    earlier: ControlEventTimestamp,
+    later: ControlEventTimestamp,
+) -> bool {
+    window.is_zero() || later - earlier <= window
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_learn::AbstractTimestamp;
+    use helgoboss_midi::test_util::*;
+
+    #[test]
+    fn exact() {
+        // Given
+        let mut source = note_source(ChordMode::Exact, Duration::ZERO);
+        // When
+        let first = feed(&mut source, note_on(0, 60, 100));
+        let second = feed(&mut source, note_on(0, 64, 100));
+        let third = feed(&mut source, note_on(0, 67, 100));
+        let fourth = feed(&mut source, note_on(0, 72, 100));
+        let fifth = feed(&mut source, note_off(0, 72, 0));
+        let sixth = feed(&mut source, note_off(0, 64, 0));
+        let seventh = feed(&mut source, note_on(0, 64, 100));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, None);
+        assert_eq!(third, Some(on()));
+        // Additional note breaks exact chord
+        assert_eq!(fourth, Some(off()));
+        assert_eq!(fifth, None);
+        assert_eq!(sixth, None);
+        assert_eq!(seventh, Some(on()));
+    }
+
+    #[test]
+    fn contains() {
+        // Given
+        let mut source = note_source(ChordMode::Contains, Duration::ZERO);
+        // When
+        feed(&mut source, note_on(0, 50, 100));
+        feed(&mut source, note_on(0, 67, 100));
+        feed(&mut source, note_on(0, 60, 100));
+        let first = feed(&mut source, note_on(0, 64, 100));
+        let second = feed(&mut source, note_off(0, 50, 0));
+        let third = feed(&mut source, note_off(0, 67, 0));
+        // Then
+        assert_eq!(first, Some(on()));
+        assert_eq!(second, None);
+        assert_eq!(third, Some(off()));
+    }
+
+    #[test]
+    fn sequence() {
+        // Given
+        let mut source = note_source(ChordMode::Sequence, Duration::ZERO);
+        // When
+        feed(&mut source, note_on(0, 64, 100));
+        feed(&mut source, note_off(0, 64, 0));
+        feed(&mut source, note_on(0, 60, 100));
+        feed(&mut source, note_off(0, 60, 0));
+        feed(&mut source, note_on(0, 64, 100));
+        feed(&mut source, note_off(0, 64, 0));
+        let first = feed(&mut source, note_on(0, 67, 100));
+        let second = feed(&mut source, note_off(0, 67, 0));
+        // Then
+        assert_eq!(first, Some(on()));
+        assert_eq!(second, Some(off()));
+    }
+
+    #[test]
+    fn sequence_with_repeated_notes() {
+        // Given
+        let mut source = notes_source(ChordMode::Sequence, &[60, 60, 67]);
+        // When
+        feed(&mut source, note_on(0, 60, 100));
+        feed(&mut source, note_off(0, 60, 0));
+        let first = feed(&mut source, note_on(0, 60, 100));
+        feed(&mut source, note_off(0, 60, 0));
+        let second = feed(&mut source, note_on(0, 67, 100));
+        let third = feed(&mut source, note_off(0, 67, 0));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, Some(on()));
+        assert_eq!(third, Some(off()));
+    }
+
+    #[test]
+    fn sequence_with_repeated_notes_starts_over() {
+        // Given
+        let mut source = notes_source(ChordMode::Sequence, &[60, 60, 67]);
+        // When
+        feed(&mut source, note_on(0, 60, 100));
+        feed(&mut source, note_on(0, 64, 100));
+        feed(&mut source, note_on(0, 60, 100));
+        let first = feed(&mut source, note_on(0, 67, 100));
+        feed(&mut source, note_on(0, 60, 100));
+        feed(&mut source, note_on(0, 60, 100));
+        feed(&mut source, note_on(0, 60, 100));
+        let second = feed(&mut source, note_on(0, 67, 100));
+        // Then
+        // The foreign note interrupted the sequence
+        assert_eq!(first, None);
+        // The last two Cs and the G form the sequence
+        assert_eq!(second, Some(on()));
+    }
+
+    #[test]
+    fn exact_with_repeated_notes() {
+        // Given
+        let mut source = notes_source(ChordMode::Exact, &[60, 60, 67]);
+        // When
+        let first = feed(&mut source, note_on(0, 60, 100));
+        let second = feed(&mut source, note_on(0, 67, 100));
+        let third = feed(&mut source, note_off(0, 60, 0));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, Some(on()));
+        assert_eq!(third, Some(off()));
+    }
+
+    #[test]
+    fn contains_with_repeated_notes() {
+        // Given
+        let mut source = notes_source(ChordMode::Contains, &[64, 60, 64]);
+        // When
+        feed(&mut source, note_on(0, 50, 100));
+        feed(&mut source, note_on(0, 64, 100));
+        let first = feed(&mut source, note_on(0, 60, 100));
+        // Then
+        assert_eq!(first, Some(on()));
+        assert_eq!(
+            source.elements(),
+            &ChordElements::Notes {
+                channel: Some(Channel::new(0)),
+                key_numbers: vec![U7::new(64), U7::new(60)],
+            }
+        );
+    }
+
+    #[test]
+    fn time_window() {
+        // Given
+        let mut source = note_source(ChordMode::Contains, Duration::from_millis(1));
+        // When
+        feed(&mut source, note_on(0, 60, 100));
+        feed(&mut source, note_on(0, 64, 100));
+        std::thread::sleep(Duration::from_millis(20));
+        let first = feed(&mut source, note_on(0, 67, 100));
+        // Then
+        assert_eq!(first, None);
+    }
+
+    #[test]
+    fn ignore_other_channels() {
+        // Given
+        let mut source = note_source(ChordMode::Exact, Duration::ZERO);
+        // When
+        feed(&mut source, note_on(1, 60, 100));
+        feed(&mut source, note_on(1, 64, 100));
+        let first = feed(&mut source, note_on(1, 67, 100));
+        // Then
+        assert_eq!(first, None);
+    }
+
+    fn note_source(mode: ChordMode, time_window: Duration) -> ChordSource {
+        let elements = ChordElements::Notes {
+            channel: Some(Channel::new(0)),
+            key_numbers: vec![U7::new(60), U7::new(64), U7::new(67)],
+        };
+        ChordSource::new(elements, mode, time_window)
+    }
+
+    fn notes_source(mode: ChordMode, key_numbers: &[u8]) -> ChordSource {
+        let elements = ChordElements::Notes {
+            channel: Some(Channel::new(0)),
+            key_numbers: key_numbers.iter().map(|k| U7::new(*k)).collect(),
+        };
+        ChordSource::new(elements, mode, Duration::ZERO)
+    }
+
+    fn feed(source: &mut ChordSource, msg: RawShortMessage) -> Option<ControlValue> {
+        let value = MidiSourceValue::Plain(msg);
+        source.control_midi(ControlEvent::new(&value, ControlEventTimestamp::now()))
+    }
+
+    fn on() -> ControlValue {
+        ControlValue::AbsoluteContinuous(UnitValue::MAX)
+    }
+}
@@ -2,12 +2,13 @@ use crate::base::{metrics_util, Global, NamedChannelSender, SenderToNormalThread
 use crate::domain::{
     BackboneState, CompoundMappingSource, ControlEvent, ControlEventTimestamp,
     DeviceChangeDetector, DeviceControlInput, DeviceFeedbackOutput, DomainEventHandler,
-    EelTransformation, FeedbackOutput, FeedbackRealTimeTask, FinalSourceFeedbackValue, InstanceId,
-    LifecycleMidiData, MainProcessor, MidiCaptureSender, MidiDeviceChangePayload,
-    MonitoringFxChainChangeDetector, NormalRealTimeTask, OscDeviceId, OscInputDevice,
-    OscScanResult, QualifiedClipMatrixEvent, RealTimeCompoundMappingTarget, RealTimeMapping,
-    RealTimeMappingUpdate, RealTimeTargetUpdate, ReaperConfigChangeDetector, ReaperMessage,
-    ReaperTarget, SharedMainProcessors, SharedRealTimeProcessor, TouchedTrackParameterType,
+    FeedbackOutput, FeedbackRealTimeTask, FinalSourceFeedbackValue, FlexibleTransformation,
+    InstanceId, ItemChangeDetector, LifecycleMidiData, MainProcessor, MidiCaptureSender,
+    MidiDeviceChangePayload, MonitoringFxChainChangeDetector, NormalRealTimeTask, OscDeviceId,
+    OscInputDevice, OscScanResult, QualifiedClipMatrixEvent, RealTimeCompoundMappingTarget,
+    RealTimeMapping, RealTimeMappingUpdate, RealTimeTargetUpdate, ReaperConfigChangeDetector,
+    ReaperMessage, ReaperTarget, SharedMainProcessors, SharedRealTimeProcessor,
+    TouchedTrackParameterType,
 };
 use crossbeam_channel::Receiver;
 use helgoboss_learn::{AbstractTimestamp, ModeGarbage, RawMidiEvents};
@@ -64,6 +65,7 @@ pub struct RealearnControlSurfaceMiddleware<EH: DomainEventHandler> {
     garbage_receiver: crossbeam_channel::Receiver<Garbage>,
     device_change_detector: DeviceChangeDetector,
     reaper_config_change_detector: ReaperConfigChangeDetector,
+    item_change_detector: ItemChangeDetector,
     control_surface_event_sender: SenderToNormalThread<ControlSurfaceEvent<'static>>,
     control_surface_event_receiver: crossbeam_channel::Receiver<ControlSurfaceEvent<'static>>,
 }
@@ -73,7 +75,7 @@ pub enum Garbage {
     RealTimeProcessor(SharedRealTimeProcessor),
     LifecycleMidiData(LifecycleMidiData),
     ResolvedTarget(Option<RealTimeCompoundMappingTarget>),
-    Mode(ModeGarbage<EelTransformation>),
+    Mode(ModeGarbage<FlexibleTransformation>),
     MappingSource(CompoundMappingSource),
     RealTimeMappings(Vec<RealTimeMapping>),
     BoxedRealTimeMapping(Box<Option<RealTimeMapping>>),
@@ -126,6 +128,11 @@ pub enum AdditionalFeedbackEvent {
     ///
     /// REAPER itself doesn't fire any change event in this case.
     FocusSwitchedBetweenMainAndFx,
+    /// Something about the media items of a project might have changed (e.g. item properties,
+    /// item selection or edit cursor position).
+    ///
+    /// REAPER itself doesn't fire any change event in this case.
+    ItemsChanged(ItemsChangedEvent),
 }
 
 #[derive(Debug)]
@@ -164,6 +171,11 @@ pub struct BeatChangedEvent {
     pub new_value: PositionInSeconds,
 }
 
+#[derive(Debug)]
+pub struct ItemsChangedEvent {
+    pub project: Project,
+}
+
 #[derive(Debug)]
 pub struct ActionInvokedEvent {
     pub command_id: CommandId,
@@ -234,6 +246,7 @@ impl<EH: DomainEventHandler> RealearnControlSurfaceMiddleware<EH> {
             garbage_receiver,
             device_change_detector,
             reaper_config_change_detector: Default::default(),
+            item_change_detector: Default::default(),
             control_surface_event_sender,
             control_surface_event_receiver,
         }
@@ -252,6 +265,7 @@ impl<EH: DomainEventHandler> RealearnControlSurfaceMiddleware<EH> {
         self.detect_reaper_config_changes();
         self.emit_focus_switch_between_main_and_fx_as_feedback_event();
         self.emit_beats_as_feedback_events();
+        self.emit_item_changes_as_feedback_event();
         self.emit_device_changes_as_reaper_source_messages(timestamp);
         self.process_incoming_osc_messages(timestamp);
         self.poll_clip_matrixes();
@@ -563,6 +577,19 @@ impl<EH: DomainEventHandler> RealearnControlSurfaceMiddleware<EH> {
         }
     }
 
+    fn emit_item_changes_as_feedback_event(&mut self) {
+        let main_processors = self.main_processors.borrow();
+        let projects = main_processors
+            .iter()
+            .map(|p| p.project_or_current_project());
+        for project in self.item_change_detector.poll_for_changes(projects) {
+            let event = AdditionalFeedbackEvent::ItemsChanged(ItemsChangedEvent { project });
+            for p in &*main_processors {
+                p.process_additional_feedback_event(&event);
+            }
+        }
+    }
+
     fn emit_beats_as_feedback_events(&mut self) {
         for project in Reaper::get().projects() {
             let reference_pos = if project.is_playing() {
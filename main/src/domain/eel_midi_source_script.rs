@@ -1,7 +1,9 @@
 use crate::base::eel;
+use crate::domain::MidiSourceControlScript;
 use helgoboss_learn::{
-    create_raw_midi_events_singleton, AbsoluteValue, FeedbackValue, MidiSourceAddress,
-    MidiSourceScript, MidiSourceScriptOutcome, RawMidiEvent,
+    create_raw_midi_events_singleton, AbsoluteValue, ControlValue, DiscreteIncrement,
+    FeedbackValue, MidiSourceAddress, MidiSourceScript, MidiSourceScriptOutcome, RawMidiEvent,
+    UnitValue,
 };
 
 use std::sync::Arc;
@@ -94,6 +96,81 @@ impl MidiSourceScript for EelMidiSourceScript {
     }
 }
 
+#[derive(Debug)]
+struct EelControlUnit {
+    // Declared above VM in order to be dropped before VM is dropped.
+    program: eel::Program,
+    vm: eel::Vm,
+    msg_size: eel::Variable,
+    value: eel::Variable,
+    increment: eel::Variable,
+}
+
+/// Decodes incoming MIDI messages via EEL.
+///
+/// The message bytes are available in memory (`0[]`, `1[]`, ...) and its size in `msg_size`.
+/// The script either assigns an absolute value between 0.0 and 1.0 to `value` or a non-zero
+/// relative increment to `increment`. Leaving both at `none` means the message doesn't match.
+#[derive(Clone, Debug)]
+pub struct EelMidiSourceControlScript {
+    // Arc because EelControlUnit is not cloneable
+    eel_unit: Arc<EelControlUnit>,
+}
+
+impl EelMidiSourceControlScript {
+    pub fn compile(eel_script: &str) -> Result<Self, String> {
+        if eel_script.trim().is_empty() {
+            return Err("script empty".to_string());
+        }
+        let vm = eel::Vm::new();
+        let program = vm.compile(eel_script)?;
+        // Allocate memory now, not later in the real-time thread
+        vm.write_mem(0, [0.0; RawMidiEvent::MAX_LENGTH]);
+        vm.register_and_set_variable("none", NONE);
+        let eel_unit = EelControlUnit {
+            program,
+            msg_size: vm.register_variable("msg_size"),
+            value: vm.register_variable("value"),
+            increment: vm.register_variable("increment"),
+            vm,
+        };
+        Ok(Self {
+            eel_unit: Arc::new(eel_unit),
+        })
+    }
+}
+
+impl MidiSourceControlScript for EelMidiSourceControlScript {
+    fn execute(&self, bytes: &[u8]) -> Result<Option<ControlValue>, &'static str> {
+        let (value, increment) = unsafe {
+            let written_count = self
+                .eel_unit
+                .vm
+                .write_mem(0, bytes.iter().map(|b| *b as f64));
+            self.eel_unit.msg_size.set(written_count as f64);
+            self.eel_unit.value.set(NONE);
+            self.eel_unit.increment.set(NONE);
+            self.eel_unit.program.execute();
+            (self.eel_unit.value.get(), self.eel_unit.increment.get())
+        };
+        let control_value = if increment != NONE {
+            let increment = increment.round() as i32;
+            if increment == 0 {
+                return Err("increment must not be zero");
+            }
+            ControlValue::RelativeDiscrete(DiscreteIncrement::new(increment))
+        } else if value != NONE {
+            ControlValue::AbsoluteContinuous(UnitValue::new_clamped(value))
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(control_value))
+    }
+}
+
+/// Exposed as variable `none`.
+const NONE: f64 = f64::MIN;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,4 +203,31 @@ mod tests {
             vec![RawMidiEvent::try_from_slice(0, &[0xb0, 0x4b, 5]).unwrap()]
         );
     }
+
+    #[test]
+    fn control() {
+        // Given
+        let text = "
+            0[] == 0xf0 && 1[] == 0x47 && msg_size == 5 ? (
+                value = 3[] / 127;
+            ) : 0[] == 0xb0 && 1[] == 0x10 ? (
+                increment = 2[] < 64 ? 2[] : 2[] - 128;
+            );
+        ";
+        let script = EelMidiSourceControlScript::compile(text).unwrap();
+        // When
+        let sys_ex_outcome = script.execute(&[0xf0, 0x47, 0x00, 127, 0xf7]).unwrap();
+        let cc_outcome = script.execute(&[0xb0, 0x10, 126]).unwrap();
+        let unmatched_outcome = script.execute(&[0x90, 0x10, 100]).unwrap();
+        // Then
+        assert_eq!(
+            sys_ex_outcome,
+            Some(ControlValue::AbsoluteContinuous(UnitValue::MAX))
+        );
+        assert_eq!(
+            cc_outcome,
+            Some(ControlValue::RelativeDiscrete(DiscreteIncrement::new(-2)))
+        );
+        assert_eq!(unmatched_outcome, None);
+    }
 }
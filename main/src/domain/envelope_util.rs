@@ -0,0 +1,339 @@
+//! Access to envelope and automation item functions which are not (yet) exposed by the high-level
+//! API.
+use c_str_macro::c_str;
+use realearn_api::persistence::{RouteEnvelopeKind, TrackEnvelopeKind};
+use reaper_high::{Fx, FxParameter, Project, Reaper, Track, TrackRoute};
+use reaper_medium::{PositionInSeconds, TrackEnvelope, TrackSendDirection};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr::null_mut;
+
+pub const ENVELOPE_ARM: &CStr = c_str!("ARM");
+pub const ENVELOPE_VISIBLE: &CStr = c_str!("VISIBLE");
+pub const AUTOMATION_ITEM_SELECTED: &CStr = c_str!("D_UISEL");
+
+/// An envelope, identified by the object which it belongs to.
+///
+/// We don't keep the envelope pointer itself because REAPER creates and destroys envelopes
+/// whenever the user adds or removes automation. It's looked up on demand instead.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ResolvedEnvelope {
+    Track {
+        track: Track,
+        kind: TrackEnvelopeKind,
+    },
+    FxParameter(FxParameter),
+    Route {
+        route: TrackRoute,
+        kind: RouteEnvelopeKind,
+    },
+}
+
+impl ResolvedEnvelope {
+    pub fn is_available(&self) -> bool {
+        match self {
+            ResolvedEnvelope::Track { track, .. } => track.is_available(),
+            ResolvedEnvelope::FxParameter(param) => param.is_available(),
+            ResolvedEnvelope::Route { route, .. } => route.is_available(),
+        }
+    }
+
+    pub fn track(&self) -> Option<&Track> {
+        match self {
+            ResolvedEnvelope::Track { track, .. } => Some(track),
+            ResolvedEnvelope::FxParameter(param) => param.fx().track(),
+            ResolvedEnvelope::Route { route, .. } => Some(route.track()),
+        }
+    }
+
+    pub fn project(&self) -> Option<Project> {
+        Some(self.track()?.project())
+    }
+
+    pub fn fx(&self) -> Option<&Fx> {
+        match self {
+            ResolvedEnvelope::FxParameter(param) => Some(param.fx()),
+            _ => None,
+        }
+    }
+
+    pub fn route(&self) -> Option<&TrackRoute> {
+        match self {
+            ResolvedEnvelope::Route { route, .. } => Some(route),
+            _ => None,
+        }
+    }
+
+    /// Returns the envelope if it exists.
+    ///
+    /// With `create` set, a missing FX parameter envelope is created. Track and route envelopes
+    /// can't be created this way.
+    pub fn envelope(&self, create: bool) -> Option<TrackEnvelope> {
+        let low = Reaper::get().medium_reaper().low();
+        let raw_envelope = match self {
+            ResolvedEnvelope::Track { track, kind } => unsafe {
+                low.GetTrackEnvelopeByChunkName(
+                    track.raw().as_ptr(),
+                    track_envelope_chunk_name(*kind).as_ptr(),
+                )
+            },
+            ResolvedEnvelope::FxParameter(param) => {
+                let fx = param.fx();
+                let track = fx.track()?;
+                unsafe {
+                    low.GetFXEnvelope(
+                        track.raw().as_ptr(),
+                        fx.query_index().to_raw(),
+                        param.index() as i32,
+                        create,
+                    )
+                }
+            }
+            ResolvedEnvelope::Route { route, kind } => {
+                let key = route_envelope_key(*kind);
+                let (category, index) = route_category_and_index(route);
+                unsafe {
+                    low.GetSetTrackSendInfo(
+                        route.track().raw().as_ptr(),
+                        category,
+                        index,
+                        key.as_ptr(),
+                        null_mut(),
+                    ) as _
+                }
+            }
+        };
+        TrackEnvelope::new(raw_envelope)
+    }
+
+    /// Returns the current value of the parameter which is automated by the envelope, expressed
+    /// in the unit of the envelope points.
+    pub fn current_parameter_value(&self, envelope: TrackEnvelope) -> Option<f64> {
+        let low = Reaper::get().medium_reaper().low();
+        let value = match self {
+            ResolvedEnvelope::Track { track, kind } => {
+                let key = match kind {
+                    TrackEnvelopeKind::Volume => c_str!("D_VOL"),
+                    TrackEnvelopeKind::Pan => c_str!("D_PAN"),
+                    TrackEnvelopeKind::Width => c_str!("D_WIDTH"),
+                };
+                let value =
+                    unsafe { low.GetMediaTrackInfo_Value(track.raw().as_ptr(), key.as_ptr()) };
+                match kind {
+                    TrackEnvelopeKind::Volume => scale_to_envelope_mode(envelope, value),
+                    TrackEnvelopeKind::Pan => convert_pan_to_envelope_value(value),
+                    TrackEnvelopeKind::Width => value,
+                }
+            }
+            ResolvedEnvelope::FxParameter(param) => {
+                let fx = param.fx();
+                let track = fx.track()?;
+                let mut min = 0.0;
+                let mut max = 0.0;
+                unsafe {
+                    low.TrackFX_GetParam(
+                        track.raw().as_ptr(),
+                        fx.query_index().to_raw(),
+                        param.index() as i32,
+                        &mut min,
+                        &mut max,
+                    )
+                }
+            }
+            ResolvedEnvelope::Route { route, kind } => {
+                let key = match kind {
+                    RouteEnvelopeKind::Volume => c_str!("D_VOL"),
+                    RouteEnvelopeKind::Pan => c_str!("D_PAN"),
+                };
+                let (category, index) = route_category_and_index(route);
+                let value = unsafe {
+                    low.GetTrackSendInfo_Value(
+                        route.track().raw().as_ptr(),
+                        category,
+                        index,
+                        key.as_ptr(),
+                    )
+                };
+                match kind {
+                    RouteEnvelopeKind::Volume => scale_to_envelope_mode(envelope, value),
+                    RouteEnvelopeKind::Pan => convert_pan_to_envelope_value(value),
+                }
+            }
+        };
+        Some(value)
+    }
+}
+
+pub fn get_envelope_name(envelope: TrackEnvelope) -> Option<String> {
+    let mut buffer = [0 as c_char; 256];
+    let successful = unsafe {
+        Reaper::get().medium_reaper().low().GetEnvelopeName(
+            envelope.as_ptr(),
+            buffer.as_mut_ptr(),
+            buffer.len() as _,
+        )
+    };
+    if !successful {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Reads a boolean envelope property such as [`ENVELOPE_ARM`].
+pub fn get_envelope_flag(envelope: TrackEnvelope, key: &CStr) -> bool {
+    let mut buffer = [0 as c_char; 64];
+    let successful = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetSetEnvelopeInfo_String(envelope.as_ptr(), key.as_ptr(), buffer.as_mut_ptr(), false)
+    };
+    if !successful {
+        return false;
+    }
+    let value = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+    value.to_bytes() == b"1"
+}
+
+/// Writes a boolean envelope property such as [`ENVELOPE_ARM`].
+pub fn set_envelope_flag(
+    envelope: TrackEnvelope,
+    key: &CStr,
+    value: bool,
+) -> Result<(), &'static str> {
+    let mut value = if value { *b"1\0" } else { *b"0\0" };
+    let successful = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetSetEnvelopeInfo_String(
+                envelope.as_ptr(),
+                key.as_ptr(),
+                value.as_mut_ptr() as *mut c_char,
+                true,
+            )
+    };
+    if !successful {
+        return Err("couldn't set envelope property");
+    }
+    // Visibility changes affect the track layout.
+    Reaper::get()
+        .medium_reaper()
+        .low()
+        .TrackList_AdjustWindows(false);
+    update_arrange();
+    Ok(())
+}
+
+/// Inserts a point with the given value (expressed in the unit of the envelope points).
+pub fn insert_envelope_point(
+    envelope: TrackEnvelope,
+    pos: PositionInSeconds,
+    value: f64,
+) -> Result<(), &'static str> {
+    let successful = unsafe {
+        Reaper::get().medium_reaper().low().InsertEnvelopePoint(
+            envelope.as_ptr(),
+            pos.get(),
+            value,
+            0,
+            0.0,
+            false,
+            // Null means that the points get sorted right away.
+            null_mut(),
+        )
+    };
+    if !successful {
+        return Err("couldn't insert envelope point");
+    }
+    update_arrange();
+    Ok(())
+}
+
+pub fn get_automation_item_count(envelope: TrackEnvelope) -> u32 {
+    let count = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .CountAutomationItems(envelope.as_ptr())
+    };
+    count.max(0) as u32
+}
+
+pub fn get_automation_item_info_value(envelope: TrackEnvelope, index: u32, key: &CStr) -> f64 {
+    unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetSetAutomationItemInfo(envelope.as_ptr(), index as i32, key.as_ptr(), 0.0, false)
+    }
+}
+
+pub fn set_automation_item_info_value(envelope: TrackEnvelope, index: u32, key: &CStr, value: f64) {
+    unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetSetAutomationItemInfo(envelope.as_ptr(), index as i32, key.as_ptr(), value, true);
+    }
+}
+
+/// Returns the index of the first selected automation item.
+pub fn get_first_selected_automation_item(envelope: TrackEnvelope) -> Option<u32> {
+    (0..get_automation_item_count(envelope))
+        .find(|i| get_automation_item_info_value(envelope, *i, AUTOMATION_ITEM_SELECTED) != 0.0)
+}
+
+/// Selects the given automation item and unselects all others on the same envelope.
+///
+/// `None` unselects all automation items.
+pub fn select_automation_item_exclusively(envelope: TrackEnvelope, index: Option<u32>) {
+    for i in 0..get_automation_item_count(envelope) {
+        let value = if Some(i) == index { 1.0 } else { 0.0 };
+        set_automation_item_info_value(envelope, i, AUTOMATION_ITEM_SELECTED, value);
+    }
+    update_arrange();
+}
+
+fn track_envelope_chunk_name(kind: TrackEnvelopeKind) -> &'static CStr {
+    match kind {
+        TrackEnvelopeKind::Volume => c_str!("<VOLENV2"),
+        TrackEnvelopeKind::Pan => c_str!("<PANENV2"),
+        TrackEnvelopeKind::Width => c_str!("<WIDTHENV2"),
+    }
+}
+
+fn route_envelope_key(kind: RouteEnvelopeKind) -> &'static CStr {
+    match kind {
+        RouteEnvelopeKind::Volume => c_str!("P_ENV:<VOLENV"),
+        RouteEnvelopeKind::Pan => c_str!("P_ENV:<PANENV"),
+    }
+}
+
+/// Returns the category and index as expected by the `*TrackSendInfo*` functions.
+fn route_category_and_index(route: &TrackRoute) -> (i32, i32) {
+    let category = match route.direction() {
+        TrackSendDirection::Receive => -1,
+        // Hardware outputs come before track sends in this category.
+        TrackSendDirection::Send => 0,
+    };
+    (category, route.index() as i32)
+}
+
+fn scale_to_envelope_mode(envelope: TrackEnvelope, value: f64) -> f64 {
+    let low = Reaper::get().medium_reaper().low();
+    unsafe {
+        let scaling_mode = low.GetEnvelopeScalingMode(envelope.as_ptr());
+        low.ScaleToEnvelopeMode(scaling_mode, value)
+    }
+}
+
+/// Pan envelopes have the opposite direction: Positive values mean left.
+fn convert_pan_to_envelope_value(pan: f64) -> f64 {
+    -pan
+}
+
+fn update_arrange() {
+    Reaper::get().medium_reaper().low().UpdateArrange();
+}
@@ -1,5 +1,8 @@
-use crate::domain::{EelMidiSourceScript, LuaMidiSourceScript};
-use helgoboss_learn::{FeedbackValue, MidiSourceScript, MidiSourceScriptOutcome};
+use crate::domain::{
+    EelMidiSourceControlScript, EelMidiSourceScript, LuaMidiSourceControlScript,
+    LuaMidiSourceScript, MidiSourceControlScript,
+};
+use helgoboss_learn::{ControlValue, FeedbackValue, MidiSourceScript, MidiSourceScriptOutcome};
 
 #[derive(Clone, Debug)]
 pub enum FlexibleMidiSourceScript<'a> {
@@ -15,3 +18,26 @@ impl<'a> MidiSourceScript for FlexibleMidiSourceScript<'a> {
         }
     }
 }
+
+#[derive(Clone, Debug)]
+pub enum FlexibleMidiSourceControlScript {
+    Eel(EelMidiSourceControlScript),
+    Lua(LuaMidiSourceControlScript),
+}
+
+impl FlexibleMidiSourceControlScript {
+    /// Only EEL scripts can be executed in the real-time thread. Lua allocates and its state
+    /// needs to be locked, so Lua control scripts are executed in the main thread.
+    pub fn is_real_time_capable(&self) -> bool {
+        matches!(self, FlexibleMidiSourceControlScript::Eel(_))
+    }
+}
+
+impl MidiSourceControlScript for FlexibleMidiSourceControlScript {
+    fn execute(&self, bytes: &[u8]) -> Result<Option<ControlValue>, &'static str> {
+        match self {
+            FlexibleMidiSourceControlScript::Eel(s) => s.execute(bytes),
+            FlexibleMidiSourceControlScript::Lua(s) => s.execute(bytes),
+        }
+    }
+}
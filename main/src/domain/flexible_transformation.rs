@@ -0,0 +1,88 @@
+use crate::domain::{AdditionalTransformationInput, EelTransformation, LuaTransformation, Script};
+use helgoboss_learn::{Transformation, TransformationInput, TransformationOutput, UnitValue};
+use realearn_api::persistence::TransformationLanguage;
+use std::error::Error;
+
+#[derive(Clone, Debug)]
+pub enum FlexibleTransformation {
+    Eel(EelTransformation),
+    Lua(LuaTransformation),
+}
+
+impl FlexibleTransformation {
+    pub fn compile_for_control(
+        language: TransformationLanguage,
+        script: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        let t = match language {
+            TransformationLanguage::Eel => {
+                Self::Eel(EelTransformation::compile_for_control(script)?)
+            }
+            TransformationLanguage::Lua => {
+                Self::Lua(LuaTransformation::compile_for_control(script)?)
+            }
+        };
+        Ok(t)
+    }
+
+    pub fn compile_for_feedback(
+        language: TransformationLanguage,
+        script: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        let t = match language {
+            TransformationLanguage::Eel => {
+                Self::Eel(EelTransformation::compile_for_feedback(script)?)
+            }
+            TransformationLanguage::Lua => {
+                Self::Lua(LuaTransformation::compile_for_feedback(script)?)
+            }
+        };
+        Ok(t)
+    }
+}
+
+impl FlexibleTransformation {
+    /// Only EEL transformations can be executed in the real-time thread. Lua allocates and needs
+    /// its state to be locked.
+    pub fn is_real_time_capable(&self) -> bool {
+        matches!(self, FlexibleTransformation::Eel(_))
+    }
+}
+
+impl Transformation for FlexibleTransformation {
+    type AdditionalInput = AdditionalTransformationInput;
+
+    fn transform(
+        &self,
+        input: TransformationInput<f64>,
+        output_value: f64,
+        additional_input: AdditionalTransformationInput,
+    ) -> Result<TransformationOutput<f64>, &'static str> {
+        match self {
+            FlexibleTransformation::Eel(t) => t.transform(input, output_value, additional_input),
+            FlexibleTransformation::Lua(t) => t.transform(input, output_value, additional_input),
+        }
+    }
+
+    fn wants_to_be_polled(&self) -> bool {
+        match self {
+            FlexibleTransformation::Eel(t) => t.wants_to_be_polled(),
+            FlexibleTransformation::Lua(t) => t.wants_to_be_polled(),
+        }
+    }
+}
+
+impl Script for FlexibleTransformation {
+    fn uses_time(&self) -> bool {
+        self.wants_to_be_polled()
+    }
+
+    fn evaluate(
+        &self,
+        input: TransformationInput<UnitValue>,
+        output_value: UnitValue,
+        additional_input: AdditionalTransformationInput,
+    ) -> Result<TransformationOutput<UnitValue>, &'static str> {
+        self.transform_continuous(input, output_value, additional_input)
+    }
+}
@@ -0,0 +1,338 @@
+use crate::domain::{tempo_unit_value, ControlEvent, ControlEventTimestamp};
+use helgoboss_learn::{ControlValue, UnitValue};
+use realearn_api::persistence::Gesture;
+use reaper_high::Tempo;
+use reaper_medium::Bpm;
+use std::time::Duration;
+
+/// If no further press follows within this time after a release, the tap sequence is over.
+const MULTI_TAP_TIMEOUT: Duration = Duration::from_millis(300);
+/// A press which is held at least this long is a long press.
+const LONG_PRESS_TIMEOUT: Duration = Duration::from_millis(500);
+/// Rate at which "Press and hold (repeat)" keeps firing.
+const REPEAT_INTERVAL: Duration = Duration::from_millis(100);
+/// Taps which are further apart than this start a new tap tempo measurement.
+const MAX_TAP_TEMPO_INTERVAL: Duration = Duration::from_secs(2);
+/// Number of most recent tap intervals which are averaged for tap tempo.
+const TAP_TEMPO_INTERVAL_COUNT: usize = 4;
+
+/// Glue stage which classifies button press sequences into gestures.
+///
+/// Each recognizer looks out for exactly one gesture and only lets something through if it
+/// detects that gesture. Having multiple mappings with the same source but different gestures
+/// makes it possible to control different targets with the same button, depending on how it's
+/// pressed. Decisions which depend on the absence of further presses (e.g. tap vs. double tap)
+/// are made when polling.
+///
+/// The state doesn't allocate, so this can be cloned into the real-time processor as part of the
+/// mapping. It's only used in the main processor though.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GestureRecognizer {
+    gesture: Gesture,
+    /// Only relevant for [`Gesture::MultiTap`].
+    tap_count: u32,
+    state: GestureState,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+struct GestureState {
+    /// Time of the current press, `None` if the button is not held.
+    press_time: Option<ControlEventTimestamp>,
+    /// Number of short presses in the current tap sequence.
+    tap_count: u32,
+    /// Time of the last release within the current tap sequence.
+    last_release_time: Option<ControlEventTimestamp>,
+    /// Whether the current press has been classified as long press already.
+    is_long_press: bool,
+    last_repeat_time: Option<ControlEventTimestamp>,
+    last_tempo_tap_time: Option<ControlEventTimestamp>,
+    /// Ring buffer of the most recent tap tempo intervals.
+    tempo_tap_intervals: [Duration; TAP_TEMPO_INTERVAL_COUNT],
+    tempo_tap_interval_count: usize,
+    next_tempo_tap_interval_index: usize,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new(Gesture::Off, 1)
+    }
+}
+
+impl GestureRecognizer {
+    pub fn new(gesture: Gesture, tap_count: u32) -> Self {
+        Self {
+            gesture,
+            tap_count,
+            state: Default::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.gesture != Gesture::Off
+    }
+
+    /// Tap tempo is the only gesture which doesn't need any timeout.
+    pub fn wants_to_be_polled(&self) -> bool {
+        !matches!(self.gesture, Gesture::Off | Gesture::TapTempo)
+    }
+
+    pub fn reset(&mut self) {
+        self.state = Default::default();
+    }
+
+    /// Processes a button press or release.
+    ///
+    /// Returns the control value to be passed to the rest of the glue section if the event
+    /// completes the gesture. Relative control values are not buttons and are filtered out.
+    pub fn control(&mut self, evt: ControlEvent<ControlValue>) -> Option<ControlValue> {
+        if self.gesture == Gesture::Off {
+            return Some(evt.payload());
+        }
+        let is_press = match evt.payload() {
+            ControlValue::AbsoluteContinuous(v) => !v.is_zero(),
+            ControlValue::AbsoluteDiscrete(f) => f.actual() > 0,
+            ControlValue::RelativeDiscrete(_) | ControlValue::RelativeContinuous(_) => return None,
+        };
+        let timestamp = evt.timestamp();
+        let state = &mut self.state;
+        if is_press {
+            if state.press_time.is_some() {
+                // Repeated press without release in between (e.g. velocity change)
+                return None;
+            }
+            state.press_time = Some(timestamp);
+            state.is_long_press = false;
+            state.last_repeat_time = None;
+            if let Some(t) = state.last_release_time {
+                if timestamp - t > MULTI_TAP_TIMEOUT {
+                    // Polling didn't happen in time, the sequence is over anyway.
+                    state.tap_count = 0;
+                    state.last_release_time = None;
+                }
+            }
+            if self.gesture == Gesture::TapTempo {
+                return self.tap_tempo(timestamp);
+            }
+            None
+        } else {
+            state.press_time.take()?;
+            if state.is_long_press {
+                state.is_long_press = false;
+                if matches!(
+                    self.gesture,
+                    Gesture::LongPress | Gesture::PressAndHoldRepeat
+                ) {
+                    return Some(off());
+                }
+                return None;
+            }
+            state.tap_count += 1;
+            state.last_release_time = Some(timestamp);
+            None
+        }
+    }
+
+    /// Checks if any timeout has been reached which completes the gesture.
+    pub fn poll(&mut self, timestamp: ControlEventTimestamp) -> Option<ControlValue> {
+        let state = &mut self.state;
+        if let Some(press_time) = state.press_time {
+            if !state.is_long_press {
+                if timestamp - press_time < LONG_PRESS_TIMEOUT {
+                    return None;
+                }
+                // A long press ends any preceding tap sequence.
+                state.is_long_press = true;
+                state.tap_count = 0;
+                state.last_release_time = None;
+                return match self.gesture {
+                    Gesture::LongPress => Some(on()),
+                    Gesture::PressAndHoldRepeat => {
+                        state.last_repeat_time = Some(timestamp);
+                        Some(on())
+                    }
+                    _ => None,
+                };
+            }
+            if self.gesture != Gesture::PressAndHoldRepeat {
+                return None;
+            }
+            let last_repeat_time = state.last_repeat_time?;
+            if timestamp - last_repeat_time < REPEAT_INTERVAL {
+                return None;
+            }
+            state.last_repeat_time = Some(timestamp);
+            return Some(on());
+        }
+        let last_release_time = state.last_release_time?;
+        if timestamp - last_release_time < MULTI_TAP_TIMEOUT {
+            return None;
+        }
+        let tap_count = state.tap_count;
+        state.tap_count = 0;
+        state.last_release_time = None;
+        if Some(tap_count) == self.required_tap_count() {
+            Some(on())
+        } else {
+            None
+        }
+    }
+
+    fn required_tap_count(&self) -> Option<u32> {
+        match self.gesture {
+            Gesture::Tap => Some(1),
+            Gesture::DoubleTap => Some(2),
+            Gesture::TripleTap => Some(3),
+            Gesture::MultiTap => Some(self.tap_count.max(1)),
+            _ => None,
+        }
+    }
+
+    /// Returns the normalized tempo value (compatible with the "Project: Set tempo" target).
+    fn tap_tempo(&mut self, timestamp: ControlEventTimestamp) -> Option<ControlValue> {
+        let state = &mut self.state;
+        let last_tap_time = state.last_tempo_tap_time.replace(timestamp)?;
+        let interval = timestamp - last_tap_time;
+        if interval > MAX_TAP_TEMPO_INTERVAL || interval.is_zero() {
+            // Start a new measurement
+            state.tempo_tap_interval_count = 0;
+            state.next_tempo_tap_interval_index = 0;
+            return None;
+        }
+        state.tempo_tap_intervals[state.next_tempo_tap_interval_index] = interval;
+        state.next_tempo_tap_interval_index =
+            (state.next_tempo_tap_interval_index + 1) % TAP_TEMPO_INTERVAL_COUNT;
+        state.tempo_tap_interval_count =
+            (state.tempo_tap_interval_count + 1).min(TAP_TEMPO_INTERVAL_COUNT);
+        let relevant_intervals = &state.tempo_tap_intervals[..state.tempo_tap_interval_count];
+        let interval_sum: Duration = relevant_intervals.iter().sum();
+        let average_secs = interval_sum.as_secs_f64() / relevant_intervals.len() as f64;
+        let bpm = (60.0 / average_secs).clamp(Bpm::MIN.get(), Bpm::MAX.get());
+        let tempo = Tempo::from_bpm(Bpm::try_from(bpm).ok()?);
+        Some(ControlValue::AbsoluteContinuous(tempo_unit_value(tempo)))
+    }
+}
+
+fn on() -> ControlValue {
+    ControlValue::AbsoluteContinuous(UnitValue::MAX)
+}
+
+fn off() -> ControlValue {
+    ControlValue::AbsoluteContinuous(UnitValue::MIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_learn::AbstractTimestamp;
+
+    #[test]
+    fn tap_vs_double_tap() {
+        // Given
+        let mut tap = GestureRecognizer::new(Gesture::Tap, 1);
+        let mut double_tap = GestureRecognizer::new(Gesture::DoubleTap, 1);
+        // When
+        for r in [&mut tap, &mut double_tap] {
+            press(r);
+            release(r);
+            press(r);
+            release(r);
+        }
+        let tap_result_before_timeout = tap.poll(ControlEventTimestamp::now());
+        let double_tap_result_before_timeout = double_tap.poll(ControlEventTimestamp::now());
+        std::thread::sleep(MULTI_TAP_TIMEOUT + Duration::from_millis(50));
+        let tap_result = tap.poll(ControlEventTimestamp::now());
+        let double_tap_result = double_tap.poll(ControlEventTimestamp::now());
+        // Then
+        assert_eq!(tap_result_before_timeout, None);
+        assert_eq!(double_tap_result_before_timeout, None);
+        assert_eq!(tap_result, None);
+        assert_eq!(double_tap_result, Some(on()));
+    }
+
+    #[test]
+    fn multi_tap() {
+        // Given
+        let mut r = GestureRecognizer::new(Gesture::MultiTap, 4);
+        // When
+        for _ in 0..4 {
+            press(&mut r);
+            release(&mut r);
+        }
+        std::thread::sleep(MULTI_TAP_TIMEOUT + Duration::from_millis(50));
+        let result = r.poll(ControlEventTimestamp::now());
+        // Then
+        assert_eq!(result, Some(on()));
+    }
+
+    #[test]
+    fn long_press() {
+        // Given
+        let mut r = GestureRecognizer::new(Gesture::LongPress, 1);
+        // When
+        press(&mut r);
+        std::thread::sleep(LONG_PRESS_TIMEOUT + Duration::from_millis(50));
+        let first = r.poll(ControlEventTimestamp::now());
+        let second = r.poll(ControlEventTimestamp::now());
+        let third = release(&mut r);
+        // Then
+        assert_eq!(first, Some(on()));
+        assert_eq!(second, None);
+        assert_eq!(third, Some(off()));
+    }
+
+    #[test]
+    fn press_and_hold_repeat() {
+        // Given
+        let mut r = GestureRecognizer::new(Gesture::PressAndHoldRepeat, 1);
+        // When
+        press(&mut r);
+        std::thread::sleep(LONG_PRESS_TIMEOUT + Duration::from_millis(50));
+        let first = r.poll(ControlEventTimestamp::now());
+        std::thread::sleep(REPEAT_INTERVAL + Duration::from_millis(20));
+        let second = r.poll(ControlEventTimestamp::now());
+        let third = release(&mut r);
+        let fourth = r.poll(ControlEventTimestamp::now());
+        // Then
+        assert_eq!(first, Some(on()));
+        assert_eq!(second, Some(on()));
+        assert_eq!(third, Some(off()));
+        assert_eq!(fourth, None);
+    }
+
+    #[test]
+    fn long_press_is_no_tap() {
+        // Given
+        let mut r = GestureRecognizer::new(Gesture::Tap, 1);
+        // When
+        press(&mut r);
+        std::thread::sleep(LONG_PRESS_TIMEOUT + Duration::from_millis(50));
+        r.poll(ControlEventTimestamp::now());
+        release(&mut r);
+        std::thread::sleep(MULTI_TAP_TIMEOUT + Duration::from_millis(50));
+        let result = r.poll(ControlEventTimestamp::now());
+        // Then
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn tap_tempo() {
+        // Given
+        let mut r = GestureRecognizer::new(Gesture::TapTempo, 1);
+        // When
+        let first = press(&mut r);
+        release(&mut r);
+        std::thread::sleep(Duration::from_millis(100));
+        let second = press(&mut r);
+        // Then
+        assert_eq!(first, None);
+        assert!(matches!(second, Some(ControlValue::AbsoluteContinuous(_))));
+    }
+
+    fn press(r: &mut GestureRecognizer) -> Option<ControlValue> {
+        r.control(ControlEvent::new(on(), ControlEventTimestamp::now()))
+    }
+
+    fn release(r: &mut GestureRecognizer) -> Option<ControlValue> {
+        r.control(ControlEvent::new(off(), ControlEventTimestamp::now()))
+    }
+}
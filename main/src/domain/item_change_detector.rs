@@ -0,0 +1,58 @@
+use crate::domain::{get_project_state_change_count, get_selected_item, get_selected_item_count};
+use reaper_high::{Item, Project};
+use reaper_medium::{PositionInSeconds, ReaProject};
+use std::collections::HashMap;
+
+/// REAPER doesn't notify control surfaces about changes of media items, so we detect them
+/// ourselves by comparing a cheap snapshot of each relevant project in each main loop cycle.
+#[derive(Debug, Default)]
+pub struct ItemChangeDetector {
+    snapshots: HashMap<ReaProject, ItemSnapshot>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct ItemSnapshot {
+    /// Increases with each undoable change (e.g. moving items, changing item properties).
+    state_change_count: i32,
+    selected_item_count: u32,
+    first_selected_item: Option<Item>,
+    /// Relevant for resolving items at the edit cursor.
+    edit_cursor_position: PositionInSeconds,
+}
+
+impl ItemSnapshot {
+    fn take(project: Project) -> Self {
+        Self {
+            state_change_count: get_project_state_change_count(project),
+            selected_item_count: get_selected_item_count(project),
+            first_selected_item: get_selected_item(project, 0),
+            edit_cursor_position: project.edit_cursor_position(),
+        }
+    }
+}
+
+impl ItemChangeDetector {
+    /// Returns those of the given projects in which something about the items might have changed
+    /// since the last poll.
+    ///
+    /// Forgets about projects which are not given anymore.
+    pub fn poll_for_changes(&mut self, projects: impl Iterator<Item = Project>) -> Vec<Project> {
+        let mut previous_snapshots = std::mem::take(&mut self.snapshots);
+        let mut changed_projects = vec![];
+        for project in projects {
+            if self.snapshots.contains_key(&project.raw()) {
+                continue;
+            }
+            let snapshot = ItemSnapshot::take(project);
+            // The very first poll of a project shouldn't count as change.
+            let changed = previous_snapshots
+                .remove(&project.raw())
+                .map_or(false, |s| s != snapshot);
+            if changed {
+                changed_projects.push(project);
+            }
+            self.snapshots.insert(project.raw(), snapshot);
+        }
+        changed_projects
+    }
+}
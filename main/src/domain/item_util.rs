@@ -0,0 +1,319 @@
+//! Access to media item and take properties which are not (yet) exposed by the high-level API.
+use c_str_macro::c_str;
+use reaper_high::{Item, Project, Reaper, Take, Track};
+use reaper_medium::{MediaItem, MediaItemTake, PositionInSeconds};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+pub const ITEM_VOLUME: &CStr = c_str!("D_VOL");
+pub const ITEM_MUTE: &CStr = c_str!("B_MUTE");
+pub const ITEM_LOCK: &CStr = c_str!("C_LOCK");
+pub const ITEM_SELECTED: &CStr = c_str!("B_UISEL");
+pub const ITEM_POSITION: &CStr = c_str!("D_POSITION");
+pub const ITEM_LENGTH: &CStr = c_str!("D_LENGTH");
+pub const ITEM_ACTIVE_TAKE_INDEX: &CStr = c_str!("I_CURTAKE");
+pub const TAKE_VOLUME: &CStr = c_str!("D_VOL");
+pub const TAKE_PITCH: &CStr = c_str!("D_PITCH");
+pub const TAKE_PLAYRATE: &CStr = c_str!("D_PLAYRATE");
+
+pub fn get_item_info_value(item: Item, key: &CStr) -> f64 {
+    unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetMediaItemInfo_Value(item.raw().as_ptr(), key.as_ptr())
+    }
+}
+
+pub fn set_item_info_value(item: Item, key: &CStr, value: f64) -> Result<(), &'static str> {
+    let successful = unsafe {
+        Reaper::get().medium_reaper().low().SetMediaItemInfo_Value(
+            item.raw().as_ptr(),
+            key.as_ptr(),
+            value,
+        )
+    };
+    if !successful {
+        return Err("couldn't set item property");
+    }
+    update_arrange();
+    Ok(())
+}
+
+pub fn get_take_info_value(take: Take, key: &CStr) -> f64 {
+    unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetMediaItemTakeInfo_Value(take.raw().as_ptr(), key.as_ptr())
+    }
+}
+
+pub fn set_take_info_value(take: Take, key: &CStr, value: f64) -> Result<(), &'static str> {
+    let successful = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .SetMediaItemTakeInfo_Value(take.raw().as_ptr(), key.as_ptr(), value)
+    };
+    if !successful {
+        return Err("couldn't set take property");
+    }
+    update_arrange();
+    Ok(())
+}
+
+pub fn item_is_available(project: Project, item: Item) -> bool {
+    unsafe {
+        Reaper::get().medium_reaper().low().ValidatePtr2(
+            project.raw().as_ptr(),
+            item.raw().as_ptr() as _,
+            c_str!("MediaItem*").as_ptr(),
+        )
+    }
+}
+
+/// Returns the track which contains the given item.
+pub fn get_item_track(project: Project, item: Item) -> Option<Track> {
+    let raw_track = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetMediaItem_Track(item.raw().as_ptr())
+    };
+    let raw_track = reaper_medium::MediaTrack::new(raw_track)?;
+    Some(Track::new(raw_track, Some(project.raw())))
+}
+
+pub fn get_selected_item_count(project: Project) -> u32 {
+    let count = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .CountSelectedMediaItems(project.raw().as_ptr())
+    };
+    count.max(0) as u32
+}
+
+pub fn get_selected_item(project: Project, index: u32) -> Option<Item> {
+    let raw_item = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetSelectedMediaItem(project.raw().as_ptr(), index as i32)
+    };
+    Some(Item::new(MediaItem::new(raw_item)?))
+}
+
+pub fn get_selected_items(project: Project) -> impl Iterator<Item = Item> {
+    (0..get_selected_item_count(project)).filter_map(move |i| get_selected_item(project, i))
+}
+
+/// Returns the first item on the given track which spans the given position.
+pub fn get_item_at_position(track: &Track, pos: PositionInSeconds) -> Option<Item> {
+    track.items().find(|item| {
+        let start = get_item_info_value(*item, ITEM_POSITION);
+        let end = start + get_item_info_value(*item, ITEM_LENGTH);
+        start <= pos.get() && pos.get() < end
+    })
+}
+
+pub fn item_is_selected(item: Item) -> bool {
+    get_item_info_value(item, ITEM_SELECTED) > 0.0
+}
+
+/// Selects the given item and unselects all other items on the given tracks.
+pub fn select_item_exclusively_within_tracks(
+    tracks: &[Track],
+    item: Item,
+) -> Result<(), &'static str> {
+    for track in tracks {
+        for i in track.items().filter(|i| *i != item && item_is_selected(*i)) {
+            set_item_info_value(i, ITEM_SELECTED, 0.0)?;
+        }
+    }
+    set_item_info_value(item, ITEM_SELECTED, 1.0)
+}
+
+/// Returns the name of the item's active take (that's what REAPER displays as item name).
+pub fn get_item_name(item: Item) -> Option<String> {
+    get_take_name(item.active_take()?)
+}
+
+/// Scrolls the arrange view horizontally so that the given position becomes visible (if it isn't
+/// already).
+pub fn scroll_arrange_view_to_position(project: Project, pos: PositionInSeconds) {
+    let reaper = Reaper::get().medium_reaper().low();
+    let mut start = 0.0;
+    let mut end = 0.0;
+    unsafe {
+        reaper.GetSet_ArrangeView2(project.raw().as_ptr(), false, 0, 0, &mut start, &mut end);
+    }
+    let pos = pos.get();
+    if start <= pos && pos < end {
+        return;
+    }
+    let length = end - start;
+    // Leave a bit of space on the left so the start of the item is clearly visible.
+    let mut new_start = (pos - length * 0.1).max(0.0);
+    let mut new_end = new_start + length;
+    unsafe {
+        reaper.GetSet_ArrangeView2(
+            project.raw().as_ptr(),
+            true,
+            0,
+            0,
+            &mut new_start,
+            &mut new_end,
+        );
+    }
+}
+
+pub fn get_item_take_count(item: Item) -> u32 {
+    let count = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetMediaItemNumTakes(item.raw().as_ptr())
+    };
+    count.max(0) as u32
+}
+
+pub fn get_item_take(item: Item, index: u32) -> Option<Take> {
+    let raw_take = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetMediaItemTake(item.raw().as_ptr(), index as i32)
+    };
+    Some(Take::new(MediaItemTake::new(raw_take)?))
+}
+
+pub fn get_take_name(take: Take) -> Option<String> {
+    let name = unsafe {
+        let ptr = Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetTakeName(take.raw().as_ptr());
+        if ptr.is_null() {
+            return None;
+        }
+        CStr::from_ptr(ptr)
+    };
+    Some(name.to_string_lossy().into_owned())
+}
+
+pub fn get_take_fx_count(take: Take) -> u32 {
+    let count = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .TakeFX_GetCount(take.raw().as_ptr())
+    };
+    count.max(0) as u32
+}
+
+pub fn get_take_fx_param_count(take: Take, fx_index: u32) -> u32 {
+    let count = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .TakeFX_GetNumParams(take.raw().as_ptr(), fx_index as i32)
+    };
+    count.max(0) as u32
+}
+
+pub fn get_take_fx_param_value(take: Take, fx_index: u32, param_index: u32) -> f64 {
+    unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .TakeFX_GetParamNormalized(take.raw().as_ptr(), fx_index as i32, param_index as i32)
+    }
+}
+
+pub fn set_take_fx_param_value(
+    take: Take,
+    fx_index: u32,
+    param_index: u32,
+    value: f64,
+) -> Result<(), &'static str> {
+    let successful = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .TakeFX_SetParamNormalized(
+                take.raw().as_ptr(),
+                fx_index as i32,
+                param_index as i32,
+                value,
+            )
+    };
+    if successful {
+        Ok(())
+    } else {
+        Err("couldn't set take FX parameter value")
+    }
+}
+
+/// Lets the FX format the given normalized parameter value (e.g. "-6.0 dB").
+pub fn format_take_fx_param_value(
+    take: Take,
+    fx_index: u32,
+    param_index: u32,
+    value: f64,
+) -> Option<String> {
+    let mut buffer = [0 as c_char; 256];
+    let successful = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .TakeFX_FormatParamValueNormalized(
+                take.raw().as_ptr(),
+                fx_index as i32,
+                param_index as i32,
+                value,
+                buffer.as_mut_ptr(),
+                buffer.len() as i32,
+            )
+    };
+    if !successful {
+        return None;
+    }
+    let text = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+    Some(text.to_string_lossy().into_owned())
+}
+
+/// Converts the given project time to quarter notes (which takes tempo changes into account).
+pub fn convert_time_to_quarter_notes(project: Project, pos: PositionInSeconds) -> f64 {
+    unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .TimeMap2_timeToQN(project.raw().as_ptr(), pos.get())
+    }
+}
+
+/// Converts the given quarter notes to project time (which takes tempo changes into account).
+pub fn convert_quarter_notes_to_time(project: Project, quarter_notes: f64) -> PositionInSeconds {
+    let seconds = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .TimeMap2_QNToTime(project.raw().as_ptr(), quarter_notes)
+    };
+    PositionInSeconds::new(seconds)
+}
+
+pub fn get_project_state_change_count(project: Project) -> i32 {
+    unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetProjectStateChangeCount(project.raw().as_ptr())
+    }
+}
+
+fn update_arrange() {
+    Reaper::get().medium_reaper().low().UpdateArrange();
+}
@@ -1,10 +1,12 @@
-use crate::domain::SafeLua;
+use crate::domain::{ExecutionTimeLimit, MidiSourceControlScript, SafeLua};
 use helgoboss_learn::{
-    AbsoluteValue, FeedbackValue, MidiSourceAddress, MidiSourceScript, MidiSourceScriptOutcome,
-    RawMidiEvent, RgbColor,
+    AbsoluteValue, ControlValue, DiscreteIncrement, FeedbackValue, MidiSourceAddress,
+    MidiSourceScript, MidiSourceScriptOutcome, RawMidiEvent, RgbColor, UnitValue,
 };
-use mlua::{Function, LuaSerdeExt, Table, ToLua, Value};
+use mlua::{Function, LuaSerdeExt, RegistryKey, Table, ToLua, Value};
 use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub struct LuaMidiSourceScript<'lua> {
@@ -126,6 +128,106 @@ struct LuaScriptOutcome {
     messages: Vec<Vec<u8>>,
 }
 
+/// Decodes incoming MIDI messages via Lua.
+///
+/// The message bytes are available as table `msg`. The script returns `nil` if the message
+/// doesn't match, `{ value = ... }` for an absolute value between 0.0 and 1.0 or
+/// `{ increment = ... }` for a non-zero relative increment.
+#[derive(Clone, Debug)]
+pub struct LuaMidiSourceControlScript {
+    // Arc because LuaControlUnit is not cloneable, Mutex because Lua is not Sync (the source is
+    // also moved to the real-time processor, even though it never executes Lua control scripts).
+    lua_unit: Arc<Mutex<LuaControlUnit>>,
+}
+
+#[derive(Debug)]
+struct LuaControlUnit {
+    // Control scripts are executed in the main thread but the source needs to be sendable to the
+    // real-time thread, so we can't borrow the main thread Lua state.
+    lua: SafeLua,
+    execution_time_limit: ExecutionTimeLimit,
+    function: RegistryKey,
+    env: RegistryKey,
+}
+
+impl LuaMidiSourceControlScript {
+    pub fn compile(lua_script: &str) -> Result<Self, Box<dyn Error>> {
+        if lua_script.trim().is_empty() {
+            return Err("script empty".into());
+        }
+        let lua = SafeLua::new()?;
+        let (function, env) = {
+            let env = lua.create_fresh_environment(false)?;
+            let function =
+                lua.compile_as_function("MIDI source control script", lua_script, env.clone())?;
+            let lua_ref = lua.as_ref();
+            (
+                lua_ref.create_registry_value(function)?,
+                lua_ref.create_registry_value(env)?,
+            )
+        };
+        let execution_time_limit = lua.install_execution_time_limit(MAX_EXECUTION_TIME)?;
+        let lua_unit = LuaControlUnit {
+            lua,
+            execution_time_limit,
+            function,
+            env,
+        };
+        Ok(Self {
+            lua_unit: Arc::new(Mutex::new(lua_unit)),
+        })
+    }
+}
+
+impl MidiSourceControlScript for LuaMidiSourceControlScript {
+    fn execute(&self, bytes: &[u8]) -> Result<Option<ControlValue>, &'static str> {
+        // This is only executed in the main thread, so there's no contention.
+        let lua_unit = self.lua_unit.lock().map_err(|_| "Lua script is poisoned")?;
+        let lua = lua_unit.lua.as_ref();
+        let env: Table = lua
+            .registry_value(&lua_unit.env)
+            .map_err(|_| "couldn't get Lua environment")?;
+        // Set input data as variable "msg"
+        let msg = lua
+            .create_sequence_from(bytes.iter().copied())
+            .map_err(|_| "couldn't create msg table")?;
+        env.raw_set("msg", msg)
+            .map_err(|_| "couldn't set msg variable")?;
+        // Invoke script
+        let function: Function = lua
+            .registry_value(&lua_unit.function)
+            .map_err(|_| "couldn't get Lua function")?;
+        lua_unit.execution_time_limit.restart();
+        let value: Value = function
+            .call(())
+            .map_err(|_| "failed to invoke Lua script")?;
+        // Process return value
+        let outcome: Option<LuaControlScriptOutcome> = lua
+            .from_value(value)
+            .map_err(|_| "Lua script result has wrong type")?;
+        let outcome = match outcome {
+            None => return Ok(None),
+            Some(o) => o,
+        };
+        let control_value = match (outcome.value, outcome.increment) {
+            (_, Some(0)) => return Err("increment must not be zero"),
+            (_, Some(i)) => ControlValue::RelativeDiscrete(DiscreteIncrement::new(i)),
+            (Some(v), None) => ControlValue::AbsoluteContinuous(UnitValue::new_clamped(v)),
+            (None, None) => return Ok(None),
+        };
+        Ok(Some(control_value))
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct LuaControlScriptOutcome {
+    value: Option<f64>,
+    increment: Option<i32>,
+}
+
+/// Guards against infinite loops (which would otherwise freeze REAPER).
+const MAX_EXECUTION_TIME: Duration = Duration::from_millis(10);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,4 +350,32 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn control() {
+        // Given
+        let text = "
+            if msg[1] == 0xf0 and msg[2] == 0x47 and #msg == 5 then
+                return { value = msg[4] / 127 }
+            elseif msg[1] == 0xb0 and msg[2] == 0x10 then
+                local v = msg[3]
+                return { increment = v < 64 and v or v - 128 }
+            end
+        ";
+        let script = LuaMidiSourceControlScript::compile(text).unwrap();
+        // When
+        let sys_ex_outcome = script.execute(&[0xf0, 0x47, 0x00, 127, 0xf7]).unwrap();
+        let cc_outcome = script.execute(&[0xb0, 0x10, 126]).unwrap();
+        let unmatched_outcome = script.execute(&[0x90, 0x10, 100]).unwrap();
+        // Then
+        assert_eq!(
+            sys_ex_outcome,
+            Some(ControlValue::AbsoluteContinuous(UnitValue::MAX))
+        );
+        assert_eq!(
+            cc_outcome,
+            Some(ControlValue::RelativeDiscrete(DiscreteIncrement::new(-2)))
+        );
+        assert_eq!(unmatched_outcome, None);
+    }
 }
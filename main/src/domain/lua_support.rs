@@ -1,5 +1,6 @@
 use mlua::{ChunkMode, Function, HookTriggers, Lua, Table, Value};
 use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -104,6 +105,54 @@ impl SafeLua {
         )?;
         Ok(self)
     }
+
+    /// Call once in order to limit the execution time of user code that is executed repeatedly.
+    ///
+    /// In contrast to [`Self::start_execution_time_limit_countdown`], the countdown doesn't start
+    /// immediately. It must be (re)started before each execution using the returned handle.
+    pub fn install_execution_time_limit(
+        &self,
+        max_duration: Duration,
+    ) -> Result<ExecutionTimeLimit, Box<dyn Error>> {
+        let limit = ExecutionTimeLimit {
+            base_instant: Instant::now(),
+            start_nanos: Default::default(),
+        };
+        let base_instant = limit.base_instant;
+        let start_nanos = limit.start_nanos.clone();
+        let max_nanos = max_duration.as_nanos() as u64;
+        self.0.set_hook(
+            HookTriggers::every_nth_instruction(10),
+            move |_lua, _debug| {
+                let now_nanos = base_instant.elapsed().as_nanos() as u64;
+                let start_nanos = start_nanos.load(Ordering::Relaxed);
+                if now_nanos.saturating_sub(start_nanos) > max_nanos {
+                    Err(mlua::Error::ExternalError(Arc::new(
+                        RealearnScriptError::Timeout,
+                    )))
+                } else {
+                    Ok(())
+                }
+            },
+        )?;
+        Ok(limit)
+    }
+}
+
+/// Handle for restarting the countdown of an installed execution time limit.
+#[derive(Debug)]
+pub struct ExecutionTimeLimit {
+    base_instant: Instant,
+    /// Nanoseconds between the base instant and the start of the current execution.
+    start_nanos: Arc<AtomicU64>,
+}
+
+impl ExecutionTimeLimit {
+    /// Call right before executing user code.
+    pub fn restart(&self) {
+        let nanos = self.base_instant.elapsed().as_nanos() as u64;
+        self.start_nanos.store(nanos, Ordering::Relaxed);
+    }
 }
 
 impl AsRef<Lua> for SafeLua {
@@ -0,0 +1,333 @@
+use crate::domain::{
+    AdditionalTransformationInput, ExecutionTimeLimit, OutputVariable, SafeLua, Script,
+};
+use helgoboss_learn::{Transformation, TransformationInput, TransformationOutput, UnitValue};
+use mlua::{Function, RegistryKey, Table, Value};
+use std::error::Error;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Represents a value transformation done via Lua scripting language.
+///
+/// The script has access to the variables `x`, `y`, `y_last` and `rel_time` (the latter only if
+/// the script mentions it, in which case the transformation wants to be polled). It communicates
+/// the result by assigning the output variable (`y` for control, `x` for feedback):
+///
+/// - A number means "control with this value".
+/// - `nil` means "don't do anything".
+/// - `stop` means "stop firing" (only relevant for transformations that use `rel_time`).
+/// - `stop(v)` means "control with value v and stop firing".
+///
+/// Variables that the script defines itself are kept between invocations.
+///
+/// Lua transformations are never executed in the real-time thread. Mappings that use them are
+/// always controlled from the main thread.
+#[derive(Debug)]
+pub struct LuaTransformation {
+    // Mutex because Lua is not Sync. None if not compiled yet (happens lazily for clones).
+    lua_unit: Mutex<Option<LuaUnit>>,
+    lua_script: String,
+    output_var: OutputVariable,
+    wants_to_be_polled: bool,
+}
+
+impl Clone for LuaTransformation {
+    /// Each clone gets its own Lua state, so variables defined by the script are not shared.
+    fn clone(&self) -> Self {
+        Self {
+            lua_unit: Mutex::new(None),
+            lua_script: self.lua_script.clone(),
+            output_var: self.output_var.clone(),
+            wants_to_be_polled: self.wants_to_be_polled,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LuaUnit {
+    lua: SafeLua,
+    execution_time_limit: ExecutionTimeLimit,
+    function: RegistryKey,
+    env: RegistryKey,
+}
+
+impl Script for LuaTransformation {
+    fn uses_time(&self) -> bool {
+        self.wants_to_be_polled()
+    }
+
+    fn evaluate(
+        &self,
+        input: TransformationInput<UnitValue>,
+        output_value: UnitValue,
+        additional_input: AdditionalTransformationInput,
+    ) -> Result<TransformationOutput<UnitValue>, &'static str> {
+        self.transform_continuous(input, output_value, additional_input)
+    }
+}
+
+impl LuaTransformation {
+    pub fn compile_for_control(lua_script: &str) -> Result<LuaTransformation, Box<dyn Error>> {
+        LuaTransformation::compile(lua_script, OutputVariable::Y)
+    }
+
+    pub fn compile_for_feedback(lua_script: &str) -> Result<LuaTransformation, Box<dyn Error>> {
+        LuaTransformation::compile(lua_script, OutputVariable::X)
+    }
+
+    // Compiles the given script and creates an appropriate transformation.
+    fn compile(
+        lua_script: &str,
+        output_var: OutputVariable,
+    ) -> Result<LuaTransformation, Box<dyn Error>> {
+        if lua_script.trim().is_empty() {
+            return Err("script empty".into());
+        }
+        // Compile eagerly in order to report errors early.
+        let lua_unit = LuaUnit::compile(lua_script)?;
+        let transformation = LuaTransformation {
+            lua_unit: Mutex::new(Some(lua_unit)),
+            lua_script: lua_script.to_string(),
+            output_var,
+            wants_to_be_polled: lua_script.contains(REL_TIME_VAR_NAME),
+        };
+        Ok(transformation)
+    }
+}
+
+impl LuaUnit {
+    fn compile(lua_script: &str) -> Result<LuaUnit, Box<dyn Error>> {
+        let lua = SafeLua::new()?;
+        let (function, env) = {
+            let env = lua.create_fresh_environment(false)?;
+            env.raw_set("stop", create_stop_sentinel(&lua)?)?;
+            let function =
+                lua.compile_as_function("Transformation script", lua_script, env.clone())?;
+            let lua_ref = lua.as_ref();
+            (
+                lua_ref.create_registry_value(function)?,
+                lua_ref.create_registry_value(env)?,
+            )
+        };
+        let execution_time_limit = lua.install_execution_time_limit(MAX_EXECUTION_TIME)?;
+        let lua_unit = LuaUnit {
+            lua,
+            execution_time_limit,
+            function,
+            env,
+        };
+        Ok(lua_unit)
+    }
+}
+
+impl Transformation for LuaTransformation {
+    type AdditionalInput = AdditionalTransformationInput;
+
+    fn transform(
+        &self,
+        input: TransformationInput<f64>,
+        output_value: f64,
+        additional_input: AdditionalTransformationInput,
+    ) -> Result<TransformationOutput<f64>, &'static str> {
+        let mut lua_unit_guard = self
+            .lua_unit
+            .lock()
+            .map_err(|_| "Lua transformation poisoned")?;
+        if lua_unit_guard.is_none() {
+            let lua_unit = LuaUnit::compile(&self.lua_script)
+                .map_err(|_| "couldn't compile Lua transformation")?;
+            *lua_unit_guard = Some(lua_unit);
+        }
+        let lua_unit = lua_unit_guard.as_ref().expect("Lua unit just compiled");
+        let lua = lua_unit.lua.as_ref();
+        let env: Table = lua
+            .registry_value(&lua_unit.env)
+            .map_err(|_| "couldn't get Lua environment")?;
+        use OutputVariable::*;
+        let (input_var_name, output_var_name) = match self.output_var {
+            X => ("y", "x"),
+            Y => ("x", "y"),
+        };
+        // Set input data as variables
+        env.raw_set(input_var_name, input.value)
+            .map_err(|_| "couldn't set input variable")?;
+        env.raw_set(output_var_name, output_value)
+            .map_err(|_| "couldn't set output variable")?;
+        env.raw_set("y_last", additional_input.y_last)
+            .map_err(|_| "couldn't set y_last variable")?;
+        if self.wants_to_be_polled {
+            let rel_time_millis = input.meta_data.rel_time.as_millis() as f64;
+            env.raw_set(REL_TIME_VAR_NAME, rel_time_millis)
+                .map_err(|_| "couldn't set rel_time variable")?;
+        }
+        // Invoke script
+        let function: Function = lua
+            .registry_value(&lua_unit.function)
+            .map_err(|_| "couldn't get Lua function")?;
+        lua_unit.execution_time_limit.restart();
+        function
+            .call::<_, ()>(())
+            .map_err(|_| "failed to invoke Lua transformation")?;
+        // Process output variable
+        let v: Value = env
+            .raw_get(output_var_name)
+            .map_err(|_| "couldn't get output variable")?;
+        let output = match v {
+            Value::Nil => TransformationOutput::None,
+            Value::Number(v) => TransformationOutput::Control(v),
+            Value::Integer(v) => TransformationOutput::Control(v as f64),
+            Value::Table(t) => {
+                let is_stop: bool = t.raw_get("stop").unwrap_or(false);
+                if !is_stop {
+                    return Err("Lua transformation result has wrong type");
+                }
+                match t.raw_get::<_, Option<f64>>("value") {
+                    Ok(Some(v)) => TransformationOutput::ControlAndStop(v),
+                    _ => TransformationOutput::Stop,
+                }
+            }
+            _ => return Err("Lua transformation result has wrong type"),
+        };
+        Ok(output)
+    }
+
+    fn wants_to_be_polled(&self) -> bool {
+        self.wants_to_be_polled
+    }
+}
+
+/// Creates the value exposed as `stop`.
+///
+/// Assigning it directly means "stop", calling it (e.g. `stop(0.5)`) means "control and stop".
+fn create_stop_sentinel(lua: &SafeLua) -> Result<Table, Box<dyn Error>> {
+    let lua = lua.as_ref();
+    let sentinel = lua.create_table()?;
+    sentinel.raw_set("stop", true)?;
+    let call = lua.create_function(|lua, (_, v): (Table, f64)| {
+        let t = lua.create_table()?;
+        t.raw_set("stop", true)?;
+        t.raw_set("value", v.clamp(0.0, 1.0))?;
+        Ok(t)
+    })?;
+    let metatable = lua.create_table()?;
+    metatable.raw_set("__call", call)?;
+    sentinel.set_metatable(Some(metatable));
+    Ok(sentinel)
+}
+
+const REL_TIME_VAR_NAME: &str = "rel_time";
+
+/// Guards against infinite loops (which would otherwise freeze REAPER).
+const MAX_EXECUTION_TIME: Duration = Duration::from_millis(10);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_learn::TransformationInputMetaData;
+
+    #[test]
+    fn control() {
+        // Given
+        let text = "
+            local curve = { 0.0, 0.1, 0.5, 1.0 }
+            local index = math.floor(x * (#curve - 1)) + 1
+            y = curve[index]
+        ";
+        let transformation = LuaTransformation::compile_for_control(text).unwrap();
+        // When
+        let output = transformation
+            .transform(input(0.5), 0.0, Default::default())
+            .unwrap();
+        // Then
+        assert!(matches!(output, TransformationOutput::Control(v) if v == 0.1));
+        assert!(!transformation.wants_to_be_polled());
+    }
+
+    #[test]
+    fn feedback() {
+        // Given
+        let transformation = LuaTransformation::compile_for_feedback("x = 1 - y").unwrap();
+        // When
+        let output = transformation
+            .transform(input(0.25), 0.0, Default::default())
+            .unwrap();
+        // Then
+        assert!(matches!(output, TransformationOutput::Control(v) if v == 0.75));
+    }
+
+    #[test]
+    fn none_and_stop() {
+        // Given
+        let text = "
+            if rel_time < 100 then
+                y = nil
+            elseif rel_time < 200 then
+                y = stop(0.5)
+            else
+                y = stop
+            end
+        ";
+        let transformation = LuaTransformation::compile_for_control(text).unwrap();
+        // When
+        let outputs: Vec<_> = [50, 150, 250]
+            .into_iter()
+            .map(|millis| {
+                let input = TransformationInput::new(
+                    0.0,
+                    TransformationInputMetaData {
+                        rel_time: Duration::from_millis(millis),
+                    },
+                );
+                transformation
+                    .transform(input, 0.0, Default::default())
+                    .unwrap()
+            })
+            .collect();
+        // Then
+        assert!(transformation.wants_to_be_polled());
+        assert!(matches!(outputs[0], TransformationOutput::None));
+        assert!(matches!(outputs[1], TransformationOutput::ControlAndStop(v) if v == 0.5));
+        assert!(matches!(outputs[2], TransformationOutput::Stop));
+    }
+
+    #[test]
+    fn clones_have_independent_state() {
+        // Given
+        let text = "
+            count = (count or 0) + 1
+            y = count / 10
+        ";
+        let transformation = LuaTransformation::compile_for_control(text).unwrap();
+        let clone = transformation.clone();
+        // When
+        for _ in 0..3 {
+            transformation
+                .transform(input(0.0), 0.0, Default::default())
+                .unwrap();
+        }
+        let clone_output = clone
+            .transform(input(0.0), 0.0, Default::default())
+            .unwrap();
+        // Then
+        assert!(matches!(clone_output, TransformationOutput::Control(v) if v == 0.1));
+    }
+
+    #[test]
+    fn execution_time_limit() {
+        // Given
+        let transformation = LuaTransformation::compile_for_control("while true do end").unwrap();
+        // When
+        let result = transformation.transform(input(0.0), 0.0, Default::default());
+        // Then
+        assert!(result.is_err());
+    }
+
+    fn input(value: f64) -> TransformationInput<f64> {
+        TransformationInput::new(
+            value,
+            TransformationInputMetaData {
+                rel_time: Duration::ZERO,
+            },
+        )
+    }
+}
@@ -1,25 +1,27 @@
 use crate::domain::{
     aggregate_target_values, get_project_options, say, AdditionalFeedbackEvent, BackboneState,
-    ClipMatrixRelevance, Compartment, CompoundChangeEvent, CompoundFeedbackValue,
-    CompoundMappingSource, CompoundMappingSourceAddress, CompoundMappingTarget, ControlContext,
-    ControlEvent, ControlEventTimestamp, ControlInput, ControlLogContext, ControlLogEntry,
-    ControlLogEntryKind, ControlMode, ControlOutcome, DeviceFeedbackOutput, DomainEvent,
-    DomainEventHandler, ExtendedProcessorContext, FeedbackAudioHookTask, FeedbackCollector,
-    FeedbackDestinations, FeedbackOutput, FeedbackRealTimeTask, FeedbackResolution,
-    FeedbackSendBehavior, FinalRealFeedbackValue, FinalSourceFeedbackValue,
+    BoxedHitInstruction, ClipMatrixRelevance, Compartment, CompartmentParamIndex,
+    CompoundChangeEvent, CompoundFeedbackValue, CompoundMappingSource,
+    CompoundMappingSourceAddress, CompoundMappingTarget, ControlContext, ControlEvent,
+    ControlEventTimestamp, ControlInput, ControlLogContext, ControlLogEntry, ControlLogEntryKind,
+    ControlMode, ControlOutcome, DeviceFeedbackOutput, DomainEvent, DomainEventHandler,
+    EnableMappingsInstruction, Exclusivity, ExtendedProcessorContext, FeedbackAudioHookTask,
+    FeedbackCollector, FeedbackDestinations, FeedbackOutput, FeedbackRealTimeTask,
+    FeedbackResolution, FeedbackSendBehavior, FinalRealFeedbackValue, FinalSourceFeedbackValue,
     GlobalControlAndFeedbackState, GroupId, HitInstructionContext, HitInstructionResponse,
     InstanceContainer, InstanceOrchestrationEvent, InstanceStateChanged, IoUpdatedEvent,
-    KeyMessage, LimitedAsciiString, MainMapping, MainSourceMessage, MappingActivationEffect,
-    MappingControlResult, MappingId, MappingInfo, MessageCaptureEvent, MessageCaptureResult,
-    MidiControlInput, MidiDestination, MidiScanResult, NormalRealTimeTask, OrderedMappingIdSet,
-    OrderedMappingMap, OscDeviceId, OscFeedbackTask, PluginParamIndex, PluginParams,
-    PotStateChangedEvent, ProcessorContext, ProjectOptions, ProjectionFeedbackValue,
+    KeyMessage, LimitedAsciiString, LoadMappingSnapshotInstruction, MainMapping, MainSourceMessage,
+    MappingActivationEffect, MappingControlResult, MappingId, MappingInfo, MessageCaptureEvent,
+    MessageCaptureResult, MidiControlInput, MidiDestination, MidiScanResult, NormalRealTimeTask,
+    OrderedMappingIdSet, OrderedMappingMap, OscDeviceId, OscFeedbackTask, PluginParamIndex,
+    PluginParams, PotStateChangedEvent, ProcessorContext, ProjectOptions, ProjectionFeedbackValue,
     QualifiedClipMatrixEvent, QualifiedMappingId, QualifiedSource, RawParamValue,
     RealTimeMappingUpdate, RealTimeTargetUpdate, RealearnMonitoringFxParameterValueChangedEvent,
     RealearnParameterChangePayload, ReaperConfigChange, ReaperMessage, ReaperSourceFeedbackValue,
     ReaperTarget, SharedInstanceState, SourceReleasedEvent, SpecificCompoundFeedbackValue,
-    TargetControlEvent, TargetValueChangedEvent, UpdatedSingleMappingOnStateEvent,
-    VirtualControlElement, VirtualSourceValue,
+    SpeechSettings, TagScope, TargetControlEvent, TargetValueChangedEvent,
+    UpdatedSingleMappingOnStateEvent, VirtualControlElement, VirtualMappingSnapshotIdForLoad,
+    VirtualSourceValue, XyAxis,
 };
 use derive_more::Display;
 use enum_map::EnumMap;
@@ -30,7 +32,7 @@ use helgoboss_learn::{
 use std::borrow::Cow;
 use std::cell::RefCell;
 
-use crate::base::{NamedChannelSender, SenderToNormalThread, SenderToRealTimeThread};
+use crate::base::{Global, NamedChannelSender, SenderToNormalThread, SenderToRealTimeThread};
 use crate::domain::ui_util::{
     format_control_input_with_match_result, format_incoming_midi_message, format_midi_source_value,
     format_osc_message, format_osc_packet, format_raw_midi, log_lifecycle_output,
@@ -42,7 +44,7 @@ use helgoboss_midi::{ControlChange14BitMessage, ParameterNumberMessage, RawShort
 use playtime_clip_engine::base::ClipMatrixEvent;
 use playtime_clip_engine::rt::{QualifiedSlotChangeEvent, SlotChangeEvent};
 use playtime_clip_engine::{clip_timeline, Timeline};
-use reaper_high::{ChangeEvent, Reaper};
+use reaper_high::{ChangeEvent, Project, Reaper};
 use reaper_medium::ReaperNormalizedFxParamValue;
 use rosc::{OscMessage, OscPacket, OscType};
 use slog::{debug, trace};
@@ -77,6 +79,7 @@ struct Basics<EH: DomainEventHandler> {
     instance_container: &'static dyn InstanceContainer,
     logger: slog::Logger,
     settings: BasicSettings,
+    speech_settings: SpeechSettings,
     control_is_globally_enabled: bool,
     // TODO-medium Now that we communicate the feedback output separately, we could limit the scope
     //  of its meaning to "instance enabled etc."
@@ -283,6 +286,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 source_context: SourceContext,
                 logger: logger.clone(),
                 settings: Default::default(),
+                speech_settings: Default::default(),
                 control_is_globally_enabled: false,
                 feedback_is_globally_enabled: false,
                 event_handler,
@@ -327,6 +331,11 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         &self.basics.instance_id
     }
 
+    /// Returns the project to which the targets of this instance are bound.
+    pub fn project_or_current_project(&self) -> Project {
+        self.basics.context.project_or_current_project()
+    }
+
     /// This is the chance to take over a source from another instance (send our feedback).
     ///
     /// This is a very important principle when using multiple instances. It allows feedback to
@@ -423,6 +432,11 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             } => {
                 let _ = self.control(compartment, mapping_id, event, options);
             }
+            ControlMidi { event } => {
+                let source_value = event.payload().to_source_value();
+                let msg = MainSourceMessage::Midi(&source_value);
+                self.process_incoming_message_internal(event.with_payload(msg));
+            }
             LogVirtualControlInput {
                 event: value,
                 match_outcome: match_result,
@@ -473,7 +487,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
     fn poll_control(&mut self, timestamp: ControlEventTimestamp) {
         for compartment in Compartment::enum_iter() {
             for id in self.poll_control_mappings[compartment].iter() {
-                let (is_source_poll, control_result, group_interaction) = if let Some(m) =
+                let (poll_results, group_interaction) = if let Some(m) =
                     self.collections.mappings[compartment].get_mut(id)
                 {
                     let control_context = self.basics.control_context();
@@ -496,9 +510,11 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                     } else {
                         Default::default()
                     };
-                    let (is_source_poll, mut final_poll_result) = if mode_poll_result
-                        .at_least_one_target_was_reached
-                    {
+                    let wants_gesture_poll = m.has_gesture() && m.control_is_effectively_on();
+                    let wants_source_poll =
+                        m.source().wants_to_be_polled() && m.control_is_effectively_on();
+                    // Each entry says whether it's a source poll and contains the result.
+                    let mut poll_results = if mode_poll_result.at_least_one_target_was_reached {
                         // Mode was polled successfully. This one has precedence.
                         // We poll even if control is effectively off because it might have been
                         // on before and user might have pressed a button which started some
@@ -508,67 +524,99 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                         // release the modifier. If we wouldn't poll anymore in that case, the
                         // single press would be discarded - or worse, fired when the mapping
                         // is enabled again.
-                        (false, mode_poll_result)
-                    } else if m.source().wants_to_be_polled() && m.control_is_effectively_on() {
-                        // Mode was either not polled at all or without result, poll source.
-                        let res = if let Some(source_control_value) = m.poll_source() {
-                            let control_event = ControlEvent::new(source_control_value, timestamp);
-                            control_mapping_stage_one(
-                                &self.basics,
-                                &self.collections.parameters,
-                                m,
-                                control_event,
-                                ControlOptions::default(),
-                            )
+                        [Some((false, mode_poll_result)), None]
+                    } else if wants_gesture_poll || wants_source_poll {
+                        // Mode was either not polled at all or without result. Poll gesture
+                        // recognizer (e.g. for detecting the end of a tap sequence) and source
+                        // independently of each other. A mapping can have both, e.g. a gesture
+                        // applied to a timer source.
+                        let gesture_poll_result = if wants_gesture_poll {
+                            if let Some(gesture_control_value) = m.poll_gesture(timestamp) {
+                                let control_event =
+                                    ControlEvent::new(gesture_control_value, timestamp);
+                                Some(control_mapping_from_mode(
+                                    &self.basics,
+                                    &self.collections.parameters,
+                                    m,
+                                    control_event,
+                                    ControlOptions::default(),
+                                ))
+                            } else {
+                                None
+                            }
                         } else {
-                            Default::default()
+                            None
                         };
-                        (true, res)
+                        let source_poll_result = if wants_source_poll {
+                            if let Some(source_control_value) = m.poll_source(&self.basics.context)
+                            {
+                                let control_event =
+                                    ControlEvent::new(source_control_value, timestamp);
+                                Some(control_mapping_stage_one(
+                                    &self.basics,
+                                    &self.collections.parameters,
+                                    m,
+                                    control_event,
+                                    ControlOptions::default(),
+                                ))
+                            } else {
+                                None
+                            }
+                        } else {
+                            None
+                        };
+                        [
+                            gesture_poll_result.map(|r| (true, r)),
+                            source_poll_result.map(|r| (true, r)),
+                        ]
                     } else {
-                        // Mode was either not polled at all or without result, source doesn't
-                        // want to be polled.
-                        (false, mode_poll_result)
+                        // Mode was either not polled at all or without result, neither gesture
+                        // recognizer nor source want to be polled.
+                        [Some((false, mode_poll_result)), None]
                     };
-                    control_mapping_stage_two(
-                        &self.basics,
-                        &mut final_poll_result,
-                        m,
-                        ManualFeedbackProcessing::On {
-                            mappings_with_virtual_targets: &self
-                                .collections
-                                .mappings_with_virtual_targets,
-                        },
-                    );
-                    (is_source_poll, final_poll_result, m.group_interaction())
+                    for (_, control_result) in poll_results.iter_mut().flatten() {
+                        control_mapping_stage_two(
+                            &self.basics,
+                            control_result,
+                            m,
+                            ManualFeedbackProcessing::On {
+                                mappings_with_virtual_targets: &self
+                                    .collections
+                                    .mappings_with_virtual_targets,
+                            },
+                        );
+                    }
+                    (poll_results, m.group_interaction())
                 } else {
                     continue;
                 };
-
-                // When this is a mode poll, we only do target-value based group interaction after
-                // polling (makes sense because control-value based one has been done at control
-                // time already).
-                let needs_group_interaction = control_result.at_least_one_target_was_reached
-                    && (is_source_poll || group_interaction.is_target_based());
-                control_mapping_stage_three(
-                    &self.basics,
-                    &mut self.collections,
-                    compartment,
-                    control_result,
-                    if needs_group_interaction {
-                        GroupInteractionProcessing::On(GroupInteractionInput {
-                            mapping_id: *id,
-                            // Control value is not important because we only do target-value
-                            // based group interaction.
-                            control_event: ControlEvent::new(
-                                ControlValue::AbsoluteContinuous(Default::default()),
-                                timestamp,
-                            ),
-                            group_interaction,
-                        })
-                    } else {
-                        GroupInteractionProcessing::Off
-                    },
-                );
+                for (is_source_poll, control_result) in poll_results.into_iter().flatten() {
+                    // When this is a mode poll, we only do target-value based group interaction
+                    // after polling (makes sense because control-value based one has been done at
+                    // control time already).
+                    let needs_group_interaction = control_result.at_least_one_target_was_reached
+                        && (is_source_poll || group_interaction.is_target_based());
+                    control_mapping_stage_three(
+                        &self.basics,
+                        &mut self.collections,
+                        compartment,
+                        control_result,
+                        if needs_group_interaction {
+                            GroupInteractionProcessing::On(GroupInteractionInput {
+                                mapping_id: *id,
+                                // Control value is not important because we only do target-value
+                                // based group interaction.
+                                control_event: ControlEvent::new(
+                                    ControlValue::AbsoluteContinuous(Default::default()),
+                                    timestamp,
+                                ),
+                                group_interaction,
+                            })
+                        } else {
+                            GroupInteractionProcessing::Off
+                        },
+                    );
+                }
             }
         }
     }
@@ -581,6 +629,14 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         control_event: ControlEvent<ControlValue>,
         options: ControlOptions,
     ) -> Result<(), &'static str> {
+        if compartment == Compartment::Controller
+            && self
+                .collections
+                .mappings_with_virtual_targets
+                .contains_key(&mapping_id)
+        {
+            return self.control_virtualizing(mapping_id, control_event, options);
+        }
         // Resolving mappings with virtual targets is not necessary anymore. It has
         // been done in the real-time processor already.
         let (control_result, group_interaction) = {
@@ -623,6 +679,33 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         Ok(())
     }
 
+    /// Processes incoming control values of controller mappings with virtual targets whose glue
+    /// couldn't be processed in the real-time processor (e.g. because of a Lua transformation).
+    fn control_virtualizing(
+        &mut self,
+        mapping_id: MappingId,
+        control_event: ControlEvent<ControlValue>,
+        options: ControlOptions,
+    ) -> Result<(), &'static str> {
+        let m = self
+            .collections
+            .mappings_with_virtual_targets
+            .get_mut(&mapping_id)
+            .ok_or("mapping not found")?;
+        if !m.control_is_effectively_on() {
+            return Ok(());
+        }
+        let virtual_source_value = match m.control_virtualizing_value(control_event) {
+            None => return Ok(()),
+            Some(v) => v,
+        };
+        self.process_virtual_source_value_with_options(
+            control_event.with_payload(virtual_source_value),
+            options,
+        );
+        Ok(())
+    }
+
     /// This should be regularly called by the control surface, even during global target learning.
     pub fn run_essential(&mut self, timestamp: ControlEventTimestamp) {
         self.process_normal_tasks_from_real_time_processor();
@@ -1192,6 +1275,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 UpdateSettings(settings) => {
                     self.update_settings(settings);
                 }
+                UpdateSpeechSettings(settings) => {
+                    self.basics.speech_settings = settings;
+                }
                 UpdateAllMappings(compartment, mappings) => {
                     self.update_all_mappings(compartment, mappings);
                 }
@@ -1202,9 +1288,46 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 HitTarget { id, value } => {
                     self.hit_target(id, value);
                 }
+                ProcessVirtualSourceValue(value) => {
+                    self.process_virtual_source_value(ControlEvent::new(value, timestamp));
+                }
+                EnableMappingsByTag {
+                    compartment,
+                    scope,
+                    exclusivity,
+                    is_enable,
+                } => {
+                    let instruction = EnableMappingsInstruction {
+                        compartment,
+                        scope,
+                        initiator: None,
+                        is_enable,
+                        exclusivity,
+                    };
+                    self.execute_hit_instruction(compartment, Box::new(instruction));
+                }
+                LoadMappingSnapshot {
+                    compartment,
+                    scope,
+                    active_mappings_only,
+                    snapshot_id,
+                } => {
+                    let instruction = LoadMappingSnapshotInstruction {
+                        compartment,
+                        scope,
+                        active_mappings_only,
+                        snapshot_id,
+                        default_value: None,
+                    };
+                    self.execute_hit_instruction(compartment, Box::new(instruction));
+                }
                 NotifyConditionsChanged => {
                     self.notify_conditions_changed();
                 }
+                NotifyItemsChanged => {
+                    self.refresh_targets(|m| m.needs_refresh_when_items_changed());
+                    self.update_on_mappings();
+                }
                 UpdateSingleMapping(mapping) => {
                     self.update_single_mapping(mapping);
                 }
@@ -1335,6 +1458,17 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         }
         // Refresh all targets
         debug!(self.basics.logger, "Refreshing all targets...");
+        self.refresh_targets(|_| true);
+        // Update on mappings
+        self.update_on_mappings();
+        // Notify session (for UI purposes)
+        self.basics
+            .event_handler
+            .handle_event_ignoring_error(DomainEvent::ConditionsChanged);
+    }
+
+    /// Re-resolves the targets of all mappings matching the given predicate.
+    fn refresh_targets(&mut self, predicate: impl Fn(&MainMapping) -> bool) {
         for compartment in Compartment::enum_iter() {
             let mut target_updates: Vec<RealTimeTargetUpdate> = vec![];
             let mut changed_mappings = vec![];
@@ -1342,6 +1476,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             // Mappings with virtual targets don't have to be refreshed because virtual
             // targets are always active and never change depending on circumstances.
             for m in self.collections.mappings[compartment].values_mut() {
+                if !predicate(m) {
+                    continue;
+                }
                 let control_context = self.basics.control_context();
                 let context = ExtendedProcessorContext::new(
                     &self.basics.context,
@@ -1368,12 +1505,6 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 changed_mappings.into_iter(),
             );
         }
-        // Update on mappings
-        self.update_on_mappings();
-        // Notify session (for UI purposes)
-        self.basics
-            .event_handler
-            .handle_event_ignoring_error(DomainEvent::ConditionsChanged);
     }
 
     fn update_settings(&mut self, settings: BasicSettings) {
@@ -1624,6 +1755,13 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                     .self_normal_sender
                     .send_complaining(NormalMainTask::NotifyConditionsChanged);
             }
+            if let AdditionalFeedbackEvent::ItemsChanged(_) = event {
+                // Item targets might refer to other items now (e.g. selection changed)
+                self.basics
+                    .channels
+                    .self_normal_sender
+                    .send_complaining(NormalMainTask::NotifyItemsChanged);
+            }
             // Okay, not fired that frequently, we can iterate over all mappings
             self.process_feedback_related_reaper_event(|mapping, target| {
                 mapping.process_change_event(
@@ -1819,7 +1957,61 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 evt,
                 &self.collections.parameters,
             );
+        self.process_virtual_control_results(control_results);
+        self.process_mappings_with_real_targets(evt);
+    }
+
+    fn process_virtual_source_value(&mut self, evt: ControlEvent<VirtualSourceValue>) {
+        self.process_virtual_source_value_with_options(evt, ControlOptions::default());
+    }
+
+    fn process_virtual_source_value_with_options(
+        &mut self,
+        evt: ControlEvent<VirtualSourceValue>,
+        options: ControlOptions,
+    ) {
+        if self.basics.control_mode != ControlMode::Controlling
+            || !self.basics.instance_control_is_effectively_enabled()
+        {
+            return;
+        }
+        let mut control_results = self.basics.process_main_mappings_with_virtual_sources(
+            &mut self.collections.mappings[Compartment::Main],
+            evt,
+            options,
+            &self.collections.parameters,
+        );
+        if self.basics.settings.virtual_input_logging_enabled {
+            let match_outcome = if control_results.is_empty() {
+                MatchOutcome::Unmatched
+            } else {
+                MatchOutcome::Matched
+            };
+            log_virtual_control_input(
+                &self.basics.instance_id,
+                format_control_input_with_match_result(evt.payload(), match_outcome),
+            );
+        }
+        // Feedback goes back via the controller mappings, just as if a real control element had
+        // been used.
+        self.basics.send_feedback(
+            &self.collections.mappings_with_virtual_targets,
+            FeedbackReason::Normal,
+            control_results
+                .iter_mut()
+                .filter_map(|r| r.control_result.feedback_value.take()),
+        );
+        self.process_virtual_control_results(control_results);
+    }
+
+    /// Finishes processing the results of main mappings with virtual sources.
+    fn process_virtual_control_results(
+        &mut self,
+        control_results: Vec<ExtendedMappingControlResult>,
+    ) {
+        let mut target_updates = vec![];
         for r in control_results {
+            target_updates.extend(r.target_update);
             control_mapping_stage_three(
                 &self.basics,
                 &mut self.collections,
@@ -1828,7 +2020,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 GroupInteractionProcessing::On(r.group_interaction_input),
             )
         }
-        self.process_mappings_with_real_targets(evt);
+        self.process_target_updates(Compartment::Main, target_updates);
     }
 
     fn log_incoming_message<T: Display>(&self, msg: T) {
@@ -1871,15 +2063,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 evt,
                 &self.collections.parameters,
             );
-        for r in control_results {
-            control_mapping_stage_three(
-                &self.basics,
-                &mut self.collections,
-                r.compartment,
-                r.control_result,
-                GroupInteractionProcessing::On(r.group_interaction_input),
-            )
-        }
+        self.process_virtual_control_results(control_results);
         let real_match_outcome = self.process_mappings_with_real_targets(evt);
         virtual_match_outcome.merge_with(real_match_outcome)
     }
@@ -1895,6 +2079,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         }
         match evt.payload() {
             OscPacket::Message(msg) => {
+                if self.process_incoming_osc_parameter_message(msg) {
+                    return;
+                }
                 let msg = MainSourceMessage::Osc(msg);
                 self.process_incoming_message_internal(evt.with_payload(msg));
             }
@@ -1906,6 +2093,39 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         }
     }
 
+    /// Sets the value of a compartment parameter if the given message is addressed to it (see
+    /// [`format_osc_parameter_address`]).
+    ///
+    /// Returns `true` if the message was addressed to a parameter of this instance.
+    fn process_incoming_osc_parameter_message(&self, msg: &OscMessage) -> bool {
+        let (compartment, index) = match parse_osc_parameter_address(&msg.addr, &self.instance_id) {
+            None => return false,
+            Some(r) => r,
+        };
+        let effective_value = match msg.args.first() {
+            Some(OscType::Float(v)) => *v as f64,
+            Some(OscType::Double(v)) => *v,
+            Some(OscType::Int(v)) => *v as f64,
+            Some(OscType::Long(v)) => *v as f64,
+            _ => return true,
+        };
+        let plugin_param_index = compartment.to_plugin_param_index(index);
+        let raw_value = self
+            .collections
+            .parameters
+            .at(plugin_param_index)
+            .setting()
+            .convert_to_raw_value(effective_value);
+        // Set it as if it had been automated, exactly like the other APIs do
+        let fx = self.basics.context.containing_fx().clone();
+        let _ = Global::task_support().do_later_in_main_thread_from_main_thread_asap(move || {
+            let _ = fx
+                .parameter_by_index(plugin_param_index.get())
+                .set_reaper_normalized_value(raw_value as f64);
+        });
+        true
+    }
+
     fn process_incoming_message_internal(
         &mut self,
         evt: ControlEvent<MainSourceMessage>,
@@ -1965,6 +2185,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             let mut enforce_target_refresh = false;
             // Search for 958 to know why we use a for loop here instead of collect().
             let mut results = vec![];
+            let mut target_updates = vec![];
             for m in self.collections.mappings[compartment]
                 .values_mut()
                 .filter(|m| m.control_is_effectively_on())
@@ -1975,6 +2196,18 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                     Some(ControlOutcome::Matched(v)) => v,
                     _ => continue,
                 };
+                // Target expressions might refer to captures of the OSC address pattern.
+                let control_context = self.basics.control_context();
+                let context = ExtendedProcessorContext::new(
+                    &self.basics.context,
+                    &self.collections.parameters,
+                    control_context,
+                );
+                if let Some(target_update) =
+                    m.refresh_target_if_osc_captures_changed(context, control_context)
+                {
+                    target_updates.push(target_update);
+                }
                 let control_event = evt.with_payload(control_value);
                 let options = ControlOptions {
                     enforce_target_refresh,
@@ -2001,6 +2234,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                         group_interaction: m.group_interaction(),
                         control_event,
                     },
+                    target_update: None,
                 };
                 results.push(extended_control_result);
             }
@@ -2013,6 +2247,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                     GroupInteractionProcessing::On(r.group_interaction_input),
                 )
             }
+            self.process_target_updates(compartment, target_updates);
         }
         match_outcome
     }
@@ -2695,6 +2930,21 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             );
         }
     }
+
+    /// Executes a hit instruction which isn't caused by a particular mapping.
+    fn execute_hit_instruction(&mut self, compartment: Compartment, hi: BoxedHitInstruction) {
+        let control_result = MappingControlResult {
+            hit_instruction: Some(hi),
+            ..Default::default()
+        };
+        control_mapping_stage_three(
+            &self.basics,
+            &mut self.collections,
+            compartment,
+            control_result,
+            GroupInteractionProcessing::Off,
+        );
+    }
 }
 
 /// State that contains only those properties of a mapping which ...
@@ -2735,12 +2985,41 @@ pub enum NormalMainTask {
         id: QualifiedMappingId,
         value: ControlValue,
     },
+    /// Lets main mappings with virtual sources process the given value as if it had been
+    /// emitted by a controller mapping.
+    ///
+    /// Used for virtual control element values which don't stem from a real control element,
+    /// e.g. values sent by a WebSocket client.
+    ProcessVirtualSourceValue(VirtualSourceValue),
+    /// Enables or disables all mappings in the given compartment which match the tag scope,
+    /// exactly like the "ReaLearn: Enable/disable mappings" target does.
+    EnableMappingsByTag {
+        compartment: Compartment,
+        scope: TagScope,
+        exclusivity: Exclusivity,
+        is_enable: bool,
+    },
+    /// Loads the given mapping snapshot, exactly like the "ReaLearn: Load mapping snapshot"
+    /// target does.
+    LoadMappingSnapshot {
+        compartment: Compartment,
+        scope: TagScope,
+        active_mappings_only: bool,
+        snapshot_id: VirtualMappingSnapshotIdForLoad,
+    },
     /// This should be sent on events such as track list change, FX focus etc.
     ///
     /// It will trigger a refresh of all targets (re-resolve) or even a preset change (if
     /// auto-load is enabled).
     NotifyConditionsChanged,
+    /// This should be sent when media items changed.
+    ///
+    /// It will trigger a refresh of the targets which refer to items.
+    NotifyItemsChanged,
     UpdateSettings(BasicSettings),
+    /// Speech settings are not part of the basic settings because the real-time processor
+    /// doesn't need them.
+    UpdateSpeechSettings(SpeechSettings),
     PotentiallyEnableOrDisableControlOrFeedback,
     SendAllFeedback,
     LogDebugInfo,
@@ -2889,6 +3168,9 @@ pub enum FeedbackMainTask {
 }
 
 /// A control-related task (which is potentially sent very frequently).
+// Forwarded sys-ex messages are copied into a fixed-size event because boxing them would allocate in
+// the real-time thread.
+#[allow(clippy::large_enum_variant)]
 pub enum ControlMainTask {
     Control {
         compartment: Compartment,
@@ -2896,6 +3178,10 @@ pub enum ControlMainTask {
         event: ControlEvent<ControlValue>,
         options: ControlOptions,
     },
+    /// Incoming MIDI message for mappings whose sources can't process it in real-time.
+    ControlMidi {
+        event: ControlEvent<ForwardedMidiMessage>,
+    },
     LogVirtualControlInput {
         event: ControlEvent<VirtualSourceValue>,
         match_outcome: MatchOutcome,
@@ -2921,6 +3207,23 @@ pub enum OwnedIncomingMidiMessage {
     SysEx(Vec<u8>),
 }
 
+/// Like [`OwnedIncomingMidiMessage`] but can be created in the real-time thread without allocating.
+#[derive(Copy, Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum ForwardedMidiMessage {
+    Short(RawShortMessage),
+    SysEx(RawMidiEvent),
+}
+
+impl ForwardedMidiMessage {
+    pub fn to_source_value(&self) -> MidiSourceValue<RawShortMessage> {
+        match self {
+            ForwardedMidiMessage::Short(msg) => MidiSourceValue::Plain(*msg),
+            ForwardedMidiMessage::SysEx(evt) => MidiSourceValue::BorrowedSysEx(evt.bytes()),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
 pub struct ControlOptions {
     pub enforce_send_feedback_after_control: bool,
@@ -2931,6 +3234,9 @@ pub struct ControlOptions {
     /// refreshing all targets *after* the transaction, which might be to late if the user relies on
     /// mapping order! Setting `refresh_target` will enforce refreshing (without updating cache).
     pub enforce_target_refresh: bool,
+    /// Which axis to control if the mapping has an XY source. `Y` makes the mapping control its
+    /// Y target.
+    pub xy_axis: XyAxis,
 }
 
 impl<EH: DomainEventHandler> Drop for MainProcessor<EH> {
@@ -3386,10 +3692,19 @@ impl<EH: DomainEventHandler> Basics<EH> {
                 Some(new_value)
             })
             .collect();
-        if !at_least_one_target_is_affected {
+        // The Y target of mappings with XY sources contributes to the same feedback message.
+        let y_target_is_affected = m.y_targets().iter().any(|target| match target {
+            CompoundMappingTarget::Reaper(t) => f(m, t).0,
+            _ => false,
+        });
+        if !at_least_one_target_is_affected && !y_target_is_affected {
             return;
         }
-        let new_target_value = aggregate_target_values(new_values.into_iter());
+        let new_target_value = if at_least_one_target_is_affected {
+            aggregate_target_values(new_values.into_iter())
+        } else {
+            m.current_aggregated_target_value(self.control_context())
+        };
         if let Some(new_value) = new_target_value {
             // Feedback
             let mapping_feedback_is_effectively_on = m.feedback_is_effectively_on();
@@ -3409,7 +3724,9 @@ impl<EH: DomainEventHandler> Basics<EH> {
                 FeedbackReason::Normal,
                 feedback_value,
             );
-            self.notify_target_value_changed(m, new_value);
+            if at_least_one_target_is_affected {
+                self.notify_target_value_changed(m, new_value);
+            }
         }
     }
 
@@ -3465,7 +3782,7 @@ impl<EH: DomainEventHandler> Basics<EH> {
             .values_mut()
             .filter(|m| m.control_is_effectively_on())
             .flat_map(|m| {
-                let virtual_source_value = match m.control_virtualizing(evt) {
+                let virtual_source_values = match m.control_virtualizing(evt) {
                     Some(ControlOutcome::Matched(v)) => v,
                     unmatched_or_consumed => {
                         match_outcome.upgrade_from(unmatched_or_consumed.into());
@@ -3474,42 +3791,48 @@ impl<EH: DomainEventHandler> Basics<EH> {
                 };
                 self.event_handler
                     .notify_mapping_matched(Compartment::Controller, m.id());
-                let results = self.process_main_mappings_with_virtual_sources(
-                    main_mappings,
-                    evt.with_payload(virtual_source_value),
-                    ControlOptions {
-                        // We inherit "Send feedback after control" if it's
-                        // enabled for the virtual mapping. That's the easy way to do it.
-                        // Downside: If multiple real control elements are mapped to one
-                        // virtual control element,
-                        // "feedback after control" will be sent to all of
-                        // those, which is technically not
-                        // necessary. It would be enough to just send it
-                        // to the one that was touched. However, it also doesn't really
-                        // hurt.
-                        enforce_send_feedback_after_control: m.options().feedback_send_behavior
-                            == FeedbackSendBehavior::SendFeedbackAfterControl,
-                        mode_control_options: m.mode_control_options(),
-                        // Not yet important at this point because one virtual target can't
-                        // affect a subsequent one.
-                        enforce_target_refresh: false,
-                    },
-                    params,
-                );
-                let child_match_outcome = if results.is_empty() {
-                    MatchOutcome::Unmatched
-                } else {
-                    MatchOutcome::Matched
-                };
-                match_outcome.upgrade_from(child_match_outcome);
-                if self.settings.virtual_input_logging_enabled {
-                    log_virtual_control_input(
-                        &self.instance_id,
-                        format_control_input_with_match_result(
-                            virtual_source_value,
-                            child_match_outcome,
-                        ),
+                let mut results = vec![];
+                for virtual_source_value in virtual_source_values {
+                    let value_results = self.process_main_mappings_with_virtual_sources(
+                        main_mappings,
+                        evt.with_payload(virtual_source_value),
+                        ControlOptions {
+                            // We inherit "Send feedback after control" if it's
+                            // enabled for the virtual mapping. That's the easy way to do it.
+                            // Downside: If multiple real control elements are mapped to one
+                            // virtual control element,
+                            // "feedback after control" will be sent to all of
+                            // those, which is technically not
+                            // necessary. It would be enough to just send it
+                            // to the one that was touched. However, it also doesn't really
+                            // hurt.
+                            enforce_send_feedback_after_control: m.options().feedback_send_behavior
+                                == FeedbackSendBehavior::SendFeedbackAfterControl,
+                            mode_control_options: m.mode_control_options(),
+                            // Not yet important at this point because one virtual target can't
+                            // affect a subsequent one.
+                            enforce_target_refresh: false,
+                            // Set per mapping because it depends on the virtual source value.
+                            xy_axis: Default::default(),
+                        },
+                        params,
                     );
+                    let child_match_outcome = if value_results.is_empty() {
+                        MatchOutcome::Unmatched
+                    } else {
+                        MatchOutcome::Matched
+                    };
+                    match_outcome.upgrade_from(child_match_outcome);
+                    if self.settings.virtual_input_logging_enabled {
+                        log_virtual_control_input(
+                            &self.instance_id,
+                            format_control_input_with_match_result(
+                                virtual_source_value,
+                                child_match_outcome,
+                            ),
+                        );
+                    }
+                    results.extend(value_results);
                 }
                 results
             })
@@ -3555,6 +3878,18 @@ impl<EH: DomainEventHandler> Basics<EH> {
                         // Should always be true.
                         if let Some(t) = m.virtual_target() {
                             if t.control_element() == value.control_element() {
+                                // With XY control elements, a virtual target for the Y axis gets
+                                // the Y value only. A virtual target for the X axis gets both,
+                                // so that the source can send them in one message.
+                                let (x, y) = match (t.control_element(), t.axis()) {
+                                    (VirtualControlElement::Xy(_), XyAxis::Y) => {
+                                        match value.y_feedback_value() {
+                                            None => continue,
+                                            Some(y) => (y, None),
+                                        }
+                                    }
+                                    _ => (value.feedback_value(), value.y_feedback_value()),
+                                };
                                 // Virtual source matched virtual target. The following method
                                 // will always produce real target values (because controller
                                 // mappings can't have virtual sources).
@@ -3562,7 +3897,8 @@ impl<EH: DomainEventHandler> Basics<EH> {
                                     // This clone is unavoidable because we are producing
                                     // real feedback values and these will be sent to another
                                     //  thread, so they must be self-contained.
-                                    Cow::Borrowed(value.feedback_value()),
+                                    Cow::Borrowed(x),
+                                    y,
                                     FeedbackDestinations {
                                         with_source_feedback: destinations.with_source_feedback
                                             && m.feedback_is_enabled(),
@@ -3712,7 +4048,7 @@ impl<EH: DomainEventHandler> Basics<EH> {
                         .send_complaining(OscFeedbackTask::new(dev_id, msg));
                 }
                 (FinalSourceFeedbackValue::Reaper(ReaperSourceFeedbackValue::Speech(v)), _) => {
-                    let _ = say(v);
+                    let _ = say(&v.text, &v.settings.apply_to(&self.speech_settings));
                 }
                 _ => {}
             }
@@ -3813,35 +4149,40 @@ impl<EH: DomainEventHandler> Basics<EH> {
             .values_mut()
             .filter(|m| m.control_is_effectively_on())
             .filter_map(|m| {
-                if let CompoundMappingSource::Virtual(s) = &m.source() {
-                    let control_value = s.control(&evt.payload())?;
-                    let control_event = evt.with_payload(control_value);
-                    let options = ControlOptions {
-                        enforce_target_refresh,
-                        ..options
-                    };
-                    let control_result = control_mapping_stage_one_and_two(
-                        self,
-                        params,
-                        m,
+                let payload = evt.payload();
+                let control_value = m.control_virtual_source(evt.with_payload(&payload))?;
+                // Target expressions might refer to OSC address captures forwarded by the
+                // controller mapping.
+                let control_context = self.control_context();
+                let context = ExtendedProcessorContext::new(&self.context, params, control_context);
+                let target_update =
+                    m.refresh_target_if_osc_captures_changed(context, control_context);
+                let control_event = evt.with_payload(control_value);
+                let options = ControlOptions {
+                    enforce_target_refresh,
+                    xy_axis: evt.payload().axis(),
+                    ..options
+                };
+                let control_result = control_mapping_stage_one_and_two(
+                    self,
+                    params,
+                    m,
+                    control_event,
+                    options,
+                    ManualFeedbackProcessing::Off,
+                );
+                enforce_target_refresh = true;
+                let extended_control_result = ExtendedMappingControlResult {
+                    control_result,
+                    compartment: m.compartment(),
+                    group_interaction_input: GroupInteractionInput {
+                        mapping_id: m.id(),
+                        group_interaction: m.group_interaction(),
                         control_event,
-                        options,
-                        ManualFeedbackProcessing::Off,
-                    );
-                    enforce_target_refresh = true;
-                    let extended_control_result = ExtendedMappingControlResult {
-                        control_result,
-                        compartment: m.compartment(),
-                        group_interaction_input: GroupInteractionInput {
-                            mapping_id: m.id(),
-                            group_interaction: m.group_interaction(),
-                            control_event,
-                        },
-                    };
-                    Some(extended_control_result)
-                } else {
-                    None
-                }
+                    },
+                    target_update,
+                };
+                Some(extended_control_result)
             })
             .collect()
     }
@@ -3905,6 +4246,54 @@ impl InstanceId {
     }
 }
 
+/// Returns the OSC address with which the given compartment parameter can be read and written.
+///
+/// Uses the instance ID (not the user-defined session ID) because it's guaranteed to be a valid
+/// OSC address segment.
+pub fn format_osc_parameter_address(
+    instance_id: &InstanceId,
+    compartment: Compartment,
+    index: CompartmentParamIndex,
+) -> String {
+    format!(
+        "/realearn/{}/{}/parameters/{}",
+        instance_id,
+        get_osc_compartment_segment(compartment),
+        index.get() + 1
+    )
+}
+
+fn parse_osc_parameter_address(
+    address: &str,
+    instance_id: &InstanceId,
+) -> Option<(Compartment, CompartmentParamIndex)> {
+    let mut segments = address.strip_prefix("/realearn/")?.split('/');
+    if segments.next()? != instance_id.to_string() {
+        return None;
+    }
+    let compartment = match segments.next()? {
+        "controller" => Compartment::Controller,
+        "main" => Compartment::Main,
+        _ => return None,
+    };
+    if segments.next()? != "parameters" {
+        return None;
+    }
+    let position: u32 = segments.next()?.parse().ok()?;
+    if segments.next().is_some() {
+        return None;
+    }
+    let index = CompartmentParamIndex::try_from(position.checked_sub(1)?).ok()?;
+    Some((compartment, index))
+}
+
+fn get_osc_compartment_segment(compartment: Compartment) -> &'static str {
+    match compartment {
+        Compartment::Controller => "controller",
+        Compartment::Main => "main",
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Display)]
 pub enum MatchOutcome {
     /// Message didn't match at all, was not even consumed.
@@ -3971,7 +4360,8 @@ fn control_mapping_stage_one_and_two<EH: DomainEventHandler>(
 /// Takes care of:
 ///
 /// 1. Notifying that mapping matched
-/// 2. Controlling with given control value (probably produced by source) starting from mode.
+/// 2. Recognizing gestures (if the mapping has one)
+/// 3. Controlling with given control value (probably produced by source) starting from mode.
 #[must_use]
 fn control_mapping_stage_one<EH: DomainEventHandler>(
     basics: &Basics<EH>,
@@ -3983,6 +4373,23 @@ fn control_mapping_stage_one<EH: DomainEventHandler>(
     basics
         .event_handler
         .notify_mapping_matched(m.compartment(), m.id());
+    let control_event = match m.recognize_gesture(control_event) {
+        Some(e) => e,
+        // Gesture not complete (yet)
+        None => return Default::default(),
+    };
+    control_mapping_from_mode(basics, params, m, control_event, options)
+}
+
+/// Executes the part of stage one which comes after gesture recognition.
+#[must_use]
+fn control_mapping_from_mode<EH: DomainEventHandler>(
+    basics: &Basics<EH>,
+    params: &PluginParams,
+    m: &mut MainMapping,
+    control_event: ControlEvent<ControlValue>,
+    options: ControlOptions,
+) -> MappingControlResult {
     let result = m.control_from_mode(
         control_event,
         options,
@@ -4104,6 +4511,8 @@ struct ExtendedMappingControlResult {
     control_result: MappingControlResult,
     compartment: Compartment,
     group_interaction_input: GroupInteractionInput,
+    /// Is `Some` if the target has been re-resolved before controlling it.
+    target_update: Option<RealTimeTargetUpdate>,
 }
 
 struct GroupInteractionInput {
@@ -1,16 +1,17 @@
 use crate::domain::{
     get_prop_value, prop_feedback_resolution, prop_is_affected_by, ActivationChange,
-    ActivationCondition, BoxedHitInstruction, CompartmentParamIndex, CompoundChangeEvent,
-    ControlContext, ControlEvent, ControlEventTimestamp, ControlOptions, ExtendedProcessorContext,
-    FeedbackResolution, GroupId, HitResponse, KeyMessage, KeySource, MappingActivationEffect,
-    MappingControlContext, MappingData, MappingInfo, MessageCaptureEvent, MidiScanResult,
-    MidiSource, Mode, OscDeviceId, OscScanResult, PersistentMappingProcessingState,
-    PluginParamIndex, PluginParams, RealTimeMappingUpdate, RealTimeReaperTarget,
+    ActivationCondition, BoxedHitInstruction, ChordSource, CompartmentParamIndex,
+    CompoundChangeEvent, ControlContext, ControlEvent, ControlEventTimestamp, ControlOptions,
+    ExtendedProcessorContext, FeedbackResolution, GestureRecognizer, GroupId, HitResponse,
+    KeyMessage, KeySource, MappingActivationEffect, MappingControlContext, MappingData,
+    MappingInfo, MessageCaptureEvent, MidiScanResult, MidiSource, MidiSourceExt, Mode, MpeSource,
+    MpeState, OscCaptures, OscDeviceId, OscScanResult, OscSource, PersistentMappingProcessingState,
+    PluginParamIndex, PluginParams, ProcessorContext, RealTimeMappingUpdate, RealTimeReaperTarget,
     RealTimeTargetUpdate, RealearnParameterChangePayload, RealearnParameterSource, RealearnTarget,
     ReaperMessage, ReaperSource, ReaperSourceFeedbackValue, ReaperTarget, ReaperTargetType, Tag,
     TargetCharacter, TrackExclusivity, UnresolvedReaperTarget, VirtualControlElement,
-    VirtualFeedbackValue, VirtualSource, VirtualSourceAddress, VirtualSourceValue, VirtualTarget,
-    COMPARTMENT_PARAMETER_COUNT,
+    VirtualFeedbackValue, VirtualOscCaptures, VirtualSource, VirtualSourceAddress,
+    VirtualSourceValue, VirtualTarget, XyAxis, COMPARTMENT_PARAMETER_COUNT,
 };
 use derive_more::Display;
 use enum_iterator::IntoEnumIterator;
@@ -19,8 +20,8 @@ use helgoboss_learn::{
     format_percentage_without_unit, parse_percentage_without_unit, AbsoluteValue, ControlResult,
     ControlType, ControlValue, FeedbackValue, GroupInteraction, MidiSourceAddress, MidiSourceValue,
     ModeControlOptions, ModeControlResult, ModeFeedbackOptions, NumericFeedbackValue, NumericValue,
-    OscSource, OscSourceAddress, PreliminaryMidiSourceFeedbackValue, PropValue, RawMidiEvent,
-    SourceCharacter, SourceContext, Target, UnitValue, ValueFormatter, ValueParser,
+    OscSourceAddress, PreliminaryMidiSourceFeedbackValue, PropValue, RawMidiEvent, SourceCharacter,
+    SourceContext, Target, UnitValue, ValueFormatter, ValueParser,
 };
 use helgoboss_midi::{Channel, RawShortMessage, ShortMessage};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -30,7 +31,7 @@ use std::cell::Cell;
 use crate::domain::unresolved_reaper_target::UnresolvedReaperTargetDef;
 use indexmap::map::IndexMap;
 use indexmap::set::IndexSet;
-use reaper_high::{Fx, Project, Track, TrackRoute};
+use reaper_high::{Fx, Item, Project, Track, TrackRoute};
 use reaper_medium::MidiInputDeviceId;
 use rosc::OscMessage;
 use serde::{Deserialize, Serialize};
@@ -147,7 +148,7 @@ impl From<MappingKey> for String {
     }
 }
 
-const MAX_ECHO_FEEDBACK_DELAY: Duration = Duration::from_millis(100);
+pub const MAX_ECHO_FEEDBACK_DELAY: Duration = Duration::from_millis(100);
 
 #[derive(Debug)]
 pub enum LifecycleMidiMessage {
@@ -199,6 +200,35 @@ pub struct MainMapping {
     initial_target_value: Option<AbsoluteValue>,
     /// Called "y_last" in the control transformation formula.
     last_non_performance_target_value: Cell<Option<AbsoluteValue>>,
+    /// Numbers captured from the address of the last matching OSC message.
+    ///
+    /// Exposed to target expressions as variables named after the address pattern placeholders.
+    osc_captures: Vec<f64>,
+    /// Numbers captured from the OSC address of a controller mapping, forwarded via the last
+    /// matching virtual source value.
+    virtual_osc_captures: VirtualOscCaptures,
+    /// Whether the OSC captures changed since the target has been resolved the last time.
+    osc_captures_changed: bool,
+    /// Text which came with the last matching source message (e.g. an OSC string argument).
+    incoming_text: Option<String>,
+    /// Is `Some` if this mapping has an XY source and a target for the Y axis.
+    y_axis: Option<Box<YAxis>>,
+    /// One mode for each additional argument of an OSC source.
+    ///
+    /// Have the same settings as the mapping mode but their own processing state.
+    additional_arg_modes: Vec<Mode>,
+}
+
+/// Target-related state of the Y axis of a mapping with an XY source.
+///
+/// While controlling the Y axis, this is temporarily swapped with the corresponding state of the
+/// mapping itself (which represents the X axis).
+#[derive(Debug)]
+struct YAxis {
+    unresolved_target: Option<UnresolvedCompoundMappingTarget>,
+    targets: Vec<CompoundMappingTarget>,
+    /// Has the same settings as the mapping mode but its own processing state.
+    mode: Mode,
 }
 
 #[derive(Default, Debug)]
@@ -225,12 +255,31 @@ impl MainMapping {
         source: CompoundMappingSource,
         mode: Mode,
         group_interaction: GroupInteraction,
+        gesture: GestureRecognizer,
         unresolved_target: Option<UnresolvedCompoundMappingTarget>,
+        unresolved_y_target: Option<UnresolvedCompoundMappingTarget>,
         activation_condition_1: ActivationCondition,
         activation_condition_2: ActivationCondition,
         options: ProcessorMappingOptions,
         extension: MappingExtension,
     ) -> MainMapping {
+        let y_axis = match (&source, unresolved_y_target) {
+            (CompoundMappingSource::Virtual(s), Some(t))
+                if matches!(s.control_element(), VirtualControlElement::Xy(_)) =>
+            {
+                let y_axis = YAxis {
+                    unresolved_target: Some(t),
+                    targets: vec![],
+                    mode: mode.clone(),
+                };
+                Some(Box::new(y_axis))
+            }
+            _ => None,
+        };
+        let additional_arg_modes = match &source {
+            CompoundMappingSource::Osc(s) => vec![mode.clone(); s.additional_arg_count()],
+            _ => vec![],
+        };
         MainMapping {
             core: MappingCore {
                 compartment,
@@ -239,6 +288,7 @@ impl MainMapping {
                 source,
                 mode,
                 group_interaction,
+                gesture,
                 options,
                 time_of_last_control: None,
                 invocation_count: 0,
@@ -257,6 +307,12 @@ impl MainMapping {
             extension,
             initial_target_value: None,
             last_non_performance_target_value: Cell::new(None),
+            osc_captures: vec![],
+            virtual_osc_captures: Default::default(),
+            osc_captures_changed: false,
+            incoming_text: None,
+            y_axis,
+            additional_arg_modes,
         }
     }
 
@@ -481,12 +537,20 @@ impl MainMapping {
         MappingActivationEffect::new(self.id(), effect_1, effect_2)
     }
 
-    /// Returns if this target is dynamic.
+    /// Returns if this target (or the Y target) is dynamic.
     pub fn target_can_be_affected_by_parameters(&self) -> bool {
-        match &self.unresolved_target {
-            Some(UnresolvedCompoundMappingTarget::Reaper(t)) => t.can_be_affected_by_parameters(),
-            _ => false,
-        }
+        let y_target = self
+            .y_axis
+            .as_ref()
+            .and_then(|y| y.unresolved_target.as_ref());
+        [self.unresolved_target.as_ref(), y_target]
+            .into_iter()
+            .any(|t| match t {
+                Some(UnresolvedCompoundMappingTarget::Reaper(t)) => {
+                    t.can_be_affected_by_parameters()
+                }
+                _ => false,
+            })
     }
 
     /// Returns if the mapping's activation conditions can be affected by parameter changes in
@@ -554,6 +618,7 @@ impl MainMapping {
         let (targets, is_active) = self.resolve_target(context, control_context);
         self.targets = targets;
         self.core.options.target_is_active = is_active;
+        self.refresh_y_targets(context, control_context);
         self.update_activation_from_params(context.params());
         let target_value = self.current_aggregated_target_value(control_context);
         self.initial_target_value = target_value;
@@ -565,6 +630,15 @@ impl MainMapping {
         context: ExtendedProcessorContext,
         control_context: ControlContext,
     ) -> (Vec<CompoundMappingTarget>, bool) {
+        self.osc_captures_changed = false;
+        let osc_captures = match &self.core.source {
+            CompoundMappingSource::Osc(s) => {
+                OscCaptures::new(s.capture_names(), &self.osc_captures)
+            }
+            CompoundMappingSource::Virtual(_) => OscCaptures::forwarded(&self.virtual_osc_captures),
+            _ => Default::default(),
+        };
+        let context = context.with_osc_captures(osc_captures);
         match self.unresolved_target.as_ref() {
             None => (vec![], false),
             Some(ut) => match ut.resolve(context, self.core.compartment).ok() {
@@ -585,6 +659,17 @@ impl MainMapping {
         }
     }
 
+    pub fn needs_refresh_when_items_changed(&self) -> bool {
+        let y_target = self
+            .y_axis
+            .as_ref()
+            .and_then(|y| y.unresolved_target.as_ref());
+        self.unresolved_target
+            .iter()
+            .chain(y_target)
+            .any(|t| t.refers_to_item())
+    }
+
     pub fn needs_refresh_when_target_touched(&self) -> bool {
         matches!(
             self.unresolved_target,
@@ -618,7 +703,9 @@ impl MainMapping {
     }
 
     pub fn wants_to_be_polled_for_control(&self) -> bool {
-        self.core.source.wants_to_be_polled() || self.core.mode.wants_to_be_polled()
+        self.core.source.wants_to_be_polled()
+            || self.core.mode.wants_to_be_polled()
+            || self.core.gesture.wants_to_be_polled()
     }
 
     /// The boolean return value tells if the resolved target changed in some way, the activation
@@ -629,6 +716,14 @@ impl MainMapping {
         context: ExtendedProcessorContext,
         control_context: ControlContext,
     ) -> Option<RealTimeTargetUpdate> {
+        let y_target_is_dynamic = self
+            .y_axis
+            .as_ref()
+            .and_then(|y| y.unresolved_target.as_ref())
+            .map_or(false, |t| t.can_be_affected_by_change_events());
+        if y_target_is_dynamic {
+            self.refresh_y_targets(context, control_context);
+        }
         match self.unresolved_target.as_ref() {
             None => return None,
             Some(t) => {
@@ -637,6 +732,28 @@ impl MainMapping {
                 }
             }
         }
+        self.refresh_target_internal(context, control_context)
+    }
+
+    /// Re-resolves the target if the captures of the OSC address pattern changed, so that target
+    /// expressions can pick up the new values.
+    #[must_use]
+    pub fn refresh_target_if_osc_captures_changed(
+        &mut self,
+        context: ExtendedProcessorContext,
+        control_context: ControlContext,
+    ) -> Option<RealTimeTargetUpdate> {
+        if !self.osc_captures_changed || self.unresolved_target.is_none() {
+            return None;
+        }
+        self.refresh_target_internal(context, control_context)
+    }
+
+    fn refresh_target_internal(
+        &mut self,
+        context: ExtendedProcessorContext,
+        control_context: ControlContext,
+    ) -> Option<RealTimeTargetUpdate> {
         let was_effectively_active_before = self.target_is_effectively_active();
         let (targets, is_active) = self.resolve_target(context, control_context);
         let target_changed = targets != self.targets;
@@ -745,10 +862,63 @@ impl MainMapping {
         &self.core.source
     }
 
+    /// Lets the source of this mapping process the given virtual source value.
+    ///
+    /// Returns `None` if this mapping doesn't have a virtual source or the source doesn't react.
+    pub fn control_virtual_source(
+        &mut self,
+        evt: ControlEvent<&VirtualSourceValue>,
+    ) -> Option<ControlValue> {
+        let control_value = self.core.source.control_virtual(evt)?;
+        let osc_captures = evt.payload().osc_captures();
+        if *osc_captures != self.virtual_osc_captures {
+            self.virtual_osc_captures = *osc_captures;
+            self.osc_captures_changed = true;
+        }
+        Some(control_value)
+    }
+
     pub fn targets(&self) -> &[CompoundMappingTarget] {
         &self.targets
     }
 
+    /// Returns the resolved targets of the Y axis (empty if this mapping doesn't have a Y target).
+    pub fn y_targets(&self) -> &[CompoundMappingTarget] {
+        match &self.y_axis {
+            None => &[],
+            Some(y) => &y.targets,
+        }
+    }
+
+    /// Temporarily swaps in the Y axis state and executes the given function.
+    ///
+    /// Returns `None` if this mapping doesn't have a Y target.
+    fn with_y_axis<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> Option<R> {
+        let mut y_axis = self.y_axis.take()?;
+        self.swap_y_axis(&mut y_axis);
+        let result = f(self);
+        self.swap_y_axis(&mut y_axis);
+        self.y_axis = Some(y_axis);
+        Some(result)
+    }
+
+    fn swap_y_axis(&mut self, y_axis: &mut YAxis) {
+        std::mem::swap(&mut self.unresolved_target, &mut y_axis.unresolved_target);
+        std::mem::swap(&mut self.targets, &mut y_axis.targets);
+        std::mem::swap(&mut self.core.mode, &mut y_axis.mode);
+    }
+
+    fn refresh_y_targets(
+        &mut self,
+        context: ExtendedProcessorContext,
+        control_context: ControlContext,
+    ) {
+        self.with_y_axis(|m| {
+            let (targets, _) = m.resolve_target(context, control_context);
+            m.targets = targets;
+        });
+    }
+
     /// This makes the button fire modes work (e.g. "Fire after delay").
     #[must_use]
     pub fn poll_mode(
@@ -774,6 +944,28 @@ impl MainMapping {
         self.core.group_interaction
     }
 
+    pub fn has_gesture(&self) -> bool {
+        self.core.has_gesture()
+    }
+
+    /// Lets the gesture recognizer process the given control event.
+    ///
+    /// Returns the event unchanged if this mapping doesn't have a gesture and `None` if the event
+    /// doesn't complete the gesture.
+    pub fn recognize_gesture(
+        &mut self,
+        evt: ControlEvent<ControlValue>,
+    ) -> Option<ControlEvent<ControlValue>> {
+        let value = self.core.gesture.control(evt)?;
+        Some(evt.with_payload(value))
+    }
+
+    /// Returns a control value if a gesture timeout has been reached which completes the
+    /// gesture.
+    pub fn poll_gesture(&mut self, timestamp: ControlEventTimestamp) -> Option<ControlValue> {
+        self.core.gesture.poll(timestamp)
+    }
+
     /// Controls mode => target.
     ///
     /// Don't execute in real-time processor because this executes REAPER main-thread-only
@@ -790,6 +982,45 @@ impl MainMapping {
         last_non_performance_target_value: Option<AbsoluteValue>,
         log_mode_control_result: impl Fn(ControlLogEntry),
     ) -> MappingControlResult {
+        if options.xy_axis == XyAxis::Y {
+            let result = self.with_y_axis(|m| {
+                m.control_from_mode(
+                    source_control_event,
+                    ControlOptions {
+                        xy_axis: XyAxis::X,
+                        ..options
+                    },
+                    context,
+                    logger,
+                    processor_context,
+                    None,
+                    log_mode_control_result,
+                )
+            });
+            let result = result.unwrap_or_default();
+            // Feedback produced while the Y axis was swapped in lacks the X coordinate, so we
+            // produce it again.
+            let feedback_value = result.feedback_value.and_then(|v| {
+                let value = self.feedback_entry_point(
+                    !v.is_feedback_after_control,
+                    true,
+                    self.current_aggregated_target_value(context)?,
+                    context,
+                )?;
+                let v = CompoundFeedbackValue {
+                    value,
+                    is_feedback_after_control: v.is_feedback_after_control,
+                };
+                Some(v)
+            });
+            return MappingControlResult {
+                // The new target value is the one of the main target. It's used for target-based
+                // conditional activation and UI updates, so we must not report the Y value.
+                new_target_value: None,
+                feedback_value,
+                ..result
+            };
+        }
         let result = self.control_internal(
             options,
             context,
@@ -935,6 +1166,7 @@ impl MainMapping {
         let ctx = MappingControlContext {
             control_context: context,
             mapping_data: self.data(),
+            incoming_text: self.incoming_text.as_deref(),
         };
         let actual_targets = if options.enforce_target_refresh {
             &mut fresh_targets
@@ -1147,8 +1379,14 @@ impl MainMapping {
         } else {
             true
         };
-        self.feedback_given_target_value(
+        let mode_value = self.core.mode.feedback_with_options_detail(
             Cow::Owned(feedback_value),
+            self.mode_feedback_options(),
+            Default::default(),
+        )?;
+        self.feedback_given_mode_value(
+            mode_value,
+            self.y_mode_feedback_value(control_context),
             FeedbackDestinations {
                 with_projection_feedback,
                 with_source_feedback: with_source_feedback && source_feedback_is_okay,
@@ -1157,6 +1395,30 @@ impl MainMapping {
         )
     }
 
+    /// Returns the current value of the Y target, already processed by the mode.
+    ///
+    /// Returns `None` if this mapping doesn't have a Y target.
+    fn y_mode_feedback_value(&self, context: ControlContext) -> Option<FeedbackValue<'static>> {
+        let y_axis = self.y_axis.as_ref()?;
+        let target_value =
+            aggregate_target_values(y_axis.targets.iter().map(|t| t.current_value(context)))?;
+        let feedback_value =
+            FeedbackValue::Numeric(NumericFeedbackValue::new(Default::default(), target_value));
+        let mode_value = y_axis.mode.feedback_with_options_detail(
+            Cow::Owned(feedback_value),
+            self.mode_feedback_options(),
+            Default::default(),
+        )?;
+        Some(mode_value.into_owned().make_owned())
+    }
+
+    fn mode_feedback_options(&self) -> ModeFeedbackOptions {
+        ModeFeedbackOptions {
+            source_is_virtual: self.core.source.is_virtual(),
+            max_discrete_source_value: self.core.source.max_discrete_value(),
+        }
+    }
+
     pub fn current_aggregated_target_value(
         &self,
         context: ControlContext,
@@ -1178,27 +1440,37 @@ impl MainMapping {
     /// can give us ownership of the feedback value. It's also better than taking an owned value
     /// because it's possible that we don't produce a feedback value at all! In which a consumer
     /// that can't give up ownership would need to make a clone in advance - for nothing!
+    ///
+    /// The Y feedback value is only relevant for mappings with a virtual XY target. It's processed
+    /// by the same mode and sent along with the X value (if the source supports that).
     pub fn feedback_given_target_value(
         &self,
         feedback_value: Cow<FeedbackValue>,
+        y_feedback_value: Option<&FeedbackValue>,
         destinations: FeedbackDestinations,
         source_context: &SourceContext,
     ) -> Option<SpecificCompoundFeedbackValue> {
-        let options = ModeFeedbackOptions {
-            source_is_virtual: self.core.source.is_virtual(),
-            max_discrete_source_value: self.core.source.max_discrete_value(),
-        };
         let mode_value = self.core.mode.feedback_with_options_detail(
             feedback_value,
-            options,
+            self.mode_feedback_options(),
             Default::default(),
         )?;
-        self.feedback_given_mode_value(mode_value, destinations, source_context)
+        let y_mode_value = y_feedback_value
+            .and_then(|v| {
+                self.core.mode.feedback_with_options_detail(
+                    Cow::Borrowed(v),
+                    self.mode_feedback_options(),
+                    Default::default(),
+                )
+            })
+            .map(|v| v.into_owned().make_owned());
+        self.feedback_given_mode_value(mode_value, y_mode_value, destinations, source_context)
     }
 
     fn feedback_given_mode_value(
         &self,
         mode_value: Cow<FeedbackValue>,
+        y_mode_value: Option<FeedbackValue<'static>>,
         destinations: FeedbackDestinations,
         source_context: &SourceContext,
     ) -> Option<SpecificCompoundFeedbackValue> {
@@ -1207,6 +1479,7 @@ impl MainMapping {
             self.key.clone(),
             &self.core.source,
             mode_value,
+            y_mode_value,
             destinations,
             source_context,
         )
@@ -1220,6 +1493,7 @@ impl MainMapping {
         //  have different values for that (at the moment it's not though).
         self.feedback_given_mode_value(
             Cow::Owned(FeedbackValue::Off),
+            None,
             FeedbackDestinations {
                 with_projection_feedback: true,
                 with_source_feedback: true,
@@ -1257,7 +1531,8 @@ impl MainMapping {
 
     /// Controls the source only.
     ///
-    /// Doesn't consider MIDI sources because they are handled completely in the real-time mapping.
+    /// Considers MIDI sources only if they have a control script which can't be executed in
+    /// real-time. All other MIDI sources are handled completely in the real-time mapping.
     pub fn control_source(
         &mut self,
         msg: MainSourceMessage,
@@ -1267,7 +1542,13 @@ impl MainMapping {
             (MainSourceMessage::Osc(m), CompoundMappingSource::Osc(s)) => {
                 // With OSC sources, we don't distinguish between matched or consumed because
                 // there's no such thing such as "letting messages through".
-                s.control(m).map(ControlOutcome::Matched)
+                let result = s.control(m)?;
+                if result.captures != self.osc_captures {
+                    self.osc_captures = result.captures;
+                    self.osc_captures_changed = true;
+                }
+                self.incoming_text = result.text;
+                Some(ControlOutcome::Matched(result.value))
             }
             (MainSourceMessage::Reaper(m), CompoundMappingSource::Reaper(s)) => {
                 // With REAPER sources, we don't distinguish between matched or consumed because
@@ -1275,39 +1556,115 @@ impl MainMapping {
                 s.control(m, compartment).map(ControlOutcome::Matched)
             }
             (MainSourceMessage::Key(m), CompoundMappingSource::Key(s)) => s.control(m),
+            (MainSourceMessage::Midi(v), CompoundMappingSource::Midi(s)) => {
+                // The real-time processor has taken care of letting messages through already.
+                s.control_in_main_thread(v).map(ControlOutcome::Matched)
+            }
             _ => None,
         }
     }
 
     /// Polls the source.
-    pub fn poll_source(&mut self) -> Option<ControlValue> {
+    pub fn poll_source(&mut self, context: &ProcessorContext) -> Option<ControlValue> {
         match &mut self.core.source {
-            CompoundMappingSource::Reaper(s) => s.poll(),
+            CompoundMappingSource::Reaper(s) => s.poll(context),
             _ => None,
         }
     }
 
+    /// Usually returns exactly one virtual source value. OSC sources with additional arguments
+    /// can return more (one for each argument, addressing subsequent virtual control elements).
+    /// If the virtual target is an XY control element, the first additional argument addresses the
+    /// other axis instead.
     pub fn control_virtualizing(
         &mut self,
         evt: ControlEvent<MainSourceMessage>,
-    ) -> Option<ControlOutcome<VirtualSourceValue>> {
+    ) -> Option<ControlOutcome<Vec<VirtualSourceValue>>> {
         if self.targets.is_empty() {
             return None;
         }
-        let control_value = match self.control_source(evt.payload())? {
-            ControlOutcome::Consumed => {
-                return Some(ControlOutcome::Consumed);
+        let osc_result = match (evt.payload(), &self.core.source) {
+            (MainSourceMessage::Osc(m), CompoundMappingSource::Osc(s)) => {
+                let result = s.control(m)?;
+                let captures = VirtualOscCaptures::new(s.capture_names(), &result.captures);
+                Some((result, captures))
             }
-            ControlOutcome::Matched(v) => v,
+            _ => None,
+        };
+        let (control_value, additional_control_values, osc_captures) = match osc_result {
+            Some((result, captures)) => (result.value, result.additional_values, captures),
+            None => match self.control_source(evt.payload())? {
+                ControlOutcome::Consumed => {
+                    return Some(ControlOutcome::Consumed);
+                }
+                ControlOutcome::Matched(v) => (v, vec![], Default::default()),
+            },
         };
         // First target is enough because this does nothing yet.
-        let virtual_source_value = match self.targets.first()? {
-            CompoundMappingTarget::Virtual(t) => {
-                match_partially(&mut self.core, t, evt.with_payload(control_value))
+        let target = match self.targets.first()? {
+            CompoundMappingTarget::Virtual(t) => t,
+            CompoundMappingTarget::Reaper(_) => return None,
+        };
+        let mut virtual_source_values = vec![];
+        virtual_source_values.extend(match_partially(
+            &mut self.core,
+            target,
+            evt.with_payload(control_value),
+        ));
+        for (i, control_value) in additional_control_values.into_iter().enumerate() {
+            let control_value = match control_value {
+                None => continue,
+                Some(v) => v,
+            };
+            let additional_target = if let VirtualControlElement::Xy(_) = target.control_element() {
+                // The first additional argument provides the other axis of the XY control element.
+                if i > 0 {
+                    continue;
+                }
+                let other_axis = match target.axis() {
+                    XyAxis::X => XyAxis::Y,
+                    XyAxis::Y => XyAxis::X,
+                };
+                target.with_axis(other_axis)
+            } else {
+                let offset = i as u32 + 1;
+                VirtualTarget::new(target.control_element().with_offset(offset))
+            };
+            let mode = match self.additional_arg_modes.get_mut(i) {
+                None => continue,
+                Some(m) => m,
+            };
+            let value = match_partially_with_mode(
+                mode,
+                &additional_target,
+                evt.with_payload(control_value),
+            );
+            if value.is_some() {
+                self.core.time_of_last_control = Some(Instant::now());
             }
-            CompoundMappingTarget::Reaper(_) => None,
+            virtual_source_values.extend(value);
+        }
+        if virtual_source_values.is_empty() {
+            return None;
+        }
+        let virtual_source_values = virtual_source_values
+            .into_iter()
+            .map(|v| v.with_osc_captures(osc_captures))
+            .collect();
+        Some(ControlOutcome::Matched(virtual_source_values))
+    }
+
+    /// Virtualizes a control value which has been forwarded from the real-time processor because
+    /// the glue of this mapping can't be processed in real-time.
+    pub fn control_virtualizing_value(
+        &mut self,
+        evt: ControlEvent<ControlValue>,
+    ) -> Option<VirtualSourceValue> {
+        let target = match self.targets.first()? {
+            CompoundMappingTarget::Virtual(t) => t,
+            CompoundMappingTarget::Reaper(_) => return None,
         };
-        Some(ControlOutcome::Matched(virtual_source_value?))
+        match_partially(&mut self.core, target, evt)
     }
 }
 
@@ -1316,6 +1673,9 @@ pub enum MainSourceMessage<'a> {
     Osc(&'a OscMessage),
     Reaper(&'a ReaperMessage),
     Key(KeyMessage),
+    /// MIDI messages are usually processed completely in the real-time processor. This is only
+    /// used for MIDI script sources whose control script can't be executed in real-time (Lua).
+    Midi(&'a MidiSourceValue<'a, RawShortMessage>),
 }
 
 impl<'a> MainSourceMessage<'a> {
@@ -1329,6 +1689,8 @@ impl<'a> MainSourceMessage<'a> {
                 dev_id: None,
             }),
             Key(msg) => MessageCaptureResult::Keyboard(msg),
+            // MIDI learning happens in the real-time processor.
+            Midi(_) => return None,
             Reaper(msg) => {
                 use ReaperMessage::*;
                 match msg {
@@ -1479,16 +1841,16 @@ impl RealTimeMapping {
     pub fn control_midi_virtualizing(
         &mut self,
         evt: ControlEvent<&MidiSourceValue<RawShortMessage>>,
+        mpe_state: &MpeState,
     ) -> Option<PartialControlMatch> {
         if !self.target_is_resolved {
             return None;
         }
-        let control_value = if let CompoundMappingSource::Midi(s) = &self.core.source {
-            s.control(evt.payload())?
-        } else {
-            return None;
-        };
+        let control_value = self.core.source.control_midi(evt, mpe_state)?;
         if let Some(RealTimeCompoundMappingTarget::Virtual(t)) = self.resolved_target.as_ref() {
+            if !self.core.mode_is_real_time_capable() {
+                return Some(PartialControlMatch::ForwardVirtual(control_value));
+            }
             match_partially(&mut self.core, t, evt.with_payload(control_value))
                 .map(PartialControlMatch::ProcessVirtual)
         } else {
@@ -1500,6 +1862,9 @@ impl RealTimeMapping {
 pub enum PartialControlMatch {
     ProcessVirtual(VirtualSourceValue),
     ProcessDirect(ControlValue),
+    /// The glue of this mapping with virtual target can't be processed in real-time, so the
+    /// control value needs to be forwarded to the main processor, which does the virtualization.
+    ForwardVirtual(ControlValue),
 }
 
 #[derive(Clone, Debug)]
@@ -1510,6 +1875,7 @@ pub struct MappingCore {
     pub source: CompoundMappingSource,
     pub mode: Mode,
     group_interaction: GroupInteraction,
+    gesture: GestureRecognizer,
     options: ProcessorMappingOptions,
     /// Used for preventing echo feedback.
     time_of_last_control: Option<Instant>,
@@ -1530,6 +1896,20 @@ impl MappingCore {
         self.invocation_count = self.invocation_count.wrapping_add(1);
     }
 
+    /// Gestures are recognized in the main processor only.
+    pub fn has_gesture(&self) -> bool {
+        self.gesture.is_enabled()
+    }
+
+    /// Returns `false` if the glue needs to be processed in the main processor because its control
+    /// transformation can't be executed in real-time (Lua).
+    pub fn mode_is_real_time_capable(&self) -> bool {
+        match &self.mode.settings().control_transformation {
+            None => true,
+            Some(t) => t.is_real_time_capable(),
+        }
+    }
+
     fn is_echo(&self) -> bool {
         if let Some(t) = self.time_of_last_control {
             t.elapsed() <= MAX_ECHO_FEEDBACK_DELAY
@@ -1549,6 +1929,7 @@ impl MappingCore {
     fn on_deactivate(&mut self) {
         self.source.on_deactivate();
         self.mode.on_deactivate();
+        self.gesture.reset();
     }
 }
 
@@ -1561,6 +1942,8 @@ pub enum CompoundMappingSource {
     Virtual(VirtualSource),
     Reaper(ReaperSource),
     Key(KeySource),
+    Mpe(MpeSource),
+    Chord(ChordSource),
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -1594,6 +1977,7 @@ impl QualifiedSource {
             self.mapping_key,
             &self.source,
             Cow::Owned(FeedbackValue::Off),
+            None,
             FeedbackDestinations {
                 with_projection_feedback: true,
                 with_source_feedback: true,
@@ -1614,6 +1998,7 @@ impl CompoundMappingSource {
         use CompoundMappingSource::*;
         match self {
             Reaper(s) => s.on_deactivate(),
+            Chord(s) => s.reset(),
             _ => {}
         }
     }
@@ -1695,9 +2080,15 @@ impl CompoundMappingSource {
     ) -> Option<ControlResult> {
         use CompoundMappingSource::*;
         match (self, value) {
+            (Midi(s @ MidiSource::Script { .. }), IncomingCompoundSourceValue::Midi(v)) => {
+                s.control_including_script(v).map(ControlResult::Processed)
+            }
             (Midi(s), IncomingCompoundSourceValue::Midi(v)) => s.control_flexible(v),
+            (Mpe(s), IncomingCompoundSourceValue::Midi(MidiSourceValue::Plain(msg))) => {
+                s.reacts_to_message_with(msg).map(ControlResult::Processed)
+            }
             (Osc(s), IncomingCompoundSourceValue::Osc(m)) => {
-                s.control(m).map(ControlResult::Processed)
+                s.control(m).map(|res| ControlResult::Processed(res.value))
             }
             (Virtual(s), IncomingCompoundSourceValue::Virtual(m)) => {
                 s.control(m).map(ControlResult::Processed)
@@ -1743,7 +2134,10 @@ impl CompoundMappingSource {
             Virtual(s) => s.format_control_value(value),
             Osc(s) => s.format_control_value(value),
             Reaper(s) => s.format_control_value(value),
-            Never | Key(_) => Ok(format_percentage_without_unit(value.to_unit_value()?.get())),
+            Mpe(s) => s.format_control_value(value),
+            Never | Key(_) | Chord(_) => {
+                Ok(format_percentage_without_unit(value.to_unit_value()?.get()))
+            }
         }
     }
 
@@ -1754,7 +2148,8 @@ impl CompoundMappingSource {
             Virtual(s) => s.parse_control_value(text),
             Osc(s) => s.parse_control_value(text),
             Reaper(s) => s.parse_control_value(text),
-            Never | Key(_) => parse_percentage_without_unit(text)?.try_into(),
+            Mpe(s) => s.parse_control_value(text),
+            Never | Key(_) | Chord(_) => parse_percentage_without_unit(text)?.try_into(),
         }
     }
 
@@ -1766,7 +2161,8 @@ impl CompoundMappingSource {
             Osc(s) => ExtendedSourceCharacter::Normal(s.character()),
             Reaper(s) => ExtendedSourceCharacter::Normal(s.character()),
             Never => ExtendedSourceCharacter::VirtualContinuous,
-            Key(_) => ExtendedSourceCharacter::Normal(SourceCharacter::MomentaryButton),
+            Key(_) | Chord(_) => ExtendedSourceCharacter::Normal(SourceCharacter::MomentaryButton),
+            Mpe(s) => ExtendedSourceCharacter::Normal(s.character()),
         }
     }
 
@@ -1789,7 +2185,7 @@ impl CompoundMappingSource {
             // This is handled in a special way by consumers.
             Virtual(_) => None,
             // No feedback for other sources.
-            Key(_) | Never => None,
+            Key(_) | Mpe(_) | Chord(_) | Never => None,
         }
     }
 
@@ -1797,7 +2193,9 @@ impl CompoundMappingSource {
         use CompoundMappingSource::*;
         match self {
             Midi(s) => s.consumes(msg),
-            Reaper(_) | Virtual(_) | Osc(_) | Never | Key(_) => false,
+            // MPE and chord sources never consume because the same messages can be of interest
+            // for other mappings as well.
+            Reaper(_) | Virtual(_) | Osc(_) | Never | Key(_) | Mpe(_) | Chord(_) => false,
         }
     }
 
@@ -1805,13 +2203,57 @@ impl CompoundMappingSource {
         matches!(self, CompoundMappingSource::Virtual(_))
     }
 
+    /// Returns `true` if incoming MIDI messages need to be forwarded to the main processor because
+    /// this source can't process them in real-time (MIDI script source with Lua control script).
+    pub fn wants_main_thread_midi_control(&self) -> bool {
+        match self {
+            CompoundMappingSource::Midi(s) => s.wants_main_thread_control(),
+            _ => false,
+        }
+    }
+
+    /// Lets MIDI sources (including MIDI script, MPE and chord sources) process the given
+    /// incoming MIDI value in the real-time processor.
+    ///
+    /// MPE sources need the note allocation tracked in the given MPE state. MIDI script sources
+    /// with control scripts that can't be executed in real-time are ignored, see
+    /// [`Self::wants_main_thread_midi_control`].
+    pub fn control_midi(
+        &mut self,
+        evt: ControlEvent<&MidiSourceValue<RawShortMessage>>,
+        mpe_state: &MpeState,
+    ) -> Option<ControlValue> {
+        use CompoundMappingSource::*;
+        match (self, evt.payload()) {
+            (Midi(s), value) => s.control_in_real_time(value),
+            (Mpe(s), MidiSourceValue::Plain(msg)) => s.control(msg, mpe_state),
+            (Chord(s), _) => s.control_midi(evt),
+            _ => None,
+        }
+    }
+
+    /// Lets virtual sources (including virtual chord sources) process the given incoming virtual
+    /// source value.
+    pub fn control_virtual(
+        &mut self,
+        evt: ControlEvent<&VirtualSourceValue>,
+    ) -> Option<ControlValue> {
+        use CompoundMappingSource::*;
+        match self {
+            Virtual(s) => s.control(evt.payload()),
+            Chord(s) => s.control_virtual(evt),
+            _ => None,
+        }
+    }
+
     pub fn max_discrete_value(&self) -> Option<u32> {
         use CompoundMappingSource::*;
         match self {
             Midi(s) => s.max_discrete_value(),
+            Mpe(s) => s.max_discrete_value(),
             // TODO-medium OSC will also support discrete values as soon as we allow integers and
             //  configuring max values
-            Reaper(_) | Virtual(_) | Osc(_) | Never | Key(_) => None,
+            Reaper(_) | Virtual(_) | Osc(_) | Never | Key(_) | Chord(_) => None,
         }
     }
 }
@@ -1867,6 +2309,7 @@ impl SpecificCompoundFeedbackValue {
         mapping_key: Rc<str>,
         source: &CompoundMappingSource,
         mode_value: Cow<FeedbackValue>,
+        y_mode_value: Option<FeedbackValue<'static>>,
         destinations: FeedbackDestinations,
         source_context: &SourceContext,
     ) -> Option<SpecificCompoundFeedbackValue> {
@@ -1877,7 +2320,9 @@ impl SpecificCompoundFeedbackValue {
             // Virtual source
             SpecificCompoundFeedbackValue::Virtual {
                 destinations,
-                value: vs.feedback(mode_value.into_owned()),
+                value: vs
+                    .feedback(mode_value.into_owned())
+                    .with_y_feedback_value(y_mode_value),
             }
         } else {
             // Real source
@@ -1892,7 +2337,13 @@ impl SpecificCompoundFeedbackValue {
                 None
             };
             let source = if destinations.with_source_feedback {
-                source.feedback(mode_value, source_context)
+                match (source, y_mode_value) {
+                    // Send both coordinates of an XY control element in one message
+                    (CompoundMappingSource::Osc(s), Some(y)) => s
+                        .feedback_xy(mode_value.into_owned(), y)
+                        .map(PreliminarySourceFeedbackValue::Osc),
+                    _ => source.feedback(mode_value, source_context),
+                }
             } else {
                 None
             };
@@ -2021,6 +2472,16 @@ impl UnresolvedCompoundMappingTarget {
         }
     }
 
+    /// Returns whether this target refers to a media item, so it might resolve to another item
+    /// when items change (e.g. when the item selection changes).
+    pub fn refers_to_item(&self) -> bool {
+        use UnresolvedCompoundMappingTarget::*;
+        match self {
+            Reaper(t) => t.item_descriptor().is_some(),
+            Virtual(_) => false,
+        }
+    }
+
     /// `None` means that no polling is necessary for feedback because we are notified via events.
     pub fn feedback_resolution(&self) -> Option<FeedbackResolution> {
         use UnresolvedCompoundMappingTarget::*;
@@ -2300,6 +2761,14 @@ impl RealearnTarget for CompoundMappingTarget {
         }
     }
 
+    fn item(&self) -> Option<Item> {
+        use CompoundMappingTarget::*;
+        match self {
+            Reaper(t) => t.item(),
+            Virtual(_) => None,
+        }
+    }
+
     fn track_exclusivity(&self) -> Option<TrackExclusivity> {
         use CompoundMappingTarget::*;
         match self {
@@ -2463,6 +2932,15 @@ impl Compartment {
             .unwrap()
     }
 
+    /// Returns the plug-in parameter index corresponding to the given compartment-local parameter
+    /// index.
+    pub fn to_plugin_param_index(
+        self,
+        compartment_param_index: CompartmentParamIndex,
+    ) -> PluginParamIndex {
+        (self.plugin_param_offset() + compartment_param_index.get()).unwrap()
+    }
+
     /// Returns the plug-in parameter range corresponding to this compartment.
     pub fn plugin_param_range(self) -> RangeInclusive<PluginParamIndex> {
         let offset = self.plugin_param_offset();
@@ -2487,6 +2965,16 @@ fn match_partially(
     core: &mut MappingCore,
     target: &VirtualTarget,
     control_event: ControlEvent<ControlValue>,
+) -> Option<VirtualSourceValue> {
+    let res = match_partially_with_mode(&mut core.mode, target, control_event)?;
+    core.time_of_last_control = Some(Instant::now());
+    Some(res)
+}
+
+fn match_partially_with_mode(
+    mode: &mut Mode,
+    target: &VirtualTarget,
+    control_event: ControlEvent<ControlValue>,
 ) -> Option<VirtualSourceValue> {
     // Determine resulting virtual control value in real-time processor.
     // It's important to do that here. We need to know the result in order to
@@ -2495,7 +2983,7 @@ fn match_partially(
     // TODO-medium If we want to support fire after timeout and turbo for mappings with
     //  virtual targets one day, we need to poll this in real-time processor and OSC
     //  processing, too!
-    let res = core.mode.control_with_options(
+    let res = mode.control_with_options(
         control_event,
         target,
         (),
@@ -2505,8 +2993,8 @@ fn match_partially(
     )?;
     let transformed_control_value: Option<ControlValue> = res.into();
     let transformed_control_value = transformed_control_value?;
-    core.time_of_last_control = Some(Instant::now());
-    let res = VirtualSourceValue::new(target.control_element(), transformed_control_value);
+    let res = VirtualSourceValue::new(target.control_element(), transformed_control_value)
+        .with_axis(target.axis());
     Some(res)
 }
 
@@ -1,3 +1,210 @@
-use crate::domain::FlexibleMidiSourceScript;
+use crate::domain::{FlexibleMidiSourceControlScript, FlexibleMidiSourceScript};
+use helgoboss_learn::{
+    ControlValue, FeedbackValue, MidiSourceScript, MidiSourceScriptOutcome, MidiSourceValue,
+};
+use helgoboss_midi::{RawShortMessage, ShortMessage};
 
-pub type MidiSource = helgoboss_learn::MidiSource<FlexibleMidiSourceScript<'static>>;
+pub type MidiSource = helgoboss_learn::MidiSource<MidiSourceScripts<'static>>;
+
+/// Script which decodes incoming MIDI messages (control direction of a MIDI script source).
+pub trait MidiSourceControlScript {
+    /// Returns `None` if the script isn't interested in the given message.
+    fn execute(&self, bytes: &[u8]) -> Result<Option<ControlValue>, &'static str>;
+}
+
+/// The scripts of a MIDI script source.
+///
+/// The feedback script encodes feedback values, the control script decodes incoming messages.
+/// Both are optional, so a script source can be used for just one direction.
+#[derive(Clone, Debug)]
+pub struct MidiSourceScripts<'a> {
+    pub feedback_script: Option<FlexibleMidiSourceScript<'a>>,
+    pub control_script: Option<FlexibleMidiSourceControlScript>,
+}
+
+impl<'a> MidiSourceScripts<'a> {
+    /// Returns `None` if there's neither a feedback nor a control script.
+    pub fn new(
+        feedback_script: Option<FlexibleMidiSourceScript<'a>>,
+        control_script: Option<FlexibleMidiSourceControlScript>,
+    ) -> Option<Self> {
+        if feedback_script.is_none() && control_script.is_none() {
+            return None;
+        }
+        let scripts = Self {
+            feedback_script,
+            control_script,
+        };
+        Some(scripts)
+    }
+}
+
+impl<'a> MidiSourceScript for MidiSourceScripts<'a> {
+    fn execute(&self, input_value: FeedbackValue) -> Result<MidiSourceScriptOutcome, &'static str> {
+        self.feedback_script
+            .as_ref()
+            .ok_or("no feedback script")?
+            .execute(input_value)
+    }
+}
+
+pub trait MidiSourceExt {
+    /// Like [`helgoboss_learn::MidiSource::control`] but also lets the control script of a MIDI
+    /// script source decode the incoming message, no matter in which language it is written.
+    ///
+    /// Must not be called in the real-time thread!
+    fn control_including_script(
+        &self,
+        value: &MidiSourceValue<RawShortMessage>,
+    ) -> Option<ControlValue>;
+
+    /// Like [`Self::control_including_script`] but only lets real-time capable control scripts
+    /// (EEL) decode the incoming message.
+    ///
+    /// Messages for other control scripts (Lua) must be forwarded to the main processor, see
+    /// [`Self::wants_main_thread_control`].
+    fn control_in_real_time(
+        &self,
+        value: &MidiSourceValue<RawShortMessage>,
+    ) -> Option<ControlValue>;
+
+    /// Lets control scripts which can't be executed in the real-time thread (Lua) decode the
+    /// incoming message.
+    fn control_in_main_thread(
+        &self,
+        value: &MidiSourceValue<RawShortMessage>,
+    ) -> Option<ControlValue>;
+
+    /// Returns `true` if this is a MIDI script source with a control script that can't be executed
+    /// in the real-time thread (Lua).
+    fn wants_main_thread_control(&self) -> bool;
+}
+
+impl MidiSourceExt for MidiSource {
+    fn control_including_script(
+        &self,
+        value: &MidiSourceValue<RawShortMessage>,
+    ) -> Option<ControlValue> {
+        match control_script(self) {
+            None => self.control(value),
+            Some(s) => execute_control_script(s, value),
+        }
+    }
+
+    fn control_in_real_time(
+        &self,
+        value: &MidiSourceValue<RawShortMessage>,
+    ) -> Option<ControlValue> {
+        match control_script(self) {
+            None => self.control(value),
+            Some(s) if s.is_real_time_capable() => execute_control_script(s, value),
+            Some(_) => None,
+        }
+    }
+
+    fn control_in_main_thread(
+        &self,
+        value: &MidiSourceValue<RawShortMessage>,
+    ) -> Option<ControlValue> {
+        match control_script(self) {
+            Some(s) if !s.is_real_time_capable() => execute_control_script(s, value),
+            _ => None,
+        }
+    }
+
+    fn wants_main_thread_control(&self) -> bool {
+        matches!(control_script(self), Some(s) if !s.is_real_time_capable())
+    }
+}
+
+/// Returns `None` if this is not a script source or if it doesn't have a control script.
+fn control_script(source: &MidiSource) -> Option<&FlexibleMidiSourceControlScript> {
+    match source {
+        MidiSource::Script {
+            script: Some(scripts),
+        } => scripts.control_script.as_ref(),
+        _ => None,
+    }
+}
+
+fn execute_control_script(
+    control_script: &FlexibleMidiSourceControlScript,
+    value: &MidiSourceValue<RawShortMessage>,
+) -> Option<ControlValue> {
+    let result = match value {
+        MidiSourceValue::Plain(msg) => {
+            // Short messages are always passed as 3 bytes, no matter their actual size.
+            let (status_byte, data_byte_1, data_byte_2) = msg.to_bytes();
+            control_script.execute(&[status_byte, data_byte_1.get(), data_byte_2.get()])
+        }
+        MidiSourceValue::BorrowedSysEx(bytes) => control_script.execute(bytes),
+        _ => return None,
+    };
+    result.ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        CompoundMappingSource, ControlEvent, ControlEventTimestamp, EelMidiSourceControlScript,
+        LuaMidiSourceControlScript, MpeState,
+    };
+    use helgoboss_learn::UnitValue;
+    use helgoboss_midi::test_util::*;
+
+    #[test]
+    fn real_time_path_never_executes_lua_control_script() {
+        // Given
+        let text = "
+            invocation_count = (invocation_count or 0) + 1
+            return { value = invocation_count / 10 }
+        ";
+        let control_script = LuaMidiSourceControlScript::compile(text).unwrap();
+        let mut source = script_source(FlexibleMidiSourceControlScript::Lua(control_script));
+        let value = MidiSourceValue::Plain(control_change(0, 16, 1));
+        let mpe_state = MpeState::default();
+        // When
+        let real_time_outcomes: Vec<_> = (0..3)
+            .map(|_| source.control_midi(ControlEvent::new(&value, now()), &mpe_state))
+            .collect();
+        let main_thread_outcome = match &source {
+            CompoundMappingSource::Midi(s) => s.control_in_main_thread(&value),
+            _ => unreachable!(),
+        };
+        // Then
+        assert!(source.wants_main_thread_midi_control());
+        assert_eq!(real_time_outcomes, vec![None, None, None]);
+        // If the real-time path had executed the script, the invocation count would be higher.
+        assert_eq!(
+            main_thread_outcome,
+            Some(ControlValue::AbsoluteContinuous(UnitValue::new(0.1)))
+        );
+    }
+
+    #[test]
+    fn real_time_path_executes_eel_control_script() {
+        // Given
+        let text = "0[] == 0xb0 && 1[] == 16 ? value = 1;";
+        let control_script = EelMidiSourceControlScript::compile(text).unwrap();
+        let mut source = script_source(FlexibleMidiSourceControlScript::Eel(control_script));
+        let value = MidiSourceValue::Plain(control_change(0, 16, 1));
+        // When
+        let outcome = source.control_midi(ControlEvent::new(&value, now()), &MpeState::default());
+        // Then
+        assert!(!source.wants_main_thread_midi_control());
+        assert_eq!(
+            outcome,
+            Some(ControlValue::AbsoluteContinuous(UnitValue::MAX))
+        );
+    }
+
+    fn script_source(control_script: FlexibleMidiSourceControlScript) -> CompoundMappingSource {
+        let scripts = MidiSourceScripts::new(None, Some(control_script));
+        CompoundMappingSource::Midi(MidiSource::Script { script: scripts })
+    }
+
+    fn now() -> ControlEventTimestamp {
+        ControlEventTimestamp::now()
+    }
+}
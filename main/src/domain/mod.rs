@@ -22,6 +22,9 @@ pub use mode::*;
 mod midi_source;
 pub use midi_source::*;
 
+mod osc_source;
+pub use osc_source::*;
+
 mod eel_transformation;
 pub use eel_transformation::*;
 
@@ -34,6 +37,12 @@ pub use lua_midi_source_script::*;
 mod flexible_midi_source_script;
 pub use flexible_midi_source_script::*;
 
+mod lua_transformation;
+pub use lua_transformation::*;
+
+mod flexible_transformation;
+pub use flexible_transformation::*;
+
 mod realearn_target;
 pub use realearn_target::*;
 
@@ -58,6 +67,18 @@ pub use midi_source_scanner::*;
 mod midi_clock_calculator;
 pub use midi_clock_calculator::*;
 
+mod mpe;
+pub use mpe::*;
+
+mod chord_source;
+pub use chord_source::*;
+
+mod gesture;
+pub use gesture::*;
+
+mod speech;
+pub use speech::*;
+
 mod conditional_activation;
 pub use conditional_activation::*;
 
@@ -99,6 +120,12 @@ pub use midi_types::*;
 mod reaper_source;
 pub use reaper_source::*;
 
+mod musical_timer_source;
+pub use musical_timer_source::*;
+
+mod audio_level_source;
+pub use audio_level_source::*;
+
 mod key_source;
 pub use key_source::*;
 
@@ -111,6 +138,18 @@ pub use reaper_config_change_detector::*;
 mod monitoring_fx_chain_change_detector;
 pub use monitoring_fx_chain_change_detector::*;
 
+mod item_change_detector;
+pub use item_change_detector::*;
+
+mod item_util;
+pub use item_util::*;
+
+mod bookmark_util;
+pub use bookmark_util::*;
+
+mod envelope_util;
+pub use envelope_util::*;
+
 mod small_ascii_string;
 pub use small_ascii_string::*;
 
@@ -1,3 +1,3 @@
-use crate::domain::{ControlEventTimestamp, EelTransformation};
+use crate::domain::{ControlEventTimestamp, FlexibleTransformation};
 
-pub type Mode = helgoboss_learn::Mode<EelTransformation, ControlEventTimestamp>;
+pub type Mode = helgoboss_learn::Mode<FlexibleTransformation, ControlEventTimestamp>;
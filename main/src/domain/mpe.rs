@@ -0,0 +1,494 @@
+use helgoboss_learn::{
+    format_percentage_without_unit, parse_percentage_without_unit, ControlValue,
+    DetailedSourceCharacter, Fraction, SourceCharacter, UnitValue,
+};
+use helgoboss_midi::{Channel, RawShortMessage, ShortMessage, StructuredShortMessage, U7};
+use realearn_api::persistence::{MpeDimension, MpeZone};
+use std::convert::TryInto;
+use std::ops::RangeInclusive;
+
+/// CC number which MPE controllers use for the "slide" dimension (Y axis on the key surface).
+const SLIDE_CONTROLLER_NUMBER: u8 = 74;
+
+/// RPN number of the MPE Configuration Message (MCM).
+const MCM_RPN_NUMBER: (u8, u8) = (0, 6);
+
+/// Source which exposes one dimension of one note played on an MPE controller.
+///
+/// In contrast to the normal MIDI sources, this one is not bound to a fixed channel. MPE
+/// controllers move each note to its own member channel, so we need the note allocation tracked by
+/// [`MpeState`] in order to find out which channel carries the note we are interested in.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct MpeSource {
+    pub zone: MpeZone,
+    /// Number of member channels (1 to 15).
+    ///
+    /// Only used until the controller announces the zone layout via MPE Configuration Message.
+    pub member_channel_count: u8,
+    pub dimension: MpeDimension,
+    pub note: MpeNote,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum MpeNote {
+    /// The most recently pressed note, even if it has been released already.
+    LastTouched,
+    /// The n-th currently held note (zero-rooted, in the order in which the notes were pressed).
+    Held(u8),
+}
+
+impl MpeSource {
+    /// Returns a control value if the given message affects the selected dimension of the selected
+    /// note.
+    ///
+    /// The given state must already contain the given message.
+    pub fn control(&self, msg: &RawShortMessage, state: &MpeState) -> Option<ControlValue> {
+        let (channel, control_value) = self.extract_control_value(msg)?;
+        let member_channels = self.member_channels(state.member_channel_count(self.zone));
+        if !member_channels.contains(&channel.get()) {
+            return None;
+        }
+        let selected_channel = match self.note {
+            MpeNote::LastTouched => state.last_touched_channel(member_channels)?,
+            MpeNote::Held(index) => state.held_channel(member_channels, index)?,
+        };
+        if channel != selected_channel {
+            return None;
+        }
+        Some(control_value)
+    }
+
+    /// Non-mutating and stateless! Used for checks (learning, filtering).
+    ///
+    /// Reacts to matching messages on any member channel, no matter which note is selected. As
+    /// there's no state, the manually configured member channel count is used.
+    pub fn reacts_to_message_with(&self, msg: &RawShortMessage) -> Option<ControlValue> {
+        let (channel, control_value) = self.extract_control_value(msg)?;
+        if !self.member_channels(None).contains(&channel.get()) {
+            return None;
+        }
+        Some(control_value)
+    }
+
+    pub fn character(&self) -> SourceCharacter {
+        match self.dimension {
+            MpeDimension::Velocity => SourceCharacter::MomentaryButton,
+            _ => SourceCharacter::RangeElement,
+        }
+    }
+
+    pub fn possible_detailed_characters(&self) -> Vec<DetailedSourceCharacter> {
+        match self.dimension {
+            MpeDimension::Velocity => vec![
+                DetailedSourceCharacter::MomentaryVelocitySensitiveButton,
+                DetailedSourceCharacter::MomentaryOnOffButton,
+                DetailedSourceCharacter::Trigger,
+            ],
+            _ => vec![DetailedSourceCharacter::RangeControl],
+        }
+    }
+
+    pub fn max_discrete_value(&self) -> Option<u32> {
+        match self.dimension {
+            MpeDimension::NoteNumber => Some(U7::MAX.get() as _),
+            _ => None,
+        }
+    }
+
+    pub fn format_control_value(&self, value: ControlValue) -> Result<String, &'static str> {
+        match value {
+            ControlValue::AbsoluteDiscrete(f) => Ok(f.actual().to_string()),
+            _ => Ok(format_percentage_without_unit(value.to_unit_value()?.get())),
+        }
+    }
+
+    pub fn parse_control_value(&self, text: &str) -> Result<UnitValue, &'static str> {
+        match self.dimension {
+            MpeDimension::NoteNumber => {
+                let number: u8 = text.parse().map_err(|_| "not a valid note number")?;
+                let value = number.min(U7::MAX.get()) as f64 / U7::MAX.get() as f64;
+                Ok(UnitValue::new(value))
+            }
+            _ => parse_percentage_without_unit(text)?.try_into(),
+        }
+    }
+
+    /// Zero-rooted indexes of the member channels of the configured zone.
+    ///
+    /// If the controller has announced a member channel count, it takes precedence over the
+    /// manually configured one. An announced count of zero means that the zone is disabled and
+    /// results in an empty range.
+    fn member_channels(&self, announced_count: Option<u8>) -> RangeInclusive<u8> {
+        let count = match announced_count {
+            Some(c) => c.min(15),
+            None => self.member_channel_count.clamp(1, 15),
+        };
+        match self.zone {
+            MpeZone::Lower => 1..=count,
+            MpeZone::Upper => (15 - count)..=14,
+        }
+    }
+
+    fn extract_control_value(&self, msg: &RawShortMessage) -> Option<(Channel, ControlValue)> {
+        use MpeDimension::*;
+        use StructuredShortMessage::*;
+        let (channel, control_value) = match (msg.to_structured(), self.dimension) {
+            (
+                NoteOn {
+                    channel, velocity, ..
+                },
+                Velocity,
+            ) => (channel, unit_value_from_7_bit(velocity)),
+            (NoteOff { channel, .. }, Velocity) => (channel, UnitValue::MIN),
+            (
+                NoteOn {
+                    channel,
+                    key_number,
+                    velocity,
+                },
+                NoteNumber,
+            ) if velocity > U7::MIN => {
+                let fraction = Fraction::new(key_number.get() as _, U7::MAX.get() as _);
+                return Some((channel, ControlValue::AbsoluteDiscrete(fraction)));
+            }
+            (
+                PitchBendChange {
+                    channel,
+                    pitch_bend_value,
+                },
+                PitchBend,
+            ) => {
+                let value = pitch_bend_value.get() as f64 / 16383.0;
+                (channel, UnitValue::new_clamped(value))
+            }
+            (
+                ChannelPressure {
+                    channel,
+                    pressure_amount,
+                },
+                Pressure,
+            ) => (channel, unit_value_from_7_bit(pressure_amount)),
+            (
+                ControlChange {
+                    channel,
+                    controller_number,
+                    control_value,
+                },
+                Slide,
+            ) if controller_number.get() == SLIDE_CONTROLLER_NUMBER => {
+                (channel, unit_value_from_7_bit(control_value))
+            }
+            _ => return None,
+        };
+        Some((channel, ControlValue::AbsoluteContinuous(control_value)))
+    }
+}
+
+/// Tracks which notes are currently held on which MPE member channels.
+///
+/// Also follows the MPE Configuration Messages (RPN 6 on the master channel) which controllers
+/// send to announce the zone layout.
+///
+/// Lives in the real-time processor and must be fed with each incoming short message *before*
+/// the message is used to control mappings with [`MpeSource`]s. Doesn't allocate.
+#[derive(Clone, Debug, Default)]
+pub struct MpeState {
+    channels: [MpeChannelState; 16],
+    note_on_counter: u32,
+    lower_zone: MpeZoneState,
+    upper_zone: MpeZoneState,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct MpeZoneState {
+    /// RPN number (MSB, LSB) most recently selected on the master channel.
+    rpn_msb: Option<u8>,
+    rpn_lsb: Option<u8>,
+    /// Member channel count announced via MPE Configuration Message (zero = zone disabled).
+    announced_member_channel_count: Option<u8>,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct MpeChannelState {
+    /// Increasing with every note-on in any channel, so it can be used to order the notes.
+    ///
+    /// Zero means that there hasn't been any note-on in this channel yet.
+    note_on_order: u32,
+    note_state: MpeNoteState,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum MpeNoteState {
+    Idle,
+    Held,
+    /// The note has been released with the message that is currently being processed.
+    ///
+    /// We still treat it as held until the next message arrives. Otherwise the note-off would
+    /// never reach the source which tracks this note.
+    JustReleased,
+}
+
+impl Default for MpeNoteState {
+    fn default() -> Self {
+        Self::Idle
+    }
+}
+
+impl MpeChannelState {
+    fn is_held(&self) -> bool {
+        self.note_state != MpeNoteState::Idle
+    }
+}
+
+impl MpeState {
+    pub fn process(&mut self, msg: &impl ShortMessage) {
+        for ch in self.channels.iter_mut() {
+            if ch.note_state == MpeNoteState::JustReleased {
+                ch.note_state = MpeNoteState::Idle;
+            }
+        }
+        use StructuredShortMessage::*;
+        match msg.to_structured() {
+            NoteOn {
+                channel, velocity, ..
+            } if velocity > U7::MIN => {
+                self.note_on_counter = self.note_on_counter.wrapping_add(1);
+                let ch = &mut self.channels[channel.get() as usize];
+                ch.note_on_order = self.note_on_counter;
+                ch.note_state = MpeNoteState::Held;
+            }
+            NoteOn { channel, .. } | NoteOff { channel, .. } => {
+                self.release(channel);
+            }
+            // All sound off, all notes off
+            ControlChange {
+                channel,
+                controller_number,
+                ..
+            } if matches!(controller_number.get(), 120 | 123) => {
+                self.release(channel);
+            }
+            ControlChange {
+                channel,
+                controller_number,
+                control_value,
+            } => {
+                self.process_master_channel_cc(channel, controller_number.get(), control_value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the member channel count which the controller has announced for the given zone,
+    /// if any.
+    pub fn member_channel_count(&self, zone: MpeZone) -> Option<u8> {
+        match zone {
+            MpeZone::Lower => self.lower_zone.announced_member_channel_count,
+            MpeZone::Upper => self.upper_zone.announced_member_channel_count,
+        }
+    }
+
+    fn process_master_channel_cc(&mut self, channel: Channel, controller_number: u8, value: U7) {
+        let (zone, other_zone) = match channel.get() {
+            0 => (&mut self.lower_zone, &mut self.upper_zone),
+            15 => (&mut self.upper_zone, &mut self.lower_zone),
+            _ => return,
+        };
+        match controller_number {
+            101 => zone.rpn_msb = Some(value.get()),
+            100 => zone.rpn_lsb = Some(value.get()),
+            // Data entry MSB
+            6 if (zone.rpn_msb, zone.rpn_lsb)
+                == (Some(MCM_RPN_NUMBER.0), Some(MCM_RPN_NUMBER.1)) =>
+            {
+                let count = value.get().min(15);
+                zone.announced_member_channel_count = Some(count);
+                // According to the MPE specification, zones must not overlap. The most recently
+                // configured zone wins and shrinks the other one.
+                if let Some(other_count) = other_zone.announced_member_channel_count {
+                    other_zone.announced_member_channel_count =
+                        Some(other_count.min(14u8.saturating_sub(count)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn release(&mut self, channel: Channel) {
+        let ch = &mut self.channels[channel.get() as usize];
+        if ch.note_state == MpeNoteState::Held {
+            ch.note_state = MpeNoteState::JustReleased;
+        }
+    }
+
+    fn last_touched_channel(&self, channels: RangeInclusive<u8>) -> Option<Channel> {
+        let i = channels
+            .filter(|i| self.channels[*i as usize].note_on_order > 0)
+            .max_by_key(|i| self.channels[*i as usize].note_on_order)?;
+        Some(Channel::new(i))
+    }
+
+    fn held_channel(&self, channels: RangeInclusive<u8>, index: u8) -> Option<Channel> {
+        let mut min_order = 0;
+        let mut result = None;
+        for _ in 0..=index {
+            let i = channels
+                .clone()
+                .filter(|i| {
+                    let ch = &self.channels[*i as usize];
+                    ch.is_held() && ch.note_on_order > min_order
+                })
+                .min_by_key(|i| self.channels[*i as usize].note_on_order)?;
+            min_order = self.channels[i as usize].note_on_order;
+            result = Some(i);
+        }
+        result.map(Channel::new)
+    }
+}
+
+fn unit_value_from_7_bit(value: U7) -> UnitValue {
+    UnitValue::new(value.get() as f64 / U7::MAX.get() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_midi::test_util::*;
+
+    #[test]
+    fn last_touched_note() {
+        // Given
+        let source = pressure_source(MpeNote::LastTouched);
+        let mut state = MpeState::default();
+        // When
+        let first = feed(&source, &mut state, note_on(1, 60, 100));
+        let second = feed(&source, &mut state, channel_pressure(1, 64));
+        let third = feed(&source, &mut state, note_on(2, 64, 100));
+        let fourth = feed(&source, &mut state, channel_pressure(1, 127));
+        let fifth = feed(&source, &mut state, channel_pressure(2, 127));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, Some(abs(64)));
+        assert_eq!(third, None);
+        assert_eq!(fourth, None);
+        assert_eq!(fifth, Some(abs(127)));
+    }
+
+    #[test]
+    fn held_note() {
+        // Given
+        let source = pressure_source(MpeNote::Held(1));
+        let mut state = MpeState::default();
+        // When
+        feed(&source, &mut state, note_on(3, 60, 100));
+        feed(&source, &mut state, note_on(1, 64, 100));
+        let first = feed(&source, &mut state, channel_pressure(1, 10));
+        let second = feed(&source, &mut state, channel_pressure(3, 20));
+        feed(&source, &mut state, note_off(3, 60, 0));
+        let third = feed(&source, &mut state, channel_pressure(1, 30));
+        // Then
+        assert_eq!(first, Some(abs(10)));
+        assert_eq!(second, None);
+        // After releasing the first note, there's no second held note anymore.
+        assert_eq!(third, None);
+    }
+
+    #[test]
+    fn note_off_reaches_released_note() {
+        // Given
+        let source = MpeSource {
+            dimension: MpeDimension::Velocity,
+            ..pressure_source(MpeNote::Held(0))
+        };
+        let mut state = MpeState::default();
+        // When
+        let first = feed(&source, &mut state, note_on(1, 60, 127));
+        let second = feed(&source, &mut state, note_off(1, 60, 0));
+        // Then
+        assert_eq!(first, Some(abs(127)));
+        assert_eq!(second, Some(abs(0)));
+    }
+
+    #[test]
+    fn ignore_channels_outside_of_zone() {
+        // Given
+        let source = MpeSource {
+            member_channel_count: 2,
+            ..pressure_source(MpeNote::LastTouched)
+        };
+        let mut state = MpeState::default();
+        // When
+        feed(&source, &mut state, note_on(3, 60, 100));
+        let first = feed(&source, &mut state, channel_pressure(3, 50));
+        feed(&source, &mut state, note_on(0, 60, 100));
+        let second = feed(&source, &mut state, channel_pressure(0, 50));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, None);
+    }
+
+    #[test]
+    fn follow_mpe_configuration_message() {
+        // Given
+        let source = MpeSource {
+            member_channel_count: 2,
+            ..pressure_source(MpeNote::LastTouched)
+        };
+        let mut state = MpeState::default();
+        // When
+        feed(&source, &mut state, control_change(0, 101, 0));
+        feed(&source, &mut state, control_change(0, 100, 6));
+        feed(&source, &mut state, control_change(0, 6, 5));
+        feed(&source, &mut state, note_on(4, 60, 100));
+        let first = feed(&source, &mut state, channel_pressure(4, 50));
+        feed(&source, &mut state, note_on(6, 60, 100));
+        let second = feed(&source, &mut state, channel_pressure(6, 50));
+        // Then
+        assert_eq!(state.member_channel_count(MpeZone::Lower), Some(5));
+        assert_eq!(first, Some(abs(50)));
+        assert_eq!(second, None);
+    }
+
+    #[test]
+    fn mpe_configuration_message_shrinks_other_zone() {
+        // Given
+        let source = pressure_source(MpeNote::LastTouched);
+        let mut state = MpeState::default();
+        // When
+        for msg in [
+            control_change(15, 101, 0),
+            control_change(15, 100, 6),
+            control_change(15, 6, 7),
+            control_change(0, 101, 0),
+            control_change(0, 100, 6),
+            control_change(0, 6, 10),
+        ] {
+            feed(&source, &mut state, msg);
+        }
+        // Then
+        assert_eq!(state.member_channel_count(MpeZone::Lower), Some(10));
+        assert_eq!(state.member_channel_count(MpeZone::Upper), Some(4));
+    }
+
+    fn pressure_source(note: MpeNote) -> MpeSource {
+        MpeSource {
+            zone: MpeZone::Lower,
+            member_channel_count: 15,
+            dimension: MpeDimension::Pressure,
+            note,
+        }
+    }
+
+    fn feed(
+        source: &MpeSource,
+        state: &mut MpeState,
+        msg: RawShortMessage,
+    ) -> Option<ControlValue> {
+        state.process(&msg);
+        source.control(&msg, state)
+    }
+
+    fn abs(value: u8) -> ControlValue {
+        ControlValue::AbsoluteContinuous(unit_value_from_7_bit(U7::new(value)))
+    }
+}
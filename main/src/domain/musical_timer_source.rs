@@ -0,0 +1,360 @@
+use helgoboss_learn::{ControlValue, UnitValue};
+use realearn_api::persistence::{MusicalTimerOutput, MusicalTimerTrigger};
+use reaper_high::{BookmarkType, Project, Reaper};
+use reaper_medium::PositionInSeconds;
+
+/// Timer which fires on musical positions of the project timeline (bars, beats, regions, markers).
+///
+/// In contrast to the [`TimerSource`](crate::domain::TimerSource), this one follows the play
+/// position (or the edit cursor if the project is not playing), so it stays in sync with the
+/// music, respects tempo and time signature changes and fires again when looping.
+#[derive(Clone, Debug)]
+pub struct MusicalTimerSource {
+    settings: MusicalTimerSettings,
+    state: MusicalTimerState,
+}
+
+// The state is transient and not relevant for deciding whether two sources are the same.
+impl PartialEq for MusicalTimerSource {
+    fn eq(&self, other: &Self) -> bool {
+        self.settings == other.settings
+    }
+}
+
+impl Eq for MusicalTimerSource {}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MusicalTimerSettings {
+    pub trigger: MusicalTimerTrigger,
+    /// Number of bars or beats between two firings (for "Every n bars/beats").
+    pub interval: u32,
+    /// Bar numbers starting with 1 (for "Specific bars").
+    pub bars: Vec<u32>,
+    pub only_while_playing: bool,
+    pub output: MusicalTimerOutput,
+}
+
+impl Default for MusicalTimerSettings {
+    fn default() -> Self {
+        Self {
+            trigger: Default::default(),
+            interval: 1,
+            bars: vec![],
+            only_while_playing: false,
+            output: Default::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct MusicalTimerState {
+    /// `None` if not polled yet.
+    was_playing: Option<bool>,
+    /// `None` if there's no previous position to compare with (e.g. right after activation).
+    last_slot: Option<Option<i64>>,
+    last_ramp_value: Option<UnitValue>,
+}
+
+/// Where on the timeline the reference position currently is.
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct TimelineSnapshot {
+    /// Identifies the current interval (e.g. the current group of bars or the current region).
+    ///
+    /// `None` if the position is not within any relevant interval (e.g. not within a region).
+    slot: Option<i64>,
+    /// Progress through the current interval.
+    progress: UnitValue,
+}
+
+impl MusicalTimerSource {
+    pub fn new(settings: MusicalTimerSettings) -> Self {
+        Self {
+            settings,
+            state: Default::default(),
+        }
+    }
+
+    pub fn settings(&self) -> &MusicalTimerSettings {
+        &self.settings
+    }
+
+    pub fn on_deactivate(&mut self) {
+        self.state = Default::default();
+    }
+
+    pub fn poll(&mut self, project: Project) -> Option<ControlValue> {
+        let is_playing = project.is_playing();
+        let snapshot = if self.settings.only_while_playing && !is_playing {
+            None
+        } else {
+            let pos = if is_playing {
+                project.play_position_latency_compensated()
+            } else {
+                project.edit_cursor_position()
+            };
+            Some(self.take_snapshot(project, pos))
+        };
+        self.process(is_playing, snapshot)
+    }
+
+    fn process(
+        &mut self,
+        is_playing: bool,
+        snapshot: Option<TimelineSnapshot>,
+    ) -> Option<ControlValue> {
+        let state = &mut self.state;
+        let playback_started = state.was_playing == Some(false) && is_playing;
+        state.was_playing = Some(is_playing);
+        let snapshot = match snapshot {
+            None => {
+                state.last_slot = None;
+                return None;
+            }
+            Some(s) => s,
+        };
+        let previous_slot = if playback_started {
+            // Starting playback counts as entering the current interval.
+            None
+        } else {
+            // Right after activation, there's nothing to compare with, so don't fire.
+            state.last_slot.unwrap_or(snapshot.slot)
+        };
+        state.last_slot = Some(snapshot.slot);
+        match self.settings.output {
+            MusicalTimerOutput::Trigger => {
+                let fires = if self.settings.trigger == MusicalTimerTrigger::RegionEnd {
+                    previous_slot.is_some() && snapshot.slot != previous_slot
+                } else {
+                    snapshot.slot.is_some() && snapshot.slot != previous_slot
+                };
+                if fires {
+                    Some(ControlValue::AbsoluteContinuous(UnitValue::MAX))
+                } else {
+                    None
+                }
+            }
+            MusicalTimerOutput::Ramp => {
+                let value = if snapshot.slot.is_some() {
+                    snapshot.progress
+                } else {
+                    UnitValue::MIN
+                };
+                if state.last_ramp_value == Some(value) {
+                    return None;
+                }
+                state.last_ramp_value = Some(value);
+                Some(ControlValue::AbsoluteContinuous(value))
+            }
+        }
+    }
+
+    fn take_snapshot(&self, project: Project, pos: PositionInSeconds) -> TimelineSnapshot {
+        use MusicalTimerTrigger::*;
+        match self.settings.trigger {
+            EveryNBars | SpecificBars => {
+                let res = Reaper::get()
+                    .medium_reaper()
+                    .time_map_2_time_to_beats(project.context(), pos);
+                let measure_index = res.measure_index as i64;
+                let beats_per_measure = res.time_signature.numerator.get() as f64;
+                let progress_in_measure =
+                    (res.beats_since_measure.get() / beats_per_measure).clamp(0.0, 1.0);
+                if self.settings.trigger == SpecificBars {
+                    let bar_number = measure_index + 1;
+                    let is_relevant_bar =
+                        self.settings.bars.iter().any(|b| *b as i64 == bar_number);
+                    TimelineSnapshot {
+                        slot: if is_relevant_bar {
+                            Some(measure_index)
+                        } else {
+                            None
+                        },
+                        progress: UnitValue::new_clamped(progress_in_measure),
+                    }
+                } else {
+                    let interval = self.settings.interval.max(1) as i64;
+                    let progress = (measure_index.rem_euclid(interval) as f64
+                        + progress_in_measure)
+                        / interval as f64;
+                    TimelineSnapshot {
+                        slot: Some(measure_index.div_euclid(interval)),
+                        progress: UnitValue::new_clamped(progress),
+                    }
+                }
+            }
+            EveryNBeats => {
+                let res = Reaper::get()
+                    .medium_reaper()
+                    .time_map_2_time_to_beats(project.context(), pos);
+                let interval = self.settings.interval.max(1) as f64;
+                let intervals = res.full_beats.get() / interval;
+                TimelineSnapshot {
+                    slot: Some(intervals.floor() as i64),
+                    progress: UnitValue::new_clamped(intervals - intervals.floor()),
+                }
+            }
+            RegionStart | RegionEnd => {
+                let region = project
+                    .current_bookmark_at(pos)
+                    .region_index
+                    .and_then(|i| Some((i, project.find_bookmark_by_index(i)?.basic_info())));
+                match region {
+                    Some((i, info)) => {
+                        let end_pos = info.region_end_position.unwrap_or(info.position);
+                        TimelineSnapshot {
+                            slot: Some(i as i64),
+                            progress: calc_progress(pos, info.position, end_pos),
+                        }
+                    }
+                    None => TimelineSnapshot {
+                        slot: None,
+                        progress: UnitValue::MIN,
+                    },
+                }
+            }
+            Marker => {
+                let marker = project
+                    .current_bookmark_at(pos)
+                    .marker_index
+                    .and_then(|i| Some((i, project.find_bookmark_by_index(i)?.basic_info())));
+                match marker {
+                    Some((i, info)) => {
+                        // The interval of a marker ends with the next marker.
+                        let next_marker_pos = project
+                            .bookmarks()
+                            .map(|b| b.basic_info())
+                            .filter(|next| {
+                                next.bookmark_type() == BookmarkType::Marker
+                                    && next.position > info.position
+                            })
+                            .map(|next| next.position)
+                            .min_by(|a, b| a.get().total_cmp(&b.get()));
+                        let progress = match next_marker_pos {
+                            None => UnitValue::MIN,
+                            Some(end_pos) => calc_progress(pos, info.position, end_pos),
+                        };
+                        TimelineSnapshot {
+                            slot: Some(i as i64),
+                            progress,
+                        }
+                    }
+                    None => TimelineSnapshot {
+                        slot: None,
+                        progress: UnitValue::MIN,
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn calc_progress(
+    pos: PositionInSeconds,
+    start_pos: PositionInSeconds,
+    end_pos: PositionInSeconds,
+) -> UnitValue {
+    let length = end_pos.get() - start_pos.get();
+    if length <= 0.0 {
+        return UnitValue::MIN;
+    }
+    UnitValue::new_clamped((pos.get() - start_pos.get()) / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_on_interval_change() {
+        // Given
+        let mut source = source(MusicalTimerTrigger::EveryNBars, MusicalTimerOutput::Trigger);
+        // When
+        let first = source.process(true, snapshot(Some(0), 0.5));
+        let second = source.process(true, snapshot(Some(0), 0.9));
+        let third = source.process(true, snapshot(Some(1), 0.0));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, None);
+        assert_eq!(third, Some(on()));
+    }
+
+    #[test]
+    fn trigger_on_playback_start() {
+        // Given
+        let mut source = source(MusicalTimerTrigger::EveryNBars, MusicalTimerOutput::Trigger);
+        // When
+        let first = source.process(false, snapshot(Some(0), 0.0));
+        let second = source.process(true, snapshot(Some(0), 0.0));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, Some(on()));
+    }
+
+    #[test]
+    fn trigger_on_region_end() {
+        // Given
+        let mut source = source(MusicalTimerTrigger::RegionEnd, MusicalTimerOutput::Trigger);
+        // When
+        let first = source.process(true, snapshot(None, 0.0));
+        let second = source.process(true, snapshot(Some(3), 0.0));
+        let third = source.process(true, snapshot(None, 0.0));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, None);
+        assert_eq!(third, Some(on()));
+    }
+
+    #[test]
+    fn ramp() {
+        // Given
+        let mut source = source(MusicalTimerTrigger::EveryNBeats, MusicalTimerOutput::Ramp);
+        // When
+        let first = source.process(true, snapshot(Some(0), 0.25));
+        let second = source.process(true, snapshot(Some(0), 0.25));
+        let third = source.process(true, snapshot(Some(0), 0.5));
+        let fourth = source.process(true, snapshot(None, 0.5));
+        // Then
+        assert_eq!(first, Some(value(0.25)));
+        assert_eq!(second, None);
+        assert_eq!(third, Some(value(0.5)));
+        assert_eq!(fourth, Some(value(0.0)));
+    }
+
+    #[test]
+    fn only_while_playing() {
+        // Given
+        let mut source = source(MusicalTimerTrigger::EveryNBars, MusicalTimerOutput::Trigger);
+        // When
+        source.process(true, snapshot(Some(0), 0.5));
+        let first = source.process(false, None);
+        let second = source.process(true, snapshot(Some(4), 0.5));
+        // Then
+        assert_eq!(first, None);
+        assert_eq!(second, Some(on()));
+    }
+
+    fn source(trigger: MusicalTimerTrigger, output: MusicalTimerOutput) -> MusicalTimerSource {
+        MusicalTimerSource::new(MusicalTimerSettings {
+            trigger,
+            interval: 1,
+            bars: vec![],
+            only_while_playing: false,
+            output,
+        })
+    }
+
+    fn snapshot(slot: Option<i64>, progress: f64) -> Option<TimelineSnapshot> {
+        Some(TimelineSnapshot {
+            slot,
+            progress: UnitValue::new(progress),
+        })
+    }
+
+    fn on() -> ControlValue {
+        value(1.0)
+    }
+
+    fn value(v: f64) -> ControlValue {
+        ControlValue::AbsoluteContinuous(UnitValue::new(v))
+    }
+}
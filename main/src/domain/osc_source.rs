@@ -0,0 +1,400 @@
+use crate::domain::SmallAsciiString;
+use ascii::AsciiStr;
+use helgoboss_learn::{
+    ControlValue, DetailedSourceCharacter, FeedbackValue, OscArgDescriptor, OscSourceAddress,
+    SourceCharacter, UnitValue,
+};
+use regex::Regex;
+use rosc::{OscMessage, OscType};
+
+/// OSC source which adds a few ReaLearn-specific features on top of
+/// [`helgoboss_learn::OscSource`]:
+///
+/// - **Additional arguments:** Binds several argument indexes at once. Each additional argument
+///   produces its own control value.
+/// - **Address captures:** The address pattern can contain placeholders such as
+///   `/track/{n}/volume`. Each placeholder matches a number, which is then available as variable
+///   in target expressions.
+///
+/// Feedback is sent for the primary argument only (plus the first additional argument for XY
+/// control elements) and only if the address pattern doesn't contain captures (because then it's
+/// not clear which address to send it to).
+#[derive(Clone, PartialEq, Debug)]
+pub struct OscSource {
+    /// Responsible for the primary argument and for feedback.
+    primary: helgoboss_learn::OscSource,
+    /// One source for each additional argument, all with the same address pattern.
+    additional: Vec<helgoboss_learn::OscSource>,
+    /// Is `Some` if the address pattern contains captures.
+    address_matcher: Option<OscAddressMatcher>,
+}
+
+/// The result of an OSC message which matched an [`OscSource`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct OscSourceControlResult {
+    /// Control value of the primary argument.
+    pub value: ControlValue,
+    /// Control values of the additional arguments, in order.
+    ///
+    /// An entry is `None` if the message doesn't contain the corresponding argument.
+    pub additional_values: Vec<Option<ControlValue>>,
+    /// Numbers captured from the address, in the order of the placeholders.
+    pub captures: Vec<f64>,
+    /// Text of the primary argument if it's a string.
+    pub text: Option<String>,
+}
+
+impl OscSource {
+    pub fn new(
+        primary: helgoboss_learn::OscSource,
+        additional_arg_descriptors: impl IntoIterator<Item = OscArgDescriptor>,
+    ) -> Self {
+        let address_pattern = primary.address_pattern();
+        let additional = additional_arg_descriptors
+            .into_iter()
+            .map(|d| helgoboss_learn::OscSource::new(address_pattern.to_owned(), Some(d), vec![]))
+            .collect();
+        Self {
+            address_matcher: OscAddressMatcher::from_address_pattern(address_pattern),
+            primary,
+            additional,
+        }
+    }
+
+    pub fn from_source_value(msg: OscMessage, arg_index_hint: Option<u32>) -> Self {
+        Self::new(
+            helgoboss_learn::OscSource::from_source_value(msg, arg_index_hint),
+            vec![],
+        )
+    }
+
+    pub fn address_pattern(&self) -> &str {
+        self.primary.address_pattern()
+    }
+
+    pub fn arg_descriptor(&self) -> Option<OscArgDescriptor> {
+        self.primary.arg_descriptor()
+    }
+
+    pub fn additional_arg_descriptors(&self) -> impl Iterator<Item = OscArgDescriptor> + '_ {
+        self.additional.iter().filter_map(|s| s.arg_descriptor())
+    }
+
+    pub fn additional_arg_count(&self) -> usize {
+        self.additional.len()
+    }
+
+    /// Returns the names of the address captures, in the order of the placeholders.
+    pub fn capture_names(&self) -> &[String] {
+        match &self.address_matcher {
+            None => &[],
+            Some(m) => &m.capture_names,
+        }
+    }
+
+    pub fn control(&self, msg: &OscMessage) -> Option<OscSourceControlResult> {
+        match &self.address_matcher {
+            None => self.control_internal(msg, vec![]),
+            Some(matcher) => {
+                let captures = matcher.match_address(&msg.addr)?;
+                // The wrapped sources compare the address literally, so we present them a
+                // message with the address pattern as address.
+                let msg = OscMessage {
+                    addr: self.primary.address_pattern().to_owned(),
+                    args: msg.args.clone(),
+                };
+                self.control_internal(&msg, captures)
+            }
+        }
+    }
+
+    fn control_internal(
+        &self,
+        msg: &OscMessage,
+        captures: Vec<f64>,
+    ) -> Option<OscSourceControlResult> {
+        let text =
+            self.primary
+                .arg_descriptor()
+                .and_then(|d| match msg.args.get(d.index() as usize)? {
+                    OscType::String(s) => Some(s.clone()),
+                    _ => None,
+                });
+        let value = match self.primary.control(msg) {
+            Some(v) => v,
+            // A string argument doesn't have a numeric value but still triggers the target
+            None if text.is_some() => ControlValue::AbsoluteContinuous(UnitValue::MAX),
+            None => return None,
+        };
+        let result = OscSourceControlResult {
+            value,
+            additional_values: self.additional.iter().map(|s| s.control(msg)).collect(),
+            captures,
+            text,
+        };
+        Some(result)
+    }
+
+    pub fn feedback(&self, feedback_value: FeedbackValue) -> Option<OscMessage> {
+        if self.address_matcher.is_some() {
+            return None;
+        }
+        self.primary.feedback(feedback_value)
+    }
+
+    /// Like [`Self::feedback`] but additionally puts the Y coordinate of an XY control element
+    /// into the first additional argument, so both coordinates are sent in one message.
+    pub fn feedback_xy(&self, x: FeedbackValue, y: FeedbackValue) -> Option<OscMessage> {
+        let mut msg = self.feedback(x)?;
+        let y_source = match self.additional.first() {
+            None => return Some(msg),
+            Some(s) => s,
+        };
+        if let (Some(d), Some(y_msg)) = (y_source.arg_descriptor(), y_source.feedback(y)) {
+            let index = d.index() as usize;
+            if let Some(arg) = y_msg.args.get(index) {
+                if msg.args.len() <= index {
+                    msg.args.resize(index + 1, OscType::Nil);
+                }
+                msg.args[index] = arg.clone();
+            }
+        }
+        Some(msg)
+    }
+
+    pub fn feedback_address(&self) -> &OscSourceAddress {
+        self.primary.feedback_address()
+    }
+
+    pub fn has_same_feedback_address_as_value(&self, value: &OscMessage) -> bool {
+        self.primary.has_same_feedback_address_as_value(value)
+    }
+
+    pub fn has_same_feedback_address_as_source(&self, other: &Self) -> bool {
+        self.primary
+            .has_same_feedback_address_as_source(&other.primary)
+    }
+
+    pub fn format_control_value(&self, value: ControlValue) -> Result<String, &'static str> {
+        self.primary.format_control_value(value)
+    }
+
+    pub fn parse_control_value(&self, text: &str) -> Result<UnitValue, &'static str> {
+        self.primary.parse_control_value(text)
+    }
+
+    pub fn character(&self) -> SourceCharacter {
+        self.primary.character()
+    }
+
+    pub fn possible_detailed_characters(&self) -> Vec<DetailedSourceCharacter> {
+        self.primary.possible_detailed_characters()
+    }
+}
+
+/// Gives target expressions access to the numbers captured from an OSC address.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct OscCaptures<'a> {
+    names: &'a [String],
+    values: &'a [f64],
+    /// Captures which have been forwarded from a controller mapping via a virtual source value.
+    forwarded: Option<&'a VirtualOscCaptures>,
+}
+
+impl<'a> OscCaptures<'a> {
+    pub fn new(names: &'a [String], values: &'a [f64]) -> Self {
+        Self {
+            names,
+            values,
+            forwarded: None,
+        }
+    }
+
+    pub fn forwarded(captures: &'a VirtualOscCaptures) -> Self {
+        Self {
+            forwarded: Some(captures),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the value captured by the placeholder with the given name.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        match self.names.iter().position(|n| n == name) {
+            None => self.forwarded?.get(name),
+            Some(index) => self.values.get(index).copied(),
+        }
+    }
+}
+
+/// OSC address captures in a form which can be passed along with virtual source values.
+///
+/// Doesn't allocate, so it can be copied around cheaply (also in the real-time processor). Holds
+/// at most [`VirtualOscCaptures::MAX_COUNT`] captures. Captures with names longer than
+/// [`SmallAsciiString::MAX_LENGTH`] are dropped.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct VirtualOscCaptures {
+    len: u8,
+    entries: [(SmallAsciiString, f64); VirtualOscCaptures::MAX_COUNT],
+}
+
+impl VirtualOscCaptures {
+    pub const MAX_COUNT: usize = 4;
+
+    pub fn new(names: &[String], values: &[f64]) -> Self {
+        let mut captures = Self::default();
+        for (name, value) in names.iter().zip(values) {
+            if captures.len as usize == Self::MAX_COUNT {
+                break;
+            }
+            let name = match AsciiStr::from_ascii(name) {
+                Ok(n) => n,
+                Err(_) => continue,
+            };
+            let name = match SmallAsciiString::try_from_ascii_str(name) {
+                Ok(n) => n,
+                Err(_) => continue,
+            };
+            captures.entries[captures.len as usize] = (name, *value);
+            captures.len += 1;
+        }
+        captures
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value captured by the placeholder with the given name.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.entries[..self.len as usize]
+            .iter()
+            .find(|(n, _)| n.as_slice() == name.as_bytes())
+            .map(|(_, v)| *v)
+    }
+}
+
+/// Matches addresses against an address pattern with placeholders such as `/track/{n}/volume`.
+#[derive(Clone, Debug)]
+struct OscAddressMatcher {
+    regex: Regex,
+    capture_names: Vec<String>,
+}
+
+impl PartialEq for OscAddressMatcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+    }
+}
+
+impl OscAddressMatcher {
+    /// Returns `None` if the given address pattern doesn't contain any placeholders.
+    ///
+    /// A placeholder is a valid variable name in curly braces. Anything else in curly braces is
+    /// taken literally.
+    fn from_address_pattern(address_pattern: &str) -> Option<Self> {
+        let mut regex_pattern = String::from("^");
+        let mut capture_names = vec![];
+        let mut rest = address_pattern;
+        while let Some(start) = rest.find('{') {
+            let (literal, candidate) = rest.split_at(start);
+            regex_pattern.push_str(&regex::escape(literal));
+            let placeholder = candidate[1..]
+                .find('}')
+                .map(|end| &candidate[1..end + 1])
+                .filter(|name| is_valid_capture_name(name));
+            match placeholder {
+                None => {
+                    regex_pattern.push_str(&regex::escape("{"));
+                    rest = &candidate[1..];
+                }
+                Some(name) => {
+                    regex_pattern.push_str(r"(\d+)");
+                    capture_names.push(name.to_owned());
+                    rest = &candidate[name.len() + 2..];
+                }
+            }
+        }
+        if capture_names.is_empty() {
+            return None;
+        }
+        regex_pattern.push_str(&regex::escape(rest));
+        regex_pattern.push('$');
+        let matcher = Self {
+            regex: Regex::new(&regex_pattern).ok()?,
+            capture_names,
+        };
+        Some(matcher)
+    }
+
+    /// Returns the captured numbers if the given address matches.
+    fn match_address(&self, address: &str) -> Option<Vec<f64>> {
+        let captures = self.regex.captures(address)?;
+        captures
+            .iter()
+            .skip(1)
+            .map(|c| c?.as_str().parse().ok())
+            .collect()
+    }
+}
+
+fn is_valid_capture_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_without_captures() {
+        assert!(OscAddressMatcher::from_address_pattern("/track/1/volume").is_none());
+        assert!(OscAddressMatcher::from_address_pattern("/foo/{1}/{}").is_none());
+    }
+
+    #[test]
+    fn address_with_captures() {
+        // Given
+        let matcher = OscAddressMatcher::from_address_pattern("/track/{n}/fx{fx}.volume").unwrap();
+        // When
+        let matching = matcher.match_address("/track/12/fx3.volume");
+        // Then
+        assert_eq!(matcher.capture_names, vec!["n", "fx"]);
+        assert_eq!(matching, Some(vec![12.0, 3.0]));
+        assert_eq!(matcher.match_address("/track/12/fx3xvolume"), None);
+        assert_eq!(matcher.match_address("/track/a/fx3.volume"), None);
+        assert_eq!(matcher.match_address("/track/12/fx3.volume/x"), None);
+    }
+
+    #[test]
+    fn captures_by_name() {
+        // Given
+        let names = vec!["n".to_owned(), "fx".to_owned()];
+        let values = vec![5.0, 7.0];
+        let captures = OscCaptures::new(&names, &values);
+        // When
+        let value = captures.get("fx");
+        // Then
+        assert_eq!(value, Some(7.0));
+        assert_eq!(captures.get("x"), None);
+    }
+
+    #[test]
+    fn forwarded_captures_by_name() {
+        // Given
+        let names = vec!["n".to_owned(), "fx".to_owned()];
+        let values = vec![5.0, 7.0];
+        let virtual_captures = VirtualOscCaptures::new(&names, &values);
+        let captures = OscCaptures::forwarded(&virtual_captures);
+        // When
+        let value = captures.get("n");
+        // Then
+        assert_eq!(value, Some(5.0));
+        assert_eq!(captures.get("fx"), Some(7.0));
+        assert_eq!(captures.get("x"), None);
+    }
+}
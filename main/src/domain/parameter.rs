@@ -139,7 +139,7 @@ impl ParamSetting {
         }
     }
 
-    fn convert_to_raw_value(&self, effective_value: f64) -> RawParamValue {
+    pub fn convert_to_raw_value(&self, effective_value: f64) -> RawParamValue {
         let raw_value = if let Some(value_count) = self.value_count {
             effective_value / (value_count.get() - 1) as f64
         } else {
@@ -1,4 +1,4 @@
-use crate::domain::{ControlContext, PluginParams};
+use crate::domain::{ControlContext, OscCaptures, PluginParams};
 use derivative::Derivative;
 use reaper_high::{Fx, FxChain, FxChainContext, Project, Reaper, Track};
 use reaper_medium::{ParamId, TypeSpecificPluginContext};
@@ -11,6 +11,8 @@ pub struct ExtendedProcessorContext<'a> {
     pub context: &'a ProcessorContext,
     pub params: &'a PluginParams,
     pub control_context: ControlContext<'a>,
+    /// Numbers captured from the address of the OSC message which triggered the mapping.
+    pub osc_captures: OscCaptures<'a>,
 }
 
 impl<'a> ExtendedProcessorContext<'a> {
@@ -23,6 +25,14 @@ impl<'a> ExtendedProcessorContext<'a> {
             context,
             params,
             control_context,
+            osc_captures: Default::default(),
+        }
+    }
+
+    pub fn with_osc_captures(self, osc_captures: OscCaptures<'a>) -> Self {
+        Self {
+            osc_captures,
+            ..self
         }
     }
 
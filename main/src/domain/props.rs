@@ -1,7 +1,7 @@
 use crate::domain::{
-    get_fx_name, get_track_color, get_track_name, CompoundChangeEvent, CompoundMappingTarget,
-    ControlContext, FeedbackResolution, MainMapping, RealearnTarget, ReaperTarget,
-    UnresolvedCompoundMappingTarget,
+    get_fx_name, get_item_name, get_track_color, get_track_name, AdditionalFeedbackEvent,
+    CompoundChangeEvent, CompoundMappingTarget, ControlContext, FeedbackResolution, MainMapping,
+    RealearnTarget, ReaperTarget, UnresolvedCompoundMappingTarget,
 };
 use enum_dispatch::enum_dispatch;
 use helgoboss_learn::{PropValue, Target};
@@ -202,6 +202,8 @@ enum TargetProps {
     RouteIndex(TargetRouteIndexProp),
     #[strum(serialize = "target.route.name")]
     RouteName(TargetRouteNameProp),
+    #[strum(serialize = "target.item.name")]
+    ItemName(TargetItemNameProp),
 }
 
 #[enum_dispatch(MappingProps)]
@@ -522,3 +524,21 @@ impl TargetProp for TargetRouteNameProp {
         ))
     }
 }
+
+#[derive(Default)]
+struct TargetItemNameProp;
+
+impl TargetProp for TargetItemNameProp {
+    fn is_affected_by(&self, args: PropIsAffectedByArgs<MappingAndTarget>) -> bool {
+        // Renaming a take is an undoable change, so the item change detection picks it up.
+        matches!(
+            args.event,
+            CompoundChangeEvent::Additional(AdditionalFeedbackEvent::ItemsChanged(_))
+        )
+    }
+
+    fn get_value(&self, args: PropGetValueArgs<MappingAndTarget>) -> Option<PropValue> {
+        let name = get_item_name(args.object.target.item()?)?;
+        Some(PropValue::Text(name.into()))
+    }
+}
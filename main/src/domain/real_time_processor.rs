@@ -1,13 +1,13 @@
 use crate::domain::{
-    classify_midi_message, BasicSettings, Compartment, CompoundMappingSource, ControlEvent,
-    ControlEventTimestamp, ControlLogEntry, ControlLogEntryKind, ControlMainTask, ControlMode,
-    ControlOptions, FeedbackSendBehavior, Garbage, GarbageBin, InstanceId, LifecycleMidiMessage,
-    LifecyclePhase, MappingId, MatchOutcome, MidiClockCalculator, MidiEvent,
-    MidiMessageClassification, MidiScanResult, MidiScanner, MidiSendTarget,
+    classify_midi_message, BasicSettings, Compartment, ControlEvent, ControlEventTimestamp,
+    ControlLogEntry, ControlLogEntryKind, ControlMainTask, ControlMode, ControlOptions,
+    FeedbackSendBehavior, ForwardedMidiMessage, Garbage, GarbageBin, InstanceId,
+    LifecycleMidiMessage, LifecyclePhase, MappingId, MatchOutcome, MidiClockCalculator, MidiEvent,
+    MidiMessageClassification, MidiScanResult, MidiScanner, MidiSendTarget, MpeState,
     NormalRealTimeToMainThreadTask, OrderedMappingMap, OwnedIncomingMidiMessage,
     PartialControlMatch, PersistentMappingProcessingState, QualifiedMappingId,
     RealTimeCompoundMappingTarget, RealTimeControlContext, RealTimeMapping, RealTimeReaperTarget,
-    SampleOffset, SendMidiDestination, VirtualSourceValue,
+    SampleOffset, SendMidiDestination, VirtualSourceValue, XyAxis,
 };
 use helgoboss_learn::{ControlValue, MidiSourceValue, ModeControlResult, RawMidiEvent};
 use helgoboss_midi::{
@@ -63,6 +63,8 @@ pub struct RealTimeProcessor {
     midi_scanner: MidiScanner,
     // For MIDI timing clock calculations
     midi_clock_calculator: MidiClockCalculator,
+    // For MPE sources
+    mpe_state: MpeState,
     sample_rate: Hz,
     clip_matrix: Option<WeakMatrix>,
     clip_matrix_is_owned: bool,
@@ -106,6 +108,7 @@ impl RealTimeProcessor {
             cc_14_bit_scanner: Default::default(),
             midi_scanner: Default::default(),
             midi_clock_calculator: Default::default(),
+            mpe_state: Default::default(),
             control_is_globally_enabled: false,
             feedback_is_globally_enabled: false,
             garbage_bin,
@@ -709,6 +712,11 @@ impl RealTimeProcessor {
         event: ControlEvent<MidiEvent<IncomingMidiMessage>>,
         caller: Caller,
     ) -> MatchOutcome {
+        // MPE sources need to know which notes are held on which channels, no matter if the
+        // message ends up matching a mapping or not.
+        if let IncomingMidiMessage::Short(short_msg) = event.payload().payload() {
+            self.mpe_state.process(&short_msg);
+        }
         match self.control_mode {
             ControlMode::Controlling => {
                 if self.control_is_globally_enabled {
@@ -976,6 +984,7 @@ impl RealTimeProcessor {
                 controller_mappings,
                 main_mappings,
                 value_event,
+                &self.mpe_state,
                 caller,
                 self.settings.midi_destination(),
                 LogOptions::from_basic_settings(&self.settings),
@@ -986,9 +995,44 @@ impl RealTimeProcessor {
             unreachable!()
         };
         let main_outcome = self.control_main_mappings_midi(value_event, caller, is_rendering);
+        if self.any_mapping_wants_main_thread_midi_control() {
+            self.forward_midi_to_main_processor(value_event);
+        }
         controller_outcome.merge_with(main_outcome)
     }
 
+    fn any_mapping_wants_main_thread_midi_control(&self) -> bool {
+        self.all_mappings().any(|m| {
+            m.control_is_effectively_on() && m.core.source.wants_main_thread_midi_control()
+        })
+    }
+
+    /// Lets the main processor execute control scripts that can't be executed in real-time (Lua).
+    ///
+    /// Whether the message matched is not known at this point, so it's treated as unmatched when it
+    /// comes to letting messages through.
+    fn forward_midi_to_main_processor(
+        &self,
+        value_event: ControlEvent<MidiEvent<&MidiSourceValue<RawShortMessage>>>,
+    ) {
+        let msg = match value_event.payload().payload() {
+            MidiSourceValue::Plain(msg) => ForwardedMidiMessage::Short(*msg),
+            MidiSourceValue::BorrowedSysEx(bytes) => {
+                // Copying into a fixed-size event doesn't allocate.
+                match RawMidiEvent::try_from_slice(0, bytes) {
+                    Ok(evt) => ForwardedMidiMessage::SysEx(evt),
+                    Err(_) => return,
+                }
+            }
+            // Control scripts only receive plain short messages and sys-ex.
+            _ => return,
+        };
+        self.control_main_task_sender
+            .send_complaining(ControlMainTask::ControlMidi {
+                event: value_event.with_payload(msg),
+            });
+    }
+
     fn control_main_mappings_midi(
         &mut self,
         source_value_event: ControlEvent<MidiEvent<&MidiSourceValue<RawShortMessage>>>,
@@ -1003,29 +1047,30 @@ impl RealTimeProcessor {
             // doesn't. Check again that it's a REAPER target.
             .filter(|m| m.control_is_effectively_on() && m.has_reaper_target())
         {
-            if let CompoundMappingSource::Midi(s) = &m.source() {
-                let midi_event = source_value_event.payload();
-                if let Some(control_value) = s.control(midi_event.payload()) {
-                    process_real_mapping(
-                        m,
-                        &self.control_main_task_sender,
-                        &self.feedback_task_sender,
-                        compartment,
-                        source_value_event
-                            .with_payload(MidiEvent::new(midi_event.offset(), control_value)),
-                        ControlOptions {
-                            enforce_target_refresh: match_outcome.matched(),
-                            ..Default::default()
-                        },
-                        caller,
-                        self.settings.midi_destination(),
-                        LogOptions::from_basic_settings(&self.settings),
-                        self.clip_matrix.as_ref(),
-                        is_rendering,
-                    );
-                    // It can't be consumed because we checked this before for all mappings.
-                    match_outcome = MatchOutcome::Matched;
-                }
+            let midi_event = source_value_event.payload();
+            if let Some(control_value) = m.core.source.control_midi(
+                source_value_event.with_payload(midi_event.payload()),
+                &self.mpe_state,
+            ) {
+                process_real_mapping(
+                    m,
+                    &self.control_main_task_sender,
+                    &self.feedback_task_sender,
+                    compartment,
+                    source_value_event
+                        .with_payload(MidiEvent::new(midi_event.offset(), control_value)),
+                    ControlOptions {
+                        enforce_target_refresh: match_outcome.matched(),
+                        ..Default::default()
+                    },
+                    caller,
+                    self.settings.midi_destination(),
+                    LogOptions::from_basic_settings(&self.settings),
+                    self.clip_matrix.as_ref(),
+                    is_rendering,
+                );
+                // It can't be consumed because we checked this before for all mappings.
+                match_outcome = MatchOutcome::Matched;
             }
         }
         match_outcome
@@ -1382,6 +1427,7 @@ fn control_controller_mappings_midi(
     // Mappings with virtual sources
     main_mappings: &mut OrderedMappingMap<RealTimeMapping>,
     value_event: ControlEvent<MidiEvent<&MidiSourceValue<RawShortMessage>>>,
+    mpe_state: &MpeState,
     caller: Caller,
     midi_feedback_output: Option<MidiDestination>,
     log_options: LogOptions,
@@ -1395,7 +1441,7 @@ fn control_controller_mappings_midi(
         .filter(|m| m.control_is_effectively_on())
     {
         if let Some(control_match) =
-            m.control_midi_virtualizing(flatten_control_midi_event(value_event))
+            m.control_midi_virtualizing(flatten_control_midi_event(value_event), mpe_state)
         {
             use PartialControlMatch::*;
             let child_match_outcome = match control_match {
@@ -1421,6 +1467,7 @@ fn control_controller_mappings_midi(
                             // Not important yet at this point because virtual targets can't affect
                             // subsequent virtual targets.
                             enforce_target_refresh: false,
+                            xy_axis: virtual_source_value.axis(),
                         },
                         caller,
                         midi_feedback_output,
@@ -1461,6 +1508,23 @@ fn control_controller_mappings_midi(
                     enforce_target_refresh = true;
                     MatchOutcome::Matched
                 }
+                ForwardVirtual(control_value) => {
+                    // The glue can't be processed in real-time (e.g. Lua transformation), so the
+                    // main processor needs to virtualize the value and control the main mappings.
+                    forward_control_to_main_processor(
+                        main_task_sender,
+                        Compartment::Controller,
+                        m.id(),
+                        value_event.with_payload(control_value),
+                        ControlOptions {
+                            enforce_send_feedback_after_control: m.options().feedback_send_behavior
+                                == FeedbackSendBehavior::SendFeedbackAfterControl,
+                            mode_control_options: m.mode_control_options(),
+                            ..Default::default()
+                        },
+                    );
+                    MatchOutcome::Matched
+                }
             };
             match_outcome.upgrade_from(child_match_outcome);
         }
@@ -1487,7 +1551,14 @@ fn process_real_mapping(
     if let Some(RealTimeCompoundMappingTarget::Reaper(reaper_target)) =
         mapping.resolved_target.as_mut()
     {
-        if reaper_target.wants_real_time_control(caller, is_rendering) {
+        // The resolved real-time target is always the one of the X axis. Y targets are
+        // controlled in the main thread. So are mappings with gestures because recognizing them
+        // requires polling, and mappings whose glue can't be processed in real-time (Lua).
+        if options.xy_axis == XyAxis::X
+            && !mapping.core.has_gesture()
+            && mapping.core.mode_is_real_time_capable()
+            && reaper_target.wants_real_time_control(caller, is_rendering)
+        {
             // Try to process directly here in real-time.
             mapping.core.increase_invocation_count();
             let control_context = RealTimeControlContext { clip_matrix };
@@ -1693,28 +1764,31 @@ fn control_main_mappings_virtual(
         .values_mut()
         .filter(|m| m.control_is_effectively_on())
     {
-        if let CompoundMappingSource::Virtual(s) = &m.source() {
-            let midi_event = value_event.payload();
-            if let Some(control_value) = s.control(&midi_event.payload()) {
-                process_real_mapping(
-                    m,
-                    main_task_sender,
-                    rt_feedback_sender,
-                    Compartment::Main,
-                    value_event.with_payload(MidiEvent::new(midi_event.offset(), control_value)),
-                    ControlOptions {
-                        enforce_target_refresh: match_outcome.matched(),
-                        ..options
-                    },
-                    caller,
-                    midi_feedback_output,
-                    log_options,
-                    matrix,
-                    is_rendering,
-                );
-                // If we find an associated main mapping, this is not just consumed, it's matched.
-                match_outcome = MatchOutcome::Matched;
-            }
+        let midi_event = value_event.payload();
+        let virtual_source_value = midi_event.payload();
+        if let Some(control_value) = m
+            .core
+            .source
+            .control_virtual(value_event.with_payload(&virtual_source_value))
+        {
+            process_real_mapping(
+                m,
+                main_task_sender,
+                rt_feedback_sender,
+                Compartment::Main,
+                value_event.with_payload(MidiEvent::new(midi_event.offset(), control_value)),
+                ControlOptions {
+                    enforce_target_refresh: match_outcome.matched(),
+                    ..options
+                },
+                caller,
+                midi_feedback_output,
+                log_options,
+                matrix,
+                is_rendering,
+            );
+            // If we find an associated main mapping, this is not just consumed, it's matched.
+            match_outcome = MatchOutcome::Matched;
         }
     }
     match_outcome
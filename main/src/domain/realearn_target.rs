@@ -11,16 +11,21 @@ use crate::domain::{
     OrderedMappingMap, OscFeedbackTask, ProcessorContext, QualifiedMappingId, RealTimeReaperTarget,
     ReaperTarget, SharedInstanceState, Tag, TagScope, TargetCharacter, TrackExclusivity,
     ACTION_TARGET, ALL_TRACK_FX_ENABLE_TARGET, ANY_ON_TARGET, AUTOMATION_MODE_OVERRIDE_TARGET,
-    BROWSE_FXS_TARGET, BROWSE_GROUP_MAPPINGS_TARGET, BROWSE_POT_FILTER_ITEMS_TARGET,
-    BROWSE_POT_PRESETS_TARGET, CLIP_COLUMN_TARGET, CLIP_MANAGEMENT_TARGET, CLIP_MATRIX_TARGET,
-    CLIP_ROW_TARGET, CLIP_SEEK_TARGET, CLIP_TRANSPORT_TARGET, CLIP_VOLUME_TARGET, DUMMY_TARGET,
-    ENABLE_INSTANCES_TARGET, ENABLE_MAPPINGS_TARGET, FX_ENABLE_TARGET, FX_ONLINE_TARGET,
-    FX_OPEN_TARGET, FX_PARAMETER_TARGET, FX_PARAMETER_TOUCH_STATE_TARGET, FX_PRESET_TARGET,
-    FX_TOOL_TARGET, GO_TO_BOOKMARK_TARGET, LOAD_FX_SNAPSHOT_TARGET, LOAD_MAPPING_SNAPSHOT_TARGET,
-    LOAD_POT_PRESET_TARGET, MIDI_SEND_TARGET, MOUSE_TARGET, OSC_SEND_TARGET, PLAYRATE_TARGET,
-    PREVIEW_POT_PRESET_TARGET, ROUTE_AUTOMATION_MODE_TARGET, ROUTE_MONO_TARGET, ROUTE_MUTE_TARGET,
-    ROUTE_PAN_TARGET, ROUTE_PHASE_TARGET, ROUTE_TOUCH_STATE_TARGET, ROUTE_VOLUME_TARGET,
-    SAVE_MAPPING_SNAPSHOT_TARGET, SEEK_TARGET, SELECTED_TRACK_TARGET, TEMPO_TARGET,
+    BROWSE_AUTOMATION_ITEMS_TARGET, BROWSE_FXS_TARGET, BROWSE_GROUP_MAPPINGS_TARGET,
+    BROWSE_ITEMS_TARGET, BROWSE_POT_FILTER_ITEMS_TARGET, BROWSE_POT_PRESETS_TARGET,
+    CLIP_COLUMN_TARGET, CLIP_MANAGEMENT_TARGET, CLIP_MATRIX_TARGET, CLIP_ROW_TARGET,
+    CLIP_SEEK_TARGET, CLIP_TRANSPORT_TARGET, CLIP_VOLUME_TARGET, DUMMY_TARGET,
+    ENABLE_INSTANCES_TARGET, ENABLE_MAPPINGS_TARGET, ENVELOPE_ARM_TARGET, ENVELOPE_SHOW_TARGET,
+    ENVELOPE_WRITE_VALUE_TARGET, FX_ENABLE_TARGET, FX_ONLINE_TARGET, FX_OPEN_TARGET,
+    FX_PARAMETER_TARGET, FX_PARAMETER_TOUCH_STATE_TARGET, FX_PRESET_TARGET, FX_TOOL_TARGET,
+    GO_TO_BOOKMARK_TARGET, ITEM_BROWSE_TAKES_TARGET, ITEM_LOCK_TARGET, ITEM_MUTE_TARGET,
+    ITEM_POSITION_TARGET, ITEM_SELECTION_TARGET, ITEM_VOLUME_TARGET, LOAD_FX_SNAPSHOT_TARGET,
+    LOAD_MAPPING_SNAPSHOT_TARGET, LOAD_POT_PRESET_TARGET, MARKER_MANAGEMENT_TARGET,
+    MIDI_SEND_TARGET, MOUSE_TARGET, OSC_SEND_TARGET, PLAYRATE_TARGET, PREVIEW_POT_PRESET_TARGET,
+    ROUTE_AUTOMATION_MODE_TARGET, ROUTE_MONO_TARGET, ROUTE_MUTE_TARGET, ROUTE_PAN_TARGET,
+    ROUTE_PHASE_TARGET, ROUTE_TOUCH_STATE_TARGET, ROUTE_VOLUME_TARGET,
+    SAVE_MAPPING_SNAPSHOT_TARGET, SEEK_TARGET, SELECTED_TRACK_TARGET, TAKE_FX_PARAMETER_TARGET,
+    TAKE_PITCH_TARGET, TAKE_PLAYRATE_TARGET, TAKE_VOLUME_TARGET, TEMPO_TARGET, TIME_RANGE_TARGET,
     TRACK_ARM_TARGET, TRACK_AUTOMATION_MODE_TARGET, TRACK_MONITORING_MODE_TARGET,
     TRACK_MUTE_TARGET, TRACK_PAN_TARGET, TRACK_PARENT_SEND_TARGET, TRACK_PEAK_TARGET,
     TRACK_PHASE_TARGET, TRACK_SELECTION_TARGET, TRACK_SHOW_TARGET, TRACK_SOLO_TARGET,
@@ -38,7 +43,7 @@ use playtime_clip_engine::base::ClipMatrixEvent;
 use playtime_clip_engine::rt;
 use playtime_clip_engine::rt::WeakMatrix;
 use realearn_api::persistence::TrackScope;
-use reaper_high::{ChangeEvent, Fx, Guid, Project, Reaper, Track, TrackRoute};
+use reaper_high::{ChangeEvent, Fx, Guid, Item, Project, Reaper, Track, TrackRoute};
 use reaper_medium::CommandId;
 use serde_repr::*;
 use std::borrow::Cow;
@@ -240,6 +245,12 @@ pub trait RealearnTarget {
     fn route(&self) -> Option<&TrackRoute> {
         None
     }
+    /// Returns the media item that this target currently affects.
+    ///
+    /// Not a reference because some targets determine the item dynamically.
+    fn item(&self) -> Option<Item> {
+        None
+    }
     fn track_exclusivity(&self) -> Option<TrackExclusivity> {
         None
     }
@@ -466,6 +477,8 @@ impl<'a> ControlContext<'a> {
 pub struct MappingControlContext<'a> {
     pub control_context: ControlContext<'a>,
     pub mapping_data: MappingData,
+    /// Text which came with the incoming source message, if any (e.g. an OSC string argument).
+    pub incoming_text: Option<&'a str>,
 }
 
 impl<'a> TransformationInputProvider<AdditionalTransformationInput> for MappingControlContext<'a> {
@@ -599,11 +612,13 @@ pub enum ReaperTargetType {
     Action = 0,
     Transport = 16,
     Seek = 23,
+    TimeRange = 73,
     PlayRate = 11,
     Tempo = 10,
 
     // Marker/region targets
     GoToBookmark = 22,
+    MarkerManagement = 74,
 
     // Track targets
     TrackTool = 44,
@@ -622,6 +637,21 @@ pub enum ReaperTargetType {
     TrackVolume = 2,
     TrackShow = 24,
     TrackSolo = 8,
+    BrowseItems = 72,
+
+    // Item targets
+    ItemVolume = 62,
+    ItemMute = 63,
+    ItemLock = 64,
+    ItemSelection = 65,
+    ItemPosition = 66,
+    ItemBrowseTakes = 67,
+
+    // Take targets
+    TakeVolume = 68,
+    TakePitch = 69,
+    TakePlayrate = 70,
+    TakeFxParameter = 71,
 
     // FX chain targets
     BrowseFxs = 28,
@@ -653,6 +683,12 @@ pub enum ReaperTargetType {
     RoutePan = 9,
     RouteVolume = 3,
 
+    // Envelope targets
+    EnvelopeArm = 75,
+    EnvelopeShow = 76,
+    EnvelopeWriteValue = 77,
+    BrowseAutomationItems = 78,
+
     // Clip targets
     ClipManagement = 46,
     ClipTransport = 31,
@@ -720,9 +756,11 @@ impl ReaperTargetType {
             Transport => &TRANSPORT_TARGET,
             BrowseTracks => &SELECTED_TRACK_TARGET,
             Seek => &SEEK_TARGET,
+            TimeRange => &TIME_RANGE_TARGET,
             PlayRate => &PLAYRATE_TARGET,
             Tempo => &TEMPO_TARGET,
             GoToBookmark => &GO_TO_BOOKMARK_TARGET,
+            MarkerManagement => &MARKER_MANAGEMENT_TARGET,
             TrackArm => &TRACK_ARM_TARGET,
             TrackParentSend => &TRACK_PARENT_SEND_TARGET,
             AllTrackFxEnable => &ALL_TRACK_FX_ENABLE_TARGET,
@@ -739,6 +777,17 @@ impl ReaperTargetType {
             TrackVolume => &TRACK_VOLUME_TARGET,
             TrackShow => &TRACK_SHOW_TARGET,
             TrackSolo => &TRACK_SOLO_TARGET,
+            BrowseItems => &BROWSE_ITEMS_TARGET,
+            ItemVolume => &ITEM_VOLUME_TARGET,
+            ItemMute => &ITEM_MUTE_TARGET,
+            ItemLock => &ITEM_LOCK_TARGET,
+            ItemSelection => &ITEM_SELECTION_TARGET,
+            ItemPosition => &ITEM_POSITION_TARGET,
+            ItemBrowseTakes => &ITEM_BROWSE_TAKES_TARGET,
+            TakeVolume => &TAKE_VOLUME_TARGET,
+            TakePitch => &TAKE_PITCH_TARGET,
+            TakePlayrate => &TAKE_PLAYRATE_TARGET,
+            TakeFxParameter => &TAKE_FX_PARAMETER_TARGET,
             FxTool => &FX_TOOL_TARGET,
             BrowseFxs => &BROWSE_FXS_TARGET,
             FxEnable => &FX_ENABLE_TARGET,
@@ -755,6 +804,10 @@ impl ReaperTargetType {
             RoutePan => &ROUTE_PAN_TARGET,
             RouteVolume => &ROUTE_VOLUME_TARGET,
             RouteTouchState => &ROUTE_TOUCH_STATE_TARGET,
+            EnvelopeArm => &ENVELOPE_ARM_TARGET,
+            EnvelopeShow => &ENVELOPE_SHOW_TARGET,
+            EnvelopeWriteValue => &ENVELOPE_WRITE_VALUE_TARGET,
+            BrowseAutomationItems => &BROWSE_AUTOMATION_ITEMS_TARGET,
             ClipTransport => &CLIP_TRANSPORT_TARGET,
             ClipColumn => &CLIP_COLUMN_TARGET,
             ClipRow => &CLIP_ROW_TARGET,
@@ -817,6 +870,14 @@ impl ReaperTargetType {
         self.definition().supports_send()
     }
 
+    pub fn supports_item(self) -> bool {
+        self.definition().supports_item()
+    }
+
+    pub fn supports_envelope(self) -> bool {
+        self.definition().supports_envelope()
+    }
+
     pub fn supports_track_exclusivity(self) -> bool {
         self.definition().supports_track_exclusivity()
     }
@@ -867,6 +928,8 @@ pub struct TargetTypeDef {
     pub supports_fx_display_type: bool,
     pub supports_tags: bool,
     pub supports_send: bool,
+    pub supports_item: bool,
+    pub supports_envelope: bool,
     pub supports_track_exclusivity: bool,
     pub supports_exclusivity: bool,
     pub supports_poll_for_feedback: bool,
@@ -937,6 +1000,12 @@ impl TargetTypeDef {
     pub const fn supports_send(&self) -> bool {
         self.supports_send
     }
+    pub const fn supports_item(&self) -> bool {
+        self.supports_item
+    }
+    pub const fn supports_envelope(&self) -> bool {
+        self.supports_envelope
+    }
     pub const fn supports_track_exclusivity(&self) -> bool {
         self.supports_track_exclusivity
     }
@@ -980,6 +1049,8 @@ pub const DEFAULT_TARGET: TargetTypeDef = TargetTypeDef {
     supports_fx_display_type: false,
     supports_tags: false,
     supports_send: false,
+    supports_item: false,
+    supports_envelope: false,
     supports_track_exclusivity: false,
     supports_exclusivity: false,
     supports_poll_for_feedback: false,
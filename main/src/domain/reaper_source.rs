@@ -1,14 +1,17 @@
-use crate::domain::{Compartment, CompartmentParamIndex, RawParamValue, ReaperSourceAddress};
+use crate::domain::{
+    AudioLevelSource, Compartment, CompartmentParamIndex, MusicalTimerSource, ProcessorContext,
+    RawParamValue, ReaperSourceAddress, SpeechSettingsOverride,
+};
 use core::fmt;
 use derive_more::Display;
 use helgoboss_learn::{
     format_percentage_without_unit, parse_percentage_without_unit, ControlValue,
     DetailedSourceCharacter, FeedbackValue, SourceCharacter, UnitValue,
 };
+use realearn_api::persistence::{AudioLevelMeasurement, MusicalTimerOutput};
 use reaper_medium::{MidiInputDeviceId, MidiOutputDeviceId};
 use std::collections::HashSet;
 use std::convert::TryInto;
-use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::time::{Duration, Instant};
 
@@ -17,68 +20,34 @@ pub enum ReaperSource {
     MidiDeviceChanges,
     RealearnInstanceStart,
     Timer(TimerSource),
+    MusicalTimer(MusicalTimerSource),
+    AudioLevel(AudioLevelSource),
     RealearnParameter(RealearnParameterSource),
     Speech(SpeechSource),
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Default)]
-pub struct SpeechSource {}
+pub struct SpeechSource {
+    settings: SpeechSettingsOverride,
+}
 
 impl SpeechSource {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(settings: SpeechSettingsOverride) -> Self {
+        Self { settings }
+    }
+
+    pub fn settings(&self) -> &SpeechSettingsOverride {
+        &self.settings
     }
 
     pub fn feedback(&self, feedback_value: &FeedbackValue) -> SpeechSourceFeedbackValue {
         SpeechSourceFeedbackValue {
             text: feedback_value.to_textual().text.to_string(),
+            settings: self.settings.clone(),
         }
     }
 }
 
-pub fn say(feedback_value: SpeechSourceFeedbackValue) -> Result<(), Box<dyn Error>> {
-    #[cfg(any(target_os = "windows", target_os = "macos"))]
-    {
-        fn get_default_tts() -> Result<tts::Tts, tts::Error> {
-            #[cfg(target_os = "macos")]
-            {
-                let mut tts = tts::Tts::default()?;
-                // On macOS, at least with AVFoundation, it's necessary to set a voice first.
-                // Prefer an English voice as default.
-                if let Ok(voices) = tts.voices() {
-                    let voice = voices
-                        .iter()
-                        .find(|v| v.language().language.as_str() == "en")
-                        .or_else(|| voices.first());
-                    if let Some(v) = voice {
-                        tts.set_voice(v)?;
-                    }
-                }
-                Ok(tts)
-            }
-            #[cfg(target_os = "windows")]
-            {
-                tts::Tts::default()
-            }
-        }
-        use once_cell::sync::Lazy;
-        use std::sync::Mutex;
-        static TTS: Lazy<Result<Mutex<tts::Tts>, tts::Error>> =
-            Lazy::new(|| get_default_tts().map(Mutex::new));
-        let tts = TTS.as_ref()?;
-        // TODO-medium This is only necessary because tts exposes a non-optimal API.
-        let mut tts = tts.lock()?;
-        // TODO-medium This cloning is totally unnecessary but ... non-optimal API.
-        tts.speak(feedback_value.text, true)?;
-        Ok(())
-    }
-    #[cfg(target_os = "linux")]
-    {
-        let _ = feedback_value;
-        Err("not yet supported on Linux".into())
-    }
-}
-
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub struct RealearnParameterSource {
     pub parameter_index: CompartmentParamIndex,
@@ -135,13 +104,18 @@ impl ReaperSource {
     pub fn on_deactivate(&mut self) {
         match self {
             ReaperSource::Timer(s) => s.on_deactivate(),
+            ReaperSource::MusicalTimer(s) => s.on_deactivate(),
+            ReaperSource::AudioLevel(s) => s.on_deactivate(),
             _ => {}
         }
     }
 
     /// If this returns `true`, the `poll` method should be called, on a regular basis.
     pub fn wants_to_be_polled(&self) -> bool {
-        matches!(self, ReaperSource::Timer(_))
+        matches!(
+            self,
+            ReaperSource::Timer(_) | ReaperSource::MusicalTimer(_) | ReaperSource::AudioLevel(_)
+        )
     }
 
     pub fn possible_detailed_characters(&self) -> Vec<DetailedSourceCharacter> {
@@ -150,6 +124,14 @@ impl ReaperSource {
             MidiDeviceChanges => vec![DetailedSourceCharacter::MomentaryOnOffButton],
             RealearnInstanceStart => vec![DetailedSourceCharacter::MomentaryOnOffButton],
             Timer(_) => vec![DetailedSourceCharacter::Trigger],
+            MusicalTimer(s) => match s.settings().output {
+                MusicalTimerOutput::Trigger => vec![DetailedSourceCharacter::Trigger],
+                MusicalTimerOutput::Ramp => vec![DetailedSourceCharacter::RangeControl],
+            },
+            AudioLevel(s) => match s.settings().measurement {
+                AudioLevelMeasurement::Onset => vec![DetailedSourceCharacter::Trigger],
+                _ => vec![DetailedSourceCharacter::RangeControl],
+            },
             RealearnParameter(_) => vec![
                 DetailedSourceCharacter::RangeControl,
                 DetailedSourceCharacter::MomentaryVelocitySensitiveButton,
@@ -175,16 +157,25 @@ impl ReaperSource {
             MidiDeviceChanges | RealearnInstanceStart | Timer(_) => {
                 SourceCharacter::MomentaryButton
             }
+            MusicalTimer(s) => match s.settings().output {
+                MusicalTimerOutput::Trigger => SourceCharacter::MomentaryButton,
+                MusicalTimerOutput::Ramp => SourceCharacter::RangeElement,
+            },
+            AudioLevel(s) => match s.settings().measurement {
+                AudioLevelMeasurement::Onset => SourceCharacter::MomentaryButton,
+                _ => SourceCharacter::RangeElement,
+            },
             RealearnParameter(_) => SourceCharacter::RangeElement,
             Speech(_) => SourceCharacter::RangeElement,
         }
     }
 
-    pub fn poll(&mut self) -> Option<ControlValue> {
-        if let ReaperSource::Timer(t) = self {
-            t.poll()
-        } else {
-            None
+    pub fn poll(&mut self, context: &ProcessorContext) -> Option<ControlValue> {
+        match self {
+            ReaperSource::Timer(t) => t.poll(),
+            ReaperSource::MusicalTimer(t) => t.poll(context.project_or_current_project()),
+            ReaperSource::AudioLevel(s) => s.poll(context),
+            _ => None,
         }
     }
 
@@ -224,7 +215,12 @@ impl ReaperSource {
     pub fn feedback(&self, feedback_value: &FeedbackValue) -> Option<ReaperSourceFeedbackValue> {
         use ReaperSource::*;
         match self {
-            MidiDeviceChanges | RealearnInstanceStart | Timer(_) | RealearnParameter(_) => None,
+            MidiDeviceChanges
+            | RealearnInstanceStart
+            | Timer(_)
+            | MusicalTimer(_)
+            | AudioLevel(_)
+            | RealearnParameter(_) => None,
             Speech(s) => Some(ReaperSourceFeedbackValue::Speech(
                 s.feedback(feedback_value),
             )),
@@ -248,6 +244,8 @@ impl ReaperSourceFeedbackValue {
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct SpeechSourceFeedbackValue {
     pub text: String,
+    /// Applied to the speech settings of the instance when speaking.
+    pub settings: SpeechSettingsOverride,
 }
 
 #[derive(PartialEq, Debug, Display)]
@@ -30,21 +30,24 @@ use crate::base::Global;
 use crate::domain::ui_util::convert_bool_to_unit_value;
 use crate::domain::{
     get_reaper_track_area_of_scope, handle_exclusivity, ActionTarget, AdditionalFeedbackEvent,
-    AllTrackFxEnableTarget, AutomationModeOverrideTarget, BrowseFxsTarget,
-    BrowsePotFilterItemsTarget, BrowsePotPresetsTarget, BrowseTracksTarget, Caller,
-    ClipColumnTarget, ClipManagementTarget, ClipMatrixTarget, ClipRowTarget, ClipSeekTarget,
-    ClipTransportTarget, ClipVolumeTarget, ControlContext, DummyTarget, EnigoMouseTarget,
+    AllTrackFxEnableTarget, AutomationModeOverrideTarget, BrowseAutomationItemsTarget,
+    BrowseFxsTarget, BrowseItemsTarget, BrowsePotFilterItemsTarget, BrowsePotPresetsTarget,
+    BrowseTracksTarget, Caller, ClipColumnTarget, ClipManagementTarget, ClipMatrixTarget,
+    ClipRowTarget, ClipSeekTarget, ClipTransportTarget, ClipVolumeTarget, ControlContext,
+    DummyTarget, EnigoMouseTarget, EnvelopeArmTarget, EnvelopeShowTarget, EnvelopeWriteValueTarget,
     FxEnableTarget, FxOnlineTarget, FxOpenTarget, FxParameterTarget, FxParameterTouchStateTarget,
     FxPresetTarget, FxToolTarget, GoToBookmarkTarget, HierarchyEntry, HierarchyEntryProvider,
-    LoadFxSnapshotTarget, LoadPotPresetTarget, MappingControlContext, MidiSendTarget,
-    OscSendTarget, PlayrateTarget, PreviewPotPresetTarget, RealTimeClipColumnTarget,
-    RealTimeClipMatrixTarget, RealTimeClipRowTarget, RealTimeClipTransportTarget,
-    RealTimeControlContext, RealTimeFxParameterTarget, RouteMuteTarget, RoutePanTarget,
-    RouteTouchStateTarget, RouteVolumeTarget, SeekTarget, TakeMappingSnapshotTarget, TargetTypeDef,
-    TempoTarget, TrackArmTarget, TrackAutomationModeTarget, TrackMonitoringModeTarget,
-    TrackMuteTarget, TrackPanTarget, TrackParentSendTarget, TrackPeakTarget, TrackSelectionTarget,
-    TrackShowTarget, TrackSoloTarget, TrackTouchStateTarget, TrackVolumeTarget, TrackWidthTarget,
-    TransportTarget,
+    ItemBrowseTakesTarget, ItemLockTarget, ItemMuteTarget, ItemPositionTarget, ItemSelectionTarget,
+    ItemVolumeTarget, LoadFxSnapshotTarget, LoadPotPresetTarget, MappingControlContext,
+    MarkerManagementTarget, MidiSendTarget, OscSendTarget, PlayrateTarget, PreviewPotPresetTarget,
+    RealTimeClipColumnTarget, RealTimeClipMatrixTarget, RealTimeClipRowTarget,
+    RealTimeClipTransportTarget, RealTimeControlContext, RealTimeFxParameterTarget,
+    RouteMuteTarget, RoutePanTarget, RouteTouchStateTarget, RouteVolumeTarget, SeekTarget,
+    TakeFxParameterTarget, TakeMappingSnapshotTarget, TakePitchTarget, TakePlayrateTarget,
+    TakeVolumeTarget, TargetTypeDef, TempoTarget, TimeRangeTarget, TrackArmTarget,
+    TrackAutomationModeTarget, TrackMonitoringModeTarget, TrackMuteTarget, TrackPanTarget,
+    TrackParentSendTarget, TrackPeakTarget, TrackSelectionTarget, TrackShowTarget, TrackSoloTarget,
+    TrackTouchStateTarget, TrackVolumeTarget, TrackWidthTarget, TransportTarget,
 };
 use crate::domain::{
     AnyOnTarget, BrowseGroupMappingsTarget, CompoundChangeEvent, EnableInstancesTarget,
@@ -124,6 +127,10 @@ pub enum ReaperTarget {
     RouteMono(RouteMonoTarget),
     RouteAutomationMode(RouteAutomationModeTarget),
     RouteTouchState(RouteTouchStateTarget),
+    EnvelopeArm(EnvelopeArmTarget),
+    EnvelopeShow(EnvelopeShowTarget),
+    EnvelopeWriteValue(EnvelopeWriteValueTarget),
+    BrowseAutomationItems(BrowseAutomationItemsTarget),
     Tempo(TempoTarget),
     Playrate(PlayrateTarget),
     AutomationModeOverride(AutomationModeOverrideTarget),
@@ -132,6 +139,7 @@ pub enum ReaperTarget {
     FxOpen(FxOpenTarget),
     FxPreset(FxPresetTarget),
     BrowseTracks(BrowseTracksTarget),
+    BrowseItems(BrowseItemsTarget),
     BrowseFxs(BrowseFxsTarget),
     AllTrackFxEnable(AllTrackFxEnableTarget),
     Transport(TransportTarget),
@@ -139,7 +147,9 @@ pub enum ReaperTarget {
     LoadFxSnapshot(LoadFxSnapshotTarget),
     TrackAutomationTouchState(TrackTouchStateTarget),
     GoToBookmark(GoToBookmarkTarget),
+    MarkerManagement(MarkerManagementTarget),
     Seek(SeekTarget),
+    TimeRange(TimeRangeTarget),
     SendMidi(MidiSendTarget),
     SendOsc(OscSendTarget),
     Dummy(DummyTarget),
@@ -159,6 +169,16 @@ pub enum ReaperTarget {
     BrowsePotPresets(BrowsePotPresetsTarget),
     PreviewPotPreset(PreviewPotPresetTarget),
     LoadPotPreset(LoadPotPresetTarget),
+    ItemVolume(ItemVolumeTarget),
+    ItemMute(ItemMuteTarget),
+    ItemLock(ItemLockTarget),
+    ItemSelection(ItemSelectionTarget),
+    ItemPosition(ItemPositionTarget),
+    ItemBrowseTakes(ItemBrowseTakesTarget),
+    TakeVolume(TakeVolumeTarget),
+    TakePitch(TakePitchTarget),
+    TakePlayrate(TakePlayrateTarget),
+    TakeFxParameter(TakeFxParameterTarget),
 }
 
 #[derive(
@@ -617,6 +637,11 @@ impl<'a> Target<'a> for ReaperTarget {
             RouteMono(t) => t.current_value(context),
             RouteAutomationMode(t) => t.current_value(context),
             RouteTouchState(t) => t.current_value(context),
+            EnvelopeArm(t) => t.current_value(context),
+            EnvelopeShow(t) => t.current_value(context),
+            EnvelopeWriteValue(t) => t.current_value(context),
+            // Discrete
+            BrowseAutomationItems(t) => t.current_value(context),
             Tempo(t) => t.current_value(context),
             Playrate(t) => t.current_value(context),
             AutomationModeOverride(t) => t.current_value(context),
@@ -630,13 +655,17 @@ impl<'a> Target<'a> for ReaperTarget {
             // Discrete
             BrowseTracks(t) => t.current_value(context),
             // Discrete
+            BrowseItems(t) => t.current_value(context),
+            // Discrete
             BrowseFxs(t) => t.current_value(context),
             AllTrackFxEnable(t) => t.current_value(context),
             Transport(t) => t.current_value(context),
             AnyOn(t) => t.current_value(context),
             TrackAutomationTouchState(t) => t.current_value(context),
             GoToBookmark(t) => t.current_value(context),
+            MarkerManagement(t) => t.current_value(context),
             Seek(t) => t.current_value(context),
+            TimeRange(t) => t.current_value(context),
             ClipTransport(t) => t.current_value(context),
             ClipColumn(t) => t.current_value(context),
             ClipRow(t) => t.current_value(context),
@@ -653,6 +682,16 @@ impl<'a> Target<'a> for ReaperTarget {
             BrowsePotPresets(t) => t.current_value(context),
             PreviewPotPreset(t) => t.current_value(context),
             LoadPotPreset(t) => t.current_value(context),
+            ItemVolume(t) => t.current_value(context),
+            ItemMute(t) => t.current_value(context),
+            ItemLock(t) => t.current_value(context),
+            ItemSelection(t) => t.current_value(context),
+            ItemPosition(t) => t.current_value(context),
+            ItemBrowseTakes(t) => t.current_value(context),
+            TakeVolume(t) => t.current_value(context),
+            TakePitch(t) => t.current_value(context),
+            TakePlayrate(t) => t.current_value(context),
+            TakeFxParameter(t) => t.current_value(context),
         }
     }
 
@@ -51,6 +51,15 @@ impl<const N: usize> LimitedAsciiString<N> {
     }
 }
 
+impl<const N: usize> Default for LimitedAsciiString<N> {
+    fn default() -> Self {
+        Self {
+            length: 0,
+            content: [0u8; SmallAsciiString::MAX_LENGTH],
+        }
+    }
+}
+
 pub fn convert_to_identifier(text: &str) -> Result<SmallAsciiString, &'static str> {
     let ascii_string: AsciiString = text
         .chars()
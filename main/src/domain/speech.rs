@@ -0,0 +1,442 @@
+use once_cell::sync::Lazy;
+use realearn_api::persistence::SpeechQueuePolicy;
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Rate (in percent) at which the backend speaks by default.
+pub const NORMAL_SPEECH_RATE: u32 = 100;
+pub const MIN_SPEECH_RATE: u32 = 10;
+pub const MAX_SPEECH_RATE: u32 = 400;
+
+/// Speech settings of a ReaLearn instance, relevant for the speech backend.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SpeechSettings {
+    /// Name of the voice. Empty means the default voice of the backend.
+    pub voice: String,
+    /// Rate in percent of the normal rate.
+    pub rate: u32,
+    pub queue_policy: SpeechQueuePolicy,
+}
+
+impl Default for SpeechSettings {
+    fn default() -> Self {
+        Self {
+            voice: String::new(),
+            rate: NORMAL_SPEECH_RATE,
+            queue_policy: Default::default(),
+        }
+    }
+}
+
+/// Speech settings of a speech source. Each setting which is not set is taken from the instance.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct SpeechSettingsOverride {
+    pub voice: Option<String>,
+    pub rate: Option<u32>,
+    pub queue_policy: Option<SpeechQueuePolicy>,
+}
+
+impl SpeechSettingsOverride {
+    pub fn apply_to(&self, instance_settings: &SpeechSettings) -> SpeechSettings {
+        SpeechSettings {
+            voice: self
+                .voice
+                .clone()
+                .unwrap_or_else(|| instance_settings.voice.clone()),
+            rate: self.rate.unwrap_or(instance_settings.rate),
+            queue_policy: self.queue_policy.unwrap_or(instance_settings.queue_policy),
+        }
+    }
+}
+
+/// A text-to-speech engine.
+pub trait SpeechBackend: Send {
+    /// Speaks the given text, asynchronously.
+    fn speak(&mut self, text: &str, settings: &SpeechSettings) -> Result<(), Box<dyn Error>>;
+}
+
+/// Speaks the given text using the default speech backend of the current platform.
+pub fn say(text: &str, settings: &SpeechSettings) -> Result<(), Box<dyn Error>> {
+    static BACKEND: Lazy<Result<Mutex<Box<dyn SpeechBackend>>, String>> = Lazy::new(|| {
+        create_default_speech_backend()
+            .map(Mutex::new)
+            .map_err(|e| e.to_string())
+    });
+    let backend = BACKEND.as_ref().map_err(|e| e.clone())?;
+    let mut backend = backend.lock()?;
+    backend.speak(text, settings)
+}
+
+fn create_default_speech_backend() -> Result<Box<dyn SpeechBackend>, Box<dyn Error>> {
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    {
+        Ok(Box::new(tts_backend::TtsSpeechBackend::new()?))
+    }
+    #[cfg(target_os = "linux")]
+    {
+        Ok(Box::new(
+            command_line_backend::CommandLineSpeechBackend::new()?,
+        ))
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        Err("speech is not supported on this operating system".into())
+    }
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+mod tts_backend {
+    use crate::domain::{SpeechBackend, SpeechSettings};
+    use realearn_api::persistence::SpeechQueuePolicy;
+    use std::error::Error;
+
+    /// Uses the native speech engine of the operating system.
+    pub struct TtsSpeechBackend {
+        tts: tts::Tts,
+        /// Voice which has been set most recently (empty if the default voice).
+        current_voice: String,
+    }
+
+    impl TtsSpeechBackend {
+        pub fn new() -> Result<Self, tts::Error> {
+            #[cfg(target_os = "macos")]
+            {
+                let mut tts = tts::Tts::default()?;
+                // On macOS, at least with AVFoundation, it's necessary to set a voice first.
+                // Prefer an English voice as default.
+                if let Ok(voices) = tts.voices() {
+                    let voice = voices
+                        .iter()
+                        .find(|v| v.language().language.as_str() == "en")
+                        .or_else(|| voices.first());
+                    if let Some(v) = voice {
+                        tts.set_voice(v)?;
+                    }
+                }
+                Ok(Self {
+                    tts,
+                    current_voice: String::new(),
+                })
+            }
+            #[cfg(target_os = "windows")]
+            {
+                Ok(Self {
+                    tts: tts::Tts::default()?,
+                    current_voice: String::new(),
+                })
+            }
+        }
+
+        fn apply_voice(&mut self, voice: &str) -> Result<(), tts::Error> {
+            if voice.is_empty() || voice == self.current_voice {
+                return Ok(());
+            }
+            let voices = self.tts.voices()?;
+            if let Some(v) = voices.iter().find(|v| v.name() == voice || v.id() == voice) {
+                self.tts.set_voice(v)?;
+                self.current_voice = voice.to_owned();
+            }
+            Ok(())
+        }
+
+        fn apply_rate(&mut self, rate: u32) -> Result<(), tts::Error> {
+            if !self.tts.supported_features().rate {
+                return Ok(());
+            }
+            let factor = rate as f32 / super::NORMAL_SPEECH_RATE as f32;
+            let native_rate =
+                (self.tts.normal_rate() * factor).clamp(self.tts.min_rate(), self.tts.max_rate());
+            self.tts.set_rate(native_rate)?;
+            Ok(())
+        }
+    }
+
+    impl SpeechBackend for TtsSpeechBackend {
+        fn speak(&mut self, text: &str, settings: &SpeechSettings) -> Result<(), Box<dyn Error>> {
+            self.apply_voice(&settings.voice)?;
+            self.apply_rate(settings.rate)?;
+            let interrupt = settings.queue_policy == SpeechQueuePolicy::Interrupt;
+            self.tts.speak(text, interrupt)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod command_line_backend {
+    use crate::domain::{SpeechBackend, SpeechSettings, NORMAL_SPEECH_RATE};
+    use crossbeam_channel::{Receiver, Sender};
+    use realearn_api::persistence::SpeechQueuePolicy;
+    use std::error::Error;
+    use std::process::{Child, Command, Stdio};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Uses one of the speech programs which are commonly available on Linux.
+    ///
+    /// Everything which involves running a program (detecting the program, speaking, cancelling)
+    /// happens in a dedicated worker thread, so the caller never waits for a program.
+    pub struct CommandLineSpeechBackend {
+        sender: Sender<SpeechJob>,
+        /// Increased with each interruption. Jobs of an older generation are skipped or stopped.
+        generation: Arc<AtomicU64>,
+    }
+
+    struct SpeechJob {
+        generation: u64,
+        text: String,
+        settings: SpeechSettings,
+    }
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    enum SpeechProgram {
+        EspeakNg,
+        SpdSay,
+    }
+
+    impl CommandLineSpeechBackend {
+        pub fn new() -> Result<Self, Box<dyn Error>> {
+            let (sender, receiver) = crossbeam_channel::unbounded();
+            let generation = Arc::new(AtomicU64::new(0));
+            let worker_generation = generation.clone();
+            std::thread::Builder::new()
+                .name(String::from("ReaLearn speech"))
+                .spawn(move || {
+                    match SpeechProgram::detect() {
+                        None => {
+                            // Dropping the receiver lets subsequent speak attempts fail.
+                            tracing::warn!("Neither espeak-ng nor spd-say is installed");
+                        }
+                        Some(program) => keep_speaking(program, receiver, &worker_generation),
+                    }
+                })?;
+            let backend = Self { sender, generation };
+            Ok(backend)
+        }
+    }
+
+    impl SpeechBackend for CommandLineSpeechBackend {
+        fn speak(&mut self, text: &str, settings: &SpeechSettings) -> Result<(), Box<dyn Error>> {
+            let generation = if settings.queue_policy == SpeechQueuePolicy::Interrupt {
+                // The worker thread notices this and stops the current text itself.
+                self.generation.fetch_add(1, Ordering::SeqCst) + 1
+            } else {
+                self.generation.load(Ordering::SeqCst)
+            };
+            let job = SpeechJob {
+                generation,
+                text: text.to_owned(),
+                settings: settings.clone(),
+            };
+            self.sender
+                .send(job)
+                .map_err(|_| "speech worker thread not running")?;
+            Ok(())
+        }
+    }
+
+    fn keep_speaking(
+        program: SpeechProgram,
+        receiver: Receiver<SpeechJob>,
+        generation: &AtomicU64,
+    ) {
+        for job in receiver {
+            if job.generation != generation.load(Ordering::SeqCst) {
+                // Interrupted while waiting in the queue
+                continue;
+            }
+            let child = match program.build_command(&job.text, &job.settings).spawn() {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+            wait_until_finished_or_interrupted(program, child, || {
+                job.generation != generation.load(Ordering::SeqCst)
+            });
+        }
+    }
+
+    fn wait_until_finished_or_interrupted(
+        program: SpeechProgram,
+        mut child: Child,
+        is_interrupted: impl Fn() -> bool,
+    ) {
+        loop {
+            if !matches!(child.try_wait(), Ok(None)) {
+                return;
+            }
+            if is_interrupted() {
+                let _ = child.kill();
+                let _ = child.wait();
+                program.cancel();
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    impl SpeechProgram {
+        fn detect() -> Option<Self> {
+            [Self::EspeakNg, Self::SpdSay]
+                .into_iter()
+                .find(|p| p.is_available())
+        }
+
+        fn executable(&self) -> &'static str {
+            match self {
+                Self::EspeakNg => "espeak-ng",
+                Self::SpdSay => "spd-say",
+            }
+        }
+
+        fn is_available(&self) -> bool {
+            Command::new(self.executable())
+                .arg("--version")
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|s| s.success())
+                .unwrap_or(false)
+        }
+
+        fn build_command(&self, text: &str, settings: &SpeechSettings) -> Command {
+            let mut cmd = Command::new(self.executable());
+            match self {
+                Self::EspeakNg => {
+                    if !settings.voice.is_empty() {
+                        cmd.arg("-v").arg(&settings.voice);
+                    }
+                    // espeak-ng speaks 175 words per minute by default.
+                    let words_per_minute =
+                        (175 * settings.rate / NORMAL_SPEECH_RATE).clamp(80, 450);
+                    cmd.arg("-s").arg(words_per_minute.to_string());
+                }
+                Self::SpdSay => {
+                    // Without waiting, the worker thread wouldn't know when to start the next
+                    // text.
+                    cmd.arg("--wait");
+                    if !settings.voice.is_empty() {
+                        cmd.arg("--synthesis-voice").arg(&settings.voice);
+                    }
+                    // spd-say expects a rate between -100 and 100, 0 being normal.
+                    let rate = (settings.rate as i64 - NORMAL_SPEECH_RATE as i64).clamp(-100, 100);
+                    cmd.arg("--rate").arg(rate.to_string());
+                }
+            }
+            // Texts starting with a dash must not be interpreted as options.
+            cmd.arg("--").arg(text);
+            cmd.stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            cmd
+        }
+
+        /// Killing spd-say doesn't stop speech-dispatcher from speaking.
+        fn cancel(&self) {
+            if *self == Self::SpdSay {
+                let _ = Command::new(self.executable())
+                    .arg("--cancel")
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status();
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn args(program: SpeechProgram, text: &str, settings: &SpeechSettings) -> Vec<String> {
+            program
+                .build_command(text, settings)
+                .get_args()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect()
+        }
+
+        fn settings(voice: &str, rate: u32) -> SpeechSettings {
+            SpeechSettings {
+                voice: voice.to_owned(),
+                rate,
+                queue_policy: SpeechQueuePolicy::Interrupt,
+            }
+        }
+
+        #[test]
+        fn espeak_ng_defaults() {
+            // Given
+            let settings = SpeechSettings::default();
+            // When
+            let command = SpeechProgram::EspeakNg.build_command("hello", &settings);
+            let args = args(SpeechProgram::EspeakNg, "hello", &settings);
+            // Then
+            assert_eq!(command.get_program(), "espeak-ng");
+            assert_eq!(args, vec!["-s", "175", "--", "hello"]);
+        }
+
+        #[test]
+        fn espeak_ng_voice_and_rate() {
+            assert_eq!(
+                args(SpeechProgram::EspeakNg, "hello", &settings("en-us", 200)),
+                vec!["-v", "en-us", "-s", "350", "--", "hello"]
+            );
+            // Clamped to what espeak-ng supports
+            assert_eq!(
+                args(SpeechProgram::EspeakNg, "hello", &settings("", 10)),
+                vec!["-s", "80", "--", "hello"]
+            );
+            assert_eq!(
+                args(SpeechProgram::EspeakNg, "hello", &settings("", 400)),
+                vec!["-s", "450", "--", "hello"]
+            );
+        }
+
+        #[test]
+        fn spd_say_defaults() {
+            // Given
+            let settings = SpeechSettings::default();
+            // When
+            let command = SpeechProgram::SpdSay.build_command("hello", &settings);
+            let args = args(SpeechProgram::SpdSay, "hello", &settings);
+            // Then
+            assert_eq!(command.get_program(), "spd-say");
+            assert_eq!(args, vec!["--wait", "--rate", "0", "--", "hello"]);
+        }
+
+        #[test]
+        fn spd_say_voice_and_rate() {
+            assert_eq!(
+                args(SpeechProgram::SpdSay, "hello", &settings("male1", 150)),
+                vec![
+                    "--wait",
+                    "--synthesis-voice",
+                    "male1",
+                    "--rate",
+                    "50",
+                    "--",
+                    "hello"
+                ]
+            );
+            assert_eq!(
+                args(SpeechProgram::SpdSay, "hello", &settings("", 10)),
+                vec!["--wait", "--rate", "-90", "--", "hello"]
+            );
+            // Clamped to what spd-say supports
+            assert_eq!(
+                args(SpeechProgram::SpdSay, "hello", &settings("", 400)),
+                vec!["--wait", "--rate", "100", "--", "hello"]
+            );
+        }
+
+        #[test]
+        fn text_starting_with_dash_is_no_option() {
+            for program in [SpeechProgram::EspeakNg, SpeechProgram::SpdSay] {
+                let args = args(program, "-6 dB", &SpeechSettings::default());
+                assert_eq!(&args[args.len() - 2..], ["--", "-6 dB"]);
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use crate::domain::{
+    convert_count_to_step_size, convert_discrete_to_unit_value_with_none,
+    convert_unit_to_discrete_value_with_none, get_automation_item_count,
+    get_first_selected_automation_item, select_automation_item_exclusively, Compartment,
+    ControlContext, EnvelopeDescriptor, ExtendedProcessorContext, FeedbackResolution,
+    FxParameterDescriptor, HitResponse, MappingControlContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, ResolvedEnvelope, TargetCharacter, TargetTypeDef, TrackDescriptor,
+    TrackRouteDescriptor, UnresolvedReaperTargetDef, AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    DEFAULT_TARGET,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, Target, UnitValue,
+};
+use reaper_high::{Fx, Project, Track, TrackRoute};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedBrowseAutomationItemsTarget {
+    pub descriptor: EnvelopeDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedBrowseAutomationItemsTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(self
+            .descriptor
+            .resolve(context, compartment)?
+            .into_iter()
+            .map(|envelope| {
+                ReaperTarget::BrowseAutomationItems(BrowseAutomationItemsTarget { envelope })
+            })
+            .collect())
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // REAPER doesn't notify us about changes of the automation item selection.
+        Some(FeedbackResolution::High)
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        self.descriptor.track_descriptor()
+    }
+
+    fn route_descriptor(&self) -> Option<&TrackRouteDescriptor> {
+        self.descriptor.route_descriptor()
+    }
+
+    fn fx_parameter_descriptor(&self) -> Option<&FxParameterDescriptor> {
+        self.descriptor.fx_parameter_descriptor()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BrowseAutomationItemsTarget {
+    pub envelope: ResolvedEnvelope,
+}
+
+impl RealearnTarget for BrowseAutomationItemsTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteDiscrete {
+                atomic_step_size: self.step_size(),
+                is_retriggerable: false,
+            },
+            TargetCharacter::Discrete,
+        )
+    }
+
+    fn parse_as_value(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn parse_as_step_size(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn convert_unit_value_to_discrete_value(
+        &self,
+        input: UnitValue,
+        _: ControlContext,
+    ) -> Result<u32, &'static str> {
+        let value = convert_unit_to_discrete_value_with_none(input, self.automation_item_count())
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        Ok(value)
+    }
+
+    fn convert_discrete_value_to_unit_value(
+        &self,
+        value: u32,
+        _: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        let index = if value == 0 { None } else { Some(value - 1) };
+        let uv = convert_discrete_to_unit_value_with_none(index, self.automation_item_count());
+        Ok(uv)
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        match convert_unit_to_discrete_value_with_none(value, self.automation_item_count()) {
+            None => "<None>".to_string(),
+            Some(i) => (i + 1).to_string(),
+        }
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let envelope = self
+            .envelope
+            .envelope(false)
+            .ok_or("envelope doesn't exist")?;
+        let count = get_automation_item_count(envelope);
+        let index = match value.to_absolute_value()? {
+            AbsoluteValue::Continuous(v) => convert_unit_to_discrete_value_with_none(v, count),
+            AbsoluteValue::Discrete(f) => {
+                if f.actual() == 0 {
+                    None
+                } else {
+                    Some(f.actual() - 1)
+                }
+            }
+        };
+        if let Some(i) = index {
+            if i >= count {
+                return Err("automation item not available");
+            }
+        }
+        select_automation_item_exclusively(envelope, index);
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.envelope.is_available() && self.envelope.envelope(false).is_some()
+    }
+
+    fn project(&self) -> Option<Project> {
+        self.envelope.project()
+    }
+
+    fn track(&self) -> Option<&Track> {
+        self.envelope.track()
+    }
+
+    fn fx(&self) -> Option<&Fx> {
+        self.envelope.fx()
+    }
+
+    fn route(&self) -> Option<&TrackRoute> {
+        self.envelope.route()
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        let index = self.current_automation_item_index()?;
+        Some(format!("Automation item {}", index + 1).into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        let index = self.current_automation_item_index()?;
+        Some(NumericValue::Discrete(index as i32 + 1))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::BrowseAutomationItems)
+    }
+}
+
+impl<'a> Target<'a> for BrowseAutomationItemsTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        Some(self.percentage_for(self.current_automation_item_index()))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+impl BrowseAutomationItemsTarget {
+    fn automation_item_count(&self) -> u32 {
+        self.envelope
+            .envelope(false)
+            .map(get_automation_item_count)
+            .unwrap_or(0)
+    }
+
+    fn current_automation_item_index(&self) -> Option<u32> {
+        get_first_selected_automation_item(self.envelope.envelope(false)?)
+    }
+
+    fn step_size(&self) -> UnitValue {
+        // `+ 1` because "<None>" is also a possible value.
+        convert_count_to_step_size(self.automation_item_count() + 1)
+    }
+
+    fn percentage_for(&self, index: Option<u32>) -> AbsoluteValue {
+        // Because we count "<None>" as a possible value, this is equal.
+        let max_value = self.automation_item_count();
+        let actual_value = index.map(|i| i + 1).unwrap_or(0);
+        AbsoluteValue::Discrete(Fraction::new(actual_value, max_value))
+    }
+}
+
+pub const BROWSE_AUTOMATION_ITEMS_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Envelope: Browse automation items",
+    short_name: "Browse automation items",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_track: true,
+    if_so_supports_track_must_be_selected: false,
+    supports_envelope: true,
+    ..DEFAULT_TARGET
+};
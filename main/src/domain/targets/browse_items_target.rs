@@ -0,0 +1,334 @@
+use crate::domain::{
+    convert_count_to_step_size, convert_discrete_to_unit_value_with_none,
+    convert_unit_to_discrete_value_with_none, get_effective_tracks, get_item_at_position,
+    get_item_info_value, get_item_name, item_is_selected, scroll_arrange_view_to_position,
+    select_item_exclusively_within_tracks, set_item_info_value, AdditionalFeedbackEvent,
+    Compartment, CompoundChangeEvent, ControlContext, ExtendedProcessorContext, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetTypeDef, TrackDescriptor, UnresolvedReaperTargetDef, DEFAULT_TARGET, ITEM_POSITION,
+    ITEM_SELECTED,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, Target, UnitValue,
+};
+use reaper_high::{Item, Project, Track};
+use reaper_medium::{PositionInSeconds, SetEditCurPosOptions};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedBrowseItemsTarget {
+    pub track_descriptor: TrackDescriptor,
+    pub scroll_arrange_view: bool,
+    pub move_edit_cursor: bool,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedBrowseItemsTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        // Multiple tracks (e.g. <Selected>) result in one target which browses the items of all
+        // tracks, not in one target per track.
+        let tracks = get_effective_tracks(context, &self.track_descriptor.track, compartment)?;
+        if tracks.is_empty() {
+            return Err("no tracks");
+        }
+        let target = BrowseItemsTarget {
+            tracks,
+            scroll_arrange_view: self.scroll_arrange_view,
+            move_edit_cursor: self.move_edit_cursor,
+        };
+        Ok(vec![ReaperTarget::BrowseItems(target)])
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        Some(&self.track_descriptor)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BrowseItemsTarget {
+    /// Never empty.
+    pub tracks: Vec<Track>,
+    pub scroll_arrange_view: bool,
+    pub move_edit_cursor: bool,
+}
+
+impl RealearnTarget for BrowseItemsTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteDiscrete {
+                atomic_step_size: self.step_size(),
+                is_retriggerable: false,
+            },
+            TargetCharacter::Discrete,
+        )
+    }
+
+    fn parse_as_value(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn parse_as_step_size(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn convert_unit_value_to_discrete_value(
+        &self,
+        input: UnitValue,
+        _: ControlContext,
+    ) -> Result<u32, &'static str> {
+        let value = convert_unit_to_discrete_value_with_none(input, self.item_count())
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        Ok(value)
+    }
+
+    fn convert_discrete_value_to_unit_value(
+        &self,
+        value: u32,
+        _: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        let index = if value == 0 { None } else { Some(value - 1) };
+        let uv = convert_discrete_to_unit_value_with_none(index, self.item_count());
+        Ok(uv)
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        match convert_unit_to_discrete_value_with_none(value, self.item_count()) {
+            None => "<None>".to_string(),
+            Some(i) => (i + 1).to_string(),
+        }
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let item_count = self.item_count();
+        let item_index = match value.to_absolute_value()? {
+            AbsoluteValue::Continuous(v) => convert_unit_to_discrete_value_with_none(v, item_count),
+            AbsoluteValue::Discrete(f) => {
+                if f.actual() == 0 {
+                    None
+                } else {
+                    Some(f.actual() - 1)
+                }
+            }
+        };
+        let item = match item_index {
+            None => {
+                for item in self.items().into_iter().filter(|i| item_is_selected(*i)) {
+                    set_item_info_value(item, ITEM_SELECTED, 0.0)?;
+                }
+                return Ok(HitResponse::processed_with_effect());
+            }
+            Some(i) => *self.items().get(i as usize).ok_or("item not available")?,
+        };
+        select_item_exclusively_within_tracks(&self.tracks, item)?;
+        let project = self.project_internal();
+        let pos = PositionInSeconds::new(get_item_info_value(item, ITEM_POSITION));
+        if self.move_edit_cursor {
+            project.set_edit_cursor_position(
+                pos,
+                SetEditCurPosOptions {
+                    move_view: self.scroll_arrange_view,
+                    seek_play: false,
+                },
+            );
+        } else if self.scroll_arrange_view {
+            scroll_arrange_view_to_position(project, pos);
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.tracks.iter().all(|t| t.is_available())
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.project_internal())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        self.tracks.first()
+    }
+
+    fn item(&self) -> Option<Item> {
+        match self.current_item()? {
+            CurrentItem::Exact { item, .. } => Some(item),
+            CurrentItem::Between { .. } => None,
+        }
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        true
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Additional(AdditionalFeedbackEvent::ItemsChanged(e))
+                if e.project == self.project_internal() =>
+            {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        match self.current_item()? {
+            CurrentItem::Exact { item, .. } => Some(get_item_name(item)?.into()),
+            CurrentItem::Between {
+                floor_index: Some(i),
+            } => {
+                let floor_item = *self.items().get(i as usize)?;
+                Some(format!("After {}", get_item_name(floor_item)?).into())
+            }
+            CurrentItem::Between { floor_index: None } => None,
+        }
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        match self.current_item()? {
+            CurrentItem::Exact { index, .. } => Some(NumericValue::Discrete(index as i32 + 1)),
+            CurrentItem::Between { floor_index } => {
+                let floor_value = floor_index.map(|i| i as f64 + 1.0).unwrap_or(0.0);
+                Some(NumericValue::Decimal(floor_value + 0.5))
+            }
+        }
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::BrowseItems)
+    }
+}
+
+impl<'a> Target<'a> for BrowseItemsTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        match self.current_item() {
+            None => Some(self.percentage_for(None)),
+            Some(CurrentItem::Exact { index, .. }) => Some(self.percentage_for(Some(index))),
+            Some(CurrentItem::Between { floor_index }) => {
+                let floor_percentage = self.percentage_for(floor_index);
+                // Add half of the atomic step size to indicate that it's inbetween two values!
+                let inbetween_percentage =
+                    floor_percentage.to_unit_value().get() + self.step_size().get() / 2.0;
+                UnitValue::try_new(inbetween_percentage).map(AbsoluteValue::Continuous)
+            }
+        }
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+impl BrowseItemsTarget {
+    fn project_internal(&self) -> Project {
+        self.tracks[0].project()
+    }
+
+    /// Returns the items of all tracks, ordered by position (on the same position, by track).
+    fn items(&self) -> Vec<Item> {
+        let mut items: Vec<_> = self.tracks.iter().flat_map(|t| t.items()).collect();
+        // Stable sort, so the track order is kept for items on the same position.
+        items.sort_by(|a, b| {
+            let a_pos = get_item_info_value(*a, ITEM_POSITION);
+            let b_pos = get_item_info_value(*b, ITEM_POSITION);
+            a_pos.total_cmp(&b_pos)
+        });
+        items
+    }
+
+    fn item_count(&self) -> u32 {
+        self.tracks.iter().map(|t| t.items().count() as u32).sum()
+    }
+
+    fn step_size(&self) -> UnitValue {
+        // `+ 1` because "<None>" is also a possible value.
+        convert_count_to_step_size(self.item_count() + 1)
+    }
+
+    fn percentage_for(&self, item_index: Option<u32>) -> AbsoluteValue {
+        // Because we count "<None>" as a possible value, this is equal.
+        let max_value = self.item_count();
+        let actual_value = item_index.map(|i| i + 1).unwrap_or(0);
+        AbsoluteValue::Discrete(Fraction::new(actual_value, max_value))
+    }
+
+    /// Determines the item from which browsing starts.
+    ///
+    /// The first selected item on the tracks has priority. If there's none, the position of the
+    /// edit cursor counts. Returns `None` if the tracks don't have any items.
+    fn current_item(&self) -> Option<CurrentItem> {
+        let items = self.items();
+        if items.is_empty() {
+            return None;
+        }
+        if let Some((i, item)) = items
+            .iter()
+            .enumerate()
+            .find(|(_, i)| item_is_selected(**i))
+        {
+            return Some(CurrentItem::Exact {
+                index: i as u32,
+                item: *item,
+            });
+        }
+        let edit_cursor_pos = self.project_internal().edit_cursor_position();
+        let item_at_edit_cursor = self
+            .tracks
+            .iter()
+            .filter_map(|t| get_item_at_position(t, edit_cursor_pos))
+            .filter_map(|item| Some((items.iter().position(|i| *i == item)?, item)))
+            .min_by_key(|(index, _)| *index);
+        if let Some((index, item)) = item_at_edit_cursor {
+            return Some(CurrentItem::Exact {
+                index: index as u32,
+                item,
+            });
+        }
+        let floor_index = items
+            .iter()
+            .rposition(|i| get_item_info_value(*i, ITEM_POSITION) < edit_cursor_pos.get());
+        Some(CurrentItem::Between {
+            floor_index: floor_index.map(|i| i as u32),
+        })
+    }
+}
+
+enum CurrentItem {
+    /// The item is selected or spans the edit cursor.
+    Exact { index: u32, item: Item },
+    /// No item is selected and the edit cursor is located between items.
+    Between {
+        /// The edit cursor comes after this item (`None` means it's before the first item).
+        floor_index: Option<u32>,
+    },
+}
+
+pub const BROWSE_ITEMS_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Track: Browse items",
+    short_name: "Browse items",
+    hint: "Selects previous/next item on the track(s)",
+    supports_track: true,
+    if_so_supports_track_must_be_selected: false,
+    ..DEFAULT_TARGET
+};
@@ -1,9 +1,9 @@
 use crate::domain::{
     format_value_as_on_off, Compartment, CompoundChangeEvent, ControlContext, DomainEvent,
     Exclusivity, ExtendedProcessorContext, HitInstruction, HitInstructionContext,
-    HitInstructionResponse, HitResponse, InstanceStateChanged, MappingControlContext, MappingData,
-    MappingEnabledChangeRequestedEvent, RealearnTarget, ReaperTarget, ReaperTargetType, TagScope,
-    TargetCharacter, TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET,
+    HitInstructionResponse, HitResponse, InstanceStateChanged, MappingControlContext,
+    MappingEnabledChangeRequestedEvent, MappingId, RealearnTarget, ReaperTarget, ReaperTargetType,
+    TagScope, TargetCharacter, TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET,
 };
 use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
 use std::borrow::Cow;
@@ -54,75 +54,12 @@ impl RealearnTarget for EnableMappingsTarget {
     ) -> Result<HitResponse, &'static str> {
         let value = value.to_unit_value()?;
         let is_enable = !value.is_zero();
-        struct EnableMappingsInstruction {
-            compartment: Compartment,
-            scope: TagScope,
-            mapping_data: MappingData,
-            is_enable: bool,
-            exclusivity: Exclusivity,
-        }
-        impl HitInstruction for EnableMappingsInstruction {
-            fn execute(self: Box<Self>, context: HitInstructionContext) -> HitInstructionResponse {
-                let mut activated_inverse_tags = HashSet::new();
-                for m in context.mappings.values_mut() {
-                    // Don't touch ourselves.
-                    if m.id() == self.mapping_data.mapping_id {
-                        continue;
-                    }
-                    // Determine how to change the mappings.
-                    let flag = match self.scope.determine_enable_disable_change(
-                        self.exclusivity,
-                        m.tags(),
-                        self.is_enable,
-                    ) {
-                        None => continue,
-                        Some(f) => f,
-                    };
-                    if self.exclusivity == Exclusivity::Exclusive && !self.is_enable {
-                        // Collect all *other* mapping tags because they are going to be activated
-                        // and we have to know about them!
-                        activated_inverse_tags.extend(m.tags().iter().cloned());
-                    }
-                    // Finally request change of mapping enabled state!
-                    context.domain_event_handler.handle_event_ignoring_error(
-                        DomainEvent::MappingEnabledChangeRequested(
-                            MappingEnabledChangeRequestedEvent {
-                                compartment: m.compartment(),
-                                mapping_id: m.id(),
-                                is_enabled: if self.is_enable { flag } else { !flag },
-                            },
-                        ),
-                    );
-                }
-                let mut instance_state = context.control_context.instance_state.borrow_mut();
-                use Exclusivity::*;
-                if self.exclusivity == Exclusive
-                    || (self.exclusivity == ExclusiveOnOnly && self.is_enable)
-                {
-                    // Completely replace
-                    let new_active_tags = if self.is_enable {
-                        self.scope.tags.clone()
-                    } else {
-                        activated_inverse_tags
-                    };
-                    instance_state.set_active_mapping_tags(self.compartment, new_active_tags);
-                } else {
-                    // Add or remove
-                    instance_state.activate_or_deactivate_mapping_tags(
-                        self.compartment,
-                        &self.scope.tags,
-                        self.is_enable,
-                    );
-                }
-                HitInstructionResponse::CausedEffect(vec![])
-            }
-        }
         let instruction = EnableMappingsInstruction {
             compartment: self.compartment,
             // So far this clone is okay because enabling/disable mappings is not something that
             // happens every few milliseconds. No need to use a ref to this target.
             scope: self.scope.clone(),
-            mapping_data: context.mapping_data,
+            initiator: Some(context.mapping_data.mapping_id),
             is_enable,
             exclusivity: self.exclusivity,
         };
@@ -156,6 +93,70 @@ impl RealearnTarget for EnableMappingsTarget {
     }
 }
 
+/// Enables or disables all mappings in a compartment which match the given tag scope.
+pub struct EnableMappingsInstruction {
+    pub compartment: Compartment,
+    pub scope: TagScope,
+    /// The mapping which caused this instruction (if any). It's left untouched.
+    pub initiator: Option<MappingId>,
+    pub is_enable: bool,
+    pub exclusivity: Exclusivity,
+}
+
+impl HitInstruction for EnableMappingsInstruction {
+    fn execute(self: Box<Self>, context: HitInstructionContext) -> HitInstructionResponse {
+        let mut activated_inverse_tags = HashSet::new();
+        for m in context.mappings.values_mut() {
+            // Don't touch ourselves.
+            if Some(m.id()) == self.initiator {
+                continue;
+            }
+            // Determine how to change the mappings.
+            let flag = match self.scope.determine_enable_disable_change(
+                self.exclusivity,
+                m.tags(),
+                self.is_enable,
+            ) {
+                None => continue,
+                Some(f) => f,
+            };
+            if self.exclusivity == Exclusivity::Exclusive && !self.is_enable {
+                // Collect all *other* mapping tags because they are going to be activated
+                // and we have to know about them!
+                activated_inverse_tags.extend(m.tags().iter().cloned());
+            }
+            // Finally request change of mapping enabled state!
+            context.domain_event_handler.handle_event_ignoring_error(
+                DomainEvent::MappingEnabledChangeRequested(MappingEnabledChangeRequestedEvent {
+                    compartment: m.compartment(),
+                    mapping_id: m.id(),
+                    is_enabled: if self.is_enable { flag } else { !flag },
+                }),
+            );
+        }
+        let mut instance_state = context.control_context.instance_state.borrow_mut();
+        use Exclusivity::*;
+        if self.exclusivity == Exclusive || (self.exclusivity == ExclusiveOnOnly && self.is_enable)
+        {
+            // Completely replace
+            let new_active_tags = if self.is_enable {
+                self.scope.tags.clone()
+            } else {
+                activated_inverse_tags
+            };
+            instance_state.set_active_mapping_tags(self.compartment, new_active_tags);
+        } else {
+            // Add or remove
+            instance_state.activate_or_deactivate_mapping_tags(
+                self.compartment,
+                &self.scope.tags,
+                self.is_enable,
+            );
+        }
+        HitInstructionResponse::CausedEffect(vec![])
+    }
+}
+
 impl<'a> Target<'a> for EnableMappingsTarget {
     type Context = ControlContext<'a>;
 
@@ -0,0 +1,135 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    format_value_as_on_off, get_envelope_flag, set_envelope_flag, Compartment, ControlContext,
+    EnvelopeDescriptor, ExtendedProcessorContext, FeedbackResolution, FxParameterDescriptor,
+    HitResponse, MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType,
+    ResolvedEnvelope, TargetCharacter, TargetTypeDef, TrackDescriptor, TrackRouteDescriptor,
+    UnresolvedReaperTargetDef, AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET, ENVELOPE_ARM,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use reaper_high::{Fx, Project, Track, TrackRoute};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedEnvelopeArmTarget {
+    pub descriptor: EnvelopeDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedEnvelopeArmTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(self
+            .descriptor
+            .resolve(context, compartment)?
+            .into_iter()
+            .map(|envelope| ReaperTarget::EnvelopeArm(EnvelopeArmTarget { envelope }))
+            .collect())
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // REAPER doesn't notify us about changes of the envelope arm state.
+        Some(FeedbackResolution::High)
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        self.descriptor.track_descriptor()
+    }
+
+    fn route_descriptor(&self) -> Option<&TrackRouteDescriptor> {
+        self.descriptor.route_descriptor()
+    }
+
+    fn fx_parameter_descriptor(&self) -> Option<&FxParameterDescriptor> {
+        self.descriptor.fx_parameter_descriptor()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnvelopeArmTarget {
+    pub envelope: ResolvedEnvelope,
+}
+
+impl RealearnTarget for EnvelopeArmTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::AbsoluteContinuous, TargetCharacter::Switch)
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let arm = !value.to_unit_value()?.is_zero();
+        // Arming a parameter which doesn't have an envelope yet creates one (if possible).
+        let envelope = self
+            .envelope
+            .envelope(arm)
+            .ok_or("envelope doesn't exist")?;
+        set_envelope_flag(envelope, ENVELOPE_ARM, arm)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.envelope.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        self.envelope.project()
+    }
+
+    fn track(&self) -> Option<&Track> {
+        self.envelope.track()
+    }
+
+    fn fx(&self) -> Option<&Fx> {
+        self.envelope.fx()
+    }
+
+    fn route(&self) -> Option<&TrackRoute> {
+        self.envelope.route()
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::EnvelopeArm)
+    }
+}
+
+impl<'a> Target<'a> for EnvelopeArmTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let is_armed = self
+            .envelope
+            .envelope(false)
+            .map(|e| get_envelope_flag(e, ENVELOPE_ARM))
+            .unwrap_or(false);
+        Some(AbsoluteValue::Continuous(convert_bool_to_unit_value(
+            is_armed,
+        )))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ENVELOPE_ARM_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Envelope: Arm/disarm",
+    short_name: "(Dis)arm envelope",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_track: true,
+    if_so_supports_track_must_be_selected: false,
+    supports_envelope: true,
+    ..DEFAULT_TARGET
+};
@@ -0,0 +1,127 @@
+use crate::domain::{
+    insert_envelope_point, Compartment, ControlContext, EnvelopeDescriptor,
+    ExtendedProcessorContext, FxParameterDescriptor, HitResponse, MappingControlContext,
+    RealearnTarget, ReaperTarget, ReaperTargetType, ResolvedEnvelope, TargetCharacter,
+    TargetTypeDef, TrackDescriptor, TrackRouteDescriptor, UnresolvedReaperTargetDef,
+    DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target};
+use reaper_high::{Fx, Project, Track, TrackRoute};
+
+#[derive(Debug)]
+pub struct UnresolvedEnvelopeWriteValueTarget {
+    pub descriptor: EnvelopeDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedEnvelopeWriteValueTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(self
+            .descriptor
+            .resolve(context, compartment)?
+            .into_iter()
+            .map(|envelope| ReaperTarget::EnvelopeWriteValue(EnvelopeWriteValueTarget { envelope }))
+            .collect())
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        self.descriptor.track_descriptor()
+    }
+
+    fn route_descriptor(&self) -> Option<&TrackRouteDescriptor> {
+        self.descriptor.route_descriptor()
+    }
+
+    fn fx_parameter_descriptor(&self) -> Option<&FxParameterDescriptor> {
+        self.descriptor.fx_parameter_descriptor()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnvelopeWriteValueTarget {
+    pub envelope: ResolvedEnvelope,
+}
+
+impl RealearnTarget for EnvelopeWriteValueTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Trigger,
+        )
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        if value.to_unit_value()?.is_zero() {
+            return Ok(HitResponse::ignored());
+        }
+        let envelope = self
+            .envelope
+            .envelope(true)
+            .ok_or("envelope doesn't exist")?;
+        let project = self.envelope.project().ok_or("project not available")?;
+        let pos = project.play_or_edit_cursor_position();
+        let value = self
+            .envelope
+            .current_parameter_value(envelope)
+            .ok_or("couldn't determine current parameter value")?;
+        insert_envelope_point(envelope, pos, value)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.envelope.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        self.envelope.project()
+    }
+
+    fn track(&self) -> Option<&Track> {
+        self.envelope.track()
+    }
+
+    fn fx(&self) -> Option<&Fx> {
+        self.envelope.fx()
+    }
+
+    fn route(&self) -> Option<&TrackRoute> {
+        self.envelope.route()
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::EnvelopeWriteValue)
+    }
+
+    fn can_report_current_value(&self) -> bool {
+        false
+    }
+}
+
+impl<'a> Target<'a> for EnvelopeWriteValueTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        None
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ENVELOPE_WRITE_VALUE_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Envelope: Write current value at cursor",
+    short_name: "Write envelope point",
+    hint: "Inserts an envelope point at the play/edit cursor",
+    supports_track: true,
+    if_so_supports_track_must_be_selected: false,
+    supports_envelope: true,
+    ..DEFAULT_TARGET
+};
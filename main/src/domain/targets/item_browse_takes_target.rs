@@ -0,0 +1,209 @@
+use crate::domain::{
+    convert_count_to_step_size, convert_discrete_to_unit_value, convert_unit_to_discrete_value,
+    get_item_info_value, get_item_take_count, get_items, get_take_name, item_is_available,
+    set_item_info_value, AdditionalFeedbackEvent, Compartment, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, ItemDescriptor,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET, ITEM_ACTIVE_TAKE_INDEX,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, Target, UnitValue,
+};
+use reaper_high::{Item, Project, Track};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedItemBrowseTakesTarget {
+    pub descriptor: ItemDescriptor,
+    pub poll_for_feedback: bool,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedItemBrowseTakesTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let items = get_items(context, &self.descriptor, compartment)?;
+        let targets = items
+            .into_iter()
+            .map(|(track, item)| {
+                ReaperTarget::ItemBrowseTakes(ItemBrowseTakesTarget {
+                    track,
+                    item,
+                    poll_for_feedback: self.poll_for_feedback,
+                })
+            })
+            .collect();
+        Ok(targets)
+    }
+
+    fn item_descriptor(&self) -> Option<&ItemDescriptor> {
+        Some(&self.descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        if self.poll_for_feedback {
+            Some(FeedbackResolution::High)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ItemBrowseTakesTarget {
+    pub track: Track,
+    pub item: Item,
+    pub poll_for_feedback: bool,
+}
+
+impl RealearnTarget for ItemBrowseTakesTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteDiscrete {
+                atomic_step_size: convert_count_to_step_size(self.take_count()),
+                is_retriggerable: false,
+            },
+            TargetCharacter::Discrete,
+        )
+    }
+
+    fn parse_as_value(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn parse_as_step_size(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn convert_unit_value_to_discrete_value(
+        &self,
+        input: UnitValue,
+        _: ControlContext,
+    ) -> Result<u32, &'static str> {
+        Ok(convert_unit_to_discrete_value(input, self.take_count()))
+    }
+
+    fn convert_discrete_value_to_unit_value(
+        &self,
+        value: u32,
+        _: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        Ok(convert_discrete_to_unit_value(value, self.take_count()))
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        (convert_unit_to_discrete_value(value, self.take_count()) + 1).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let take_count = self.take_count();
+        if take_count == 0 {
+            return Err("item doesn't have any takes");
+        }
+        let take_index = match value.to_absolute_value()? {
+            AbsoluteValue::Continuous(v) => convert_unit_to_discrete_value(v, take_count),
+            AbsoluteValue::Discrete(f) => f.actual(),
+        };
+        let take_index = take_index.min(take_count - 1);
+        set_item_info_value(self.item, ITEM_ACTIVE_TAKE_INDEX, take_index as f64)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        item_is_available(self.track.project(), self.item)
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn item(&self) -> Option<Item> {
+        Some(self.item)
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        true
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Additional(AdditionalFeedbackEvent::ItemsChanged(e))
+                if e.project == self.track.project() =>
+            {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        let take = self.item.active_take()?;
+        Some(get_take_name(take)?.into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        Some(NumericValue::Discrete(self.active_take_index() as i32 + 1))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::ItemBrowseTakes)
+    }
+}
+
+impl ItemBrowseTakesTarget {
+    fn take_count(&self) -> u32 {
+        get_item_take_count(self.item)
+    }
+
+    fn active_take_index(&self) -> u32 {
+        get_item_info_value(self.item, ITEM_ACTIVE_TAKE_INDEX).max(0.0) as u32
+    }
+}
+
+impl<'a> Target<'a> for ItemBrowseTakesTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let take_count = self.take_count();
+        if take_count == 0 {
+            return None;
+        }
+        let fraction = Fraction::new(self.active_take_index(), take_count - 1);
+        Some(AbsoluteValue::Discrete(fraction))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ITEM_BROWSE_TAKES_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Item: Browse takes",
+    short_name: "Browse takes",
+    supports_track: true,
+    supports_item: true,
+    supports_poll_for_feedback: true,
+    ..DEFAULT_TARGET
+};
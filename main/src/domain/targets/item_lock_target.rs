@@ -0,0 +1,147 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    format_value_as_on_off, get_item_info_value, get_items, item_is_available, set_item_info_value,
+    AdditionalFeedbackEvent, Compartment, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, ItemDescriptor,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET, ITEM_LOCK,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use reaper_high::{Item, Project, Track};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedItemLockTarget {
+    pub descriptor: ItemDescriptor,
+    pub poll_for_feedback: bool,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedItemLockTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let items = get_items(context, &self.descriptor, compartment)?;
+        let targets = items
+            .into_iter()
+            .map(|(track, item)| {
+                ReaperTarget::ItemLock(ItemLockTarget {
+                    track,
+                    item,
+                    poll_for_feedback: self.poll_for_feedback,
+                })
+            })
+            .collect();
+        Ok(targets)
+    }
+
+    fn item_descriptor(&self) -> Option<&ItemDescriptor> {
+        Some(&self.descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        if self.poll_for_feedback {
+            Some(FeedbackResolution::High)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ItemLockTarget {
+    pub track: Track,
+    pub item: Item,
+    pub poll_for_feedback: bool,
+}
+
+impl RealearnTarget for ItemLockTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::AbsoluteContinuous, TargetCharacter::Switch)
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let lock = !value.to_unit_value()?.is_zero();
+        // Bit 0 is the "locked" flag, leave the other bits alone.
+        let flags = get_item_info_value(self.item, ITEM_LOCK) as u8;
+        let new_flags = if lock { flags | 1 } else { flags & !1 };
+        set_item_info_value(self.item, ITEM_LOCK, new_flags as f64)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        item_is_available(self.track.project(), self.item)
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn item(&self) -> Option<Item> {
+        Some(self.item)
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        true
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Additional(AdditionalFeedbackEvent::ItemsChanged(e))
+                if e.project == self.track.project() =>
+            {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::ItemLock)
+    }
+}
+
+impl<'a> Target<'a> for ItemLockTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let is_locked = (get_item_info_value(self.item, ITEM_LOCK) as u8 & 1) != 0;
+        Some(AbsoluteValue::Continuous(convert_bool_to_unit_value(
+            is_locked,
+        )))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ITEM_LOCK_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Item: Lock/unlock",
+    short_name: "(Un)lock item",
+    supports_track: true,
+    supports_item: true,
+    supports_poll_for_feedback: true,
+    ..DEFAULT_TARGET
+};
@@ -0,0 +1,127 @@
+use crate::domain::{
+    convert_quarter_notes_to_time, convert_time_to_quarter_notes, get_item_info_value, get_items,
+    item_is_available, set_item_info_value, Compartment, ControlContext, ExtendedProcessorContext,
+    HitResponse, ItemDescriptor, MappingControlContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, TargetCharacter, TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET,
+    ITEM_POSITION,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target};
+use reaper_high::{Item, Project, Track};
+use reaper_medium::PositionInSeconds;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedItemPositionTarget {
+    pub descriptor: ItemDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedItemPositionTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let items = get_items(context, &self.descriptor, compartment)?;
+        let targets = items
+            .into_iter()
+            .map(|(track, item)| ReaperTarget::ItemPosition(ItemPositionTarget { track, item }))
+            .collect();
+        Ok(targets)
+    }
+
+    fn item_descriptor(&self) -> Option<&ItemDescriptor> {
+        Some(&self.descriptor)
+    }
+}
+
+/// Nudges the item position by beats (quarter notes), so it stays in sync with the tempo map.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ItemPositionTarget {
+    pub track: Track,
+    pub item: Item,
+}
+
+impl RealearnTarget for ItemPositionTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::Relative, TargetCharacter::Discrete)
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let beats = match value {
+            ControlValue::RelativeContinuous(v) => v.to_discrete_increment().get(),
+            ControlValue::RelativeDiscrete(v) => v.get(),
+            _ => return Err("needs to be controlled relatively"),
+        };
+        let project = self.track.project();
+        let current_pos = self.position();
+        let current_quarter_notes = convert_time_to_quarter_notes(project, current_pos);
+        let new_pos = convert_quarter_notes_to_time(project, current_quarter_notes + beats as f64);
+        let new_pos = new_pos.get().max(0.0);
+        set_item_info_value(self.item, ITEM_POSITION, new_pos)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        item_is_available(self.track.project(), self.item)
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn item(&self) -> Option<Item> {
+        Some(self.item)
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        false
+    }
+
+    fn can_report_current_value(&self) -> bool {
+        false
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format!("{:.3} s", self.position().get()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::ItemPosition)
+    }
+}
+
+impl ItemPositionTarget {
+    fn position(&self) -> PositionInSeconds {
+        PositionInSeconds::new(get_item_info_value(self.item, ITEM_POSITION))
+    }
+}
+
+impl<'a> Target<'a> for ItemPositionTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        None
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ITEM_POSITION_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Item: Nudge position",
+    short_name: "Nudge item",
+    hint: "Moves the item by beats",
+    supports_track: true,
+    supports_item: true,
+    supports_feedback: false,
+    ..DEFAULT_TARGET
+};
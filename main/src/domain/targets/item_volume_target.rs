@@ -0,0 +1,177 @@
+use crate::domain::ui_util::{
+    format_value_as_db, format_value_as_db_without_unit, parse_value_from_db, volume_unit_value,
+};
+use crate::domain::{
+    get_item_info_value, get_items, item_is_available, set_item_info_value,
+    AdditionalFeedbackEvent, Compartment, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, ItemDescriptor,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET, ITEM_VOLUME,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, NumericValue, Target, UnitValue};
+use reaper_high::{Item, Project, Track, Volume};
+use reaper_medium::ReaperVolumeValue;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedItemVolumeTarget {
+    pub descriptor: ItemDescriptor,
+    pub poll_for_feedback: bool,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedItemVolumeTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let items = get_items(context, &self.descriptor, compartment)?;
+        let targets = items
+            .into_iter()
+            .map(|(track, item)| {
+                ReaperTarget::ItemVolume(ItemVolumeTarget {
+                    track,
+                    item,
+                    poll_for_feedback: self.poll_for_feedback,
+                })
+            })
+            .collect();
+        Ok(targets)
+    }
+
+    fn item_descriptor(&self) -> Option<&ItemDescriptor> {
+        Some(&self.descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        if self.poll_for_feedback {
+            Some(FeedbackResolution::High)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ItemVolumeTarget {
+    pub track: Track,
+    pub item: Item,
+    pub poll_for_feedback: bool,
+}
+
+impl RealearnTarget for ItemVolumeTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::AbsoluteContinuous, TargetCharacter::Continuous)
+    }
+
+    fn parse_as_value(&self, text: &str, _: ControlContext) -> Result<UnitValue, &'static str> {
+        parse_value_from_db(text)
+    }
+
+    fn format_value_without_unit(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_db_without_unit(value)
+    }
+
+    fn hide_formatted_value(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn hide_formatted_step_size(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn value_unit(&self, _: ControlContext) -> &'static str {
+        "dB"
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_db(value)
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let volume = Volume::try_from_soft_normalized_value(value.to_unit_value()?.get())
+            .unwrap_or(Volume::MIN);
+        set_item_info_value(self.item, ITEM_VOLUME, volume.reaper_value().get())?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        item_is_available(self.track.project(), self.item)
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn item(&self) -> Option<Item> {
+        Some(self.item)
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        true
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Additional(AdditionalFeedbackEvent::ItemsChanged(e))
+                if e.project == self.track.project() =>
+            {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(self.volume().to_string().into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        Some(NumericValue::Decimal(self.volume().db().get()))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::ItemVolume)
+    }
+}
+
+impl ItemVolumeTarget {
+    fn volume(&self) -> Volume {
+        let value = get_item_info_value(self.item, ITEM_VOLUME);
+        Volume::from_reaper_value(ReaperVolumeValue::new(value))
+    }
+}
+
+impl<'a> Target<'a> for ItemVolumeTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = volume_unit_value(self.volume());
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ITEM_VOLUME_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Item: Set volume",
+    short_name: "Item volume",
+    supports_track: true,
+    supports_item: true,
+    supports_poll_for_feedback: true,
+    ..DEFAULT_TARGET
+};
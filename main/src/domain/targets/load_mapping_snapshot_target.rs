@@ -127,7 +127,7 @@ impl RealearnTarget for LoadMappingSnapshotTarget {
             compartment: self.compartment,
             scope: self.scope.clone(),
             active_mappings_only: self.active_mappings_only,
-            snapshot: self.snapshot_id.clone(),
+            snapshot_id: self.snapshot_id.clone(),
             default_value: self.default_value,
         };
         Ok(HitResponse::hit_instruction(Box::new(instruction)))
@@ -178,12 +178,14 @@ pub const LOAD_MAPPING_SNAPSHOT_TARGET: TargetTypeDef = TargetTypeDef {
     ..DEFAULT_TARGET
 };
 
-struct LoadMappingSnapshotInstruction {
-    compartment: Compartment,
-    scope: TagScope,
-    active_mappings_only: bool,
-    snapshot: VirtualMappingSnapshotIdForLoad,
-    default_value: Option<AbsoluteValue>,
+/// Loads the given mapping snapshot into all mappings in a compartment which match the given
+/// tag scope.
+pub struct LoadMappingSnapshotInstruction {
+    pub compartment: Compartment,
+    pub scope: TagScope,
+    pub active_mappings_only: bool,
+    pub snapshot_id: VirtualMappingSnapshotIdForLoad,
+    pub default_value: Option<AbsoluteValue>,
 }
 
 impl LoadMappingSnapshotInstruction {
@@ -241,13 +243,13 @@ impl LoadMappingSnapshotInstruction {
     }
 
     fn mark_snapshot_as_active(&self, instance_state: &mut InstanceState) {
-        instance_state.mark_snapshot_active(self.compartment, &self.scope, &self.snapshot);
+        instance_state.mark_snapshot_active(self.compartment, &self.scope, &self.snapshot_id);
     }
 }
 
 impl HitInstruction for LoadMappingSnapshotInstruction {
     fn execute(self: Box<Self>, mut context: HitInstructionContext) -> HitInstructionResponse {
-        let results = match &self.snapshot {
+        let results = match &self.snapshot_id {
             VirtualMappingSnapshotIdForLoad::Initial => {
                 self.load_snapshot(&mut context, |m| m.initial_target_value())
             }
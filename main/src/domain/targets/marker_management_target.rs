@@ -0,0 +1,175 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    add_marker, add_region, delete_bookmark, find_nearest_marker, format_value_as_on_off,
+    rename_bookmark, AdditionalFeedbackEvent, Compartment, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, MappingControlContext,
+    RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter, TargetTypeDef,
+    UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, PropValue, RgbColor, Target, UnitValue,
+};
+use realearn_api::persistence::MarkerManagementAction;
+use reaper_high::{ChangeEvent, Project, Reaper};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedMarkerManagementTarget {
+    pub action: MarkerManagementAction,
+    pub name: String,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedMarkerManagementTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        _: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(vec![ReaperTarget::MarkerManagement(
+            MarkerManagementTarget {
+                project: context.context().project_or_current_project(),
+                action: self.action,
+                name: self.name.clone(),
+            },
+        )])
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        Some(FeedbackResolution::Beat)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarkerManagementTarget {
+    pub project: Project,
+    pub action: MarkerManagementAction,
+    pub name: String,
+}
+
+impl RealearnTarget for MarkerManagementTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Trigger,
+        )
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        context: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        if value.to_unit_value()?.is_zero() {
+            return Ok(HitResponse::ignored());
+        }
+        let pos = self.project.play_or_edit_cursor_position();
+        match self.action {
+            MarkerManagementAction::AddMarker => {
+                add_marker(self.project, pos, &self.name)?;
+            }
+            MarkerManagementAction::RenameNearestMarker => {
+                let (_, info) =
+                    find_nearest_marker(self.project, pos).ok_or("project has no markers")?;
+                // Prefer the text sent by the controller, e.g. an OSC string argument
+                let name = context.incoming_text.unwrap_or(&self.name);
+                rename_bookmark(self.project, &info, name)?;
+            }
+            MarkerManagementAction::DeleteNearestMarker => {
+                let (index, _) =
+                    find_nearest_marker(self.project, pos).ok_or("project has no markers")?;
+                delete_bookmark(self.project, index)?;
+            }
+            MarkerManagementAction::CreateRegionFromTimeSelection => {
+                let range = self
+                    .project
+                    .time_selection()
+                    .ok_or("project has no time selection")?;
+                add_region(self.project, range.start, range.end, &self.name)?;
+            }
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.project.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.project)
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        use CompoundChangeEvent::*;
+        match evt {
+            Reaper(ChangeEvent::BookmarksChanged(e)) if e.project == self.project => (true, None),
+            Additional(AdditionalFeedbackEvent::BeatChanged(e)) if e.project == self.project => {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        let index = self.current_bookmark_index()?;
+        let bookmark = self.project.find_bookmark_by_index(index)?;
+        Some(bookmark.name().into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::MarkerManagement)
+    }
+
+    fn prop_value(&self, key: &str, _: ControlContext) -> Option<PropValue> {
+        let index = self.current_bookmark_index()?;
+        let bookmark = self.project.find_bookmark_by_index(index)?;
+        match key {
+            "bookmark.color" => {
+                let reaper_medium::RgbColor { r, g, b } = Reaper::get()
+                    .medium_reaper()
+                    .color_from_native(bookmark.basic_info().color);
+                Some(PropValue::Color(RgbColor::new(r, g, b)))
+            }
+            "bookmark.index" => Some(PropValue::Index(index)),
+            "bookmark.name" => Some(PropValue::Text(bookmark.name().into())),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Target<'a> for MarkerManagementTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let value = convert_bool_to_unit_value(self.current_bookmark_index().is_some());
+        Some(AbsoluteValue::Continuous(value))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+impl MarkerManagementTarget {
+    /// Returns the index of the region at the play/edit cursor or, if there's none, the index of
+    /// the current marker.
+    fn current_bookmark_index(&self) -> Option<u32> {
+        let pos = self.project.play_or_edit_cursor_position();
+        let current = self.project.current_bookmark_at(pos);
+        current.region_index.or(current.marker_index)
+    }
+}
+
+pub const MARKER_MANAGEMENT_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Marker/region: Manage",
+    short_name: "Manage markers",
+    hint: "Adds, renames or deletes markers/regions",
+    ..DEFAULT_TARGET
+};
@@ -67,6 +67,9 @@ pub use load_fx_snapshot_target::*;
 mod browse_tracks_target;
 pub use browse_tracks_target::*;
 
+mod browse_items_target;
+pub use browse_items_target::*;
+
 mod browse_fxs_target;
 pub use browse_fxs_target::*;
 
@@ -82,6 +85,12 @@ pub use track_touch_state_target::*;
 mod go_to_bookmark_target;
 pub use go_to_bookmark_target::*;
 
+mod marker_management_target;
+pub use marker_management_target::*;
+
+mod time_range_target;
+pub use time_range_target::*;
+
 mod seek_target;
 pub use seek_target::*;
 
@@ -183,3 +192,45 @@ pub use preview_pot_preset_target::*;
 
 mod load_pot_preset_target;
 pub use load_pot_preset_target::*;
+
+mod item_volume_target;
+pub use item_volume_target::*;
+
+mod item_mute_target;
+pub use item_mute_target::*;
+
+mod item_lock_target;
+pub use item_lock_target::*;
+
+mod item_selection_target;
+pub use item_selection_target::*;
+
+mod item_position_target;
+pub use item_position_target::*;
+
+mod item_browse_takes_target;
+pub use item_browse_takes_target::*;
+
+mod take_volume_target;
+pub use take_volume_target::*;
+
+mod take_pitch_target;
+pub use take_pitch_target::*;
+
+mod take_playrate_target;
+pub use take_playrate_target::*;
+
+mod take_fx_parameter_target;
+pub use take_fx_parameter_target::*;
+
+mod envelope_arm_target;
+pub use envelope_arm_target::*;
+
+mod envelope_show_target;
+pub use envelope_show_target::*;
+
+mod envelope_write_value_target;
+pub use envelope_write_value_target::*;
+
+mod browse_automation_items_target;
+pub use browse_automation_items_target::*;
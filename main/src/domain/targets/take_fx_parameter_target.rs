@@ -0,0 +1,186 @@
+use crate::domain::{
+    format_take_fx_param_value, get_items, get_take_fx_count, get_take_fx_param_count,
+    get_take_fx_param_value, item_is_available, set_take_fx_param_value, AdditionalFeedbackEvent,
+    Compartment, CompoundChangeEvent, ControlContext, ExtendedProcessorContext, FeedbackResolution,
+    HitResponse, ItemDescriptor, MappingControlContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, TargetCharacter, TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, NumericValue, Target, UnitValue};
+use reaper_high::{Item, Project, Take, Track};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedTakeFxParameterTarget {
+    pub descriptor: ItemDescriptor,
+    pub fx_index: u32,
+    pub param_index: u32,
+    pub poll_for_feedback: bool,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTakeFxParameterTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let items = get_items(context, &self.descriptor, compartment)?;
+        let targets = items
+            .into_iter()
+            .map(|(track, item)| {
+                ReaperTarget::TakeFxParameter(TakeFxParameterTarget {
+                    track,
+                    item,
+                    fx_index: self.fx_index,
+                    param_index: self.param_index,
+                    poll_for_feedback: self.poll_for_feedback,
+                })
+            })
+            .collect();
+        Ok(targets)
+    }
+
+    fn item_descriptor(&self) -> Option<&ItemDescriptor> {
+        Some(&self.descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        if self.poll_for_feedback {
+            Some(FeedbackResolution::High)
+        } else {
+            None
+        }
+    }
+}
+
+/// Always refers to an FX of the currently active take of the item.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TakeFxParameterTarget {
+    pub track: Track,
+    pub item: Item,
+    pub fx_index: u32,
+    pub param_index: u32,
+    pub poll_for_feedback: bool,
+}
+
+impl RealearnTarget for TakeFxParameterTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::AbsoluteContinuous, TargetCharacter::Continuous)
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        self.take()
+            .and_then(|take| {
+                format_take_fx_param_value(take, self.fx_index, self.param_index, value.get())
+            })
+            .unwrap_or_default()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let take = self.take().ok_or("take FX parameter not available")?;
+        set_take_fx_param_value(
+            take,
+            self.fx_index,
+            self.param_index,
+            value.to_unit_value()?.get(),
+        )?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        item_is_available(self.track.project(), self.item) && self.take().is_some()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn item(&self) -> Option<Item> {
+        Some(self.item)
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        true
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Additional(AdditionalFeedbackEvent::ItemsChanged(e))
+                if e.project == self.track.project() =>
+            {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        let take = self.take()?;
+        let value = self.param_value()?;
+        let text = format_take_fx_param_value(take, self.fx_index, self.param_index, value)?;
+        Some(text.into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        Some(NumericValue::Decimal(self.param_value()?))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TakeFxParameter)
+    }
+}
+
+impl TakeFxParameterTarget {
+    /// Returns the active take only if it contains the FX parameter.
+    fn take(&self) -> Option<Take> {
+        let take = self.item.active_take()?;
+        if self.fx_index >= get_take_fx_count(take)
+            || self.param_index >= get_take_fx_param_count(take, self.fx_index)
+        {
+            return None;
+        }
+        Some(take)
+    }
+
+    fn param_value(&self) -> Option<f64> {
+        let take = self.take()?;
+        Some(get_take_fx_param_value(
+            take,
+            self.fx_index,
+            self.param_index,
+        ))
+    }
+}
+
+impl<'a> Target<'a> for TakeFxParameterTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = UnitValue::new_clamped(self.param_value()?);
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TAKE_FX_PARAMETER_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Take: Set FX parameter value",
+    short_name: "Take FX parameter",
+    supports_track: true,
+    supports_item: true,
+    supports_poll_for_feedback: true,
+    ..DEFAULT_TARGET
+};
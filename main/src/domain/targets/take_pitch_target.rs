@@ -0,0 +1,213 @@
+use crate::domain::{
+    get_items, get_take_info_value, item_is_available, set_take_info_value,
+    AdditionalFeedbackEvent, Compartment, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, ItemDescriptor,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET, TAKE_PITCH,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, NumericValue, Target, UnitValue};
+use reaper_high::{Item, Project, Track};
+use std::borrow::Cow;
+
+/// The pitch range which is covered by the target's unit interval (in both directions).
+const MAX_PITCH_SEMITONES: f64 = 24.0;
+
+#[derive(Debug)]
+pub struct UnresolvedTakePitchTarget {
+    pub descriptor: ItemDescriptor,
+    pub poll_for_feedback: bool,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTakePitchTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let items = get_items(context, &self.descriptor, compartment)?;
+        let targets = items
+            .into_iter()
+            .map(|(track, item)| {
+                ReaperTarget::TakePitch(TakePitchTarget {
+                    track,
+                    item,
+                    poll_for_feedback: self.poll_for_feedback,
+                })
+            })
+            .collect();
+        Ok(targets)
+    }
+
+    fn item_descriptor(&self) -> Option<&ItemDescriptor> {
+        Some(&self.descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        if self.poll_for_feedback {
+            Some(FeedbackResolution::High)
+        } else {
+            None
+        }
+    }
+}
+
+/// Always refers to the currently active take of the item.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TakePitchTarget {
+    pub track: Track,
+    pub item: Item,
+    pub poll_for_feedback: bool,
+}
+
+impl RealearnTarget for TakePitchTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRoundable {
+                // One semitone
+                rounding_step_size: UnitValue::new(1.0 / (2.0 * MAX_PITCH_SEMITONES)),
+            },
+            TargetCharacter::Continuous,
+        )
+    }
+
+    fn parse_as_value(&self, text: &str, _: ControlContext) -> Result<UnitValue, &'static str> {
+        let semitones: f64 = text.parse().map_err(|_| "not a decimal value")?;
+        Ok(convert_semitones_to_unit_value(semitones))
+    }
+
+    fn parse_as_step_size(&self, text: &str, _: ControlContext) -> Result<UnitValue, &'static str> {
+        let semitones: f64 = text.parse().map_err(|_| "not a decimal value")?;
+        let span = 2.0 * MAX_PITCH_SEMITONES;
+        if semitones < 0.0 || semitones > span {
+            return Err("not in pitch increment range");
+        }
+        Ok(UnitValue::new(semitones / span))
+    }
+
+    fn format_value_without_unit(&self, value: UnitValue, _: ControlContext) -> String {
+        format_semitones(convert_unit_value_to_semitones(value))
+    }
+
+    fn format_step_size_without_unit(&self, step_size: UnitValue, _: ControlContext) -> String {
+        format_semitones(step_size.get() * 2.0 * MAX_PITCH_SEMITONES)
+    }
+
+    fn hide_formatted_value(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn hide_formatted_step_size(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn value_unit(&self, _: ControlContext) -> &'static str {
+        "semitones"
+    }
+
+    fn step_size_unit(&self, _: ControlContext) -> &'static str {
+        "semitones"
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let take = self
+            .item
+            .active_take()
+            .ok_or("item doesn't have an active take")?;
+        let semitones = convert_unit_value_to_semitones(value.to_unit_value()?);
+        set_take_info_value(take, TAKE_PITCH, semitones)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        item_is_available(self.track.project(), self.item) && self.item.active_take().is_some()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn item(&self) -> Option<Item> {
+        Some(self.item)
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        true
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Additional(AdditionalFeedbackEvent::ItemsChanged(e))
+                if e.project == self.track.project() =>
+            {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format!("{} semitones", format_semitones(self.pitch()?)).into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        Some(NumericValue::Decimal(self.pitch()?))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TakePitch)
+    }
+}
+
+impl TakePitchTarget {
+    fn pitch(&self) -> Option<f64> {
+        let take = self.item.active_take()?;
+        Some(get_take_info_value(take, TAKE_PITCH))
+    }
+}
+
+impl<'a> Target<'a> for TakePitchTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = convert_semitones_to_unit_value(self.pitch()?);
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TAKE_PITCH_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Take: Set pitch",
+    short_name: "Take pitch",
+    hint: "Range: -24 to +24 semitones",
+    supports_track: true,
+    supports_item: true,
+    supports_poll_for_feedback: true,
+    ..DEFAULT_TARGET
+};
+
+fn convert_semitones_to_unit_value(semitones: f64) -> UnitValue {
+    UnitValue::new_clamped((semitones + MAX_PITCH_SEMITONES) / (2.0 * MAX_PITCH_SEMITONES))
+}
+
+fn convert_unit_value_to_semitones(value: UnitValue) -> f64 {
+    value.get() * 2.0 * MAX_PITCH_SEMITONES - MAX_PITCH_SEMITONES
+}
+
+fn format_semitones(semitones: f64) -> String {
+    format!("{:.2}", semitones)
+}
@@ -0,0 +1,205 @@
+use crate::domain::{
+    format_step_size_as_playback_speed_factor_without_unit,
+    format_value_as_playback_speed_factor_without_unit, get_items, get_take_info_value,
+    item_is_available, parse_step_size_from_playback_speed_factor,
+    parse_value_from_playback_speed_factor, playback_speed_factor_span, playrate_unit_value,
+    set_take_info_value, AdditionalFeedbackEvent, Compartment, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, ItemDescriptor,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET, TAKE_PLAYRATE,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, NumericValue, Target, UnitValue};
+use reaper_high::{Item, PlayRate, Project, Track};
+use reaper_medium::{NormalizedPlayRate, PlaybackSpeedFactor};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedTakePlayrateTarget {
+    pub descriptor: ItemDescriptor,
+    pub poll_for_feedback: bool,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTakePlayrateTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let items = get_items(context, &self.descriptor, compartment)?;
+        let targets = items
+            .into_iter()
+            .map(|(track, item)| {
+                ReaperTarget::TakePlayrate(TakePlayrateTarget {
+                    track,
+                    item,
+                    poll_for_feedback: self.poll_for_feedback,
+                })
+            })
+            .collect();
+        Ok(targets)
+    }
+
+    fn item_descriptor(&self) -> Option<&ItemDescriptor> {
+        Some(&self.descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        if self.poll_for_feedback {
+            Some(FeedbackResolution::High)
+        } else {
+            None
+        }
+    }
+}
+
+/// Always refers to the currently active take of the item.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TakePlayrateTarget {
+    pub track: Track,
+    pub item: Item,
+    pub poll_for_feedback: bool,
+}
+
+impl RealearnTarget for TakePlayrateTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRoundable {
+                rounding_step_size: UnitValue::new(1.0 / (playback_speed_factor_span() * 100.0)),
+            },
+            TargetCharacter::Continuous,
+        )
+    }
+
+    fn parse_as_value(&self, text: &str, _: ControlContext) -> Result<UnitValue, &'static str> {
+        parse_value_from_playback_speed_factor(text)
+    }
+
+    fn parse_as_step_size(&self, text: &str, _: ControlContext) -> Result<UnitValue, &'static str> {
+        parse_step_size_from_playback_speed_factor(text)
+    }
+
+    fn format_value_without_unit(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_playback_speed_factor_without_unit(value)
+    }
+
+    fn format_step_size_without_unit(&self, step_size: UnitValue, _: ControlContext) -> String {
+        format_step_size_as_playback_speed_factor_without_unit(step_size)
+    }
+
+    fn hide_formatted_value(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn hide_formatted_step_size(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn value_unit(&self, _: ControlContext) -> &'static str {
+        "x"
+    }
+
+    fn step_size_unit(&self, _: ControlContext) -> &'static str {
+        "x"
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let take = self
+            .item
+            .active_take()
+            .ok_or("item doesn't have an active take")?;
+        let play_rate =
+            PlayRate::from_normalized_value(NormalizedPlayRate::new(value.to_unit_value()?.get()));
+        set_take_info_value(take, TAKE_PLAYRATE, play_rate.playback_speed_factor().get())?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        item_is_available(self.track.project(), self.item) && self.item.active_take().is_some()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn item(&self) -> Option<Item> {
+        Some(self.item)
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        true
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Additional(AdditionalFeedbackEvent::ItemsChanged(e))
+                if e.project == self.track.project() =>
+            {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format!("{:.2}", self.playrate()?.playback_speed_factor().get()).into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        Some(NumericValue::Decimal(
+            self.playrate()?.playback_speed_factor().get(),
+        ))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TakePlayrate)
+    }
+}
+
+impl TakePlayrateTarget {
+    fn playrate(&self) -> Option<PlayRate> {
+        let take = self.item.active_take()?;
+        // Takes support a wider playrate range than projects, so we clamp.
+        let factor: PlaybackSpeedFactor = get_take_info_value(take, TAKE_PLAYRATE)
+            .clamp(
+                PlaybackSpeedFactor::MIN.get(),
+                PlaybackSpeedFactor::MAX.get(),
+            )
+            .try_into()
+            .ok()?;
+        Some(PlayRate::from_playback_speed_factor(factor))
+    }
+}
+
+impl<'a> Target<'a> for TakePlayrateTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = playrate_unit_value(self.playrate()?);
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TAKE_PLAYRATE_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Take: Set playrate",
+    short_name: "Take playrate",
+    supports_track: true,
+    supports_item: true,
+    supports_poll_for_feedback: true,
+    ..DEFAULT_TARGET
+};
@@ -0,0 +1,348 @@
+use crate::domain::{
+    format_value_as_on_off, Compartment, ControlContext, ExtendedProcessorContext,
+    FeedbackResolution, HitResponse, MappingControlContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, TargetCharacter, TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, NumericValue, PropValue, Target, UnitValue,
+};
+use realearn_api::persistence::{TimeRangeAction, TimeRangeKind, TimeRangeQuantization};
+use reaper_high::{BookmarkType, Project, Reaper};
+use reaper_medium::{
+    AutoSeekBehavior, PositionInQuarterNotes, PositionInSeconds, TimeModeOverride,
+};
+use std::borrow::Cow;
+use std::ptr::null_mut;
+
+#[derive(Debug)]
+pub struct UnresolvedTimeRangeTarget {
+    pub kind: TimeRangeKind,
+    pub action: TimeRangeAction,
+    pub quantization: TimeRangeQuantization,
+    pub region_index: u32,
+    pub bar_count: u32,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTimeRangeTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        _: Compartment,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(vec![ReaperTarget::TimeRange(TimeRangeTarget {
+            project: context.context().project_or_current_project(),
+            kind: self.kind,
+            action: self.action,
+            quantization: self.quantization,
+            region_index: self.region_index,
+            bar_count: self.bar_count,
+        })])
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // REAPER doesn't notify us about changes of the time selection or loop points.
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimeRangeTarget {
+    pub project: Project,
+    pub kind: TimeRangeKind,
+    pub action: TimeRangeAction,
+    pub quantization: TimeRangeQuantization,
+    pub region_index: u32,
+    pub bar_count: u32,
+}
+
+impl RealearnTarget for TimeRangeTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        if self.action.moves_edge() {
+            if self.quantization == TimeRangeQuantization::Off {
+                (ControlType::AbsoluteContinuous, TargetCharacter::Continuous)
+            } else {
+                (ControlType::Relative, TargetCharacter::Discrete)
+            }
+        } else {
+            (
+                ControlType::AbsoluteContinuousRetriggerable,
+                TargetCharacter::Trigger,
+            )
+        }
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        if self.action.moves_edge() {
+            let pos = PositionInSeconds::new(value.get() * self.project.length().get());
+            format_pos(pos)
+        } else {
+            format_value_as_on_off(value).to_string()
+        }
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let (start, end) = match self.action {
+            TimeRangeAction::MoveStart | TimeRangeAction::MoveEnd => {
+                let (start, end) = self.current_range().unwrap_or_else(|| {
+                    let pos = self.project.edit_cursor_position();
+                    (pos, pos)
+                });
+                let is_start = self.action == TimeRangeAction::MoveStart;
+                let old_edge = if is_start { start } else { end };
+                let new_edge = match value {
+                    ControlValue::RelativeContinuous(v) => {
+                        self.nudge(old_edge, v.to_discrete_increment().get())
+                    }
+                    ControlValue::RelativeDiscrete(v) => self.nudge(old_edge, v.get()),
+                    _ => {
+                        let value = value.to_unit_value()?;
+                        PositionInSeconds::new(value.get() * self.project.length().get())
+                    }
+                };
+                if is_start {
+                    (min_pos(new_edge, end), end)
+                } else {
+                    (start, max_pos(new_edge, start))
+                }
+            }
+            TimeRangeAction::SetToRegion | TimeRangeAction::SetToBars => {
+                if value.to_unit_value()?.is_zero() {
+                    return Ok(HitResponse::ignored());
+                }
+                self.desired_range().ok_or("region doesn't exist")?
+            }
+        };
+        match self.kind {
+            TimeRangeKind::TimeSelection => {
+                self.project.set_time_selection(start, end);
+            }
+            TimeRangeKind::LoopPoints => {
+                self.project
+                    .set_loop_points(start, end, AutoSeekBehavior::DenyAutoSeek);
+            }
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.project.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.project)
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        if self.action.moves_edge() {
+            Some(format_pos(self.current_edge()?).into())
+        } else {
+            Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+        }
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        if !self.action.moves_edge() {
+            return None;
+        }
+        Some(NumericValue::Decimal(self.current_edge()?.get()))
+    }
+
+    fn numeric_value_unit(&self, _: ControlContext) -> &'static str {
+        if self.action.moves_edge() {
+            "s"
+        } else {
+            ""
+        }
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TimeRange)
+    }
+
+    fn prop_value(&self, key: &str, _: ControlContext) -> Option<PropValue> {
+        let (start, end) = self.current_range()?;
+        let value = match key {
+            "time_range.start" => PropValue::Text(format_pos(start).into()),
+            "time_range.end" => PropValue::Text(format_pos(end).into()),
+            "time_range.length" => {
+                PropValue::Numeric(NumericValue::Decimal(end.get() - start.get()))
+            }
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+impl<'a> Target<'a> for TimeRangeTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let value = if self.action.moves_edge() {
+            match self.current_edge() {
+                None => UnitValue::MIN,
+                Some(pos) => {
+                    let length = self.project.length().get();
+                    if length > 0.0 {
+                        UnitValue::new_clamped(pos.get() / length)
+                    } else {
+                        UnitValue::MIN
+                    }
+                }
+            }
+        } else {
+            let is_on = match (self.current_range(), self.desired_range()) {
+                (Some(current), Some(desired)) => {
+                    pos_equals(current.0, desired.0) && pos_equals(current.1, desired.1)
+                }
+                _ => false,
+            };
+            if is_on {
+                UnitValue::MAX
+            } else {
+                UnitValue::MIN
+            }
+        };
+        Some(AbsoluteValue::Continuous(value))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+impl TimeRangeTarget {
+    fn current_range(&self) -> Option<(PositionInSeconds, PositionInSeconds)> {
+        let range = match self.kind {
+            TimeRangeKind::TimeSelection => self.project.time_selection(),
+            TimeRangeKind::LoopPoints => self.project.loop_points(),
+        }?;
+        Some((range.start, range.end))
+    }
+
+    fn current_edge(&self) -> Option<PositionInSeconds> {
+        let (start, end) = self.current_range()?;
+        if self.action == TimeRangeAction::MoveEnd {
+            Some(end)
+        } else {
+            Some(start)
+        }
+    }
+
+    /// Returns the range which is going to be set by the "Set to ..." actions.
+    fn desired_range(&self) -> Option<(PositionInSeconds, PositionInSeconds)> {
+        match self.action {
+            TimeRangeAction::SetToRegion => {
+                let res = self
+                    .project
+                    .find_bookmark_by_type_and_index(BookmarkType::Region, self.region_index)?;
+                let end = res.basic_info.region_end_position?;
+                Some((res.basic_info.position, end))
+            }
+            TimeRangeAction::SetToBars => {
+                let reaper = Reaper::get().medium_reaper();
+                let pos = self.project.play_or_edit_cursor_position();
+                let res = reaper.time_map_2_time_to_beats(self.project.context(), pos);
+                let start = self.bar_start(res.measure_index);
+                let end = self.bar_start(res.measure_index + self.bar_count.max(1) as i32);
+                Some((start, end))
+            }
+            TimeRangeAction::MoveStart | TimeRangeAction::MoveEnd => None,
+        }
+    }
+
+    /// Moves the given position by the given amount of grid divisions or bars, snapping it to
+    /// the grid or bars on the way.
+    fn nudge(&self, pos: PositionInSeconds, amount: i32) -> PositionInSeconds {
+        let reaper = Reaper::get().medium_reaper();
+        let context = self.project.context();
+        let new_pos = match self.quantization {
+            TimeRangeQuantization::Bars => {
+                let res = reaper.time_map_2_time_to_beats(context, pos);
+                // If we are not exactly on a bar, the first step snaps to the adjacent bar.
+                let floor_index = res.measure_index;
+                let is_on_bar = res.beats_since_measure.get() < BAR_EPSILON;
+                let new_index = if amount < 0 && !is_on_bar {
+                    floor_index + amount + 1
+                } else {
+                    floor_index + amount
+                };
+                self.bar_start(new_index)
+            }
+            TimeRangeQuantization::Grid | TimeRangeQuantization::Off => {
+                let qn = reaper.time_map_2_time_to_qn_abs(context, pos).get();
+                let grid_size = self.grid_size_in_quarter_notes();
+                let current_grid_pos = (qn / grid_size).round();
+                let new_qn = (current_grid_pos + amount as f64) * grid_size;
+                reaper.time_map_2_qn_to_time_abs(context, PositionInQuarterNotes::new(new_qn))
+            }
+        };
+        max_pos(new_pos, PositionInSeconds::new(0.0))
+    }
+
+    fn bar_start(&self, measure_index: i32) -> PositionInSeconds {
+        let reaper = Reaper::get().medium_reaper();
+        let context = self.project.context();
+        let res = reaper.time_map_get_measure_info(context, measure_index.max(0) as _);
+        reaper.time_map_2_qn_to_time_abs(context, res.start_qn)
+    }
+
+    fn grid_size_in_quarter_notes(&self) -> f64 {
+        // The grid division is expressed in whole notes (e.g. 0.25 means quarter notes).
+        let mut division = 0.0;
+        unsafe {
+            Reaper::get().medium_reaper().low().GetSetProjectGrid(
+                self.project.raw().as_ptr(),
+                false,
+                &mut division,
+                null_mut(),
+                null_mut(),
+            );
+        }
+        if division > 0.0 {
+            division * 4.0
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Positions which are closer to the beginning of a bar than this (in beats) count as on the bar.
+const BAR_EPSILON: f64 = 0.0001;
+
+fn format_pos(pos: PositionInSeconds) -> String {
+    Reaper::get()
+        .medium_reaper()
+        .format_timestr_pos(pos, 32, TimeModeOverride::ProjectDefault)
+        .into_string()
+}
+
+fn pos_equals(a: PositionInSeconds, b: PositionInSeconds) -> bool {
+    (a.get() - b.get()).abs() < 0.001
+}
+
+fn min_pos(a: PositionInSeconds, b: PositionInSeconds) -> PositionInSeconds {
+    if a.get() <= b.get() {
+        a
+    } else {
+        b
+    }
+}
+
+fn max_pos(a: PositionInSeconds, b: PositionInSeconds) -> PositionInSeconds {
+    if a.get() >= b.get() {
+        a
+    } else {
+        b
+    }
+}
+
+pub const TIME_RANGE_TARGET: TargetTypeDef = TargetTypeDef {
+    name: "Project: Set time selection/loop points",
+    short_name: "Time range",
+    hint: "Moves start/end or sets to region/bars",
+    ..DEFAULT_TARGET
+};
@@ -60,37 +60,39 @@ impl<'a> Target<'a> for TrackPeakTarget {
 
 impl TrackPeakTarget {
     fn peak(&self) -> Option<Volume> {
-        let reaper = Reaper::get().medium_reaper();
-        if self.track.project().any_solo() && self.track.solo_mode() == SoloMode::Off {
-            // Another track is soloed. In this case, reporting the peak would be misleading.
-            return Some(Volume::MIN);
-        }
-        let vu_mode = unsafe {
-            reaper.get_media_track_info_value(self.track.raw(), TrackAttributeKey::VuMode) as i32
-        };
-        let channel_count = if matches!(vu_mode, 2 | 8) {
-            // These VU modes have multi-channel support.
-            unsafe {
-                reaper.get_media_track_info_value(self.track.raw(), TrackAttributeKey::Nchan) as i32
-            }
-        } else {
-            // Other VU modes always use stereo.
-            2
-        };
-        if channel_count <= 0 {
-            return None;
-        }
-        let mut sum = 0.0;
-        for ch in 0..channel_count {
-            let volume = unsafe { reaper.track_get_peak_info(self.track.raw(), ch as u32) };
-            sum += volume.get();
-        }
-        let avg = sum / channel_count as f64;
-        let vol = ReaperVolumeValue::new(avg);
-        Some(Volume::from_reaper_value(vol))
+        get_track_peak(&self.track)
     }
 }
 
+/// Returns the current peak of the given track, averaged over all channels shown in its meter.
+pub fn get_track_peak(track: &Track) -> Option<Volume> {
+    let reaper = Reaper::get().medium_reaper();
+    if track.project().any_solo() && track.solo_mode() == SoloMode::Off {
+        // Another track is soloed. In this case, reporting the peak would be misleading.
+        return Some(Volume::MIN);
+    }
+    let vu_mode =
+        unsafe { reaper.get_media_track_info_value(track.raw(), TrackAttributeKey::VuMode) as i32 };
+    let channel_count = if matches!(vu_mode, 2 | 8) {
+        // These VU modes have multi-channel support.
+        unsafe { reaper.get_media_track_info_value(track.raw(), TrackAttributeKey::Nchan) as i32 }
+    } else {
+        // Other VU modes always use stereo.
+        2
+    };
+    if channel_count <= 0 {
+        return None;
+    }
+    let mut sum = 0.0;
+    for ch in 0..channel_count {
+        let volume = unsafe { reaper.track_get_peak_info(track.raw(), ch as u32) };
+        sum += volume.get();
+    }
+    let avg = sum / channel_count as f64;
+    let vol = ReaperVolumeValue::new(avg);
+    Some(Volume::from_reaper_value(vol))
+}
+
 impl RealearnTarget for TrackPeakTarget {
     fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
         (ControlType::AbsoluteContinuous, TargetCharacter::Continuous)
@@ -1,24 +1,31 @@
 use crate::application::BookmarkAnchorType;
 use crate::domain::realearn_target::RealearnTarget;
 use crate::domain::{
-    scoped_track_index, BackboneState, Compartment, CompartmentParamIndex, CompartmentParams,
-    ExtendedProcessorContext, FeedbackResolution, ReaperTarget, UnresolvedActionTarget,
+    get_item_at_position, get_item_track, get_selected_items, scoped_track_index, BackboneState,
+    Compartment, CompartmentParamIndex, CompartmentParams, ExtendedProcessorContext,
+    FeedbackResolution, ReaperTarget, ResolvedEnvelope, UnresolvedActionTarget,
     UnresolvedAllTrackFxEnableTarget, UnresolvedAnyOnTarget,
-    UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget,
+    UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseAutomationItemsTarget,
+    UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget, UnresolvedBrowseItemsTarget,
     UnresolvedBrowsePotFilterItemsTarget, UnresolvedBrowsePotPresetsTarget,
     UnresolvedBrowseTracksTarget, UnresolvedClipColumnTarget, UnresolvedClipManagementTarget,
     UnresolvedClipMatrixTarget, UnresolvedClipRowTarget, UnresolvedClipSeekTarget,
     UnresolvedClipTransportTarget, UnresolvedClipVolumeTarget, UnresolvedDummyTarget,
-    UnresolvedEnableInstancesTarget, UnresolvedEnableMappingsTarget, UnresolvedFxEnableTarget,
+    UnresolvedEnableInstancesTarget, UnresolvedEnableMappingsTarget, UnresolvedEnvelopeArmTarget,
+    UnresolvedEnvelopeShowTarget, UnresolvedEnvelopeWriteValueTarget, UnresolvedFxEnableTarget,
     UnresolvedFxOnlineTarget, UnresolvedFxOpenTarget, UnresolvedFxParameterTarget,
     UnresolvedFxParameterTouchStateTarget, UnresolvedFxPresetTarget, UnresolvedFxToolTarget,
-    UnresolvedGoToBookmarkTarget, UnresolvedLastTouchedTarget, UnresolvedLoadFxSnapshotTarget,
-    UnresolvedLoadMappingSnapshotTarget, UnresolvedLoadPotPresetTarget, UnresolvedMidiSendTarget,
-    UnresolvedMouseTarget, UnresolvedOscSendTarget, UnresolvedPlayrateTarget,
-    UnresolvedPreviewPotPresetTarget, UnresolvedRouteAutomationModeTarget,
-    UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget, UnresolvedRoutePanTarget,
-    UnresolvedRoutePhaseTarget, UnresolvedRouteTouchStateTarget, UnresolvedRouteVolumeTarget,
-    UnresolvedSeekTarget, UnresolvedTakeMappingSnapshotTarget, UnresolvedTempoTarget,
+    UnresolvedGoToBookmarkTarget, UnresolvedItemBrowseTakesTarget, UnresolvedItemLockTarget,
+    UnresolvedItemMuteTarget, UnresolvedItemPositionTarget, UnresolvedItemSelectionTarget,
+    UnresolvedItemVolumeTarget, UnresolvedLastTouchedTarget, UnresolvedLoadFxSnapshotTarget,
+    UnresolvedLoadMappingSnapshotTarget, UnresolvedLoadPotPresetTarget,
+    UnresolvedMarkerManagementTarget, UnresolvedMidiSendTarget, UnresolvedMouseTarget,
+    UnresolvedOscSendTarget, UnresolvedPlayrateTarget, UnresolvedPreviewPotPresetTarget,
+    UnresolvedRouteAutomationModeTarget, UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget,
+    UnresolvedRoutePanTarget, UnresolvedRoutePhaseTarget, UnresolvedRouteTouchStateTarget,
+    UnresolvedRouteVolumeTarget, UnresolvedSeekTarget, UnresolvedTakeFxParameterTarget,
+    UnresolvedTakeMappingSnapshotTarget, UnresolvedTakePitchTarget, UnresolvedTakePlayrateTarget,
+    UnresolvedTakeVolumeTarget, UnresolvedTempoTarget, UnresolvedTimeRangeTarget,
     UnresolvedTrackArmTarget, UnresolvedTrackAutomationModeTarget,
     UnresolvedTrackMonitoringModeTarget, UnresolvedTrackMuteTarget, UnresolvedTrackPanTarget,
     UnresolvedTrackParentSendTarget, UnresolvedTrackPeakTarget, UnresolvedTrackPhaseTarget,
@@ -34,10 +41,10 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use playtime_clip_engine::base::ClipSlotAddress;
 use realearn_api::persistence::{
     ClipColumnDescriptor, ClipColumnTrackContext, FxChainDescriptor, FxDescriptorCommons,
-    TrackDescriptorCommons, TrackScope,
+    RouteEnvelopeKind, TrackDescriptorCommons, TrackEnvelopeKind, TrackScope,
 };
 use reaper_high::{
-    BookmarkType, FindBookmarkResult, Fx, FxChain, FxParameter, Guid, Project, Reaper,
+    BookmarkType, FindBookmarkResult, Fx, FxChain, FxParameter, Guid, Item, Project, Reaper,
     SendPartnerType, Track, TrackRoute,
 };
 use reaper_medium::{BookmarkId, MasterTrackBehavior, TrackArea};
@@ -79,6 +86,10 @@ pub enum UnresolvedReaperTarget {
     RouteMono(UnresolvedRouteMonoTarget),
     RouteAutomationMode(UnresolvedRouteAutomationModeTarget),
     RouteTouchState(UnresolvedRouteTouchStateTarget),
+    EnvelopeArm(UnresolvedEnvelopeArmTarget),
+    EnvelopeShow(UnresolvedEnvelopeShowTarget),
+    EnvelopeWriteValue(UnresolvedEnvelopeWriteValueTarget),
+    BrowseAutomationItems(UnresolvedBrowseAutomationItemsTarget),
     Tempo(UnresolvedTempoTarget),
     Playrate(UnresolvedPlayrateTarget),
     AutomationModeOverride(UnresolvedAutomationModeOverrideTarget),
@@ -88,13 +99,16 @@ pub enum UnresolvedReaperTarget {
     FxOpen(UnresolvedFxOpenTarget),
     FxPreset(UnresolvedFxPresetTarget),
     SelectedTrack(UnresolvedBrowseTracksTarget),
+    BrowseItems(UnresolvedBrowseItemsTarget),
     BrowseFxs(UnresolvedBrowseFxsTarget),
     AllTrackFxEnable(UnresolvedAllTrackFxEnableTarget),
     Transport(UnresolvedTransportTarget),
     LoadFxPreset(UnresolvedLoadFxSnapshotTarget),
     TrackTouchState(UnresolvedTrackTouchStateTarget),
     GoToBookmark(UnresolvedGoToBookmarkTarget),
+    MarkerManagement(UnresolvedMarkerManagementTarget),
     Seek(UnresolvedSeekTarget),
+    TimeRange(UnresolvedTimeRangeTarget),
     SendMidi(UnresolvedMidiSendTarget),
     SendOsc(UnresolvedOscSendTarget),
     Dummy(UnresolvedDummyTarget),
@@ -116,6 +130,16 @@ pub enum UnresolvedReaperTarget {
     BrowsePotPresets(UnresolvedBrowsePotPresetsTarget),
     PreviewPotPreset(UnresolvedPreviewPotPresetTarget),
     LoadPotPreset(UnresolvedLoadPotPresetTarget),
+    ItemVolume(UnresolvedItemVolumeTarget),
+    ItemMute(UnresolvedItemMuteTarget),
+    ItemLock(UnresolvedItemLockTarget),
+    ItemSelection(UnresolvedItemSelectionTarget),
+    ItemPosition(UnresolvedItemPositionTarget),
+    ItemBrowseTakes(UnresolvedItemBrowseTakesTarget),
+    TakeVolume(UnresolvedTakeVolumeTarget),
+    TakePitch(UnresolvedTakePitchTarget),
+    TakePlayrate(UnresolvedTakePlayrateTarget),
+    TakeFxParameter(UnresolvedTakeFxParameterTarget),
 }
 
 impl UnresolvedReaperTarget {
@@ -168,6 +192,11 @@ impl UnresolvedReaperTarget {
                 return true;
             }
         }
+        if let Some(desc) = descriptors.item {
+            if desc.item.can_be_affected_by_parameters() {
+                return true;
+            }
+        }
         if let Some(desc) = descriptors.fx_param {
             if desc.fx_parameter.can_be_affected_by_parameters() {
                 return true;
@@ -214,6 +243,13 @@ impl UnresolvedReaperTarget {
                 ..Default::default()
             };
         }
+        if let Some(d) = self.item_descriptor() {
+            return Descriptors {
+                track: Some(&d.track_descriptor),
+                item: Some(d),
+                ..Default::default()
+            };
+        }
         if let Some(d) = self.track_descriptor() {
             return Descriptors {
                 track: Some(d),
@@ -252,6 +288,22 @@ pub fn get_effective_tracks(
         .map_err(|_| "track couldn't be resolved")
 }
 
+/// Returns the resolved items together with the tracks which contain them.
+pub fn get_items(
+    context: ExtendedProcessorContext,
+    descriptor: &ItemDescriptor,
+    compartment: Compartment,
+) -> Result<Vec<(Track, Item)>, &'static str> {
+    let project = context.context().project_or_current_project();
+    let items = descriptor
+        .item
+        .resolve(context, &descriptor.track_descriptor, compartment)?
+        .into_iter()
+        .filter_map(|item| Some((get_item_track(project, item)?, item)))
+        .collect();
+    Ok(items)
+}
+
 // Returns an error if that send (or track) doesn't exist.
 pub fn get_track_routes(
     context: ExtendedProcessorContext,
@@ -629,7 +681,9 @@ impl TrackRouteSelector {
     ) -> Result<u32, TrackRouteResolveError> {
         let compartment_params = context.params().compartment_params(compartment);
         let result = evaluator
-            .evaluate_with_params(compartment_params)
+            .evaluate_with_params_and_vars(compartment_params, |name, _| {
+                context.osc_captures.get(name)
+            })
             .map_err(|_| TrackRouteResolveError::ExpressionFailed)?
             .round() as i32;
         if result < 0 {
@@ -709,6 +763,182 @@ impl VirtualTrackRoute {
     }
 }
 
+#[derive(Debug)]
+pub struct ItemDescriptor {
+    pub track_descriptor: TrackDescriptor,
+    pub item: VirtualItem,
+}
+
+#[derive(Debug)]
+pub enum VirtualItem {
+    /// Currently selected item(s) in the project, no matter on which track.
+    Selected { allow_multiple: bool },
+    /// Item on the track at the edit cursor position.
+    AtEditCursor,
+    /// Position on the track based on parameter values.
+    Dynamic(Box<ExpressionEvaluator>),
+    /// Position on the track.
+    ByIndex(u32),
+}
+
+impl Default for VirtualItem {
+    fn default() -> Self {
+        Self::Selected {
+            allow_multiple: false,
+        }
+    }
+}
+
+impl fmt::Display for VirtualItem {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        use VirtualItem::*;
+        match self {
+            Selected { allow_multiple } => f.write_str(if *allow_multiple {
+                "<All selected>"
+            } else {
+                "<Selected>"
+            }),
+            AtEditCursor => f.write_str("<At edit cursor>"),
+            Dynamic(_) => f.write_str("<Dynamic>"),
+            ByIndex(i) => write!(f, "#{}", i + 1),
+        }
+    }
+}
+
+impl VirtualItem {
+    /// Returns whether the item is looked up on a particular track.
+    pub fn requires_track(&self) -> bool {
+        !matches!(self, VirtualItem::Selected { .. })
+    }
+
+    pub fn can_be_affected_by_parameters(&self) -> bool {
+        matches!(self, VirtualItem::Dynamic(_))
+    }
+
+    pub fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        track_descriptor: &TrackDescriptor,
+        compartment: Compartment,
+    ) -> Result<Vec<Item>, &'static str> {
+        use VirtualItem::*;
+        let items = match self {
+            Selected { allow_multiple } => {
+                let project = context.context().project_or_current_project();
+                let items = get_selected_items(project);
+                if *allow_multiple {
+                    items.take(MAX_MULTIPLE).collect()
+                } else {
+                    items.take(1).collect()
+                }
+            }
+            AtEditCursor => get_effective_tracks(context, &track_descriptor.track, compartment)?
+                .into_iter()
+                .filter_map(|track| {
+                    let pos = track.project().edit_cursor_position();
+                    get_item_at_position(&track, pos)
+                })
+                .collect(),
+            Dynamic(evaluator) => {
+                let compartment_params = context.params().compartment_params(compartment);
+                let index = evaluator
+                    .evaluate_with_params_and_vars(compartment_params, |name, _| {
+                        context.osc_captures.get(name)
+                    })
+                    .map_err(|_| "item expression failed")?
+                    .round();
+                if index < 0.0 {
+                    return Err("item index must not be negative");
+                }
+                get_items_by_index(context, track_descriptor, index as u32, compartment)?
+            }
+            ByIndex(index) => get_items_by_index(context, track_descriptor, *index, compartment)?,
+        };
+        Ok(items)
+    }
+}
+
+fn get_items_by_index(
+    context: ExtendedProcessorContext,
+    track_descriptor: &TrackDescriptor,
+    index: u32,
+    compartment: Compartment,
+) -> Result<Vec<Item>, &'static str> {
+    let items = get_effective_tracks(context, &track_descriptor.track, compartment)?
+        .into_iter()
+        .filter_map(|track| track.items().nth(index as usize))
+        .collect();
+    Ok(items)
+}
+
+#[derive(Debug)]
+pub enum EnvelopeDescriptor {
+    Track {
+        track_descriptor: TrackDescriptor,
+        kind: TrackEnvelopeKind,
+    },
+    FxParameter(FxParameterDescriptor),
+    Route {
+        route_descriptor: TrackRouteDescriptor,
+        kind: RouteEnvelopeKind,
+    },
+}
+
+impl EnvelopeDescriptor {
+    pub fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: Compartment,
+    ) -> Result<Vec<ResolvedEnvelope>, &'static str> {
+        let envelopes = match self {
+            EnvelopeDescriptor::Track {
+                track_descriptor,
+                kind,
+            } => get_effective_tracks(context, &track_descriptor.track, compartment)?
+                .into_iter()
+                .map(|track| ResolvedEnvelope::Track { track, kind: *kind })
+                .collect(),
+            EnvelopeDescriptor::FxParameter(d) => get_fx_params(context, d, compartment)?
+                .into_iter()
+                .map(ResolvedEnvelope::FxParameter)
+                .collect(),
+            EnvelopeDescriptor::Route {
+                route_descriptor,
+                kind,
+            } => get_track_routes(context, route_descriptor, compartment)?
+                .into_iter()
+                .map(|route| ResolvedEnvelope::Route { route, kind: *kind })
+                .collect(),
+        };
+        Ok(envelopes)
+    }
+
+    pub fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        match self {
+            EnvelopeDescriptor::Track {
+                track_descriptor, ..
+            } => Some(track_descriptor),
+            _ => None,
+        }
+    }
+
+    pub fn fx_parameter_descriptor(&self) -> Option<&FxParameterDescriptor> {
+        match self {
+            EnvelopeDescriptor::FxParameter(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn route_descriptor(&self) -> Option<&TrackRouteDescriptor> {
+        match self {
+            EnvelopeDescriptor::Route {
+                route_descriptor, ..
+            } => Some(route_descriptor),
+            _ => None,
+        }
+    }
+}
+
 #[derive(
     Clone,
     Copy,
@@ -772,10 +1002,17 @@ impl VirtualClipSlot {
                 row_evaluator,
             } => {
                 let compartment_params = context.params().compartment_params(compartment);
-                let column_index =
-                    to_slot_coordinate(column_evaluator.evaluate_with_params(compartment_params))?;
-                let row_index =
-                    to_slot_coordinate(row_evaluator.evaluate_with_params(compartment_params))?;
+                let column_index = to_slot_coordinate(
+                    column_evaluator
+                        .evaluate_with_params_and_vars(compartment_params, |name, _| {
+                            context.osc_captures.get(name)
+                        }),
+                )?;
+                let row_index = to_slot_coordinate(
+                    row_evaluator.evaluate_with_params_and_vars(compartment_params, |name, _| {
+                        context.osc_captures.get(name)
+                    }),
+                )?;
                 ClipSlotAddress::new(column_index, row_index)
             }
         };
@@ -837,7 +1074,11 @@ impl VirtualClipColumn {
             ByIndex(index) => *index,
             Dynamic(evaluator) => {
                 let compartment_params = context.params().compartment_params(compartment);
-                to_slot_coordinate(evaluator.evaluate_with_params(compartment_params))?
+                to_slot_coordinate(
+                    evaluator.evaluate_with_params_and_vars(compartment_params, |name, _| {
+                        context.osc_captures.get(name)
+                    }),
+                )?
             }
         };
         // let column_exists = BackboneState::get()
@@ -880,7 +1121,11 @@ impl VirtualClipRow {
             ByIndex(index) => *index,
             Dynamic(evaluator) => {
                 let compartment_params = context.params().compartment_params(compartment);
-                to_slot_coordinate(evaluator.evaluate_with_params(compartment_params))?
+                to_slot_coordinate(
+                    evaluator.evaluate_with_params_and_vars(compartment_params, |name, _| {
+                        context.osc_captures.get(name)
+                    }),
+                )?
             }
         };
         // let row_exists = BackboneState::get()
@@ -1039,7 +1284,7 @@ impl VirtualFxParameter {
                         .unwrap_or(EXPRESSION_NONE_VALUE);
                     Some(index)
                 }
-                _ => None,
+                _ => context.osc_captures.get(name),
             })
             .map_err(|_| FxParameterResolveError::ExpressionFailed)?
             .round() as i32;
@@ -1520,7 +1765,7 @@ impl VirtualTrack {
                             }
                         }
                     }
-                    _ => None,
+                    _ => context.osc_captures.get(name),
                 }
             })
             .map_err(|_| TrackResolveError::ExpressionFailed)?
@@ -1829,7 +2074,7 @@ impl VirtualChainFx {
                         .unwrap_or(EXPRESSION_NONE_VALUE);
                     Some(index)
                 }
-                _ => None,
+                _ => context.osc_captures.get(name),
             })
             .map_err(|_| FxResolveError::ExpressionFailed)?
             .round() as i32;
@@ -2100,6 +2345,7 @@ struct Descriptors<'a> {
     track: Option<&'a TrackDescriptor>,
     fx: Option<&'a FxDescriptor>,
     route: Option<&'a TrackRouteDescriptor>,
+    item: Option<&'a ItemDescriptor>,
     fx_param: Option<&'a FxParameterDescriptor>,
     clip_slot: Option<&'a VirtualClipSlot>,
     clip_column: Option<&'a VirtualClipColumn>,
@@ -2147,6 +2393,10 @@ pub trait UnresolvedReaperTargetDef {
         None
     }
 
+    fn item_descriptor(&self) -> Option<&ItemDescriptor> {
+        None
+    }
+
     fn fx_parameter_descriptor(&self) -> Option<&FxParameterDescriptor> {
         None
     }
@@ -1,9 +1,14 @@
 use crate::domain::ui_util::{format_as_percentage_without_unit, parse_unit_value_from_percentage};
-use crate::domain::{ExtendedSourceCharacter, SmallAsciiString, TargetCharacter};
+use crate::domain::{
+    ExtendedSourceCharacter, SmallAsciiString, TargetCharacter, VirtualOscCaptures,
+};
 use ascii::{AsciiString, ToAsciiChar};
+use enum_iterator::IntoEnumIterator;
 use helgoboss_learn::{
     AbsoluteValue, ControlType, ControlValue, FeedbackValue, SourceCharacter, Target, UnitValue,
 };
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
@@ -11,21 +16,35 @@ use std::str::FromStr;
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 pub struct VirtualTarget {
     control_element: VirtualControlElement,
+    /// Only relevant for XY control elements.
+    axis: XyAxis,
 }
 
 impl VirtualTarget {
     pub fn new(control_element: VirtualControlElement) -> VirtualTarget {
-        VirtualTarget { control_element }
+        VirtualTarget {
+            control_element,
+            axis: Default::default(),
+        }
+    }
+
+    /// Makes this target control the given axis of an XY control element.
+    pub fn with_axis(self, axis: XyAxis) -> Self {
+        Self { axis, ..self }
     }
 
     pub fn control_element(&self) -> VirtualControlElement {
         self.control_element
     }
 
+    pub fn axis(&self) -> XyAxis {
+        self.axis
+    }
+
     pub fn character(&self) -> TargetCharacter {
         use VirtualControlElement::*;
         match self.control_element {
-            Multi(_) => TargetCharacter::VirtualMulti,
+            Multi(_) | Xy(_) => TargetCharacter::VirtualMulti,
             Button(_) => TargetCharacter::VirtualButton,
         }
     }
@@ -41,7 +60,7 @@ impl<'a> Target<'a> for VirtualTarget {
     fn control_type(&self, _: ()) -> ControlType {
         use VirtualControlElement::*;
         match self.control_element {
-            Multi(_) => ControlType::VirtualMulti,
+            Multi(_) | Xy(_) => ControlType::VirtualMulti,
             Button(_) => ControlType::VirtualButton,
         }
     }
@@ -81,6 +100,10 @@ impl VirtualSource {
         self.control_element
     }
 
+    /// Returns the control value if the given value belongs to this control element.
+    ///
+    /// With XY control elements, this matches values of both axes. Use
+    /// [`VirtualSourceValue::axis`] to find out which one is meant.
     pub fn control(&self, value: &VirtualSourceValue) -> Option<ControlValue> {
         if self.control_element != value.control_element {
             return None;
@@ -105,7 +128,7 @@ impl VirtualSource {
         use VirtualControlElement::*;
         match self.control_element {
             Button(_) => ExtendedSourceCharacter::Normal(SourceCharacter::MomentaryButton),
-            Multi(_) => ExtendedSourceCharacter::VirtualContinuous,
+            Multi(_) | Xy(_) => ExtendedSourceCharacter::VirtualContinuous,
         }
     }
 }
@@ -113,16 +136,28 @@ impl VirtualSource {
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct VirtualSourceValue {
     control_element: VirtualControlElement,
+    /// Only relevant for XY control elements.
+    axis: XyAxis,
     control_value: ControlValue,
+    /// Captures of the OSC address pattern of the controller mapping which produced this value.
+    osc_captures: VirtualOscCaptures,
 }
 
 impl Display for VirtualSourceValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{} with value {}",
-            self.control_element, self.control_value
-        )
+        if let VirtualControlElement::Xy(_) = self.control_element {
+            write!(
+                f,
+                "{} ({}) with value {}",
+                self.control_element, self.axis, self.control_value
+            )
+        } else {
+            write!(
+                f,
+                "{} with value {}",
+                self.control_element, self.control_value
+            )
+        }
     }
 }
 
@@ -133,7 +168,23 @@ impl VirtualSourceValue {
     ) -> VirtualSourceValue {
         VirtualSourceValue {
             control_element,
+            axis: Default::default(),
             control_value,
+            osc_captures: Default::default(),
+        }
+    }
+
+    /// Marks this value as value of the given axis of an XY control element.
+    pub fn with_axis(self, axis: XyAxis) -> Self {
+        Self { axis, ..self }
+    }
+
+    /// Attaches the captures of an OSC address pattern, so that they are available in target
+    /// expressions of the main mapping.
+    pub fn with_osc_captures(self, osc_captures: VirtualOscCaptures) -> Self {
+        Self {
+            osc_captures,
+            ..self
         }
     }
 
@@ -141,15 +192,27 @@ impl VirtualSourceValue {
         self.control_element
     }
 
+    pub fn axis(&self) -> XyAxis {
+        self.axis
+    }
+
     pub fn control_value(&self) -> ControlValue {
         self.control_value
     }
+
+    pub fn osc_captures(&self) -> &VirtualOscCaptures {
+        &self.osc_captures
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct VirtualFeedbackValue {
     control_element: VirtualControlElement,
     feedback_value: FeedbackValue<'static>,
+    /// With XY control elements, this carries the feedback value of the Y axis (whereas
+    /// `feedback_value` carries the one of the X axis). This way both coordinates can be sent
+    /// back to the controller in one message.
+    y_feedback_value: Option<FeedbackValue<'static>>,
 }
 
 impl Display for VirtualFeedbackValue {
@@ -170,6 +233,14 @@ impl VirtualFeedbackValue {
         VirtualFeedbackValue {
             control_element,
             feedback_value,
+            y_feedback_value: None,
+        }
+    }
+
+    pub fn with_y_feedback_value(self, y_feedback_value: Option<FeedbackValue<'static>>) -> Self {
+        Self {
+            y_feedback_value,
+            ..self
         }
     }
 
@@ -180,12 +251,49 @@ impl VirtualFeedbackValue {
     pub fn feedback_value(&self) -> &FeedbackValue {
         &self.feedback_value
     }
+
+    pub fn y_feedback_value(&self) -> Option<&FeedbackValue> {
+        self.y_feedback_value.as_ref()
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
 pub enum VirtualControlElement {
     Multi(VirtualControlElementId),
     Button(VirtualControlElementId),
+    /// Two-dimensional control element such as an XY pad or a joystick.
+    Xy(VirtualControlElementId),
+}
+
+/// Axis of an XY control element.
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    Serialize_repr,
+    Deserialize_repr,
+    IntoEnumIterator,
+    TryFromPrimitive,
+    IntoPrimitive,
+    derive_more::Display,
+)]
+#[repr(usize)]
+pub enum XyAxis {
+    #[display(fmt = "X")]
+    X,
+    #[display(fmt = "Y")]
+    Y,
+}
+
+impl Default for XyAxis {
+    fn default() -> Self {
+        Self::X
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
@@ -234,6 +342,7 @@ impl Display for VirtualControlElement {
         match self {
             Multi(id) => write!(f, "Multi {}", id),
             Button(id) => write!(f, "Button {}", id),
+            Xy(id) => write!(f, "XY {}", id),
         }
     }
 }
@@ -252,7 +361,37 @@ impl VirtualControlElement {
     pub fn id(&self) -> VirtualControlElementId {
         use VirtualControlElement::*;
         match self {
-            Multi(i) | Button(i) => *i,
+            Multi(i) | Button(i) | Xy(i) => *i,
+        }
+    }
+
+    /// Returns the control element of the same type which is the given number of positions after
+    /// this one.
+    ///
+    /// Numbered elements are counted up. Named elements get the one-based position as suffix,
+    /// e.g. `xy` with offset 1 becomes `xy/2`.
+    pub fn with_offset(&self, offset: u32) -> Self {
+        use VirtualControlElement::*;
+        let id = self.id().with_offset(offset);
+        match self {
+            Multi(_) => Multi(id),
+            Button(_) => Button(id),
+            Xy(_) => Xy(id),
+        }
+    }
+}
+
+impl VirtualControlElementId {
+    fn with_offset(&self, offset: u32) -> Self {
+        use VirtualControlElementId::*;
+        match self {
+            Indexed(index) => Indexed(index + offset),
+            Named(name) => {
+                let name_with_suffix = format!("{}/{}", name, offset + 1);
+                create_control_element_name_lossy(&name_with_suffix)
+                    .map(Named)
+                    .unwrap_or(*self)
+            }
         }
     }
 }
@@ -411,9 +550,12 @@ pub mod control_element_domains {
             "cursor-up",
             "cursor-down",
         ];
+
+        pub const PREDEFINED_VIRTUAL_XY_NAMES: &[&str] = &["xy-pad", "joystick"];
     }
     pub mod grid {
         pub const PREDEFINED_VIRTUAL_MULTI_NAMES: &[&str] = &[];
+        pub const PREDEFINED_VIRTUAL_XY_NAMES: &[&str] = &[];
         pub const PREDEFINED_VIRTUAL_BUTTON_NAMES: &[&str] = &[
             "col1/stop",
             "col2/stop",
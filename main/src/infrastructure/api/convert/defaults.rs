@@ -36,12 +36,16 @@ pub const TARGET_POLL_FOR_FEEDBACK: bool = true;
 pub const TARGET_RETRIGGER: bool = false;
 pub const TARGET_TRACK_SELECTION_SCROLL_ARRANGE_VIEW: bool = false;
 pub const TARGET_TRACK_SELECTION_SCROLL_MIXER: bool = false;
+pub const TARGET_BROWSE_ITEMS_SCROLL_ARRANGE_VIEW: bool = false;
+pub const TARGET_BROWSE_ITEMS_MOVE_EDIT_CURSOR: bool = false;
 pub const TARGET_SEEK_USE_TIME_SELECTION: bool = false;
 pub const TARGET_SEEK_USE_LOOP_POINTS: bool = false;
 pub const TARGET_SEEK_USE_REGIONS: bool = false;
 pub const TARGET_SEEK_USE_PROJECT: bool = true;
 pub const TARGET_SEEK_MOVE_VIEW: bool = true;
 pub const TARGET_SEEK_SEEK_PLAY: bool = true;
+pub const TARGET_TIME_RANGE_REGION_INDEX: u32 = 0;
+pub const TARGET_TIME_RANGE_BAR_COUNT: u32 = 1;
 pub const TARGET_LOAD_MAPPING_SNAPSHOT_ACTIVE_MAPPINGS_ONLY: bool = false;
 pub const TARGET_SAVE_MAPPING_SNAPSHOT_ACTIVE_MAPPINGS_ONLY: bool = false;
 pub const TARGET_RECORD_ONLY_IF_TRACK_ARMED: bool = false;
@@ -1,3 +1,4 @@
+use crate::application::ModeModel;
 use crate::infrastructure::api::convert::from_data::ConversionStyle;
 use crate::infrastructure::api::convert::{defaults, ConversionResult};
 use crate::infrastructure::data::ModeModelData;
@@ -6,7 +7,7 @@ use helgoboss_learn::{
     GroupInteraction, OutOfRangeBehavior, TakeoverMode, UnitValue, VirtualColor,
 };
 use realearn_api::persistence;
-use realearn_api::persistence::{NumericFeedback, PropColor, TextFeedback};
+use realearn_api::persistence::{NumericFeedback, PropColor, TextFeedback, Transformation};
 
 pub fn convert_glue(
     data: ModeModelData,
@@ -75,7 +76,9 @@ pub fn convert_glue(
             data.round_target_value,
             defaults::GLUE_ROUND_TARGET_VALUE,
         ),
-        control_transformation: style.required_value(data.eel_control_transformation),
+        control_transformation: style
+            .required_value(data.eel_control_transformation)
+            .map(|script| Transformation::new(data.control_transformation_language, script)),
         button_filter: {
             use persistence::ButtonFilter as T;
             use ButtonUsage::*;
@@ -124,7 +127,9 @@ pub fn convert_glue(
                         data.feedback_color,
                         data.feedback_background_color,
                     )?,
-                    transformation: style.required_value(data.eel_feedback_transformation),
+                    transformation: style.required_value(data.eel_feedback_transformation).map(
+                        |script| Transformation::new(data.feedback_transformation_language, script),
+                    ),
                 }),
                 Textual => T::Text(TextFeedback {
                     commons: convert_feedback_commons(
@@ -180,6 +185,15 @@ pub fn convert_glue(
             };
             style.required_value(v)
         },
+        gesture: style.required_value(data.gesture),
+        tap_count: if data.gesture == persistence::Gesture::MultiTap {
+            style.required_value_with_default(
+                data.gesture_tap_count,
+                ModeModel::DEFAULT_GESTURE_TAP_COUNT,
+            )
+        } else {
+            None
+        },
         feedback_value_table: data.feedback_value_table,
     };
     Ok(glue)
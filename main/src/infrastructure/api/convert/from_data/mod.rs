@@ -10,10 +10,12 @@ mod mapping;
 pub use mapping::*;
 mod compartment;
 pub use compartment::*;
+mod session;
+pub use session::*;
 mod target;
 
 use crate::application::{ActivationType, VirtualControlElementType};
-use crate::domain::{Keystroke, Tag};
+use crate::domain::{Keystroke, Tag, XyAxis};
 use crate::infrastructure::data::{
     ActivationConditionData, OscValueRange, VirtualControlElementIdData,
 };
@@ -42,6 +44,16 @@ fn convert_control_element_kind(
     let res = match v {
         Multi => T::Multi,
         Button => T::Button,
+        Xy => T::Xy,
+    };
+    style.required_value(res)
+}
+
+fn convert_xy_axis(v: XyAxis, style: ConversionStyle) -> Option<persistence::XyAxis> {
+    use persistence::XyAxis as T;
+    let res = match v {
+        XyAxis::X => T::X,
+        XyAxis::Y => T::Y,
     };
     style.required_value(res)
 }
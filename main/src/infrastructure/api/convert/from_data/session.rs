@@ -0,0 +1,268 @@
+use crate::application::{session_defaults, FxPresetLink, MainPresetAutoLoadMode};
+use crate::domain::{StayActiveWhenProjectInBackground, Tag};
+use crate::infrastructure::api::convert::from_data::{
+    convert_compartment, convert_tags, ConversionStyle,
+};
+use crate::infrastructure::api::convert::ConversionResult;
+use crate::infrastructure::data::{
+    ClipMatrixRefData, CompartmentModelData, CompartmentState, ControlDeviceId, FeedbackDeviceId,
+    GroupModelData, MappingModelData, ParameterData, SessionData,
+};
+use realearn_api::persistence;
+use realearn_api::persistence::MappingSnapshot;
+use std::collections::{HashMap, HashSet};
+
+pub fn convert_session(
+    data: SessionData,
+    style: ConversionStyle,
+) -> ConversionResult<persistence::Session> {
+    // Legacy clip slots (ReaLearn <= 2.12.0-pre.4) are converted to a clip matrix when the
+    // session is loaded, so we don't need to consider them here. The pot state is UI state and
+    // therefore not part of the API.
+    let controller = SessionCompartmentData {
+        active_preset_id: data.active_controller_id,
+        default_group: data.default_controller_group,
+        groups: data.controller_groups,
+        mappings: data.controller_mappings,
+        parameters: data.controller_parameters,
+        custom_data: data.controller_custom_data,
+        notes: data.controller_notes,
+        mapping_snapshots: data.controller_mapping_snapshots,
+        state: data.controller,
+    };
+    let main = SessionCompartmentData {
+        active_preset_id: data.active_main_preset_id,
+        default_group: data.default_group,
+        groups: data.groups,
+        mappings: data.mappings,
+        parameters: data.parameters,
+        custom_data: Default::default(),
+        notes: data.main_notes,
+        mapping_snapshots: data.mapping_snapshots,
+        state: data.main,
+    };
+    let session = persistence::Session {
+        id: data.id,
+        control_input: style.required_value(convert_control_input(data.control_device_id)?),
+        feedback_output: data
+            .feedback_device_id
+            .map(convert_feedback_output)
+            .transpose()?,
+        let_matched_events_through: style.required_value_with_default(
+            data.let_matched_events_through,
+            session_defaults::LET_MATCHED_EVENTS_THROUGH,
+        ),
+        let_unmatched_events_through: style.required_value_with_default(
+            data.let_unmatched_events_through,
+            session_defaults::LET_UNMATCHED_EVENTS_THROUGH,
+        ),
+        stay_active_when_project_in_background: {
+            // Sessions saved before this setting was introduced behaved like "Always"
+            let v = data
+                .stay_active_when_project_in_background
+                .unwrap_or(StayActiveWhenProjectInBackground::Always);
+            style.required_value(convert_stay_active_when_project_in_background(v))
+        },
+        auto_correct_settings: style.required_value_with_default(
+            data.always_auto_detect_mode,
+            session_defaults::AUTO_CORRECT_SETTINGS,
+        ),
+        lives_on_upper_floor: style.required_value_with_default(
+            data.lives_on_upper_floor,
+            session_defaults::LIVES_ON_UPPER_FLOOR,
+        ),
+        send_feedback_only_if_armed: style.required_value_with_default(
+            data.send_feedback_only_if_armed,
+            session_defaults::SEND_FEEDBACK_ONLY_IF_ARMED,
+        ),
+        reset_feedback_when_releasing_source: style.required_value_with_default(
+            data.reset_feedback_when_releasing_source,
+            session_defaults::RESET_FEEDBACK_WHEN_RELEASING_SOURCE,
+        ),
+        speech: style.required_value(persistence::SpeechSettings {
+            voice: Some(data.speech_voice).filter(|v| !v.is_empty()),
+            rate: data.speech_rate,
+            queue_policy: style.required_value(data.speech_queue_policy),
+        }),
+        main_preset_auto_load_mode: style.required_value(convert_main_preset_auto_load_mode(
+            data.main_preset_auto_load_mode,
+        )),
+        instance_track: style.required_value(data.instance_track),
+        instance_fx: style.required_value_with_default(
+            data.instance_fx,
+            session_defaults::INSTANCE_FX_DESCRIPTOR,
+        ),
+        tags: convert_tags(&data.tags, style),
+        active_instance_tags: convert_tag_set(&data.active_instance_tags, style),
+        instance_preset_links: style.required_value(
+            data.instance_preset_link_config
+                .links()
+                .map(convert_preset_link)
+                .collect(),
+        ),
+        use_instance_preset_links_only: style.required_value(data.use_instance_preset_links_only),
+        controller: Some(convert_session_compartment(controller, style)?),
+        main: Some(convert_session_compartment(main, style)?),
+        clip_matrix: data.clip_matrix.map(convert_clip_matrix_ref),
+        memorized_main_compartment: data
+            .memorized_main_compartment
+            .map(|c| convert_compartment(c, style))
+            .transpose()?,
+    };
+    Ok(session)
+}
+
+/// The parts of the session data which belong to one compartment.
+struct SessionCompartmentData {
+    active_preset_id: Option<String>,
+    default_group: Option<GroupModelData>,
+    groups: Vec<GroupModelData>,
+    mappings: Vec<MappingModelData>,
+    parameters: HashMap<String, ParameterData>,
+    custom_data: HashMap<String, serde_json::Value>,
+    notes: String,
+    mapping_snapshots: Vec<MappingSnapshot>,
+    state: CompartmentState,
+}
+
+fn convert_session_compartment(
+    data: SessionCompartmentData,
+    style: ConversionStyle,
+) -> ConversionResult<persistence::SessionCompartment> {
+    let parameter_values = {
+        let v: Result<Vec<_>, _> = data
+            .parameters
+            .iter()
+            .filter(|(_, p)| p.value != 0.0)
+            .map(|(index, p)| convert_parameter_value(index, p))
+            .collect();
+        let mut v = v?;
+        v.sort_by_key(|p| p.index);
+        style.required_value(v)
+    };
+    let compartment_data = CompartmentModelData {
+        default_group: data.default_group,
+        groups: data.groups,
+        mappings: data.mappings,
+        parameters: data
+            .parameters
+            .into_iter()
+            .map(|(index, p)| (index, p.setting))
+            .collect(),
+        custom_data: data.custom_data,
+        notes: data.notes,
+    };
+    let session_compartment = persistence::SessionCompartment {
+        active_preset_id: data.active_preset_id,
+        compartment: Some(convert_compartment(compartment_data, style)?),
+        parameter_values,
+        mapping_snapshots: style.required_value(data.mapping_snapshots),
+        active_mapping_tags: convert_tag_set(&data.state.active_mapping_tags, style),
+        active_mapping_snapshots: {
+            let mut v: Vec<_> = data
+                .state
+                .active_mapping_snapshots
+                .iter()
+                .map(|(tag, id)| persistence::ActiveMappingSnapshot {
+                    tag: tag.to_string(),
+                    id: id.to_string(),
+                })
+                .collect();
+            v.sort_by(|a, b| a.tag.cmp(&b.tag));
+            style.required_value(v)
+        },
+    };
+    Ok(session_compartment)
+}
+
+/// Sorts the tags in order to get a deterministic result.
+fn convert_tag_set(tags: &HashSet<Tag>, style: ConversionStyle) -> Option<Vec<String>> {
+    let mut tags: Vec<_> = tags.iter().map(|t| t.to_string()).collect();
+    tags.sort();
+    style.required_value(tags)
+}
+
+fn convert_preset_link(link: &FxPresetLink) -> persistence::PresetLink {
+    fn non_empty(s: &str) -> Option<String> {
+        if s.is_empty() {
+            None
+        } else {
+            Some(s.to_owned())
+        }
+    }
+    persistence::PresetLink {
+        fx: persistence::PresetLinkFx {
+            name: non_empty(link.fx_id.name()),
+            file_name: non_empty(link.fx_id.file_name()),
+            preset_name: non_empty(link.fx_id.preset_name()),
+        },
+        preset_id: link.preset_id.clone(),
+    }
+}
+
+fn convert_parameter_value(
+    index: &str,
+    data: &ParameterData,
+) -> ConversionResult<persistence::ParameterValue> {
+    let v = persistence::ParameterValue {
+        index: index.parse()?,
+        value: data.value as f64,
+    };
+    Ok(v)
+}
+
+fn convert_control_input(
+    data: Option<ControlDeviceId>,
+) -> ConversionResult<persistence::ControlInput> {
+    use persistence::ControlInput as T;
+    let res = match data {
+        None => T::FxInput,
+        Some(ControlDeviceId::Keyboard(_)) => T::Keyboard,
+        Some(ControlDeviceId::Midi(id)) => T::MidiDevice { id: id.parse()? },
+        Some(ControlDeviceId::Osc(id)) => T::OscDevice { id: id.to_string() },
+    };
+    Ok(res)
+}
+
+fn convert_feedback_output(
+    data: FeedbackDeviceId,
+) -> ConversionResult<persistence::FeedbackOutput> {
+    use persistence::FeedbackOutput as T;
+    let res = match data {
+        FeedbackDeviceId::MidiOrFxOutput(s) if s == "fx-output" => T::FxOutput,
+        FeedbackDeviceId::MidiOrFxOutput(id) => T::MidiDevice { id: id.parse()? },
+        FeedbackDeviceId::Osc(id) => T::OscDevice { id: id.to_string() },
+    };
+    Ok(res)
+}
+
+fn convert_stay_active_when_project_in_background(
+    v: StayActiveWhenProjectInBackground,
+) -> persistence::StayActiveWhenProjectInBackground {
+    use persistence::StayActiveWhenProjectInBackground as T;
+    use StayActiveWhenProjectInBackground::*;
+    match v {
+        Never => T::Never,
+        OnlyIfBackgroundProjectIsRunning => T::OnlyIfBackgroundProjectIsRunning,
+        Always => T::Always,
+    }
+}
+
+fn convert_main_preset_auto_load_mode(
+    v: MainPresetAutoLoadMode,
+) -> persistence::MainPresetAutoLoadMode {
+    use persistence::MainPresetAutoLoadMode as T;
+    use MainPresetAutoLoadMode::*;
+    match v {
+        Off => T::Off,
+        InstanceFx => T::InstanceFx,
+    }
+}
+
+fn convert_clip_matrix_ref(data: ClipMatrixRefData) -> persistence::ClipMatrixRef {
+    use persistence::ClipMatrixRef as T;
+    match data {
+        ClipMatrixRefData::Own(m) => T::Own(Box::new(m)),
+        ClipMatrixRefData::Foreign(session_id) => T::Foreign { session_id },
+    }
+}
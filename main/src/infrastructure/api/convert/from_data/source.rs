@@ -1,4 +1,6 @@
-use crate::application::{MidiSourceType, ReaperSourceType, SourceCategory};
+use crate::application::{
+    MidiSourceType, ReaperSourceType, SourceCategory, VirtualControlElementType,
+};
 use crate::infrastructure::api::convert::from_data::{
     convert_control_element_id, convert_control_element_kind, convert_keystroke,
     convert_osc_argument, ConversionStyle,
@@ -135,9 +137,30 @@ pub fn convert_source(
                     let s = persistence::MidiScriptSource {
                         kind: style.required_value(data.midi_script_kind),
                         script: style.required_value(data.midi_script),
+                        control_script: style.required_value(data.midi_control_script),
                     };
                     persistence::Source::MidiScript(s)
                 }
+                Mpe => {
+                    let s = persistence::MidiMpeSource {
+                        zone: style.required_value(data.mpe_zone),
+                        member_channel_count: data.mpe_member_channel_count,
+                        dimension: style.required_value(data.mpe_dimension),
+                        note: style.required_value(data.mpe_note_selector),
+                        held_note_index: style.required_value(data.mpe_held_note_index),
+                    };
+                    persistence::Source::MidiMpe(s)
+                }
+                Chord => {
+                    let s = persistence::MidiChordSource {
+                        channel: convert_channel(data.channel),
+                        notes: style
+                            .required_value(data.chord_notes.iter().map(|n| n.get()).collect()),
+                        mode: style.required_value(data.chord_mode),
+                        time_window: style.required_value(data.chord_time_window_millis),
+                    };
+                    persistence::Source::MidiChord(s)
+                }
                 Display => {
                     use DisplayType::*;
                     match data.display_type {
@@ -227,6 +250,7 @@ pub fn convert_source(
                     data.osc_arg_value_range,
                     style,
                 ),
+                additional_argument_indexes: style.required_value(data.osc_additional_arg_indexes),
                 relative: style.required_value_with_default(
                     data.osc_arg_is_relative,
                     defaults::SOURCE_OSC_IS_RELATIVE,
@@ -247,20 +271,56 @@ pub fn convert_source(
                 Timer => persistence::Source::Timer(persistence::TimerSource {
                     duration: data.timer_millis,
                 }),
+                MusicalTimer => {
+                    persistence::Source::MusicalTimer(persistence::MusicalTimerSource {
+                        trigger: style.required_value(data.musical_timer_trigger),
+                        interval: data.musical_timer_interval,
+                        bars: style.required_value(data.musical_timer_bars),
+                        only_while_playing: style
+                            .required_value(data.musical_timer_only_while_playing),
+                        output: style.required_value(data.musical_timer_output),
+                    })
+                }
+                AudioLevel => persistence::Source::AudioLevel(persistence::AudioLevelSource {
+                    input: style.required_value(data.audio_level_input),
+                    index: data.audio_level_index,
+                    measurement: style.required_value(data.audio_level_measurement),
+                    attack: data.audio_level_attack_millis,
+                    release: data.audio_level_release_millis,
+                    onset_threshold: data.audio_level_onset_threshold_db,
+                }),
                 RealearnParameter => {
                     persistence::Source::RealearnParameter(persistence::RealearnParameterSource {
                         parameter_index: data.parameter_index.get(),
                     })
                 }
-                Speech => persistence::Source::Speech(persistence::SpeechSource {}),
+                Speech => persistence::Source::Speech(persistence::SpeechSource {
+                    voice: Some(data.speech_voice).filter(|v| !v.is_empty()),
+                    rate: data.speech_rate,
+                    queue_policy: data.speech_queue_policy,
+                }),
             }
         }
         Virtual => {
-            let s = persistence::VirtualSource {
-                id: convert_control_element_id(data.control_element_index),
-                character: convert_control_element_kind(data.control_element_type, style),
-            };
-            persistence::Source::Virtual(s)
+            if data.control_element_type == VirtualControlElementType::Button
+                && !data.chord_control_element_ids.is_empty()
+            {
+                let s = persistence::VirtualChordSource {
+                    ids: std::iter::once(data.control_element_index)
+                        .chain(data.chord_control_element_ids)
+                        .map(convert_control_element_id)
+                        .collect(),
+                    mode: style.required_value(data.chord_mode),
+                    time_window: style.required_value(data.chord_time_window_millis),
+                };
+                persistence::Source::VirtualChord(s)
+            } else {
+                let s = persistence::VirtualSource {
+                    id: convert_control_element_id(data.control_element_index),
+                    character: convert_control_element_kind(data.control_element_type, style),
+                };
+                persistence::Source::Virtual(s)
+            }
         }
         Keyboard => {
             let s = persistence::KeySource {
@@ -1,7 +1,7 @@
 use crate::application::{
-    AutomationModeOverrideType, BookmarkAnchorType, RealearnAutomationMode, RealearnTrackArea,
-    TargetCategory, TargetUnit, TrackRouteSelectorType, VirtualFxParameterType, VirtualFxType,
-    VirtualTrackType,
+    AutomationModeOverrideType, BookmarkAnchorType, EnvelopeType, RealearnAutomationMode,
+    RealearnTrackArea, TargetCategory, TargetUnit, TrackRouteSelectorType, VirtualFxParameterType,
+    VirtualFxType, VirtualItemType, VirtualTrackType,
 };
 use crate::domain::{
     ActionInvocationType, AnyOnParameter, Exclusivity, FeedbackResolution, FxDisplayType,
@@ -10,7 +10,7 @@ use crate::domain::{
 };
 use crate::infrastructure::api::convert::from_data::{
     convert_control_element_id, convert_control_element_kind, convert_osc_argument, convert_tags,
-    ConversionStyle,
+    convert_xy_axis, ConversionStyle,
 };
 use crate::infrastructure::api::convert::{defaults, ConversionResult};
 use crate::infrastructure::data::{
@@ -21,22 +21,26 @@ use realearn_api::persistence;
 use realearn_api::persistence::{
     AllTrackFxOnOffStateTarget, AnyOnTarget, AutomationModeOverrideTarget,
     BackwardCompatibleMappingSnapshotDescForTake, BookmarkDescriptor, BookmarkRef,
-    BrowseFxChainTarget, BrowseFxPresetsTarget, BrowseGroupMappingsTarget,
+    BrowseAutomationItemsTarget, BrowseFxChainTarget, BrowseFxPresetsTarget,
+    BrowseGroupMappingsTarget, BrowseItemTakesTarget, BrowseItemsTarget,
     BrowsePotFilterItemsTarget, BrowsePotPresetsTarget, BrowseTracksTarget, ClipColumnDescriptor,
     ClipColumnTarget, ClipManagementTarget, ClipMatrixTarget, ClipRowTarget, ClipSeekTarget,
     ClipTransportActionTarget, ClipVolumeTarget, DummyTarget, EnableInstancesTarget,
-    EnableMappingsTarget, FxOnOffStateTarget, FxOnlineOfflineStateTarget,
+    EnableMappingsTarget, EnvelopeArmStateTarget, EnvelopeVisibilityTarget,
+    EnvelopeWriteValueTarget, FxOnOffStateTarget, FxOnlineOfflineStateTarget,
     FxParameterAutomationTouchStateTarget, FxParameterValueTarget, FxToolTarget,
-    FxVisibilityTarget, GoToBookmarkTarget, LastTouchedTarget, LoadFxSnapshotTarget,
-    LoadMappingSnapshotTarget, LoadPotPresetTarget, MouseTarget, PlayRateTarget,
-    PreviewPotPresetTarget, ReaperActionTarget, RouteAutomationModeTarget, RouteMonoStateTarget,
-    RouteMuteStateTarget, RoutePanTarget, RoutePhaseTarget, RouteTouchStateTarget,
-    RouteVolumeTarget, SeekTarget, SendMidiTarget, SendOscTarget, TakeMappingSnapshotTarget,
-    TempoTarget, TrackArmStateTarget, TrackAutomationModeTarget, TrackAutomationTouchStateTarget,
-    TrackMonitoringModeTarget, TrackMuteStateTarget, TrackPanTarget, TrackParentSendStateTarget,
-    TrackPeakTarget, TrackPhaseTarget, TrackSelectionStateTarget, TrackSoloStateTarget,
-    TrackToolTarget, TrackVisibilityTarget, TrackVolumeTarget, TrackWidthTarget,
-    TransportActionTarget,
+    FxVisibilityTarget, GoToBookmarkTarget, ItemLockStateTarget, ItemMuteStateTarget,
+    ItemPositionTarget, ItemSelectionStateTarget, ItemVolumeTarget, LastTouchedTarget,
+    LoadFxSnapshotTarget, LoadMappingSnapshotTarget, LoadPotPresetTarget, MarkerManagementTarget,
+    MouseTarget, PlayRateTarget, PreviewPotPresetTarget, ReaperActionTarget,
+    RouteAutomationModeTarget, RouteMonoStateTarget, RouteMuteStateTarget, RoutePanTarget,
+    RoutePhaseTarget, RouteTouchStateTarget, RouteVolumeTarget, SeekTarget, SendMidiTarget,
+    SendOscTarget, TakeFxParameterValueTarget, TakeMappingSnapshotTarget, TakePitchTarget,
+    TakePlayRateTarget, TakeVolumeTarget, TempoTarget, TimeRangeTarget, TrackArmStateTarget,
+    TrackAutomationModeTarget, TrackAutomationTouchStateTarget, TrackMonitoringModeTarget,
+    TrackMuteStateTarget, TrackPanTarget, TrackParentSendStateTarget, TrackPeakTarget,
+    TrackPhaseTarget, TrackSelectionStateTarget, TrackSoloStateTarget, TrackToolTarget,
+    TrackVisibilityTarget, TrackVolumeTarget, TrackWidthTarget, TransportActionTarget,
 };
 
 pub fn convert_target(
@@ -276,6 +280,100 @@ fn convert_real_target(
             },
             route: convert_route_descriptor(data, style),
         }),
+        EnvelopeArm => T::EnvelopeArmState(EnvelopeArmStateTarget {
+            commons,
+            envelope: convert_envelope_descriptor(data, style),
+        }),
+        EnvelopeShow => T::EnvelopeVisibility(EnvelopeVisibilityTarget {
+            commons,
+            envelope: convert_envelope_descriptor(data, style),
+        }),
+        EnvelopeWriteValue => T::EnvelopeWriteValue(EnvelopeWriteValueTarget {
+            commons,
+            envelope: convert_envelope_descriptor(data, style),
+        }),
+        BrowseAutomationItems => T::BrowseAutomationItems(BrowseAutomationItemsTarget {
+            commons,
+            envelope: convert_envelope_descriptor(data, style),
+        }),
+        ItemVolume => T::ItemVolume(ItemVolumeTarget {
+            commons,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            item: convert_item_descriptor(data, style),
+        }),
+        ItemMute => T::ItemMuteState(ItemMuteStateTarget {
+            commons,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            item: convert_item_descriptor(data, style),
+        }),
+        ItemLock => T::ItemLockState(ItemLockStateTarget {
+            commons,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            item: convert_item_descriptor(data, style),
+        }),
+        ItemSelection => T::ItemSelectionState(ItemSelectionStateTarget {
+            commons,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            item: convert_item_descriptor(data, style),
+        }),
+        ItemPosition => T::ItemPosition(ItemPositionTarget {
+            commons,
+            item: convert_item_descriptor(data, style),
+        }),
+        ItemBrowseTakes => T::BrowseItemTakes(BrowseItemTakesTarget {
+            commons,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            item: convert_item_descriptor(data, style),
+        }),
+        TakeVolume => T::TakeVolume(TakeVolumeTarget {
+            commons,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            item: convert_item_descriptor(data, style),
+        }),
+        TakePitch => T::TakePitch(TakePitchTarget {
+            commons,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            item: convert_item_descriptor(data, style),
+        }),
+        TakePlayrate => T::TakePlayRate(TakePlayRateTarget {
+            commons,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            item: convert_item_descriptor(data, style),
+        }),
+        TakeFxParameter => T::TakeFxParameterValue(TakeFxParameterValueTarget {
+            commons,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            fx_index: data.take_fx_index,
+            parameter_index: data.take_fx_param_index,
+            item: convert_item_descriptor(data, style),
+        }),
         ClipTransport => T::ClipTransportAction(ClipTransportActionTarget {
             commons,
             slot: data.clip_slot.unwrap_or_default(),
@@ -380,6 +478,25 @@ fn convert_real_target(
             ),
             behavior: style.optional_value(data.seek_behavior),
         }),
+        MarkerManagement => T::MarkerManagement(MarkerManagementTarget {
+            commons,
+            action: style.required_value(data.marker_management_action),
+            name: style.required_value(data.marker_name),
+        }),
+        TimeRange => T::TimeRange(TimeRangeTarget {
+            commons,
+            range: style.required_value(data.time_range_kind),
+            action: style.required_value(data.time_range_action),
+            quantization: style.required_value(data.time_range_quantization),
+            region_index: style.required_value_with_default(
+                data.time_range_region_index,
+                defaults::TARGET_TIME_RANGE_REGION_INDEX,
+            ),
+            bar_count: style.required_value_with_default(
+                data.time_range_bar_count,
+                defaults::TARGET_TIME_RANGE_BAR_COUNT,
+            ),
+        }),
         PlayRate => T::PlayRate(PlayRateTarget { commons }),
         Tempo => T::Tempo(TempoTarget { commons }),
         TrackArm => T::TrackArmState(TrackArmStateTarget {
@@ -581,6 +698,23 @@ fn convert_real_target(
                 defaults::TARGET_USE_SELECTION_GANGING,
             ),
         }),
+        BrowseItems => T::BrowseItems(BrowseItemsTarget {
+            commons,
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+            scroll_arrange_view: style.required_value_with_default(
+                data.scroll_arrange_view,
+                defaults::TARGET_BROWSE_ITEMS_SCROLL_ARRANGE_VIEW,
+            ),
+            move_edit_cursor: style.required_value_with_default(
+                data.move_edit_cursor,
+                defaults::TARGET_BROWSE_ITEMS_MOVE_EDIT_CURSOR,
+            ),
+        }),
         FxTool => T::FxTool(FxToolTarget {
             commons,
             action: Some(data.fx_tool_action),
@@ -806,6 +940,7 @@ fn convert_virtual_target(data: TargetModelData, style: ConversionStyle) -> pers
     persistence::Target::Virtual(persistence::VirtualTarget {
         id: convert_control_element_id(data.control_element_index),
         character: convert_control_element_kind(data.control_element_type, style),
+        axis: convert_xy_axis(data.control_element_axis, style),
     })
 }
 
@@ -964,6 +1099,69 @@ fn convert_route_descriptor(
     }
 }
 
+fn convert_envelope_descriptor(
+    data: TargetModelData,
+    style: ConversionStyle,
+) -> persistence::EnvelopeDescriptor {
+    use persistence::EnvelopeDescriptor as T;
+    match data.envelope_type {
+        EnvelopeType::Track => T::Track {
+            kind: style.required_value(data.track_envelope_kind),
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+        },
+        EnvelopeType::FxParameter => T::FxParameter {
+            parameter: convert_fx_parameter_descriptor(data, style),
+        },
+        EnvelopeType::Route => T::Route {
+            kind: style.required_value(data.route_envelope_kind),
+            route: convert_route_descriptor(data, style),
+        },
+    }
+}
+
+fn convert_item_descriptor(
+    data: TargetModelData,
+    style: ConversionStyle,
+) -> persistence::ItemDescriptor {
+    use persistence::ItemDescriptor as T;
+    use VirtualItemType::*;
+    match data.item_type {
+        Selected | AllSelected => T::Selected {
+            allow_multiple: style.required_value(data.item_type == AllSelected),
+        },
+        AtEditCursor => T::AtEditCursor {
+            commons: convert_item_descriptor_commons(data, style),
+        },
+        Dynamic => T::Dynamic {
+            expression: data.item_expression.clone(),
+            commons: convert_item_descriptor_commons(data, style),
+        },
+        ByIndex => T::ByIndex {
+            index: data.item_index,
+            commons: convert_item_descriptor_commons(data, style),
+        },
+    }
+}
+
+fn convert_item_descriptor_commons(
+    data: TargetModelData,
+    style: ConversionStyle,
+) -> persistence::ItemDescriptorCommons {
+    persistence::ItemDescriptorCommons {
+        track: convert_track_descriptor(
+            data.track_data,
+            data.enable_only_if_track_is_selected,
+            &data.clip_column,
+            style,
+        ),
+    }
+}
+
 fn convert_fx_descriptor(
     data: TargetModelData,
     style: ConversionStyle,
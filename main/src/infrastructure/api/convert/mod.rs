@@ -10,3 +10,109 @@ fn convert_multiple<A, B>(
 ) -> ConversionResult<Vec<B>> {
     input.into_iter().map(f).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{FxId, FxPresetLinkConfig, PresetLinkMutator};
+    use crate::domain::{MappingSnapshotId, Tag};
+    use crate::infrastructure::data::{
+        CompartmentModelData, CompartmentState, ParameterData, SessionData,
+    };
+    use realearn_api::persistence::SpeechQueuePolicy;
+    use semver::Version;
+    use std::str::FromStr;
+
+    #[test]
+    fn session_round_trip() {
+        // Given
+        let version = Version::parse("2.14.0-pre.1").unwrap();
+        let mut preset_link_config = FxPresetLinkConfig::default();
+        preset_link_config.link_preset_to_fx(
+            "my-preset".to_owned(),
+            FxId {
+                name: "ReaEQ*".to_owned(),
+                ..Default::default()
+            },
+        );
+        let main_state = CompartmentState {
+            active_mapping_by_group: Default::default(),
+            active_mapping_tags: [tag("a"), tag("b")].into_iter().collect(),
+            active_mapping_snapshots: [(tag("scene"), MappingSnapshotId::from_str("s1").unwrap())]
+                .into_iter()
+                .collect(),
+        };
+        let controller_state = CompartmentState {
+            active_mapping_by_group: Default::default(),
+            active_mapping_tags: [tag("c")].into_iter().collect(),
+            active_mapping_snapshots: Default::default(),
+        };
+        let data = SessionData {
+            version: Some(version.clone()),
+            id: Some("instance-1".to_owned()),
+            let_matched_events_through: true,
+            speech_voice: "en-us".to_owned(),
+            speech_rate: Some(150),
+            speech_queue_policy: SpeechQueuePolicy::Enqueue,
+            tags: vec![tag("drums")],
+            parameters: [(
+                "3".to_owned(),
+                ParameterData {
+                    setting: Default::default(),
+                    value: 0.5,
+                },
+            )]
+            .into_iter()
+            .collect(),
+            controller: controller_state.clone(),
+            main: main_state.clone(),
+            active_instance_tags: [tag("live")].into_iter().collect(),
+            instance_preset_link_config: preset_link_config.clone(),
+            use_instance_preset_links_only: true,
+            memorized_main_compartment: Some(CompartmentModelData {
+                default_group: None,
+                groups: vec![],
+                mappings: vec![],
+                parameters: Default::default(),
+                custom_data: Default::default(),
+                notes: "memorized".to_owned(),
+            }),
+            ..Default::default()
+        };
+        // When
+        let api_session =
+            from_data::convert_session(data, from_data::ConversionStyle::Minimal).unwrap();
+        let data = to_data::convert_session(api_session, version.clone()).unwrap();
+        // Then
+        assert_eq!(data.version, Some(version));
+        assert_eq!(data.id.as_deref(), Some("instance-1"));
+        assert!(data.let_matched_events_through);
+        assert_eq!(data.speech_voice, "en-us");
+        assert_eq!(data.speech_rate, Some(150));
+        assert_eq!(data.speech_queue_policy, SpeechQueuePolicy::Enqueue);
+        assert_eq!(data.tags, vec![tag("drums")]);
+        assert_eq!(
+            data.parameters["3"],
+            ParameterData {
+                setting: Default::default(),
+                value: 0.5
+            }
+        );
+        assert_eq!(data.main, main_state);
+        assert_eq!(data.controller, controller_state);
+        assert_eq!(
+            data.active_instance_tags,
+            [tag("live")].into_iter().collect()
+        );
+        assert_eq!(data.instance_preset_link_config, preset_link_config);
+        assert!(data.use_instance_preset_links_only);
+        assert_eq!(
+            data.memorized_main_compartment.unwrap().notes,
+            "memorized".to_owned()
+        );
+    }
+
+    fn tag(name: &str) -> Tag {
+        Tag::from_str(name).unwrap()
+    }
+}
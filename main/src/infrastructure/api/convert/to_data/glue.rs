@@ -1,3 +1,4 @@
+use crate::application::ModeModel;
 use crate::infrastructure::api::convert::defaults;
 use crate::infrastructure::api::convert::ConversionResult;
 use crate::infrastructure::data::ModeModelData;
@@ -21,6 +22,10 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
             .unwrap_or(defaults::GLUE_STEP_FACTOR_INTERVAL),
     )?;
     let fire_mode = g.fire_mode.unwrap_or_default();
+    let (control_transformation_language, control_transformation) = g
+        .control_transformation
+        .map(Transformation::into_parts)
+        .unwrap_or_default();
     struct FbCommonsData {
         color: Option<helgoboss_learn::VirtualColor>,
         background_color: Option<helgoboss_learn::VirtualColor>,
@@ -29,6 +34,7 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
         feedback_type: helgoboss_learn::FeedbackType,
         commons: FbCommonsData,
         transformation: String,
+        transformation_language: TransformationLanguage,
     }
     let fb_data = {
         use helgoboss_learn::FeedbackType as T;
@@ -40,15 +46,23 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
             }
         }
         match g.feedback.unwrap_or_default() {
-            Numeric(fb) => FbData {
-                feedback_type: T::Numerical,
-                commons: convert_fb_commons(fb.commons),
-                transformation: fb.transformation.unwrap_or_default(),
-            },
+            Numeric(fb) => {
+                let (language, script) = fb
+                    .transformation
+                    .map(Transformation::into_parts)
+                    .unwrap_or_default();
+                FbData {
+                    feedback_type: T::Numerical,
+                    commons: convert_fb_commons(fb.commons),
+                    transformation: script,
+                    transformation_language: language,
+                }
+            }
             Text(fb) => FbData {
                 feedback_type: T::Textual,
                 commons: convert_fb_commons(fb.commons),
                 transformation: fb.text_expression.unwrap_or_default(),
+                transformation_language: Default::default(),
             },
         }
     };
@@ -114,7 +128,9 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
                 _ => 0,
             }
         },
-        eel_control_transformation: g.control_transformation.unwrap_or_default(),
+        control_transformation_language,
+        feedback_transformation_language: fb_data.transformation_language,
+        eel_control_transformation: control_transformation,
         eel_feedback_transformation: fb_data.transformation,
         reverse_is_enabled: g.reverse.unwrap_or(defaults::GLUE_REVERSE),
         feedback_color: fb_data.commons.color,
@@ -202,6 +218,8 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
         } else {
             Default::default()
         },
+        gesture: g.gesture.unwrap_or_default(),
+        gesture_tap_count: g.tap_count.unwrap_or(ModeModel::DEFAULT_GESTURE_TAP_COUNT),
         feedback_type: fb_data.feedback_type,
         feedback_value_table: g.feedback_value_table,
     };
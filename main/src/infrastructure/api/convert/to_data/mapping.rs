@@ -51,6 +51,7 @@ pub fn convert_mapping(
         source: convert_source(m.source.unwrap_or_default())?,
         mode: convert_glue(m.glue.unwrap_or_default())?,
         target: convert_target(m.target.unwrap_or_default())?,
+        y_target: m.y_target.map(convert_target).transpose()?,
         is_enabled: m.enabled.unwrap_or(defaults::MAPPING_ENABLED),
         enabled_data: {
             EnabledData {
@@ -6,12 +6,15 @@ use crate::infrastructure::data::{ActivationConditionData, OscValueRange};
 use crate::{application, domain};
 pub use compartment::*;
 use enumflags2::BitFlags;
+pub use group::*;
 pub use mapping::*;
+pub use parameter::*;
 use realearn_api::persistence::{
     ActivationCondition, Interval, Keystroke, ModifierState, OscArgKind, ParamRef,
-    VirtualControlElementCharacter, VirtualControlElementId,
+    VirtualControlElementCharacter, VirtualControlElementId, XyAxis,
 };
 use reaper_medium::AcceleratorKeyCode;
+pub use session::*;
 use source::*;
 
 mod compartment;
@@ -19,6 +22,7 @@ mod glue;
 mod group;
 mod mapping;
 mod parameter;
+mod session;
 mod source;
 mod target;
 
@@ -30,6 +34,14 @@ fn convert_control_element_type(
     match s {
         Multi => T::Multi,
         Button => T::Button,
+        Xy => T::Xy,
+    }
+}
+
+fn convert_xy_axis(s: XyAxis) -> domain::XyAxis {
+    match s {
+        XyAxis::X => domain::XyAxis::X,
+        XyAxis::Y => domain::XyAxis::Y,
     }
 }
 
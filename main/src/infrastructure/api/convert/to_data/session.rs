@@ -0,0 +1,219 @@
+use crate::application::{session_defaults, FxId, FxPresetLinkConfig, PresetLinkMutator};
+use crate::domain::{MappingSnapshotId, OscDeviceId, Tag};
+use crate::infrastructure::api::convert::to_data::{convert_compartment, convert_tags};
+use crate::infrastructure::api::convert::ConversionResult;
+use crate::infrastructure::data::{
+    ClipMatrixRefData, CompartmentState, ControlDeviceId, FeedbackDeviceId, KeyboardDevice,
+    ParameterData, SessionData,
+};
+use crate::{application, domain};
+use realearn_api::persistence::*;
+use semver::Version;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// Converts the given API session to session data.
+///
+/// The version is the ReaLearn version with which the API session has been created. It's important
+/// for applying the correct migrations when loading the session data.
+pub fn convert_session(s: Session, version: Version) -> ConversionResult<SessionData> {
+    let controller = s.controller.unwrap_or_default();
+    let main = s.main.unwrap_or_default();
+    let speech = s.speech.unwrap_or_default();
+    let controller_state = convert_compartment_state(&controller)?;
+    let main_state = convert_compartment_state(&main)?;
+    let controller_compartment = convert_compartment(controller.compartment.unwrap_or_default())?;
+    let main_compartment = convert_compartment(main.compartment.unwrap_or_default())?;
+    let data = SessionData {
+        version: Some(version),
+        id: s.id,
+        let_matched_events_through: s
+            .let_matched_events_through
+            .unwrap_or(session_defaults::LET_MATCHED_EVENTS_THROUGH),
+        let_unmatched_events_through: s
+            .let_unmatched_events_through
+            .unwrap_or(session_defaults::LET_UNMATCHED_EVENTS_THROUGH),
+        stay_active_when_project_in_background: Some(
+            s.stay_active_when_project_in_background
+                .map(convert_stay_active_when_project_in_background)
+                .unwrap_or(session_defaults::STAY_ACTIVE_WHEN_PROJECT_IN_BACKGROUND),
+        ),
+        always_auto_detect_mode: s
+            .auto_correct_settings
+            .unwrap_or(session_defaults::AUTO_CORRECT_SETTINGS),
+        lives_on_upper_floor: s
+            .lives_on_upper_floor
+            .unwrap_or(session_defaults::LIVES_ON_UPPER_FLOOR),
+        send_feedback_only_if_armed: s
+            .send_feedback_only_if_armed
+            .unwrap_or(session_defaults::SEND_FEEDBACK_ONLY_IF_ARMED),
+        reset_feedback_when_releasing_source: s
+            .reset_feedback_when_releasing_source
+            .unwrap_or(session_defaults::RESET_FEEDBACK_WHEN_RELEASING_SOURCE),
+        speech_voice: speech.voice.unwrap_or_default(),
+        speech_rate: speech.rate,
+        speech_queue_policy: speech.queue_policy.unwrap_or_default(),
+        control_device_id: convert_control_input(s.control_input.unwrap_or_default())?,
+        feedback_device_id: s.feedback_output.map(convert_feedback_output).transpose()?,
+        default_group: main_compartment.default_group,
+        groups: main_compartment.groups,
+        default_controller_group: controller_compartment.default_group,
+        controller_groups: controller_compartment.groups,
+        mappings: main_compartment.mappings,
+        controller_mappings: controller_compartment.mappings,
+        controller_custom_data: controller_compartment.custom_data,
+        controller_notes: controller_compartment.notes,
+        main_notes: main_compartment.notes,
+        active_controller_id: controller.active_preset_id,
+        active_main_preset_id: main.active_preset_id,
+        main_preset_auto_load_mode: s
+            .main_preset_auto_load_mode
+            .map(convert_main_preset_auto_load_mode)
+            .unwrap_or(session_defaults::MAIN_PRESET_AUTO_LOAD_MODE),
+        parameters: convert_parameters(
+            main_compartment.parameters,
+            main.parameter_values.unwrap_or_default(),
+        )?,
+        controller_parameters: convert_parameters(
+            controller_compartment.parameters,
+            controller.parameter_values.unwrap_or_default(),
+        )?,
+        clip_matrix: s.clip_matrix.map(convert_clip_matrix_ref),
+        tags: convert_tags(s.tags.unwrap_or_default())?,
+        instance_track: s.instance_track.unwrap_or_default(),
+        instance_fx: s
+            .instance_fx
+            .unwrap_or(session_defaults::INSTANCE_FX_DESCRIPTOR),
+        mapping_snapshots: main.mapping_snapshots.unwrap_or_default(),
+        controller_mapping_snapshots: controller.mapping_snapshots.unwrap_or_default(),
+        controller: controller_state,
+        main: main_state,
+        active_instance_tags: convert_tag_set(s.active_instance_tags.unwrap_or_default())?,
+        instance_preset_link_config: convert_preset_links(
+            s.instance_preset_links.unwrap_or_default(),
+        ),
+        use_instance_preset_links_only: s.use_instance_preset_links_only.unwrap_or_default(),
+        memorized_main_compartment: s
+            .memorized_main_compartment
+            .map(convert_compartment)
+            .transpose()?,
+        ..Default::default()
+    };
+    Ok(data)
+}
+
+fn convert_compartment_state(c: &SessionCompartment) -> ConversionResult<CompartmentState> {
+    let state = CompartmentState {
+        // Not part of the API because it refers to technical IDs
+        active_mapping_by_group: Default::default(),
+        active_mapping_tags: convert_tag_set(c.active_mapping_tags.clone().unwrap_or_default())?,
+        active_mapping_snapshots: c
+            .active_mapping_snapshots
+            .iter()
+            .flatten()
+            .map(|s| -> ConversionResult<(Tag, MappingSnapshotId)> {
+                let tag = Tag::from_str(&s.tag)?;
+                let id = MappingSnapshotId::from_str(&s.id)?;
+                Ok((tag, id))
+            })
+            .collect::<ConversionResult<_>>()?,
+    };
+    Ok(state)
+}
+
+fn convert_tag_set(tags: Vec<String>) -> ConversionResult<HashSet<Tag>> {
+    let tags = convert_tags(tags)?;
+    Ok(tags.into_iter().collect())
+}
+
+fn convert_preset_links(links: Vec<PresetLink>) -> FxPresetLinkConfig {
+    let mut config = FxPresetLinkConfig::default();
+    for link in links {
+        let fx_id = FxId {
+            name: link.fx.name.unwrap_or_default(),
+            file_name: link.fx.file_name.unwrap_or_default(),
+            preset_name: link.fx.preset_name.unwrap_or_default(),
+        };
+        config.link_preset_to_fx(link.preset_id, fx_id);
+    }
+    config
+}
+
+/// Merges parameter settings and values into the session representation of parameters.
+fn convert_parameters(
+    settings: HashMap<String, domain::ParamSetting>,
+    values: Vec<ParameterValue>,
+) -> ConversionResult<HashMap<String, ParameterData>> {
+    let mut parameters: HashMap<String, ParameterData> = settings
+        .into_iter()
+        .map(|(index, setting)| {
+            (
+                index,
+                ParameterData {
+                    setting,
+                    value: 0.0,
+                },
+            )
+        })
+        .collect();
+    for v in values {
+        let index = domain::CompartmentParamIndex::try_from(v.index)?;
+        let p = parameters
+            .entry(index.to_string())
+            .or_insert_with(|| ParameterData {
+                setting: Default::default(),
+                value: 0.0,
+            });
+        p.value = v.value as f32;
+    }
+    Ok(parameters)
+}
+
+fn convert_control_input(s: ControlInput) -> ConversionResult<Option<ControlDeviceId>> {
+    let res = match s {
+        ControlInput::FxInput => None,
+        ControlInput::Keyboard => Some(ControlDeviceId::Keyboard(KeyboardDevice::TheKeyboard)),
+        ControlInput::MidiDevice { id } => Some(ControlDeviceId::Midi(id.to_string())),
+        ControlInput::OscDevice { id } => Some(ControlDeviceId::Osc(OscDeviceId::from_str(&id)?)),
+    };
+    Ok(res)
+}
+
+fn convert_feedback_output(s: FeedbackOutput) -> ConversionResult<FeedbackDeviceId> {
+    let res = match s {
+        FeedbackOutput::FxOutput => FeedbackDeviceId::MidiOrFxOutput("fx-output".to_owned()),
+        FeedbackOutput::MidiDevice { id } => FeedbackDeviceId::MidiOrFxOutput(id.to_string()),
+        FeedbackOutput::OscDevice { id } => FeedbackDeviceId::Osc(OscDeviceId::from_str(&id)?),
+    };
+    Ok(res)
+}
+
+fn convert_stay_active_when_project_in_background(
+    s: StayActiveWhenProjectInBackground,
+) -> domain::StayActiveWhenProjectInBackground {
+    use domain::StayActiveWhenProjectInBackground as T;
+    use StayActiveWhenProjectInBackground::*;
+    match s {
+        Never => T::Never,
+        OnlyIfBackgroundProjectIsRunning => T::OnlyIfBackgroundProjectIsRunning,
+        Always => T::Always,
+    }
+}
+
+fn convert_main_preset_auto_load_mode(
+    s: MainPresetAutoLoadMode,
+) -> application::MainPresetAutoLoadMode {
+    use application::MainPresetAutoLoadMode as T;
+    use MainPresetAutoLoadMode::*;
+    match s {
+        Off => T::Off,
+        InstanceFx => T::InstanceFx,
+    }
+}
+
+fn convert_clip_matrix_ref(s: ClipMatrixRef) -> ClipMatrixRefData {
+    match s {
+        ClipMatrixRef::Own(m) => ClipMatrixRefData::Own(*m),
+        ClipMatrixRef::Foreign { session_id } => ClipMatrixRefData::Foreign(session_id),
+    }
+}
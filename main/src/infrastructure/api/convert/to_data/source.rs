@@ -1,4 +1,6 @@
-use crate::application::{MidiSourceType, ReaperSourceType, SourceCategory};
+use crate::application::{
+    MidiSourceType, ReaperSourceType, SourceCategory, VirtualControlElementType,
+};
 use crate::infrastructure::api::convert::to_data::{
     convert_control_element_id, convert_control_element_type, convert_keystroke,
     convert_osc_arg_type, convert_osc_value_range,
@@ -48,6 +50,49 @@ pub fn convert_source(s: Source) -> ConversionResult<SourceModelData> {
             MidiScript(s) => s.script.as_ref().cloned().unwrap_or_default(),
             _ => Default::default(),
         },
+        midi_control_script: match &s {
+            MidiScript(s) => s.control_script.as_ref().cloned().unwrap_or_default(),
+            _ => Default::default(),
+        },
+        mpe_zone: match &s {
+            MidiMpe(s) => s.zone.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        mpe_member_channel_count: match &s {
+            MidiMpe(s) => s.member_channel_count,
+            _ => None,
+        },
+        mpe_dimension: match &s {
+            MidiMpe(s) => s.dimension.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        mpe_note_selector: match &s {
+            MidiMpe(s) => s.note.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        mpe_held_note_index: match &s {
+            MidiMpe(s) => s.held_note_index.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        chord_notes: match &s {
+            MidiChord(s) => s
+                .notes
+                .iter()
+                .flatten()
+                .map(|n| (*n).try_into())
+                .collect::<Result<_, _>>()?,
+            _ => Default::default(),
+        },
+        chord_mode: match &s {
+            MidiChord(s) => s.mode.unwrap_or_default(),
+            VirtualChord(s) => s.mode.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        chord_time_window_millis: match &s {
+            MidiChord(s) => s.time_window.unwrap_or_default(),
+            VirtualChord(s) => s.time_window.unwrap_or_default(),
+            _ => Default::default(),
+        },
         display_type: match &s {
             MackieLcd(s) => {
                 let extender_index = s
@@ -106,6 +151,14 @@ pub fn convert_source(s: Source) -> ConversionResult<SourceModelData> {
             Osc(s) => convert_osc_value_range(s.argument.and_then(|a| a.value_range)),
             _ => Default::default(),
         },
+        osc_additional_arg_indexes: match &s {
+            Osc(s) => s
+                .additional_argument_indexes
+                .as_ref()
+                .cloned()
+                .unwrap_or_default(),
+            _ => Default::default(),
+        },
         osc_feedback_args: match &s {
             Osc(s) => s.feedback_arguments.as_ref().cloned().unwrap_or_default(),
             _ => Default::default(),
@@ -116,27 +169,103 @@ pub fn convert_source(s: Source) -> ConversionResult<SourceModelData> {
         },
         control_element_type: match &s {
             Virtual(s) => convert_control_element_type(s.character.unwrap_or_default()),
+            VirtualChord(_) => VirtualControlElementType::Button,
             _ => Default::default(),
         },
         control_element_index: match &s {
             Virtual(s) => convert_control_element_id(s.id.clone()),
+            VirtualChord(s) => s
+                .ids
+                .first()
+                .cloned()
+                .map(convert_control_element_id)
+                .unwrap_or_default(),
+            _ => Default::default(),
+        },
+        chord_control_element_ids: match &s {
+            VirtualChord(s) => s
+                .ids
+                .iter()
+                .skip(1)
+                .cloned()
+                .map(convert_control_element_id)
+                .collect(),
             _ => Default::default(),
         },
         reaper_source_type: match &s {
             MidiDeviceChanges(_) => ReaperSourceType::MidiDeviceChanges,
             RealearnInstanceStart(_) => ReaperSourceType::RealearnInstanceStart,
             Timer(_) => ReaperSourceType::Timer,
+            MusicalTimer(_) => ReaperSourceType::MusicalTimer,
+            AudioLevel(_) => ReaperSourceType::AudioLevel,
             RealearnParameter(_) => ReaperSourceType::RealearnParameter,
+            Speech(_) => ReaperSourceType::Speech,
             _ => Default::default(),
         },
         timer_millis: match &s {
             Timer(t) => t.duration,
             _ => Default::default(),
         },
+        musical_timer_trigger: match &s {
+            MusicalTimer(t) => t.trigger.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        musical_timer_interval: match &s {
+            MusicalTimer(t) => t.interval,
+            _ => None,
+        },
+        musical_timer_bars: match &s {
+            MusicalTimer(t) => t.bars.clone().unwrap_or_default(),
+            _ => Default::default(),
+        },
+        musical_timer_only_while_playing: match &s {
+            MusicalTimer(t) => t.only_while_playing.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        musical_timer_output: match &s {
+            MusicalTimer(t) => t.output.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        audio_level_input: match &s {
+            AudioLevel(s) => s.input.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        audio_level_index: match &s {
+            AudioLevel(s) => s.index,
+            _ => None,
+        },
+        audio_level_measurement: match &s {
+            AudioLevel(s) => s.measurement.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        audio_level_attack_millis: match &s {
+            AudioLevel(s) => s.attack,
+            _ => None,
+        },
+        audio_level_release_millis: match &s {
+            AudioLevel(s) => s.release,
+            _ => None,
+        },
+        audio_level_onset_threshold_db: match &s {
+            AudioLevel(s) => s.onset_threshold,
+            _ => None,
+        },
         parameter_index: match &s {
             RealearnParameter(s) => s.parameter_index.try_into()?,
             _ => Default::default(),
         },
+        speech_voice: match &s {
+            Speech(s) => s.voice.clone().unwrap_or_default(),
+            _ => Default::default(),
+        },
+        speech_rate: match &s {
+            Speech(s) => s.rate,
+            _ => None,
+        },
+        speech_queue_policy: match &s {
+            Speech(s) => s.queue_policy,
+            _ => None,
+        },
     };
     Ok(data)
 }
@@ -148,6 +277,8 @@ fn convert_category(s: &Source) -> SourceCategory {
         MidiDeviceChanges(_)
         | RealearnInstanceStart(_)
         | Timer(_)
+        | MusicalTimer(_)
+        | AudioLevel(_)
         | RealearnParameter(_)
         | Speech(_) => SourceCategory::Reaper,
         MidiNoteVelocity(_)
@@ -163,6 +294,8 @@ fn convert_category(s: &Source) -> SourceCategory {
         | MidiClockTransport(_)
         | MidiRaw(_)
         | MidiScript(_)
+        | MidiMpe(_)
+        | MidiChord(_)
         | MackieLcd(_)
         | XTouchMackieLcd(_)
         | MackieSevenSegmentDisplay(_)
@@ -171,7 +304,7 @@ fn convert_category(s: &Source) -> SourceCategory {
         | LaunchpadProScrollingTextDisplay(_) => SourceCategory::Midi,
         Osc(_) => SourceCategory::Osc,
         Key(_) => SourceCategory::Keyboard,
-        Virtual(_) => SourceCategory::Virtual,
+        Virtual(_) | VirtualChord(_) => SourceCategory::Virtual,
     }
 }
 
@@ -191,6 +324,8 @@ fn convert_midi_source_type(s: &Source) -> MidiSourceType {
         MidiClockTransport(_) => MidiSourceType::ClockTransport,
         MidiRaw(_) => MidiSourceType::Raw,
         MidiScript(_) => MidiSourceType::Script,
+        MidiMpe(_) => MidiSourceType::Mpe,
+        MidiChord(_) => MidiSourceType::Chord,
         MackieLcd(_) | XTouchMackieLcd(_) | MackieSevenSegmentDisplay(_) | SiniConE24Display(_) => {
             MidiSourceType::Display
         }
@@ -210,6 +345,7 @@ fn convert_midi_channel(s: &Source) -> ConversionResult<Option<Channel>> {
         MidiChannelPressureAmount(s) => s.channel,
         MidiPitchBendChangeValue(s) => s.channel,
         MidiParameterNumberValue(s) => s.channel,
+        MidiChord(s) => s.channel,
         _ => None,
     };
     if let Some(ch) = ch {
@@ -1,8 +1,8 @@
 use crate::application::{
-    AutomationModeOverrideType, BookmarkAnchorType, FxParameterPropValues, FxPropValues,
-    RealearnAutomationMode, RealearnTrackArea, TargetCategory, TrackPropValues,
+    AutomationModeOverrideType, BookmarkAnchorType, EnvelopeType, FxParameterPropValues,
+    FxPropValues, RealearnAutomationMode, RealearnTrackArea, TargetCategory, TrackPropValues,
     TrackRoutePropValues, TrackRouteSelectorType, VirtualFxParameterType, VirtualFxType,
-    VirtualTrackType,
+    VirtualItemType, VirtualTrackType,
 };
 use crate::domain::{
     ActionInvocationType, Exclusivity, FxDisplayType, ReaperTargetType, SeekOptions,
@@ -10,7 +10,7 @@ use crate::domain::{
 };
 use crate::infrastructure::api::convert::to_data::{
     convert_control_element_id, convert_control_element_type, convert_osc_arg_type,
-    convert_osc_value_range, convert_tags,
+    convert_osc_value_range, convert_tags, convert_xy_axis,
 };
 use crate::infrastructure::api::convert::{defaults, ConversionResult};
 use crate::infrastructure::data::{
@@ -137,6 +137,25 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
             seek_behavior: d.behavior,
             ..init(d.commons)
         },
+        Target::MarkerManagement(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::MarkerManagement,
+            marker_management_action: d.action.unwrap_or_default(),
+            marker_name: d.name.unwrap_or_default(),
+            ..init(d.commons)
+        },
+        Target::TimeRange(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::TimeRange,
+            time_range_kind: d.range.unwrap_or_default(),
+            time_range_action: d.action.unwrap_or_default(),
+            time_range_quantization: d.quantization.unwrap_or_default(),
+            time_range_region_index: d
+                .region_index
+                .unwrap_or(defaults::TARGET_TIME_RANGE_REGION_INDEX),
+            time_range_bar_count: d.bar_count.unwrap_or(defaults::TARGET_TIME_RANGE_BAR_COUNT),
+            ..init(d.commons)
+        },
         Target::PlayRate(d) => TargetModelData {
             category: TargetCategory::Reaper,
             r#type: ReaperTargetType::PlayRate,
@@ -471,6 +490,23 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::BrowseItems(d) => {
+            let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::BrowseItems,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                scroll_arrange_view: d
+                    .scroll_arrange_view
+                    .unwrap_or(defaults::TARGET_BROWSE_ITEMS_SCROLL_ARRANGE_VIEW),
+                move_edit_cursor: d
+                    .move_edit_cursor
+                    .unwrap_or(defaults::TARGET_BROWSE_ITEMS_MOVE_EDIT_CURSOR),
+                ..init(d.commons)
+            }
+        }
         Target::BrowseFxChain(d) => {
             let chain_desc = convert_chain_desc(d.chain)?;
             let track_desc = chain_desc.track_desc;
@@ -727,6 +763,199 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::EnvelopeArmState(d) => {
+            convert_envelope_target(ReaperTargetType::EnvelopeArm, d.envelope, d.commons)?
+        }
+        Target::EnvelopeVisibility(d) => {
+            convert_envelope_target(ReaperTargetType::EnvelopeShow, d.envelope, d.commons)?
+        }
+        Target::EnvelopeWriteValue(d) => {
+            convert_envelope_target(ReaperTargetType::EnvelopeWriteValue, d.envelope, d.commons)?
+        }
+        Target::BrowseAutomationItems(d) => convert_envelope_target(
+            ReaperTargetType::BrowseAutomationItems,
+            d.envelope,
+            d.commons,
+        )?,
+        Target::ItemVolume(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::ItemVolume,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                ..init(d.commons)
+            }
+        }
+        Target::ItemMuteState(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::ItemMute,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                ..init(d.commons)
+            }
+        }
+        Target::ItemLockState(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::ItemLock,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                ..init(d.commons)
+            }
+        }
+        Target::ItemSelectionState(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::ItemSelection,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                ..init(d.commons)
+            }
+        }
+        Target::ItemPosition(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::ItemPosition,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                ..init(d.commons)
+            }
+        }
+        Target::BrowseItemTakes(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::ItemBrowseTakes,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                ..init(d.commons)
+            }
+        }
+        Target::TakeVolume(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TakeVolume,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                ..init(d.commons)
+            }
+        }
+        Target::TakePitch(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TakePitch,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                ..init(d.commons)
+            }
+        }
+        Target::TakePlayRate(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TakePlayrate,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                ..init(d.commons)
+            }
+        }
+        Target::TakeFxParameterValue(d) => {
+            let item_desc = convert_item_desc(d.item)?;
+            let track_desc = item_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TakeFxParameter,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                item_type: item_desc.item_type,
+                item_index: item_desc.item_index,
+                item_expression: item_desc.item_expression,
+                take_fx_index: d.fx_index,
+                take_fx_param_index: d.parameter_index,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                ..init(d.commons)
+            }
+        }
         Target::ClipTransportAction(d) => TargetModelData {
             category: TargetCategory::Reaper,
             r#type: ReaperTargetType::ClipTransport,
@@ -933,6 +1162,7 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
             category: TargetCategory::Virtual,
             control_element_type: convert_control_element_type(d.character.unwrap_or_default()),
             control_element_index: convert_control_element_id(d.id),
+            control_element_axis: convert_xy_axis(d.axis.unwrap_or_default()),
             ..Default::default()
         },
     };
@@ -953,6 +1183,61 @@ fn init(commons: TargetCommons) -> TargetModelData {
     }
 }
 
+fn convert_envelope_target(
+    r#type: ReaperTargetType,
+    envelope: EnvelopeDescriptor,
+    commons: TargetCommons,
+) -> ConversionResult<TargetModelData> {
+    let data = match envelope {
+        EnvelopeDescriptor::Track { track, kind } => {
+            let track_desc = convert_track_desc(track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type,
+                envelope_type: EnvelopeType::Track,
+                track_envelope_kind: kind.unwrap_or_default(),
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                ..init(commons)
+            }
+        }
+        EnvelopeDescriptor::FxParameter { parameter } => {
+            let fx_parameter_desc = convert_fx_parameter_desc(parameter)?;
+            let fx_desc = fx_parameter_desc.fx_desc;
+            let track_desc = fx_desc.chain_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type,
+                envelope_type: EnvelopeType::FxParameter,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                fx_data: fx_desc.fx_data,
+                enable_only_if_fx_has_focus: fx_desc.fx_must_have_focus,
+                fx_parameter_data: fx_parameter_desc.fx_parameter_data,
+                ..init(commons)
+            }
+        }
+        EnvelopeDescriptor::Route { route, kind } => {
+            let route_desc = convert_route_desc(route)?;
+            let track_desc = route_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type,
+                envelope_type: EnvelopeType::Route,
+                route_envelope_kind: kind.unwrap_or_default(),
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                track_route_data: route_desc.track_route_data,
+                ..init(commons)
+            }
+        }
+    };
+    Ok(data)
+}
+
 fn convert_automation_mode(mode: AutomationMode) -> RealearnAutomationMode {
     use AutomationMode::*;
     use RealearnAutomationMode as T;
@@ -985,6 +1270,14 @@ struct RouteDesc {
     track_route_data: TrackRouteData,
 }
 
+#[derive(Default)]
+struct ItemDesc {
+    track_desc: TrackDesc,
+    item_type: VirtualItemType,
+    item_index: u32,
+    item_expression: String,
+}
+
 #[derive(Default)]
 struct FxDesc {
     chain_desc: FxChainDesc,
@@ -1202,6 +1495,41 @@ fn convert_route_desc(t: RouteDescriptor) -> ConversionResult<RouteDesc> {
     Ok(desc)
 }
 
+fn convert_item_desc(t: ItemDescriptor) -> ConversionResult<ItemDesc> {
+    use ItemDescriptor::*;
+    let desc = match t {
+        Selected { allow_multiple } => ItemDesc {
+            item_type: if allow_multiple.unwrap_or(false) {
+                VirtualItemType::AllSelected
+            } else {
+                VirtualItemType::Selected
+            },
+            ..Default::default()
+        },
+        AtEditCursor { commons } => ItemDesc {
+            track_desc: convert_track_desc(commons.track.unwrap_or_default())?,
+            item_type: VirtualItemType::AtEditCursor,
+            ..Default::default()
+        },
+        Dynamic {
+            commons,
+            expression,
+        } => ItemDesc {
+            track_desc: convert_track_desc(commons.track.unwrap_or_default())?,
+            item_type: VirtualItemType::Dynamic,
+            item_expression: expression,
+            ..Default::default()
+        },
+        ByIndex { commons, index } => ItemDesc {
+            track_desc: convert_track_desc(commons.track.unwrap_or_default())?,
+            item_type: VirtualItemType::ByIndex,
+            item_index: index,
+            ..Default::default()
+        },
+    };
+    Ok(desc)
+}
+
 fn convert_route_kind(kind: TrackRouteKind) -> TrackRouteType {
     use TrackRouteKind::*;
     use TrackRouteType as T;
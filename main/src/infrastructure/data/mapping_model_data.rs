@@ -1,4 +1,4 @@
-use crate::application::{Change, MappingCommand, MappingModel};
+use crate::application::{Change, MappingCommand, MappingModel, TargetModel};
 use crate::base::default_util::{bool_true, deserialize_null_default, is_bool_true, is_default};
 use crate::domain::{
     Compartment, ExtendedProcessorContext, FeedbackSendBehavior, GroupId, GroupKey, MappingId,
@@ -47,6 +47,13 @@ pub struct MappingModelData {
     pub source: SourceModelData,
     pub mode: ModeModelData,
     pub target: TargetModelData,
+    /// Target for the Y axis of an XY source.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub y_target: Option<TargetModelData>,
     #[serde(default = "bool_true", skip_serializing_if = "is_bool_true")]
     pub is_enabled: bool,
     #[serde(flatten)]
@@ -98,6 +105,9 @@ impl MappingModelData {
             source: SourceModelData::from_model(&model.source_model),
             mode: ModeModelData::from_model(&model.mode_model),
             target: TargetModelData::from_model(&model.target_model, conversion_context),
+            y_target: model
+                .y_target_model()
+                .map(|t| TargetModelData::from_model(t, conversion_context)),
             is_enabled: model.is_enabled(),
             enabled_data: EnabledData {
                 control_is_enabled: model.control_is_enabled(),
@@ -238,6 +248,22 @@ impl MappingModelData {
             conversion_context,
             migration_descriptor,
         )?;
+        let y_target_model = match &self.y_target {
+            None => None,
+            Some(y_target) => {
+                let mut y_target_model = TargetModel::default_for_compartment(compartment);
+                y_target.apply_to_model_flexible(
+                    &mut y_target_model,
+                    processor_context,
+                    preset_version,
+                    compartment,
+                    conversion_context,
+                    migration_descriptor,
+                )?;
+                Some(y_target_model)
+            }
+        };
+        model.change(P::SetYTarget(y_target_model));
         model.change(P::SetIsEnabled(self.is_enabled));
         model.change(P::SetControlIsEnabled(self.enabled_data.control_is_enabled));
         model.change(P::SetFeedbackIsEnabled(
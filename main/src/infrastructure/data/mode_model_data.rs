@@ -9,7 +9,7 @@ use helgoboss_learn::{
     GroupInteraction, Interval, OutOfRangeBehavior, SoftSymmetricUnitValue, TakeoverMode,
     UnitValue, ValueSequence, VirtualColor,
 };
-use realearn_api::persistence::FeedbackValueTable;
+use realearn_api::persistence::{FeedbackValueTable, Gesture, TransformationLanguage};
 use serde::{Deserialize, Serialize};
 use slog::debug;
 use std::time::Duration;
@@ -97,8 +97,25 @@ pub struct ModeModelData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
+    pub control_transformation_language: TransformationLanguage,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub feedback_transformation_language: TransformationLanguage,
+    /// Written in the language given by `control_transformation_language` (named like this for
+    /// historical reasons).
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
     pub eel_control_transformation: String,
-    /// Also used as text expression for text feedback
+    /// Written in the language given by `feedback_transformation_language` (named like this for
+    /// historical reasons).
+    ///
+    /// Also used as text expression for text feedback (always EEL).
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
@@ -198,6 +215,17 @@ pub struct ModeModelData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
+    pub gesture: Gesture,
+    #[serde(
+        default = "default_gesture_tap_count",
+        skip_serializing_if = "is_default_gesture_tap_count"
+    )]
+    pub gesture_tap_count: u32,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
     pub target_value_sequence: ValueSequence,
     #[serde(
         default,
@@ -213,6 +241,14 @@ pub struct ModeModelData {
     pub feedback_value_table: Option<FeedbackValueTable>,
 }
 
+fn default_gesture_tap_count() -> u32 {
+    ModeModel::DEFAULT_GESTURE_TAP_COUNT
+}
+
+fn is_default_gesture_tap_count(v: &u32) -> bool {
+    *v == default_gesture_tap_count()
+}
+
 fn default_step_size() -> SoftSymmetricUnitValue {
     SoftSymmetricUnitValue::new(0.01)
 }
@@ -244,11 +280,13 @@ impl ModeModelData {
             min_press_millis: model.press_duration_interval().min_val().as_millis() as _,
             max_press_millis: model.press_duration_interval().max_val().as_millis() as _,
             turbo_rate: model.turbo_rate().as_millis() as _,
-            eel_control_transformation: model.eel_control_transformation().to_owned(),
+            control_transformation_language: model.control_transformation_language(),
+            feedback_transformation_language: model.feedback_transformation_language(),
+            eel_control_transformation: model.control_transformation().to_owned(),
             eel_feedback_transformation: if model.feedback_type().is_textual() {
                 model.textual_feedback_expression().to_owned()
             } else {
-                model.eel_feedback_transformation().to_owned()
+                model.feedback_transformation().to_owned()
             },
             feedback_color: model.feedback_color().cloned(),
             feedback_background_color: model.feedback_background_color().cloned(),
@@ -266,6 +304,8 @@ impl ModeModelData {
             rotate_is_enabled: model.rotate(),
             make_absolute_enabled: model.make_absolute(),
             group_interaction: model.group_interaction(),
+            gesture: model.gesture(),
+            gesture_tap_count: model.gesture_tap_count(),
             target_value_sequence: model.target_value_sequence().clone(),
             feedback_type: model.feedback_type(),
             feedback_value_table: model.feedback_value_table().cloned(),
@@ -365,15 +405,21 @@ impl ModeModelData {
         };
         model.change(P::SetLegacyJumpInterval(legacy_jump_interval));
         model.change(P::SetTakeoverMode(takeover_mode));
-        model.change(P::SetEelControlTransformation(
+        model.change(P::SetControlTransformationLanguage(
+            self.control_transformation_language,
+        ));
+        model.change(P::SetFeedbackTransformationLanguage(
+            self.feedback_transformation_language,
+        ));
+        model.change(P::SetControlTransformation(
             self.eel_control_transformation.clone(),
         ));
-        let (eel_fb_transformation, textual_fb_expression) = if self.feedback_type.is_textual() {
+        let (fb_transformation, textual_fb_expression) = if self.feedback_type.is_textual() {
             (String::new(), self.eel_feedback_transformation.clone())
         } else {
             (self.eel_feedback_transformation.clone(), String::new())
         };
-        model.change(P::SetEelFeedbackTransformation(eel_fb_transformation));
+        model.change(P::SetFeedbackTransformation(fb_transformation));
         model.change(P::SetTextualFeedbackExpression(textual_fb_expression));
         model.change(P::SetFeedbackColor(self.feedback_color.clone()));
         model.change(P::SetFeedbackBackgroundColor(
@@ -394,6 +440,8 @@ impl ModeModelData {
         model.change(P::SetRotate(self.rotate_is_enabled));
         model.change(P::SetMakeAbsolute(self.make_absolute_enabled));
         model.change(P::SetGroupInteraction(self.group_interaction));
+        model.change(P::SetGesture(self.gesture));
+        model.change(P::SetGestureTapCount(self.gesture_tap_count));
         model.change(P::SetTargetValueSequence(
             self.target_value_sequence.clone(),
         ));
@@ -7,8 +7,8 @@ use crate::domain::{
     compartment_param_index_iter, pot, BackboneState, ClipMatrixRef, Compartment,
     CompartmentParamIndex, ControlInput, FeedbackOutput, GroupId, GroupKey, InstanceState,
     MappingId, MappingKey, MappingSnapshotContainer, MappingSnapshotId, MidiControlInput,
-    MidiDestination, OscDeviceId, Param, PluginParamIndex, PluginParams,
-    StayActiveWhenProjectInBackground, Tag,
+    MidiDestination, OscDeviceId, Param, PluginParamIndex, PluginParams, SpeechSettings,
+    StayActiveWhenProjectInBackground, Tag, MAX_SPEECH_RATE, MIN_SPEECH_RATE, NORMAL_SPEECH_RATE,
 };
 use crate::infrastructure::data::{
     convert_target_value_to_api, convert_target_value_to_model,
@@ -23,7 +23,7 @@ use crate::infrastructure::data::clip_legacy::{
 };
 use playtime_api::persistence::Matrix;
 use realearn_api::persistence::{
-    FxDescriptor, MappingInSnapshot, MappingSnapshot, TrackDescriptor,
+    FxDescriptor, MappingInSnapshot, MappingSnapshot, SpeechQueuePolicy, TrackDescriptor,
 };
 use reaper_medium::{MidiInputDeviceId, MidiOutputDeviceId};
 use semver::Version;
@@ -55,30 +55,30 @@ pub struct SessionData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    id: Option<String>,
+    pub id: Option<String>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    let_matched_events_through: bool,
+    pub let_matched_events_through: bool,
     #[serde(default = "bool_true", skip_serializing_if = "is_bool_true")]
-    let_unmatched_events_through: bool,
+    pub let_unmatched_events_through: bool,
     /// Introduced with ReaLearn 2.14.0-pre.1. Before that "Always".
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    stay_active_when_project_in_background: Option<StayActiveWhenProjectInBackground>,
+    pub stay_active_when_project_in_background: Option<StayActiveWhenProjectInBackground>,
     #[serde(default = "bool_true", skip_serializing_if = "is_bool_true")]
-    always_auto_detect_mode: bool,
+    pub always_auto_detect_mode: bool,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    lives_on_upper_floor: bool,
+    pub lives_on_upper_floor: bool,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
@@ -86,16 +86,36 @@ pub struct SessionData {
     )]
     // false by default because in older versions, feedback was always sent no matter if armed or
     // not
-    send_feedback_only_if_armed: bool,
+    pub send_feedback_only_if_armed: bool,
     #[serde(default = "bool_true", skip_serializing_if = "is_bool_true")]
-    reset_feedback_when_releasing_source: bool,
+    pub reset_feedback_when_releasing_source: bool,
+    /// Empty means the default voice of the speech backend.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub speech_voice: String,
+    /// `None` means normal rate.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub speech_rate: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub speech_queue_policy: SpeechQueuePolicy,
     /// `None` means "<FX input>"
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    control_device_id: Option<ControlDeviceId>,
+    pub control_device_id: Option<ControlDeviceId>,
     ///
     /// - `None` means "\<None>"
     /// - `Some("fx-output")` means "\<FX output>"
@@ -104,80 +124,80 @@ pub struct SessionData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    feedback_device_id: Option<FeedbackDeviceId>,
+    pub feedback_device_id: Option<FeedbackDeviceId>,
     // Not set before 1.12.0-pre9
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    default_group: Option<GroupModelData>,
+    pub default_group: Option<GroupModelData>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    groups: Vec<GroupModelData>,
+    pub groups: Vec<GroupModelData>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    default_controller_group: Option<GroupModelData>,
+    pub default_controller_group: Option<GroupModelData>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    controller_groups: Vec<GroupModelData>,
+    pub controller_groups: Vec<GroupModelData>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    mappings: Vec<MappingModelData>,
+    pub mappings: Vec<MappingModelData>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    controller_mappings: Vec<MappingModelData>,
+    pub controller_mappings: Vec<MappingModelData>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    controller_custom_data: HashMap<String, serde_json::Value>,
+    pub controller_custom_data: HashMap<String, serde_json::Value>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    controller_notes: String,
+    pub controller_notes: String,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    main_notes: String,
+    pub main_notes: String,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    active_controller_id: Option<String>,
+    pub active_controller_id: Option<String>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    active_main_preset_id: Option<String>,
+    pub active_main_preset_id: Option<String>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    main_preset_auto_load_mode: MainPresetAutoLoadMode,
+    pub main_preset_auto_load_mode: MainPresetAutoLoadMode,
     // String key workaround because otherwise deserialization doesn't work with flattening,
     // which is used in CompartmentModelData.
     #[serde(
@@ -185,7 +205,7 @@ pub struct SessionData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    parameters: HashMap<String, ParameterData>,
+    pub parameters: HashMap<String, ParameterData>,
     // String key workaround because otherwise deserialization doesn't work with flattening,
     // which is used in CompartmentModelData.
     #[serde(
@@ -193,21 +213,21 @@ pub struct SessionData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    controller_parameters: HashMap<String, ParameterData>,
+    pub controller_parameters: HashMap<String, ParameterData>,
     // Legacy (ReaLearn <= 2.12.0-pre.4)
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    clip_slots: Vec<QualifiedSlotDescriptor>,
+    pub clip_slots: Vec<QualifiedSlotDescriptor>,
     // New since 2.12.0-pre.5
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    clip_matrix: Option<ClipMatrixRefData>,
+    pub clip_matrix: Option<ClipMatrixRefData>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
@@ -219,63 +239,63 @@ pub struct SessionData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    controller: CompartmentState,
+    pub controller: CompartmentState,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    main: CompartmentState,
+    pub main: CompartmentState,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    active_instance_tags: HashSet<Tag>,
+    pub active_instance_tags: HashSet<Tag>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    instance_preset_link_config: FxPresetLinkConfig,
+    pub instance_preset_link_config: FxPresetLinkConfig,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    use_instance_preset_links_only: bool,
+    pub use_instance_preset_links_only: bool,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    instance_track: TrackDescriptor,
+    pub instance_track: TrackDescriptor,
     #[serde(default = "focused_fx_descriptor")]
-    instance_fx: FxDescriptor,
+    pub instance_fx: FxDescriptor,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    mapping_snapshots: Vec<MappingSnapshot>,
+    pub mapping_snapshots: Vec<MappingSnapshot>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    controller_mapping_snapshots: Vec<MappingSnapshot>,
+    pub controller_mapping_snapshots: Vec<MappingSnapshot>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    pot_state: pot::PersistentState,
+    pub pot_state: pot::PersistentState,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    memorized_main_compartment: Option<CompartmentModelData>,
+    pub memorized_main_compartment: Option<CompartmentModelData>,
 }
 
 fn focused_fx_descriptor() -> FxDescriptor {
@@ -284,32 +304,32 @@ fn focused_fx_descriptor() -> FxDescriptor {
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
-enum ClipMatrixRefData {
+pub enum ClipMatrixRefData {
     Own(Matrix),
     Foreign(String),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-struct CompartmentState {
+pub struct CompartmentState {
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    active_mapping_by_group: HashMap<GroupId, MappingId>,
+    pub active_mapping_by_group: HashMap<GroupId, MappingId>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    active_mapping_tags: HashSet<Tag>,
+    pub active_mapping_tags: HashSet<Tag>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
-    active_mapping_snapshots: HashMap<Tag, MappingSnapshotId>,
+    pub active_mapping_snapshots: HashMap<Tag, MappingSnapshotId>,
 }
 
 impl CompartmentState {
@@ -327,21 +347,21 @@ impl CompartmentState {
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
-enum ControlDeviceId {
+pub enum ControlDeviceId {
     Keyboard(KeyboardDevice),
     Osc(OscDeviceId),
     Midi(String),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-enum KeyboardDevice {
+pub enum KeyboardDevice {
     #[serde(rename = "keyboard")]
     TheKeyboard,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
-enum FeedbackDeviceId {
+pub enum FeedbackDeviceId {
     Osc(OscDeviceId),
     MidiOrFxOutput(String),
 }
@@ -362,6 +382,9 @@ impl Default for SessionData {
             send_feedback_only_if_armed: session_defaults::SEND_FEEDBACK_ONLY_IF_ARMED,
             reset_feedback_when_releasing_source:
                 session_defaults::RESET_FEEDBACK_WHEN_RELEASING_SOURCE,
+            speech_voice: String::new(),
+            speech_rate: None,
+            speech_queue_policy: Default::default(),
             control_device_id: None,
             feedback_device_id: None,
             default_group: None,
@@ -425,6 +448,7 @@ impl SessionData {
         };
         let main_preset_auto_load_mode = session.main_preset_auto_load_mode.get();
         let instance_state = session.instance_state().borrow();
+        let speech_settings = session.speech_settings.get_ref();
         SessionData {
             version: Some(App::version().clone()),
             id: Some(session.id().to_string()),
@@ -439,6 +463,9 @@ impl SessionData {
             reset_feedback_when_releasing_source: session
                 .reset_feedback_when_releasing_source
                 .get(),
+            speech_voice: speech_settings.voice.clone(),
+            speech_rate: Some(speech_settings.rate).filter(|r| *r != NORMAL_SPEECH_RATE),
+            speech_queue_policy: speech_settings.queue_policy,
             control_device_id: {
                 match session.control_input() {
                     ControlInput::Midi(MidiControlInput::FxInput) => None,
@@ -613,6 +640,16 @@ impl SessionData {
         session
             .reset_feedback_when_releasing_source
             .set_without_notification(self.reset_feedback_when_releasing_source);
+        session
+            .speech_settings
+            .set_without_notification(SpeechSettings {
+                voice: self.speech_voice.clone(),
+                rate: self
+                    .speech_rate
+                    .unwrap_or(NORMAL_SPEECH_RATE)
+                    .clamp(MIN_SPEECH_RATE, MAX_SPEECH_RATE),
+                queue_policy: self.speech_queue_policy,
+            });
         session
             .control_input
             .set_without_notification(control_input);
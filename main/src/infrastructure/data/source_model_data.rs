@@ -1,16 +1,22 @@
 use super::none_if_minus_one;
 use crate::application::{
     Change, MidiSourceType, ReaperSourceType, SourceCategory, SourceCommand, SourceModel,
-    VirtualControlElementType,
+    VirtualControlElementType, DEFAULT_MPE_MEMBER_CHANNEL_COUNT,
 };
 use crate::base::default_util::{deserialize_null_default, is_default};
 use crate::base::notification;
-use crate::domain::{Compartment, CompartmentParamIndex, Keystroke};
+use crate::domain::{
+    Compartment, CompartmentParamIndex, Keystroke, DEFAULT_AUDIO_LEVEL_ATTACK_MILLIS,
+    DEFAULT_AUDIO_LEVEL_ONSET_THRESHOLD_DB, DEFAULT_AUDIO_LEVEL_RELEASE_MILLIS,
+};
 use crate::infrastructure::data::common::OscValueRange;
 use crate::infrastructure::data::VirtualControlElementIdData;
 use helgoboss_learn::{DisplayType, MidiClockTransportMessage, OscTypeTag, SourceCharacter};
 use helgoboss_midi::{Channel, U14, U7};
-use realearn_api::persistence::MidiScriptKind;
+use realearn_api::persistence::{
+    AudioLevelInput, AudioLevelMeasurement, ChordMode, MidiScriptKind, MpeDimension,
+    MpeNoteSelector, MpeZone, MusicalTimerOutput, MusicalTimerTrigger, SpeechQueuePolicy,
+};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
@@ -93,6 +99,12 @@ pub struct SourceModelData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
+    pub midi_control_script: String,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
     pub display_type: DisplayType,
     #[serde(
         default,
@@ -106,6 +118,56 @@ pub struct SourceModelData {
         skip_serializing_if = "is_default"
     )]
     pub line: Option<u8>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub mpe_zone: MpeZone,
+    /// `None` means the default member channel count.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub mpe_member_channel_count: Option<u8>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub mpe_dimension: MpeDimension,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub mpe_note_selector: MpeNoteSelector,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub mpe_held_note_index: u8,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub chord_notes: Vec<U7>,
+    // MIDI and virtual
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub chord_mode: ChordMode,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub chord_time_window_millis: u64,
     // OSC
     #[serde(
         default,
@@ -142,6 +204,12 @@ pub struct SourceModelData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
+    pub osc_additional_arg_indexes: Vec<u32>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
     pub osc_feedback_args: Vec<String>,
     // Keyboard
     #[serde(
@@ -163,6 +231,12 @@ pub struct SourceModelData {
         skip_serializing_if = "is_default"
     )]
     pub control_element_index: VirtualControlElementIdData,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub chord_control_element_ids: Vec<VirtualControlElementIdData>,
     // REAPER
     #[serde(
         default,
@@ -181,7 +255,99 @@ pub struct SourceModelData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
+    pub musical_timer_trigger: MusicalTimerTrigger,
+    /// `None` means 1.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub musical_timer_interval: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub musical_timer_bars: Vec<u32>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub musical_timer_only_while_playing: bool,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub musical_timer_output: MusicalTimerOutput,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_level_input: AudioLevelInput,
+    /// `None` means 1.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_level_index: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_level_measurement: AudioLevelMeasurement,
+    /// `None` means default attack.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_level_attack_millis: Option<u32>,
+    /// `None` means default release.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_level_release_millis: Option<u32>,
+    /// `None` means default threshold.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_level_onset_threshold_db: Option<i32>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
     pub parameter_index: CompartmentParamIndex,
+    /// Empty means the voice of the instance.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub speech_voice: String,
+    /// `None` means the rate of the instance.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub speech_rate: Option<u32>,
+    /// `None` means the queue policy of the instance.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub speech_queue_policy: Option<SpeechQueuePolicy>,
 }
 
 impl SourceModelData {
@@ -202,23 +368,59 @@ impl SourceModelData {
             raw_midi_pattern: model.raw_midi_pattern().to_owned(),
             midi_script_kind: model.midi_script_kind(),
             midi_script: model.midi_script().to_owned(),
+            midi_control_script: model.midi_control_script().to_owned(),
             display_type: model.display_type(),
             display_id: model.display_id(),
             line: model.line(),
+            mpe_zone: model.mpe_zone(),
+            mpe_member_channel_count: Some(model.mpe_member_channel_count())
+                .filter(|c| *c != DEFAULT_MPE_MEMBER_CHANNEL_COUNT),
+            mpe_dimension: model.mpe_dimension(),
+            mpe_note_selector: model.mpe_note_selector(),
+            mpe_held_note_index: model.mpe_held_note_index(),
+            chord_notes: model.chord_notes().to_vec(),
+            chord_mode: model.chord_mode(),
+            chord_time_window_millis: model.chord_time_window_millis(),
             osc_address_pattern: model.osc_address_pattern().to_owned(),
             osc_arg_index: model.osc_arg_index(),
             osc_arg_type: model.osc_arg_type_tag(),
             osc_arg_is_relative: model.osc_arg_is_relative(),
             osc_arg_value_range: OscValueRange::from_interval(model.osc_arg_value_range()),
+            osc_additional_arg_indexes: model.osc_additional_arg_indexes().to_vec(),
             osc_feedback_args: model.osc_feedback_args().to_vec(),
             keystroke: model.keystroke(),
             control_element_type: model.control_element_type(),
             control_element_index: VirtualControlElementIdData::from_model(
                 model.control_element_id(),
             ),
+            chord_control_element_ids: model
+                .chord_control_element_ids()
+                .iter()
+                .map(|id| VirtualControlElementIdData::from_model(*id))
+                .collect(),
             reaper_source_type: model.reaper_source_type(),
             timer_millis: model.timer_millis(),
+            musical_timer_trigger: model.musical_timer_settings().trigger,
+            musical_timer_interval: Some(model.musical_timer_settings().interval)
+                .filter(|i| *i != 1),
+            musical_timer_bars: model.musical_timer_settings().bars.clone(),
+            musical_timer_only_while_playing: model.musical_timer_settings().only_while_playing,
+            musical_timer_output: model.musical_timer_settings().output,
+            audio_level_input: model.audio_level_settings().input,
+            audio_level_index: Some(model.audio_level_settings().index).filter(|i| *i != 1),
+            audio_level_measurement: model.audio_level_settings().measurement,
+            audio_level_attack_millis: Some(model.audio_level_settings().attack.as_millis() as u32)
+                .filter(|ms| *ms as u64 != DEFAULT_AUDIO_LEVEL_ATTACK_MILLIS),
+            audio_level_release_millis: Some(
+                model.audio_level_settings().release.as_millis() as u32
+            )
+            .filter(|ms| *ms as u64 != DEFAULT_AUDIO_LEVEL_RELEASE_MILLIS),
+            audio_level_onset_threshold_db: Some(model.audio_level_settings().onset_threshold_db)
+                .filter(|db| *db != DEFAULT_AUDIO_LEVEL_ONSET_THRESHOLD_DB),
             parameter_index: model.parameter_index(),
+            speech_voice: model.speech_settings().voice.clone().unwrap_or_default(),
+            speech_rate: model.speech_settings().rate,
+            speech_queue_policy: model.speech_settings().queue_policy,
         }
     }
 
@@ -281,9 +483,21 @@ impl SourceModelData {
         model.change(P::SetRawMidiPattern(self.raw_midi_pattern.clone()));
         model.change(P::SetMidiScriptKind(self.midi_script_kind));
         model.change(P::SetMidiScript(self.midi_script.clone()));
+        model.change(P::SetMidiControlScript(self.midi_control_script.clone()));
         model.change(P::SetDisplayType(self.display_type));
         model.change(P::SetDisplayId(self.display_id));
         model.change(P::SetLine(self.line));
+        model.change(P::SetMpeZone(self.mpe_zone));
+        model.change(P::SetMpeMemberChannelCount(
+            self.mpe_member_channel_count
+                .unwrap_or(DEFAULT_MPE_MEMBER_CHANNEL_COUNT),
+        ));
+        model.change(P::SetMpeDimension(self.mpe_dimension));
+        model.change(P::SetMpeNoteSelector(self.mpe_note_selector));
+        model.change(P::SetMpeHeldNoteIndex(self.mpe_held_note_index));
+        model.change(P::SetChordNotes(self.chord_notes.clone()));
+        model.change(P::SetChordMode(self.chord_mode));
+        model.change(P::SetChordTimeWindowMillis(self.chord_time_window_millis));
         model.change(P::SetOscAddressPattern(self.osc_address_pattern.clone()));
         model.change(P::SetOscArgIndex(self.osc_arg_index));
         model.change(P::SetOscArgTypeTag(self.osc_arg_type));
@@ -291,14 +505,54 @@ impl SourceModelData {
         model.change(P::SetOscArgValueRange(
             self.osc_arg_value_range.to_interval(),
         ));
+        model.change(P::SetOscAdditionalArgIndexes(
+            self.osc_additional_arg_indexes.clone(),
+        ));
         model.change(P::SetOscFeedbackArgs(self.osc_feedback_args.clone()));
         model.change(P::SetControlElementType(self.control_element_type));
         model.change(P::SetControlElementId(
             self.control_element_index.to_model(),
         ));
+        model.change(P::SetChordControlElementIds(
+            self.chord_control_element_ids
+                .iter()
+                .map(|id| id.to_model())
+                .collect(),
+        ));
         model.change(P::SetReaperSourceType(self.reaper_source_type));
         model.change(P::SetTimerMillis(self.timer_millis));
+        model.change(P::SetMusicalTimerTrigger(self.musical_timer_trigger));
+        model.change(P::SetMusicalTimerInterval(
+            self.musical_timer_interval.unwrap_or(1),
+        ));
+        model.change(P::SetMusicalTimerBars(self.musical_timer_bars.clone()));
+        model.change(P::SetMusicalTimerOnlyWhilePlaying(
+            self.musical_timer_only_while_playing,
+        ));
+        model.change(P::SetMusicalTimerOutput(self.musical_timer_output));
+        model.change(P::SetAudioLevelInput(self.audio_level_input));
+        model.change(P::SetAudioLevelIndex(self.audio_level_index.unwrap_or(1)));
+        model.change(P::SetAudioLevelMeasurement(self.audio_level_measurement));
+        model.change(P::SetAudioLevelAttackMillis(
+            self.audio_level_attack_millis
+                .map(|ms| ms as u64)
+                .unwrap_or(DEFAULT_AUDIO_LEVEL_ATTACK_MILLIS),
+        ));
+        model.change(P::SetAudioLevelReleaseMillis(
+            self.audio_level_release_millis
+                .map(|ms| ms as u64)
+                .unwrap_or(DEFAULT_AUDIO_LEVEL_RELEASE_MILLIS),
+        ));
+        model.change(P::SetAudioLevelOnsetThresholdDb(
+            self.audio_level_onset_threshold_db
+                .unwrap_or(DEFAULT_AUDIO_LEVEL_ONSET_THRESHOLD_DB),
+        ));
         model.change(P::SetParameterIndex(self.parameter_index));
+        model.change(P::SetSpeechVoice(
+            Some(self.speech_voice.clone()).filter(|v| !v.is_empty()),
+        ));
+        model.change(P::SetSpeechRate(self.speech_rate));
+        model.change(P::SetSpeechQueuePolicy(self.speech_queue_policy));
         model.change(P::SetKeystroke(self.keystroke));
     }
 }
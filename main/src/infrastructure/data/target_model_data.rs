@@ -3,11 +3,12 @@ use super::none_if_minus_one;
 use reaper_high::{BookmarkType, Fx, Guid, Reaper};
 
 use crate::application::{
-    AutomationModeOverrideType, BookmarkAnchorType, Change, FxParameterPropValues, FxPropValues,
-    FxSnapshot, MappingSnapshotTypeForLoad, MappingSnapshotTypeForTake, RealearnAutomationMode,
-    RealearnTrackArea, TargetCategory, TargetCommand, TargetModel, TargetUnit, TrackPropValues,
-    TrackRoutePropValues, TrackRouteSelectorType, VirtualControlElementType,
-    VirtualFxParameterType, VirtualFxType, VirtualTrackType,
+    AutomationModeOverrideType, BookmarkAnchorType, Change, EnvelopeType, FxParameterPropValues,
+    FxPropValues, FxSnapshot, MappingSnapshotTypeForLoad, MappingSnapshotTypeForTake,
+    RealearnAutomationMode, RealearnTrackArea, TargetCategory, TargetCommand, TargetModel,
+    TargetUnit, TrackPropValues, TrackRoutePropValues, TrackRouteSelectorType,
+    VirtualControlElementType, VirtualFxParameterType, VirtualFxType, VirtualItemType,
+    VirtualTrackType,
 };
 use crate::base::default_util::{
     bool_true, deserialize_null_default, is_bool_true, is_default, is_none_or_some_default,
@@ -17,7 +18,7 @@ use crate::domain::{
     get_fx_chains, ActionInvocationType, AnyOnParameter, Compartment, Exclusivity,
     ExtendedProcessorContext, FxDisplayType, GroupKey, OscDeviceId, ReaperTargetType, SeekOptions,
     SendMidiDestination, SoloBehavior, Tag, TouchedRouteParameterType, TouchedTrackParameterType,
-    TrackExclusivity, TrackGangBehavior, TrackRouteType, TransportAction, VirtualTrack,
+    TrackExclusivity, TrackGangBehavior, TrackRouteType, TransportAction, VirtualTrack, XyAxis,
 };
 use crate::infrastructure::data::common::OscValueRange;
 use crate::infrastructure::data::{
@@ -31,8 +32,9 @@ use realearn_api::persistence::{
     BrowseTracksMode, ClipColumnAction, ClipColumnDescriptor, ClipColumnTrackContext,
     ClipManagementAction, ClipMatrixAction, ClipRowAction, ClipRowDescriptor, ClipSlotDescriptor,
     ClipTransportAction, FxToolAction, MappingSnapshotDescForLoad, MappingSnapshotDescForTake,
-    MonitoringMode, MouseAction, PotFilterItemKind, SeekBehavior, TargetValue, TrackScope,
-    TrackToolAction,
+    MarkerManagementAction, MonitoringMode, MouseAction, PotFilterItemKind, RouteEnvelopeKind,
+    SeekBehavior, TargetValue, TimeRangeAction, TimeRangeKind, TimeRangeQuantization,
+    TrackEnvelopeKind, TrackScope, TrackToolAction,
 };
 use semver::Version;
 use serde::{Deserialize, Serialize};
@@ -191,6 +193,12 @@ pub struct TargetModelData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
+    pub control_element_axis: XyAxis,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
     pub fx_snapshot: Option<FxSnapshot>,
     #[serde(
         default,
@@ -265,6 +273,57 @@ pub struct TargetModelData {
         skip_serializing_if = "is_default"
     )]
     pub scroll_mixer: bool,
+    // Browse items
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub move_edit_cursor: bool,
+    // Time range
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub time_range_kind: TimeRangeKind,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub time_range_action: TimeRangeAction,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub time_range_quantization: TimeRangeQuantization,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub time_range_region_index: u32,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub time_range_bar_count: u32,
+    // Marker management
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub marker_management_action: MarkerManagementAction,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub marker_name: String,
     // Send MIDI
     #[serde(
         default,
@@ -316,6 +375,56 @@ pub struct TargetModelData {
         skip_serializing_if = "is_default"
     )]
     pub mouse_action: MouseAction,
+    // Item and take targets
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub item_type: VirtualItemType,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub item_index: u32,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub item_expression: String,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub take_fx_index: u32,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub take_fx_param_index: u32,
+    // Envelope targets
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub envelope_type: EnvelopeType,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub track_envelope_kind: TrackEnvelopeKind,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub route_envelope_kind: RouteEnvelopeKind,
     #[serde(default = "bool_true", skip_serializing_if = "is_bool_true")]
     pub poll_for_feedback: bool,
     #[serde(default, skip_serializing_if = "is_default")]
@@ -526,6 +635,7 @@ impl TargetModelData {
             control_element_index: VirtualControlElementIdData::from_model(
                 model.control_element_id(),
             ),
+            control_element_axis: model.control_element_axis(),
             fx_snapshot: model.fx_snapshot().cloned(),
             touched_parameter_type: model.touched_track_parameter_type(),
             touched_route_parameter_type: model.touched_route_parameter_type(),
@@ -543,6 +653,14 @@ impl TargetModelData {
             fx_display_type: model.fx_display_type(),
             scroll_arrange_view: model.scroll_arrange_view(),
             scroll_mixer: model.scroll_mixer(),
+            move_edit_cursor: model.move_edit_cursor(),
+            time_range_kind: model.time_range_kind(),
+            time_range_action: model.time_range_action(),
+            time_range_quantization: model.time_range_quantization(),
+            time_range_region_index: model.time_range_region_index(),
+            time_range_bar_count: model.time_range_bar_count(),
+            marker_management_action: model.marker_management_action(),
+            marker_name: model.marker_name().to_owned(),
             send_midi_destination: model.send_midi_destination(),
             raw_midi_pattern: model.raw_midi_pattern().to_owned(),
             osc_address_pattern: model.osc_address_pattern().to_owned(),
@@ -587,6 +705,14 @@ impl TargetModelData {
             clip_play_start_timing: model.clip_play_start_timing(),
             clip_play_stop_timing: model.clip_play_stop_timing(),
             mouse_action: model.mouse_action(),
+            item_type: model.item_type(),
+            item_index: model.item_index(),
+            item_expression: model.item_expression().to_owned(),
+            take_fx_index: model.take_fx_index(),
+            take_fx_param_index: model.take_fx_param_index(),
+            envelope_type: model.envelope_type(),
+            track_envelope_kind: model.track_envelope_kind(),
+            route_envelope_kind: model.route_envelope_kind(),
             pot_filter_item_kind: model.pot_filter_item_kind(),
         }
     }
@@ -758,6 +884,7 @@ impl TargetModelData {
         model.change(C::SetControlElementId(
             self.control_element_index.to_model(),
         ));
+        model.change(C::SetControlElementAxis(self.control_element_axis));
         model.change(C::SetFxSnapshot(self.fx_snapshot.clone()));
         model.change(C::SetTouchedTrackParameterType(self.touched_parameter_type));
         model.change(C::SetTouchedRouteParameterType(
@@ -795,6 +922,14 @@ impl TargetModelData {
             self.scroll_mixer
         };
         model.change(C::SetScrollMixer(scroll_mixer));
+        model.change(C::SetMoveEditCursor(self.move_edit_cursor));
+        model.change(C::SetTimeRangeKind(self.time_range_kind));
+        model.change(C::SetTimeRangeAction(self.time_range_action));
+        model.change(C::SetTimeRangeQuantization(self.time_range_quantization));
+        model.change(C::SetTimeRangeRegionIndex(self.time_range_region_index));
+        model.change(C::SetTimeRangeBarCount(self.time_range_bar_count));
+        model.change(C::SetMarkerManagementAction(self.marker_management_action));
+        model.change(C::SetMarkerName(self.marker_name.clone()));
         model.change(C::SetSendMidiDestination(self.send_midi_destination));
         model.change(C::SetRawMidiPattern(self.raw_midi_pattern.clone()));
         model.change(C::SetOscAddressPattern(self.osc_address_pattern.clone()));
@@ -804,6 +939,14 @@ impl TargetModelData {
             self.osc_arg_value_range.to_interval(),
         ));
         model.change(C::SetOscDevId(self.osc_dev_id));
+        model.change(C::SetItemType(self.item_type));
+        model.change(C::SetItemIndex(self.item_index));
+        model.change(C::SetItemExpression(self.item_expression.clone()));
+        model.change(C::SetTakeFxIndex(self.take_fx_index));
+        model.change(C::SetTakeFxParamIndex(self.take_fx_param_index));
+        model.change(C::SetEnvelopeType(self.envelope_type));
+        model.change(C::SetTrackEnvelopeKind(self.track_envelope_kind));
+        model.change(C::SetRouteEnvelopeKind(self.route_envelope_kind));
         model.change(C::SetPollForFeedback(self.poll_for_feedback));
         model.change(C::SetRetrigger(self.retrigger));
         model.change(C::SetTags(self.tags.clone()));
@@ -1,6 +1,7 @@
 mod api;
 mod data;
 mod plugin;
+mod proto;
 mod server;
 mod test;
 mod ui;
@@ -32,9 +32,9 @@ use crate::infrastructure::ui::MessagePanel;
 
 use crate::infrastructure::plugin::tracing_util::setup_tracing;
 use crate::infrastructure::server::grpc::{
-    ContinuousColumnUpdateBatch, ContinuousMatrixUpdateBatch, ContinuousSlotUpdateBatch,
-    OccasionalClipUpdateBatch, OccasionalMatrixUpdateBatch, OccasionalSlotUpdateBatch,
-    OccasionalTrackUpdateBatch,
+    ContinuousColumnUpdateBatch, ContinuousMappingUpdateBatch, ContinuousMatrixUpdateBatch,
+    ContinuousSlotUpdateBatch, OccasionalClipUpdateBatch, OccasionalMappingUpdateBatch,
+    OccasionalMatrixUpdateBatch, OccasionalSlotUpdateBatch, OccasionalTrackUpdateBatch,
 };
 use once_cell::sync::Lazy;
 use realearn_api::persistence::{
@@ -114,6 +114,8 @@ pub struct App {
     continuous_matrix_update_sender: tokio::sync::broadcast::Sender<ContinuousMatrixUpdateBatch>,
     continuous_column_update_sender: tokio::sync::broadcast::Sender<ContinuousColumnUpdateBatch>,
     continuous_slot_update_sender: tokio::sync::broadcast::Sender<ContinuousSlotUpdateBatch>,
+    occasional_mapping_update_sender: tokio::sync::broadcast::Sender<OccasionalMappingUpdateBatch>,
+    continuous_mapping_update_sender: tokio::sync::broadcast::Sender<ContinuousMappingUpdateBatch>,
 }
 
 #[derive(Debug)]
@@ -287,6 +289,7 @@ impl App {
                 config.main.server_http_port,
                 config.main.server_https_port,
                 config.main.server_grpc_port,
+                Some(config.main.server_osc_query_port).filter(|p| *p > 0),
                 App::server_resource_dir_path().join("certificates"),
                 MetricsReporter::new(),
             ))),
@@ -314,6 +317,8 @@ impl App {
             continuous_slot_update_sender: tokio::sync::broadcast::channel(1000).0,
             continuous_column_update_sender: tokio::sync::broadcast::channel(500).0,
             continuous_matrix_update_sender: tokio::sync::broadcast::channel(500).0,
+            occasional_mapping_update_sender: tokio::sync::broadcast::channel(100).0,
+            continuous_mapping_update_sender: tokio::sync::broadcast::channel(1000).0,
         }
     }
 
@@ -684,6 +689,18 @@ impl App {
         &self.continuous_matrix_update_sender
     }
 
+    pub fn occasional_mapping_update_sender(
+        &self,
+    ) -> &tokio::sync::broadcast::Sender<OccasionalMappingUpdateBatch> {
+        &self.occasional_mapping_update_sender
+    }
+
+    pub fn continuous_mapping_update_sender(
+        &self,
+    ) -> &tokio::sync::broadcast::Sender<ContinuousMappingUpdateBatch> {
+        &self.continuous_mapping_update_sender
+    }
+
     fn temporarily_reclaim_control_surface_ownership(
         &self,
         f: impl FnOnce(&mut RealearnControlSurface),
@@ -1590,6 +1607,9 @@ struct MainConfig {
         skip_serializing_if = "is_default_server_grpc_port"
     )]
     server_grpc_port: u16,
+    /// OSCQuery service is only started if a port is set.
+    #[serde(default, skip_serializing_if = "is_default")]
+    server_osc_query_port: u16,
     #[serde(
         default = "default_companion_web_app_url",
         skip_serializing_if = "is_default_companion_web_app_url"
@@ -1640,6 +1660,7 @@ impl Default for MainConfig {
             server_http_port: default_server_http_port(),
             server_https_port: default_server_https_port(),
             server_grpc_port: default_server_grpc_port(),
+            server_osc_query_port: Default::default(),
             companion_web_app_url: default_companion_web_app_url(),
         }
     }
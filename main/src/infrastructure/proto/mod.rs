@@ -0,0 +1,79 @@
+mod realearn;
+
+use crate::application::Session;
+use crate::domain;
+use crate::domain::{MappingKey, QualifiedMappingId};
+use enum_iterator::IntoEnumIterator;
+use helgoboss_learn::AbsoluteValue;
+pub use realearn::*;
+
+impl Compartment {
+    pub fn from_domain(compartment: domain::Compartment) -> Self {
+        match compartment {
+            domain::Compartment::Controller => Self::Controller,
+            domain::Compartment::Main => Self::Main,
+        }
+    }
+
+    pub fn to_domain(self) -> domain::Compartment {
+        match self {
+            Self::Controller => domain::Compartment::Controller,
+            Self::Main => domain::Compartment::Main,
+        }
+    }
+}
+
+impl MappingExclusivity {
+    pub fn to_domain(self) -> domain::Exclusivity {
+        match self {
+            Self::NonExclusive => domain::Exclusivity::NonExclusive,
+            Self::Exclusive => domain::Exclusivity::Exclusive,
+            Self::ExclusiveOnOnly => domain::Exclusivity::ExclusiveOnOnly,
+        }
+    }
+}
+
+impl QualifiedOccasionalMappingUpdate {
+    pub fn is_on(compartment: domain::Compartment, mapping_key: &MappingKey, is_on: bool) -> Self {
+        Self {
+            compartment: Compartment::from_domain(compartment).into(),
+            mapping_key: mapping_key.to_string(),
+            update: Some(qualified_occasional_mapping_update::Update::IsOn(is_on)),
+        }
+    }
+
+    /// Creates on/off state updates for all mappings of the given session.
+    pub fn all_on_states(session: &Session) -> Vec<Self> {
+        domain::Compartment::enum_iter()
+            .flat_map(|compartment| session.mappings(compartment))
+            .map(|m| {
+                let m = m.borrow();
+                let is_on = session.mapping_is_on(m.qualified_id());
+                Self::is_on(m.compartment(), m.key(), is_on)
+            })
+            .collect()
+    }
+
+    /// Creates an on/off state update for the given mapping (if it still exists).
+    pub fn is_on_by_id(session: &Session, id: QualifiedMappingId, is_on: bool) -> Option<Self> {
+        let key = session.find_mapping_key_by_id(id)?;
+        Some(Self::is_on(id.compartment, &key, is_on))
+    }
+}
+
+impl QualifiedContinuousMappingUpdate {
+    /// Creates a target value update for the given mapping (if it still exists).
+    pub fn target_value(
+        session: &Session,
+        id: QualifiedMappingId,
+        value: AbsoluteValue,
+    ) -> Option<Self> {
+        let key = session.find_mapping_key_by_id(id)?;
+        let update = Self {
+            compartment: Compartment::from_domain(id.compartment).into(),
+            mapping_key: key.to_string(),
+            target_value: value.to_unit_value().get(),
+        };
+        Some(update)
+    }
+}
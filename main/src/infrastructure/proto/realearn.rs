@@ -0,0 +1,455 @@
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FullCompartmentAddress {
+    #[prost(string, tag = "1")]
+    pub session_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "Compartment", tag = "2")]
+    pub compartment: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FullMappingAddress {
+    #[prost(message, optional, tag = "1")]
+    pub compartment_address: ::core::option::Option<FullCompartmentAddress>,
+    /// Mapping key (the ID used in presets and in the REST API)
+    #[prost(string, tag = "2")]
+    pub mapping_key: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Empty {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HitTargetRequest {
+    #[prost(message, optional, tag = "1")]
+    pub mapping_address: ::core::option::Option<FullMappingAddress>,
+    /// Normalized target value (0.0 to 1.0)
+    #[prost(double, tag = "2")]
+    pub value: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetMappingsEnabledByTagRequest {
+    #[prost(message, optional, tag = "1")]
+    pub compartment_address: ::core::option::Option<FullCompartmentAddress>,
+    #[prost(string, repeated, tag = "2")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag = "3")]
+    pub enabled: bool,
+    #[prost(enumeration = "MappingExclusivity", tag = "4")]
+    pub exclusivity: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LoadMappingSnapshotRequest {
+    #[prost(message, optional, tag = "1")]
+    pub compartment_address: ::core::option::Option<FullCompartmentAddress>,
+    /// Only mappings with at least one of these tags are affected. If empty, all mappings are affected.
+    #[prost(string, repeated, tag = "2")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// If true, mappings which are inactive due to conditional activation are left untouched.
+    #[prost(bool, tag = "3")]
+    pub active_mappings_only: bool,
+    /// If not set, the initial mapping snapshot is loaded.
+    #[prost(string, optional, tag = "4")]
+    pub snapshot_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetCompartmentParameterValueRequest {
+    #[prost(message, optional, tag = "1")]
+    pub compartment_address: ::core::option::Option<FullCompartmentAddress>,
+    /// Compartment-local parameter index (0 to 99)
+    #[prost(uint32, tag = "2")]
+    pub parameter_index: u32,
+    /// Normalized parameter value (0.0 to 1.0)
+    #[prost(double, tag = "3")]
+    pub value: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOccasionalMappingUpdatesRequest {
+    #[prost(string, tag = "1")]
+    pub session_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOccasionalMappingUpdatesReply {
+    #[prost(message, repeated, tag = "1")]
+    pub mapping_updates: ::prost::alloc::vec::Vec<QualifiedOccasionalMappingUpdate>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetContinuousMappingUpdatesRequest {
+    #[prost(string, tag = "1")]
+    pub session_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetContinuousMappingUpdatesReply {
+    #[prost(message, repeated, tag = "1")]
+    pub mapping_updates: ::prost::alloc::vec::Vec<QualifiedContinuousMappingUpdate>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QualifiedOccasionalMappingUpdate {
+    #[prost(enumeration = "Compartment", tag = "1")]
+    pub compartment: i32,
+    #[prost(string, tag = "2")]
+    pub mapping_key: ::prost::alloc::string::String,
+    #[prost(oneof = "qualified_occasional_mapping_update::Update", tags = "3")]
+    pub update: ::core::option::Option<qualified_occasional_mapping_update::Update>,
+}
+/// Nested message and enum types in `QualifiedOccasionalMappingUpdate`.
+pub mod qualified_occasional_mapping_update {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Update {
+        /// Whether the mapping is on (enabled and active)
+        #[prost(bool, tag = "3")]
+        IsOn(bool),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QualifiedContinuousMappingUpdate {
+    #[prost(enumeration = "Compartment", tag = "1")]
+    pub compartment: i32,
+    #[prost(string, tag = "2")]
+    pub mapping_key: ::prost::alloc::string::String,
+    /// New normalized target value (0.0 to 1.0)
+    #[prost(double, tag = "3")]
+    pub target_value: f64,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Compartment {
+    Main = 0,
+    Controller = 1,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum MappingExclusivity {
+    NonExclusive = 0,
+    Exclusive = 1,
+    ExclusiveOnOnly = 2,
+}
+pub mod realearn_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with RealearnServer."]
+    #[async_trait]
+    pub trait Realearn: Send + Sync + 'static {
+        #[doc = " Mapping commands"]
+        async fn hit_target(
+            &self,
+            request: tonic::Request<super::HitTargetRequest>,
+        ) -> Result<tonic::Response<super::Empty>, tonic::Status>;
+        async fn set_mappings_enabled_by_tag(
+            &self,
+            request: tonic::Request<super::SetMappingsEnabledByTagRequest>,
+        ) -> Result<tonic::Response<super::Empty>, tonic::Status>;
+        async fn load_mapping_snapshot(
+            &self,
+            request: tonic::Request<super::LoadMappingSnapshotRequest>,
+        ) -> Result<tonic::Response<super::Empty>, tonic::Status>;
+        #[doc = " Parameter commands"]
+        async fn set_compartment_parameter_value(
+            &self,
+            request: tonic::Request<super::SetCompartmentParameterValueRequest>,
+        ) -> Result<tonic::Response<super::Empty>, tonic::Status>;
+        #[doc = "Server streaming response type for the GetOccasionalMappingUpdates method."]
+        type GetOccasionalMappingUpdatesStream: futures_core::Stream<
+                Item = Result<super::GetOccasionalMappingUpdatesReply, tonic::Status>,
+            > + Send
+            + Sync
+            + 'static;
+        #[doc = " Mapping events"]
+        async fn get_occasional_mapping_updates(
+            &self,
+            request: tonic::Request<super::GetOccasionalMappingUpdatesRequest>,
+        ) -> Result<tonic::Response<Self::GetOccasionalMappingUpdatesStream>, tonic::Status>;
+        #[doc = "Server streaming response type for the GetContinuousMappingUpdates method."]
+        type GetContinuousMappingUpdatesStream: futures_core::Stream<
+                Item = Result<super::GetContinuousMappingUpdatesReply, tonic::Status>,
+            > + Send
+            + Sync
+            + 'static;
+        async fn get_continuous_mapping_updates(
+            &self,
+            request: tonic::Request<super::GetContinuousMappingUpdatesRequest>,
+        ) -> Result<tonic::Response<Self::GetContinuousMappingUpdatesStream>, tonic::Status>;
+    }
+    #[derive(Debug)]
+    pub struct RealearnServer<T: Realearn> {
+        inner: _Inner<T>,
+        accept_compression_encodings: (),
+        send_compression_encodings: (),
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Realearn> RealearnServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for RealearnServer<T>
+    where
+        T: Realearn,
+        B: Body + Send + Sync + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/realearn.Realearn/HitTarget" => {
+                    #[allow(non_camel_case_types)]
+                    struct HitTargetSvc<T: Realearn>(pub Arc<T>);
+                    impl<T: Realearn> tonic::server::UnaryService<super::HitTargetRequest> for HitTargetSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HitTargetRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).hit_target(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = HitTargetSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/realearn.Realearn/SetMappingsEnabledByTag" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetMappingsEnabledByTagSvc<T: Realearn>(pub Arc<T>);
+                    impl<T: Realearn>
+                        tonic::server::UnaryService<super::SetMappingsEnabledByTagRequest>
+                        for SetMappingsEnabledByTagSvc<T>
+                    {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetMappingsEnabledByTagRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).set_mappings_enabled_by_tag(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetMappingsEnabledByTagSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/realearn.Realearn/LoadMappingSnapshot" => {
+                    #[allow(non_camel_case_types)]
+                    struct LoadMappingSnapshotSvc<T: Realearn>(pub Arc<T>);
+                    impl<T: Realearn> tonic::server::UnaryService<super::LoadMappingSnapshotRequest>
+                        for LoadMappingSnapshotSvc<T>
+                    {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LoadMappingSnapshotRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).load_mapping_snapshot(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = LoadMappingSnapshotSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/realearn.Realearn/SetCompartmentParameterValue" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetCompartmentParameterValueSvc<T: Realearn>(pub Arc<T>);
+                    impl<T: Realearn>
+                        tonic::server::UnaryService<super::SetCompartmentParameterValueRequest>
+                        for SetCompartmentParameterValueSvc<T>
+                    {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetCompartmentParameterValueRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).set_compartment_parameter_value(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetCompartmentParameterValueSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/realearn.Realearn/GetOccasionalMappingUpdates" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetOccasionalMappingUpdatesSvc<T: Realearn>(pub Arc<T>);
+                    impl<T: Realearn>
+                        tonic::server::ServerStreamingService<
+                            super::GetOccasionalMappingUpdatesRequest,
+                        > for GetOccasionalMappingUpdatesSvc<T>
+                    {
+                        type Response = super::GetOccasionalMappingUpdatesReply;
+                        type ResponseStream = T::GetOccasionalMappingUpdatesStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetOccasionalMappingUpdatesRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_occasional_mapping_updates(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetOccasionalMappingUpdatesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/realearn.Realearn/GetContinuousMappingUpdates" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetContinuousMappingUpdatesSvc<T: Realearn>(pub Arc<T>);
+                    impl<T: Realearn>
+                        tonic::server::ServerStreamingService<
+                            super::GetContinuousMappingUpdatesRequest,
+                        > for GetContinuousMappingUpdatesSvc<T>
+                    {
+                        type Response = super::GetContinuousMappingUpdatesReply;
+                        type ResponseStream = T::GetContinuousMappingUpdatesStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetContinuousMappingUpdatesRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_continuous_mapping_updates(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetContinuousMappingUpdatesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: Realearn> Clone for RealearnServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: Realearn> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: Realearn> tonic::transport::NamedService for RealearnServer<T> {
+        const NAME: &'static str = "realearn.Realearn";
+    }
+}
@@ -1,16 +1,26 @@
 //! Contains the actual application interface and implementation without any HTTP-specific stuff.
 
 use crate::application::{
-    ControllerPreset, Preset, PresetManager, Session, SourceCategory, TargetCategory,
+    ControllerPreset, GroupModel, MappingModel, Preset, PresetManager, Session, SharedSession,
+    SourceCategory, TargetCategory,
 };
-use crate::domain::{BackboneState, Compartment, MappingKey, ProjectionFeedbackValue};
-use crate::infrastructure::data::{ControllerPresetData, PresetData};
+use crate::domain::{
+    BackboneState, Compartment, CompartmentParamIndex, GroupId, MappingId, MappingKey,
+    ParamSetting, ProjectionFeedbackValue, QualifiedMappingId, VirtualControlElement,
+    VirtualControlElementId, VirtualSourceValue, XyAxis,
+};
+use crate::infrastructure::api::convert::from_data::ConversionStyle;
+use crate::infrastructure::api::convert::to_data::ApiToDataConversionContext;
+use crate::infrastructure::api::convert::{from_data, to_data};
+use crate::infrastructure::data::{CompartmentModelData, ControllerPresetData, PresetData};
 use crate::infrastructure::plugin::App;
-use helgoboss_learn::UnitValue;
+use helgoboss_learn::{ControlValue, DiscreteIncrement, UnitValue};
 use maplit::hashmap;
+use realearn_api::persistence;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
+use std::iter;
 use std::rc::Rc;
 
 #[derive(Serialize)]
@@ -18,6 +28,7 @@ use std::rc::Rc;
 // Right now just a placeholder
 pub struct SessionResponseData {}
 
+#[derive(Debug)]
 pub enum DataError {
     SessionNotFound,
     SessionHasNoActiveController,
@@ -26,12 +37,24 @@ pub enum DataError {
     OnlyCustomDataKeyIsSupportedAsPatchPath,
     ControllerUpdateFailed,
     ClipMatrixNotFound,
+    CompartmentNotFound,
+    CompartmentIsReadOnly,
+    MappingNotFound,
+    GroupNotFound,
+    ParameterNotFound,
+    IdAlreadyExists,
+    IdDoesntMatchPath,
+    InvalidCompartmentData,
+    InvalidVirtualControlElement,
+    InvalidControlValue,
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum DataErrorCategory {
     NotFound,
     BadRequest,
     MethodNotAllowed,
+    Conflict,
     InternalServerError,
 }
 
@@ -48,6 +71,18 @@ impl DataError {
             }
             ControllerUpdateFailed => "couldn't update controller",
             ClipMatrixNotFound => "clip matrix not found",
+            CompartmentNotFound => "only 'main' and 'controller' are valid compartments",
+            CompartmentIsReadOnly => {
+                "compartment is read-only (preset auto-loaded or learning many mappings)"
+            }
+            MappingNotFound => "mapping not found",
+            GroupNotFound => "group not found",
+            ParameterNotFound => "parameter not found or has default settings",
+            IdAlreadyExists => "compartment already contains an item with this ID",
+            IdDoesntMatchPath => "ID in body doesn't match ID in path",
+            InvalidCompartmentData => "data is not valid in the context of this compartment",
+            InvalidVirtualControlElement => "invalid virtual control element",
+            InvalidControlValue => "relative control value must not be zero",
         }
     }
 
@@ -57,9 +92,18 @@ impl DataError {
             SessionNotFound
             | SessionHasNoActiveController
             | ControllerNotFound
-            | ClipMatrixNotFound => DataErrorCategory::NotFound,
+            | ClipMatrixNotFound
+            | CompartmentNotFound
+            | MappingNotFound
+            | GroupNotFound
+            | ParameterNotFound => DataErrorCategory::NotFound,
             OnlyPatchReplaceIsSupported => DataErrorCategory::MethodNotAllowed,
-            OnlyCustomDataKeyIsSupportedAsPatchPath => DataErrorCategory::BadRequest,
+            OnlyCustomDataKeyIsSupportedAsPatchPath
+            | IdDoesntMatchPath
+            | InvalidCompartmentData
+            | InvalidVirtualControlElement
+            | InvalidControlValue => DataErrorCategory::BadRequest,
+            CompartmentIsReadOnly | IdAlreadyExists => DataErrorCategory::Conflict,
             ControllerUpdateFailed => DataErrorCategory::InternalServerError,
         }
     }
@@ -231,6 +275,409 @@ pub fn patch_controller(controller_id: String, req: PatchRequest) -> Result<(),
     Ok(())
 }
 
+/// A mapping, group or parameter which can be read and edited individually via the API.
+pub trait CompartmentItem: Sized {
+    const NOT_FOUND: DataError;
+
+    fn items(compartment: &mut persistence::Compartment) -> &mut Option<Vec<Self>>;
+
+    fn key(&self) -> Option<String>;
+
+    /// Assigns the given key. Called for items which are created or updated without key.
+    fn set_key(&mut self, key: String);
+
+    fn exists_in(compartment: &LiveCompartment, key: &str) -> bool;
+
+    fn add_to(self, compartment: &LiveCompartment) -> Result<(), DataError>;
+
+    /// Replaces the existing item which has the same key.
+    fn replace_in(self, compartment: &LiveCompartment) -> Result<(), DataError>;
+
+    fn remove_from(compartment: &LiveCompartment, key: &str) -> Result<(), DataError>;
+}
+
+/// A compartment of a running session.
+///
+/// Items are edited one by one using targeted session operations, so mapping actions (e.g.
+/// learning) and parameter values are not affected by editing unrelated items.
+pub struct LiveCompartment {
+    session: SharedSession,
+    compartment: Compartment,
+}
+
+impl LiveCompartment {
+    fn convert_mapping(&self, mapping: persistence::Mapping) -> Result<MappingModel, DataError> {
+        let session = self.session.borrow();
+        let context = session.compartment_in_session(self.compartment);
+        let data = to_data::convert_mapping(mapping, &context)
+            .map_err(|_| DataError::InvalidCompartmentData)?;
+        data.to_model(
+            self.compartment,
+            &context,
+            Some(session.extended_context()),
+            Some(App::version()),
+        )
+        .map_err(|_| DataError::InvalidCompartmentData)
+    }
+
+    fn convert_group(&self, group: persistence::Group) -> Result<GroupModel, DataError> {
+        let session = self.session.borrow();
+        let context = session.compartment_in_session(self.compartment);
+        let data = to_data::convert_group(group, false, |key| context.param_index_by_key(key))
+            .map_err(|_| DataError::InvalidCompartmentData)?;
+        Ok(data.to_model(self.compartment, false, &context))
+    }
+
+    fn find_group_id(&self, key: &str) -> Option<GroupId> {
+        let session = self.session.borrow();
+        let group = session.find_group_by_key(self.compartment, &key.to_string().into())?;
+        let id = group.borrow().id();
+        Some(id)
+    }
+
+    fn find_mapping_id(&self, key: &str) -> Option<MappingId> {
+        self.session
+            .borrow()
+            .find_mapping_id_by_key(self.compartment, &key.to_string().into())
+    }
+
+    fn parameter_has_settings(&self, index: CompartmentParamIndex) -> bool {
+        let session = self.session.borrow();
+        let params = session.params().compartment_params(self.compartment);
+        !params.at(index).setting.is_default()
+    }
+
+    fn update_parameter_setting(&self, index: CompartmentParamIndex, setting: ParamSetting) {
+        self.session
+            .borrow_mut()
+            .update_certain_param_settings(self.compartment, vec![(index, setting)]);
+    }
+}
+
+impl CompartmentItem for persistence::Mapping {
+    const NOT_FOUND: DataError = DataError::MappingNotFound;
+
+    fn items(compartment: &mut persistence::Compartment) -> &mut Option<Vec<Self>> {
+        &mut compartment.mappings
+    }
+
+    fn key(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    fn set_key(&mut self, key: String) {
+        self.id = Some(key);
+    }
+
+    fn exists_in(compartment: &LiveCompartment, key: &str) -> bool {
+        compartment.find_mapping_id(key).is_some()
+    }
+
+    fn add_to(self, compartment: &LiveCompartment) -> Result<(), DataError> {
+        let mapping = compartment.convert_mapping(self)?;
+        let mut session = compartment.session.borrow_mut();
+        let index = session.mapping_count(compartment.compartment);
+        session.insert_mappings_at(compartment.compartment, index, iter::once(mapping));
+        Ok(())
+    }
+
+    fn replace_in(self, compartment: &LiveCompartment) -> Result<(), DataError> {
+        let mapping = compartment.convert_mapping(self)?;
+        compartment
+            .session
+            .borrow_mut()
+            .replace_mapping(mapping, Rc::downgrade(&compartment.session))
+            .map_err(|_| Self::NOT_FOUND)
+    }
+
+    fn remove_from(compartment: &LiveCompartment, key: &str) -> Result<(), DataError> {
+        let id = compartment.find_mapping_id(key).ok_or(Self::NOT_FOUND)?;
+        compartment
+            .session
+            .borrow_mut()
+            .remove_mapping(QualifiedMappingId::new(compartment.compartment, id));
+        Ok(())
+    }
+}
+
+impl CompartmentItem for persistence::Group {
+    const NOT_FOUND: DataError = DataError::GroupNotFound;
+
+    fn items(compartment: &mut persistence::Compartment) -> &mut Option<Vec<Self>> {
+        &mut compartment.groups
+    }
+
+    fn key(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    fn set_key(&mut self, key: String) {
+        self.id = Some(key);
+    }
+
+    fn exists_in(compartment: &LiveCompartment, key: &str) -> bool {
+        compartment.find_group_id(key).is_some()
+    }
+
+    fn add_to(self, compartment: &LiveCompartment) -> Result<(), DataError> {
+        let group = compartment.convert_group(self)?;
+        compartment
+            .session
+            .borrow_mut()
+            .add_group(compartment.compartment, group);
+        Ok(())
+    }
+
+    fn replace_in(self, compartment: &LiveCompartment) -> Result<(), DataError> {
+        let group = compartment.convert_group(self)?;
+        compartment
+            .session
+            .borrow_mut()
+            .replace_group(group, Rc::downgrade(&compartment.session))
+            .map_err(|_| Self::NOT_FOUND)
+    }
+
+    fn remove_from(compartment: &LiveCompartment, key: &str) -> Result<(), DataError> {
+        let id = compartment.find_group_id(key).ok_or(Self::NOT_FOUND)?;
+        // Same as removing a group in the UI without deleting its mappings
+        compartment
+            .session
+            .borrow_mut()
+            .remove_group(compartment.compartment, id, false);
+        Ok(())
+    }
+}
+
+impl CompartmentItem for persistence::Parameter {
+    const NOT_FOUND: DataError = DataError::ParameterNotFound;
+
+    fn items(compartment: &mut persistence::Compartment) -> &mut Option<Vec<Self>> {
+        &mut compartment.parameters
+    }
+
+    fn key(&self) -> Option<String> {
+        Some(self.index.to_string())
+    }
+
+    fn set_key(&mut self, key: String) {
+        if let Ok(index) = key.parse() {
+            self.index = index;
+        }
+    }
+
+    fn exists_in(compartment: &LiveCompartment, key: &str) -> bool {
+        match parse_param_index(key) {
+            Ok(index) => compartment.parameter_has_settings(index),
+            Err(_) => false,
+        }
+    }
+
+    fn add_to(self, compartment: &LiveCompartment) -> Result<(), DataError> {
+        let index = CompartmentParamIndex::try_from(self.index)
+            .map_err(|_| DataError::InvalidCompartmentData)?;
+        let setting =
+            to_data::convert_parameter(self).map_err(|_| DataError::InvalidCompartmentData)?;
+        compartment.update_parameter_setting(index, setting);
+        Ok(())
+    }
+
+    fn replace_in(self, compartment: &LiveCompartment) -> Result<(), DataError> {
+        // Only changes the parameter settings, not the parameter value
+        self.add_to(compartment)
+    }
+
+    fn remove_from(compartment: &LiveCompartment, key: &str) -> Result<(), DataError> {
+        let index = parse_param_index(key)?;
+        compartment.update_parameter_setting(index, Default::default());
+        Ok(())
+    }
+}
+
+/// Targeted operations for editing the items of one compartment.
+trait CompartmentItemEditor<T> {
+    fn is_read_only(&self) -> bool;
+
+    fn contains(&self, key: &str) -> bool;
+
+    fn add(&mut self, item: T) -> Result<(), DataError>;
+
+    fn replace(&mut self, item: T) -> Result<(), DataError>;
+
+    fn remove(&mut self, key: &str) -> Result<(), DataError>;
+}
+
+impl<T: CompartmentItem> CompartmentItemEditor<T> for LiveCompartment {
+    fn is_read_only(&self) -> bool {
+        self.session
+            .borrow()
+            .mappings_are_read_only(self.compartment)
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        T::exists_in(self, key)
+    }
+
+    fn add(&mut self, item: T) -> Result<(), DataError> {
+        item.add_to(self)
+    }
+
+    fn replace(&mut self, item: T) -> Result<(), DataError> {
+        item.replace_in(self)
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), DataError> {
+        T::remove_from(self, key)
+    }
+}
+
+pub fn get_compartment_items<T: CompartmentItem>(
+    session_id: &str,
+    compartment: &str,
+) -> Result<Vec<T>, DataError> {
+    let session = find_session(session_id)?;
+    let compartment = parse_compartment(compartment)?;
+    let mut api_compartment = extract_api_compartment(&session.borrow(), compartment)?;
+    Ok(T::items(&mut api_compartment).take().unwrap_or_default())
+}
+
+pub fn get_compartment_item<T: CompartmentItem>(
+    session_id: &str,
+    compartment: &str,
+    key: &str,
+) -> Result<T, DataError> {
+    get_compartment_items::<T>(session_id, compartment)?
+        .into_iter()
+        .find(|item| item.key().as_deref() == Some(key))
+        .ok_or(T::NOT_FOUND)
+}
+
+/// Returns the created item as it has been added to the compartment, which contains the
+/// generated key if none was given.
+pub fn create_compartment_item<T: CompartmentItem>(
+    session_id: &str,
+    compartment: &str,
+    item: T,
+) -> Result<T, DataError> {
+    let mut live_compartment = find_live_compartment(session_id, compartment)?;
+    let key = create_item(&mut live_compartment, item)?;
+    get_compartment_item(session_id, compartment, &key)
+}
+
+/// Replaces the complete item, so properties which are not given are reset to their defaults.
+pub fn update_compartment_item<T: CompartmentItem>(
+    session_id: &str,
+    compartment: &str,
+    key: &str,
+    item: T,
+) -> Result<(), DataError> {
+    let mut live_compartment = find_live_compartment(session_id, compartment)?;
+    update_item(&mut live_compartment, key, item)
+}
+
+pub fn delete_compartment_item<T: CompartmentItem>(
+    session_id: &str,
+    compartment: &str,
+    key: &str,
+) -> Result<(), DataError> {
+    let mut live_compartment = find_live_compartment(session_id, compartment)?;
+    delete_item::<T>(&mut live_compartment, key)
+}
+
+/// Returns the key of the created item.
+fn create_item<T: CompartmentItem>(
+    editor: &mut impl CompartmentItemEditor<T>,
+    mut item: T,
+) -> Result<String, DataError> {
+    if editor.is_read_only() {
+        return Err(DataError::CompartmentIsReadOnly);
+    }
+    let key = match item.key() {
+        None => {
+            let key = nanoid::nanoid!();
+            item.set_key(key.clone());
+            key
+        }
+        Some(k) => k,
+    };
+    if editor.contains(&key) {
+        return Err(DataError::IdAlreadyExists);
+    }
+    editor.add(item)?;
+    Ok(key)
+}
+
+fn update_item<T: CompartmentItem>(
+    editor: &mut impl CompartmentItemEditor<T>,
+    key: &str,
+    mut item: T,
+) -> Result<(), DataError> {
+    match item.key() {
+        None => item.set_key(key.to_string()),
+        Some(k) if k != key => return Err(DataError::IdDoesntMatchPath),
+        Some(_) => {}
+    }
+    if editor.is_read_only() {
+        return Err(DataError::CompartmentIsReadOnly);
+    }
+    if !editor.contains(key) {
+        return Err(T::NOT_FOUND);
+    }
+    editor.replace(item)
+}
+
+fn delete_item<T: CompartmentItem>(
+    editor: &mut impl CompartmentItemEditor<T>,
+    key: &str,
+) -> Result<(), DataError> {
+    if editor.is_read_only() {
+        return Err(DataError::CompartmentIsReadOnly);
+    }
+    if !editor.contains(key) {
+        return Err(T::NOT_FOUND);
+    }
+    editor.remove(key)
+}
+
+fn find_live_compartment(
+    session_id: &str,
+    compartment: &str,
+) -> Result<LiveCompartment, DataError> {
+    let live_compartment = LiveCompartment {
+        session: find_session(session_id)?,
+        compartment: parse_compartment(compartment)?,
+    };
+    Ok(live_compartment)
+}
+
+fn parse_param_index(key: &str) -> Result<CompartmentParamIndex, DataError> {
+    let index: u32 = key.parse().map_err(|_| DataError::ParameterNotFound)?;
+    CompartmentParamIndex::try_from(index).map_err(|_| DataError::ParameterNotFound)
+}
+
+fn extract_api_compartment(
+    session: &Session,
+    compartment: Compartment,
+) -> Result<persistence::Compartment, DataError> {
+    let model = session.extract_compartment_model(compartment);
+    let data = CompartmentModelData::from_model(&model);
+    from_data::convert_compartment(data, ConversionStyle::Minimal)
+        .map_err(|_| DataError::InvalidCompartmentData)
+}
+
+fn find_session(session_id: &str) -> Result<SharedSession, DataError> {
+    App::get()
+        .find_session_by_id(session_id)
+        .ok_or(DataError::SessionNotFound)
+}
+
+fn parse_compartment(compartment: &str) -> Result<Compartment, DataError> {
+    match compartment {
+        "main" => Ok(Compartment::Main),
+        "controller" => Ok(Compartment::Controller),
+        _ => Err(DataError::CompartmentNotFound),
+    }
+}
+
 #[derive(Deserialize)]
 pub struct WebSocketRequest {
     pub topics: String,
@@ -276,6 +723,119 @@ impl TryFrom<&str> for Topic {
     }
 }
 
+/// A message sent from a WebSocket client to ReaLearn.
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+pub enum WebSocketClientMessage {
+    /// Sets the value of a virtual control element, just like a controller mapping would do.
+    ///
+    /// The resulting projection feedback is sent to all clients subscribed to the feedback topic,
+    /// except the one which sent this message (echo feedback prevention).
+    #[serde(rename_all = "camelCase")]
+    ControlVirtualElement {
+        session_id: String,
+        element_id: persistence::VirtualControlElementId,
+        #[serde(default)]
+        element_character: persistence::VirtualControlElementCharacter,
+        /// Only relevant for XY control elements.
+        #[serde(default)]
+        element_axis: persistence::XyAxis,
+        value: VirtualControlValue,
+    },
+}
+
+#[derive(Copy, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VirtualControlValue {
+    /// Value between 0.0 and 1.0.
+    Absolute(f64),
+    /// Number of increments, negative for decrements.
+    Relative(i32),
+}
+
+/// A virtual control element which has been controlled by a WebSocket client.
+pub struct ControlledVirtualElement {
+    pub session_id: String,
+    pub control_element: VirtualControlElement,
+}
+
+pub fn process_websocket_client_message(
+    msg: WebSocketClientMessage,
+) -> Result<ControlledVirtualElement, DataError> {
+    let controlled_element = match msg {
+        WebSocketClientMessage::ControlVirtualElement {
+            session_id,
+            element_id,
+            element_character,
+            element_axis,
+            value,
+        } => {
+            let session = find_session(&session_id)?;
+            let element_id = match element_id {
+                persistence::VirtualControlElementId::Indexed(i) => {
+                    VirtualControlElementId::Indexed(i)
+                }
+                persistence::VirtualControlElementId::Named(name) => name
+                    .parse()
+                    .map_err(|_| DataError::InvalidVirtualControlElement)?,
+            };
+            let control_element = match element_character {
+                persistence::VirtualControlElementCharacter::Multi => {
+                    VirtualControlElement::Multi(element_id)
+                }
+                persistence::VirtualControlElementCharacter::Button => {
+                    VirtualControlElement::Button(element_id)
+                }
+                persistence::VirtualControlElementCharacter::Xy => {
+                    VirtualControlElement::Xy(element_id)
+                }
+            };
+            let control_value = match value {
+                VirtualControlValue::Absolute(v) => {
+                    ControlValue::AbsoluteContinuous(UnitValue::new_clamped(v))
+                }
+                VirtualControlValue::Relative(0) => return Err(DataError::InvalidControlValue),
+                VirtualControlValue::Relative(i) => {
+                    ControlValue::RelativeDiscrete(DiscreteIncrement::new(i))
+                }
+            };
+            let axis = match element_axis {
+                persistence::XyAxis::X => XyAxis::X,
+                persistence::XyAxis::Y => XyAxis::Y,
+            };
+            session.borrow().process_virtual_source_value(
+                VirtualSourceValue::new(control_element, control_value).with_axis(axis),
+            );
+            ControlledVirtualElement {
+                session_id,
+                control_element,
+            }
+        }
+    };
+    Ok(controlled_element)
+}
+
+/// Returns the virtual control element targeted by the given mapping.
+///
+/// Returns `None` if it's not a controller mapping with a virtual target.
+pub fn get_virtual_target_control_element(
+    session: &Session,
+    compartment: Compartment,
+    mapping_key: &str,
+) -> Option<VirtualControlElement> {
+    if compartment != Compartment::Controller {
+        return None;
+    }
+    session.mappings(compartment).find_map(|m| {
+        let m = m.borrow();
+        let key: &str = m.key().as_ref();
+        if key != mapping_key || m.target_model.category() != TargetCategory::Virtual {
+            return None;
+        }
+        Some(m.target_model.create_control_element())
+    })
+}
+
 pub fn send_initial_feedback(session_id: &str) {
     if let Some(session) = App::get().find_session_by_id(session_id) {
         session.borrow_mut().send_all_feedback();
@@ -387,3 +947,175 @@ fn get_controller_preset_data_internal(
     );
     Ok(ControllerPresetData::from_model(&preset))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeCompartment {
+        read_only: bool,
+        mappings: Vec<persistence::Mapping>,
+    }
+
+    impl CompartmentItemEditor<persistence::Mapping> for FakeCompartment {
+        fn is_read_only(&self) -> bool {
+            self.read_only
+        }
+
+        fn contains(&self, key: &str) -> bool {
+            self.mappings.iter().any(|m| m.id.as_deref() == Some(key))
+        }
+
+        fn add(&mut self, item: persistence::Mapping) -> Result<(), DataError> {
+            self.mappings.push(item);
+            Ok(())
+        }
+
+        fn replace(&mut self, item: persistence::Mapping) -> Result<(), DataError> {
+            let existing = self
+                .mappings
+                .iter_mut()
+                .find(|m| m.id == item.id)
+                .ok_or(DataError::MappingNotFound)?;
+            *existing = item;
+            Ok(())
+        }
+
+        fn remove(&mut self, key: &str) -> Result<(), DataError> {
+            self.mappings.retain(|m| m.id.as_deref() != Some(key));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn create_generates_key() {
+        // Given
+        let mut compartment = FakeCompartment::default();
+        // When
+        let key = create_item(&mut compartment, persistence::Mapping::default()).unwrap();
+        // Then
+        assert!(!key.is_empty());
+        assert_eq!(compartment.mappings[0].id.as_deref(), Some(key.as_str()));
+    }
+
+    #[test]
+    fn create_rejects_existing_key() {
+        // Given
+        let mut compartment = FakeCompartment::default();
+        create_item(&mut compartment, mapping("a", "first")).unwrap();
+        // When
+        let result = create_item(&mut compartment, mapping("a", "second"));
+        // Then
+        assert!(matches!(result, Err(DataError::IdAlreadyExists)));
+        assert_eq!(compartment.mappings.len(), 1);
+    }
+
+    #[test]
+    fn update_replaces_item() {
+        // Given
+        let mut compartment = FakeCompartment::default();
+        create_item(&mut compartment, mapping("a", "old")).unwrap();
+        // When
+        let mut new_mapping = mapping("a", "new");
+        new_mapping.id = None;
+        update_item(&mut compartment, "a", new_mapping).unwrap();
+        // Then
+        assert_eq!(compartment.mappings.len(), 1);
+        assert_eq!(compartment.mappings[0].id.as_deref(), Some("a"));
+        assert_eq!(compartment.mappings[0].name.as_deref(), Some("new"));
+    }
+
+    #[test]
+    fn update_rejects_invalid_key() {
+        // Given
+        let mut compartment = FakeCompartment::default();
+        create_item(&mut compartment, mapping("a", "old")).unwrap();
+        // When
+        let mismatch_result = update_item(&mut compartment, "a", mapping("b", "new"));
+        let not_found_result = update_item(&mut compartment, "b", mapping("b", "new"));
+        // Then
+        assert!(matches!(mismatch_result, Err(DataError::IdDoesntMatchPath)));
+        assert!(matches!(not_found_result, Err(DataError::MappingNotFound)));
+    }
+
+    #[test]
+    fn delete_removes_item() {
+        // Given
+        let mut compartment = FakeCompartment::default();
+        create_item(&mut compartment, mapping("a", "first")).unwrap();
+        create_item(&mut compartment, mapping("b", "second")).unwrap();
+        // When
+        delete_item::<persistence::Mapping>(&mut compartment, "a").unwrap();
+        let result = delete_item::<persistence::Mapping>(&mut compartment, "a");
+        // Then
+        assert!(matches!(result, Err(DataError::MappingNotFound)));
+        assert_eq!(compartment.mappings.len(), 1);
+        assert_eq!(compartment.mappings[0].id.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn read_only_compartment_rejects_changes() {
+        // Given
+        let mut compartment = FakeCompartment {
+            read_only: true,
+            mappings: vec![mapping("a", "first")],
+        };
+        // When
+        let create_result = create_item(&mut compartment, mapping("b", "second"));
+        let update_result = update_item(&mut compartment, "a", mapping("a", "new"));
+        let delete_result = delete_item::<persistence::Mapping>(&mut compartment, "a");
+        // Then
+        assert!(matches!(
+            create_result,
+            Err(DataError::CompartmentIsReadOnly)
+        ));
+        assert!(matches!(
+            update_result,
+            Err(DataError::CompartmentIsReadOnly)
+        ));
+        assert!(matches!(
+            delete_result,
+            Err(DataError::CompartmentIsReadOnly)
+        ));
+        assert_eq!(compartment.mappings[0].name.as_deref(), Some("first"));
+    }
+
+    #[test]
+    fn parameter_keys() {
+        assert!(parse_param_index("0").is_ok());
+        assert!(matches!(
+            parse_param_index("foo"),
+            Err(DataError::ParameterNotFound)
+        ));
+        assert!(matches!(
+            parse_param_index("100000"),
+            Err(DataError::ParameterNotFound)
+        ));
+    }
+
+    #[test]
+    fn error_categories() {
+        use DataError::*;
+        use DataErrorCategory as C;
+        assert_eq!(MappingNotFound.category(), C::NotFound);
+        assert_eq!(GroupNotFound.category(), C::NotFound);
+        assert_eq!(ParameterNotFound.category(), C::NotFound);
+        assert_eq!(CompartmentNotFound.category(), C::NotFound);
+        assert_eq!(SessionNotFound.category(), C::NotFound);
+        assert_eq!(IdDoesntMatchPath.category(), C::BadRequest);
+        assert_eq!(InvalidCompartmentData.category(), C::BadRequest);
+        assert_eq!(IdAlreadyExists.category(), C::Conflict);
+        assert_eq!(CompartmentIsReadOnly.category(), C::Conflict);
+        assert_eq!(OnlyPatchReplaceIsSupported.category(), C::MethodNotAllowed);
+        assert_eq!(ControllerUpdateFailed.category(), C::InternalServerError);
+    }
+
+    fn mapping(key: &str, name: &str) -> persistence::Mapping {
+        persistence::Mapping {
+            id: Some(key.to_owned()),
+            name: Some(name.to_owned()),
+            ..Default::default()
+        }
+    }
+}
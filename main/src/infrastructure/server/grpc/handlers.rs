@@ -1,7 +1,19 @@
-use crate::domain::RealearnClipMatrix;
+use crate::application::Session;
+use crate::domain::{
+    Compartment, CompartmentParamIndex, MappingKey, QualifiedMappingId, RealearnClipMatrix, Tag,
+    TagScope, VirtualMappingSnapshotIdForLoad,
+};
 use crate::infrastructure::plugin::App;
+use crate::infrastructure::proto::{
+    realearn_server, Compartment as ProtoCompartment, Empty as RealearnEmpty,
+    FullCompartmentAddress, GetContinuousMappingUpdatesReply, GetContinuousMappingUpdatesRequest,
+    GetOccasionalMappingUpdatesReply, GetOccasionalMappingUpdatesRequest, HitTargetRequest,
+    LoadMappingSnapshotRequest, MappingExclusivity, QualifiedOccasionalMappingUpdate,
+    SetCompartmentParameterValueRequest, SetMappingsEnabledByTagRequest,
+};
 use crate::infrastructure::server::grpc::WithSessionId;
 use futures::{Stream, StreamExt};
+use helgoboss_learn::{ControlValue, UnitValue};
 use playtime_clip_engine::base::{ClipAddress, ClipSlotAddress};
 use playtime_clip_engine::proto;
 use playtime_clip_engine::proto::{
@@ -23,7 +35,8 @@ use playtime_clip_engine::proto::{
 use playtime_clip_engine::rt::ColumnPlayClipOptions;
 use reaper_high::{GroupingBehavior, Guid, OrCurrentProject, Pan, Reaper, Tempo, Track, Volume};
 use reaper_medium::{Bpm, CommandId, Db, GangBehavior, ReaperPanValue, UndoBehavior};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::pin::Pin;
 use std::{future, iter};
 use tokio_stream::wrappers::BroadcastStream;
@@ -433,6 +446,129 @@ impl clip_engine_server::ClipEngine for RealearnClipEngine {
     }
 }
 
+#[derive(Debug, Default)]
+pub struct RealearnService {}
+
+#[tonic::async_trait]
+impl realearn_server::Realearn for RealearnService {
+    async fn hit_target(
+        &self,
+        request: Request<HitTargetRequest>,
+    ) -> Result<Response<RealearnEmpty>, Status> {
+        let req = request.into_inner();
+        let mapping_address = req
+            .mapping_address
+            .ok_or_else(|| Status::invalid_argument("need full mapping address"))?;
+        let value = parse_unit_value(req.value)?;
+        let key = MappingKey::from(mapping_address.mapping_key);
+        handle_compartment_command(
+            &mapping_address.compartment_address,
+            |session, compartment| {
+                let mapping_id = session
+                    .find_mapping_id_by_key(compartment, &key)
+                    .ok_or_else(|| Status::not_found("mapping not found"))?;
+                session.hit_target(
+                    QualifiedMappingId::new(compartment, mapping_id),
+                    ControlValue::AbsoluteContinuous(value),
+                );
+                Ok(())
+            },
+        )
+    }
+
+    async fn set_mappings_enabled_by_tag(
+        &self,
+        request: Request<SetMappingsEnabledByTagRequest>,
+    ) -> Result<Response<RealearnEmpty>, Status> {
+        let req = request.into_inner();
+        let exclusivity = MappingExclusivity::from_i32(req.exclusivity)
+            .ok_or_else(|| Status::invalid_argument("unknown mapping exclusivity"))?
+            .to_domain();
+        let scope = parse_tag_scope(&req.tags)?;
+        handle_compartment_command(&req.compartment_address, |session, compartment| {
+            session.enable_mappings_by_tag(compartment, scope, exclusivity, req.enabled);
+            Ok(())
+        })
+    }
+
+    async fn load_mapping_snapshot(
+        &self,
+        request: Request<LoadMappingSnapshotRequest>,
+    ) -> Result<Response<RealearnEmpty>, Status> {
+        let req = request.into_inner();
+        let scope = parse_tag_scope(&req.tags)?;
+        let snapshot_id = match &req.snapshot_id {
+            None => VirtualMappingSnapshotIdForLoad::Initial,
+            Some(id) => {
+                VirtualMappingSnapshotIdForLoad::ById(id.parse().map_err(Status::invalid_argument)?)
+            }
+        };
+        handle_compartment_command(&req.compartment_address, |session, compartment| {
+            session.load_mapping_snapshot(
+                compartment,
+                scope,
+                req.active_mappings_only,
+                snapshot_id,
+            );
+            Ok(())
+        })
+    }
+
+    async fn set_compartment_parameter_value(
+        &self,
+        request: Request<SetCompartmentParameterValueRequest>,
+    ) -> Result<Response<RealearnEmpty>, Status> {
+        let req = request.into_inner();
+        let index = CompartmentParamIndex::try_from(req.parameter_index)
+            .map_err(Status::invalid_argument)?;
+        let value = parse_unit_value(req.value)?;
+        handle_compartment_command(&req.compartment_address, |session, compartment| {
+            session.set_compartment_parameter_value(compartment, index, value);
+            Ok(())
+        })
+    }
+
+    type GetOccasionalMappingUpdatesStream =
+        SyncBoxStream<'static, Result<GetOccasionalMappingUpdatesReply, Status>>;
+
+    async fn get_occasional_mapping_updates(
+        &self,
+        request: Request<GetOccasionalMappingUpdatesRequest>,
+    ) -> Result<Response<Self::GetOccasionalMappingUpdatesStream>, Status> {
+        // Initial
+        let session = App::get()
+            .find_session_by_id(&request.get_ref().session_id)
+            .ok_or_else(|| Status::not_found("session not found"))?;
+        let initial_reply = GetOccasionalMappingUpdatesReply {
+            mapping_updates: QualifiedOccasionalMappingUpdate::all_on_states(&session.borrow()),
+        };
+        // On change
+        let receiver = App::get().occasional_mapping_update_sender().subscribe();
+        stream_by_session_id(
+            request.into_inner().session_id,
+            receiver,
+            |mapping_updates| GetOccasionalMappingUpdatesReply { mapping_updates },
+            Some(initial_reply).into_iter(),
+        )
+    }
+
+    type GetContinuousMappingUpdatesStream =
+        SyncBoxStream<'static, Result<GetContinuousMappingUpdatesReply, Status>>;
+
+    async fn get_continuous_mapping_updates(
+        &self,
+        request: Request<GetContinuousMappingUpdatesRequest>,
+    ) -> Result<Response<Self::GetContinuousMappingUpdatesStream>, Status> {
+        let receiver = App::get().continuous_mapping_update_sender().subscribe();
+        stream_by_session_id(
+            request.into_inner().session_id,
+            receiver,
+            |mapping_updates| GetContinuousMappingUpdatesReply { mapping_updates },
+            iter::empty(),
+        )
+    }
+}
+
 type SyncBoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + Sync + 'a>>;
 
 fn stream_by_session_id<T, R, F, I>(
@@ -545,3 +681,34 @@ fn convert_clip_address_to_engine(
         .map_err(Status::invalid_argument)?;
     Ok(addr)
 }
+
+fn handle_compartment_command(
+    compartment_address: &Option<FullCompartmentAddress>,
+    handler: impl FnOnce(&Session, Compartment) -> Result<(), Status>,
+) -> Result<Response<RealearnEmpty>, Status> {
+    let compartment_address = compartment_address
+        .as_ref()
+        .ok_or_else(|| Status::invalid_argument("need full compartment address"))?;
+    let compartment = ProtoCompartment::from_i32(compartment_address.compartment)
+        .ok_or_else(|| Status::invalid_argument("unknown compartment"))?
+        .to_domain();
+    let session = App::get()
+        .find_session_by_id(&compartment_address.session_id)
+        .ok_or_else(|| Status::not_found("session not found"))?;
+    let session = session.borrow();
+    handler(&session, compartment)?;
+    Ok(Response::new(RealearnEmpty {}))
+}
+
+fn parse_tag_scope(tags: &[String]) -> Result<TagScope, Status> {
+    let tags: Result<HashSet<Tag>, _> = tags.iter().map(|t| t.parse()).collect();
+    let scope = TagScope {
+        tags: tags.map_err(Status::invalid_argument)?,
+    };
+    Ok(scope)
+}
+
+fn parse_unit_value(value: f64) -> Result<UnitValue, Status> {
+    UnitValue::try_from(value)
+        .map_err(|_| Status::invalid_argument("value must be between 0.0 and 1.0"))
+}
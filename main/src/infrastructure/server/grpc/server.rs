@@ -1,4 +1,8 @@
-use crate::infrastructure::server::grpc::handlers::RealearnClipEngine;
+use crate::infrastructure::proto::realearn_server::RealearnServer;
+use crate::infrastructure::proto::{
+    QualifiedContinuousMappingUpdate, QualifiedOccasionalMappingUpdate,
+};
+use crate::infrastructure::server::grpc::handlers::{RealearnClipEngine, RealearnService};
 use crate::infrastructure::server::layers::MainThreadLayer;
 use playtime_clip_engine::proto::clip_engine_server::ClipEngineServer;
 use playtime_clip_engine::proto::{
@@ -15,9 +19,11 @@ pub async fn start_grpc_server(
     mut shutdown_receiver: broadcast::Receiver<()>,
 ) -> Result<(), tonic::transport::Error> {
     let clip_engine = RealearnClipEngine::default();
+    let realearn = RealearnService::default();
     Server::builder()
         .layer(MainThreadLayer)
         .add_service(ClipEngineServer::new(clip_engine))
+        .add_service(RealearnServer::new(realearn))
         .serve_with_shutdown(
             address,
             async move { shutdown_receiver.recv().await.unwrap() },
@@ -38,3 +44,5 @@ pub type OccasionalClipUpdateBatch = WithSessionId<Vec<QualifiedOccasionalClipUp
 pub type ContinuousMatrixUpdateBatch = WithSessionId<ContinuousMatrixUpdate>;
 pub type ContinuousColumnUpdateBatch = WithSessionId<Vec<ContinuousColumnUpdate>>;
 pub type ContinuousSlotUpdateBatch = WithSessionId<Vec<QualifiedContinuousSlotUpdate>>;
+pub type OccasionalMappingUpdateBatch = WithSessionId<Vec<QualifiedOccasionalMappingUpdate>>;
+pub type ContinuousMappingUpdateBatch = WithSessionId<Vec<QualifiedContinuousMappingUpdate>>;
@@ -1,9 +1,11 @@
 //! Contains the mainly technical HTTP/WebSocket server code.
 
+use crate::domain::{VirtualControlElement, MAX_ECHO_FEEDBACK_DELAY};
 use crate::infrastructure::server::data::{Topic, Topics};
 use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::sync::mpsc;
 
 // We don't take the async RwLock by Tokio because we need to access this in sync code, too!
@@ -14,9 +16,22 @@ pub struct WebSocketClient {
     pub id: usize,
     pub topics: Topics,
     pub sender: mpsc::UnboundedSender<String>,
+    /// Virtual control elements recently controlled by this client (per session ID).
+    ///
+    /// Used for preventing echo feedback.
+    controlled_elements: Arc<Mutex<HashMap<(String, VirtualControlElement), Instant>>>,
 }
 
 impl WebSocketClient {
+    pub fn new(id: usize, topics: Topics, sender: mpsc::UnboundedSender<String>) -> Self {
+        Self {
+            id,
+            topics,
+            sender,
+            controlled_elements: Default::default(),
+        }
+    }
+
     pub fn send(&self, msg: impl Serialize) -> Result<(), &'static str> {
         let json = serde_json::to_string(&msg).map_err(|_| "couldn't serialize")?;
         self.sender.send(json).map_err(|_| "couldn't send")
@@ -25,4 +40,26 @@ impl WebSocketClient {
     pub fn is_subscribed_to(&self, topic: &Topic) -> bool {
         self.topics.contains(topic)
     }
+
+    pub fn notify_element_controlled(&self, session_id: &str, element: VirtualControlElement) {
+        let mut controlled_elements = match self.controlled_elements.lock() {
+            Ok(e) => e,
+            Err(_) => return,
+        };
+        let now = Instant::now();
+        controlled_elements.retain(|_, time| now.duration_since(*time) <= MAX_ECHO_FEEDBACK_DELAY);
+        controlled_elements.insert((session_id.to_owned(), element), now);
+    }
+
+    /// Returns whether feedback for the given element would be an echo of this client's own
+    /// control.
+    pub fn is_echo(&self, session_id: &str, element: VirtualControlElement) -> bool {
+        let controlled_elements = match self.controlled_elements.lock() {
+            Ok(e) => e,
+            Err(_) => return false,
+        };
+        controlled_elements
+            .get(&(session_id.to_owned(), element))
+            .map_or(false, |time| time.elapsed() <= MAX_ECHO_FEEDBACK_DELAY)
+    }
 }
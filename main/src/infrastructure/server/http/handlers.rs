@@ -1,9 +1,11 @@
 use crate::base::Global;
 use crate::infrastructure::data::ControllerPresetData;
 use crate::infrastructure::server::data::{
-    get_clip_matrix_data, get_controller_preset_data, get_controller_routing_by_session_id,
-    patch_controller, ControllerRouting, DataError, DataErrorCategory, PatchRequest,
-    SessionResponseData, Topics,
+    create_compartment_item, delete_compartment_item, get_clip_matrix_data, get_compartment_item,
+    get_compartment_items, get_controller_preset_data, get_controller_routing_by_session_id,
+    patch_controller, process_websocket_client_message, update_compartment_item, CompartmentItem,
+    ControllerRouting, DataError, DataErrorCategory, PatchRequest, SessionResponseData, Topics,
+    WebSocketClientMessage,
 };
 use crate::infrastructure::server::http::{send_initial_events, ServerClients, WebSocketClient};
 use crate::infrastructure::server::MetricsReporter;
@@ -13,6 +15,8 @@ use axum::extract::Path;
 use axum::http::{Response, StatusCode};
 use axum::response::Html;
 use axum::Json;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -66,6 +70,50 @@ pub async fn patch_controller_handler(
     Ok(StatusCode::OK)
 }
 
+/// Needs to be executed in the main thread!
+pub async fn compartment_items_handler<T: CompartmentItem + Serialize>(
+    Path((session_id, compartment)): Path<(String, String)>,
+) -> Result<Json<Vec<T>>, SimpleResponse> {
+    let items = get_compartment_items(&session_id, &compartment).map_err(translate_data_error)?;
+    Ok(Json(items))
+}
+
+/// Needs to be executed in the main thread!
+pub async fn compartment_item_handler<T: CompartmentItem + Serialize>(
+    Path((session_id, compartment, key)): Path<(String, String, String)>,
+) -> Result<Json<T>, SimpleResponse> {
+    let item =
+        get_compartment_item(&session_id, &compartment, &key).map_err(translate_data_error)?;
+    Ok(Json(item))
+}
+
+/// Needs to be executed in the main thread!
+pub async fn create_compartment_item_handler<T: CompartmentItem + Serialize + DeserializeOwned>(
+    Path((session_id, compartment)): Path<(String, String)>,
+    Json(item): Json<T>,
+) -> Result<(StatusCode, Json<T>), SimpleResponse> {
+    let item =
+        create_compartment_item(&session_id, &compartment, item).map_err(translate_data_error)?;
+    Ok((StatusCode::CREATED, Json(item)))
+}
+
+/// Needs to be executed in the main thread!
+pub async fn update_compartment_item_handler<T: CompartmentItem + DeserializeOwned>(
+    Path((session_id, compartment, key)): Path<(String, String, String)>,
+    Json(item): Json<T>,
+) -> Result<StatusCode, SimpleResponse> {
+    update_compartment_item(&session_id, &compartment, &key, item).map_err(translate_data_error)?;
+    Ok(StatusCode::OK)
+}
+
+/// Needs to be executed in the main thread!
+pub async fn delete_compartment_item_handler<T: CompartmentItem>(
+    Path((session_id, compartment, key)): Path<(String, String, String)>,
+) -> Result<StatusCode, SimpleResponse> {
+    delete_compartment_item::<T>(&session_id, &compartment, &key).map_err(translate_data_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub fn create_cert_response(cert: String, cert_file_name: &str) -> Response<BoxBody> {
     Response::builder()
         .status(StatusCode::OK)
@@ -108,40 +156,67 @@ pub async fn handle_websocket_upgrade(socket: WebSocket, topics: Topics, clients
     // Create client struct
     static NEXT_CLIENT_ID: AtomicUsize = AtomicUsize::new(1);
     let client_id = NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed);
-    let client = WebSocketClient {
-        id: client_id,
-        topics,
-        sender: client_sender,
-    };
+    let client = WebSocketClient::new(client_id, topics, client_sender);
     // Memorize client
     clients.write().unwrap().insert(client_id, client.clone());
     // Send initial events
-    Global::task_support()
-        .do_later_in_main_thread_asap(move || {
-            send_initial_events(&client);
-        })
-        .unwrap();
+    {
+        let client = client.clone();
+        Global::task_support()
+            .do_later_in_main_thread_asap(move || {
+                send_initial_events(&client);
+            })
+            .unwrap();
+    }
     // Keep receiving websocket receiver stream messages
     while let Some(result) = ws_receiver_stream.next().await {
-        // We will need this as soon as we are interested in what the client says
-        let _msg = match result {
+        let msg = match result {
             Ok(msg) => msg,
             Err(e) => {
                 eprintln!("websocket error: {}", e);
                 break;
             }
         };
+        if let Message::Text(json) = msg {
+            handle_websocket_client_message(&client, &json);
+        }
     }
     // Stream closed up, so remove from the client list
     clients.write().unwrap().remove(&client_id);
 }
 
+fn handle_websocket_client_message(client: &WebSocketClient, json: &str) {
+    let msg: WebSocketClientMessage = match serde_json::from_str(json) {
+        Ok(msg) => msg,
+        Err(e) => {
+            eprintln!("invalid websocket client message: {}", e);
+            return;
+        }
+    };
+    // A client flooding us with messages must not be able to crash REAPER, so we rather drop
+    // messages if the main thread can't keep up.
+    let client = client.clone();
+    let result = Global::task_support().do_later_in_main_thread_asap(move || {
+        match process_websocket_client_message(msg) {
+            Ok(e) => client.notify_element_controlled(&e.session_id, e.control_element),
+            Err(e) => eprintln!(
+                "couldn't process websocket client message: {}",
+                e.description()
+            ),
+        }
+    });
+    if let Err(e) = result {
+        eprintln!("dropped websocket client message: {}", e);
+    }
+}
+
 fn translate_data_error(e: DataError) -> SimpleResponse {
     use DataErrorCategory::*;
     let status_code = match e.category() {
         NotFound => StatusCode::NOT_FOUND,
         BadRequest => StatusCode::BAD_REQUEST,
         MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+        Conflict => StatusCode::CONFLICT,
         InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
     };
     (status_code, e.description())
@@ -5,8 +5,8 @@ use crate::domain::ProjectionFeedbackValue;
 use crate::infrastructure::plugin::App;
 use crate::infrastructure::server::data::{
     get_active_controller_updated_event, get_controller_routing_updated_event,
-    get_projection_feedback_event, get_session_updated_event, send_initial_feedback,
-    SessionResponseData, Topic,
+    get_projection_feedback_event, get_session_updated_event, get_virtual_target_control_element,
+    send_initial_feedback, SessionResponseData, Topic,
 };
 use crate::infrastructure::server::http::client::WebSocketClient;
 use rxrust::prelude::*;
@@ -97,14 +97,29 @@ pub fn send_updated_controller_routing(session: &Session) -> Result<(), &'static
 }
 
 pub fn send_projection_feedback_to_subscribed_clients(
-    session_id: &str,
+    session: &Session,
     value: ProjectionFeedbackValue,
 ) -> Result<(), &'static str> {
-    send_to_clients_subscribed_to(
-        &Topic::Feedback {
-            session_id: session_id.to_string(),
+    let session_id = session.id();
+    let topic = Topic::Feedback {
+        session_id: session_id.to_string(),
+    };
+    let control_element =
+        get_virtual_target_control_element(session, value.compartment, &value.mapping_key);
+    for_each_client(
+        |client, event| {
+            if !client.is_subscribed_to(&topic) {
+                return;
+            }
+            // Don't send feedback back to the client which just controlled the element
+            if let Some(e) = control_element {
+                if client.is_echo(session_id, e) {
+                    return;
+                }
+            }
+            let _ = client.send(event);
         },
-        || Some(get_projection_feedback_event(session_id, value)),
+        || get_projection_feedback_event(session_id, value),
     )
 }
 
@@ -7,6 +7,9 @@ use axum::http::Method;
 use axum::routing::{get, patch};
 use axum::Router;
 use axum_server::Handle;
+use realearn_api::persistence::{Group, Mapping, Parameter};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::io;
 use std::net::SocketAddr;
 use std::time::Duration;
@@ -14,7 +17,7 @@ use tokio::sync::broadcast;
 use tower_http::cors::{any, CorsLayer};
 
 use crate::base::Global;
-use crate::infrastructure::server::data::WebSocketRequest;
+use crate::infrastructure::server::data::{CompartmentItem, WebSocketRequest};
 pub use crate::infrastructure::server::http::handlers::*;
 use crate::infrastructure::server::layers::MainThreadLayer;
 use crate::infrastructure::server::MetricsReporter;
@@ -108,6 +111,9 @@ fn create_router(
             "/realearn/metrics",
             get(move || async move { create_metrics_response(metrics_reporter).await }),
         );
+    let router = route_compartment_items::<Mapping>(router, "mappings");
+    let router = route_compartment_items::<Group>(router, "groups");
+    let router = route_compartment_items::<Parameter>(router, "parameters");
     router
         .layer(
             CorsLayer::new()
@@ -131,3 +137,26 @@ fn create_router(
             ),
         )
 }
+
+/// Adds routes for listing, getting, creating, updating and deleting compartment items.
+fn route_compartment_items<T>(router: Router, items_name: &str) -> Router
+where
+    T: CompartmentItem + Serialize + DeserializeOwned + Send + 'static,
+{
+    let items_path = format!(
+        "/realearn/session/:id/compartment/:compartment/{}",
+        items_name
+    );
+    router
+        .route(
+            &items_path,
+            get(compartment_items_handler::<T>.layer(MainThreadLayer))
+                .post(create_compartment_item_handler::<T>.layer(MainThreadLayer)),
+        )
+        .route(
+            &format!("{}/:key", items_path),
+            get(compartment_item_handler::<T>.layer(MainThreadLayer))
+                .put(update_compartment_item_handler::<T>.layer(MainThreadLayer))
+                .delete(delete_compartment_item_handler::<T>.layer(MainThreadLayer)),
+        )
+}
@@ -19,6 +19,7 @@ use url::Url;
 use crate::infrastructure::server::grpc::start_grpc_server;
 use crate::infrastructure::server::http::start_http_server;
 use crate::infrastructure::server::http::ServerClients;
+use crate::infrastructure::server::osc_query::start_osc_query_server;
 use derivative::Derivative;
 use std::thread::JoinHandle;
 use std::time::Duration;
@@ -29,12 +30,14 @@ mod data;
 pub mod grpc;
 pub mod http;
 mod layers;
+mod osc_query;
 
 #[derive(Debug)]
 pub struct RealearnServer {
     http_port: u16,
     https_port: u16,
     grpc_port: u16,
+    osc_query_port: Option<u16>,
     state: ServerState,
     certs_dir_path: PathBuf,
     changed_subject: LocalSubject<'static, (), ()>,
@@ -117,6 +120,7 @@ impl RealearnServer {
         http_port: u16,
         https_port: u16,
         grpc_port: u16,
+        osc_query_port: Option<u16>,
         certs_dir_path: PathBuf,
         metrics_reporter: MetricsReporter,
     ) -> RealearnServer {
@@ -124,6 +128,7 @@ impl RealearnServer {
             http_port,
             https_port,
             grpc_port,
+            osc_query_port,
             state: ServerState::Stopped,
             certs_dir_path,
             changed_subject: Default::default(),
@@ -140,15 +145,20 @@ impl RealearnServer {
         check_port(PortType::Http, self.http_port)?;
         check_port(PortType::Https, self.https_port)?;
         check_port(PortType::Grpc, self.grpc_port)?;
+        if let Some(port) = self.osc_query_port {
+            check_port(PortType::OscQuery, port)?;
+        }
         let clients: ServerClients = Default::default();
         let clients_clone = clients.clone();
         let http_port = self.http_port;
         let https_port = self.https_port;
         let grpc_port = self.grpc_port;
+        let osc_query_port = self.osc_query_port;
         let key_and_cert = self.key_and_cert();
         let (shutdown_sender, http_shutdown_receiver) = broadcast::channel(5);
         let https_shutdown_receiver = shutdown_sender.subscribe();
         let grpc_shutdown_receiver = shutdown_sender.subscribe();
+        let osc_query_shutdown_receiver = shutdown_sender.subscribe();
         let metrics_reporter = self.metrics_reporter.clone();
         let server_thread_join_handle = std::thread::Builder::new()
             .name("ReaLearn server".to_string())
@@ -161,11 +171,13 @@ impl RealearnServer {
                     http_port,
                     https_port,
                     grpc_port,
+                    osc_query_port,
                     clients_clone,
                     key_and_cert,
                     http_shutdown_receiver,
                     https_shutdown_receiver,
                     grpc_shutdown_receiver,
+                    osc_query_shutdown_receiver,
                     metrics_reporter,
                 ));
                 runtime.shutdown_timeout(Duration::from_secs(1));
@@ -285,6 +297,10 @@ impl RealearnServer {
         self.grpc_port
     }
 
+    pub fn osc_query_port(&self) -> Option<u16> {
+        self.osc_query_port
+    }
+
     pub fn log_debug_info(&self, session_id: &str) {
         let msg = format!(
             "\n\
@@ -313,11 +329,13 @@ async fn start_servers(
     http_port: u16,
     https_port: u16,
     grpc_port: u16,
+    osc_query_port: Option<u16>,
     clients: ServerClients,
     (key, cert): (String, String),
     http_shutdown_receiver: broadcast::Receiver<()>,
     https_shutdown_receiver: broadcast::Receiver<()>,
     grpc_shutdown_receiver: broadcast::Receiver<()>,
+    osc_query_shutdown_receiver: broadcast::Receiver<()>,
     metrics_reporter: MetricsReporter,
 ) {
     let http_server_future = start_http_server(
@@ -333,10 +351,21 @@ async fn start_servers(
         SocketAddr::from(([127, 0, 0, 1], grpc_port)),
         grpc_shutdown_receiver,
     );
-    let (http_result, grpc_result) =
-        futures::future::join(http_server_future, grpc_server_future).await;
+    let osc_query_server_future = async move {
+        match osc_query_port {
+            None => Ok(()),
+            Some(port) => start_osc_query_server(port, osc_query_shutdown_receiver).await,
+        }
+    };
+    let (http_result, grpc_result, osc_query_result) = futures::future::join3(
+        http_server_future,
+        grpc_server_future,
+        osc_query_server_future,
+    )
+    .await;
     http_result.expect("HTTP server error");
     grpc_result.expect("gRPC server error");
+    osc_query_result.expect("OSCQuery server error");
 }
 
 fn get_key_and_cert(ip: IpAddr, cert_dir_path: &Path) -> (String, String) {
@@ -413,6 +442,8 @@ enum PortType {
     Https,
     #[display(fmt = "gRPC")]
     Grpc,
+    #[display(fmt = "OSCQuery")]
+    OscQuery,
 }
 
 impl PortType {
@@ -421,6 +452,7 @@ impl PortType {
             PortType::Http => "http",
             PortType::Https => "https",
             PortType::Grpc => "grpc",
+            PortType::OscQuery => "osc_query",
         }
     }
 
@@ -429,6 +461,7 @@ impl PortType {
             PortType::Http => 40080,
             PortType::Https => 40443,
             PortType::Grpc => 40051,
+            PortType::OscQuery => 40082,
         }
     }
 }
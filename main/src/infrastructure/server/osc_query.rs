@@ -0,0 +1,378 @@
+//! Contains an [OSCQuery](https://github.com/Vidvox/OSCQueryProposal) service which publishes the
+//! OSC addresses that ReaLearn listens to, so that OSC apps can discover and bind to them.
+
+use crate::application::SourceCategory;
+use crate::domain::{
+    compartment_param_index_iter, format_osc_parameter_address, Compartment, ControlInput,
+};
+use crate::infrastructure::plugin::App;
+use crate::infrastructure::server::layers::MainThreadLayer;
+use axum::extract::RawQuery;
+use axum::handler::Handler;
+use axum::http::{StatusCode, Uri};
+use axum::routing::get;
+use axum::{Json, Router};
+use axum_server::Handle;
+use helgoboss_learn::OscTypeTag;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+pub async fn start_osc_query_server(
+    port: u16,
+    mut shutdown_receiver: broadcast::Receiver<()>,
+) -> Result<(), io::Error> {
+    let router = Router::new()
+        .route("/", get(osc_query_handler.layer(MainThreadLayer)))
+        .route("/*path", get(osc_query_handler.layer(MainThreadLayer)));
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let handle = Handle::new();
+    let cloned_handle = handle.clone();
+    tokio::spawn(async move {
+        shutdown_receiver.recv().await.unwrap();
+        cloned_handle.graceful_shutdown(Some(Duration::ZERO));
+    });
+    axum_server::bind(addr)
+        .handle(handle)
+        .serve(router.into_make_service())
+        .await
+}
+
+/// Needs to be executed in the main thread!
+async fn osc_query_handler(
+    uri: Uri,
+    RawQuery(query): RawQuery,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if query.as_deref() == Some("HOST_INFO") {
+        let host_info = serde_json::to_value(get_host_info()).unwrap();
+        return Ok(Json(host_info));
+    }
+    let namespace = build_namespace(collect_methods());
+    let node = namespace.find(uri.path()).ok_or(StatusCode::NOT_FOUND)?;
+    let json = serde_json::to_value(node).unwrap();
+    match query {
+        None => Ok(Json(json)),
+        Some(attribute) => {
+            // The spec demands "204 No Content" for attributes which the node doesn't have
+            let value = json.get(&attribute).ok_or(StatusCode::NO_CONTENT)?;
+            Ok(Json(serde_json::json!({ attribute: value })))
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct HostInfo {
+    name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    osc_port: Option<u16>,
+    osc_transport: &'static str,
+    extensions: BTreeMap<&'static str, bool>,
+}
+
+fn get_host_info() -> HostInfo {
+    // OSCQuery assumes that there's exactly one OSC port. We take the one of the first OSC
+    // device that is used as control input.
+    let osc_port = first_osc_control_input_device_port();
+    HostInfo {
+        name: "ReaLearn",
+        osc_port,
+        osc_transport: "UDP",
+        extensions: ["ACCESS", "DESCRIPTION", "RANGE", "TYPE", "VALUE"]
+            .into_iter()
+            .map(|ext| (ext, true))
+            .collect(),
+    }
+}
+
+fn first_osc_control_input_device_port() -> Option<u16> {
+    let dev_id = App::get().with_weak_sessions(|sessions| {
+        sessions
+            .iter()
+            .filter_map(|s| s.upgrade())
+            .find_map(|s| match s.borrow().control_input() {
+                ControlInput::Osc(dev_id) => Some(dev_id),
+                _ => None,
+            })
+    })?;
+    let dev_manager = App::get().osc_device_manager();
+    let dev_manager = dev_manager.borrow();
+    dev_manager.find_device_by_id(&dev_id)?.local_port()
+}
+
+/// Something which can be addressed via OSC.
+struct OscMethod {
+    address: String,
+    description: String,
+    /// Bit 1 means ReaLearn sends values (feedback), bit 2 means ReaLearn receives values (control).
+    access: u8,
+    /// Arguments at the indexes which are known. Others are assumed to be floats.
+    arguments: BTreeMap<u32, OscMethodArgument>,
+}
+
+#[derive(Copy, Clone)]
+struct OscMethodArgument {
+    type_tag: char,
+    range: Option<(f64, f64)>,
+    value: Option<f64>,
+}
+
+/// Collects the OSC methods of all sessions which take their control input from an OSC device.
+fn collect_methods() -> Vec<OscMethod> {
+    let mut methods = vec![];
+    App::get().with_weak_sessions(|sessions| {
+        for session in sessions.iter().filter_map(|s| s.upgrade()) {
+            let session = session.borrow();
+            if !matches!(session.control_input(), ControlInput::Osc(_)) {
+                continue;
+            }
+            for compartment in [Compartment::Controller, Compartment::Main] {
+                for mapping in session.mappings(compartment) {
+                    let mapping = mapping.borrow();
+                    let source = &mapping.source_model;
+                    if source.category() != SourceCategory::Osc {
+                        continue;
+                    }
+                    let access = (mapping.feedback_is_enabled() as u8)
+                        | ((mapping.control_is_enabled() as u8) << 1);
+                    if access == 0 {
+                        continue;
+                    }
+                    // Additional arguments share type and range with the primary one
+                    let arguments = source
+                        .osc_arg_index()
+                        .into_iter()
+                        .chain(source.osc_additional_arg_indexes().iter().copied())
+                        .map(|i| {
+                            let range = source.osc_arg_value_range();
+                            let arg = OscMethodArgument {
+                                type_tag: convert_type_tag(source.osc_arg_type_tag()),
+                                range: Some((range.min_val(), range.max_val())),
+                                value: None,
+                            };
+                            (i, arg)
+                        })
+                        .collect();
+                    let method = OscMethod {
+                        address: source.osc_address_pattern().to_string(),
+                        description: mapping.effective_name(),
+                        access,
+                        arguments,
+                    };
+                    methods.push(method);
+                }
+                // Parameters can be set via OSC and their current values can be queried
+                let params = session.params().compartment_params(compartment);
+                for i in compartment_param_index_iter() {
+                    let param = params.at(i);
+                    let (type_tag, max) = match param.setting().value_count {
+                        None => ('f', 1.0),
+                        Some(count) => ('i', (count.get() - 1) as f64),
+                    };
+                    let arg = OscMethodArgument {
+                        type_tag,
+                        range: Some((0.0, max)),
+                        value: Some(param.effective_value().into()),
+                    };
+                    let method = OscMethod {
+                        address: format_osc_parameter_address(
+                            session.instance_id(),
+                            compartment,
+                            i,
+                        ),
+                        description: params.get_parameter_name(i).to_string(),
+                        access: 3,
+                        arguments: [(0, arg)].into_iter().collect(),
+                    };
+                    methods.push(method);
+                }
+            }
+        }
+    });
+    methods
+}
+
+fn convert_type_tag(tag: OscTypeTag) -> char {
+    use OscTypeTag::*;
+    match tag {
+        Float => 'f',
+        Double => 'd',
+        Bool => 'T',
+        Nil => 'N',
+        Inf => 'I',
+        Int => 'i',
+        String => 's',
+        Blob => 'b',
+        Time => 't',
+        Long => 'h',
+        Char => 'c',
+        Color => 'r',
+        Midi => 'm',
+        Array => '[',
+    }
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct Node {
+    full_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(rename = "TYPE", skip_serializing_if = "Option::is_none")]
+    type_tags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    access: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    range: Option<Vec<Option<Range>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<Vec<Option<f64>>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    contents: BTreeMap<String, Node>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct Range {
+    min: f64,
+    max: f64,
+}
+
+impl Node {
+    fn find(&self, path: &str) -> Option<&Node> {
+        path.split('/')
+            .filter(|s| !s.is_empty())
+            .try_fold(self, |node, segment| node.contents.get(segment))
+    }
+}
+
+/// Builds the namespace tree. Methods with the same address are merged.
+fn build_namespace(methods: impl IntoIterator<Item = OscMethod>) -> Node {
+    let mut root = Node {
+        full_path: "/".to_string(),
+        ..Default::default()
+    };
+    let mut merged_methods: BTreeMap<String, OscMethod> = BTreeMap::new();
+    for m in methods {
+        if !is_plain_address(&m.address) {
+            continue;
+        }
+        match merged_methods.get_mut(&m.address) {
+            None => {
+                merged_methods.insert(m.address.clone(), m);
+            }
+            Some(existing) => {
+                existing.access |= m.access;
+                existing.description = format!("{}, {}", existing.description, m.description);
+                for (i, arg) in m.arguments {
+                    existing.arguments.entry(i).or_insert(arg);
+                }
+            }
+        }
+    }
+    for m in merged_methods.into_values() {
+        let mut node = &mut root;
+        let mut full_path = String::new();
+        for segment in m.address.split('/').filter(|s| !s.is_empty()) {
+            full_path.push('/');
+            full_path.push_str(segment);
+            node = node
+                .contents
+                .entry(segment.to_string())
+                .or_insert_with(|| Node {
+                    full_path: full_path.clone(),
+                    ..Default::default()
+                });
+        }
+        let arg_count = m.arguments.keys().last().map(|i| i + 1).unwrap_or(0);
+        let args: Vec<_> = (0..arg_count).map(|i| m.arguments.get(&i)).collect();
+        node.description = Some(m.description);
+        node.access = Some(m.access);
+        if !args.is_empty() {
+            node.type_tags = Some(
+                args.iter()
+                    .map(|a| a.map(|a| a.type_tag).unwrap_or('f'))
+                    .collect(),
+            );
+            node.range = Some(
+                args.iter()
+                    .map(|a| {
+                        let (min, max) = (*a)?.range?;
+                        Some(Range { min, max })
+                    })
+                    .collect(),
+            );
+            if args
+                .iter()
+                .any(|a| matches!(a, Some(OscMethodArgument { value: Some(_), .. })))
+            {
+                node.value = Some(args.iter().map(|a| (*a)?.value).collect());
+            }
+        }
+    }
+    root
+}
+
+/// Returns `false` if the address is a pattern, which can't be published.
+fn is_plain_address(address: &str) -> bool {
+    address.starts_with('/') && !address.contains(['*', '?', '[', ']', '{', '}', '#', ' '])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespace() {
+        // Given
+        let arg = |type_tag, range| OscMethodArgument {
+            type_tag,
+            range,
+            value: None,
+        };
+        let methods = vec![
+            OscMethod {
+                address: "/mixer/fader1".to_string(),
+                description: "Fader 1".to_string(),
+                access: 2,
+                arguments: [(0, arg('i', Some((0.0, 127.0))))].into_iter().collect(),
+            },
+            OscMethod {
+                address: "/xy".to_string(),
+                description: "X".to_string(),
+                access: 2,
+                arguments: [(0, arg('f', None))].into_iter().collect(),
+            },
+            OscMethod {
+                address: "/xy".to_string(),
+                description: "Y".to_string(),
+                access: 1,
+                arguments: [(2, arg('d', None))].into_iter().collect(),
+            },
+            OscMethod {
+                address: "/track/*/volume".to_string(),
+                description: "Pattern".to_string(),
+                access: 2,
+                arguments: Default::default(),
+            },
+        ];
+        // When
+        let namespace = build_namespace(methods);
+        // Then
+        assert_eq!(namespace.contents.len(), 2);
+        let fader = namespace.find("/mixer/fader1").unwrap();
+        assert_eq!(fader.full_path, "/mixer/fader1");
+        assert_eq!(fader.type_tags.as_deref(), Some("i"));
+        let xy = namespace.find("/xy").unwrap();
+        assert_eq!(xy.access, Some(3));
+        assert_eq!(xy.description.as_deref(), Some("X, Y"));
+        assert_eq!(xy.type_tags.as_deref(), Some("ffd"));
+        let json = serde_json::to_value(namespace.find("/mixer").unwrap()).unwrap();
+        assert_eq!(
+            json["CONTENTS"]["fader1"]["RANGE"],
+            serde_json::json!([{"MIN": 0.0, "MAX": 127.0}])
+        );
+    }
+}
@@ -0,0 +1,1027 @@
+//! A fake REAPER for running the real main and real-time processors in ordinary `cargo test`
+//! cases.
+//!
+//! REAPER hands its API to VST plug-ins via the host callback: the plug-in asks for each function
+//! by name and gets a function pointer back. We answer these requests with our own implementations
+//! which work on a simulated project (tracks with volume, mute and arm state, FX chains with
+//! parameters, transport). Everything else in the stack - reaper-low, reaper-medium, reaper-high
+//! and ReaLearn itself - is the real thing.
+//!
+//! Only the functions which are necessary for the current headless tests are emulated. If a test
+//! runs into a REAPER function which is not emulated, reaper-low panics with the name of the
+//! missing function. Extend [`lookup_function`] in that case.
+//!
+//! REAPER's API must always be called from the thread in which it has been set up. That's why all
+//! code touching the fake REAPER must be executed via [`run_in_main_thread`].
+use crate::base::SenderToNormalThread;
+use crate::domain::{
+    AdditionalFeedbackEvent, BackboneState, RealearnTargetState, WAITING_FOR_SESSION_PARAM_NAME,
+};
+use once_cell::sync::Lazy;
+use reaper_high::{CrashInfo, Reaper};
+use reaper_low::{static_vst_plugin_context, PluginContext};
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::AssertUnwindSafe;
+use std::ptr::null_mut;
+use vst::api::AEffect;
+use vst::plugin::HostCallback;
+
+/// Number of parameters of the fake ReaLearn FX, not counting "Bypass" and "Wet".
+const REALEARN_PARAM_COUNT: usize = crate::domain::PLUGIN_PARAMETER_COUNT as usize;
+
+/// Initial volume of each track (0 dB).
+const INITIAL_TRACK_VOLUME: f64 = 1.0;
+
+/// Initial normalized value of each parameter of a non-ReaLearn FX.
+const INITIAL_FX_PARAM_VALUE: f64 = 0.5;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static MAIN_THREAD: Lazy<crossbeam_channel::Sender<Job>> = Lazy::new(|| {
+    let (sender, receiver) = crossbeam_channel::unbounded::<Job>();
+    std::thread::Builder::new()
+        .name(String::from("fake REAPER main thread"))
+        .spawn(move || {
+            install();
+            for job in receiver {
+                job();
+            }
+        })
+        .expect("couldn't spawn fake REAPER main thread");
+    sender
+});
+
+thread_local! {
+    static STATE: RefCell<FakeReaperState> = RefCell::new(FakeReaperState::new());
+}
+
+/// Executes the given function in the main thread of the fake REAPER, starting with a fresh
+/// project which contains one track with a ReaLearn FX on it.
+///
+/// Calls are serialized, so tests can't interfere with each other. Panics are propagated to the
+/// caller.
+pub fn run_in_main_thread<R, F>(f: F) -> R
+where
+    R: Send + 'static,
+    F: FnOnce() -> R + Send + 'static,
+{
+    let (result_sender, result_receiver) = crossbeam_channel::bounded(1);
+    MAIN_THREAD
+        .send(Box::new(move || {
+            STATE.with(|s| s.replace(FakeReaperState::new()));
+            let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+            let _ = result_sender.send(result);
+        }))
+        .expect("fake REAPER main thread gone");
+    match result_receiver
+        .recv()
+        .expect("fake REAPER main thread died")
+    {
+        Ok(r) => r,
+        Err(e) => std::panic::resume_unwind(e),
+    }
+}
+
+/// Returns a host callback which behaves like the one REAPER passes to the ReaLearn FX on the
+/// first track.
+pub fn realearn_host() -> HostCallback {
+    // The effect pointer is only passed around as opaque handle, it's never dereferenced.
+    static EFFECT: Lazy<usize> = Lazy::new(|| Box::into_raw(Box::new(0u64)) as usize);
+    HostCallback::wrap(host_callback, *EFFECT as *mut AEffect)
+}
+
+/// Adds a track with the given FX (name and parameter count) to the end of the track list and
+/// returns its index.
+pub fn add_track(name: &str, fxs: &[(&str, usize)]) -> usize {
+    with_state(|s| {
+        let mut track = FakeTrack::new(name, s.next_guid());
+        for (fx_name, param_count) in fxs {
+            let fx = FakeFx::new(
+                fx_name,
+                s.next_guid(),
+                vec![INITIAL_FX_PARAM_VALUE; *param_count],
+            );
+            track.fxs.push(Box::new(fx));
+        }
+        s.tracks.push(Box::new(track));
+        s.tracks.len() - 1
+    })
+}
+
+/// A change of the fake REAPER's state, which REAPER would report to control surfaces.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum FakeChange {
+    TrackVolume {
+        track_index: usize,
+        new_value: f64,
+    },
+    TrackMute {
+        track_index: usize,
+        new_value: bool,
+    },
+    TrackArm {
+        track_index: usize,
+        new_value: bool,
+    },
+    FxParameterValue {
+        track_index: usize,
+        fx_index: usize,
+        param_index: usize,
+        new_value: f64,
+    },
+    PlayState {
+        is_playing: bool,
+        is_paused: bool,
+    },
+}
+
+/// Returns all state changes since the last call.
+pub fn take_changes() -> Vec<FakeChange> {
+    with_state(|s| std::mem::take(&mut s.changes))
+}
+
+/// Returns all additional feedback events (emitted by ReaLearn targets) since the last call.
+pub fn take_additional_feedback_events() -> Vec<AdditionalFeedbackEvent> {
+    ADDITIONAL_FEEDBACK_EVENT_RECEIVER.with(|r| match r.borrow().as_ref() {
+        None => vec![],
+        Some(r) => r.try_iter().collect(),
+    })
+}
+
+thread_local! {
+    static ADDITIONAL_FEEDBACK_EVENT_RECEIVER: RefCell<
+        Option<crossbeam_channel::Receiver<AdditionalFeedbackEvent>>,
+    > = RefCell::new(None);
+}
+
+fn install() {
+    let host = realearn_host();
+    let context = PluginContext::from_vst_plugin(&host, static_vst_plugin_context())
+        .expect("couldn't create plug-in context from fake host");
+    let logger = slog::Logger::root(slog::Discard, slog::o!());
+    Reaper::setup_with_defaults(
+        context,
+        logger,
+        CrashInfo {
+            plugin_name: "ReaLearn (headless)".to_string(),
+            plugin_version: env!("CARGO_PKG_VERSION").to_string(),
+            support_email_address: "info@helgoboss.org".to_string(),
+        },
+    );
+    let (sender, receiver) =
+        SenderToNormalThread::new_unbounded_channel("additional feedback events");
+    ADDITIONAL_FEEDBACK_EVENT_RECEIVER.with(|r| r.replace(Some(receiver)));
+    BackboneState::make_available_globally(BackboneState::new(RealearnTargetState::new(sender)));
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct FakeGuid {
+    data_1: u32,
+    data_2: u16,
+    data_3: u16,
+    data_4: [u8; 8],
+}
+
+impl FakeGuid {
+    fn new(n: u32) -> Self {
+        Self {
+            data_1: n,
+            data_2: 0,
+            data_3: 0,
+            data_4: [0; 8],
+        }
+    }
+
+    fn format(&self) -> String {
+        let d = &self.data_4;
+        format!(
+            "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+            self.data_1, self.data_2, self.data_3, d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]
+        )
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let hex: String = text.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+        if hex.len() != 32 {
+            return None;
+        }
+        let byte = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok();
+        let mut data_4 = [0; 8];
+        for (i, b) in data_4.iter_mut().enumerate() {
+            *b = byte(8 + i)?;
+        }
+        Some(Self {
+            data_1: u32::from_str_radix(&hex[0..8], 16).ok()?,
+            data_2: u16::from_str_radix(&hex[8..12], 16).ok()?,
+            data_3: u16::from_str_radix(&hex[12..16], 16).ok()?,
+            data_4,
+        })
+    }
+}
+
+struct FakeReaperState {
+    /// Only its address matters (used as `ReaProject*`).
+    project: Box<u8>,
+    master_track: Box<FakeTrack>,
+    tracks: Vec<Box<FakeTrack>>,
+    is_playing: bool,
+    is_paused: bool,
+    changes: Vec<FakeChange>,
+    guid_counter: u32,
+}
+
+impl FakeReaperState {
+    fn new() -> Self {
+        let mut state = Self {
+            project: Box::new(0),
+            master_track: Box::new(FakeTrack::new("MASTER", FakeGuid::new(0))),
+            tracks: vec![],
+            is_playing: false,
+            is_paused: false,
+            changes: vec![],
+            guid_counter: 0,
+        };
+        let mut realearn_track = FakeTrack::new("ReaLearn", state.next_guid());
+        let mut realearn_fx = FakeFx::new(
+            "VSTi: ReaLearn (Helgoboss)",
+            state.next_guid(),
+            vec![0.0; REALEARN_PARAM_COUNT + 2],
+        );
+        realearn_fx.is_realearn = true;
+        realearn_track.fxs.push(Box::new(realearn_fx));
+        state.tracks.push(Box::new(realearn_track));
+        state
+    }
+
+    fn next_guid(&mut self) -> FakeGuid {
+        self.guid_counter += 1;
+        FakeGuid::new(self.guid_counter)
+    }
+
+    fn project_ptr(&self) -> *mut c_void {
+        &*self.project as *const u8 as *mut c_void
+    }
+
+    /// Returns the track index (`None` for the master track) and the track.
+    fn track_mut(&mut self, ptr: *mut c_void) -> Option<(Option<usize>, &mut FakeTrack)> {
+        if ptr == track_ptr(&self.master_track) {
+            return Some((None, &mut self.master_track));
+        }
+        self.tracks
+            .iter_mut()
+            .enumerate()
+            .find(|(_, t)| track_ptr(t) == ptr)
+            .map(|(i, t)| (Some(i), &mut **t))
+    }
+}
+
+fn track_ptr(track: &FakeTrack) -> *mut c_void {
+    track as *const FakeTrack as *mut c_void
+}
+
+struct FakeTrack {
+    guid: FakeGuid,
+    name: std::ffi::CString,
+    volume: f64,
+    pan: f64,
+    mute: bool,
+    arm: bool,
+    selected: bool,
+    fxs: Vec<Box<FakeFx>>,
+}
+
+impl FakeTrack {
+    fn new(name: &str, guid: FakeGuid) -> Self {
+        Self {
+            guid,
+            name: std::ffi::CString::new(name).unwrap(),
+            volume: INITIAL_TRACK_VOLUME,
+            pan: 0.0,
+            mute: false,
+            arm: false,
+            selected: false,
+            fxs: vec![],
+        }
+    }
+}
+
+struct FakeFx {
+    guid: FakeGuid,
+    name: String,
+    enabled: bool,
+    /// The last two parameters of a ReaLearn FX are "Bypass" and "Wet".
+    params: Vec<f64>,
+    is_realearn: bool,
+}
+
+impl FakeFx {
+    fn new(name: &str, guid: FakeGuid, params: Vec<f64>) -> Self {
+        Self {
+            guid,
+            name: name.to_string(),
+            enabled: true,
+            params,
+            is_realearn: false,
+        }
+    }
+}
+
+fn with_state<R>(f: impl FnOnce(&mut FakeReaperState) -> R) -> R {
+    STATE.with(|s| f(&mut s.borrow_mut()))
+}
+
+/// Executes the given function with the track behind the given pointer (if it's valid).
+fn with_track<R>(
+    ptr: *mut c_void,
+    f: impl FnOnce(Option<usize>, &mut FakeTrack, &mut Vec<FakeChange>) -> R,
+) -> Option<R> {
+    with_state(|s| {
+        let mut changes = std::mem::take(&mut s.changes);
+        let result = s.track_mut(ptr).map(|(i, t)| f(i, t, &mut changes));
+        s.changes = changes;
+        result
+    })
+}
+
+/// Executes the given function with the FX at the given index (as REAPER passes it) on the track
+/// behind the given pointer. Input FX are not simulated.
+fn with_fx<R>(
+    track: *mut c_void,
+    fx_index: c_int,
+    f: impl FnOnce(Option<usize>, usize, &mut FakeFx, &mut Vec<FakeChange>) -> R,
+) -> Option<R> {
+    if fx_index < 0 {
+        return None;
+    }
+    let fx_index = fx_index as usize;
+    with_track(track, |track_index, t, changes| {
+        let fx = t.fxs.get_mut(fx_index)?;
+        Some(f(track_index, fx_index, fx, changes))
+    })
+    .flatten()
+}
+
+unsafe fn c_str<'a>(ptr: *const c_char) -> &'a str {
+    if ptr.is_null() {
+        return "";
+    }
+    CStr::from_ptr(ptr).to_str().unwrap_or_default()
+}
+
+unsafe fn write_c_str(buf: *mut c_char, buf_size: c_int, value: &str) -> bool {
+    if buf.is_null() || buf_size <= 0 {
+        return false;
+    }
+    let bytes = value.as_bytes();
+    let len = bytes.len().min(buf_size as usize - 1);
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, len);
+    *buf.add(len) = 0;
+    true
+}
+
+const REAPER_VST_EXTENSION_OPCODE: i32 = 0xdeadbeef_u32 as i32;
+const GET_FUNC_INDEX: i32 = 0xdeadf00d_u32 as i32;
+const GET_CONTEXT_INDEX: i32 = 0xdeadf00e_u32 as i32;
+const CONTEXT_TRACK: isize = 1;
+const CONTEXT_PROJECT: isize = 3;
+
+extern "C" fn host_callback(
+    _effect: *mut AEffect,
+    opcode: i32,
+    index: i32,
+    value: isize,
+    ptr: *mut c_void,
+    _opt: f32,
+) -> isize {
+    if opcode != REAPER_VST_EXTENSION_OPCODE {
+        return 0;
+    }
+    match index {
+        GET_FUNC_INDEX => {
+            let name = unsafe { c_str(ptr as *const c_char) };
+            lookup_function(name) as isize
+        }
+        GET_CONTEXT_INDEX => with_state(|s| match value {
+            CONTEXT_TRACK => track_ptr(&s.tracks[0]) as isize,
+            CONTEXT_PROJECT => s.project_ptr() as isize,
+            _ => 0,
+        }),
+        _ => 0,
+    }
+}
+
+extern "C" fn get_func(name: *const c_char) -> *mut c_void {
+    lookup_function(unsafe { c_str(name) })
+}
+
+fn lookup_function(name: &str) -> *mut c_void {
+    let address = match name {
+        "GetFunc" => get_func as usize,
+        // General
+        "GetAppVersion" => get_app_version as usize,
+        "get_config_var" => get_config_var as usize,
+        "plugin_register" => plugin_register as usize,
+        "ShowConsoleMsg" => show_console_msg as usize,
+        "IsInRealTimeAudio" => is_in_real_time_audio as usize,
+        "Audio_IsRunning" => audio_is_running as usize,
+        "DB2SLIDER" => db2slider as usize,
+        "SLIDER2DB" => slider2db as usize,
+        "guidToString" => guid_to_string as usize,
+        "stringToGuid" => string_to_guid as usize,
+        "ValidatePtr" => validate_ptr as usize,
+        "ValidatePtr2" => validate_ptr_2 as usize,
+        // Project and transport
+        "EnumProjects" => enum_projects as usize,
+        "GetPlayState" => get_play_state as usize,
+        "GetPlayStateEx" => get_play_state_ex as usize,
+        "OnPlayButtonEx" => on_play_button_ex as usize,
+        "OnStopButtonEx" => on_stop_button_ex as usize,
+        "OnPauseButtonEx" => on_pause_button_ex as usize,
+        "Master_GetTempo" => master_get_tempo as usize,
+        // Tracks
+        "CountTracks" => count_tracks as usize,
+        "GetTrack" => get_track as usize,
+        "GetMasterTrack" => get_master_track as usize,
+        "CSurf_TrackToID" => csurf_track_to_id as usize,
+        "CSurf_TrackFromID" => csurf_track_from_id as usize,
+        "GetTrackGUID" => get_track_guid as usize,
+        "GetSetMediaTrackInfo" => get_set_media_track_info as usize,
+        "GetMediaTrackInfo_Value" => get_media_track_info_value as usize,
+        "SetMediaTrackInfo_Value" => set_media_track_info_value as usize,
+        "GetTrackUIVolPan" => get_track_ui_vol_pan as usize,
+        "GetTrackUIMute" => get_track_ui_mute as usize,
+        "SetTrackUIVolume" => set_track_ui_volume as usize,
+        "SetTrackUIMute" => set_track_ui_mute as usize,
+        "SetTrackUIRecArm" => set_track_ui_rec_arm as usize,
+        "CSurf_OnVolumeChangeEx" => csurf_on_volume_change_ex as usize,
+        "CSurf_OnMuteChangeEx" => csurf_on_mute_change_ex as usize,
+        "CSurf_OnRecArmChangeEx" => csurf_on_rec_arm_change_ex as usize,
+        "CSurf_SetSurfaceVolume" => csurf_set_surface_volume as usize,
+        "CSurf_SetSurfaceMute" => csurf_set_surface_mute as usize,
+        "CSurf_SetSurfaceRecArm" => csurf_set_surface_rec_arm as usize,
+        // FX
+        "TrackFX_GetCount" => track_fx_get_count as usize,
+        "TrackFX_GetRecCount" => track_fx_get_rec_count as usize,
+        "TrackFX_GetFXGUID" => track_fx_get_fx_guid as usize,
+        "TrackFX_GetFXName" => track_fx_get_fx_name as usize,
+        "TrackFX_GetEnabled" => track_fx_get_enabled as usize,
+        "TrackFX_SetEnabled" => track_fx_set_enabled as usize,
+        "TrackFX_GetNamedConfigParm" => track_fx_get_named_config_parm as usize,
+        "TrackFX_GetNumParams" => track_fx_get_num_params as usize,
+        "TrackFX_GetParamFromIdent" => track_fx_get_param_from_ident as usize,
+        "TrackFX_GetParamName" => track_fx_get_param_name as usize,
+        "TrackFX_GetParam" => track_fx_get_param as usize,
+        "TrackFX_GetParamNormalized" => track_fx_get_param_normalized as usize,
+        "TrackFX_SetParamNormalized" => track_fx_set_param_normalized as usize,
+        "TrackFX_FormatParamValueNormalized" => track_fx_format_param_value_normalized as usize,
+        "TrackFX_GetParameterStepSizes" => track_fx_get_parameter_step_sizes as usize,
+        _ => return null_mut(),
+    };
+    address as *mut c_void
+}
+
+// General
+
+extern "C" fn get_app_version() -> *const c_char {
+    b"6.71/linux-x86_64\0".as_ptr() as *const c_char
+}
+
+extern "C" fn get_config_var(_name: *const c_char, _size_out: *mut c_int) -> *mut c_void {
+    // Not simulated, callers fall back to defaults.
+    null_mut()
+}
+
+extern "C" fn plugin_register(_name: *const c_char, _info: *mut c_void) -> c_int {
+    1
+}
+
+extern "C" fn show_console_msg(msg: *const c_char) {
+    print!("{}", unsafe { c_str(msg) });
+}
+
+extern "C" fn is_in_real_time_audio() -> c_int {
+    0
+}
+
+extern "C" fn audio_is_running() -> c_int {
+    // The harness simulates audio blocks, so tasks sent to the real-time processor are processed.
+    1
+}
+
+/// Approximates REAPER's fader curve: -inf at 0, 0 dB at 716.21 and +12 dB at 1000.
+extern "C" fn db2slider(db: f64) -> f64 {
+    if db <= -150.0 {
+        return 0.0;
+    }
+    (716.21 + 283.79 * (db / 12.0).cbrt()).clamp(0.0, 1000.0)
+}
+
+/// Inverse of [`db2slider`]. The bottom of the fader is -1000 dB (-inf).
+extern "C" fn slider2db(slider: f64) -> f64 {
+    if slider <= 0.0 {
+        return -1000.0;
+    }
+    12.0 * ((slider - 716.21) / 283.79).powi(3)
+}
+
+extern "C" fn guid_to_string(guid: *const FakeGuid, dest: *mut c_char) {
+    if guid.is_null() {
+        return;
+    }
+    let text = unsafe { &*guid }.format();
+    unsafe {
+        write_c_str(dest, 64, &text);
+    }
+}
+
+extern "C" fn string_to_guid(text: *const c_char, guid: *mut FakeGuid) {
+    if guid.is_null() {
+        return;
+    }
+    if let Some(g) = FakeGuid::parse(unsafe { c_str(text) }) {
+        unsafe {
+            *guid = g;
+        }
+    }
+}
+
+extern "C" fn validate_ptr(pointer: *mut c_void, type_name: *const c_char) -> bool {
+    validate_ptr_2(null_mut(), pointer, type_name)
+}
+
+extern "C" fn validate_ptr_2(
+    _project: *mut c_void,
+    pointer: *mut c_void,
+    type_name: *const c_char,
+) -> bool {
+    match unsafe { c_str(type_name) } {
+        "MediaTrack*" => with_track(pointer, |_, _, _| ()).is_some(),
+        "ReaProject*" => with_state(|s| s.project_ptr() == pointer),
+        _ => false,
+    }
+}
+
+// Project and transport
+
+extern "C" fn enum_projects(
+    index: c_int,
+    file_name_out: *mut c_char,
+    file_name_out_size: c_int,
+) -> *mut c_void {
+    // There's just one project. It's both the current project (-1) and the first one (0).
+    if index > 0 {
+        return null_mut();
+    }
+    unsafe {
+        write_c_str(file_name_out, file_name_out_size, "");
+    }
+    with_state(|s| s.project_ptr())
+}
+
+extern "C" fn get_play_state() -> c_int {
+    with_state(|s| (s.is_playing as c_int) | ((s.is_paused as c_int) << 1))
+}
+
+extern "C" fn get_play_state_ex(_project: *mut c_void) -> c_int {
+    get_play_state()
+}
+
+fn change_play_state(is_playing: bool, is_paused: bool) {
+    with_state(|s| {
+        if (s.is_playing, s.is_paused) == (is_playing, is_paused) {
+            return;
+        }
+        s.is_playing = is_playing;
+        s.is_paused = is_paused;
+        s.changes.push(FakeChange::PlayState {
+            is_playing,
+            is_paused,
+        });
+    })
+}
+
+extern "C" fn on_play_button_ex(_project: *mut c_void) {
+    change_play_state(true, false);
+}
+
+extern "C" fn on_stop_button_ex(_project: *mut c_void) {
+    change_play_state(false, false);
+}
+
+extern "C" fn on_pause_button_ex(_project: *mut c_void) {
+    let is_playing = with_state(|s| s.is_playing);
+    change_play_state(is_playing, is_playing);
+}
+
+extern "C" fn master_get_tempo() -> f64 {
+    120.0
+}
+
+// Tracks
+
+extern "C" fn count_tracks(_project: *mut c_void) -> c_int {
+    with_state(|s| s.tracks.len() as c_int)
+}
+
+extern "C" fn get_track(_project: *mut c_void, index: c_int) -> *mut c_void {
+    with_state(|s| {
+        if index < 0 {
+            return null_mut();
+        }
+        s.tracks
+            .get(index as usize)
+            .map(|t| track_ptr(t))
+            .unwrap_or(null_mut())
+    })
+}
+
+extern "C" fn get_master_track(_project: *mut c_void) -> *mut c_void {
+    with_state(|s| track_ptr(&s.master_track))
+}
+
+extern "C" fn csurf_track_to_id(track: *mut c_void, _mcp_view: bool) -> c_int {
+    with_track(track, |i, _, _| i.map(|i| i as c_int + 1).unwrap_or(0)).unwrap_or(-1)
+}
+
+extern "C" fn csurf_track_from_id(id: c_int, _mcp_view: bool) -> *mut c_void {
+    if id == 0 {
+        get_master_track(null_mut())
+    } else {
+        get_track(null_mut(), id - 1)
+    }
+}
+
+extern "C" fn get_track_guid(track: *mut c_void) -> *mut FakeGuid {
+    with_track(track, |_, t, _| &mut t.guid as *mut FakeGuid).unwrap_or(null_mut())
+}
+
+extern "C" fn get_set_media_track_info(
+    track: *mut c_void,
+    param_name: *const c_char,
+    _set_new_value: *mut c_void,
+) -> *mut c_void {
+    let param_name = unsafe { c_str(param_name) };
+    let project = with_state(|s| s.project_ptr());
+    with_track(track, |_, t, _| match param_name {
+        "P_PROJECT" => project,
+        "GUID" => &mut t.guid as *mut FakeGuid as *mut c_void,
+        "P_NAME" => t.name.as_ptr() as *mut c_void,
+        _ => null_mut(),
+    })
+    .unwrap_or(null_mut())
+}
+
+extern "C" fn get_media_track_info_value(track: *mut c_void, param_name: *const c_char) -> f64 {
+    let param_name = unsafe { c_str(param_name) };
+    with_track(track, |i, t, _| match param_name {
+        "D_VOL" => t.volume,
+        "D_PAN" => t.pan,
+        "B_MUTE" => t.mute as i32 as f64,
+        "I_RECARM" => t.arm as i32 as f64,
+        "I_SELECTED" => t.selected as i32 as f64,
+        "IP_TRACKNUMBER" => i.map(|i| i as f64 + 1.0).unwrap_or(-1.0),
+        _ => 0.0,
+    })
+    .unwrap_or(0.0)
+}
+
+extern "C" fn set_media_track_info_value(
+    track: *mut c_void,
+    param_name: *const c_char,
+    new_value: f64,
+) -> bool {
+    match unsafe { c_str(param_name) } {
+        "D_VOL" => set_volume(track, new_value).is_some(),
+        "B_MUTE" => set_mute(track, new_value != 0.0).is_some(),
+        "I_RECARM" => set_arm(track, new_value != 0.0).is_some(),
+        "I_SELECTED" => with_track(track, |_, t, _| t.selected = new_value != 0.0).is_some(),
+        _ => false,
+    }
+}
+
+#[allow(clippy::float_cmp)]
+fn set_volume(track: *mut c_void, new_value: f64) -> Option<f64> {
+    with_track(track, |i, t, changes| {
+        if t.volume != new_value {
+            t.volume = new_value;
+            if let Some(track_index) = i {
+                changes.push(FakeChange::TrackVolume {
+                    track_index,
+                    new_value,
+                });
+            }
+        }
+        t.volume
+    })
+}
+
+fn set_mute(track: *mut c_void, new_value: bool) -> Option<bool> {
+    with_track(track, |i, t, changes| {
+        if t.mute != new_value {
+            t.mute = new_value;
+            if let Some(track_index) = i {
+                changes.push(FakeChange::TrackMute {
+                    track_index,
+                    new_value,
+                });
+            }
+        }
+        t.mute
+    })
+}
+
+fn set_arm(track: *mut c_void, new_value: bool) -> Option<bool> {
+    with_track(track, |i, t, changes| {
+        if t.arm != new_value {
+            t.arm = new_value;
+            if let Some(track_index) = i {
+                changes.push(FakeChange::TrackArm {
+                    track_index,
+                    new_value,
+                });
+            }
+        }
+        t.arm
+    })
+}
+
+/// Interprets the REAPER convention for "set, toggle or leave" integer arguments.
+fn resolve_flag(requested: c_int, current: bool) -> bool {
+    if requested < 0 {
+        !current
+    } else {
+        requested > 0
+    }
+}
+
+extern "C" fn get_track_ui_vol_pan(
+    track: *mut c_void,
+    volume_out: *mut f64,
+    pan_out: *mut f64,
+) -> bool {
+    with_track(track, |_, t, _| unsafe {
+        if !volume_out.is_null() {
+            *volume_out = t.volume;
+        }
+        if !pan_out.is_null() {
+            *pan_out = t.pan;
+        }
+    })
+    .is_some()
+}
+
+extern "C" fn get_track_ui_mute(track: *mut c_void, mute_out: *mut bool) -> bool {
+    with_track(track, |_, t, _| unsafe {
+        if !mute_out.is_null() {
+            *mute_out = t.mute;
+        }
+    })
+    .is_some()
+}
+
+extern "C" fn set_track_ui_volume(
+    track: *mut c_void,
+    volume: f64,
+    relative: bool,
+    _done: bool,
+    _ignore_group_flags: c_int,
+) -> f64 {
+    csurf_on_volume_change_ex(track, volume, relative, false)
+}
+
+extern "C" fn set_track_ui_mute(
+    track: *mut c_void,
+    mute: c_int,
+    _ignore_group_flags: c_int,
+) -> c_int {
+    let current = with_track(track, |_, t, _| t.mute).unwrap_or_default();
+    set_mute(track, resolve_flag(mute, current)).unwrap_or_default() as c_int
+}
+
+extern "C" fn set_track_ui_rec_arm(
+    track: *mut c_void,
+    arm: c_int,
+    _ignore_group_flags: c_int,
+) -> c_int {
+    let current = with_track(track, |_, t, _| t.arm).unwrap_or_default();
+    set_arm(track, resolve_flag(arm, current)).unwrap_or_default() as c_int
+}
+
+extern "C" fn csurf_on_volume_change_ex(
+    track: *mut c_void,
+    volume: f64,
+    relative: bool,
+    _allow_gang: bool,
+) -> f64 {
+    let new_value = if relative {
+        let current = with_track(track, |_, t, _| t.volume).unwrap_or_default();
+        current + volume
+    } else {
+        volume
+    };
+    set_volume(track, new_value.max(0.0)).unwrap_or_default()
+}
+
+extern "C" fn csurf_on_mute_change_ex(track: *mut c_void, mute: c_int, _allow_gang: bool) -> bool {
+    let current = with_track(track, |_, t, _| t.mute).unwrap_or_default();
+    set_mute(track, resolve_flag(mute, current)).unwrap_or_default()
+}
+
+extern "C" fn csurf_on_rec_arm_change_ex(
+    track: *mut c_void,
+    arm: c_int,
+    _allow_gang: bool,
+) -> bool {
+    let current = with_track(track, |_, t, _| t.arm).unwrap_or_default();
+    set_arm(track, resolve_flag(arm, current)).unwrap_or_default()
+}
+
+// There are no control surfaces to inform. Changes are reported via [`take_changes`].
+
+extern "C" fn csurf_set_surface_volume(_track: *mut c_void, _volume: f64, _ignore: *mut c_void) {}
+
+extern "C" fn csurf_set_surface_mute(_track: *mut c_void, _mute: bool, _ignore: *mut c_void) {}
+
+extern "C" fn csurf_set_surface_rec_arm(_track: *mut c_void, _arm: bool, _ignore: *mut c_void) {}
+
+// FX
+
+extern "C" fn track_fx_get_count(track: *mut c_void) -> c_int {
+    with_track(track, |_, t, _| t.fxs.len() as c_int).unwrap_or(0)
+}
+
+extern "C" fn track_fx_get_rec_count(_track: *mut c_void) -> c_int {
+    0
+}
+
+extern "C" fn track_fx_get_fx_guid(track: *mut c_void, fx: c_int) -> *mut FakeGuid {
+    with_fx(track, fx, |_, _, fx, _| &mut fx.guid as *mut FakeGuid).unwrap_or(null_mut())
+}
+
+extern "C" fn track_fx_get_fx_name(
+    track: *mut c_void,
+    fx: c_int,
+    buf: *mut c_char,
+    buf_size: c_int,
+) -> bool {
+    with_fx(track, fx, |_, _, fx, _| unsafe {
+        write_c_str(buf, buf_size, &fx.name)
+    })
+    .unwrap_or(false)
+}
+
+extern "C" fn track_fx_get_enabled(track: *mut c_void, fx: c_int) -> bool {
+    with_fx(track, fx, |_, _, fx, _| fx.enabled).unwrap_or(false)
+}
+
+extern "C" fn track_fx_set_enabled(track: *mut c_void, fx: c_int, enabled: bool) {
+    with_fx(track, fx, |_, _, fx, _| fx.enabled = enabled);
+}
+
+extern "C" fn track_fx_get_named_config_parm(
+    track: *mut c_void,
+    fx: c_int,
+    param_name: *const c_char,
+    buf: *mut c_char,
+    buf_size: c_int,
+) -> bool {
+    let param_name = unsafe { c_str(param_name) };
+    with_fx(track, fx, |_, _, fx, _| {
+        if !fx.is_realearn || param_name != WAITING_FOR_SESSION_PARAM_NAME || buf_size < 1 {
+            return false;
+        }
+        // The headless harness creates the processors itself, so there's never a session.
+        unsafe {
+            *buf = 1;
+        }
+        true
+    })
+    .unwrap_or(false)
+}
+
+extern "C" fn track_fx_get_num_params(track: *mut c_void, fx: c_int) -> c_int {
+    with_fx(track, fx, |_, _, fx, _| fx.params.len() as c_int).unwrap_or(0)
+}
+
+extern "C" fn track_fx_get_param_from_ident(
+    track: *mut c_void,
+    fx: c_int,
+    ident: *const c_char,
+) -> c_int {
+    let ident = unsafe { c_str(ident) };
+    with_fx(track, fx, |_, _, fx, _| {
+        if !fx.is_realearn {
+            return -1;
+        }
+        match ident {
+            ":bypass" => REALEARN_PARAM_COUNT as c_int,
+            ":wet" => REALEARN_PARAM_COUNT as c_int + 1,
+            _ => -1,
+        }
+    })
+    .unwrap_or(-1)
+}
+
+extern "C" fn track_fx_get_param_name(
+    track: *mut c_void,
+    fx: c_int,
+    param: c_int,
+    buf: *mut c_char,
+    buf_size: c_int,
+) -> bool {
+    with_fx(track, fx, |_, _, fx, _| {
+        if param < 0 || param as usize >= fx.params.len() {
+            return false;
+        }
+        unsafe { write_c_str(buf, buf_size, &format!("Param {}", param + 1)) }
+    })
+    .unwrap_or(false)
+}
+
+extern "C" fn track_fx_get_param(
+    track: *mut c_void,
+    fx: c_int,
+    param: c_int,
+    min_out: *mut f64,
+    max_out: *mut f64,
+) -> f64 {
+    unsafe {
+        if !min_out.is_null() {
+            *min_out = 0.0;
+        }
+        if !max_out.is_null() {
+            *max_out = 1.0;
+        }
+    }
+    track_fx_get_param_normalized(track, fx, param)
+}
+
+extern "C" fn track_fx_get_param_normalized(track: *mut c_void, fx: c_int, param: c_int) -> f64 {
+    with_fx(track, fx, |_, _, fx, _| {
+        if param < 0 {
+            return None;
+        }
+        fx.params.get(param as usize).copied()
+    })
+    .flatten()
+    .unwrap_or(0.0)
+}
+
+#[allow(clippy::float_cmp)]
+extern "C" fn track_fx_set_param_normalized(
+    track: *mut c_void,
+    fx: c_int,
+    param: c_int,
+    value: f64,
+) -> bool {
+    with_fx(track, fx, |track_index, fx_index, fx, changes| {
+        if param < 0 {
+            return false;
+        }
+        let param_index = param as usize;
+        let current = match fx.params.get_mut(param_index) {
+            None => return false,
+            Some(v) => v,
+        };
+        if *current != value {
+            *current = value;
+            if let Some(track_index) = track_index {
+                changes.push(FakeChange::FxParameterValue {
+                    track_index,
+                    fx_index,
+                    param_index,
+                    new_value: value,
+                });
+            }
+        }
+        true
+    })
+    .unwrap_or(false)
+}
+
+extern "C" fn track_fx_format_param_value_normalized(
+    _track: *mut c_void,
+    _fx: c_int,
+    _param: c_int,
+    value: f64,
+    buf: *mut c_char,
+    buf_size: c_int,
+) -> bool {
+    unsafe { write_c_str(buf, buf_size, &format!("{:.2}", value)) }
+}
+
+extern "C" fn track_fx_get_parameter_step_sizes(
+    _track: *mut c_void,
+    _fx: c_int,
+    _param: c_int,
+    _step: *mut f64,
+    _small_step: *mut f64,
+    _large_step: *mut f64,
+    _is_toggle: *mut bool,
+) -> bool {
+    // All simulated parameters are continuous.
+    false
+}
@@ -0,0 +1,607 @@
+//! A harness for replaying presets without a running REAPER instance.
+//!
+//! It instantiates the real [`MainProcessor`] and [`RealTimeProcessor`], wires them with the same
+//! channels as the plug-in does and runs them against the [`fake_reaper`], which simulates tracks,
+//! FX parameters and transport. Incoming MIDI, OSC and key events take the same way as in
+//! production, so the complete chain from source over controller compartment virtualization to
+//! the resolved REAPER target and the resulting source feedback is covered by ordinary
+//! `cargo test` cases.
+//!
+//! The session (UI, presets, undo) is not part of it. That's still the job of the integration
+//! test running within REAPER.
+use crate::application::GroupData;
+use crate::base::{SenderToNormalThread, SenderToRealTimeThread};
+use crate::domain::{
+    AdditionalFeedbackEvent, BackboneState, FeedbackAudioHookTask, Garbage,
+    InstanceOrchestrationEvent, NormalAudioHookTask, OscFeedbackTask, QualifiedClipMatrixEvent,
+};
+use crate::domain::{
+    AudioBlockProps, BasicSettings, ChangeInstanceFxArgs, ChangeInstanceTrackArgs, Compartment,
+    ControlEvent, ControlEventTimestamp, ControlInput, DomainEvent, DomainEventHandler,
+    EnableInstancesArgs, FeedbackOutput, FinalSourceFeedbackValue, GarbageBin, IncomingMidiMessage,
+    InstanceContainer, InstanceId, KeyMessage, MainMapping, MainProcessor, MidiControlInput,
+    MidiDestination, MidiEvent, NormalMainTask, NormalRealTimeTask, ProcessorContext,
+    RealTimeProcessor, Tag,
+};
+use crate::infrastructure::data::{
+    MappingModelData, SessionData, SimpleDataToModelConversionContext,
+};
+use crate::infrastructure::test::fake_reaper;
+use crate::infrastructure::test::fake_reaper::FakeChange;
+use crossbeam_channel::Receiver;
+use helgoboss_learn::AbstractTimestamp;
+use helgoboss_midi::RawShortMessage;
+use reaper_high::{
+    ChangeEvent, FxParameter, FxParameterValueChangedEvent, PlayStateChangedEvent, Reaper, Track,
+    TrackArmChangedEvent, TrackMuteChangedEvent, TrackVolumeChangedEvent,
+};
+use reaper_medium::{Hz, PlayState, ReaperNormalizedFxParamValue, ReaperVolumeValue};
+use rosc::OscPacket;
+use std::collections::HashSet;
+use std::error::Error;
+use vst::buffer::AudioBuffer;
+use vst::plugin::HostCallback;
+
+const BLOCK_LENGTH: usize = 512;
+const SAMPLE_RATE: f64 = 44100.0;
+
+/// Number of main loop and audio cycles which are run after each incoming event.
+///
+/// Control and feedback go back and forth between the processors a few times (e.g. MIDI source
+/// matched in real-time processor, target hit in main processor, REAPER reports change, main
+/// processor sends feedback), so one cycle is not enough.
+const SETTLE_CYCLE_COUNT: usize = 5;
+
+/// A ReaLearn instance on the first track of the fake REAPER project.
+pub struct HeadlessRealearn {
+    host: HostCallback,
+    real_time_processor: RealTimeProcessor,
+    main_processor: MainProcessor<HeadlessEventHandler>,
+    feedback_receiver: Receiver<FinalSourceFeedbackValue>,
+    _unused_receivers: UnusedReceivers,
+}
+
+/// Receivers of channels which are served by other parts of ReaLearn in production (e.g. the
+/// audio hook or the OSC output). They must be kept alive, otherwise sending would fail.
+struct UnusedReceivers {
+    _feedback_audio_hook_tasks: Receiver<FeedbackAudioHookTask>,
+    _additional_feedback_events: Receiver<AdditionalFeedbackEvent>,
+    _instance_orchestration_events: Receiver<InstanceOrchestrationEvent>,
+    _osc_feedback_tasks: Receiver<OscFeedbackTask>,
+    _clip_matrix_events: Receiver<QualifiedClipMatrixEvent>,
+    _normal_audio_hook_tasks: Receiver<NormalAudioHookTask>,
+    _garbage: Receiver<Garbage>,
+}
+
+impl HeadlessRealearn {
+    /// Loads the given session JSON (the format of the presets in `infrastructure/test/presets`).
+    ///
+    /// Must be called within [`fake_reaper::run_in_main_thread`].
+    pub fn load(session_json: &str) -> Self {
+        let session_data: SessionData =
+            serde_json::from_str(session_json).expect("invalid session preset");
+        // Channels (see RealearnPlugin and App)
+        let (normal_real_time_task_sender, normal_real_time_task_receiver) =
+            SenderToRealTimeThread::new_channel("normal real-time tasks", 1000);
+        let (feedback_real_time_task_sender, feedback_real_time_task_receiver) =
+            SenderToRealTimeThread::new_channel("feedback real-time tasks", 2000);
+        let (normal_main_task_sender, normal_main_task_receiver) =
+            SenderToNormalThread::new_unbounded_channel("normal main tasks");
+        let (normal_rt_to_main_task_sender, normal_rt_to_main_task_receiver) =
+            SenderToNormalThread::new_bounded_channel("normal real-time to main tasks", 10_000);
+        let (control_main_task_sender, control_main_task_receiver) =
+            SenderToNormalThread::new_bounded_channel("control main tasks", 5000);
+        let (_, parameter_main_task_receiver) =
+            SenderToNormalThread::new_bounded_channel("parameter main tasks", 5000);
+        let (instance_feedback_event_sender, instance_feedback_event_receiver) =
+            SenderToNormalThread::new_unbounded_channel("instance state change events");
+        let (feedback_audio_hook_task_sender, feedback_audio_hook_task_receiver) =
+            SenderToRealTimeThread::new_channel("feedback audio hook tasks", 100_000);
+        let (additional_feedback_event_sender, additional_feedback_event_receiver) =
+            SenderToNormalThread::new_unbounded_channel("additional feedback events");
+        let (instance_orchestration_event_sender, instance_orchestration_event_receiver) =
+            SenderToNormalThread::new_unbounded_channel("instance orchestration events");
+        let (osc_feedback_task_sender, osc_feedback_task_receiver) =
+            SenderToNormalThread::new_unbounded_channel("osc feedback tasks");
+        let (clip_matrix_event_sender, clip_matrix_event_receiver) =
+            SenderToNormalThread::new_unbounded_channel("clip matrix events");
+        let (normal_audio_hook_task_sender, normal_audio_hook_task_receiver) =
+            SenderToRealTimeThread::new_channel("normal audio hook tasks", 2000);
+        let (garbage_sender, garbage_receiver) =
+            SenderToNormalThread::new_bounded_channel("garbage", 50_000);
+        let (feedback_sender, feedback_receiver) =
+            SenderToNormalThread::new_unbounded_channel("headless feedback");
+        // Processors
+        let instance_id = InstanceId::random();
+        let logger = slog::Logger::root(slog::Discard, slog::o!());
+        let host = fake_reaper::realearn_host();
+        let context = ProcessorContext::from_host(host).expect("couldn't create processor context");
+        let instance_state = BackboneState::get().create_instance(
+            instance_id,
+            instance_feedback_event_sender,
+            clip_matrix_event_sender,
+            normal_audio_hook_task_sender,
+            normal_real_time_task_sender.clone(),
+            context.track().cloned(),
+        );
+        let main_processor = MainProcessor::new(
+            instance_id,
+            &logger,
+            normal_main_task_sender.clone(),
+            normal_main_task_receiver,
+            normal_rt_to_main_task_receiver,
+            parameter_main_task_receiver,
+            control_main_task_receiver,
+            instance_feedback_event_receiver,
+            normal_real_time_task_sender.clone(),
+            feedback_real_time_task_sender.clone(),
+            feedback_audio_hook_task_sender,
+            additional_feedback_event_sender,
+            instance_orchestration_event_sender,
+            osc_feedback_task_sender,
+            HeadlessEventHandler,
+            context,
+            instance_state,
+            &HeadlessInstanceContainer,
+        );
+        let real_time_processor = RealTimeProcessor::new(
+            instance_id,
+            &logger,
+            normal_real_time_task_receiver,
+            feedback_real_time_task_receiver,
+            feedback_real_time_task_sender,
+            normal_rt_to_main_task_sender,
+            control_main_task_sender,
+            GarbageBin::new(garbage_sender),
+        );
+        let mut realearn = Self {
+            host,
+            real_time_processor,
+            main_processor,
+            feedback_receiver,
+            _unused_receivers: UnusedReceivers {
+                _feedback_audio_hook_tasks: feedback_audio_hook_task_receiver,
+                _additional_feedback_events: additional_feedback_event_receiver,
+                _instance_orchestration_events: instance_orchestration_event_receiver,
+                _osc_feedback_tasks: osc_feedback_task_receiver,
+                _clip_matrix_events: clip_matrix_event_receiver,
+                _normal_audio_hook_tasks: normal_audio_hook_task_receiver,
+                _garbage: garbage_receiver,
+            },
+        };
+        // Settings and mappings (see Session)
+        let settings = BasicSettings {
+            control_input: ControlInput::Midi(MidiControlInput::FxInput),
+            feedback_output: Some(FeedbackOutput::Midi(MidiDestination::FxOutput)),
+            ..Default::default()
+        };
+        normal_real_time_task_sender.send_complaining(NormalRealTimeTask::UpdateSettings(settings));
+        normal_main_task_sender.send_complaining(NormalMainTask::UpdateSettings(settings));
+        normal_main_task_sender.send_complaining(NormalMainTask::UseIntegrationTestFeedbackSender(
+            feedback_sender,
+        ));
+        let compartment_mappings = [
+            (Compartment::Controller, &session_data.controller_mappings),
+            (Compartment::Main, &session_data.mappings),
+        ];
+        for (compartment, mapping_datas) in compartment_mappings {
+            let main_mappings = create_main_mappings(compartment, mapping_datas);
+            normal_main_task_sender.send_complaining(NormalMainTask::UpdateAllMappings(
+                compartment,
+                main_mappings,
+            ));
+        }
+        normal_main_task_sender
+            .send_complaining(NormalMainTask::PotentiallyEnableOrDisableControlOrFeedback);
+        realearn.settle();
+        realearn
+    }
+
+    /// Sends the given MIDI message to the FX input.
+    pub fn send_midi(&mut self, msg: RawShortMessage) {
+        let evt = MidiEvent::without_offset(IncomingMidiMessage::Short(msg));
+        self.real_time_processor.process_incoming_midi_from_vst(
+            ControlEvent::new(evt, ControlEventTimestamp::now()),
+            false,
+            &self.host,
+        );
+        self.settle();
+    }
+
+    /// Sends the given OSC packet as if it came from the OSC input device.
+    pub fn send_osc(&mut self, packet: &OscPacket) {
+        self.main_processor
+            .process_incoming_osc_packet(ControlEvent::new(packet, ControlEventTimestamp::now()));
+        self.settle();
+    }
+
+    /// Sends the given key message as if it came from the computer keyboard.
+    pub fn send_key(&mut self, msg: KeyMessage) {
+        self.main_processor
+            .process_incoming_key_msg(ControlEvent::new(msg, ControlEventTimestamp::now()));
+        self.settle();
+    }
+
+    /// Returns the track which contains this ReaLearn instance.
+    pub fn track(&self) -> Track {
+        Reaper::get().current_project().track_by_index(0).unwrap()
+    }
+
+    /// Returns all feedback sent since the last call.
+    pub fn pop_feedback(&self) -> Vec<FinalSourceFeedbackValue> {
+        self.feedback_receiver.try_iter().collect()
+    }
+
+    /// Lets REAPER's main loop and audio thread run a few times.
+    pub fn settle(&mut self) {
+        for _ in 0..SETTLE_CYCLE_COUNT {
+            self.run_audio_cycle();
+            self.run_main_loop_cycle();
+        }
+    }
+
+    /// Does what the audio hook and the VST process callback do in each audio block.
+    fn run_audio_cycle(&mut self) {
+        let block_props = AudioBlockProps {
+            block_length: BLOCK_LENGTH,
+            frame_rate: Hz::new(SAMPLE_RATE),
+        };
+        self.real_time_processor.run_from_audio_hook_all(
+            block_props,
+            false,
+            ControlEventTimestamp::now(),
+        );
+        // The processor only looks at the input channels (for clip recording), so we can do with
+        // an empty buffer.
+        let inputs: [*const f64; 0] = [];
+        let mut outputs: [*mut f64; 0] = [];
+        let mut buffer = unsafe {
+            AudioBuffer::from_raw(0, 0, inputs.as_ptr(), outputs.as_mut_ptr(), BLOCK_LENGTH)
+        };
+        self.real_time_processor
+            .run_from_vst(&mut buffer, block_props, &self.host);
+    }
+
+    /// Does what the control surface does in each main loop cycle.
+    fn run_main_loop_cycle(&mut self) {
+        let timestamp = ControlEventTimestamp::now();
+        self.main_processor.run_essential(timestamp);
+        self.main_processor.run_control(timestamp);
+        let change_events: Vec<_> = fake_reaper::take_changes()
+            .into_iter()
+            .map(convert_change)
+            .collect();
+        self.main_processor
+            .process_control_surface_change_events(&change_events);
+        for event in fake_reaper::take_additional_feedback_events() {
+            self.main_processor
+                .process_additional_feedback_event(&event);
+        }
+    }
+}
+
+fn create_main_mappings(
+    compartment: Compartment,
+    mapping_datas: &[MappingModelData],
+) -> Vec<MainMapping> {
+    let conversion_context =
+        SimpleDataToModelConversionContext::from_session_or_random(&[], mapping_datas, None);
+    mapping_datas
+        .iter()
+        .map(|data| {
+            let model = data
+                .to_model(compartment, &conversion_context, None, None)
+                .expect("invalid mapping");
+            model.create_main_mapping(GroupData::default())
+        })
+        .collect()
+}
+
+/// Converts a fake REAPER change into the event REAPER's control surface would emit.
+fn convert_change(change: FakeChange) -> ChangeEvent {
+    let project = Reaper::get().current_project();
+    let track = |index: usize| project.track_by_index(index as u32).unwrap();
+    match change {
+        FakeChange::TrackVolume {
+            track_index,
+            new_value,
+        } => ChangeEvent::TrackVolumeChanged(TrackVolumeChangedEvent {
+            touched: true,
+            track: track(track_index),
+            new_value: ReaperVolumeValue::new(new_value),
+        }),
+        FakeChange::TrackMute {
+            track_index,
+            new_value,
+        } => ChangeEvent::TrackMuteChanged(TrackMuteChangedEvent {
+            touched: true,
+            track: track(track_index),
+            new_value,
+        }),
+        FakeChange::TrackArm {
+            track_index,
+            new_value,
+        } => ChangeEvent::TrackArmChanged(TrackArmChangedEvent {
+            track: track(track_index),
+            new_value,
+        }),
+        FakeChange::FxParameterValue {
+            track_index,
+            fx_index,
+            param_index,
+            new_value,
+        } => {
+            let parameter: FxParameter = track(track_index)
+                .normal_fx_chain()
+                .fx_by_index(fx_index as u32)
+                .unwrap()
+                .parameter_by_index(param_index as u32);
+            ChangeEvent::FxParameterValueChanged(FxParameterValueChangedEvent {
+                touched: true,
+                parameter,
+                new_value: ReaperNormalizedFxParamValue::new(new_value),
+            })
+        }
+        FakeChange::PlayState {
+            is_playing,
+            is_paused,
+        } => ChangeEvent::PlayStateChanged(PlayStateChangedEvent {
+            project,
+            new_value: PlayState {
+                is_playing,
+                is_paused,
+                is_recording: false,
+            },
+        }),
+    }
+}
+
+/// Stands in for the session, which is not part of the headless setup.
+#[derive(Debug)]
+struct HeadlessEventHandler;
+
+impl DomainEventHandler for HeadlessEventHandler {
+    fn handle_event(&self, _: DomainEvent) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn auto_load_different_preset_if_necessary(&self) -> Result<bool, &'static str> {
+        Ok(false)
+    }
+}
+
+/// Stands in for the app, which manages all other ReaLearn instances.
+#[derive(Debug)]
+struct HeadlessInstanceContainer;
+
+impl InstanceContainer for HeadlessInstanceContainer {
+    fn enable_instances(&self, _: EnableInstancesArgs) -> Option<HashSet<Tag>> {
+        None
+    }
+
+    fn change_instance_fx(&self, _: ChangeInstanceFxArgs) -> Result<(), &'static str> {
+        Err("there are no other instances in headless mode")
+    }
+
+    fn change_instance_track(&self, _: ChangeInstanceTrackArgs) -> Result<(), &'static str> {
+        Err("there are no other instances in headless mode")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Keystroke;
+    use approx::assert_abs_diff_eq;
+    use enumflags2::BitFlags;
+    use helgoboss_learn::{MidiSourceValue, BASE_EPSILON};
+    use helgoboss_midi::test_util::*;
+    use reaper_high::{GroupingBehavior, Volume};
+    use reaper_medium::{AccelMsgKind, AcceleratorKeyCode, Db, GangBehavior};
+    use rosc::{OscMessage, OscType};
+    use FinalSourceFeedbackValue::Midi;
+    use MidiSourceValue::Plain;
+
+    #[test]
+    fn basics() {
+        fake_reaper::run_in_main_thread(|| {
+            // Given
+            let mut realearn = HeadlessRealearn::load(include_str!("presets/basics.json"));
+            // Then
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 91)))],
+                "feedback should be sent after loading preset"
+            );
+            // When
+            realearn.send_midi(note_on(0, 64, 0));
+            // Then
+            assert_eq!(
+                realearn.track().volume().db(),
+                Db::MINUS_INF,
+                "NOTE OFF should turn down volume completely"
+            );
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 0)))],
+                "feedback should be sent on target value change"
+            );
+            // When
+            realearn.send_midi(note_on(0, 64, 0));
+            // Then
+            assert_eq!(realearn.track().volume().db(), Db::MINUS_INF);
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![],
+                "no feedback should be sent if target value not changed"
+            );
+            // When
+            realearn.send_midi(note_on(0, 64, 127));
+            // Then
+            assert_eq!(realearn.track().volume().db(), Db::TWELVE_DB);
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 127)))],
+                "feedback should be sent on target value change"
+            );
+        });
+    }
+
+    #[test]
+    fn basics_controller_compartment() {
+        fake_reaper::run_in_main_thread(|| {
+            // Given
+            let mut realearn =
+                HeadlessRealearn::load(include_str!("presets/basics-controller-compartment.json"));
+            // Then
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 91)))],
+                "feedback should be sent after loading preset"
+            );
+            // When
+            realearn.send_midi(note_on(0, 64, 0));
+            // Then
+            assert_eq!(realearn.track().volume().db(), Db::MINUS_INF);
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 0)))],
+                "feedback should be sent on target value change"
+            );
+            // When
+            realearn.track().set_volume(
+                Volume::from_db(Db::TWELVE_DB),
+                GangBehavior::DenyGang,
+                GroupingBehavior::PreventGrouping,
+            );
+            realearn.settle();
+            // Then
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 127)))],
+                "feedback should be sent when target value changed in REAPER"
+            );
+        });
+    }
+
+    #[test]
+    fn fx_by_position() {
+        fake_reaper::run_in_main_thread(|| {
+            // Given
+            let track_index = fake_reaper::add_track(
+                "FX",
+                &[
+                    ("ReaDelay (Cockos)", 4),
+                    ("ReaEQ (Cockos)", 4),
+                    ("ReaSynth (Cockos)", 4),
+                ],
+            );
+            let mut realearn = HeadlessRealearn::load(include_str!("presets/fx-by-position.json"));
+            // Then
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 64)))],
+                "feedback should be sent after loading preset"
+            );
+            // When
+            realearn.send_midi(note_on(0, 64, 10));
+            // Then
+            let eq = Reaper::get()
+                .current_project()
+                .track_by_index(track_index as u32)
+                .unwrap()
+                .normal_fx_chain()
+                .fx_by_index(1)
+                .unwrap();
+            assert_abs_diff_eq!(
+                eq.parameter_by_index(1).reaper_normalized_value().get(),
+                10.0 / 127.0,
+                epsilon = BASE_EPSILON
+            );
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 10)))],
+                "feedback should be sent on target value change"
+            );
+        });
+    }
+
+    #[test]
+    fn transport() {
+        fake_reaper::run_in_main_thread(|| {
+            // Given
+            let mut realearn = HeadlessRealearn::load(include_str!("presets/transport.json"));
+            let project = Reaper::get().current_project();
+            // Then
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 0)))],
+                "feedback should be sent after loading preset"
+            );
+            // When
+            realearn.send_midi(note_on(0, 64, 127));
+            // Then
+            assert!(project.is_playing());
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 127)))],
+                "feedback should be sent when transport starts"
+            );
+            // When
+            realearn.send_midi(note_on(0, 64, 0));
+            // Then
+            assert!(!project.is_playing());
+            assert_eq!(
+                realearn.pop_feedback(),
+                vec![Midi(Plain(note_on(0, 64, 0)))],
+                "feedback should be sent when transport stops"
+            );
+        });
+    }
+
+    #[test]
+    fn osc() {
+        fake_reaper::run_in_main_thread(|| {
+            // Given
+            let mut realearn = HeadlessRealearn::load(include_str!("presets/osc.json"));
+            let message = |value: f32| {
+                OscPacket::Message(OscMessage {
+                    addr: "/volume".to_string(),
+                    args: vec![OscType::Float(value)],
+                })
+            };
+            // When
+            realearn.send_osc(&message(0.0));
+            // Then
+            assert_eq!(realearn.track().volume().db(), Db::MINUS_INF);
+            // When
+            realearn.send_osc(&message(1.0));
+            // Then
+            assert_eq!(realearn.track().volume().db(), Db::TWELVE_DB);
+        });
+    }
+
+    #[test]
+    fn key() {
+        fake_reaper::run_in_main_thread(|| {
+            // Given
+            let stroke = Keystroke::new(BitFlags::empty(), AcceleratorKeyCode::new(b'M' as u16));
+            let mut preset: serde_json::Value =
+                serde_json::from_str(include_str!("presets/key.json")).unwrap();
+            preset["mappings"][0]["source"]["keystroke"] = serde_json::to_value(stroke).unwrap();
+            let mut realearn = HeadlessRealearn::load(&preset.to_string());
+            assert!(!realearn.track().is_muted());
+            // When
+            realearn.send_key(KeyMessage::new(AccelMsgKind::KeyDown, stroke));
+            // Then
+            assert!(realearn.track().is_muted(), "key press should mute track");
+            // When
+            realearn.send_key(KeyMessage::new(AccelMsgKind::KeyUp, stroke));
+            // Then
+            assert!(
+                !realearn.track().is_muted(),
+                "key release should unmute track"
+            );
+        });
+    }
+}
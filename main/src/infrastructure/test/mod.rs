@@ -13,6 +13,11 @@ use tokio::time::Duration;
 use FinalSourceFeedbackValue::Midi;
 use MidiSourceValue::{ParameterNumber, Plain};
 
+#[cfg(test)]
+mod fake_reaper;
+#[cfg(test)]
+mod headless;
+
 pub fn register_test_action() {
     Reaper::get().register_action(
         "REALEARN_INTEGRATION_TEST",
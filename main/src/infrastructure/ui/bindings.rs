@@ -106,7 +106,7 @@ pub mod root {
     pub const ID_CLEAR_SOURCE_FILTER_BUTTON: u32 = 30037;
     pub const ID_FILTER_BY_TARGET_BUTTON: u32 = 30038;
     pub const ID_CLEAR_TARGET_FILTER_BUTTON: u32 = 30039;
-    pub const ID_MAPPING_PANEL: u32 = 30191;
+    pub const ID_MAPPING_PANEL: u32 = 30197;
     pub const ID_MAPPING_FEEDBACK_SEND_BEHAVIOR_COMBO_BOX: u32 = 30046;
     pub const ID_MAPPING_SHOW_IN_PROJECTION_CHECK_BOX: u32 = 30047;
     pub const ID_MAPPING_ADVANCED_BUTTON: u32 = 30048;
@@ -161,139 +161,145 @@ pub mod root {
     pub const ID_TARGET_LINE_4_LABEL_3: u32 = 30101;
     pub const ID_TARGET_LINE_5_LABEL_1: u32 = 30102;
     pub const ID_TARGET_LINE_5_EDIT_CONTROL: u32 = 30103;
-    pub const ID_TARGET_CHECK_BOX_1: u32 = 30104;
-    pub const ID_TARGET_CHECK_BOX_2: u32 = 30105;
-    pub const ID_TARGET_CHECK_BOX_3: u32 = 30106;
-    pub const ID_TARGET_CHECK_BOX_4: u32 = 30107;
-    pub const ID_TARGET_CHECK_BOX_5: u32 = 30108;
-    pub const ID_TARGET_CHECK_BOX_6: u32 = 30109;
-    pub const ID_TARGET_VALUE_LABEL_TEXT: u32 = 30110;
-    pub const ID_TARGET_VALUE_OFF_BUTTON: u32 = 30111;
-    pub const ID_TARGET_VALUE_ON_BUTTON: u32 = 30112;
-    pub const ID_TARGET_VALUE_SLIDER_CONTROL: u32 = 30113;
-    pub const ID_TARGET_VALUE_EDIT_CONTROL: u32 = 30114;
-    pub const ID_TARGET_VALUE_TEXT: u32 = 30115;
-    pub const ID_TARGET_UNIT_BUTTON: u32 = 30116;
-    pub const ID_SETTINGS_RESET_BUTTON: u32 = 30118;
-    pub const ID_SETTINGS_SOURCE_LABEL: u32 = 30119;
+    pub const ID_TARGET_LINE_5_COMBO_BOX_1: u32 = 30104;
+    pub const ID_TARGET_LINE_5_COMBO_BOX_2: u32 = 30105;
+    pub const ID_TARGET_CHECK_BOX_1: u32 = 30106;
+    pub const ID_TARGET_CHECK_BOX_2: u32 = 30107;
+    pub const ID_TARGET_CHECK_BOX_3: u32 = 30108;
+    pub const ID_TARGET_CHECK_BOX_4: u32 = 30109;
+    pub const ID_TARGET_CHECK_BOX_5: u32 = 30110;
+    pub const ID_TARGET_CHECK_BOX_6: u32 = 30111;
+    pub const ID_TARGET_VALUE_LABEL_TEXT: u32 = 30112;
+    pub const ID_TARGET_VALUE_OFF_BUTTON: u32 = 30113;
+    pub const ID_TARGET_VALUE_ON_BUTTON: u32 = 30114;
+    pub const ID_TARGET_VALUE_SLIDER_CONTROL: u32 = 30115;
+    pub const ID_TARGET_VALUE_EDIT_CONTROL: u32 = 30116;
+    pub const ID_TARGET_VALUE_TEXT: u32 = 30117;
+    pub const ID_TARGET_UNIT_BUTTON: u32 = 30118;
+    pub const ID_SETTINGS_RESET_BUTTON: u32 = 30120;
+    pub const ID_SETTINGS_SOURCE_LABEL: u32 = 30121;
     #[allow(dead_code)]
-    pub const ID_SETTINGS_SOURCE_GROUP: u32 = 30120;
-    pub const ID_SETTINGS_SOURCE_MIN_LABEL: u32 = 30121;
-    pub const ID_SETTINGS_MIN_SOURCE_VALUE_SLIDER_CONTROL: u32 = 30122;
-    pub const ID_SETTINGS_MIN_SOURCE_VALUE_EDIT_CONTROL: u32 = 30123;
-    pub const ID_SETTINGS_SOURCE_MAX_LABEL: u32 = 30124;
-    pub const ID_SETTINGS_MAX_SOURCE_VALUE_SLIDER_CONTROL: u32 = 30125;
-    pub const ID_SETTINGS_MAX_SOURCE_VALUE_EDIT_CONTROL: u32 = 30126;
-    pub const ID_MODE_OUT_OF_RANGE_LABEL_TEXT: u32 = 30127;
-    pub const ID_MODE_OUT_OF_RANGE_COMBOX_BOX: u32 = 30128;
-    pub const ID_MODE_GROUP_INTERACTION_LABEL_TEXT: u32 = 30129;
-    pub const ID_MODE_GROUP_INTERACTION_COMBO_BOX: u32 = 30130;
-    pub const ID_SETTINGS_TARGET_LABEL_TEXT: u32 = 30131;
-    pub const ID_SETTINGS_TARGET_SEQUENCE_LABEL_TEXT: u32 = 30132;
-    pub const ID_MODE_TARGET_SEQUENCE_EDIT_CONTROL: u32 = 30133;
+    pub const ID_SETTINGS_SOURCE_GROUP: u32 = 30122;
+    pub const ID_SETTINGS_SOURCE_MIN_LABEL: u32 = 30123;
+    pub const ID_SETTINGS_MIN_SOURCE_VALUE_SLIDER_CONTROL: u32 = 30124;
+    pub const ID_SETTINGS_MIN_SOURCE_VALUE_EDIT_CONTROL: u32 = 30125;
+    pub const ID_SETTINGS_SOURCE_MAX_LABEL: u32 = 30126;
+    pub const ID_SETTINGS_MAX_SOURCE_VALUE_SLIDER_CONTROL: u32 = 30127;
+    pub const ID_SETTINGS_MAX_SOURCE_VALUE_EDIT_CONTROL: u32 = 30128;
+    pub const ID_MODE_OUT_OF_RANGE_LABEL_TEXT: u32 = 30129;
+    pub const ID_MODE_OUT_OF_RANGE_COMBOX_BOX: u32 = 30130;
+    pub const ID_MODE_GROUP_INTERACTION_LABEL_TEXT: u32 = 30131;
+    pub const ID_MODE_GROUP_INTERACTION_COMBO_BOX: u32 = 30132;
+    pub const ID_SETTINGS_TARGET_LABEL_TEXT: u32 = 30133;
+    pub const ID_SETTINGS_TARGET_SEQUENCE_LABEL_TEXT: u32 = 30134;
+    pub const ID_MODE_TARGET_SEQUENCE_EDIT_CONTROL: u32 = 30135;
     #[allow(dead_code)]
-    pub const ID_SETTINGS_TARGET_GROUP: u32 = 30134;
-    pub const ID_SETTINGS_MIN_TARGET_LABEL_TEXT: u32 = 30135;
-    pub const ID_SETTINGS_MIN_TARGET_VALUE_SLIDER_CONTROL: u32 = 30136;
-    pub const ID_SETTINGS_MIN_TARGET_VALUE_EDIT_CONTROL: u32 = 30137;
-    pub const ID_SETTINGS_MIN_TARGET_VALUE_TEXT: u32 = 30138;
-    pub const ID_SETTINGS_MAX_TARGET_LABEL_TEXT: u32 = 30139;
-    pub const ID_SETTINGS_MAX_TARGET_VALUE_SLIDER_CONTROL: u32 = 30140;
-    pub const ID_SETTINGS_MAX_TARGET_VALUE_EDIT_CONTROL: u32 = 30141;
-    pub const ID_SETTINGS_MAX_TARGET_VALUE_TEXT: u32 = 30142;
-    pub const ID_SETTINGS_REVERSE_CHECK_BOX: u32 = 30143;
-    pub const IDC_MODE_FEEDBACK_TYPE_COMBO_BOX: u32 = 30144;
-    pub const ID_MODE_EEL_FEEDBACK_TRANSFORMATION_EDIT_CONTROL: u32 = 30145;
-    pub const IDC_MODE_FEEDBACK_TYPE_BUTTON: u32 = 30146;
-    pub const ID_MODE_KNOB_FADER_GROUP_BOX: u32 = 30147;
-    pub const ID_SETTINGS_MODE_LABEL: u32 = 30148;
-    pub const ID_SETTINGS_MODE_COMBO_BOX: u32 = 30149;
-    pub const ID_MODE_TAKEOVER_LABEL: u32 = 30150;
-    pub const ID_MODE_TAKEOVER_MODE: u32 = 30151;
-    pub const ID_SETTINGS_ROUND_TARGET_VALUE_CHECK_BOX: u32 = 30152;
-    pub const ID_MODE_EEL_CONTROL_TRANSFORMATION_LABEL: u32 = 30153;
-    pub const ID_MODE_EEL_CONTROL_TRANSFORMATION_EDIT_CONTROL: u32 = 30154;
-    pub const ID_MODE_EEL_CONTROL_TRANSFORMATION_DETAIL_BUTTON: u32 = 30155;
-    pub const ID_MODE_RELATIVE_GROUP_BOX: u32 = 30156;
-    pub const ID_SETTINGS_STEP_SIZE_LABEL_TEXT: u32 = 30157;
+    pub const ID_SETTINGS_TARGET_GROUP: u32 = 30136;
+    pub const ID_SETTINGS_MIN_TARGET_LABEL_TEXT: u32 = 30137;
+    pub const ID_SETTINGS_MIN_TARGET_VALUE_SLIDER_CONTROL: u32 = 30138;
+    pub const ID_SETTINGS_MIN_TARGET_VALUE_EDIT_CONTROL: u32 = 30139;
+    pub const ID_SETTINGS_MIN_TARGET_VALUE_TEXT: u32 = 30140;
+    pub const ID_SETTINGS_MAX_TARGET_LABEL_TEXT: u32 = 30141;
+    pub const ID_SETTINGS_MAX_TARGET_VALUE_SLIDER_CONTROL: u32 = 30142;
+    pub const ID_SETTINGS_MAX_TARGET_VALUE_EDIT_CONTROL: u32 = 30143;
+    pub const ID_SETTINGS_MAX_TARGET_VALUE_TEXT: u32 = 30144;
+    pub const ID_SETTINGS_REVERSE_CHECK_BOX: u32 = 30145;
+    pub const IDC_MODE_FEEDBACK_TYPE_COMBO_BOX: u32 = 30146;
+    pub const ID_MODE_EEL_FEEDBACK_TRANSFORMATION_EDIT_CONTROL: u32 = 30147;
+    pub const IDC_MODE_FEEDBACK_TYPE_BUTTON: u32 = 30148;
+    pub const ID_MODE_KNOB_FADER_GROUP_BOX: u32 = 30149;
+    pub const ID_SETTINGS_MODE_LABEL: u32 = 30150;
+    pub const ID_SETTINGS_MODE_COMBO_BOX: u32 = 30151;
+    pub const ID_MODE_GESTURE_LABEL_TEXT: u32 = 30152;
+    pub const ID_MODE_GESTURE_COMBO_BOX: u32 = 30153;
+    pub const ID_MODE_GESTURE_TAP_COUNT_EDIT_CONTROL: u32 = 30154;
+    pub const ID_MODE_GESTURE_TAP_COUNT_LABEL_TEXT: u32 = 30155;
+    pub const ID_MODE_TAKEOVER_LABEL: u32 = 30156;
+    pub const ID_MODE_TAKEOVER_MODE: u32 = 30157;
+    pub const ID_SETTINGS_ROUND_TARGET_VALUE_CHECK_BOX: u32 = 30158;
+    pub const ID_MODE_EEL_CONTROL_TRANSFORMATION_LABEL: u32 = 30159;
+    pub const ID_MODE_EEL_CONTROL_TRANSFORMATION_EDIT_CONTROL: u32 = 30160;
+    pub const ID_MODE_EEL_CONTROL_TRANSFORMATION_DETAIL_BUTTON: u32 = 30161;
+    pub const ID_MODE_RELATIVE_GROUP_BOX: u32 = 30162;
+    pub const ID_SETTINGS_STEP_SIZE_LABEL_TEXT: u32 = 30163;
     #[allow(dead_code)]
-    pub const ID_SETTINGS_STEP_SIZE_GROUP: u32 = 30158;
-    pub const ID_SETTINGS_MIN_STEP_SIZE_LABEL_TEXT: u32 = 30159;
-    pub const ID_SETTINGS_MIN_STEP_SIZE_SLIDER_CONTROL: u32 = 30160;
-    pub const ID_SETTINGS_MIN_STEP_SIZE_EDIT_CONTROL: u32 = 30161;
-    pub const ID_SETTINGS_MIN_STEP_SIZE_VALUE_TEXT: u32 = 30162;
-    pub const ID_SETTINGS_MAX_STEP_SIZE_LABEL_TEXT: u32 = 30163;
-    pub const ID_SETTINGS_MAX_STEP_SIZE_SLIDER_CONTROL: u32 = 30164;
-    pub const ID_SETTINGS_MAX_STEP_SIZE_EDIT_CONTROL: u32 = 30165;
-    pub const ID_SETTINGS_MAX_STEP_SIZE_VALUE_TEXT: u32 = 30166;
-    pub const ID_MODE_RELATIVE_FILTER_COMBO_BOX: u32 = 30167;
-    pub const ID_SETTINGS_ROTATE_CHECK_BOX: u32 = 30168;
-    pub const ID_SETTINGS_MAKE_ABSOLUTE_CHECK_BOX: u32 = 30169;
-    pub const ID_MODE_BUTTON_GROUP_BOX: u32 = 30170;
-    pub const ID_MODE_FIRE_COMBO_BOX: u32 = 30171;
-    pub const ID_MODE_BUTTON_FILTER_COMBO_BOX: u32 = 30172;
-    pub const ID_MODE_FIRE_LINE_2_LABEL_1: u32 = 30173;
-    pub const ID_MODE_FIRE_LINE_2_SLIDER_CONTROL: u32 = 30174;
-    pub const ID_MODE_FIRE_LINE_2_EDIT_CONTROL: u32 = 30175;
-    pub const ID_MODE_FIRE_LINE_2_LABEL_2: u32 = 30176;
-    pub const ID_MODE_FIRE_LINE_3_LABEL_1: u32 = 30177;
-    pub const ID_MODE_FIRE_LINE_3_SLIDER_CONTROL: u32 = 30178;
-    pub const ID_MODE_FIRE_LINE_3_EDIT_CONTROL: u32 = 30179;
-    pub const ID_MODE_FIRE_LINE_3_LABEL_2: u32 = 30180;
-    pub const ID_MAPPING_HELP_SUBJECT_LABEL: u32 = 30181;
-    pub const IDC_MAPPING_MATCHED_INDICATOR_TEXT: u32 = 30182;
-    pub const ID_MAPPING_HELP_APPLICABLE_TO_LABEL: u32 = 30183;
-    pub const ID_MAPPING_HELP_APPLICABLE_TO_COMBO_BOX: u32 = 30184;
-    pub const ID_MAPPING_HELP_CONTENT_LABEL: u32 = 30185;
-    pub const IDC_BEEP_ON_SUCCESS_CHECK_BOX: u32 = 30186;
-    pub const ID_MAPPING_PANEL_PREVIOUS_BUTTON: u32 = 30187;
-    pub const ID_MAPPING_PANEL_OK: u32 = 30188;
-    pub const ID_MAPPING_PANEL_NEXT_BUTTON: u32 = 30189;
-    pub const IDC_MAPPING_ENABLED_CHECK_BOX: u32 = 30190;
-    pub const ID_MAPPING_ROW_PANEL: u32 = 30209;
-    pub const ID_MAPPING_ROW_MAPPING_LABEL: u32 = 30192;
-    pub const IDC_MAPPING_ROW_ENABLED_CHECK_BOX: u32 = 30193;
-    pub const ID_MAPPING_ROW_EDIT_BUTTON: u32 = 30194;
-    pub const ID_MAPPING_ROW_DUPLICATE_BUTTON: u32 = 30195;
-    pub const ID_MAPPING_ROW_REMOVE_BUTTON: u32 = 30196;
-    pub const ID_MAPPING_ROW_LEARN_SOURCE_BUTTON: u32 = 30197;
-    pub const ID_MAPPING_ROW_LEARN_TARGET_BUTTON: u32 = 30198;
-    pub const ID_MAPPING_ROW_CONTROL_CHECK_BOX: u32 = 30199;
-    pub const ID_MAPPING_ROW_FEEDBACK_CHECK_BOX: u32 = 30200;
-    pub const ID_MAPPING_ROW_SOURCE_LABEL_TEXT: u32 = 30201;
-    pub const ID_MAPPING_ROW_TARGET_LABEL_TEXT: u32 = 30202;
-    pub const ID_MAPPING_ROW_DIVIDER: u32 = 30203;
-    pub const ID_MAPPING_ROW_GROUP_LABEL: u32 = 30204;
-    pub const IDC_MAPPING_ROW_MATCHED_INDICATOR_TEXT: u32 = 30205;
-    pub const ID_UP_BUTTON: u32 = 30207;
-    pub const ID_DOWN_BUTTON: u32 = 30208;
-    pub const ID_MAPPING_ROWS_PANEL: u32 = 30212;
-    pub const ID_DISPLAY_ALL_GROUPS_BUTTON: u32 = 30210;
-    pub const ID_GROUP_IS_EMPTY_TEXT: u32 = 30211;
-    pub const ID_MESSAGE_PANEL: u32 = 30214;
-    pub const ID_MESSAGE_TEXT: u32 = 30213;
-    pub const ID_SHARED_GROUP_MAPPING_PANEL: u32 = 30230;
-    pub const ID_MAPPING_NAME_EDIT_CONTROL: u32 = 30216;
-    pub const ID_MAPPING_TAGS_EDIT_CONTROL: u32 = 30218;
-    pub const ID_MAPPING_CONTROL_ENABLED_CHECK_BOX: u32 = 30219;
-    pub const ID_MAPPING_FEEDBACK_ENABLED_CHECK_BOX: u32 = 30220;
-    pub const ID_MAPPING_ACTIVATION_TYPE_COMBO_BOX: u32 = 30222;
-    pub const ID_MAPPING_ACTIVATION_SETTING_1_LABEL_TEXT: u32 = 30223;
-    pub const ID_MAPPING_ACTIVATION_SETTING_1_BUTTON: u32 = 30224;
-    pub const ID_MAPPING_ACTIVATION_SETTING_1_CHECK_BOX: u32 = 30225;
-    pub const ID_MAPPING_ACTIVATION_SETTING_2_LABEL_TEXT: u32 = 30226;
-    pub const ID_MAPPING_ACTIVATION_SETTING_2_BUTTON: u32 = 30227;
-    pub const ID_MAPPING_ACTIVATION_SETTING_2_CHECK_BOX: u32 = 30228;
-    pub const ID_MAPPING_ACTIVATION_EDIT_CONTROL: u32 = 30229;
-    pub const ID_MAIN_PANEL: u32 = 30236;
-    pub const ID_MAIN_PANEL_STATUS_1_TEXT: u32 = 30232;
-    pub const ID_MAIN_PANEL_STATUS_2_TEXT: u32 = 30233;
-    pub const IDC_EDIT_TAGS_BUTTON: u32 = 30234;
-    pub const ID_MAIN_PANEL_VERSION_TEXT: u32 = 30235;
-    pub const ID_YAML_EDITOR_PANEL: u32 = 30241;
-    pub const ID_YAML_TEXT_EDITOR_BUTTON: u32 = 30237;
-    pub const ID_YAML_EDIT_CONTROL: u32 = 30238;
-    pub const ID_YAML_HELP_BUTTON: u32 = 30239;
-    pub const ID_YAML_EDIT_INFO_TEXT: u32 = 30240;
-    pub const ID_EMPTY_PANEL: u32 = 30242;
+    pub const ID_SETTINGS_STEP_SIZE_GROUP: u32 = 30164;
+    pub const ID_SETTINGS_MIN_STEP_SIZE_LABEL_TEXT: u32 = 30165;
+    pub const ID_SETTINGS_MIN_STEP_SIZE_SLIDER_CONTROL: u32 = 30166;
+    pub const ID_SETTINGS_MIN_STEP_SIZE_EDIT_CONTROL: u32 = 30167;
+    pub const ID_SETTINGS_MIN_STEP_SIZE_VALUE_TEXT: u32 = 30168;
+    pub const ID_SETTINGS_MAX_STEP_SIZE_LABEL_TEXT: u32 = 30169;
+    pub const ID_SETTINGS_MAX_STEP_SIZE_SLIDER_CONTROL: u32 = 30170;
+    pub const ID_SETTINGS_MAX_STEP_SIZE_EDIT_CONTROL: u32 = 30171;
+    pub const ID_SETTINGS_MAX_STEP_SIZE_VALUE_TEXT: u32 = 30172;
+    pub const ID_MODE_RELATIVE_FILTER_COMBO_BOX: u32 = 30173;
+    pub const ID_SETTINGS_ROTATE_CHECK_BOX: u32 = 30174;
+    pub const ID_SETTINGS_MAKE_ABSOLUTE_CHECK_BOX: u32 = 30175;
+    pub const ID_MODE_BUTTON_GROUP_BOX: u32 = 30176;
+    pub const ID_MODE_FIRE_COMBO_BOX: u32 = 30177;
+    pub const ID_MODE_BUTTON_FILTER_COMBO_BOX: u32 = 30178;
+    pub const ID_MODE_FIRE_LINE_2_LABEL_1: u32 = 30179;
+    pub const ID_MODE_FIRE_LINE_2_SLIDER_CONTROL: u32 = 30180;
+    pub const ID_MODE_FIRE_LINE_2_EDIT_CONTROL: u32 = 30181;
+    pub const ID_MODE_FIRE_LINE_2_LABEL_2: u32 = 30182;
+    pub const ID_MODE_FIRE_LINE_3_LABEL_1: u32 = 30183;
+    pub const ID_MODE_FIRE_LINE_3_SLIDER_CONTROL: u32 = 30184;
+    pub const ID_MODE_FIRE_LINE_3_EDIT_CONTROL: u32 = 30185;
+    pub const ID_MODE_FIRE_LINE_3_LABEL_2: u32 = 30186;
+    pub const ID_MAPPING_HELP_SUBJECT_LABEL: u32 = 30187;
+    pub const IDC_MAPPING_MATCHED_INDICATOR_TEXT: u32 = 30188;
+    pub const ID_MAPPING_HELP_APPLICABLE_TO_LABEL: u32 = 30189;
+    pub const ID_MAPPING_HELP_APPLICABLE_TO_COMBO_BOX: u32 = 30190;
+    pub const ID_MAPPING_HELP_CONTENT_LABEL: u32 = 30191;
+    pub const IDC_BEEP_ON_SUCCESS_CHECK_BOX: u32 = 30192;
+    pub const ID_MAPPING_PANEL_PREVIOUS_BUTTON: u32 = 30193;
+    pub const ID_MAPPING_PANEL_OK: u32 = 30194;
+    pub const ID_MAPPING_PANEL_NEXT_BUTTON: u32 = 30195;
+    pub const IDC_MAPPING_ENABLED_CHECK_BOX: u32 = 30196;
+    pub const ID_MAPPING_ROW_PANEL: u32 = 30215;
+    pub const ID_MAPPING_ROW_MAPPING_LABEL: u32 = 30198;
+    pub const IDC_MAPPING_ROW_ENABLED_CHECK_BOX: u32 = 30199;
+    pub const ID_MAPPING_ROW_EDIT_BUTTON: u32 = 30200;
+    pub const ID_MAPPING_ROW_DUPLICATE_BUTTON: u32 = 30201;
+    pub const ID_MAPPING_ROW_REMOVE_BUTTON: u32 = 30202;
+    pub const ID_MAPPING_ROW_LEARN_SOURCE_BUTTON: u32 = 30203;
+    pub const ID_MAPPING_ROW_LEARN_TARGET_BUTTON: u32 = 30204;
+    pub const ID_MAPPING_ROW_CONTROL_CHECK_BOX: u32 = 30205;
+    pub const ID_MAPPING_ROW_FEEDBACK_CHECK_BOX: u32 = 30206;
+    pub const ID_MAPPING_ROW_SOURCE_LABEL_TEXT: u32 = 30207;
+    pub const ID_MAPPING_ROW_TARGET_LABEL_TEXT: u32 = 30208;
+    pub const ID_MAPPING_ROW_DIVIDER: u32 = 30209;
+    pub const ID_MAPPING_ROW_GROUP_LABEL: u32 = 30210;
+    pub const IDC_MAPPING_ROW_MATCHED_INDICATOR_TEXT: u32 = 30211;
+    pub const ID_UP_BUTTON: u32 = 30213;
+    pub const ID_DOWN_BUTTON: u32 = 30214;
+    pub const ID_MAPPING_ROWS_PANEL: u32 = 30218;
+    pub const ID_DISPLAY_ALL_GROUPS_BUTTON: u32 = 30216;
+    pub const ID_GROUP_IS_EMPTY_TEXT: u32 = 30217;
+    pub const ID_MESSAGE_PANEL: u32 = 30220;
+    pub const ID_MESSAGE_TEXT: u32 = 30219;
+    pub const ID_SHARED_GROUP_MAPPING_PANEL: u32 = 30236;
+    pub const ID_MAPPING_NAME_EDIT_CONTROL: u32 = 30222;
+    pub const ID_MAPPING_TAGS_EDIT_CONTROL: u32 = 30224;
+    pub const ID_MAPPING_CONTROL_ENABLED_CHECK_BOX: u32 = 30225;
+    pub const ID_MAPPING_FEEDBACK_ENABLED_CHECK_BOX: u32 = 30226;
+    pub const ID_MAPPING_ACTIVATION_TYPE_COMBO_BOX: u32 = 30228;
+    pub const ID_MAPPING_ACTIVATION_SETTING_1_LABEL_TEXT: u32 = 30229;
+    pub const ID_MAPPING_ACTIVATION_SETTING_1_BUTTON: u32 = 30230;
+    pub const ID_MAPPING_ACTIVATION_SETTING_1_CHECK_BOX: u32 = 30231;
+    pub const ID_MAPPING_ACTIVATION_SETTING_2_LABEL_TEXT: u32 = 30232;
+    pub const ID_MAPPING_ACTIVATION_SETTING_2_BUTTON: u32 = 30233;
+    pub const ID_MAPPING_ACTIVATION_SETTING_2_CHECK_BOX: u32 = 30234;
+    pub const ID_MAPPING_ACTIVATION_EDIT_CONTROL: u32 = 30235;
+    pub const ID_MAIN_PANEL: u32 = 30242;
+    pub const ID_MAIN_PANEL_STATUS_1_TEXT: u32 = 30238;
+    pub const ID_MAIN_PANEL_STATUS_2_TEXT: u32 = 30239;
+    pub const IDC_EDIT_TAGS_BUTTON: u32 = 30240;
+    pub const ID_MAIN_PANEL_VERSION_TEXT: u32 = 30241;
+    pub const ID_YAML_EDITOR_PANEL: u32 = 30247;
+    pub const ID_YAML_TEXT_EDITOR_BUTTON: u32 = 30243;
+    pub const ID_YAML_EDIT_CONTROL: u32 = 30244;
+    pub const ID_YAML_HELP_BUTTON: u32 = 30245;
+    pub const ID_YAML_EDIT_INFO_TEXT: u32 = 30246;
+    pub const ID_EMPTY_PANEL: u32 = 30248;
 }
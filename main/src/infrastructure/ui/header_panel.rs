@@ -25,7 +25,8 @@ use crate::base::{when, Global};
 use crate::domain::{
     convert_compartment_param_index_range_to_iter, BackboneState, ClipMatrixRef, Compartment,
     CompartmentParamIndex, ControlInput, FeedbackOutput, GroupId, MessageCaptureEvent, OscDeviceId,
-    ParamSetting, ReaperTarget, StayActiveWhenProjectInBackground, COMPARTMENT_PARAMETER_COUNT,
+    ParamSetting, ReaperTarget, SpeechSettings, StayActiveWhenProjectInBackground,
+    COMPARTMENT_PARAMETER_COUNT, MAX_SPEECH_RATE, MIN_SPEECH_RATE,
 };
 use crate::domain::{MidiControlInput, MidiDestination};
 use crate::infrastructure::data::{
@@ -41,11 +42,14 @@ use crate::infrastructure::ui::bindings::root;
 use crate::base::notification::notify_processing_result;
 use crate::infrastructure::api::convert::from_data::ConversionStyle;
 use crate::infrastructure::ui::dialog_util::add_group_via_dialog;
-use crate::infrastructure::ui::util::{open_in_browser, open_in_file_manager};
+use crate::infrastructure::ui::util::{
+    open_in_browser, open_in_file_manager, prompt_for_file_to_read,
+};
 use crate::infrastructure::ui::{
     add_firewall_rule, copy_text_to_clipboard, deserialize_api_object_from_lua,
-    deserialize_data_object, deserialize_data_object_from_json, dry_run_lua_script,
-    get_text_from_clipboard, serialize_data_object, serialize_data_object_to_json,
+    deserialize_data_object, deserialize_data_object_from_json,
+    deserialize_data_object_from_osc_layout, dry_run_lua_script, get_text_from_clipboard,
+    serialize_data_object, serialize_data_object_to_csi, serialize_data_object_to_json,
     serialize_data_object_to_lua, DataObject, GroupFilter, GroupPanel, IndependentPanelManager,
     MappingRowsPanel, PlainTextEngine, ScriptEditorInput, SearchExpression, SerializationFormat,
     SharedIndependentPanelManager, SharedMainState, SimpleScriptEditorPanel, SourceFilter,
@@ -53,7 +57,7 @@ use crate::infrastructure::ui::{
 };
 use crate::infrastructure::ui::{dialog_util, CompanionAppPresenter};
 use itertools::Itertools;
-use realearn_api::persistence::Envelope;
+use realearn_api::persistence::{Envelope, SpeechQueuePolicy};
 use semver::Version;
 use std::cell::{Cell, RefCell};
 use std::error::Error;
@@ -232,6 +236,7 @@ impl HeaderPanel {
                     VirtualControlElementType::Multi
                 }),
                 item("Buttons", || VirtualControlElementType::Button),
+                item("XY pads and joysticks", || VirtualControlElementType::Xy),
             ])
         };
         self.view
@@ -456,6 +461,38 @@ impl HeaderPanel {
                                 })
                                 .collect(),
                         ),
+                        {
+                            let speech_settings = session.speech_settings.get_ref();
+                            let voice_label = if speech_settings.voice.is_empty() {
+                                "<Default>"
+                            } else {
+                                speech_settings.voice.as_str()
+                            };
+                            menu(
+                                "Speech",
+                                [
+                                    item(format!("Voice: {}...", voice_label), || {
+                                        MainMenuAction::EditSpeechVoice
+                                    }),
+                                    item(format!("Rate: {}%...", speech_settings.rate), || {
+                                        MainMenuAction::EditSpeechRate
+                                    }),
+                                    separator(),
+                                ]
+                                .into_iter()
+                                .chain(SpeechQueuePolicy::into_enum_iter().map(|policy| {
+                                    item_with_opts(
+                                        format!("Queue: {}", policy),
+                                        ItemOpts {
+                                            enabled: true,
+                                            checked: speech_settings.queue_policy == policy,
+                                        },
+                                        move || MainMenuAction::SetSpeechQueuePolicy(policy),
+                                    )
+                                }))
+                                .collect(),
+                            )
+                        },
                     ],
                 ),
                 menu(
@@ -567,6 +604,10 @@ impl HeaderPanel {
                         PresetLinkScope::Global,
                     ),
                 ),
+                item(
+                    "Import TouchOSC or Open Stage Control layout file...",
+                    || MainMenuAction::ImportOscLayoutFile,
+                ),
                 item("Open preset folder", || MainMenuAction::OpenPresetFolder),
                 item("Reload all presets from disk", || {
                     MainMenuAction::ReloadAllPresets
@@ -693,6 +734,9 @@ impl HeaderPanel {
             MainMenuAction::SetStayActiveWhenProjectInBackground(option) => {
                 self.set_stay_active_when_project_in_background(option)
             }
+            MainMenuAction::EditSpeechVoice => self.edit_speech_voice(),
+            MainMenuAction::EditSpeechRate => self.edit_speech_rate(),
+            MainMenuAction::SetSpeechQueuePolicy(policy) => self.set_speech_queue_policy(policy),
             MainMenuAction::ToggleServer => {
                 enum ServerAction {
                     Start,
@@ -759,6 +803,10 @@ impl HeaderPanel {
             MainMenuAction::ChangeSessionId => self.change_session_id(),
             MainMenuAction::ReloadAllPresets => self.reload_all_presets(),
             MainMenuAction::OpenPresetFolder => self.open_preset_folder(),
+            MainMenuAction::ImportOscLayoutFile => {
+                let result = self.import_osc_layout_file();
+                self.notify_user_on_error(result);
+            }
             MainMenuAction::SendFeedbackNow => self.session().borrow().send_all_feedback(),
             MainMenuAction::LogDebugInfo => self.log_debug_info(),
             MainMenuAction::EditPresetLinkFxId(scope, fx_id) => {
@@ -1013,8 +1061,9 @@ impl HeaderPanel {
         &self,
         text: &str,
     ) -> Result<(), Box<dyn Error>> {
-        let api_object = deserialize_api_object_from_lua(text)?;
-        let api_mappings = api_object
+        let res = deserialize_api_object_from_lua(text)?;
+        let api_mappings = res
+            .value
             .into_mappings()
             .ok_or("Can only paste a list of mappings into a mapping group.")?;
         let data_mappings = {
@@ -1024,6 +1073,12 @@ impl HeaderPanel {
             DataObject::try_from_api_mappings(api_mappings.value, &compartment_in_session)?
         };
         self.paste_replace_all_in_group(Envelope::new(api_mappings.version, data_mappings));
+        if !res.annotations.is_empty() {
+            notify_processing_result(
+                "Paste from Lua",
+                res.annotations.into_iter().map(|a| a.to_string()).collect(),
+            );
+        }
         Ok(())
     }
 
@@ -1212,6 +1267,48 @@ impl HeaderPanel {
             .set(value);
     }
 
+    fn edit_speech_voice(&self) {
+        let session = self.session();
+        let current_voice = session.borrow().speech_settings.get_ref().voice.clone();
+        let voice = match dialog_util::prompt_for("Voice (empty = default)", &current_voice) {
+            None => return,
+            Some(v) => v,
+        };
+        let mut session = session.borrow_mut();
+        let settings = SpeechSettings {
+            voice,
+            ..session.speech_settings.get_ref().clone()
+        };
+        session.speech_settings.set(settings);
+    }
+
+    fn edit_speech_rate(&self) {
+        let current_rate = self.session().borrow().speech_settings.get_ref().rate;
+        let rate: u32 = match dialog_util::prompt_for("Rate (%)", &current_rate.to_string())
+            .and_then(|text| text.parse().ok())
+        {
+            None => return,
+            Some(r) => r,
+        };
+        self.session()
+            .borrow_mut()
+            .speech_settings
+            .set_with(|prev| SpeechSettings {
+                rate: rate.clamp(MIN_SPEECH_RATE, MAX_SPEECH_RATE),
+                ..prev.clone()
+            });
+    }
+
+    fn set_speech_queue_policy(&self, queue_policy: SpeechQueuePolicy) {
+        self.session()
+            .borrow_mut()
+            .speech_settings
+            .set_with(|prev| SpeechSettings {
+                queue_policy,
+                ..prev.clone()
+            });
+    }
+
     fn toggle_reset_feedback_when_releasing_source(&self) {
         self.session()
             .borrow_mut()
@@ -2084,6 +2181,35 @@ impl HeaderPanel {
         Ok(())
     }
 
+    fn import_osc_layout_file(&self) -> Result<(), Box<dyn Error>> {
+        let path = match prompt_for_file_to_read("Import TouchOSC or Open Stage Control layout", "")
+        {
+            None => return Ok(()),
+            Some(p) => p,
+        };
+        let content = std::fs::read(&path)?;
+        let compartment = Compartment::Controller;
+        let res = {
+            let session = self.session();
+            let session = session.borrow();
+            let compartment_in_session = session.compartment_in_session(compartment);
+            deserialize_data_object_from_osc_layout(&content, &compartment_in_session)?
+        };
+        let envelope = match res.value {
+            DataObject::ControllerCompartment(envelope) => envelope,
+            _ => return Err("Layout didn't result in a controller compartment".into()),
+        };
+        self.import_compartment(compartment, None, envelope.value);
+        self.update_compartment(compartment);
+        if !res.annotations.is_empty() {
+            notify_processing_result(
+                "Import OSC layout",
+                res.annotations.into_iter().map(|a| a.to_string()).collect(),
+            );
+        }
+        Ok(())
+    }
+
     fn import_compartment(
         &self,
         compartment: Compartment,
@@ -2093,7 +2219,7 @@ impl HeaderPanel {
         if self.view.require_window().confirm(
             "ReaLearn",
             format!(
-                "Do you want to continue replacing the {} with the data in the clipboard?",
+                "Do you want to continue replacing the {} with the imported data?",
                 compartment
             ),
         ) {
@@ -2116,6 +2242,7 @@ impl HeaderPanel {
             ExportSession(SerializationFormat),
             ExportClipMatrix(SerializationFormat),
             ExportCompartment(SerializationFormat),
+            ExportControllerCompartmentAsCsi,
         }
         impl Default for MenuAction {
             fn default() -> Self {
@@ -2125,10 +2252,15 @@ impl HeaderPanel {
         let compartment = self.active_compartment();
         let pure_menu = {
             use swell_ui::menu_tree::*;
-            let entries = vec![
+            let mut entries = vec![
                 item("Export session as JSON", || {
                     MenuAction::ExportSession(SerializationFormat::JsonDataObject)
                 }),
+                item("Export session as Lua", || {
+                    MenuAction::ExportSession(SerializationFormat::LuaApiObject(
+                        ConversionStyle::Minimal,
+                    ))
+                }),
                 item("Export clip matrix as JSON", || {
                     MenuAction::ExportClipMatrix(SerializationFormat::JsonDataObject)
                 }),
@@ -2154,6 +2286,12 @@ impl HeaderPanel {
                     },
                 ),
             ];
+            if compartment == Compartment::Controller {
+                entries.push(item(
+                    "Export controller compartment as CSI surface file",
+                    || MenuAction::ExportControllerCompartmentAsCsi,
+                ));
+            }
             root_menu(entries)
         };
         let result = match self
@@ -2167,15 +2305,15 @@ impl HeaderPanel {
         // Execute action
         match result {
             MenuAction::None => {}
-            MenuAction::ExportSession(_) => {
+            MenuAction::ExportSession(format) => {
                 let plugin_parameters = self
                     .plugin_parameters
                     .upgrade()
                     .expect("plugin params gone");
                 let session_data = plugin_parameters.create_session_data();
                 let data_object = DataObject::Session(App::create_envelope(Box::new(session_data)));
-                let json = serialize_data_object_to_json(data_object).unwrap();
-                copy_text_to_clipboard(json);
+                let text = serialize_data_object(data_object, format)?;
+                copy_text_to_clipboard(text);
             }
             MenuAction::ExportClipMatrix(format) => {
                 let matrix = self
@@ -2203,6 +2341,22 @@ impl HeaderPanel {
                 let text = serialize_data_object(data_object, format)?;
                 copy_text_to_clipboard(text);
             }
+            MenuAction::ExportControllerCompartmentAsCsi => {
+                let session = self.session();
+                let session = session.borrow();
+                let model = session.extract_compartment_model(Compartment::Controller);
+                let data = CompartmentModelData::from_model(&model);
+                let envelope = App::create_envelope(Box::new(data));
+                let data_object = DataObject::ControllerCompartment(envelope);
+                let res = serialize_data_object_to_csi(data_object)?;
+                copy_text_to_clipboard(res.value);
+                if !res.annotations.is_empty() {
+                    notify_processing_result(
+                        "Export to CSI",
+                        res.annotations.into_iter().map(|a| a.to_string()).collect(),
+                    );
+                }
+            }
         };
         Ok(())
     }
@@ -2950,6 +3104,9 @@ enum MainMenuAction {
     ToggleResetFeedbackWhenReleasingSource,
     ToggleUpperFloorMembership,
     SetStayActiveWhenProjectInBackground(StayActiveWhenProjectInBackground),
+    EditSpeechVoice,
+    EditSpeechRate,
+    SetSpeechQueuePolicy(SpeechQueuePolicy),
     ToggleServer,
     ToggleUseInstancePresetLinksOnly,
     AddFirewallRule,
@@ -2959,6 +3116,7 @@ enum MainMenuAction {
     LinkToPreset(PresetLinkScope, FxId, String),
     ReloadAllPresets,
     OpenPresetFolder,
+    ImportOscLayoutFile,
     EditNewOscDevice,
     EditExistingOscDevice(OscDeviceId),
     RemoveOscDevice(OscDeviceId),
@@ -18,8 +18,14 @@ use crate::infrastructure::ui::lua_serializer;
 use crate::infrastructure::ui::util::open_in_browser;
 use mlua::{Lua, LuaSerdeExt, Value};
 use realearn_api::persistence;
-use realearn_api::persistence::{ApiObject, Envelope};
-use realearn_csi::{deserialize_csi_object_from_csi, AnnotatedResult, CsiObject};
+use realearn_api::persistence::{
+    find_unknown_fields, validate_api_object, ApiObject, Envelope, ValidationIssue,
+};
+use realearn_csi::{
+    deserialize_csi_object_from_csi, looks_like_csi_file, serialize_csi_object_to_csi,
+    AnnotatedResult, Annotator, CsiObject,
+};
+use realearn_osc_layout::{deserialize_osc_layout, looks_like_open_stage_control_session};
 use reaper_high::Reaper;
 use semver::Version;
 
@@ -74,6 +80,13 @@ impl DataObject {
         conversion_context: &impl ApiToDataConversionContext,
     ) -> Result<Self, Box<dyn Error>> {
         let data_object = match api_object {
+            ApiObject::Session(Envelope { value: s, version }) => {
+                // API sessions without version are considered to be written for the current
+                // ReaLearn version.
+                let data_version = version.clone().unwrap_or_else(|| App::version().clone());
+                let data_session = to_data::convert_session(*s, data_version)?;
+                DataObject::Session(Envelope::new(version, Box::new(data_session)))
+            }
             ApiObject::ClipMatrix(envelope) => DataObject::ClipMatrix(envelope),
             ApiObject::MainCompartment(Envelope { value: c, version }) => {
                 let data_compartment = to_data::convert_compartment(*c)?;
@@ -116,7 +129,10 @@ impl DataObject {
         conversion_style: ConversionStyle,
     ) -> Result<ApiObject, Box<dyn Error>> {
         let api_object = match self {
-            DataObject::Session(Envelope { .. }) => todo!("session API not yet implemented"),
+            DataObject::Session(Envelope { value: s, version }) => {
+                let api_session = from_data::convert_session(*s, conversion_style)?;
+                ApiObject::Session(Envelope::new(version, Box::new(api_session)))
+            }
             DataObject::ClipMatrix(envelope) => ApiObject::ClipMatrix(envelope),
             DataObject::MainCompartment(Envelope { value: c, version }) => {
                 let api_compartment = from_data::convert_compartment(*c, conversion_style)?;
@@ -167,57 +183,92 @@ impl DataObject {
     }
 }
 
-/// Attempts to deserialize a data object supporting both JSON and Lua.
+/// The format of text to be imported, detected by looking at its content.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ImportFormat {
+    Json,
+    Lua,
+    Csi,
+    OpenStageControl,
+}
+
+impl ImportFormat {
+    pub fn detect(text: &str) -> Self {
+        let trimmed = text.trim_start();
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            if looks_like_open_stage_control_session(text) {
+                Self::OpenStageControl
+            } else {
+                Self::Json
+            }
+        } else if looks_like_csi_file(text) {
+            Self::Csi
+        } else {
+            Self::Lua
+        }
+    }
+}
+
+/// Attempts to deserialize a data object supporting JSON, Lua and CSI.
+///
+/// The format is detected up-front, so errors refer to the detected format only.
 pub fn deserialize_data_object(
     text: &str,
     conversion_context: &impl ApiToDataConversionContext,
 ) -> Result<AnnotatedResult<UntaggedDataObject>, Box<dyn Error>> {
-    let json_err = match deserialize_untagged_data_object_from_json(text) {
-        Ok(o) => {
-            return Ok(AnnotatedResult::without_annotations(o));
-        }
-        Err(e) => e,
-    };
-    let lua_err = match deserialize_data_object_from_lua(text, conversion_context) {
-        Ok(o) => {
-            return Ok(AnnotatedResult::without_annotations(
-                UntaggedDataObject::Tagged(o),
-            ));
+    let format = ImportFormat::detect(text);
+    let res = match format {
+        ImportFormat::Json => deserialize_untagged_data_object_from_json(text),
+        ImportFormat::Lua => deserialize_data_object_from_lua(text, conversion_context)
+            .map(|r| r.map(UntaggedDataObject::Tagged)),
+        ImportFormat::Csi => deserialize_data_object_from_csi(text, conversion_context)
+            .map(|r| r.map(UntaggedDataObject::Tagged)),
+        ImportFormat::OpenStageControl => {
+            deserialize_data_object_from_osc_layout(text.as_bytes(), conversion_context)
+                .map(|r| r.map(UntaggedDataObject::Tagged))
         }
-        Err(e) => e,
     };
-    let csi_err = match deserialize_data_object_from_csi(text, conversion_context) {
-        Ok(r) => {
-            let untagged_data_object = UntaggedDataObject::Tagged(r.value);
-            let annotated_result = AnnotatedResult {
-                value: untagged_data_object,
-                annotations: r.annotations,
-            };
-            return Ok(annotated_result);
-        }
-        Err(e) => e,
-    };
-    let msg = format!(
-        "Clipboard content doesn't look like proper ReaLearn import data:\n\n\
-        Invalid JSON: \n\
-        {}\n\n\
-        Invalid Lua: \n\
-        {}\n\n\
-        Invalid CSI: \n\
-        {}",
-        json_err, lua_err, csi_err
-    );
-    Err(msg.into())
+    res.map_err(|e| {
+        let format_label = match format {
+            ImportFormat::Json => "JSON",
+            ImportFormat::Lua => "Lua",
+            ImportFormat::Csi => "CSI",
+            ImportFormat::OpenStageControl => "an Open Stage Control session",
+        };
+        format!(
+            "Clipboard content looks like {} but isn't proper ReaLearn import data:\n\n{}",
+            format_label, e
+        )
+        .into()
+    })
 }
 
 pub fn deserialize_data_object_from_json(text: &str) -> Result<DataObject, Box<dyn Error>> {
     Ok(serde_json::from_str(text)?)
 }
 
+/// Deserializes either a tagged data object or preset-like data.
+///
+/// Instead of letting serde try both variants (which would swallow the line and column of the
+/// actual error), we look at the presence of a `kind` property and deserialize accordingly.
+///
+/// Unknown fields are reported as annotations.
 pub fn deserialize_untagged_data_object_from_json(
     text: &str,
-) -> Result<UntaggedDataObject, Box<dyn Error>> {
-    Ok(serde_json::from_str(text)?)
+) -> Result<AnnotatedResult<UntaggedDataObject>, Box<dyn Error>> {
+    let value: serde_json::Value = serde_json::from_str(text)?;
+    let (untagged_data_object, warnings) = if value.get("kind").is_some() {
+        (
+            UntaggedDataObject::Tagged(serde_json::from_str(text)?),
+            find_unknown_fields::<DataObject>(&value),
+        )
+    } else {
+        (
+            UntaggedDataObject::PresetLike(serde_json::from_str(text)?),
+            find_unknown_fields::<CommonPresetData>(&value),
+        )
+    };
+    Ok(annotate_warnings(untagged_data_object, warnings, 0))
 }
 
 pub fn deserialize_data_object_from_csi(
@@ -233,13 +284,29 @@ pub fn deserialize_data_object_from_csi(
     Ok(res)
 }
 
+/// Converts a TouchOSC layout or Open Stage Control session into a controller compartment.
+pub fn deserialize_data_object_from_osc_layout(
+    content: &[u8],
+    conversion_context: &impl ApiToDataConversionContext,
+) -> Result<AnnotatedResult<DataObject>, Box<dyn Error>> {
+    let api_object_res = deserialize_osc_layout(content)?;
+    let res = AnnotatedResult {
+        value: DataObject::try_from_api_object(api_object_res.value, conversion_context)?,
+        annotations: api_object_res.annotations,
+    };
+    Ok(res)
+}
+
 pub fn deserialize_data_object_from_lua(
     text: &str,
     conversion_context: &impl ApiToDataConversionContext,
-) -> Result<DataObject, Box<dyn Error>> {
-    let api_object = deserialize_api_object_from_lua(text)?;
-    let data_object = DataObject::try_from_api_object(api_object, conversion_context)?;
-    Ok(data_object)
+) -> Result<AnnotatedResult<DataObject>, Box<dyn Error>> {
+    let api_object_res = deserialize_api_object_from_lua(text)?;
+    let res = AnnotatedResult {
+        value: DataObject::try_from_api_object(api_object_res.value, conversion_context)?,
+        annotations: api_object_res.annotations,
+    };
+    Ok(res)
 }
 
 pub fn serialize_data_object_to_json(object: DataObject) -> Result<String, Box<dyn Error>> {
@@ -289,11 +356,66 @@ pub fn serialize_data_object_to_lua(
     Ok(lua_serializer::to_string(&api_object)?)
 }
 
-pub fn deserialize_api_object_from_lua(text: &str) -> Result<ApiObject, Box<dyn Error>> {
+/// Only supports controller compartments.
+pub fn serialize_data_object_to_csi(
+    data_object: DataObject,
+) -> Result<AnnotatedResult<String>, Box<dyn Error>> {
+    let api_object = data_object.try_into_api_object(ConversionStyle::Minimal)?;
+    let csi_object_res = CsiObject::try_from_api_object(api_object)?;
+    let res = AnnotatedResult {
+        value: serialize_csi_object_to_csi(&csi_object_res.value)?,
+        annotations: csi_object_res.annotations,
+    };
+    Ok(res)
+}
+
+/// Executes the given Lua script and validates the returned table against the API schema.
+///
+/// Unknown fields are reported as annotations. If the table can't be deserialized, the error
+/// lists all schema violations together with their Lua table paths.
+pub fn deserialize_api_object_from_lua(
+    text: &str,
+) -> Result<AnnotatedResult<ApiObject>, Box<dyn Error>> {
     let lua = SafeLua::new()?;
     let lua = lua.start_execution_time_limit_countdown(Duration::from_millis(200))?;
     let value = execute_lua_import_script(&lua, text)?;
-    Ok(lua.as_ref().from_value(value)?)
+    let json_value: serde_json::Value = lua.as_ref().from_value(value.clone())?;
+    let issues = validate_api_object(&json_value);
+    match lua.as_ref().from_value::<ApiObject>(value) {
+        Ok(api_object) => {
+            // The validator deserializes from JSON, not from Lua. In the rare case that this
+            // makes a difference, the Lua deserialization has the final say.
+            let warnings = issues.into_iter().filter(|i| !i.is_error()).collect();
+            Ok(annotate_warnings(api_object, warnings, 1))
+        }
+        Err(e) => {
+            let errors: Vec<_> = issues
+                .iter()
+                .filter(|i| i.is_error())
+                .map(|i| format!("{}: {}", i.path_expression(1), i.message))
+                .collect();
+            if errors.is_empty() {
+                Err(e.into())
+            } else {
+                Err(errors.join("\n").into())
+            }
+        }
+    }
+}
+
+/// Turns validation warnings into annotations, using Lua-style indexes if `first_index` is 1.
+fn annotate_warnings<T>(
+    value: T,
+    warnings: Vec<ValidationIssue>,
+    first_index: usize,
+) -> AnnotatedResult<T> {
+    let mut annotator = Annotator::new();
+    for warning in warnings {
+        annotator.with_context(warning.path_expression(first_index), |a| {
+            a.warn(warning.message)
+        });
+    }
+    annotator.build_result(value)
 }
 
 fn execute_lua_import_script<'a>(
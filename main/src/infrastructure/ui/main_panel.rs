@@ -3,6 +3,7 @@ use crate::infrastructure::ui::{
     SharedIndependentPanelManager, SharedMainState,
 };
 
+use helgoboss_learn::AbsoluteValue;
 use lazycell::LazyCell;
 use reaper_high::{AvailablePanValue, ChangeEvent, Guid, OrCurrentProject, Reaper, Track, Volume};
 
@@ -18,13 +19,16 @@ use crate::domain::ui_util::format_tags_as_csv;
 use crate::domain::{
     Compartment, MappingId, MappingMatchedEvent, PanExt, ProjectionFeedbackValue,
     QualifiedMappingId, RealearnClipMatrix, SoundPlayer, TargetControlEvent,
-    TargetValueChangedEvent,
+    TargetValueChangedEvent, UpdatedSingleMappingOnStateEvent,
 };
 use crate::infrastructure::plugin::{App, RealearnPluginParameters};
+use crate::infrastructure::proto::{
+    QualifiedContinuousMappingUpdate, QualifiedOccasionalMappingUpdate,
+};
 use crate::infrastructure::server::grpc::{
-    ContinuousColumnUpdateBatch, ContinuousMatrixUpdateBatch, ContinuousSlotUpdateBatch,
-    OccasionalClipUpdateBatch, OccasionalMatrixUpdateBatch, OccasionalSlotUpdateBatch,
-    OccasionalTrackUpdateBatch,
+    ContinuousColumnUpdateBatch, ContinuousMappingUpdateBatch, ContinuousMatrixUpdateBatch,
+    ContinuousSlotUpdateBatch, OccasionalClipUpdateBatch, OccasionalMappingUpdateBatch,
+    OccasionalMatrixUpdateBatch, OccasionalSlotUpdateBatch, OccasionalTrackUpdateBatch,
 };
 use crate::infrastructure::server::http::{
     send_projection_feedback_to_subscribed_clients, send_updated_controller_routing,
@@ -486,10 +490,26 @@ impl SessionUi for Weak<MainPanel> {
         upgrade_panel(self).edit_mapping(compartment, mapping_id);
     }
 
-    fn target_value_changed(&self, event: TargetValueChangedEvent) {
+    fn target_value_changed(&self, session: &Session, event: TargetValueChangedEvent) {
+        let id = QualifiedMappingId::new(event.compartment, event.mapping_id);
+        send_continuous_mapping_updates(session, id, event.new_value);
         upgrade_panel(self).handle_changed_target_value(event);
     }
 
+    fn mapping_on_states_changed(&self, session: &Session) {
+        send_occasional_mapping_updates(session, || {
+            QualifiedOccasionalMappingUpdate::all_on_states(session)
+        });
+    }
+
+    fn mapping_on_state_changed(&self, session: &Session, event: UpdatedSingleMappingOnStateEvent) {
+        send_occasional_mapping_updates(session, || {
+            QualifiedOccasionalMappingUpdate::is_on_by_id(session, event.id, event.is_on)
+                .into_iter()
+                .collect()
+        });
+    }
+
     fn parameters_changed(&self, session: &Session) {
         upgrade_panel(self).handle_changed_parameters(session);
     }
@@ -507,7 +527,7 @@ impl SessionUi for Weak<MainPanel> {
     }
 
     fn send_projection_feedback(&self, session: &Session, value: ProjectionFeedbackValue) {
-        let _ = send_projection_feedback_to_subscribed_clients(session.id(), value);
+        let _ = send_projection_feedback_to_subscribed_clients(session, value);
     }
 
     fn clip_matrix_changed(
@@ -566,6 +586,42 @@ impl SessionUi for Weak<MainPanel> {
     }
 }
 
+fn send_occasional_mapping_updates(
+    session: &Session,
+    create_updates: impl FnOnce() -> Vec<QualifiedOccasionalMappingUpdate>,
+) {
+    let sender = App::get().occasional_mapping_update_sender();
+    if sender.receiver_count() == 0 {
+        return;
+    }
+    let updates = create_updates();
+    if !updates.is_empty() {
+        let batch_event = OccasionalMappingUpdateBatch {
+            session_id: session.id().to_owned(),
+            value: updates,
+        };
+        let _ = sender.send(batch_event);
+    }
+}
+
+fn send_continuous_mapping_updates(
+    session: &Session,
+    id: QualifiedMappingId,
+    new_value: AbsoluteValue,
+) {
+    let sender = App::get().continuous_mapping_update_sender();
+    if sender.receiver_count() == 0 {
+        return;
+    }
+    if let Some(update) = QualifiedContinuousMappingUpdate::target_value(session, id, new_value) {
+        let batch_event = ContinuousMappingUpdateBatch {
+            session_id: session.id().to_owned(),
+            value: vec![update],
+        };
+        let _ = sender.send(batch_event);
+    }
+}
+
 fn send_occasional_matrix_updates_caused_by_matrix(
     session: &Session,
     matrix: &RealearnClipMatrix,
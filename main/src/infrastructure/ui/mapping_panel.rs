@@ -27,25 +27,31 @@ use helgoboss_learn::{
     DEFAULT_OSC_ARG_VALUE_RANGE,
 };
 use realearn_api::persistence::{
-    Axis, BrowseTracksMode, FxToolAction, MidiScriptKind, MonitoringMode, MouseButton,
-    PotFilterItemKind, SeekBehavior, TrackToolAction,
+    AudioLevelInput, AudioLevelMeasurement, Axis, BrowseTracksMode, ChordMode, FxToolAction,
+    Gesture, MarkerManagementAction, MidiScriptKind, MonitoringMode, MouseButton, MpeDimension,
+    MpeNoteSelector, MpeZone, MusicalTimerOutput, MusicalTimerTrigger, PotFilterItemKind,
+    RouteEnvelopeKind, SeekBehavior, SpeechQueuePolicy, TimeRangeAction, TimeRangeKind,
+    TimeRangeQuantization, TrackEnvelopeKind, TrackToolAction, TransformationLanguage,
 };
 use swell_ui::{
     DialogUnits, Point, SharedView, SwellStringArg, View, ViewContext, WeakView, Window,
 };
 
 use crate::application::{
-    format_osc_feedback_args, get_bookmark_label_by_id, get_fx_label, get_fx_param_label,
-    get_non_present_bookmark_label, get_optional_fx_label, get_route_label,
-    parse_osc_feedback_args, Affected, AutomationModeOverrideType, BookmarkAnchorType, Change,
-    CompartmentProp, ConcreteFxInstruction, ConcreteTrackInstruction, MappingChangeContext,
-    MappingCommand, MappingModel, MappingProp, MappingSnapshotTypeForLoad,
-    MappingSnapshotTypeForTake, MidiSourceType, ModeCommand, ModeModel, ModeProp,
-    RealearnAutomationMode, RealearnTrackArea, ReaperSourceType, Session, SessionProp,
-    SharedMapping, SharedSession, SourceCategory, SourceCommand, SourceModel, SourceProp,
-    TargetCategory, TargetCommand, TargetModel, TargetModelWithContext, TargetProp, TargetUnit,
-    TrackRouteSelectorType, VirtualControlElementType, VirtualFxParameterType, VirtualFxType,
-    VirtualTrackType, WeakSession, KEY_UNDEFINED_LABEL,
+    format_audio_level_attack_release, format_chord_control_element_ids, format_chord_notes,
+    format_musical_timer_bars, format_osc_additional_arg_indexes, format_osc_feedback_args,
+    get_bookmark_label_by_id, get_fx_label, get_fx_param_label, get_non_present_bookmark_label,
+    get_optional_fx_label, get_route_label, parse_audio_level_attack_release,
+    parse_chord_control_element_ids, parse_chord_notes, parse_musical_timer_bars,
+    parse_osc_additional_arg_indexes, parse_osc_feedback_args, Affected,
+    AutomationModeOverrideType, BookmarkAnchorType, Change, CompartmentProp, ConcreteFxInstruction,
+    ConcreteTrackInstruction, EnvelopeType, MappingChangeContext, MappingCommand, MappingModel,
+    MappingProp, MappingSnapshotTypeForLoad, MappingSnapshotTypeForTake, MidiSourceType,
+    ModeCommand, ModeModel, ModeProp, RealearnAutomationMode, RealearnTrackArea, ReaperSourceType,
+    Session, SessionProp, SharedMapping, SharedSession, SourceCategory, SourceCommand, SourceModel,
+    SourceProp, TargetCategory, TargetCommand, TargetModel, TargetModelWithContext, TargetProp,
+    TargetUnit, TrackRouteSelectorType, VirtualControlElementType, VirtualFxParameterType,
+    VirtualFxType, VirtualItemType, VirtualTrackType, WeakSession, KEY_UNDEFINED_LABEL,
 };
 use crate::base::Global;
 use crate::base::{notification, when, Prop};
@@ -64,6 +70,7 @@ use crate::domain::{
     ExtendedProcessorContext, FeedbackResolution, FxDisplayType, QualifiedMappingId,
     RealearnTarget, SoloBehavior, TargetCharacter, TouchedTrackParameterType, TrackExclusivity,
     TrackRouteType, TransportAction, VirtualControlElement, VirtualControlElementId, VirtualFx,
+    XyAxis,
 };
 use crate::infrastructure::plugin::App;
 use crate::infrastructure::ui::bindings::root;
@@ -71,10 +78,12 @@ use crate::infrastructure::ui::util::{
     compartment_parameter_dropdown_contents, parse_tags_from_csv, symbols, MAPPING_PANEL_SCALING,
 };
 use crate::infrastructure::ui::{
-    AdvancedScriptEditorPanel, EelControlTransformationEngine, EelFeedbackTransformationEngine,
-    EelMidiScriptEngine, ItemProp, LuaMidiScriptEngine, MainPanel, MappingHeaderPanel,
-    MappingRowsPanel, OscFeedbackArgumentsEngine, RawMidiScriptEngine, ScriptEditorInput,
-    ScriptEngine, SimpleScriptEditorPanel, TextualFeedbackExpressionEngine, YamlEditorPanel,
+    dialog_util, AdvancedScriptEditorPanel, EelControlTransformationEngine,
+    EelFeedbackTransformationEngine, EelMidiControlScriptEngine, EelMidiScriptEngine, ItemProp,
+    LuaControlTransformationEngine, LuaFeedbackTransformationEngine, LuaMidiControlScriptEngine,
+    LuaMidiScriptEngine, MainPanel, MappingHeaderPanel, MappingRowsPanel,
+    OscFeedbackArgumentsEngine, RawMidiScriptEngine, ScriptEditorInput, ScriptEngine,
+    SimpleScriptEditorPanel, TextualFeedbackExpressionEngine, YamlEditorPanel,
     CONTROL_TRANSFORMATION_TEMPLATES,
 };
 
@@ -299,9 +308,48 @@ impl MappingPanel {
                                             P::Line => {
                                                 view.invalidate_source_line_5_combo_box();
                                             }
-                                            P::OscAddressPattern | P::TimerMillis => {
+                                            P::OscAddressPattern | P::TimerMillis | P::SpeechVoice => {
                                                 view.invalidate_source_line_3_edit_control(initiator);
                                             }
+                                            P::SpeechQueuePolicy => {
+                                                view.invalidate_source_line_3_combo_box_2();
+                                            }
+                                            P::SpeechRate => {
+                                                view.invalidate_source_line_5(initiator);
+                                            }
+                                            P::MusicalTimerTrigger => {
+                                                view.invalidate_source_controls();
+                                            }
+                                            P::MusicalTimerInterval => {
+                                                view.invalidate_source_line_4(initiator);
+                                            }
+                                            P::MusicalTimerBars => {
+                                                view.invalidate_source_line_7_edit_control(initiator);
+                                            }
+                                            P::MusicalTimerOnlyWhilePlaying => {
+                                                view.invalidate_source_check_box_2();
+                                            }
+                                            P::MusicalTimerOutput => {
+                                                view.invalidate_source_line_5_combo_box();
+                                                view.invalidate_mode_controls();
+                                                view.invalidate_help();
+                                            }
+                                            P::AudioLevelInput => {
+                                                view.invalidate_source_line_3_combo_box_1();
+                                                view.invalidate_source_line_4(initiator);
+                                            }
+                                            P::AudioLevelIndex => {
+                                                view.invalidate_source_line_4(initiator);
+                                            }
+                                            P::AudioLevelMeasurement => {
+                                                view.invalidate_source_line_5_combo_box();
+                                                view.invalidate_source_line_7(initiator);
+                                                view.invalidate_mode_controls();
+                                                view.invalidate_help();
+                                            }
+                                            P::AudioLevelAttackMillis | P::AudioLevelReleaseMillis | P::AudioLevelOnsetThresholdDb => {
+                                                view.invalidate_source_line_7_edit_control(initiator);
+                                            }
                                             P::RawMidiPattern  => {
                                                 view.invalidate_source_line_7_edit_control(initiator);
                                             }
@@ -314,9 +362,35 @@ impl MappingPanel {
                                             P::MidiScript | P::OscFeedbackArgs => {
                                                 view.invalidate_source_line_7_edit_control(initiator);
                                             }
-                                            P::OscArgValueRange => {
+                                            P::MidiControlScript => {}
+                                            P::MpeZone => {
+                                                view.invalidate_source_line_3_combo_box_1();
+                                            }
+                                            P::MpeMemberChannelCount => {
+                                                view.invalidate_source_line_3_combo_box_2();
+                                            }
+                                            P::MpeNoteSelector | P::MpeHeldNoteIndex => {
+                                                view.invalidate_source_line_4(initiator);
+                                            }
+                                            P::MpeDimension => {
+                                                view.invalidate_source_line_5_combo_box();
+                                                view.invalidate_mode_controls();
+                                                view.invalidate_help();
+                                            }
+                                            P::OscArgValueRange | P::ChordTimeWindowMillis => {
                                                 view.invalidate_source_line_5(initiator);
                                             }
+                                            P::ChordMode => {
+                                                view.invalidate_source_line_3_combo_box_2();
+                                            }
+                                            P::ChordNotes => {
+                                                view.invalidate_source_line_7_edit_control(initiator);
+                                            }
+                                            P::ChordControlElementIds => {
+                                                view.invalidate_source_line_7_edit_control(initiator);
+                                                view.invalidate_mode_controls();
+                                                view.invalidate_help();
+                                            }
                                             P::OscArgIsRelative => {
                                                 view.invalidate_source_controls();
                                                 view.invalidate_mode_controls();
@@ -372,11 +446,15 @@ impl MappingPanel {
                                             P::EncoderUsage => {
                                                 view.invalidate_mode_encoder_usage_combo_box();
                                             }
-                                            P::EelControlTransformation => {
+                                            P::ControlTransformationLanguage => {
+                                                view.invalidate_mode_control_transformation_label();
+                                            }
+                                            P::FeedbackTransformationLanguage => {}
+                                            P::ControlTransformation => {
                                                 view.invalidate_mode_controls_internal(initiator);
                                             }
-                                            P::EelFeedbackTransformation | P::TextualFeedbackExpression => {
-                                                view.invalidate_mode_eel_feedback_transformation_edit_control(initiator);
+                                            P::FeedbackTransformation | P::TextualFeedbackExpression => {
+                                                view.invalidate_mode_feedback_transformation_edit_control(initiator);
                                             }
                                             P::StepSizeInterval | P::StepFactorInterval => {
                                                 view.invalidate_mode_step_controls(initiator);
@@ -391,6 +469,10 @@ impl MappingPanel {
                                             P::GroupInteraction => {
                                                 view.invalidate_mode_group_interaction_combo_box();
                                             }
+                                            P::Gesture | P::GestureTapCount => {
+                                                view.invalidate_mode_gesture_controls(initiator);
+                                                view.invalidate_mode_control_visibilities();
+                                            }
                                             P::TargetValueSequence => {
                                                 view.invalidate_mode_target_value_sequence_edit_control(initiator);
                                             }
@@ -460,6 +542,15 @@ impl MappingPanel {
                                                 view.invalidate_target_controls(initiator);
                                                 view.invalidate_mode_controls();
                                             }
+                                            P::ItemType | P::ItemIndex | P::ItemExpression | P::TakeFxIndex | P::TakeFxParamIndex => {
+                                                view.invalidate_target_controls(initiator);
+                                                view.invalidate_mode_controls();
+                                            }
+                                            P::EnvelopeType | P::TrackEnvelopeKind | P::RouteEnvelopeKind => {
+                                                view.invalidate_window_title();
+                                                view.invalidate_target_controls(initiator);
+                                                view.invalidate_mode_controls();
+                                            }
                                             P::ParamType | P::ParamName | P::ParamExpression => {
                                                 view.invalidate_target_controls(initiator);
                                                 view.invalidate_mode_controls();
@@ -517,11 +608,39 @@ impl MappingPanel {
                                             P::MouseActionType => {
                                                 view.invalidate_target_controls(initiator);
                                             }
+                                            P::TimeRangeKind => {
+                                                view.invalidate_window_title();
+                                                view.invalidate_target_line_2(initiator);
+                                            }
+                                            P::TimeRangeAction => {
+                                                view.invalidate_window_title();
+                                                view.invalidate_target_line_2(initiator);
+                                                view.invalidate_target_line_3(None);
+                                                view.invalidate_target_value_controls();
+                                                view.invalidate_mode_controls();
+                                            }
+                                            P::TimeRangeQuantization => {
+                                                view.invalidate_target_line_3(initiator);
+                                                view.invalidate_target_value_controls();
+                                                view.invalidate_mode_controls();
+                                            }
+                                            P::MarkerManagementAction => {
+                                                view.invalidate_window_title();
+                                                view.invalidate_target_line_2(initiator);
+                                                view.invalidate_target_line_3(None);
+                                            }
+                                            P::MarkerName => {
+                                                view.invalidate_target_line_3(initiator);
+                                            }
+                                            P::TimeRangeRegionIndex | P::TimeRangeBarCount => {
+                                                view.invalidate_target_line_3(initiator);
+                                                view.invalidate_target_value_controls();
+                                            }
                                             P::PotFilterItemKind => {
                                                 view.invalidate_target_controls(initiator);
                                                 view.invalidate_mode_controls();
                                             }
-                                            P::Axis => {
+                                            P::Axis | P::ControlElementAxis => {
                                                 view.invalidate_target_line_3(initiator);
                                             }
                                             P::MouseButton => {
@@ -534,7 +653,10 @@ impl MappingPanel {
                                             P::GangBehavior => {
                                                 view.invalidate_target_check_boxes();
                                             }
-                                            P::EnableOnlyIfTrackSelected | P::ScrollMixer | P::MoveView => {
+                                            P::EnableOnlyIfTrackSelected
+                                            | P::ScrollMixer
+                                            | P::MoveView
+                                            | P::MoveEditCursor => {
                                                 view.invalidate_target_check_boxes();
                                             }
                                             P::WithTrack => {
@@ -705,10 +827,23 @@ impl MappingPanel {
             .mode_model
             .feedback_background_color()
             .cloned();
+        let current_transformation_language =
+            if mapping.borrow().mode_model.feedback_type().is_textual() {
+                // Text expressions are always EEL.
+                None
+            } else {
+                Some(
+                    mapping
+                        .borrow()
+                        .mode_model
+                        .feedback_transformation_language(),
+                )
+            };
         let result = show_feedback_popup_menu(
             self.view.require_window(),
             current_color,
             current_background_color,
+            current_transformation_language,
         )?;
         match result {
             FeedbackPopupMenuResult::EditMultiLine => {
@@ -723,6 +858,11 @@ impl MappingPanel {
                 };
                 self.change_mapping(MappingCommand::ChangeMode(cmd));
             }
+            FeedbackPopupMenuResult::ChangeTransformationLanguage(language) => {
+                self.change_mapping(MappingCommand::ChangeMode(
+                    ModeCommand::SetFeedbackTransformationLanguage(language),
+                ));
+            }
         }
         Ok(())
     }
@@ -871,56 +1011,143 @@ impl MappingPanel {
                 }
                 MidiSourceType::Script => {
                     let session = self.session.clone();
-                    self.edit_midi_source_script_internal(
-                        |m| m.source_model.midi_script().to_owned(),
-                        move |m, eel| {
+                    let direction = match show_midi_script_popup_menu(self.view.require_window()) {
+                        None => return,
+                        Some(d) => d,
+                    };
+                    match direction {
+                        MidiScriptDirection::Feedback => {
+                            self.edit_midi_source_script_internal(
+                                |m| m.source_model.midi_script().to_owned(),
+                                move |m, script| {
+                                    Session::change_mapping_from_ui_simple(
+                                        session.clone(),
+                                        m,
+                                        MappingCommand::ChangeSource(SourceCommand::SetMidiScript(
+                                            script,
+                                        )),
+                                        None,
+                                    );
+                                },
+                            );
+                        }
+                        MidiScriptDirection::Control => {
+                            self.edit_midi_source_control_script_internal(
+                                |m| m.source_model.midi_control_script().to_owned(),
+                                move |m, script| {
+                                    Session::change_mapping_from_ui_simple(
+                                        session.clone(),
+                                        m,
+                                        MappingCommand::ChangeSource(
+                                            SourceCommand::SetMidiControlScript(script),
+                                        ),
+                                        None,
+                                    );
+                                },
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            },
+            SourceCategory::Osc => match show_osc_source_popup_menu(self.view.require_window()) {
+                None => {}
+                Some(OscSourcePopupMenuResult::EditFeedbackArguments) => {
+                    let session = self.session.clone();
+                    self.edit_osc_feedback_arguments_internal(
+                        |m| format_osc_feedback_args(m.source_model.osc_feedback_args()),
+                        move |m, text| {
+                            let args = parse_osc_feedback_args(&text);
                             Session::change_mapping_from_ui_simple(
                                 session.clone(),
                                 m,
-                                MappingCommand::ChangeSource(SourceCommand::SetMidiScript(eel)),
+                                MappingCommand::ChangeSource(SourceCommand::SetOscFeedbackArgs(
+                                    args,
+                                )),
                                 None,
                             );
                         },
                     );
                 }
-                _ => {}
+                Some(OscSourcePopupMenuResult::EditAdditionalArguments) => {
+                    let mapping = self.mapping();
+                    let initial_value = format_osc_additional_arg_indexes(
+                        mapping.borrow().source_model.osc_additional_arg_indexes(),
+                    );
+                    let text = match dialog_util::prompt_for(
+                        "Additional argument numbers (e.g. 2 3)",
+                        &initial_value,
+                    ) {
+                        None => return,
+                        Some(t) => t,
+                    };
+                    let indexes = parse_osc_additional_arg_indexes(&text);
+                    Session::change_mapping_from_ui_simple(
+                        self.session.clone(),
+                        &mut mapping.borrow_mut(),
+                        MappingCommand::ChangeSource(SourceCommand::SetOscAdditionalArgIndexes(
+                            indexes,
+                        )),
+                        None,
+                    );
+                }
             },
-            SourceCategory::Osc => {
-                let session = self.session.clone();
-                self.edit_osc_feedback_arguments_internal(
-                    |m| format_osc_feedback_args(m.source_model.osc_feedback_args()),
-                    move |m, text| {
-                        let args = parse_osc_feedback_args(&text);
-                        Session::change_mapping_from_ui_simple(
-                            session.clone(),
-                            m,
-                            MappingCommand::ChangeSource(SourceCommand::SetOscFeedbackArgs(args)),
-                            None,
-                        );
-                    },
-                );
-            }
             _ => {}
         }
     }
 
+    fn control_transformation_button_pressed(&self) -> Result<(), &'static str> {
+        let mapping = self.displayed_mapping().ok_or("no mapping set")?;
+        let current_language = mapping
+            .borrow()
+            .mode_model
+            .control_transformation_language();
+        let result =
+            show_control_transformation_popup_menu(self.view.require_window(), current_language)?;
+        match result {
+            ControlTransformationPopupMenuResult::EditMultiLine => {
+                self.edit_control_transformation();
+            }
+            ControlTransformationPopupMenuResult::ChangeLanguage(language) => {
+                self.change_mapping(MappingCommand::ChangeMode(
+                    ModeCommand::SetControlTransformationLanguage(language),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn edit_control_transformation(&self) {
         let session = self.session.clone();
-        let engine = Box::new(EelControlTransformationEngine);
         let help_url = "https://github.com/helgoboss/realearn/blob/master/doc/user-guide.adoc#control-transformation";
-        let get_value = |m: &MappingModel| m.mode_model.eel_control_transformation().to_owned();
-        let set_value = move |m: &mut MappingModel, eel: String| {
+        let get_value = |m: &MappingModel| m.mode_model.control_transformation().to_owned();
+        let set_value = move |m: &mut MappingModel, script: String| {
             Session::change_mapping_from_ui_simple(
                 session.clone(),
                 m,
-                MappingCommand::ChangeMode(ModeCommand::SetEelControlTransformation(eel)),
+                MappingCommand::ChangeMode(ModeCommand::SetControlTransformation(script)),
                 None,
             );
         };
-        #[cfg(any(target_os = "macos", target_os = "windows"))]
-        self.edit_script_in_advanced_editor(engine, help_url, get_value, set_value);
-        #[cfg(target_os = "linux")]
-        self.edit_script_in_simple_editor(engine, help_url, get_value, set_value);
+        let language = self
+            .mapping()
+            .borrow()
+            .mode_model
+            .control_transformation_language();
+        match language {
+            TransformationLanguage::Eel => {
+                let engine = Box::new(EelControlTransformationEngine);
+                #[cfg(any(target_os = "macos", target_os = "windows"))]
+                self.edit_script_in_advanced_editor(engine, help_url, get_value, set_value);
+                #[cfg(target_os = "linux")]
+                self.edit_script_in_simple_editor(engine, help_url, get_value, set_value);
+            }
+            TransformationLanguage::Lua => {
+                // The templates of the advanced editor are EEL-only
+                let engine = Box::new(LuaControlTransformationEngine);
+                self.edit_script_in_simple_editor(engine, help_url, get_value, set_value);
+            }
+        }
     }
 
     fn edit_feedback_transformation_or_text_expression(&self) {
@@ -934,15 +1161,24 @@ impl MappingPanel {
 
     fn edit_feedback_transformation(&self) {
         let session = self.session.clone();
+        let engine: Box<dyn ScriptEngine> = match self
+            .mapping()
+            .borrow()
+            .mode_model
+            .feedback_transformation_language()
+        {
+            TransformationLanguage::Eel => Box::new(EelFeedbackTransformationEngine),
+            TransformationLanguage::Lua => Box::new(LuaFeedbackTransformationEngine),
+        };
         self.edit_script_in_simple_editor(
-            Box::new(EelFeedbackTransformationEngine),
+            engine,
             "https://github.com/helgoboss/realearn/blob/master/doc/user-guide.adoc#feedback-type",
-            |m| m.mode_model.eel_feedback_transformation().to_owned(),
-            move |m, eel| {
+            |m| m.mode_model.feedback_transformation().to_owned(),
+            move |m, script| {
                 Session::change_mapping_from_ui_simple(
                     session.clone(),
                     m,
-                    MappingCommand::ChangeMode(ModeCommand::SetEelFeedbackTransformation(eel)),
+                    MappingCommand::ChangeMode(ModeCommand::SetFeedbackTransformation(script)),
                     None,
                 );
             },
@@ -992,6 +1228,21 @@ impl MappingPanel {
         self.edit_script_in_simple_editor(engine, help_url, get_initial_value, apply);
     }
 
+    fn edit_midi_source_control_script_internal(
+        &self,
+        get_initial_value: impl Fn(&MappingModel) -> String,
+        apply: impl Fn(&mut MappingModel, String) + 'static,
+    ) {
+        let mapping = self.mapping();
+        let engine: Box<dyn ScriptEngine> = match mapping.borrow().source_model.midi_script_kind() {
+            MidiScriptKind::Eel => Box::new(EelMidiControlScriptEngine),
+            MidiScriptKind::Lua => Box::new(LuaMidiControlScriptEngine),
+        };
+        let help_url =
+            "https://github.com/helgoboss/realearn/blob/master/doc/user-guide.adoc#script-source";
+        self.edit_script_in_simple_editor(engine, help_url, get_initial_value, apply);
+    }
+
     fn edit_script_in_simple_editor(
         &self,
         engine: Box<dyn ScriptEngine>,
@@ -1604,6 +1855,14 @@ impl<'a> MutableMappingPanel<'a> {
                     SourceCommand::SetOscArgIsRelative(checked),
                 ));
             }
+            Reaper
+                if self.mapping.source_model.reaper_source_type()
+                    == ReaperSourceType::MusicalTimer =>
+            {
+                self.change_mapping(MappingCommand::ChangeSource(
+                    SourceCommand::SetMusicalTimerOnlyWhilePlaying(checked),
+                ));
+            }
             Reaper | Virtual | Never | Keyboard => {}
         };
     }
@@ -1621,6 +1880,13 @@ impl<'a> MutableMappingPanel<'a> {
                         SourceCommand::SetMidiScriptKind(kind),
                     ));
                 }
+                MidiSourceType::Mpe => {
+                    let i = b.selected_combo_box_item_index();
+                    let zone = i.try_into().expect("invalid MPE zone");
+                    self.change_mapping(MappingCommand::ChangeSource(SourceCommand::SetMpeZone(
+                        zone,
+                    )));
+                }
                 t if t.supports_channel() => {
                     let value = match b.selected_combo_box_item_data() {
                         -1 => None,
@@ -1641,6 +1907,20 @@ impl<'a> MutableMappingPanel<'a> {
                         ),
                     ));
                 }
+                ReaperSourceType::MusicalTimer => {
+                    let i = b.selected_combo_box_item_index();
+                    let trigger = i.try_into().expect("invalid musical timer trigger");
+                    self.change_mapping(MappingCommand::ChangeSource(
+                        SourceCommand::SetMusicalTimerTrigger(trigger),
+                    ));
+                }
+                ReaperSourceType::AudioLevel => {
+                    let i = b.selected_combo_box_item_index();
+                    let input = i.try_into().expect("invalid audio level input");
+                    self.change_mapping(MappingCommand::ChangeSource(
+                        SourceCommand::SetAudioLevelInput(input),
+                    ));
+                }
                 _ => b.hide(),
             },
             _ => {}
@@ -1660,6 +1940,13 @@ impl<'a> MutableMappingPanel<'a> {
                     index,
                 )));
             }
+            Midi if self.mapping.source_model.midi_source_type() == MidiSourceType::Mpe => {
+                let i = combo.selected_combo_box_item_index();
+                let selector = i.try_into().expect("invalid MPE note selector");
+                self.change_mapping(MappingCommand::ChangeSource(
+                    SourceCommand::SetMpeNoteSelector(selector),
+                ));
+            }
             _ => {}
         }
     }
@@ -1700,6 +1987,12 @@ impl<'a> MutableMappingPanel<'a> {
                             SourceCommand::SetMidiMessageNumber(value),
                         ));
                     }
+                    Mpe => {
+                        let index = b.selected_combo_box_item_index() as u8;
+                        self.change_mapping(MappingCommand::ChangeSource(
+                            SourceCommand::SetMpeHeldNoteIndex(index),
+                        ));
+                    }
                     _ => {}
                 }
             }
@@ -1739,9 +2032,36 @@ impl<'a> MutableMappingPanel<'a> {
                             SourceCommand::SetCustomCharacter(character),
                         ));
                     }
+                    Mpe => {
+                        let i = b.selected_combo_box_item_index();
+                        let dimension = i.try_into().expect("invalid MPE dimension");
+                        self.change_mapping(MappingCommand::ChangeSource(
+                            SourceCommand::SetMpeDimension(dimension),
+                        ));
+                    }
                     _ => {}
                 }
             }
+            Reaper
+                if self.mapping.source_model.reaper_source_type()
+                    == ReaperSourceType::MusicalTimer =>
+            {
+                let i = b.selected_combo_box_item_index();
+                let output = i.try_into().expect("invalid musical timer output");
+                self.change_mapping(MappingCommand::ChangeSource(
+                    SourceCommand::SetMusicalTimerOutput(output),
+                ));
+            }
+            Reaper
+                if self.mapping.source_model.reaper_source_type()
+                    == ReaperSourceType::AudioLevel =>
+            {
+                let i = b.selected_combo_box_item_index();
+                let measurement = i.try_into().expect("invalid audio level measurement");
+                self.change_mapping(MappingCommand::ChangeSource(
+                    SourceCommand::SetAudioLevelMeasurement(measurement),
+                ));
+            }
             _ => {}
         }
     }
@@ -1808,12 +2128,48 @@ impl<'a> MutableMappingPanel<'a> {
                         SourceCommand::SetDisplayType(display_type),
                     ));
                 }
+                MidiSourceType::Mpe => {
+                    let count = b.selected_combo_box_item_index() as u8 + 1;
+                    self.change_mapping(MappingCommand::ChangeSource(
+                        SourceCommand::SetMpeMemberChannelCount(count),
+                    ));
+                }
+                MidiSourceType::Chord => {
+                    self.change_chord_mode_from_combo_box(b);
+                }
+                _ => {}
+            },
+            Virtual => {
+                self.change_chord_mode_from_combo_box(b);
+            }
+            Reaper => match self.mapping.source_model.reaper_source_type() {
+                ReaperSourceType::Speech => {
+                    let policy = match b.selected_combo_box_item_data() {
+                        -1 => None,
+                        i => Some(
+                            (i as usize)
+                                .try_into()
+                                .expect("invalid speech queue policy"),
+                        ),
+                    };
+                    self.change_mapping(MappingCommand::ChangeSource(
+                        SourceCommand::SetSpeechQueuePolicy(policy),
+                    ));
+                }
                 _ => {}
             },
             _ => {}
         }
     }
 
+    fn change_chord_mode_from_combo_box(&mut self, b: Window) {
+        let i = b.selected_combo_box_item_index();
+        let mode = i.try_into().expect("invalid chord mode");
+        self.change_mapping(MappingCommand::ChangeSource(SourceCommand::SetChordMode(
+            mode,
+        )));
+    }
+
     fn handle_source_line_4_edit_control_change(&mut self) {
         let edit_control_id = root::ID_SOURCE_NUMBER_EDIT_CONTROL;
         let c = self.view.require_control(edit_control_id);
@@ -1836,6 +2192,30 @@ impl<'a> MutableMappingPanel<'a> {
                     Some(edit_control_id),
                 );
             }
+            Reaper
+                if self.mapping.source_model.reaper_source_type()
+                    == ReaperSourceType::MusicalTimer =>
+            {
+                if let Ok(interval) = text.trim().parse() {
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeSource(SourceCommand::SetMusicalTimerInterval(
+                            interval,
+                        )),
+                        Some(edit_control_id),
+                    );
+                }
+            }
+            Reaper
+                if self.mapping.source_model.reaper_source_type()
+                    == ReaperSourceType::AudioLevel =>
+            {
+                if let Ok(index) = text.trim().parse() {
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeSource(SourceCommand::SetAudioLevelIndex(index)),
+                        Some(edit_control_id),
+                    );
+                }
+            }
             Reaper | Never | Keyboard | Osc => {}
         };
     }
@@ -1854,6 +2234,28 @@ impl<'a> MutableMappingPanel<'a> {
                     Some(edit_control_id),
                 );
             }
+            Midi | Virtual => {
+                let millis = text.trim().parse().unwrap_or_default();
+                self.change_mapping_with_initiator(
+                    MappingCommand::ChangeSource(SourceCommand::SetChordTimeWindowMillis(millis)),
+                    Some(edit_control_id),
+                );
+            }
+            Reaper
+                if self.mapping.source_model.reaper_source_type() == ReaperSourceType::Speech =>
+            {
+                let rate = match text.trim() {
+                    "" => None,
+                    t => match t.parse() {
+                        Ok(r) => Some(r),
+                        Err(_) => return,
+                    },
+                };
+                self.change_mapping_with_initiator(
+                    MappingCommand::ChangeSource(SourceCommand::SetSpeechRate(rate)),
+                    Some(edit_control_id),
+                );
+            }
             _ => {}
         };
     }
@@ -1879,6 +2281,12 @@ impl<'a> MutableMappingPanel<'a> {
                             Some(edit_control_id),
                         )
                     }
+                    ReaperSourceType::Speech => self.change_mapping_with_initiator(
+                        MappingCommand::ChangeSource(SourceCommand::SetSpeechVoice(
+                            Some(value).filter(|v| !v.is_empty()),
+                        )),
+                        Some(edit_control_id),
+                    ),
                     _ => {}
                 },
                 Midi | Virtual | Never | Keyboard => {}
@@ -1906,8 +2314,22 @@ impl<'a> MutableMappingPanel<'a> {
                         Some(edit_control_id),
                     );
                 }
+                MidiSourceType::Chord => {
+                    let notes = parse_chord_notes(&value);
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeSource(SourceCommand::SetChordNotes(notes)),
+                        Some(edit_control_id),
+                    );
+                }
                 _ => {}
             },
+            Virtual => {
+                let ids = parse_chord_control_element_ids(&value);
+                self.change_mapping_with_initiator(
+                    MappingCommand::ChangeSource(SourceCommand::SetChordControlElementIds(ids)),
+                    Some(edit_control_id),
+                );
+            }
             Osc => {
                 let args = parse_osc_feedback_args(&value);
                 self.change_mapping_with_initiator(
@@ -1915,6 +2337,50 @@ impl<'a> MutableMappingPanel<'a> {
                     Some(edit_control_id),
                 )
             }
+            Reaper
+                if self.mapping.source_model.reaper_source_type()
+                    == ReaperSourceType::MusicalTimer =>
+            {
+                let bars = parse_musical_timer_bars(&value);
+                self.change_mapping_with_initiator(
+                    MappingCommand::ChangeSource(SourceCommand::SetMusicalTimerBars(bars)),
+                    Some(edit_control_id),
+                )
+            }
+            Reaper
+                if self.mapping.source_model.reaper_source_type()
+                    == ReaperSourceType::AudioLevel =>
+            {
+                match self.mapping.source_model.audio_level_settings().measurement {
+                    AudioLevelMeasurement::Envelope => {
+                        if let Some((attack, release)) = parse_audio_level_attack_release(&value) {
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeSource(
+                                    SourceCommand::SetAudioLevelAttackMillis(attack),
+                                ),
+                                Some(edit_control_id),
+                            );
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeSource(
+                                    SourceCommand::SetAudioLevelReleaseMillis(release),
+                                ),
+                                Some(edit_control_id),
+                            );
+                        }
+                    }
+                    AudioLevelMeasurement::Onset => {
+                        if let Ok(db) = value.trim().parse() {
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeSource(
+                                    SourceCommand::SetAudioLevelOnsetThresholdDb(db),
+                                ),
+                                Some(edit_control_id),
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
             _ => {}
         }
     }
@@ -1999,6 +2465,31 @@ impl<'a> MutableMappingPanel<'a> {
         ));
     }
 
+    fn update_mode_gesture(&mut self) {
+        let gesture = self
+            .view
+            .require_control(root::ID_MODE_GESTURE_COMBO_BOX)
+            .selected_combo_box_item_index()
+            .try_into()
+            .expect("invalid gesture");
+        self.change_mapping(MappingCommand::ChangeMode(ModeCommand::SetGesture(gesture)));
+    }
+
+    fn update_mode_gesture_tap_count(&mut self) {
+        let control_id = root::ID_MODE_GESTURE_TAP_COUNT_EDIT_CONTROL;
+        let tap_count = match self.view.require_control(control_id).text() {
+            Ok(text) => match text.parse() {
+                Ok(v) => v,
+                Err(_) => return,
+            },
+            Err(_) => return,
+        };
+        self.change_mapping_with_initiator(
+            MappingCommand::ChangeMode(ModeCommand::SetGestureTapCount(tap_count)),
+            Some(control_id),
+        );
+    }
+
     fn update_takeover_mode(&mut self) {
         self.update_mode_hint(ModeParameter::TakeoverMode);
         let mode = self
@@ -2259,7 +2750,7 @@ impl<'a> MutableMappingPanel<'a> {
         );
     }
 
-    fn update_mode_eel_control_transformation(&mut self) {
+    fn update_mode_control_transformation(&mut self) {
         self.update_mode_hint(ModeParameter::ControlTransformation);
         let control_id = root::ID_MODE_EEL_CONTROL_TRANSFORMATION_EDIT_CONTROL;
         let value = self
@@ -2268,7 +2759,7 @@ impl<'a> MutableMappingPanel<'a> {
             .text()
             .unwrap_or_else(|_| "".to_string());
         self.change_mapping_with_initiator(
-            MappingCommand::ChangeMode(ModeCommand::SetEelControlTransformation(value)),
+            MappingCommand::ChangeMode(ModeCommand::SetControlTransformation(value)),
             Some(control_id),
         );
     }
@@ -2289,7 +2780,7 @@ impl<'a> MutableMappingPanel<'a> {
         let cmd = if self.mapping.mode_model.feedback_type().is_textual() {
             ModeCommand::SetTextualFeedbackExpression(value)
         } else {
-            ModeCommand::SetEelFeedbackTransformation(value)
+            ModeCommand::SetFeedbackTransformation(value)
         };
         self.change_mapping_with_initiator(MappingCommand::ChangeMode(cmd), Some(control_id));
     }
@@ -2419,6 +2910,11 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetBookmarkType(bookmark_type),
                     ));
                 }
+                ReaperTargetType::BrowseItems => {
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetScrollArrangeView(is_checked),
+                    ));
+                }
                 _ if self.mapping.target_model.supports_fx_chain() => {
                     self.change_mapping(MappingCommand::ChangeTarget(
                         TargetCommand::SetFxIsInputFx(is_checked),
@@ -2457,6 +2953,11 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetScrollMixer(is_checked),
                     ));
                 }
+                ReaperTargetType::BrowseItems => {
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetMoveEditCursor(is_checked),
+                    ));
+                }
                 ReaperTargetType::Seek => {
                     self.change_mapping(MappingCommand::ChangeTarget(TargetCommand::SetMoveView(
                         is_checked,
@@ -2657,6 +3158,13 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetMappingSnapshotTypeForLoad(snapshot_type),
                     ));
                 }
+                ReaperTargetType::TimeRange => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid time range kind");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetTimeRangeKind(v),
+                    ));
+                }
                 ReaperTargetType::TakeMappingSnapshot => {
                     let snapshot_type = combo
                         .selected_combo_box_item_index()
@@ -2696,7 +3204,14 @@ impl<'a> MutableMappingPanel<'a> {
             .require_control(root::ID_TARGET_LINE_3_COMBO_BOX_1);
         match self.target_category() {
             TargetCategory::Reaper => match self.reaper_target_type() {
-                t if t.supports_fx() => {
+                ReaperTargetType::TimeRange => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid time range quantization");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetTimeRangeQuantization(v),
+                    ));
+                }
+                _ if self.mapping.target_model.supports_fx() => {
                     let fx_type = combo
                         .selected_combo_box_item_index()
                         .try_into()
@@ -2709,13 +3224,20 @@ impl<'a> MutableMappingPanel<'a> {
                         )
                     });
                 }
-                t if t.supports_send() => {
+                _ if self.mapping.target_model.supports_route() => {
                     let i = combo.selected_combo_box_item_index();
                     let v = i.try_into().expect("invalid route type");
                     self.change_mapping(MappingCommand::ChangeTarget(TargetCommand::SetRouteType(
                         v,
                     )));
                 }
+                t if t.supports_item() => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid item type");
+                    self.change_mapping(MappingCommand::ChangeTarget(TargetCommand::SetItemType(
+                        v,
+                    )));
+                }
                 _ => {}
             },
             TargetCategory::Virtual => {}
@@ -2761,7 +3283,7 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetFxToolAction(action),
                     ));
                 }
-                t if t.supports_fx_parameter() => {
+                _ if self.mapping.target_model.supports_fx_parameter() => {
                     let param_type = combo
                         .selected_combo_box_item_index()
                         .try_into()
@@ -2779,7 +3301,7 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetExclusivity(exclusivity),
                     ));
                 }
-                t if t.supports_send() => {
+                _ if self.mapping.target_model.supports_route() => {
                     let selector_type = combo
                         .selected_combo_box_item_index()
                         .try_into()
@@ -2826,6 +3348,20 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetTransportAction(v),
                     ));
                 }
+                ReaperTargetType::MarkerManagement => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid marker management action");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetMarkerManagementAction(v),
+                    ));
+                }
+                ReaperTargetType::TimeRange => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid time range action");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetTimeRangeAction(v),
+                    ));
+                }
                 ReaperTargetType::AnyOn => {
                     let i = combo.selected_combo_box_item_index();
                     let v = i.try_into().expect("invalid any-on parameter");
@@ -2907,7 +3443,7 @@ impl<'a> MutableMappingPanel<'a> {
         let combo = self.view.require_control(combo_id);
         match self.target_category() {
             TargetCategory::Reaper => match self.reaper_target_type() {
-                t if t.supports_fx() => {
+                _ if self.mapping.target_model.supports_fx() => {
                     if let Ok(track) = self.target_with_context().first_effective_track() {
                         let chain = if self.mapping.target_model.fx_is_input_fx() {
                             track.input_fx_chain()
@@ -2991,7 +3527,16 @@ impl<'a> MutableMappingPanel<'a> {
                 }
                 _ => {}
             },
-            TargetCategory::Virtual => {}
+            TargetCategory::Virtual => {
+                if self.mapping.target_model.control_element_type() == VirtualControlElementType::Xy
+                {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid XY axis");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetControlElementAxis(v),
+                    ));
+                }
+            }
         }
     }
 
@@ -3014,7 +3559,7 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetMouseButton(v),
                     ));
                 }
-                t if t.supports_fx_parameter() => {
+                _ if self.mapping.target_model.supports_fx_parameter() => {
                     let fx = get_relevant_target_fx(self.mapping, self.session);
                     if let Some(fx) = fx {
                         let i = combo.selected_combo_box_item_index();
@@ -3050,7 +3595,7 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetFxDisplayType(v),
                     ));
                 }
-                t if t.supports_send() => {
+                _ if self.mapping.target_model.supports_route() => {
                     if let Ok(track) = self.target_with_context().first_effective_track() {
                         let i = combo.selected_combo_box_item_index();
                         let route_type = self.mapping.target_model.route_type();
@@ -3164,6 +3709,31 @@ impl<'a> MutableMappingPanel<'a> {
         let control = self.view.require_control(edit_control_id);
         match self.target_category() {
             TargetCategory::Reaper => match self.reaper_target_type() {
+                ReaperTargetType::MarkerManagement => {
+                    let name = control.text().unwrap_or_default();
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeTarget(TargetCommand::SetMarkerName(name)),
+                        Some(edit_control_id),
+                    );
+                }
+                ReaperTargetType::TimeRange => {
+                    let value: u32 = control
+                        .text()
+                        .unwrap_or_default()
+                        .parse()
+                        .unwrap_or_default();
+                    let cmd = match self.target.time_range_action() {
+                        TimeRangeAction::SetToRegion => {
+                            TargetCommand::SetTimeRangeRegionIndex(value.saturating_sub(1))
+                        }
+                        TimeRangeAction::SetToBars => TargetCommand::SetTimeRangeBarCount(value),
+                        TimeRangeAction::MoveStart | TimeRangeAction::MoveEnd => return,
+                    };
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeTarget(cmd),
+                        Some(edit_control_id),
+                    );
+                }
                 ReaperTargetType::SendMidi => {
                     let text = control.text().unwrap_or_default();
                     self.change_mapping_with_initiator(
@@ -3190,27 +3760,48 @@ impl<'a> MutableMappingPanel<'a> {
                         Some(edit_control_id),
                     );
                 }
-                t if t.supports_fx() => match self.mapping.target_model.fx_type() {
-                    VirtualFxType::Dynamic => {
+                _ if self.mapping.target_model.supports_fx() => {
+                    match self.mapping.target_model.fx_type() {
+                        VirtualFxType::Dynamic => {
+                            let expression = control.text().unwrap_or_default();
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeTarget(TargetCommand::SetFxExpression(
+                                    expression,
+                                )),
+                                Some(edit_control_id),
+                            );
+                        }
+                        VirtualFxType::ByName | VirtualFxType::AllByName => {
+                            let name = control.text().unwrap_or_default();
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeTarget(TargetCommand::SetFxName(name)),
+                                Some(edit_control_id),
+                            );
+                        }
+                        VirtualFxType::ByIndex => {
+                            let index = parse_position_as_index(control);
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeTarget(TargetCommand::SetFxIndex(index)),
+                                Some(edit_control_id),
+                            );
+                        }
+                        _ => {}
+                    }
+                }
+                t if t.supports_item() => match self.mapping.target_model.item_type() {
+                    VirtualItemType::Dynamic => {
                         let expression = control.text().unwrap_or_default();
                         self.change_mapping_with_initiator(
-                            MappingCommand::ChangeTarget(TargetCommand::SetFxExpression(
+                            MappingCommand::ChangeTarget(TargetCommand::SetItemExpression(
                                 expression,
                             )),
                             Some(edit_control_id),
                         );
                     }
-                    VirtualFxType::ByName | VirtualFxType::AllByName => {
-                        let name = control.text().unwrap_or_default();
-                        self.change_mapping_with_initiator(
-                            MappingCommand::ChangeTarget(TargetCommand::SetFxName(name)),
-                            Some(edit_control_id),
-                        );
-                    }
-                    VirtualFxType::ByIndex => {
+                    VirtualItemType::ByIndex => {
                         let index = parse_position_as_index(control);
                         self.change_mapping_with_initiator(
-                            MappingCommand::ChangeTarget(TargetCommand::SetFxIndex(index)),
+                            MappingCommand::ChangeTarget(TargetCommand::SetItemIndex(index)),
                             Some(edit_control_id),
                         );
                     }
@@ -3227,58 +3818,69 @@ impl<'a> MutableMappingPanel<'a> {
         let control = self.view.require_control(edit_control_id);
         match self.target_category() {
             TargetCategory::Reaper => match self.reaper_target_type() {
-                t if t.supports_fx_parameter() => match self.mapping.target_model.param_type() {
-                    VirtualFxParameterType::Dynamic => {
-                        let expression = control.text().unwrap_or_default();
-                        self.change_mapping_with_initiator(
-                            MappingCommand::ChangeTarget(TargetCommand::SetParamExpression(
-                                expression,
-                            )),
-                            Some(edit_control_id),
-                        );
-                    }
-                    VirtualFxParameterType::ByName => {
-                        let name = control.text().unwrap_or_default();
-                        self.change_mapping_with_initiator(
-                            MappingCommand::ChangeTarget(TargetCommand::SetParamName(name)),
-                            Some(edit_control_id),
-                        );
-                    }
-                    VirtualFxParameterType::ByIndex => {
-                        let index = parse_position_as_index(control);
-                        self.change_mapping_with_initiator(
-                            MappingCommand::ChangeTarget(TargetCommand::SetParamIndex(index)),
-                            Some(edit_control_id),
-                        );
-                    }
-                    VirtualFxParameterType::ById => {}
-                },
-                t if t.supports_send() => match self.mapping.target_model.route_selector_type() {
-                    TrackRouteSelectorType::Dynamic => {
-                        let expression = control.text().unwrap_or_default();
-                        self.change_mapping_with_initiator(
-                            MappingCommand::ChangeTarget(TargetCommand::SetRouteExpression(
-                                expression,
-                            )),
-                            Some(edit_control_id),
-                        );
-                    }
-                    TrackRouteSelectorType::ByName => {
-                        let name = control.text().unwrap_or_default();
-                        self.change_mapping_with_initiator(
-                            MappingCommand::ChangeTarget(TargetCommand::SetRouteName(name)),
-                            Some(edit_control_id),
-                        );
+                _ if self.mapping.target_model.supports_fx_parameter() => {
+                    match self.mapping.target_model.param_type() {
+                        VirtualFxParameterType::Dynamic => {
+                            let expression = control.text().unwrap_or_default();
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeTarget(TargetCommand::SetParamExpression(
+                                    expression,
+                                )),
+                                Some(edit_control_id),
+                            );
+                        }
+                        VirtualFxParameterType::ByName => {
+                            let name = control.text().unwrap_or_default();
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeTarget(TargetCommand::SetParamName(name)),
+                                Some(edit_control_id),
+                            );
+                        }
+                        VirtualFxParameterType::ByIndex => {
+                            let index = parse_position_as_index(control);
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeTarget(TargetCommand::SetParamIndex(index)),
+                                Some(edit_control_id),
+                            );
+                        }
+                        VirtualFxParameterType::ById => {}
                     }
-                    TrackRouteSelectorType::ByIndex => {
-                        let index = parse_position_as_index(control);
-                        self.change_mapping_with_initiator(
-                            MappingCommand::ChangeTarget(TargetCommand::SetRouteIndex(index)),
-                            Some(edit_control_id),
-                        );
+                }
+                _ if self.mapping.target_model.supports_route() => {
+                    match self.mapping.target_model.route_selector_type() {
+                        TrackRouteSelectorType::Dynamic => {
+                            let expression = control.text().unwrap_or_default();
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeTarget(TargetCommand::SetRouteExpression(
+                                    expression,
+                                )),
+                                Some(edit_control_id),
+                            );
+                        }
+                        TrackRouteSelectorType::ByName => {
+                            let name = control.text().unwrap_or_default();
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeTarget(TargetCommand::SetRouteName(name)),
+                                Some(edit_control_id),
+                            );
+                        }
+                        TrackRouteSelectorType::ByIndex => {
+                            let index = parse_position_as_index(control);
+                            self.change_mapping_with_initiator(
+                                MappingCommand::ChangeTarget(TargetCommand::SetRouteIndex(index)),
+                                Some(edit_control_id),
+                            );
+                        }
+                        _ => {}
                     }
-                    _ => {}
-                },
+                }
+                ReaperTargetType::TakeFxParameter => {
+                    let index = parse_position_as_index(control);
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeTarget(TargetCommand::SetTakeFxIndex(index)),
+                        Some(edit_control_id),
+                    );
+                }
                 t if t.supports_tags() => {
                     let text = control.text().unwrap_or_default();
                     let v = parse_tags_from_csv(&text);
@@ -3293,6 +3895,55 @@ impl<'a> MutableMappingPanel<'a> {
         }
     }
 
+    fn handle_target_line_5_combo_box_1_change(&mut self) {
+        let combo = self
+            .view
+            .require_control(root::ID_TARGET_LINE_5_COMBO_BOX_1);
+        match self.target_category() {
+            TargetCategory::Reaper => match self.reaper_target_type() {
+                t if t.supports_envelope() => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid envelope type");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetEnvelopeType(v),
+                    ));
+                }
+                _ => {}
+            },
+            TargetCategory::Virtual => {}
+        }
+    }
+
+    fn handle_target_line_5_combo_box_2_change(&mut self) {
+        let combo = self
+            .view
+            .require_control(root::ID_TARGET_LINE_5_COMBO_BOX_2);
+        match self.target_category() {
+            TargetCategory::Reaper => match self.reaper_target_type() {
+                t if t.supports_envelope() => {
+                    let i = combo.selected_combo_box_item_index();
+                    match self.mapping.target_model.envelope_type() {
+                        EnvelopeType::Track => {
+                            let v = i.try_into().expect("invalid track envelope kind");
+                            self.change_mapping(MappingCommand::ChangeTarget(
+                                TargetCommand::SetTrackEnvelopeKind(v),
+                            ));
+                        }
+                        EnvelopeType::Route => {
+                            let v = i.try_into().expect("invalid route envelope kind");
+                            self.change_mapping(MappingCommand::ChangeTarget(
+                                TargetCommand::SetRouteEnvelopeKind(v),
+                            ));
+                        }
+                        EnvelopeType::FxParameter => {}
+                    }
+                }
+                _ => {}
+            },
+            TargetCategory::Virtual => {}
+        }
+    }
+
     #[allow(clippy::single_match)]
     fn handle_target_line_5_edit_control_change(&mut self) {
         let edit_control_id = root::ID_TARGET_LINE_5_EDIT_CONTROL;
@@ -3307,6 +3958,13 @@ impl<'a> MutableMappingPanel<'a> {
                         Some(edit_control_id),
                     );
                 }
+                ReaperTargetType::TakeFxParameter => {
+                    let index = parse_position_as_index(control);
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeTarget(TargetCommand::SetTakeFxParamIndex(index)),
+                        Some(edit_control_id),
+                    );
+                }
                 _ => {}
             },
             TargetCategory::Virtual => {}
@@ -3364,6 +4022,7 @@ impl<'a> ImmutableMappingPanel<'a> {
         self.fill_source_category_combo_box();
         self.fill_mode_out_of_range_behavior_combo_box();
         self.fill_mode_group_interaction_combo_box();
+        self.fill_mode_gesture_combo_box();
         self.fill_mode_takeover_mode_combo_box();
         self.fill_mode_button_usage_combo_box();
         self.fill_mode_encoder_usage_combo_box();
@@ -3673,6 +4332,7 @@ impl<'a> ImmutableMappingPanel<'a> {
         let text = match self.source.category() {
             Midi => match self.source.midi_source_type() {
                 MidiSourceType::Script => Some("Kind"),
+                MidiSourceType::Mpe => Some("Zone"),
                 t if t.supports_channel() => Some("Channel"),
                 _ => None,
             },
@@ -3680,6 +4340,9 @@ impl<'a> ImmutableMappingPanel<'a> {
             Reaper => match self.source.reaper_source_type() {
                 ReaperSourceType::Timer => Some("Millis"),
                 ReaperSourceType::RealearnParameter => Some("Param"),
+                ReaperSourceType::MusicalTimer => Some("Trigger"),
+                ReaperSourceType::AudioLevel => Some("Input"),
+                ReaperSourceType::Speech => Some("Voice"),
                 _ => None,
             },
             Keyboard => Some("Keystroke"),
@@ -3697,8 +4360,14 @@ impl<'a> ImmutableMappingPanel<'a> {
             Midi => match self.source.midi_source_type() {
                 MidiSourceType::ClockTransport => Some("Message"),
                 MidiSourceType::Display => Some("Protocol"),
+                MidiSourceType::Mpe => Some("Channels"),
+                MidiSourceType::Chord => Some("Mode"),
                 _ => None,
             },
+            Virtual if self.source.control_element_type() == VirtualControlElementType::Button => {
+                Some("Chord")
+            }
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::Speech => Some("Queue"),
             _ => None,
         };
         self.view
@@ -3718,6 +4387,12 @@ impl<'a> ImmutableMappingPanel<'a> {
                     b.select_combo_box_item_by_index(self.source.midi_script_kind().into())
                         .unwrap();
                 }
+                MidiSourceType::Mpe => {
+                    b.fill_combo_box_indexed(MpeZone::into_enum_iter());
+                    b.show();
+                    b.select_combo_box_item_by_index(self.source.mpe_zone().into())
+                        .unwrap();
+                }
                 t if t.supports_channel() => {
                     b.fill_combo_box_with_data_small(
                         iter::once((-1isize, "<Any> (no feedback)".to_string()))
@@ -3746,6 +4421,22 @@ impl<'a> ImmutableMappingPanel<'a> {
                     b.select_combo_box_item_by_index(self.source.parameter_index().get() as usize)
                         .unwrap();
                 }
+                ReaperSourceType::MusicalTimer => {
+                    b.fill_combo_box_indexed(MusicalTimerTrigger::into_enum_iter());
+                    b.show();
+                    b.select_combo_box_item_by_index(
+                        self.source.musical_timer_settings().trigger.into(),
+                    )
+                    .unwrap();
+                }
+                ReaperSourceType::AudioLevel => {
+                    b.fill_combo_box_indexed(AudioLevelInput::into_enum_iter());
+                    b.show();
+                    b.select_combo_box_item_by_index(
+                        self.source.audio_level_settings().input.into(),
+                    )
+                    .unwrap();
+                }
                 _ => b.hide(),
             },
             _ => {
@@ -3772,6 +4463,10 @@ impl<'a> ImmutableMappingPanel<'a> {
                 }
             }
             Osc => Some(("Is relative", self.source.osc_arg_is_relative())),
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::MusicalTimer => Some((
+                "Only while playing",
+                self.source.musical_timer_settings().only_while_playing,
+            )),
             _ => None,
         };
         self.invalidate_check_box(root::ID_SOURCE_14_BIT_CHECK_BOX, state);
@@ -3831,6 +4526,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                             Some("Display")
                         }
                     }
+                    Mpe => Some("Note"),
                     t if t.supports_midi_message_number()
                         || t.supports_parameter_number_message_number() =>
                     {
@@ -3841,6 +4537,13 @@ impl<'a> ImmutableMappingPanel<'a> {
             }
             Virtual => Some("ID"),
             Osc => Some("Argument"),
+            Reaper if self.source_is_musical_timer_with_interval() => Some("Interval"),
+            Reaper if self.source_is_audio_level_with_index() => {
+                match self.source.audio_level_settings().input {
+                    AudioLevelInput::HardwareInput => Some("Channel"),
+                    _ => Some("Track #"),
+                }
+            }
             _ => None,
         };
         self.view
@@ -3858,6 +4561,12 @@ impl<'a> ImmutableMappingPanel<'a> {
             Osc => {
                 invalidate_with_osc_arg_index(b, self.source.osc_arg_index());
             }
+            Midi if self.source.midi_source_type() == MidiSourceType::Mpe => {
+                b.show();
+                b.fill_combo_box_indexed(MpeNoteSelector::into_enum_iter());
+                b.select_combo_box_item_by_index(self.source.mpe_note_selector().into())
+                    .unwrap();
+            }
             _ => {
                 b.hide();
             }
@@ -3920,6 +4629,14 @@ impl<'a> ImmutableMappingPanel<'a> {
                         };
                         b.select_combo_box_item_by_data(data).unwrap();
                     }
+                    Mpe if self.source.mpe_note_selector() == MpeNoteSelector::HeldNote => {
+                        b.show();
+                        b.fill_combo_box_indexed((1..=15u8).map(|i| format!("#{}", i)));
+                        b.select_combo_box_item_by_index(
+                            self.source.mpe_held_note_index().min(14) as _
+                        )
+                        .unwrap();
+                    }
                     _ => {
                         b.hide();
                     }
@@ -3951,6 +4668,12 @@ impl<'a> ImmutableMappingPanel<'a> {
                 _ => None,
             },
             Virtual => Some(self.source.control_element_id().to_string()),
+            Reaper if self.source_is_musical_timer_with_interval() => {
+                Some(self.source.musical_timer_settings().interval.to_string())
+            }
+            Reaper if self.source_is_audio_level_with_index() => {
+                Some(self.source.audio_level_settings().index.to_string())
+            }
             _ => None,
         };
         self.view
@@ -3988,9 +4711,21 @@ impl<'a> ImmutableMappingPanel<'a> {
             Midi => match self.source.midi_source_type() {
                 MidiSourceType::Raw => Some("Pattern"),
                 MidiSourceType::Script => Some("Script"),
+                MidiSourceType::Chord => Some("Notes"),
                 _ => None,
             },
             Osc => Some("Feedback arguments"),
+            Virtual if self.source.control_element_type() == VirtualControlElementType::Button => {
+                Some("Chord with")
+            }
+            Reaper if self.source_is_musical_timer_with_bars() => Some("Bars"),
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::AudioLevel => {
+                match self.source.audio_level_settings().measurement {
+                    AudioLevelMeasurement::Envelope => Some("Attack / release (ms)"),
+                    AudioLevelMeasurement::Onset => Some("Threshold (dB)"),
+                    _ => None,
+                }
+            }
             _ => None,
         };
         self.view
@@ -4009,6 +4744,10 @@ impl<'a> ImmutableMappingPanel<'a> {
             Osc => Some((self.source.osc_address_pattern().to_owned(), true)),
             Reaper => match self.source.reaper_source_type() {
                 ReaperSourceType::Timer => Some((self.source.timer_millis().to_string(), true)),
+                ReaperSourceType::Speech => {
+                    let voice = self.source.speech_settings().voice.clone();
+                    Some((voice.unwrap_or_default(), true))
+                }
                 _ => None,
             },
             Keyboard => {
@@ -4066,12 +4805,37 @@ impl<'a> ImmutableMappingPanel<'a> {
                         has_multiple_lines(text),
                     )
                 }
+                MidiSourceType::Chord => {
+                    let text = format_chord_notes(self.source.chord_notes());
+                    (Some(text), false)
+                }
                 _ => (None, false),
             },
             Osc => {
                 let text = format_osc_feedback_args(self.source.osc_feedback_args());
                 (Some(text), false)
             }
+            Virtual if self.source.control_element_type() == VirtualControlElementType::Button => {
+                let text =
+                    format_chord_control_element_ids(self.source.chord_control_element_ids());
+                (Some(text), false)
+            }
+            Reaper if self.source_is_musical_timer_with_bars() => {
+                let text = format_musical_timer_bars(&self.source.musical_timer_settings().bars);
+                (Some(text), false)
+            }
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::AudioLevel => {
+                let settings = self.source.audio_level_settings();
+                let text = match settings.measurement {
+                    AudioLevelMeasurement::Envelope => Some(format_audio_level_attack_release(
+                        settings.attack,
+                        settings.release,
+                    )),
+                    AudioLevelMeasurement::Onset => Some(settings.onset_threshold_db.to_string()),
+                    _ => None,
+                };
+                (text, false)
+            }
             _ => (None, false),
         };
         c.set_text_or_hide(value_text);
@@ -4098,10 +4862,24 @@ impl<'a> ImmutableMappingPanel<'a> {
                         }
                     }
                     t if t.supports_custom_character() => Some("Character"),
+                    Mpe => Some("Dimension"),
+                    Chord => Some("Window (ms)"),
                     _ => None,
                 }
             }
             Osc if self.source.supports_osc_arg_value_range() => Some("Range"),
+            Virtual if self.source.control_element_type() == VirtualControlElementType::Button => {
+                Some("Window (ms)")
+            }
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::Speech => {
+                Some("Rate (%)")
+            }
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::MusicalTimer => {
+                Some("Output")
+            }
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::AudioLevel => {
+                Some("Measure")
+            }
             _ => None,
         };
         self.view
@@ -4144,11 +4922,33 @@ impl<'a> ImmutableMappingPanel<'a> {
                         b.select_combo_box_item_by_index(self.source.custom_character().into())
                             .unwrap();
                     }
+                    Mpe => {
+                        b.show();
+                        b.fill_combo_box_indexed(MpeDimension::into_enum_iter());
+                        b.select_combo_box_item_by_index(self.source.mpe_dimension().into())
+                            .unwrap();
+                    }
                     _ => {
                         b.hide();
                     }
                 }
             }
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::MusicalTimer => {
+                b.show();
+                b.fill_combo_box_indexed(MusicalTimerOutput::into_enum_iter());
+                b.select_combo_box_item_by_index(
+                    self.source.musical_timer_settings().output.into(),
+                )
+                .unwrap();
+            }
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::AudioLevel => {
+                b.show();
+                b.fill_combo_box_indexed(AudioLevelMeasurement::into_enum_iter());
+                b.select_combo_box_item_by_index(
+                    self.source.audio_level_settings().measurement.into(),
+                )
+                .unwrap();
+            }
             _ => {
                 b.hide();
             }
@@ -4169,6 +4969,16 @@ impl<'a> ImmutableMappingPanel<'a> {
                 );
                 Some(text)
             }
+            Midi if self.source.midi_source_type() == MidiSourceType::Chord => {
+                Some(self.source.chord_time_window_millis().to_string())
+            }
+            Virtual if self.source.control_element_type() == VirtualControlElementType::Button => {
+                Some(self.source.chord_time_window_millis().to_string())
+            }
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::Speech => {
+                let rate = self.source.speech_settings().rate;
+                Some(rate.map(|r| r.to_string()).unwrap_or_default())
+            }
             _ => None,
         };
         self.view
@@ -4197,16 +5007,72 @@ impl<'a> ImmutableMappingPanel<'a> {
                     b.select_combo_box_item_by_index(self.source.display_type().into())
                         .unwrap();
                 }
+                MidiSourceType::Mpe => {
+                    b.show();
+                    b.fill_combo_box_indexed((1..=15u8).map(|i| i.to_string()));
+                    b.select_combo_box_item_by_index(
+                        self.source.mpe_member_channel_count().clamp(1, 15) as usize - 1,
+                    )
+                    .unwrap();
+                }
+                MidiSourceType::Chord => {
+                    self.invalidate_chord_mode_combo_box(b);
+                }
                 _ => {
                     b.hide();
                 }
             },
+            Virtual if self.source.control_element_type() == VirtualControlElementType::Button => {
+                self.invalidate_chord_mode_combo_box(b);
+            }
+            Reaper if self.source.reaper_source_type() == ReaperSourceType::Speech => {
+                b.show();
+                b.fill_combo_box_with_data_small(
+                    iter::once((-1isize, "<Instance>".to_string())).chain(
+                        SpeechQueuePolicy::into_enum_iter()
+                            .map(|p| (usize::from(p) as isize, p.to_string())),
+                    ),
+                );
+                let data = match self.source.speech_settings().queue_policy {
+                    None => -1,
+                    Some(p) => usize::from(p) as isize,
+                };
+                b.select_combo_box_item_by_data(data).unwrap();
+            }
             _ => {
                 b.hide();
             }
         }
     }
 
+    fn invalidate_chord_mode_combo_box(&self, b: Window) {
+        b.show();
+        b.fill_combo_box_indexed(ChordMode::into_enum_iter());
+        b.select_combo_box_item_by_index(self.source.chord_mode().into())
+            .unwrap();
+    }
+
+    fn source_is_musical_timer_with_interval(&self) -> bool {
+        self.source.reaper_source_type() == ReaperSourceType::MusicalTimer
+            && matches!(
+                self.source.musical_timer_settings().trigger,
+                MusicalTimerTrigger::EveryNBars | MusicalTimerTrigger::EveryNBeats
+            )
+    }
+
+    fn source_is_musical_timer_with_bars(&self) -> bool {
+        self.source.reaper_source_type() == ReaperSourceType::MusicalTimer
+            && self.source.musical_timer_settings().trigger == MusicalTimerTrigger::SpecificBars
+    }
+
+    fn source_is_audio_level_with_index(&self) -> bool {
+        self.source.reaper_source_type() == ReaperSourceType::AudioLevel
+            && matches!(
+                self.source.audio_level_settings().input,
+                AudioLevelInput::TrackByPosition | AudioLevelInput::HardwareInput
+            )
+    }
+
     fn invalidate_target_controls(&self, initiator: Option<u32>) {
         self.invalidate_target_category_combo_box();
         self.invalidate_target_type_combo_box();
@@ -4289,6 +5155,8 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::Transport => Some("Action"),
                 ReaperTargetType::AnyOn => Some("Parameter"),
                 ReaperTargetType::AutomationModeOverride => Some("Behavior"),
+                ReaperTargetType::TimeRange => Some("Range"),
+                ReaperTargetType::MarkerManagement => Some("Action"),
                 ReaperTargetType::GoToBookmark => match self.target.bookmark_type() {
                     BookmarkType::Marker => Some("Marker"),
                     BookmarkType::Region => Some("Region"),
@@ -4339,6 +5207,13 @@ impl<'a> ImmutableMappingPanel<'a> {
                         .select_combo_box_item_by_index(self.target.track_type().into())
                         .unwrap();
                 }
+                ReaperTargetType::TimeRange => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(TimeRangeKind::into_enum_iter());
+                    combo
+                        .select_combo_box_item_by_index(self.target.time_range_kind().into())
+                        .unwrap();
+                }
                 ReaperTargetType::GoToBookmark => {
                     combo.show();
                     combo.fill_combo_box_indexed(BookmarkAnchorType::into_enum_iter());
@@ -4415,6 +5290,24 @@ impl<'a> ImmutableMappingPanel<'a> {
                         )
                         .unwrap();
                 }
+                ReaperTargetType::MarkerManagement => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(MarkerManagementAction::into_enum_iter());
+                    combo
+                        .select_combo_box_item_by_index(
+                            self.mapping.target_model.marker_management_action().into(),
+                        )
+                        .unwrap();
+                }
+                ReaperTargetType::TimeRange => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(TimeRangeAction::into_enum_iter());
+                    combo
+                        .select_combo_box_item_by_index(
+                            self.mapping.target_model.time_range_action().into(),
+                        )
+                        .unwrap();
+                }
                 ReaperTargetType::AnyOn => {
                     combo.show();
                     combo.fill_combo_box_indexed(AnyOnParameter::into_enum_iter());
@@ -4675,9 +5568,67 @@ impl<'a> ImmutableMappingPanel<'a> {
 
     fn invalidate_target_line_5(&self, initiator: Option<u32>) {
         self.invalidate_target_line_5_label_1();
+        self.invalidate_target_line_5_combo_box_1();
+        self.invalidate_target_line_5_combo_box_2();
         self.invalidate_target_line_5_edit_control(initiator);
     }
 
+    fn invalidate_target_line_5_combo_box_1(&self) {
+        let combo = self
+            .view
+            .require_control(root::ID_TARGET_LINE_5_COMBO_BOX_1);
+        match self.target_category() {
+            TargetCategory::Reaper => match self.target.target_type() {
+                t if t.supports_envelope() => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(EnvelopeType::into_enum_iter());
+                    combo
+                        .select_combo_box_item_by_index(self.target.envelope_type().into())
+                        .unwrap();
+                }
+                _ => combo.hide(),
+            },
+            TargetCategory::Virtual => {
+                combo.hide();
+            }
+        }
+    }
+
+    fn invalidate_target_line_5_combo_box_2(&self) {
+        let combo = self
+            .view
+            .require_control(root::ID_TARGET_LINE_5_COMBO_BOX_2);
+        match self.target_category() {
+            TargetCategory::Reaper => match self.target.target_type() {
+                t if t.supports_envelope() => match self.target.envelope_type() {
+                    EnvelopeType::Track => {
+                        combo.show();
+                        combo.fill_combo_box_indexed(TrackEnvelopeKind::into_enum_iter());
+                        combo
+                            .select_combo_box_item_by_index(
+                                self.target.track_envelope_kind().into(),
+                            )
+                            .unwrap();
+                    }
+                    EnvelopeType::Route => {
+                        combo.show();
+                        combo.fill_combo_box_indexed(RouteEnvelopeKind::into_enum_iter());
+                        combo
+                            .select_combo_box_item_by_index(
+                                self.target.route_envelope_kind().into(),
+                            )
+                            .unwrap();
+                    }
+                    EnvelopeType::FxParameter => combo.hide(),
+                },
+                _ => combo.hide(),
+            },
+            TargetCategory::Virtual => {
+                combo.hide();
+            }
+        }
+    }
+
     fn invalidate_target_line_3_button(&self) {
         let text = match self.target_category() {
             TargetCategory::Reaper => match self.reaper_target_type() {
@@ -4736,6 +5687,9 @@ impl<'a> ImmutableMappingPanel<'a> {
                     );
                     Some(text)
                 }
+                ReaperTargetType::TakeFxParameter => {
+                    Some((self.target.take_fx_param_index() + 1).to_string())
+                }
                 _ => None,
             },
             TargetCategory::Virtual => None,
@@ -4754,7 +5708,7 @@ impl<'a> ImmutableMappingPanel<'a> {
             .require_control(root::ID_TARGET_LINE_4_EDIT_CONTROL);
         match self.target_category() {
             TargetCategory::Reaper => match self.reaper_target_type() {
-                t if t.supports_fx_parameter() => {
+                _ if self.target.supports_fx_parameter() => {
                     let text = match self.target.param_type() {
                         VirtualFxParameterType::Dynamic => {
                             Some(self.target.param_expression().to_owned())
@@ -4768,7 +5722,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                     };
                     control.set_text_or_hide(text);
                 }
-                t if t.supports_send() => {
+                _ if self.target.supports_route() => {
                     let text = match self.target.route_selector_type() {
                         TrackRouteSelectorType::Dynamic => {
                             self.target.route_expression().to_owned()
@@ -4786,6 +5740,11 @@ impl<'a> ImmutableMappingPanel<'a> {
                     control.set_text(text);
                     control.show();
                 }
+                ReaperTargetType::TakeFxParameter => {
+                    let text = (self.target.take_fx_index() + 1).to_string();
+                    control.set_text(text);
+                    control.show();
+                }
                 t if t.supports_tags() => {
                     let text = format_tags_as_csv(self.target.tags());
                     control.set_text(text);
@@ -4819,6 +5778,23 @@ impl<'a> ImmutableMappingPanel<'a> {
                     let text = self.target.osc_address_pattern().to_owned();
                     (Some(text), false)
                 }
+                ReaperTargetType::MarkerManagement
+                    if self.target.marker_management_action().uses_name() =>
+                {
+                    let text = self.target.marker_name().to_owned();
+                    (Some(text), false)
+                }
+                ReaperTargetType::TimeRange => match self.target.time_range_action() {
+                    TimeRangeAction::SetToRegion => {
+                        let text = (self.target.time_range_region_index() + 1).to_string();
+                        (Some(text), false)
+                    }
+                    TimeRangeAction::SetToBars => {
+                        let text = self.target.time_range_bar_count().to_string();
+                        (Some(text), false)
+                    }
+                    TimeRangeAction::MoveStart | TimeRangeAction::MoveEnd => (None, false),
+                },
                 ReaperTargetType::LoadMappingSnapshot => {
                     let text = self
                         .target
@@ -4829,7 +5805,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                         .unwrap_or_default();
                     (Some(text), false)
                 }
-                t if t.supports_fx() => {
+                _ if self.target.supports_fx() => {
                     let text = match self.target.fx_type() {
                         VirtualFxType::Dynamic => Some(self.target.fx_expression().to_owned()),
                         VirtualFxType::ByIndex => {
@@ -4843,6 +5819,17 @@ impl<'a> ImmutableMappingPanel<'a> {
                     };
                     (text, false)
                 }
+                t if t.supports_item() => {
+                    let text = match self.target.item_type() {
+                        VirtualItemType::Dynamic => Some(self.target.item_expression().to_owned()),
+                        VirtualItemType::ByIndex => {
+                            let index = self.target.item_index();
+                            Some((index + 1).to_string())
+                        }
+                        _ => None,
+                    };
+                    (text, false)
+                }
                 _ => (None, false),
             },
             TargetCategory::Virtual => (None, false),
@@ -4865,12 +5852,28 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::SendOsc => Some("Address"),
                 ReaperTargetType::TrackMonitoringMode => Some("Mode"),
                 ReaperTargetType::LoadMappingSnapshot => Some("Default"),
+                ReaperTargetType::MarkerManagement
+                    if self.target.marker_management_action().uses_name() =>
+                {
+                    Some("Name")
+                }
+                ReaperTargetType::TimeRange => match self.target.time_range_action() {
+                    TimeRangeAction::MoveStart | TimeRangeAction::MoveEnd => Some("Quantize"),
+                    TimeRangeAction::SetToRegion => Some("Region #"),
+                    TimeRangeAction::SetToBars => Some("Bars"),
+                },
                 _ if self.target.supports_automation_mode() => Some("Mode"),
-                t if t.supports_fx() => Some("FX"),
+                _ if self.target.supports_fx() => Some("FX"),
                 t if t.supports_seek_behavior() => Some("Behavior"),
-                t if t.supports_send() => Some("Kind"),
+                _ if self.target.supports_route() => Some("Kind"),
+                t if t.supports_item() => Some("Item"),
                 _ => None,
             },
+            TargetCategory::Virtual
+                if self.target.control_element_type() == VirtualControlElementType::Xy =>
+            {
+                Some("Axis")
+            }
             TargetCategory::Virtual => None,
         };
         self.view
@@ -4884,6 +5887,8 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::SendOsc if self.target.supports_osc_arg_value_range() => {
                     Some("Range")
                 }
+                ReaperTargetType::TakeFxParameter => Some("Param #"),
+                t if t.supports_envelope() => Some("Envelope"),
                 _ => None,
             },
             TargetCategory::Virtual => None,
@@ -4903,12 +5908,13 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::LoadFxSnapshot => Some("Snapshot"),
                 ReaperTargetType::SendOsc => Some("Argument"),
                 ReaperTargetType::TrackTool | ReaperTargetType::FxTool => Some("Act/Tags"),
-                t if t.supports_fx_parameter() => Some("Parameter"),
+                ReaperTargetType::TakeFxParameter => Some("FX #"),
+                _ if self.target.supports_fx_parameter() => Some("Parameter"),
                 t if t.supports_track_exclusivity() => Some("Exclusive"),
                 t if t.supports_fx_display_type() => Some("Display"),
                 t if t.supports_tags() => Some("Tags"),
                 t if t.supports_exclusivity() => Some("Exclusivity"),
-                t if t.supports_send() => match self.target.route_type() {
+                _ if self.target.supports_route() => match self.target.route_type() {
                     TrackRouteType::Send => Some("Send"),
                     TrackRouteType::Receive => Some("Receive"),
                     TrackRouteType::HardwareOutput => Some("Output"),
@@ -4955,20 +5961,36 @@ impl<'a> ImmutableMappingPanel<'a> {
             .require_control(root::ID_TARGET_LINE_3_COMBO_BOX_1);
         match self.target_category() {
             TargetCategory::Reaper => match self.target.target_type() {
-                t if t.supports_fx() => {
+                ReaperTargetType::TimeRange if self.target.time_range_action().moves_edge() => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(TimeRangeQuantization::into_enum_iter());
+                    combo
+                        .select_combo_box_item_by_index(
+                            self.target.time_range_quantization().into(),
+                        )
+                        .unwrap();
+                }
+                _ if self.target.supports_fx() => {
                     combo.show();
                     combo.fill_combo_box_indexed(VirtualFxType::into_enum_iter());
                     combo
                         .select_combo_box_item_by_index(self.target.fx_type().into())
                         .unwrap();
                 }
-                t if t.supports_send() => {
+                _ if self.target.supports_route() => {
                     combo.show();
                     combo.fill_combo_box_indexed(TrackRouteType::into_enum_iter());
                     combo
                         .select_combo_box_item_by_index(self.target.route_type().into())
                         .unwrap();
                 }
+                t if t.supports_item() => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(VirtualItemType::into_enum_iter());
+                    combo
+                        .select_combo_box_item_by_index(self.target.item_type().into())
+                        .unwrap();
+                }
                 _ => combo.hide(),
             },
             TargetCategory::Virtual => {
@@ -5006,7 +6028,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                     let action: FxToolAction = self.target.fx_tool_action();
                     combo.select_combo_box_item_by_index(action.into()).unwrap();
                 }
-                t if t.supports_fx_parameter() => {
+                _ if self.target.supports_fx_parameter() => {
                     combo.show();
                     combo.fill_combo_box_indexed(VirtualFxParameterType::into_enum_iter());
                     combo
@@ -5020,7 +6042,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                         .select_combo_box_item_by_index(self.target.exclusivity().into())
                         .unwrap();
                 }
-                t if t.supports_send() => {
+                _ if self.target.supports_route() => {
                     combo.show();
                     combo.fill_combo_box_indexed(TrackRouteSelectorType::into_enum_iter());
                     combo
@@ -5041,7 +6063,7 @@ impl<'a> ImmutableMappingPanel<'a> {
             .require_control(root::ID_TARGET_LINE_3_COMBO_BOX_2);
         match self.target_category() {
             TargetCategory::Reaper => match self.reaper_target_type() {
-                t if t.supports_fx() => {
+                _ if self.target.supports_fx() => {
                     if matches!(
                         self.target.fx_type(),
                         VirtualFxType::ById | VirtualFxType::ByIdOrIndex
@@ -5165,7 +6187,15 @@ impl<'a> ImmutableMappingPanel<'a> {
                 }
             },
             TargetCategory::Virtual => {
-                combo.hide();
+                if self.target.control_element_type() == VirtualControlElementType::Xy {
+                    combo.show();
+                    combo.fill_combo_box_indexed(XyAxis::into_enum_iter());
+                    combo
+                        .select_combo_box_item_by_index(self.target.control_element_axis().into())
+                        .unwrap();
+                } else {
+                    combo.hide();
+                }
             }
         }
     }
@@ -5187,7 +6217,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                         .select_combo_box_item_by_index(self.target.mouse_button().into())
                         .unwrap();
                 }
-                t if t.supports_fx_parameter()
+                _ if self.target.supports_fx_parameter()
                     && self.target.param_type() == VirtualFxParameterType::ById =>
                 {
                     combo.show();
@@ -5219,7 +6249,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                         .select_combo_box_item_by_index(self.target.fx_display_type().into())
                         .unwrap();
                 }
-                t if t.supports_send() => {
+                _ if self.target.supports_route() => {
                     if self.target.route_selector_type() == TrackRouteSelectorType::ById {
                         combo.show();
                         let context = self.session.extended_context();
@@ -5287,6 +6317,9 @@ impl<'a> ImmutableMappingPanel<'a> {
                     Some(("Regions", is_regions))
                 }
                 ReaperTargetType::Seek => Some(("Seek play", self.target.seek_play())),
+                ReaperTargetType::BrowseItems => {
+                    Some(("Scroll arrange view", self.target.scroll_arrange_view()))
+                }
                 _ if self.target.supports_fx_chain() => {
                     let is_input_fx = self.target.fx_is_input_fx();
                     let label = if self.target.track_type() == VirtualTrackType::Master {
@@ -5329,6 +6362,9 @@ impl<'a> ImmutableMappingPanel<'a> {
                 t if t.supports_track_scrolling() => {
                     Some(("Scroll MCP", self.target.scroll_mixer()))
                 }
+                ReaperTargetType::BrowseItems => {
+                    Some(("Move edit cursor", self.target.move_edit_cursor()))
+                }
                 ReaperTargetType::Seek => Some(("Move view", self.target.move_view())),
                 _ => None,
             },
@@ -5635,14 +6671,16 @@ impl<'a> ImmutableMappingPanel<'a> {
         self.invalidate_mode_feedback_type_button();
         self.invalidate_mode_out_of_range_behavior_combo_box();
         self.invalidate_mode_group_interaction_combo_box();
+        self.invalidate_mode_gesture_controls(initiator);
         self.invalidate_mode_round_target_value_check_box();
         self.invalidate_mode_takeover_mode_combo_box();
         self.invalidate_mode_button_usage_combo_box();
         self.invalidate_mode_encoder_usage_combo_box();
         self.invalidate_mode_reverse_check_box();
         self.invalidate_mode_target_value_sequence_edit_control(initiator);
-        self.invalidate_mode_eel_control_transformation_edit_control(initiator);
-        self.invalidate_mode_eel_feedback_transformation_edit_control(initiator);
+        self.invalidate_mode_control_transformation_label();
+        self.invalidate_mode_control_transformation_edit_control(initiator);
+        self.invalidate_mode_feedback_transformation_edit_control(initiator);
     }
 
     fn invalidate_mode_type_combo_box(&self) {
@@ -5896,6 +6934,21 @@ impl<'a> ImmutableMappingPanel<'a> {
                 show_button_filter || show_fire_mode,
                 &[root::ID_MODE_BUTTON_GROUP_BOX],
             );
+            // Gestures are about press sequences, so they are relevant whenever fire modes are.
+            self.enable_if(
+                show_fire_mode,
+                &[
+                    root::ID_MODE_GESTURE_LABEL_TEXT,
+                    root::ID_MODE_GESTURE_COMBO_BOX,
+                ],
+            );
+            self.enable_if(
+                show_fire_mode && self.mode.gesture() == Gesture::MultiTap,
+                &[
+                    root::ID_MODE_GESTURE_TAP_COUNT_EDIT_CONTROL,
+                    root::ID_MODE_GESTURE_TAP_COUNT_LABEL_TEXT,
+                ],
+            );
         }
     }
 
@@ -6236,6 +7289,18 @@ impl<'a> ImmutableMappingPanel<'a> {
             .set_checked(self.mode.round_target_value());
     }
 
+    fn invalidate_mode_gesture_controls(&self, initiator: Option<u32>) {
+        self.view
+            .require_control(root::ID_MODE_GESTURE_COMBO_BOX)
+            .select_combo_box_item_by_index(self.mode.gesture().into())
+            .unwrap();
+        if initiator != Some(root::ID_MODE_GESTURE_TAP_COUNT_EDIT_CONTROL) {
+            self.view
+                .require_control(root::ID_MODE_GESTURE_TAP_COUNT_EDIT_CONTROL)
+                .set_text(self.mode.gesture_tap_count().to_string());
+        }
+    }
+
     fn invalidate_mode_takeover_mode_combo_box(&self) {
         let mode = self.mode.takeover_mode();
         self.view
@@ -6288,19 +7353,29 @@ impl<'a> ImmutableMappingPanel<'a> {
             .set_text(formatted);
     }
 
-    fn invalidate_mode_eel_control_transformation_edit_control(&self, initiator: Option<u32>) {
+    fn invalidate_mode_control_transformation_label(&self) {
+        let text = format!(
+            "Control transformation ({})",
+            self.mode.control_transformation_language()
+        );
+        self.view
+            .require_control(root::ID_MODE_EEL_CONTROL_TRANSFORMATION_LABEL)
+            .set_text(text);
+    }
+
+    fn invalidate_mode_control_transformation_edit_control(&self, initiator: Option<u32>) {
         if initiator == Some(root::ID_MODE_EEL_CONTROL_TRANSFORMATION_EDIT_CONTROL) {
             return;
         }
         let control = self
             .view
             .require_control(root::ID_MODE_EEL_CONTROL_TRANSFORMATION_EDIT_CONTROL);
-        let text = self.mode.eel_control_transformation();
+        let text = self.mode.control_transformation();
         control.set_text(extract_first_line(text));
         control.set_enabled(!has_multiple_lines(text));
     }
 
-    fn invalidate_mode_eel_feedback_transformation_edit_control(&self, initiator: Option<u32>) {
+    fn invalidate_mode_feedback_transformation_edit_control(&self, initiator: Option<u32>) {
         if initiator == Some(root::ID_MODE_EEL_FEEDBACK_TRANSFORMATION_EDIT_CONTROL) {
             return;
         }
@@ -6310,7 +7385,7 @@ impl<'a> ImmutableMappingPanel<'a> {
         let text = if self.mode.feedback_type().is_textual() {
             self.mode.textual_feedback_expression()
         } else {
-            self.mode.eel_feedback_transformation()
+            self.mode.feedback_transformation()
         };
         control.set_text(extract_first_line(text));
         control.set_enabled(!has_multiple_lines(text));
@@ -6396,6 +7471,12 @@ impl<'a> ImmutableMappingPanel<'a> {
             .fill_combo_box_indexed(FeedbackType::into_enum_iter());
     }
 
+    fn fill_mode_gesture_combo_box(&self) {
+        self.view
+            .require_control(root::ID_MODE_GESTURE_COMBO_BOX)
+            .fill_combo_box_indexed(Gesture::into_enum_iter());
+    }
+
     fn fill_mode_takeover_mode_combo_box(&self) {
         self.view
             .require_control(root::ID_MODE_TAKEOVER_MODE)
@@ -6500,7 +7581,7 @@ impl View for MappingPanel {
                 let _ = self.handle_source_line_4_button_press();
             }
             root::ID_MODE_EEL_CONTROL_TRANSFORMATION_DETAIL_BUTTON => {
-                self.edit_control_transformation()
+                let _ = self.control_transformation_button_pressed();
             }
             root::ID_SOURCE_SCRIPT_DETAIL_BUTTON => self.edit_source_pattern_or_script(),
             // Mode
@@ -6579,6 +7660,7 @@ impl View for MappingPanel {
             root::ID_MODE_GROUP_INTERACTION_COMBO_BOX => {
                 self.write(|p| p.update_mode_group_interaction())
             }
+            root::ID_MODE_GESTURE_COMBO_BOX => self.write(|p| p.update_mode_gesture()),
             root::ID_MODE_TAKEOVER_MODE => self.write(|p| p.update_takeover_mode()),
             root::ID_MODE_BUTTON_FILTER_COMBO_BOX => self.write(|p| p.update_button_usage()),
             root::ID_MODE_RELATIVE_FILTER_COMBO_BOX => self.write(|p| p.update_encoder_usage()),
@@ -6605,6 +7687,12 @@ impl View for MappingPanel {
             root::ID_TARGET_LINE_4_COMBO_BOX_2 => {
                 self.write(|p| p.handle_target_line_4_combo_box_2_change())
             }
+            root::ID_TARGET_LINE_5_COMBO_BOX_1 => {
+                self.write(|p| p.handle_target_line_5_combo_box_1_change())
+            }
+            root::ID_TARGET_LINE_5_COMBO_BOX_2 => {
+                self.write(|p| p.handle_target_line_5_combo_box_2_change())
+            }
             // Help
             root::ID_MAPPING_HELP_APPLICABLE_TO_COMBO_BOX => {
                 self.write(|p| p.handle_applicable_to_combo_box_change())
@@ -6692,8 +7780,11 @@ impl View for MappingPanel {
             root::ID_MODE_TARGET_SEQUENCE_EDIT_CONTROL => {
                 view.write(|p| p.update_mode_target_value_sequence());
             }
+            root::ID_MODE_GESTURE_TAP_COUNT_EDIT_CONTROL => {
+                view.write(|p| p.update_mode_gesture_tap_count());
+            }
             root::ID_MODE_EEL_CONTROL_TRANSFORMATION_EDIT_CONTROL => {
-                view.write(|p| p.update_mode_eel_control_transformation());
+                view.write(|p| p.update_mode_control_transformation());
             }
             root::ID_MODE_EEL_FEEDBACK_TRANSFORMATION_EDIT_CONTROL => {
                 view.write(|p| p.update_mode_feedback_transformation());
@@ -7034,7 +8125,7 @@ fn invalidat_target_line_3_expression_result(
 ) {
     let text = match target.category() {
         TargetCategory::Reaper => {
-            if target.target_type().supports_fx() && target.fx_type() == VirtualFxType::Dynamic {
+            if target.supports_fx() && target.fx_type() == VirtualFxType::Dynamic {
                 target
                     .with_context(context, compartment)
                     .first_fx_chain()
@@ -7062,7 +8153,7 @@ fn invalidate_target_line_4_expression_result(
 ) {
     let text = match target.category() {
         TargetCategory::Reaper => match target.target_type() {
-            t if t.supports_fx_parameter()
+            _ if target.supports_fx_parameter()
                 && target.param_type() == VirtualFxParameterType::Dynamic =>
             {
                 target
@@ -7078,7 +8169,7 @@ fn invalidate_target_line_4_expression_result(
                             .map(|i| i.to_string())
                     })
             }
-            t if t.supports_send()
+            _ if target.supports_route()
                 && target.route_selector_type() == TrackRouteSelectorType::Dynamic =>
             {
                 target
@@ -7180,6 +8271,9 @@ fn prompt_for_predefined_control_element_name(
             VirtualControlElementType::Button => {
                 control_element_domains::daw::PREDEFINED_VIRTUAL_BUTTON_NAMES
             }
+            VirtualControlElementType::Xy => {
+                control_element_domains::daw::PREDEFINED_VIRTUAL_XY_NAMES
+            }
         };
         let grid_control_names = match r#type {
             VirtualControlElementType::Multi => {
@@ -7188,6 +8282,9 @@ fn prompt_for_predefined_control_element_name(
             VirtualControlElementType::Button => {
                 control_element_domains::grid::PREDEFINED_VIRTUAL_BUTTON_NAMES
             }
+            VirtualControlElementType::Xy => {
+                control_element_domains::grid::PREDEFINED_VIRTUAL_XY_NAMES
+            }
         };
         let entries = vec![
             menu(
@@ -7237,9 +8334,93 @@ enum ColorTarget {
     BackgroundColor,
 }
 
+enum OscSourcePopupMenuResult {
+    EditFeedbackArguments,
+    EditAdditionalArguments,
+}
+
+fn show_osc_source_popup_menu(window: Window) -> Option<OscSourcePopupMenuResult> {
+    let pure_menu = {
+        use swell_ui::menu_tree::*;
+        let entries = vec![
+            item("Edit feedback arguments...", || {
+                OscSourcePopupMenuResult::EditFeedbackArguments
+            }),
+            item("Edit additional arguments...", || {
+                OscSourcePopupMenuResult::EditAdditionalArguments
+            }),
+        ];
+        root_menu(entries)
+    };
+    window.open_simple_popup_menu(pure_menu, Window::cursor_pos())
+}
+
+enum MidiScriptDirection {
+    Feedback,
+    Control,
+}
+
+fn show_midi_script_popup_menu(window: Window) -> Option<MidiScriptDirection> {
+    let pure_menu = {
+        use swell_ui::menu_tree::*;
+        let entries = vec![
+            item("Edit feedback script...", || MidiScriptDirection::Feedback),
+            item("Edit control script...", || MidiScriptDirection::Control),
+        ];
+        root_menu(entries)
+    };
+    window.open_simple_popup_menu(pure_menu, Window::cursor_pos())
+}
+
+enum ControlTransformationPopupMenuResult {
+    EditMultiLine,
+    ChangeLanguage(TransformationLanguage),
+}
+
+fn show_control_transformation_popup_menu(
+    window: Window,
+    current_language: TransformationLanguage,
+) -> Result<ControlTransformationPopupMenuResult, &'static str> {
+    use ControlTransformationPopupMenuResult as R;
+    let pure_menu = {
+        use swell_ui::menu_tree::*;
+        let entries = vec![
+            item("Edit multi-line...", || R::EditMultiLine),
+            create_transformation_language_menu(current_language, R::ChangeLanguage),
+        ];
+        root_menu(entries)
+    };
+    window
+        .open_simple_popup_menu(pure_menu, Window::cursor_pos())
+        .ok_or("control transformation menu cancelled")
+}
+
+fn create_transformation_language_menu<R>(
+    current_language: TransformationLanguage,
+    create_result: fn(TransformationLanguage) -> R,
+) -> swell_ui::menu_tree::Entry<R> {
+    use swell_ui::menu_tree::*;
+    menu(
+        "Language",
+        TransformationLanguage::into_enum_iter()
+            .map(|language| {
+                item_with_opts(
+                    language.to_string(),
+                    ItemOpts {
+                        enabled: true,
+                        checked: language == current_language,
+                    },
+                    move || create_result(language),
+                )
+            })
+            .collect(),
+    )
+}
+
 enum FeedbackPopupMenuResult {
     EditMultiLine,
     ChangeColor(ChangeColorInstruction),
+    ChangeTransformationLanguage(TransformationLanguage),
 }
 
 struct ChangeColorInstruction {
@@ -7257,12 +8438,15 @@ fn show_feedback_popup_menu(
     window: Window,
     color: Option<VirtualColor>,
     background_color: Option<VirtualColor>,
+    // `None` if textual feedback
+    transformation_language: Option<TransformationLanguage>,
 ) -> Result<FeedbackPopupMenuResult, &'static str> {
     enum MenuAction {
         ControllerDefault(ColorTarget),
         OpenColorPicker(ColorTarget),
         UseColorProp(ColorTarget, &'static str),
         EditMultiLine,
+        ChangeTransformationLanguage(TransformationLanguage),
     }
     let pure_menu = {
         use swell_ui::menu_tree::*;
@@ -7307,11 +8491,17 @@ fn show_feedback_popup_menu(
                     .collect(),
             )
         };
-        let entries = vec![
+        let mut entries = vec![
             item("Edit multi-line...", || MenuAction::EditMultiLine),
             create_color_target_menu(ColorTarget::Color),
             create_color_target_menu(ColorTarget::BackgroundColor),
         ];
+        if let Some(language) = transformation_language {
+            entries.push(create_transformation_language_menu(
+                language,
+                MenuAction::ChangeTransformationLanguage,
+            ));
+        }
         root_menu(entries)
     };
     let item = window
@@ -7319,6 +8509,9 @@ fn show_feedback_popup_menu(
         .ok_or("color selection cancelled")?;
     let result = match item {
         MenuAction::EditMultiLine => FeedbackPopupMenuResult::EditMultiLine,
+        MenuAction::ChangeTransformationLanguage(language) => {
+            FeedbackPopupMenuResult::ChangeTransformationLanguage(language)
+        }
         MenuAction::ControllerDefault(target) => {
             let instruction = ChangeColorInstruction::new(target, None);
             FeedbackPopupMenuResult::ChangeColor(instruction)
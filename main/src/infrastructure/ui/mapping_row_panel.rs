@@ -1,8 +1,9 @@
 use crate::application::{
     Affected, CompartmentProp, MappingCommand, MappingModel, MappingProp, Session, SessionProp,
-    SharedMapping, SharedSession, SourceCategory, TargetCategory, TargetModelFormatMultiLine,
-    WeakSession,
+    SharedMapping, SharedSession, SourceCategory, TargetCategory, TargetModel,
+    TargetModelFormatMultiLine, WeakSession,
 };
+use crate::base::notification::notify_processing_result;
 use crate::base::when;
 use crate::domain::{Compartment, GroupId, GroupKey, MappingId, QualifiedMappingId};
 
@@ -565,7 +566,8 @@ impl MappingRowPanel {
     }
 
     fn paste_from_lua_replace(&self, text: &str) -> Result<(), Box<dyn Error>> {
-        let api_object = deserialize_api_object_from_lua(text)?;
+        let res = deserialize_api_object_from_lua(text)?;
+        let api_object = res.value;
         if !matches!(api_object, ApiObject::Mapping(Envelope { value: _, .. })) {
             return Err("There's more than one mapping in the clipboard.".into());
         }
@@ -576,12 +578,19 @@ impl MappingRowPanel {
             DataObject::try_from_api_object(api_object, &compartment_in_session)?
         };
         paste_data_object_in_place(data_object, self.session(), self.mapping_triple()?)?;
+        if !res.annotations.is_empty() {
+            notify_processing_result(
+                "Paste from Lua",
+                res.annotations.into_iter().map(|a| a.to_string()).collect(),
+            );
+        }
         Ok(())
     }
 
     fn paste_from_lua_insert_below(&self, text: &str) -> Result<(), Box<dyn Error>> {
-        let api_object = deserialize_api_object_from_lua(text)?;
-        let api_mappings = api_object
+        let res = deserialize_api_object_from_lua(text)?;
+        let api_mappings = res
+            .value
             .into_mappings()
             .ok_or("Can only insert a list of mappings.")?;
         let data_mappings = {
@@ -597,7 +606,14 @@ impl MappingRowPanel {
             triple.compartment,
             Some(triple.mapping_id),
             triple.group_id,
-        )
+        )?;
+        if !res.annotations.is_empty() {
+            notify_processing_result(
+                "Paste from Lua",
+                res.annotations.into_iter().map(|a| a.to_string()).collect(),
+            );
+        }
+        Ok(())
     }
 
     fn mapping_triple(&self) -> Result<MappingTriple, &'static str> {
@@ -622,6 +638,9 @@ impl MappingRowPanel {
             CopyMappingAsLua(ConversionStyle),
             PasteFromLuaReplace(String),
             PasteFromLuaInsertBelow(String),
+            PasteYTarget(Box<TargetModelData>),
+            SwapTargetAndYTarget,
+            RemoveYTarget,
             LogDebugInfo,
         }
         impl Default for MenuAction {
@@ -645,6 +664,11 @@ impl MappingRowPanel {
                 text_from_clipboard.is_some() && data_object_from_clipboard.is_none();
             let text_from_clipboard_clone = text_from_clipboard.clone();
             let data_object_from_clipboard_clone = data_object_from_clipboard.clone();
+            let target_from_clipboard = match &data_object_from_clipboard {
+                Some(DataObject::Target(Envelope { value: t, .. })) => Some(t.clone()),
+                _ => None,
+            };
+            let has_y_target = mapping.y_target_model().is_some();
             let group_id = mapping.group_id();
             let entries = vec![
                 item("Copy", || MenuAction::CopyPart(ObjectType::Mapping)),
@@ -712,6 +736,35 @@ impl MappingRowPanel {
                         item("Copy target", || MenuAction::CopyPart(ObjectType::Target)),
                     ],
                 ),
+                menu(
+                    "Y target (for XY sources)",
+                    vec![
+                        if let Some(t) = target_from_clipboard {
+                            item(
+                                format!("Paste target as Y target ({})", t.category),
+                                move || MenuAction::PasteYTarget(t),
+                            )
+                        } else {
+                            disabled_item("Paste target as Y target")
+                        },
+                        item_with_opts(
+                            "Swap target and Y target",
+                            ItemOpts {
+                                enabled: has_y_target,
+                                checked: false,
+                            },
+                            || MenuAction::SwapTargetAndYTarget,
+                        ),
+                        item_with_opts(
+                            "Remove Y target",
+                            ItemOpts {
+                                enabled: has_y_target,
+                                checked: false,
+                            },
+                            || MenuAction::RemoveYTarget,
+                        ),
+                    ],
+                ),
                 menu(
                     "Move to group",
                     iter::once(item("<New group>", || MenuAction::MoveMappingToGroup(None)))
@@ -819,6 +872,19 @@ impl MappingRowPanel {
                     group_id,
                 );
             }
+            MenuAction::PasteYTarget(data) => {
+                if let Ok(y_target_model) =
+                    create_target_model_from_data(*data, self.session(), triple.compartment)
+                {
+                    self.change_mapping(MappingCommand::SetYTarget(Some(y_target_model)));
+                }
+            }
+            MenuAction::SwapTargetAndYTarget => {
+                self.change_mapping(MappingCommand::SwapTargetAndYTarget);
+            }
+            MenuAction::RemoveYTarget => {
+                self.change_mapping(MappingCommand::SetYTarget(None));
+            }
             MenuAction::LogDebugInfo => {
                 let _ = self
                     .session()
@@ -1045,6 +1111,23 @@ fn paste_data_object_in_place(
     Ok(())
 }
 
+fn create_target_model_from_data(
+    data: TargetModelData,
+    shared_session: SharedSession,
+    compartment: Compartment,
+) -> Result<TargetModel, &'static str> {
+    let session = shared_session.borrow();
+    let compartment_in_session = session.compartment_in_session(compartment);
+    let mut target_model = TargetModel::default_for_compartment(compartment);
+    data.apply_to_model(
+        &mut target_model,
+        compartment,
+        session.extended_context(),
+        &compartment_in_session,
+    )?;
+    Ok(target_model)
+}
+
 /// If `below_mapping_id` not given, it's added at the end.
 // https://github.com/rust-lang/rust-clippy/issues/6066
 #[allow(clippy::needless_collect)]
@@ -48,6 +48,7 @@ impl SessionMessagePanel {
                                 VirtualControlElementType::Button => {
                                     "Press a button-like control element!"
                                 }
+                                VirtualControlElementType::Xy => "Move an XY pad or joystick!",
                             },
                             Compartment::Main => "Touch a control element!",
                         };
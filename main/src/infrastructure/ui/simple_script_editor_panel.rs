@@ -1,6 +1,7 @@
 use crate::domain::{
-    AdditionalTransformationInput, EelMidiSourceScript, EelTransformation, LuaMidiSourceScript,
-    SafeLua, Script,
+    AdditionalTransformationInput, EelMidiSourceControlScript, EelMidiSourceScript,
+    EelTransformation, LuaMidiSourceControlScript, LuaMidiSourceScript, LuaTransformation,
+    MidiSourceControlScript, SafeLua, Script,
 };
 use crate::infrastructure::ui::bindings::root;
 use crate::infrastructure::ui::bindings::root::ID_YAML_HELP_BUTTON;
@@ -60,6 +61,37 @@ impl ScriptEngine for EelMidiScriptEngine {
     }
 }
 
+pub struct EelMidiControlScriptEngine;
+
+impl ScriptEngine for EelMidiControlScriptEngine {
+    fn compile(&self, code: &str) -> Result<Box<dyn Script>, Box<dyn Error>> {
+        let script = EelMidiSourceControlScript::compile(code)?;
+        script.execute(MIDI_CONTROL_SCRIPT_TEST_MESSAGE)?;
+        Ok(Box::new(()))
+    }
+
+    fn file_extension(&self) -> &'static str {
+        ".eel"
+    }
+}
+
+pub struct LuaMidiControlScriptEngine;
+
+impl ScriptEngine for LuaMidiControlScriptEngine {
+    fn compile(&self, code: &str) -> Result<Box<dyn Script>, Box<dyn Error>> {
+        let script = LuaMidiSourceControlScript::compile(code)?;
+        script.execute(MIDI_CONTROL_SCRIPT_TEST_MESSAGE)?;
+        Ok(Box::new(()))
+    }
+
+    fn file_extension(&self) -> &'static str {
+        ".lua"
+    }
+}
+
+/// Used to check whether a MIDI control script runs without errors.
+const MIDI_CONTROL_SCRIPT_TEST_MESSAGE: &[u8] = &[0xf0, 0x00, 0xf7];
+
 pub struct RawMidiScriptEngine;
 
 impl ScriptEngine for RawMidiScriptEngine {
@@ -128,6 +160,42 @@ impl ScriptEngine for EelFeedbackTransformationEngine {
     }
 }
 
+pub struct LuaControlTransformationEngine;
+
+impl ScriptEngine for LuaControlTransformationEngine {
+    fn compile(&self, code: &str) -> Result<Box<dyn Script>, Box<dyn Error>> {
+        let transformation = LuaTransformation::compile_for_control(code)?;
+        transformation.evaluate(
+            Default::default(),
+            Default::default(),
+            AdditionalTransformationInput::default(),
+        )?;
+        Ok(Box::new(transformation))
+    }
+
+    fn file_extension(&self) -> &'static str {
+        ".lua"
+    }
+}
+
+pub struct LuaFeedbackTransformationEngine;
+
+impl ScriptEngine for LuaFeedbackTransformationEngine {
+    fn compile(&self, code: &str) -> Result<Box<dyn Script>, Box<dyn Error>> {
+        let transformation = LuaTransformation::compile_for_feedback(code)?;
+        transformation.evaluate(
+            Default::default(),
+            Default::default(),
+            AdditionalTransformationInput::default(),
+        )?;
+        Ok(Box::new(()))
+    }
+
+    fn file_extension(&self) -> &'static str {
+        ".lua"
+    }
+}
+
 pub struct TextualFeedbackExpressionEngine;
 
 impl ScriptEngine for TextualFeedbackExpressionEngine {
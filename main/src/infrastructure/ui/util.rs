@@ -3,7 +3,9 @@ use crate::domain::{compartment_param_index_iter, Compartment, Tag};
 use crate::infrastructure::ui::bindings::root;
 use realearn_dialogs::constants;
 use reaper_high::Reaper;
-use std::path::Path;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use swell_ui::{DialogScaling, DialogUnits, Dimensions, Window};
 
@@ -215,6 +217,25 @@ const FILE_MANAGER_CMD: &str = "open";
 #[cfg(target_os = "linux")]
 const FILE_MANAGER_CMD: &str = "xdg-open";
 
+/// Shows REAPER's file dialog and returns the chosen file, if any.
+pub fn prompt_for_file_to_read(title: &str, default_extension: &str) -> Option<PathBuf> {
+    let title = CString::new(title).ok()?;
+    let default_extension = CString::new(default_extension).ok()?;
+    let mut buffer = vec![0 as c_char; 4096];
+    let chosen = unsafe {
+        Reaper::get().medium_reaper().low().GetUserFileNameForRead(
+            buffer.as_mut_ptr(),
+            title.as_ptr(),
+            default_extension.as_ptr(),
+        )
+    };
+    if !chosen {
+        return None;
+    }
+    let path = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+    Some(PathBuf::from(path.to_str().ok()?))
+}
+
 pub fn open_in_file_manager(path: &Path) -> Result<(), &'static str> {
     let final_path = path
         .canonicalize()
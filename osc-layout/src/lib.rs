@@ -0,0 +1,199 @@
+use realearn_api::persistence::{
+    ApiObject, Compartment, Envelope, Interval, Mapping, OscArgKind, OscArgument, OscSource,
+    Source, Target, VirtualControlElementCharacter, VirtualControlElementId, VirtualTarget,
+};
+use realearn_csi::{AnnotatedResult, Annotator};
+use std::collections::HashSet;
+use std::error::Error;
+
+mod open_stage_control;
+mod touch_osc;
+
+type OscLayoutResult<T> = Result<T, Box<dyn Error>>;
+
+/// Deserializes a TouchOSC layout (`.tosc`, compressed or plain XML) or an Open Stage Control
+/// session (`.json`) and converts it into a controller compartment.
+pub fn deserialize_osc_layout(content: &[u8]) -> OscLayoutResult<AnnotatedResult<ApiObject>> {
+    let mut annotator = Annotator::new();
+    let first_relevant_byte = content.iter().find(|b| !b.is_ascii_whitespace());
+    let controls = match first_relevant_byte {
+        None => return Err("Layout is empty".into()),
+        Some(b'{') => {
+            let text = std::str::from_utf8(content)?;
+            open_stage_control::parse_session(text, &mut annotator)?
+        }
+        Some(b'<') => {
+            let text = std::str::from_utf8(content)?;
+            touch_osc::parse_xml(text, &mut annotator)?
+        }
+        Some(_) => touch_osc::parse_compressed_xml(content, &mut annotator)?,
+    };
+    let compartment = convert_controls_to_compartment(controls, &mut annotator);
+    let api_object = ApiObject::ControllerCompartment(Envelope {
+        version: None,
+        value: Box::new(compartment),
+    });
+    Ok(annotator.build_result(api_object))
+}
+
+/// Checks whether the given text looks like an Open Stage Control session.
+///
+/// Used to tell sessions apart from ReaLearn's own JSON format when importing from clipboard.
+pub fn looks_like_open_stage_control_session(text: &str) -> bool {
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(v) => open_stage_control::looks_like_session(&v),
+        Err(_) => false,
+    }
+}
+
+/// A widget in the OSC app's layout that sends and/or receives one OSC message.
+struct OscControl {
+    name: String,
+    address: String,
+    control_enabled: bool,
+    feedback_enabled: bool,
+    relative: bool,
+    /// Only the arguments which carry the widget value, not constant ones.
+    arguments: Vec<OscControlArgument>,
+}
+
+struct OscControlArgument {
+    index: u32,
+    /// Used to distinguish the arguments of widgets with more than one value (e.g. "x" and "y").
+    label: String,
+    kind: OscArgKind,
+    value_range: Option<Interval<f64>>,
+    character: VirtualControlElementCharacter,
+}
+
+const MAX_CONTROL_ELEMENT_ID_LENGTH: usize = 16;
+
+fn convert_controls_to_compartment(
+    controls: Vec<OscControl>,
+    annotator: &mut Annotator,
+) -> Compartment {
+    let mut used_ids = HashSet::new();
+    let mappings = controls
+        .into_iter()
+        .flat_map(|c| {
+            annotator.with_context(format!("Widget \"{}\"", c.name), |annotator| {
+                convert_control_to_mappings(c, &mut used_ids, annotator)
+            })
+        })
+        .collect();
+    Compartment {
+        mappings: Some(mappings),
+        ..Default::default()
+    }
+}
+
+fn convert_control_to_mappings(
+    control: OscControl,
+    used_ids: &mut HashSet<String>,
+    annotator: &mut Annotator,
+) -> Vec<Mapping> {
+    if control.arguments.is_empty() {
+        annotator.info("Widget doesn't send any value, skipped.");
+        return vec![];
+    }
+    let is_multi_value = control.arguments.len() > 1;
+    control
+        .arguments
+        .into_iter()
+        .map(|arg| {
+            let (name, id_suffix) = if is_multi_value {
+                (
+                    format!("{} {}", control.name, arg.label),
+                    format!("/{}", arg.label),
+                )
+            } else {
+                (control.name.clone(), String::new())
+            };
+            let id = convert_name_to_unique_id(&control.name, &id_suffix, used_ids, annotator);
+            let source = OscSource {
+                address: Some(control.address.clone()),
+                argument: Some(OscArgument {
+                    index: Some(arg.index),
+                    kind: Some(arg.kind),
+                    value_range: arg.value_range,
+                }),
+                relative: Some(control.relative),
+                ..Default::default()
+            };
+            let target = VirtualTarget {
+                id: VirtualControlElementId::Named(id.clone()),
+                character: Some(arg.character),
+                axis: None,
+            };
+            Mapping {
+                id: Some(id),
+                name: Some(name),
+                control_enabled: Some(control.control_enabled),
+                feedback_enabled: Some(control.feedback_enabled),
+                source: Some(Source::Osc(source)),
+                target: Some(Target::Virtual(target)),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Builds a valid virtual control element ID, keeping the suffix intact.
+fn convert_name_to_unique_id(
+    name: &str,
+    suffix: &str,
+    used_ids: &mut HashSet<String>,
+    annotator: &mut Annotator,
+) -> String {
+    let sanitized_name: String = name
+        .chars()
+        .filter(|ch| ch.is_ascii_alphanumeric() || ch.is_ascii_punctuation())
+        .collect();
+    let build_id = |counter: Option<u32>| {
+        let counter_suffix = counter.map(|c| format!("_{}", c)).unwrap_or_default();
+        let max_name_length =
+            MAX_CONTROL_ELEMENT_ID_LENGTH.saturating_sub(suffix.len() + counter_suffix.len());
+        let truncated_name: String = sanitized_name.chars().take(max_name_length).collect();
+        format!("{}{}{}", truncated_name, counter_suffix, suffix)
+    };
+    let mut id = build_id(None);
+    if id.len() < sanitized_name.len() + suffix.len() {
+        annotator.info(format!("ReaLearn doesn't allow for virtual control element IDs longer than 16 characters, therefore the widget name \"{}\" was truncated to the ID \"{}\".", name, id));
+    }
+    let mut counter = 2;
+    while used_ids.contains(&id) {
+        id = build_id(Some(counter));
+        counter += 1;
+    }
+    if counter > 2 {
+        annotator.warn(format!(
+            "There are multiple widgets resulting in the same ID, therefore this one got the ID \"{}\". You might want to give the widgets unique names.",
+            id
+        ));
+    }
+    used_ids.insert(id.clone());
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_ids() {
+        // Given
+        let mut used_ids = HashSet::new();
+        let mut annotator = Annotator::new();
+        // When
+        let first = convert_name_to_unique_id("fader 1", "", &mut used_ids, &mut annotator);
+        let second = convert_name_to_unique_id("fader 1", "", &mut used_ids, &mut annotator);
+        let long = convert_name_to_unique_id("very_long_name", "/x", &mut used_ids, &mut annotator);
+        // Then
+        assert_eq!(first, "fader1");
+        assert_eq!(second, "fader1_2");
+        assert_eq!(long, "very_long_name/x");
+        let truncated =
+            convert_name_to_unique_id("even_longer_name", "/x", &mut used_ids, &mut annotator);
+        assert_eq!(truncated, "even_longer_na/x");
+    }
+}
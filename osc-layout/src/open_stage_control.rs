@@ -0,0 +1,242 @@
+//! Support for Open Stage Control sessions (https://openstagecontrol.ammd.net/).
+use crate::{OscControl, OscControlArgument, OscLayoutResult};
+use realearn_api::persistence::{Interval, OscArgKind, VirtualControlElementCharacter};
+use realearn_csi::Annotator;
+use serde_json::{Map, Value};
+
+pub fn looks_like_session(value: &Value) -> bool {
+    match value.get("type").and_then(|t| t.as_str()) {
+        Some("session") => value.get("session").is_some(),
+        Some("root") => true,
+        _ => false,
+    }
+}
+
+pub fn parse_session(text: &str, annotator: &mut Annotator) -> OscLayoutResult<Vec<OscControl>> {
+    let value: Value = serde_json::from_str(text)?;
+    if !looks_like_session(&value) {
+        return Err("Not an Open Stage Control session".into());
+    }
+    // Since version 1.0, the root widget is wrapped
+    let root = value.get("session").unwrap_or(&value);
+    let mut controls = vec![];
+    collect_controls(root, &mut controls, annotator);
+    Ok(controls)
+}
+
+fn collect_controls(widget: &Value, controls: &mut Vec<OscControl>, annotator: &mut Annotator) {
+    let widget = match widget.as_object() {
+        None => return,
+        Some(w) => w,
+    };
+    let widget_type = widget.get("type").and_then(|t| t.as_str()).unwrap_or("");
+    let widget_id = widget
+        .get("id")
+        .and_then(|t| t.as_str())
+        .unwrap_or(widget_type);
+    if let Some(kind) = WidgetKind::from_type(widget_type) {
+        annotator.with_context(format!("Widget \"{}\"", widget_id), |annotator| {
+            if let Some(c) = convert_widget(widget, widget_id, kind, annotator) {
+                controls.push(c);
+            }
+        });
+    }
+    for container_key in ["widgets", "tabs"] {
+        if let Some(children) = widget.get(container_key).and_then(|c| c.as_array()) {
+            for child in children {
+                collect_controls(child, controls, annotator);
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+enum WidgetKind {
+    Button,
+    Continuous,
+    Encoder,
+    Switch,
+    Xy,
+    Range,
+}
+
+impl WidgetKind {
+    fn from_type(widget_type: &str) -> Option<Self> {
+        use WidgetKind::*;
+        let kind = match widget_type {
+            "button" | "toggle" | "push" => Button,
+            "fader" | "knob" => Continuous,
+            "encoder" => Encoder,
+            "switch" | "dropdown" => Switch,
+            "xy" => Xy,
+            "range" => Range,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+fn convert_widget(
+    widget: &Map<String, Value>,
+    widget_id: &str,
+    kind: WidgetKind,
+    annotator: &mut Annotator,
+) -> Option<OscControl> {
+    let address = match widget.get("address").and_then(|a| a.as_str()) {
+        None | Some("auto") => format!("/{}", widget_id),
+        Some(a) => a.to_owned(),
+    };
+    if widget.get("bypass").and_then(|b| b.as_bool()) == Some(true) {
+        annotator.info("Widget doesn't send any messages (bypass is enabled), skipped.");
+        return None;
+    }
+    let pre_arg_count = match widget.get("preArgs") {
+        None | Some(Value::Null) => 0,
+        Some(Value::Array(a)) => a.len(),
+        Some(Value::String(s)) if s.is_empty() => 0,
+        Some(_) => 1,
+    };
+    let type_tags = widget
+        .get("typeTags")
+        .and_then(|t| t.as_str())
+        .unwrap_or("");
+    let arg = |offset: usize, label: &str, value_range, character| OscControlArgument {
+        index: (pre_arg_count + offset) as u32,
+        label: label.to_owned(),
+        kind: convert_type_tag(type_tags.chars().nth(pre_arg_count + offset)),
+        value_range,
+        character,
+    };
+    use VirtualControlElementCharacter::*;
+    let mut relative = false;
+    let arguments = match kind {
+        WidgetKind::Button => {
+            let on = get_number(widget, "on", 1.0, annotator);
+            let off = get_number(widget, "off", 0.0, annotator);
+            vec![arg(0, "", Some(Interval(off, on)), Button)]
+        }
+        WidgetKind::Continuous => {
+            let range = get_range(widget.get("range"), annotator);
+            vec![arg(0, "", Some(range), Multi)]
+        }
+        WidgetKind::Encoder => {
+            relative = true;
+            vec![arg(0, "", None, Multi)]
+        }
+        WidgetKind::Switch => {
+            annotator.info("Switch values are sent as-is, make sure they are numeric.");
+            vec![arg(0, "", None, Multi)]
+        }
+        WidgetKind::Xy => {
+            let x_range = get_range(widget.get("rangeX"), annotator);
+            let y_range = get_range(widget.get("rangeY"), annotator);
+            vec![
+                arg(0, "x", Some(x_range), Multi),
+                arg(1, "y", Some(y_range), Multi),
+            ]
+        }
+        WidgetKind::Range => {
+            let range = get_range(widget.get("range"), annotator);
+            vec![
+                arg(0, "low", Some(range), Multi),
+                arg(1, "high", Some(range), Multi),
+            ]
+        }
+    };
+    let control = OscControl {
+        name: widget_id.to_owned(),
+        address,
+        control_enabled: true,
+        feedback_enabled: true,
+        relative,
+        arguments,
+    };
+    Some(control)
+}
+
+fn convert_type_tag(tag: Option<char>) -> OscArgKind {
+    match tag {
+        Some('i') => OscArgKind::Int,
+        Some('h') => OscArgKind::Long,
+        Some('d') => OscArgKind::Double,
+        Some('T' | 'F') => OscArgKind::Bool,
+        Some('s') => OscArgKind::String,
+        // Open Stage Control sends numbers as float by default
+        _ => OscArgKind::Float,
+    }
+}
+
+fn get_number(
+    widget: &Map<String, Value>,
+    key: &str,
+    default_value: f64,
+    annotator: &mut Annotator,
+) -> f64 {
+    match widget.get(key) {
+        None | Some(Value::Null) => default_value,
+        Some(Value::Number(n)) => n.as_f64().unwrap_or(default_value),
+        Some(v) => {
+            annotator.warn(format!(
+                "Property \"{}\" has non-numeric value {}. Using {} instead.",
+                key, v, default_value
+            ));
+            default_value
+        }
+    }
+}
+
+/// Extracts the outer bounds of a range, which might contain intermediate steps ("50%": ...).
+fn get_range(range: Option<&Value>, annotator: &mut Annotator) -> Interval<f64> {
+    let range = match range.and_then(|r| r.as_object()) {
+        None => return Interval(0.0, 1.0),
+        Some(r) => r,
+    };
+    let min = get_range_bound(range, "min", 0.0, annotator);
+    let max = get_range_bound(range, "max", 1.0, annotator);
+    Interval(min, max)
+}
+
+fn get_range_bound(
+    range: &Map<String, Value>,
+    key: &str,
+    default_value: f64,
+    annotator: &mut Annotator,
+) -> f64 {
+    match range.get(key) {
+        // A bound can also be given as {"label": value}
+        Some(Value::Object(o)) if o.len() == 1 => {
+            let value = o.values().next().and_then(|v| v.as_f64());
+            value.unwrap_or(default_value)
+        }
+        _ => get_number(range, key, default_value, annotator),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test_session() {
+        // Given
+        let mut annotator = Annotator::new();
+        // When
+        let controls =
+            parse_session(include_str!("test_data/test.osc.json"), &mut annotator).unwrap();
+        // Then
+        let names: Vec<_> = controls.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["fader_1", "button_1", "xy_1", "encoder_1"]);
+        let fader = &controls[0];
+        assert_eq!(fader.address, "/fader_1");
+        assert!(fader.arguments[0].value_range == Some(Interval(0.0, 127.0)));
+        assert!(fader.arguments[0].kind == OscArgKind::Int);
+        let button = &controls[1];
+        assert_eq!(button.address, "/transport/play");
+        assert_eq!(button.arguments[0].index, 1);
+        assert!(button.arguments[0].character == VirtualControlElementCharacter::Button);
+        let xy = &controls[2];
+        assert_eq!(xy.arguments.len(), 2);
+        assert!(xy.arguments[1].value_range == Some(Interval(-1.0, 1.0)));
+        assert!(controls[3].relative);
+    }
+}
@@ -0,0 +1,253 @@
+//! Support for TouchOSC layouts (https://hexler.net/touchosc).
+//!
+//! A `.tosc` file is zlib-compressed XML. Each node (control) can have several OSC messages whose
+//! address and arguments are composed of "partials".
+use crate::{OscControl, OscControlArgument, OscLayoutResult};
+use flate2::read::ZlibDecoder;
+use realearn_api::persistence::{Interval, OscArgKind, VirtualControlElementCharacter};
+use realearn_csi::Annotator;
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+use std::io::Read;
+
+pub fn parse_compressed_xml(
+    content: &[u8],
+    annotator: &mut Annotator,
+) -> OscLayoutResult<Vec<OscControl>> {
+    let mut text = String::new();
+    ZlibDecoder::new(content)
+        .read_to_string(&mut text)
+        .map_err(|_| "Not a valid TouchOSC layout")?;
+    parse_xml(&text, annotator)
+}
+
+pub fn parse_xml(text: &str, annotator: &mut Annotator) -> OscLayoutResult<Vec<OscControl>> {
+    let doc = Document::parse(text)?;
+    let root_element = doc.root_element();
+    if !root_element.has_tag_name("lexml") {
+        return Err("Not a TouchOSC layout".into());
+    }
+    let root_node = child(root_element, "node").ok_or("TouchOSC layout has no root node")?;
+    let mut controls = vec![];
+    collect_controls(root_node, &mut vec![], &mut controls, annotator);
+    Ok(controls)
+}
+
+fn collect_controls<'a>(
+    node: Node<'a, 'a>,
+    ancestors: &mut Vec<HashMap<&'a str, &'a str>>,
+    controls: &mut Vec<OscControl>,
+    annotator: &mut Annotator,
+) {
+    let properties = parse_properties(node);
+    let node_type = node.attribute("type").unwrap_or("");
+    let name = properties
+        .get("name")
+        .copied()
+        .unwrap_or(node_type)
+        .to_owned();
+    ancestors.push(properties);
+    annotator.with_context(format!("Node \"{}\"", name), |annotator| {
+        let messages = child(node, "messages")
+            .into_iter()
+            .flat_map(|m| m.children())
+            .filter(|m| m.has_tag_name("osc"));
+        for message in messages {
+            if let Some(c) = convert_message(message, node_type, &name, ancestors, annotator) {
+                controls.push(c);
+            }
+        }
+    });
+    if let Some(children) = child(node, "children") {
+        for c in children.children().filter(|c| c.has_tag_name("node")) {
+            collect_controls(c, ancestors, controls, annotator);
+        }
+    }
+    ancestors.pop();
+}
+
+fn convert_message(
+    message: Node,
+    node_type: &str,
+    name: &str,
+    ancestors: &[HashMap<&str, &str>],
+    annotator: &mut Annotator,
+) -> Option<OscControl> {
+    if !flag(message, "enabled") {
+        return None;
+    }
+    let address = resolve_address(message, ancestors, annotator)?;
+    let arguments = child(message, "arguments")
+        .into_iter()
+        .flat_map(|a| a.children())
+        .filter(|p| p.has_tag_name("partial"))
+        .enumerate()
+        .filter_map(|(i, p)| convert_argument(i as u32, p, node_type, annotator))
+        .collect();
+    let control = OscControl {
+        name: name.to_owned(),
+        address,
+        // "send" and "receive" are from the perspective of TouchOSC
+        control_enabled: flag(message, "send"),
+        feedback_enabled: flag(message, "receive"),
+        relative: false,
+        arguments,
+    };
+    Some(control)
+}
+
+fn resolve_address(
+    message: Node,
+    ancestors: &[HashMap<&str, &str>],
+    annotator: &mut Annotator,
+) -> Option<String> {
+    let partials = child(message, "path")?
+        .children()
+        .filter(|p| p.has_tag_name("partial"));
+    let mut address = String::new();
+    for p in partials {
+        let value = child_text(p, "value");
+        match child_text(p, "type") {
+            "CONSTANT" => address.push_str(value),
+            "PROPERTY" => match resolve_property(value, ancestors) {
+                None => {
+                    annotator.warn(format!(
+                        "Address refers to unknown property \"{}\", message skipped.",
+                        value
+                    ));
+                    return None;
+                }
+                Some(v) => address.push_str(v),
+            },
+            t => {
+                annotator.info(format!(
+                    "Addresses with partials of type {} are not supported, message skipped.",
+                    t
+                ));
+                return None;
+            }
+        }
+    }
+    Some(address)
+}
+
+/// Resolves property expressions such as `name` or `parent.name`.
+fn resolve_property<'a>(
+    expression: &str,
+    ancestors: &[HashMap<&'a str, &'a str>],
+) -> Option<&'a str> {
+    let mut key = expression;
+    let mut level = ancestors.len().checked_sub(1)?;
+    while let Some(rest) = key.strip_prefix("parent.") {
+        level = level.checked_sub(1)?;
+        key = rest;
+    }
+    ancestors[level].get(key).copied()
+}
+
+fn convert_argument(
+    index: u32,
+    partial: Node,
+    node_type: &str,
+    annotator: &mut Annotator,
+) -> Option<OscControlArgument> {
+    if child_text(partial, "type") != "VALUE" {
+        // Constant arguments don't carry the widget value
+        return None;
+    }
+    let variable = child_text(partial, "value");
+    let scale_min: f64 = child_text(partial, "scaleMin").parse().unwrap_or(0.0);
+    let scale_max: f64 = child_text(partial, "scaleMax").parse().unwrap_or(1.0);
+    let (kind, value_range) = match child_text(partial, "conversion") {
+        "BOOLEAN" => (OscArgKind::Bool, None),
+        "INTEGER" => (OscArgKind::Int, Some(Interval(scale_min, scale_max))),
+        "FLOAT" => (OscArgKind::Float, Some(Interval(scale_min, scale_max))),
+        c => {
+            annotator.info(format!(
+                "Value \"{}\" is converted to {}, which is not supported. Argument skipped.",
+                variable, c
+            ));
+            return None;
+        }
+    };
+    let character = if node_type == "BUTTON" || variable == "touch" {
+        VirtualControlElementCharacter::Button
+    } else {
+        VirtualControlElementCharacter::Multi
+    };
+    let arg = OscControlArgument {
+        index,
+        label: variable.to_owned(),
+        kind,
+        value_range,
+        character,
+    };
+    Some(arg)
+}
+
+fn parse_properties<'a>(node: Node<'a, 'a>) -> HashMap<&'a str, &'a str> {
+    child(node, "properties")
+        .into_iter()
+        .flat_map(|p| p.children())
+        .filter(|p| p.has_tag_name("property"))
+        .map(|p| (child_text(p, "key"), child_text(p, "value")))
+        .collect()
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, tag_name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|c| c.has_tag_name(tag_name))
+}
+
+fn child_text<'a>(node: Node<'a, 'a>, tag_name: &str) -> &'a str {
+    child(node, tag_name).and_then(|c| c.text()).unwrap_or("")
+}
+
+fn flag(node: Node, tag_name: &str) -> bool {
+    child_text(node, tag_name) == "1"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn parse_test_layout() {
+        // Given
+        let mut annotator = Annotator::new();
+        // When
+        let controls = parse_xml(include_str!("test_data/test.tosc.xml"), &mut annotator).unwrap();
+        // Then
+        let names: Vec<_> = controls.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["fader1", "button1", "xy1"]);
+        let fader = &controls[0];
+        assert_eq!(fader.address, "/mixer/fader1");
+        assert!(fader.arguments[0].value_range == Some(Interval(0.0, 127.0)));
+        assert!(fader.arguments[0].kind == OscArgKind::Int);
+        let button = &controls[1];
+        assert_eq!(button.address, "/play");
+        assert!(!button.feedback_enabled);
+        assert!(button.arguments[0].character == VirtualControlElementCharacter::Button);
+        let xy = &controls[2];
+        assert_eq!(xy.arguments.len(), 2);
+        assert_eq!(xy.arguments[1].index, 2);
+        assert_eq!(xy.arguments[1].label, "y");
+    }
+
+    #[test]
+    fn parse_compressed_test_layout() {
+        // Given
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(include_bytes!("test_data/test.tosc.xml"))
+            .unwrap();
+        let content = encoder.finish().unwrap();
+        let mut annotator = Annotator::new();
+        // When
+        let controls = parse_compressed_xml(&content, &mut annotator).unwrap();
+        // Then
+        assert_eq!(controls.len(), 3);
+    }
+}